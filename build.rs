@@ -0,0 +1,73 @@
+//! Build script
+//!
+//! Records the provenance `tpu-doc version` reports: the git commit, the
+//! build date, and the rustc version. Values already set in the
+//! environment (e.g. by a packaging script building from a tarball) are
+//! kept; anything that cannot be determined is left unset.
+
+use std::env;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-changed=.git/index");
+    for var in ["TPU_DOC_GIT_HASH", "TPU_DOC_BUILD_DATE", "TPU_DOC_RUSTC_VERSION", "SOURCE_DATE_EPOCH"] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+
+    let commit = env::var("TPU_DOC_GIT_HASH").ok().or_else(git_commit);
+    let date = env::var("TPU_DOC_BUILD_DATE").ok().or_else(build_date);
+    let rustc = env::var("TPU_DOC_RUSTC_VERSION").ok().or_else(rustc_version);
+
+    for (name, value) in [
+        ("TPU_DOC_GIT_HASH", commit),
+        ("TPU_DOC_BUILD_DATE", date),
+        ("TPU_DOC_RUSTC_VERSION", rustc),
+    ] {
+        if let Some(value) = value {
+            println!("cargo:rustc-env={}={}", name, value);
+        }
+    }
+}
+
+/// Output of `program args`, trimmed, when it succeeds
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    let text = String::from_utf8(output.stdout).ok()?;
+    (output.status.success() && !text.trim().is_empty()).then(|| text.trim().to_string())
+}
+
+/// Short commit hash, with `-dirty` when the tree has uncommitted changes
+fn git_commit() -> Option<String> {
+    let hash = command_output("git", &["rev-parse", "--short=12", "HEAD"])?;
+    let dirty = command_output("git", &["status", "--porcelain", "--untracked-files=no"]).is_some();
+    Some(if dirty { format!("{}-dirty", hash) } else { hash })
+}
+
+fn rustc_version() -> Option<String> {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    command_output(&rustc, &["--version"])
+}
+
+/// Today's UTC date as YYYY-MM-DD, or `SOURCE_DATE_EPOCH`'s for
+/// reproducible builds
+fn build_date() -> Option<String> {
+    let secs = match env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch.trim().parse::<u64>().ok()?,
+        Err(_) => SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs(),
+    };
+
+    // Days since 1970-01-01 to a civil date (Howard Hinnant's algorithm)
+    let days = (secs / 86400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    Some(format!("{:04}-{:02}-{:02}", year, month, day))
+}
@@ -6,19 +6,22 @@
 //! # Usage
 //!
 //! ```no_run
+//! # fn main() -> Result<(), tpu_doc::ai::AiError> {
 //! use tpu_doc::ai::anthropic::AnthropicClient;
 //!
 //! let client = AnthropicClient::new()?;
 //! let response = client.send_message("Analyze this log...", None)?;
 //! println!("{}", response.content);
+//! # Ok(())
+//! # }
 //! ```
 //!
 //! # Environment Variables
 //!
 //! - `ANTHROPIC_API_KEY`: Required. Your Anthropic API key.
 
-use super::client::HttpClient;
-use super::{AiError, AiProvider, AnalysisResponse};
+use super::client::{BaseUrl, HttpClient};
+use super::{AiError, AiProvider, AnalysisResponse, ChatMessage, ChatRole};
 
 const API_HOST: &str = "api.anthropic.com";
 const API_PATH: &str = "/v1/messages";
@@ -57,6 +60,12 @@ impl AnthropicClient {
         self
     }
 
+    /// Send requests to `url` (e.g. an internal LLM gateway) instead of the public API host
+    pub fn with_base_url(mut self, url: &str) -> Result<Self, AiError> {
+        self.http_client = self.http_client.with_base_url(BaseUrl::parse(url)?);
+        Ok(self)
+    }
+
     /// Set the maximum tokens for response
     pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
         self.max_tokens = max_tokens;
@@ -69,7 +78,16 @@ impl AnthropicClient {
         user_message: &str,
         system_prompt: Option<&str>,
     ) -> Result<AnalysisResponse, AiError> {
-        let request_body = self.build_request_body(user_message, system_prompt);
+        self.send_conversation(&[ChatMessage::user(user_message)], system_prompt)
+    }
+
+    /// Send a multi-turn conversation (oldest first, ending with a user turn)
+    pub fn send_conversation(
+        &self,
+        messages: &[ChatMessage],
+        system_prompt: Option<&str>,
+    ) -> Result<AnalysisResponse, AiError> {
+        let request_body = self.build_conversation_body(messages, system_prompt);
 
         let headers = [
             ("Content-Type", "application/json"),
@@ -88,8 +106,12 @@ impl AnthropicClient {
         self.parse_success_response(&response.body)
     }
 
+    #[cfg(test)]
     fn build_request_body(&self, user_message: &str, system_prompt: Option<&str>) -> String {
-        let escaped_user = escape_json_string(user_message);
+        self.build_conversation_body(&[ChatMessage::user(user_message)], system_prompt)
+    }
+
+    fn build_conversation_body(&self, messages: &[ChatMessage], system_prompt: Option<&str>) -> String {
         let escaped_system = system_prompt.map(escape_json_string);
 
         let mut body = String::new();
@@ -102,10 +124,19 @@ impl AnthropicClient {
         }
 
         body.push_str("  \"messages\": [\n");
-        body.push_str("    {\n");
-        body.push_str("      \"role\": \"user\",\n");
-        body.push_str(&format!("      \"content\": \"{}\"\n", escaped_user));
-        body.push_str("    }\n");
+        for (i, message) in messages.iter().enumerate() {
+            let role = match message.role {
+                ChatRole::User => "user",
+                ChatRole::Assistant => "assistant",
+            };
+            body.push_str("    {\n");
+            body.push_str(&format!("      \"role\": \"{}\",\n", role));
+            body.push_str(&format!(
+                "      \"content\": \"{}\"\n",
+                escape_json_string(&message.content)
+            ));
+            body.push_str(if i + 1 < messages.len() { "    },\n" } else { "    }\n" });
+        }
         body.push_str("  ]\n");
         body.push_str("}\n");
 
@@ -299,6 +330,23 @@ mod tests {
         assert!(body.contains("\"content\": \"Hello, Claude!\""));
     }
 
+    #[test]
+    fn test_build_conversation_body() {
+        let client = AnthropicClient::with_key("test-key".to_string());
+        let body = client.build_conversation_body(
+            &[
+                ChatMessage::user("What failed?"),
+                ChatMessage::assistant("HBM OOM."),
+                ChatMessage::user("How do I fix it?"),
+            ],
+            None,
+        );
+
+        assert!(body.contains("\"role\": \"assistant\",\n      \"content\": \"HBM OOM.\""));
+        assert!(body.contains("\"content\": \"How do I fix it?\"\n    }\n  ]"));
+        assert_eq!(body.matches("\"role\": \"user\"").count(), 2);
+    }
+
     #[test]
     fn test_build_request_body_with_system() {
         let client = AnthropicClient::with_key("test-key".to_string());
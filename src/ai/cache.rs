@@ -0,0 +1,269 @@
+//! On-disk cache for AI analysis responses.
+//!
+//! Re-running `analyze` on the same log during an incident is common; the
+//! cache makes repeat runs free and instant. Entries are keyed by everything
+//! that shapes the answer (see [`AnalysisKey`]), and expire after a TTL.
+//!
+//! Entries live under `~/.tpu-preflight/ai-cache/<key>.txt` (entries left in
+//! the old `~/.tpu-doc/ai-cache` are still read) in a small header-plus-body
+//! text format:
+//!
+//! ```text
+//! model: gemini-1.5-flash
+//! prompt_tokens: 1234
+//! completion_tokens: 567
+//!
+//! <response content>
+//! ```
+
+use super::{AiProvider, AnalysisResponse};
+use crate::util::{self, hash};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Default time-to-live for cached responses
+pub const DEFAULT_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// A cached response and how old it is
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub response: AnalysisResponse,
+    pub age_secs: u64,
+}
+
+/// Response cache rooted at a directory
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    legacy_dir: Option<PathBuf>,
+    ttl: Duration,
+}
+
+impl ResponseCache {
+    /// Cache at the default location (`~/.tpu-preflight/ai-cache`), falling
+    /// back to `~/.tpu-doc/ai-cache` for reads
+    pub fn open_default(ttl_secs: u64) -> Option<Self> {
+        let cache = Self::new(util::state_dir()?.join("ai-cache"), ttl_secs);
+        Some(match util::legacy_state_dir() {
+            Some(legacy) => cache.with_legacy_dir(legacy.join("ai-cache")),
+            None => cache,
+        })
+    }
+
+    /// Cache rooted at `dir`
+    pub fn new(dir: PathBuf, ttl_secs: u64) -> Self {
+        ResponseCache {
+            dir,
+            legacy_dir: None,
+            ttl: Duration::from_secs(ttl_secs),
+        }
+    }
+
+    /// Also read entries missing from the cache directory from `dir`
+    pub fn with_legacy_dir(mut self, dir: PathBuf) -> Self {
+        self.legacy_dir = Some(dir);
+        self
+    }
+
+    /// Directory entries are stored in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Look up a response. Expired or unreadable entries are removed.
+    pub fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.get_at(&self.entry_path(key)).or_else(|| {
+            let legacy = self.legacy_dir.as_ref()?.join(format!("{}.txt", key));
+            self.get_at(&legacy)
+        })
+    }
+
+    fn get_at(&self, path: &Path) -> Option<CachedResponse> {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+        let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+
+        if age > self.ttl {
+            let _ = fs::remove_file(path);
+            return None;
+        }
+
+        match fs::read_to_string(path).ok().and_then(|s| parse_entry(&s)) {
+            Some(response) => Some(CachedResponse {
+                response,
+                age_secs: age.as_secs(),
+            }),
+            None => {
+                let _ = fs::remove_file(path);
+                None
+            }
+        }
+    }
+
+    /// Store a response. Failures are ignored; the cache is best-effort.
+    pub fn put(&self, key: &str, response: &AnalysisResponse) {
+        if create_private_dir(&self.dir).is_err() {
+            return;
+        }
+
+        // Write then rename so a concurrent reader never sees a partial entry
+        let path = self.entry_path(key);
+        let tmp = path.with_extension("tmp");
+        if fs::write(&tmp, format_entry(response)).is_ok() && fs::rename(&tmp, &path).is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.txt", key))
+    }
+}
+
+/// Everything that shapes one analysis response
+#[derive(Debug, Clone)]
+pub struct AnalysisKey<'a> {
+    pub provider: &'a AiProvider,
+    pub model: &'a str,
+    /// `--ai-base-url`, when requests go to a gateway instead of the provider
+    pub base_url: Option<&'a str>,
+    /// Response token budget; a smaller one can truncate the answer
+    pub max_tokens: u32,
+    /// Environment block sent with the log
+    pub environment: &'a str,
+    /// Log content
+    pub content: &'a str,
+    pub question: Option<&'a str>,
+}
+
+/// Cache key for one analysis: a hash of every field of `key`
+pub fn cache_key(key: &AnalysisKey) -> String {
+    let mut hasher = hash::Sha256::new();
+    for part in [
+        format!("{:?}", key.provider).as_bytes(),
+        key.model.as_bytes(),
+        key.base_url.unwrap_or("").as_bytes(),
+        &key.max_tokens.to_be_bytes(),
+        hash::sha256(key.environment.as_bytes()).as_slice(),
+        key.question.unwrap_or("").as_bytes(),
+        hash::sha256(key.content.as_bytes()).as_slice(),
+    ] {
+        // Length-prefix each field so ("ab", "c") and ("a", "bc") differ
+        hasher.update(&(part.len() as u64).to_be_bytes());
+        hasher.update(part);
+    }
+    hash::to_hex(&hasher.finalize())
+}
+
+fn format_entry(response: &AnalysisResponse) -> String {
+    let optional = |v: Option<u32>| v.map(|n| n.to_string()).unwrap_or_default();
+    format!(
+        "model: {}\nprompt_tokens: {}\ncompletion_tokens: {}\n\n{}",
+        response.model,
+        optional(response.prompt_tokens),
+        optional(response.completion_tokens),
+        response.content
+    )
+}
+
+fn parse_entry(text: &str) -> Option<AnalysisResponse> {
+    let (header, content) = text.split_once("\n\n")?;
+    let mut model = None;
+    let mut prompt_tokens = None;
+    let mut completion_tokens = None;
+
+    for line in header.lines() {
+        let (key, value) = line.split_once(':')?;
+        let value = value.trim();
+        match key {
+            "model" => model = Some(value.to_string()),
+            "prompt_tokens" => prompt_tokens = value.parse().ok(),
+            "completion_tokens" => completion_tokens = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    Some(AnalysisResponse {
+        content: content.to_string(),
+        model: model?,
+        prompt_tokens,
+        completion_tokens,
+    })
+}
+
+/// Create the cache directory readable only by the user (responses may
+/// quote log lines)
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response() -> AnalysisResponse {
+        AnalysisResponse {
+            content: "Root cause: HBM OOM.\n\nReduce batch size.".to_string(),
+            model: "gemini-1.5-flash".to_string(),
+            prompt_tokens: Some(120),
+            completion_tokens: None,
+        }
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let base = AnalysisKey {
+            provider: &AiProvider::Google,
+            model: "m",
+            base_url: None,
+            max_tokens: 4096,
+            environment: "TPU: v5e",
+            content: "log",
+            question: Some("why?"),
+        };
+        let a = cache_key(&base);
+        assert_eq!(a.len(), 64);
+        assert_eq!(a, cache_key(&base.clone()));
+        assert_ne!(a, cache_key(&AnalysisKey { provider: &AiProvider::Vertex, ..base.clone() }));
+        assert_ne!(a, cache_key(&AnalysisKey { content: "log2", ..base.clone() }));
+        assert_ne!(a, cache_key(&AnalysisKey { question: None, ..base.clone() }));
+        assert_ne!(a, cache_key(&AnalysisKey { max_tokens: 512, ..base.clone() }));
+        assert_ne!(a, cache_key(&AnalysisKey { base_url: Some("https://gateway.internal"), ..base.clone() }));
+        assert_ne!(a, cache_key(&AnalysisKey { environment: "TPU: v6e", ..base.clone() }));
+    }
+
+    #[test]
+    fn test_entry_round_trip() {
+        let parsed = parse_entry(&format_entry(&response())).unwrap();
+        assert_eq!(parsed.content, response().content);
+        assert_eq!(parsed.model, "gemini-1.5-flash");
+        assert_eq!(parsed.prompt_tokens, Some(120));
+        assert_eq!(parsed.completion_tokens, None);
+    }
+
+    #[test]
+    fn test_cache_get_put_and_ttl() {
+        let dir = std::env::temp_dir().join(format!("tpu-doc-ai-cache-test-{}", std::process::id()));
+        let cache = ResponseCache::new(dir.clone(), 3600);
+
+        assert!(cache.get("missing").is_none());
+        cache.put("key", &response());
+        let cached = cache.get("key").unwrap();
+        assert_eq!(cached.response.content, response().content);
+
+        // A zero TTL expires (and removes) entries as soon as they age
+        std::thread::sleep(Duration::from_millis(50));
+        let expired = ResponseCache::new(dir.clone(), 0);
+        assert!(expired.get("key").is_none());
+        assert!(!dir.join("key.txt").exists());
+
+        // Entries written to the old directory are still found
+        let legacy = dir.join("legacy");
+        ResponseCache::new(legacy.clone(), 3600).put("old", &response());
+        let cache = ResponseCache::new(dir.join("current"), 3600).with_legacy_dir(legacy);
+        assert_eq!(cache.get("old").unwrap().response.content, response().content);
+        assert!(cache.get("missing").is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
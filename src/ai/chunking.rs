@@ -0,0 +1,301 @@
+//! Log pre-filtering and chunking for large analyses.
+//!
+//! TPU training logs routinely run to megabytes, far beyond a model's
+//! context window. Rather than blindly truncating, logs are first stripped
+//! of lines that carry no diagnostic value (blank lines, progress bars,
+//! repeated lines), then split on line boundaries into chunks that fit a
+//! token budget. Each chunk is analyzed separately and the partial findings
+//! are synthesized into one answer.
+
+/// Rough characters-per-token ratio used for budgeting (English/log text)
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Default prompt budget per chunk, in tokens
+pub const DEFAULT_CHUNK_TOKENS: usize = 25_000;
+
+/// Maximum number of chunks sent for analysis in one run
+pub const MAX_CHUNKS: usize = 12;
+
+/// Keywords that mark a line as diagnostically interesting when ranking chunks
+const SIGNAL_KEYWORDS: &[&str] = &[
+    "error",
+    "exception",
+    "traceback",
+    "fatal",
+    "failed",
+    "oom",
+    "out of memory",
+    "resource_exhausted",
+    "deadline_exceeded",
+    "unavailable",
+    "abort",
+    "panic",
+    "warning",
+    "nan",
+];
+
+/// Counts from pre-filtering, reported alongside the analysis
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilterStats {
+    pub total_lines: usize,
+    pub kept_lines: usize,
+    pub blank_lines: usize,
+    pub progress_lines: usize,
+    pub repeated_lines: usize,
+}
+
+impl FilterStats {
+    /// Lines removed by the filter
+    pub fn removed_lines(&self) -> usize {
+        self.total_lines - self.kept_lines
+    }
+}
+
+/// A log split into analyzable chunks
+#[derive(Debug, Clone)]
+pub struct ChunkPlan {
+    /// Chunks selected for analysis, in log order
+    pub chunks: Vec<String>,
+    /// Total chunks before selection
+    pub total_chunks: usize,
+    pub stats: FilterStats,
+}
+
+impl ChunkPlan {
+    /// True when the whole filtered log fits in a single prompt
+    pub fn is_single(&self) -> bool {
+        self.total_chunks <= 1
+    }
+}
+
+/// Estimate the number of tokens in a string
+pub fn estimate_tokens(s: &str) -> usize {
+    s.len().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Filter a log and split it into chunks of at most `chunk_tokens` tokens.
+///
+/// When there are more than MAX_CHUNKS chunks, the ones with the most error
+/// signal are kept (always including the final chunk, where failures
+/// usually surface), preserving log order.
+pub fn plan_chunks(log: &str, chunk_tokens: usize) -> ChunkPlan {
+    let (filtered, stats) = prefilter_log(log);
+    let chunks = split_into_chunks(&filtered, chunk_tokens.max(1) * CHARS_PER_TOKEN);
+    let total_chunks = chunks.len();
+
+    ChunkPlan {
+        chunks: select_chunks(chunks, MAX_CHUNKS),
+        total_chunks,
+        stats,
+    }
+}
+
+/// Remove lines that carry no diagnostic value.
+///
+/// Drops blank lines and progress-bar updates, and collapses runs of
+/// identical lines into a single line plus a repeat marker. A line with
+/// carriage returns is reduced to its last non-empty segment, which is what
+/// a terminal would have left showing.
+pub fn prefilter_log(log: &str) -> (String, FilterStats) {
+    let mut stats = FilterStats::default();
+    let mut output = String::with_capacity(log.len().min(1 << 20));
+    let mut previous: Option<&str> = None;
+    let mut repeats = 0usize;
+
+    let flush_repeats = |output: &mut String, repeats: usize| {
+        if repeats > 0 {
+            output.push_str(&format!("[previous line repeated {} more times]\n", repeats));
+        }
+    };
+
+    for line in log.lines() {
+        stats.total_lines += 1;
+        let line = line.rsplit('\r').find(|segment| !segment.trim().is_empty()).unwrap_or("");
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            stats.blank_lines += 1;
+            continue;
+        }
+        if is_progress_line(trimmed) {
+            stats.progress_lines += 1;
+            continue;
+        }
+        if previous == Some(trimmed) {
+            stats.repeated_lines += 1;
+            repeats += 1;
+            continue;
+        }
+
+        flush_repeats(&mut output, repeats);
+        repeats = 0;
+        output.push_str(line.trim_end());
+        output.push('\n');
+        stats.kept_lines += 1;
+        previous = Some(trimmed);
+    }
+    flush_repeats(&mut output, repeats);
+
+    (output, stats)
+}
+
+/// Progress bars (tqdm, Keras) rather than log records
+fn is_progress_line(line: &str) -> bool {
+    // tqdm: " 45%|████▌     | 450/1000 [00:10<00:12, 44.1it/s]"
+    if line.contains("%|") && (line.contains("it/s]") || line.contains("s/it]") || line.contains("<")) {
+        return true;
+    }
+    // Keras: "120/500 [=====>........................] - ETA: 1:02"
+    line.contains("[=") && line.contains("ETA")
+}
+
+/// Split text on line boundaries into chunks of at most `max_chars` bytes
+fn split_into_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for line in text.lines() {
+        // Overlong single lines are cut so no chunk exceeds the budget
+        let mut rest = line;
+        while rest.len() + 1 > max_chars {
+            let cut = floor_char_boundary(rest, max_chars.saturating_sub(1).max(1));
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+            }
+            chunks.push(format!("{}\n", &rest[..cut]));
+            rest = &rest[cut..];
+        }
+
+        if current.len() + rest.len() + 1 > max_chars && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push_str(rest);
+        current.push('\n');
+    }
+
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    let mut i = index.min(s.len());
+    while i > 0 && !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    if i == 0 {
+        // A single multi-byte character wider than the budget
+        s.chars().next().map(|c| c.len_utf8()).unwrap_or(0)
+    } else {
+        i
+    }
+}
+
+/// Keep at most `max` chunks, preferring those with the most error signal
+fn select_chunks(chunks: Vec<String>, max: usize) -> Vec<String> {
+    if chunks.len() <= max {
+        return chunks;
+    }
+
+    let last = chunks.len() - 1;
+    let mut ranked: Vec<(usize, usize)> = chunks
+        .iter()
+        .enumerate()
+        .take(last)
+        .map(|(i, chunk)| (i, signal_score(chunk)))
+        .collect();
+    ranked.sort_by_key(|&(i, score)| (std::cmp::Reverse(score), i));
+
+    let mut keep: Vec<usize> = ranked.iter().take(max.saturating_sub(1)).map(|&(i, _)| i).collect();
+    keep.push(last);
+    keep.sort_unstable();
+
+    let mut chunks: Vec<Option<String>> = chunks.into_iter().map(Some).collect();
+    keep.into_iter().filter_map(|i| chunks[i].take()).collect()
+}
+
+fn signal_score(chunk: &str) -> usize {
+    chunk
+        .lines()
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            SIGNAL_KEYWORDS.iter().any(|k| lower.contains(k))
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefilter_log() {
+        let log = "step 1\n\n 45%|####      | 450/1000 [00:10<00:12, 44.1it/s]\nretrying\nretrying\nretrying\nError: OOM\n";
+        let (filtered, stats) = prefilter_log(log);
+
+        assert_eq!(
+            filtered,
+            "step 1\nretrying\n[previous line repeated 2 more times]\nError: OOM\n"
+        );
+        assert_eq!(stats.total_lines, 7);
+        assert_eq!(stats.kept_lines, 3);
+        assert_eq!(stats.blank_lines, 1);
+        assert_eq!(stats.progress_lines, 1);
+        assert_eq!(stats.repeated_lines, 2);
+        assert_eq!(stats.removed_lines(), 4);
+    }
+
+    #[test]
+    fn test_prefilter_log_keeps_last_carriage_return_segment() {
+        let bar = |n: u32| format!("{:>3}%|#   | {}/10 [00:0{}<00:05, 1.0it/s]", n * 10, n, n);
+        let log = format!(
+            "{}\r{}\rTraceback (most recent call last):\n{}\r{}\r\nXlaRuntimeError: RESOURCE_EXHAUSTED\n",
+            bar(1),
+            bar(2),
+            bar(3),
+            bar(4)
+        );
+        let (filtered, stats) = prefilter_log(&log);
+
+        assert_eq!(filtered, "Traceback (most recent call last):\nXlaRuntimeError: RESOURCE_EXHAUSTED\n");
+        assert_eq!(stats.progress_lines, 1);
+    }
+
+    #[test]
+    fn test_split_into_chunks_respects_budget() {
+        let text = (0..100).map(|i| format!("line {:03}\n", i)).collect::<String>();
+        let chunks = split_into_chunks(&text, 100);
+
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.len() <= 100));
+        assert_eq!(chunks.concat(), text);
+
+        let long = "x".repeat(250);
+        let chunks = split_into_chunks(&long, 100);
+        assert!(chunks.iter().all(|c| c.len() <= 100));
+    }
+
+    #[test]
+    fn test_select_chunks_prefers_errors_and_last() {
+        let chunks: Vec<String> = (0..6)
+            .map(|i| if i == 2 { "Traceback: error\n".to_string() } else { format!("ok {}\n", i) })
+            .collect();
+        let selected = select_chunks(chunks, 3);
+
+        assert_eq!(selected.len(), 3);
+        assert_eq!(selected[0], "ok 0\n");
+        assert_eq!(selected[1], "Traceback: error\n");
+        assert_eq!(selected[2], "ok 5\n");
+    }
+
+    #[test]
+    fn test_plan_chunks_single() {
+        let plan = plan_chunks("Error: TPU init failed\n", DEFAULT_CHUNK_TOKENS);
+        assert!(plan.is_single());
+        assert_eq!(plan.chunks.len(), 1);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+}
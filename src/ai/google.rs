@@ -6,19 +6,22 @@
 //! # Usage
 //!
 //! ```no_run
+//! # fn main() -> Result<(), tpu_doc::ai::AiError> {
 //! use tpu_doc::ai::google::GeminiClient;
 //!
 //! let client = GeminiClient::new()?;
 //! let response = client.send_message("Analyze this log...", None)?;
 //! println!("{}", response.content);
+//! # Ok(())
+//! # }
 //! ```
 //!
 //! # Environment Variables
 //!
 //! - `GOOGLE_API_KEY`: Required. Your Google API key with Gemini access.
 
-use super::client::HttpClient;
-use super::{AiError, AiProvider, AnalysisResponse};
+use super::client::{BaseUrl, HttpClient};
+use super::{AiError, AiProvider, AnalysisResponse, ChatMessage, ChatRole};
 
 const API_HOST: &str = "generativelanguage.googleapis.com";
 const DEFAULT_MODEL: &str = "gemini-1.5-flash";
@@ -55,6 +58,12 @@ impl GeminiClient {
         self
     }
 
+    /// Send requests to `url` (e.g. an internal LLM gateway) instead of the public API host
+    pub fn with_base_url(mut self, url: &str) -> Result<Self, AiError> {
+        self.http_client = self.http_client.with_base_url(BaseUrl::parse(url)?);
+        Ok(self)
+    }
+
     /// Set the maximum tokens for response
     pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
         self.max_tokens = max_tokens;
@@ -66,13 +75,22 @@ impl GeminiClient {
         &self,
         user_message: &str,
         system_prompt: Option<&str>,
+    ) -> Result<AnalysisResponse, AiError> {
+        self.send_conversation(&[ChatMessage::user(user_message)], system_prompt)
+    }
+
+    /// Send a multi-turn conversation (oldest first, ending with a user turn)
+    pub fn send_conversation(
+        &self,
+        messages: &[ChatMessage],
+        system_prompt: Option<&str>,
     ) -> Result<AnalysisResponse, AiError> {
         let api_path = format!(
             "/v1beta/models/{}:generateContent?key={}",
             self.model, self.api_key
         );
 
-        let request_body = self.build_request_body(user_message, system_prompt);
+        let request_body = build_generate_content_body(messages, system_prompt, self.max_tokens);
 
         let headers = [("Content-Type", "application/json")];
 
@@ -87,85 +105,118 @@ impl GeminiClient {
         self.parse_success_response(&response.body)
     }
 
+    #[cfg(test)]
     fn build_request_body(&self, user_message: &str, system_prompt: Option<&str>) -> String {
-        let escaped_user = escape_json_string(user_message);
-        let escaped_system = system_prompt.map(escape_json_string);
-
-        let mut body = String::new();
-        body.push_str("{\n");
-
-        // System instruction (if provided)
-        if let Some(system) = escaped_system {
-            body.push_str("  \"system_instruction\": {\n");
-            body.push_str("    \"parts\": [\n");
-            body.push_str("      {\n");
-            body.push_str(&format!("        \"text\": \"{}\"\n", system));
-            body.push_str("      }\n");
-            body.push_str("    ]\n");
-            body.push_str("  },\n");
-        }
+        build_generate_content_body(&[ChatMessage::user(user_message)], system_prompt, self.max_tokens)
+    }
+
+    fn parse_success_response(&self, body: &str) -> Result<AnalysisResponse, AiError> {
+        parse_generate_content_response(body, &self.model)
+    }
+
+    fn parse_error_response(&self, body: &str, status: u16) -> AiError {
+        parse_generate_content_error(body, status)
+    }
+}
 
-        // User content
-        body.push_str("  \"contents\": [\n");
+/// Build a `generateContent` request body.
+///
+/// Shared with the Vertex AI client, which serves the same Gemini schema.
+pub(super) fn build_generate_content_body(
+    messages: &[ChatMessage],
+    system_prompt: Option<&str>,
+    max_tokens: u32,
+) -> String {
+    let escaped_system = system_prompt.map(escape_json_string);
+
+    let mut body = String::new();
+    body.push_str("{\n");
+
+    // System instruction (if provided)
+    if let Some(system) = escaped_system {
+        body.push_str("  \"system_instruction\": {\n");
+        body.push_str("    \"parts\": [\n");
+        body.push_str("      {\n");
+        body.push_str(&format!("        \"text\": \"{}\"\n", system));
+        body.push_str("      }\n");
+        body.push_str("    ]\n");
+        body.push_str("  },\n");
+    }
+
+    // Conversation turns (Gemini calls the assistant role "model")
+    body.push_str("  \"contents\": [\n");
+    for (i, message) in messages.iter().enumerate() {
+        let role = match message.role {
+            ChatRole::User => "user",
+            ChatRole::Assistant => "model",
+        };
         body.push_str("    {\n");
-        body.push_str("      \"role\": \"user\",\n");
+        body.push_str(&format!("      \"role\": \"{}\",\n", role));
         body.push_str("      \"parts\": [\n");
         body.push_str("        {\n");
-        body.push_str(&format!("          \"text\": \"{}\"\n", escaped_user));
+        body.push_str(&format!(
+            "          \"text\": \"{}\"\n",
+            escape_json_string(&message.content)
+        ));
         body.push_str("        }\n");
         body.push_str("      ]\n");
-        body.push_str("    }\n");
-        body.push_str("  ],\n");
+        body.push_str(if i + 1 < messages.len() { "    },\n" } else { "    }\n" });
+    }
+    body.push_str("  ],\n");
 
-        // Generation config
-        body.push_str("  \"generationConfig\": {\n");
-        body.push_str(&format!("    \"maxOutputTokens\": {}\n", self.max_tokens));
-        body.push_str("  }\n");
+    // Generation config
+    body.push_str("  \"generationConfig\": {\n");
+    body.push_str(&format!("    \"maxOutputTokens\": {}\n", max_tokens));
+    body.push_str("  }\n");
 
-        body.push_str("}\n");
+    body.push_str("}\n");
 
-        body
-    }
+    body
+}
 
-    fn parse_success_response(&self, body: &str) -> Result<AnalysisResponse, AiError> {
-        // Parse the JSON response manually
-        // Expected format:
-        // {
-        //   "candidates": [{
-        //     "content": {
-        //       "parts": [{"text": "..."}],
-        //       "role": "model"
-        //     }
-        //   }],
-        //   "usageMetadata": {
-        //     "promptTokenCount": N,
-        //     "candidatesTokenCount": N
-        //   }
-        // }
-
-        let content = extract_json_string(body, "text").ok_or_else(|| AiError::ParseError {
-            message: "Could not extract 'text' from response".to_string(),
-        })?;
-
-        let prompt_tokens = extract_json_number(body, "promptTokenCount");
-        let completion_tokens = extract_json_number(body, "candidatesTokenCount");
-
-        Ok(AnalysisResponse {
-            content,
-            model: self.model.clone(),
-            prompt_tokens,
-            completion_tokens,
-        })
-    }
+/// Parse a successful `generateContent` response
+pub(super) fn parse_generate_content_response(
+    body: &str,
+    model: &str,
+) -> Result<AnalysisResponse, AiError> {
+    // Parse the JSON response manually
+    // Expected format:
+    // {
+    //   "candidates": [{
+    //     "content": {
+    //       "parts": [{"text": "..."}],
+    //       "role": "model"
+    //     }
+    //   }],
+    //   "usageMetadata": {
+    //     "promptTokenCount": N,
+    //     "candidatesTokenCount": N
+    //   }
+    // }
+
+    let content = extract_json_string(body, "text").ok_or_else(|| AiError::ParseError {
+        message: "Could not extract 'text' from response".to_string(),
+    })?;
+
+    let prompt_tokens = extract_json_number(body, "promptTokenCount");
+    let completion_tokens = extract_json_number(body, "candidatesTokenCount");
+
+    Ok(AnalysisResponse {
+        content,
+        model: model.to_string(),
+        prompt_tokens,
+        completion_tokens,
+    })
+}
 
-    fn parse_error_response(&self, body: &str, status: u16) -> AiError {
-        // Try to extract error message from response
-        let message = extract_json_string(body, "message")
-            .or_else(|| extract_json_string(body, "error"))
-            .unwrap_or_else(|| format!("HTTP {}", status));
+/// Parse an error response from a `generateContent` endpoint
+pub(super) fn parse_generate_content_error(body: &str, status: u16) -> AiError {
+    // Try to extract error message from response
+    let message = extract_json_string(body, "message")
+        .or_else(|| extract_json_string(body, "error"))
+        .unwrap_or_else(|| format!("HTTP {}", status));
 
-        AiError::ApiError { status, message }
-    }
+    AiError::ApiError { status, message }
 }
 
 /// Escape special characters for JSON string
@@ -284,6 +335,20 @@ mod tests {
         assert!(body.contains("\"maxOutputTokens\": 4096"));
     }
 
+    #[test]
+    fn test_build_conversation_body() {
+        let body = build_generate_content_body(
+            &[ChatMessage::user("What failed?"), ChatMessage::assistant("HBM OOM."), ChatMessage::user("Fix?")],
+            None,
+            1024,
+        );
+
+        assert!(body.contains("\"role\": \"model\""));
+        assert_eq!(body.matches("\"role\": \"user\"").count(), 2);
+        assert!(body.contains("\"text\": \"HBM OOM.\""));
+        assert!(body.contains("\"maxOutputTokens\": 1024"));
+    }
+
     #[test]
     fn test_build_request_body_with_system() {
         let client = GeminiClient::with_key("test-key".to_string());
@@ -20,16 +20,21 @@
 //!
 //! - Anthropic (Claude): Set ANTHROPIC_API_KEY environment variable
 //! - Google (Gemini): Set GOOGLE_API_KEY environment variable
+//! - Vertex AI (Gemini): Uses the VM's service account via the metadata server
 //!
 //! # Design Principles
 //!
 //! - No runtime dependencies for the core binary
 //! - AI features are strictly opt-in
-//! - User must provide their own API keys
+//! - User must provide their own API keys (or, for Vertex AI, their VM's
+//!   service account)
 //! - Graceful error handling for API failures
 
+pub mod cache;
+pub mod chunking;
 pub mod client;
 pub mod prompt;
+pub mod redact;
 
 #[cfg(feature = "ai")]
 pub mod anthropic;
@@ -37,6 +42,9 @@ pub mod anthropic;
 #[cfg(feature = "ai")]
 pub mod google;
 
+#[cfg(feature = "ai")]
+pub mod vertex;
+
 use crate::TpuDocError;
 
 /// AI provider selection
@@ -45,26 +53,37 @@ pub enum AiProvider {
     #[default]
     Anthropic,
     Google,
+    Vertex,
 }
 
-impl AiProvider {
+impl std::str::FromStr for AiProvider {
+    type Err = String;
+
     /// Parse provider from string
-    pub fn from_str(s: &str) -> Result<Self, String> {
+    fn from_str(s: &str) -> Result<Self, String> {
         match s.to_lowercase().as_str() {
             "anthropic" | "claude" => Ok(AiProvider::Anthropic),
             "google" | "gemini" => Ok(AiProvider::Google),
+            "vertex" | "vertex-ai" | "vertexai" => Ok(AiProvider::Vertex),
             _ => Err(format!(
-                "Unknown AI provider: '{}'. Valid providers: anthropic, google",
+                "Unknown AI provider: '{}'. Valid providers: anthropic, google, vertex",
                 s
             )),
         }
     }
+}
 
-    /// Get the environment variable name for the API key
+impl AiProvider {
+
+    /// Get the environment variable name for the API key.
+    ///
+    /// For Vertex AI this is an optional token override; the metadata server
+    /// is used when it is unset.
     pub fn api_key_env_var(&self) -> &'static str {
         match self {
             AiProvider::Anthropic => "ANTHROPIC_API_KEY",
             AiProvider::Google => "GOOGLE_API_KEY",
+            AiProvider::Vertex => "VERTEX_ACCESS_TOKEN",
         }
     }
 
@@ -73,6 +92,7 @@ impl AiProvider {
         match self {
             AiProvider::Anthropic => "claude-sonnet-4-20250514",
             AiProvider::Google => "gemini-1.5-flash",
+            AiProvider::Vertex => "gemini-1.5-flash",
         }
     }
 }
@@ -104,6 +124,38 @@ impl Default for AnalysisRequest {
     }
 }
 
+/// Speaker of a message in a multi-turn conversation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChatRole {
+    User,
+    Assistant,
+}
+
+/// One turn of a multi-turn conversation
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+impl ChatMessage {
+    /// A message from the user
+    pub fn user(content: &str) -> Self {
+        ChatMessage {
+            role: ChatRole::User,
+            content: content.to_string(),
+        }
+    }
+
+    /// A reply from the model
+    pub fn assistant(content: &str) -> Self {
+        ChatMessage {
+            role: ChatRole::Assistant,
+            content: content.to_string(),
+        }
+    }
+}
+
 /// AI analysis response
 #[derive(Debug, Clone)]
 pub struct AnalysisResponse {
@@ -166,12 +218,11 @@ impl std::fmt::Display for AiError {
     }
 }
 
+impl std::error::Error for AiError {}
+
 impl From<AiError> for TpuDocError {
     fn from(e: AiError) -> Self {
-        TpuDocError::CommandError {
-            command: "analyze".to_string(),
-            message: e.to_string(),
-        }
+        TpuDocError::command("analyze", e)
     }
 }
 
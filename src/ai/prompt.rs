@@ -41,6 +41,8 @@ pub struct PromptBuilder {
     check_results: Option<String>,
     log_content: Option<String>,
     user_question: Option<String>,
+    chunk: Option<(usize, usize)>,
+    partial_findings: Option<String>,
 }
 
 impl PromptBuilder {
@@ -73,9 +75,31 @@ impl PromptBuilder {
         self
     }
 
+    /// Mark the log content as part `index` (1-based) of `total` chunks.
+    ///
+    /// Chunk prompts ask for concise findings to be synthesized later
+    /// rather than a full answer.
+    pub fn with_chunk(mut self, index: usize, total: usize) -> Self {
+        self.chunk = Some((index, total));
+        self
+    }
+
+    /// Add findings from earlier per-chunk analyses to synthesize
+    pub fn with_partial_findings(mut self, findings: &[String]) -> Self {
+        let mut text = String::new();
+        for (i, finding) in findings.iter().enumerate() {
+            text.push_str(&format!("### Section {} of {}\n\n", i + 1, findings.len()));
+            text.push_str(finding.trim());
+            text.push_str("\n\n");
+        }
+        self.partial_findings = Some(text);
+        self
+    }
+
     /// Build the final prompt
     pub fn build(self) -> String {
         let mut prompt = String::new();
+        let synthesis = self.partial_findings.is_some();
 
         // Start with context sections
         if let Some(env) = self.environment_context {
@@ -90,8 +114,18 @@ impl PromptBuilder {
             prompt.push_str("\n\n");
         }
 
+        if let Some(findings) = self.partial_findings {
+            prompt.push_str("## Findings From Log Sections\n\n");
+            prompt.push_str(&findings);
+        }
+
         if let Some(log) = self.log_content {
-            prompt.push_str("## Log Content\n\n");
+            match self.chunk {
+                Some((index, total)) => {
+                    prompt.push_str(&format!("## Log Content (part {} of {})\n\n", index, total))
+                }
+                None => prompt.push_str("## Log Content\n\n"),
+            }
             prompt.push_str("```\n");
             prompt.push_str(&log);
             prompt.push_str("\n```\n\n");
@@ -99,7 +133,27 @@ impl PromptBuilder {
 
         // Add the question or default request
         prompt.push_str("## Request\n\n");
-        if let Some(question) = self.user_question {
+        if let Some((index, total)) = self.chunk {
+            prompt.push_str(&format!(
+                "This is part {} of {} of a large log, pre-filtered to remove blank lines, \
+                 progress bars, and repeated lines. List only the errors, warnings, and \
+                 notable events in this part, with the relevant log lines quoted. Be brief; \
+                 your findings will be combined with those from the other parts.",
+                index, total
+            ));
+            if let Some(question) = self.user_question {
+                prompt.push_str(&format!(" Focus on anything relevant to: {}", question));
+            }
+        } else if synthesis {
+            prompt.push_str(
+                "The findings above were extracted from consecutive sections of one large log. \
+                 Combine them into a single diagnosis: identify the root cause, explain what \
+                 happened, and provide specific steps to resolve it. ",
+            );
+            if let Some(question) = self.user_question {
+                prompt.push_str(&format!("Answer this question: {}", question));
+            }
+        } else if let Some(question) = self.user_question {
             prompt.push_str(&question);
         } else {
             prompt.push_str(
@@ -324,6 +378,25 @@ mod tests {
         assert!(prompt.contains("TPU initialization failed"));
     }
 
+    #[test]
+    fn test_prompt_builder_chunk_and_synthesis() {
+        let prompt = PromptBuilder::new()
+            .with_log_content("Error: OOM")
+            .with_chunk(2, 3)
+            .build();
+        assert!(prompt.contains("## Log Content (part 2 of 3)"));
+        assert!(prompt.contains("part 2 of 3 of a large log"));
+
+        let prompt = PromptBuilder::new()
+            .with_partial_findings(&["OOM at step 10".to_string(), "Retry".to_string()])
+            .with_question("Why did it crash?")
+            .build();
+        assert!(prompt.contains("### Section 1 of 2"));
+        assert!(prompt.contains("OOM at step 10"));
+        assert!(prompt.contains("Answer this question: Why did it crash?"));
+        assert!(!prompt.contains("## Log Content"));
+    }
+
     #[test]
     fn test_truncate_log_content_short() {
         let log = "Short log content";
@@ -0,0 +1,191 @@
+//! Vertex AI (Gemini) client.
+//!
+//! Calls Gemini through Vertex AI, authenticating with the VM's service
+//! account token from the GCE metadata server. On a TPU VM this means no
+//! personal API key has to be exported.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! # fn main() -> Result<(), tpu_doc::ai::AiError> {
+//! use tpu_doc::ai::vertex::VertexClient;
+//!
+//! let client = VertexClient::new()?;
+//! let response = client.send_message("Analyze this log...", None)?;
+//! println!("{}", response.content);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Environment Variables
+//!
+//! - `VERTEX_ACCESS_TOKEN`: Optional. OAuth2 token to use instead of the
+//!   metadata server (e.g. from `gcloud auth print-access-token` off GCP).
+//! - `GOOGLE_CLOUD_PROJECT`: Optional. Project to bill; defaults to the VM's project.
+//! - `GOOGLE_CLOUD_LOCATION`: Optional. Vertex AI region; defaults to `us-central1`.
+//!
+//! The service account needs the `roles/aiplatform.user` role and the VM
+//! must have the `cloud-platform` access scope.
+
+use super::client::{BaseUrl, HttpClient};
+use super::google::{
+    build_generate_content_body, parse_generate_content_error, parse_generate_content_response,
+};
+use super::{AiError, AiProvider, AnalysisResponse, ChatMessage};
+use crate::platform::gcp;
+
+const DEFAULT_MODEL: &str = "gemini-1.5-flash";
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+const DEFAULT_LOCATION: &str = "us-central1";
+
+/// Vertex AI Gemini client
+pub struct VertexClient {
+    access_token: String,
+    project: String,
+    location: String,
+    model: String,
+    max_tokens: u32,
+    http_client: HttpClient,
+}
+
+impl VertexClient {
+    /// Create a new client using the metadata server (or `VERTEX_ACCESS_TOKEN`)
+    pub fn new() -> Result<Self, AiError> {
+        let access_token = match super::get_api_key(&AiProvider::Vertex) {
+            Ok(token) => token,
+            Err(_) => gcp::get_access_token().map_err(|e| AiError::RequestFailed {
+                message: format!(
+                    "Could not get a service account token from the metadata server ({}). \
+                     Run on a GCP VM or set VERTEX_ACCESS_TOKEN.",
+                    e
+                ),
+            })?,
+        };
+
+        let project = match std::env::var("GOOGLE_CLOUD_PROJECT") {
+            Ok(project) if !project.trim().is_empty() => project,
+            _ => gcp::get_project_id().map_err(|e| AiError::RequestFailed {
+                message: format!(
+                    "Could not determine the GCP project ({}). Set GOOGLE_CLOUD_PROJECT.",
+                    e
+                ),
+            })?,
+        };
+
+        let location = std::env::var("GOOGLE_CLOUD_LOCATION")
+            .ok()
+            .filter(|l| !l.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_LOCATION.to_string());
+
+        Ok(Self::with_credentials(access_token, project, location))
+    }
+
+    /// Create a new client with an explicit token, project, and region
+    pub fn with_credentials(access_token: String, project: String, location: String) -> Self {
+        VertexClient {
+            access_token,
+            project,
+            location,
+            model: DEFAULT_MODEL.to_string(),
+            max_tokens: DEFAULT_MAX_TOKENS,
+            http_client: HttpClient::new(),
+        }
+    }
+
+    /// Set the model to use
+    pub fn with_model(mut self, model: &str) -> Self {
+        self.model = model.to_string();
+        self
+    }
+
+    /// Send requests to `url` (e.g. an internal LLM gateway) instead of the public API host
+    pub fn with_base_url(mut self, url: &str) -> Result<Self, AiError> {
+        self.http_client = self.http_client.with_base_url(BaseUrl::parse(url)?);
+        Ok(self)
+    }
+
+    /// Set the maximum tokens for response
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Send a message to the API and get a response
+    pub fn send_message(
+        &self,
+        user_message: &str,
+        system_prompt: Option<&str>,
+    ) -> Result<AnalysisResponse, AiError> {
+        self.send_conversation(&[ChatMessage::user(user_message)], system_prompt)
+    }
+
+    /// Send a multi-turn conversation (oldest first, ending with a user turn)
+    pub fn send_conversation(
+        &self,
+        messages: &[ChatMessage],
+        system_prompt: Option<&str>,
+    ) -> Result<AnalysisResponse, AiError> {
+        let request_body = build_generate_content_body(messages, system_prompt, self.max_tokens);
+
+        let authorization = format!("Bearer {}", self.access_token);
+        let headers = [
+            ("Content-Type", "application/json"),
+            ("Authorization", authorization.as_str()),
+        ];
+
+        let response =
+            self.http_client
+                .post_https(&self.api_host(), &self.api_path(), &headers, &request_body)?;
+
+        if !response.is_success() {
+            let error = parse_generate_content_error(&response.body, response.status);
+            return Err(match error {
+                AiError::ApiError { status, message } if status == 401 || status == 403 => {
+                    AiError::ApiError {
+                        status,
+                        message: format!(
+                            "{} (the service account needs roles/aiplatform.user and the VM \
+                             the cloud-platform access scope)",
+                            message
+                        ),
+                    }
+                }
+                other => other,
+            });
+        }
+
+        parse_generate_content_response(&response.body, &self.model)
+    }
+
+    fn api_host(&self) -> String {
+        format!("{}-aiplatform.googleapis.com", self.location)
+    }
+
+    fn api_path(&self) -> String {
+        format!(
+            "/v1/projects/{}/locations/{}/publishers/google/models/{}:generateContent",
+            self.project, self.location, self.model
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_endpoint() {
+        let client = VertexClient::with_credentials(
+            "token".to_string(),
+            "my-project".to_string(),
+            "europe-west4".to_string(),
+        )
+        .with_model("gemini-1.5-pro");
+
+        assert_eq!(client.api_host(), "europe-west4-aiplatform.googleapis.com");
+        assert_eq!(
+            client.api_path(),
+            "/v1/projects/my-project/locations/europe-west4/publishers/google/models/gemini-1.5-pro:generateContent"
+        );
+    }
+}
@@ -0,0 +1,553 @@
+//! Parameters for the performance benchmarks.
+//!
+//! Each benchmark the performance checks run takes its sizes, iteration
+//! counts, and seed from [`params`], and the checks record them in their
+//! result data (see [`result_data`]) so a reader of the report
+//! knows what was measured.
+//!
+//! By default a benchmark warms up adaptively: after the configured number
+//! of warm-up iterations (`[benchmarks.warmup]` in the configuration file,
+//! see [`warmup_iterations`]) it keeps going, up to
+//! [`MAX_ADAPTIVE_WARMUP_FACTOR`] times as many, until an iteration runs
+//! within 5% of the one before it. That absorbs compilation and clock
+//! ramp-up on slower stacks, but means two runs may time after different
+//! amounts of warm-up. In deterministic mode (`--deterministic`) every
+//! benchmark uses the fixed reference parameters and exactly the reference
+//! warm-up, ignoring the configuration, so results are comparable across
+//! nodes and over time.
+//!
+//! The perf level (`--perf-level`, see [`PerfLevel`]) scales the sizes and
+//! iteration counts and decides which performance checks run: `smoke` is a
+//! quick pass for gating a node at boot, `standard` the default, and `soak`
+//! a burn-in that adds the sustained-load thermal test (PERF-008).
+//! Deterministic mode fixes the parameters of whichever level is selected.
+//!
+//! The HBM bandwidth benchmark's working set scales with the chip's HBM
+//! (see [`HbmWorkingSet`]) so it stresses large-memory generations without
+//! crowding small ones; `[benchmarks] hbm_working_set` overrides it.
+//!
+//! Before running, each performance check makes sure no other workload is
+//! using the TPU ([`busy_tpu_result`]): a benchmark on a busy device
+//! measures shared hardware and slows the tenant down. What happens then
+//! is set by `[benchmarks] busy_tpu` (see [`BusyTpuPolicy`]).
+//!
+//! All of these options come from the running check's
+//! [`RunSettings`](super::settings::RunSettings).
+
+use crate::checks::settings;
+use crate::platform::tpu::{self, TpuActivity};
+use crate::CheckResult;
+use std::fmt;
+use std::time::Instant;
+
+/// Cap on adaptive warm-up, as a multiple of the warm-up iterations
+pub const MAX_ADAPTIVE_WARMUP_FACTOR: u32 = 5;
+
+/// Warm-up iterations each benchmark runs unless configured otherwise
+pub const DEFAULT_WARMUP_ITERATIONS: u32 = 1;
+
+/// Default HBM bandwidth working set, as a percentage of the chip's HBM
+/// (about 1 GB on v5e and 5.7 GB on v5p)
+pub const DEFAULT_HBM_WORKING_SET_PERCENT: u32 = 6;
+
+/// Largest HBM working set as a percentage of the chip's HBM; the
+/// benchmark holds the array and its result, so twice this must fit
+pub const MAX_HBM_WORKING_SET_PERCENT: u32 = 45;
+
+/// Warm-up iterations for each benchmark
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WarmupIterations {
+    /// MXU utilization (PERF-001)
+    pub mxu: u32,
+    /// HBM bandwidth (PERF-002)
+    pub hbm: u32,
+    /// Chip-to-chip latency (PERF-003)
+    pub latency: u32,
+    /// Collective bandwidth (PERF-006)
+    pub collective: u32,
+}
+
+impl Default for WarmupIterations {
+    fn default() -> Self {
+        WarmupIterations {
+            mxu: DEFAULT_WARMUP_ITERATIONS,
+            hbm: DEFAULT_WARMUP_ITERATIONS,
+            latency: DEFAULT_WARMUP_ITERATIONS,
+            collective: DEFAULT_WARMUP_ITERATIONS,
+        }
+    }
+}
+
+/// Mean duty cycle at or above which the TPU counts as busy
+pub const BUSY_DUTY_CYCLE_PERCENT: f64 = 5.0;
+
+/// HBM use at or above which the TPU counts as busy
+pub const BUSY_HBM_PERCENT: f64 = 5.0;
+
+/// What the performance checks do when another workload is using the TPU
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BusyTpuPolicy {
+    /// Skip the check without running the benchmark
+    #[default]
+    Skip = 0,
+    /// Warn without running the benchmark, so CI notices
+    Warn = 1,
+    /// Run the benchmark anyway
+    Run = 2,
+}
+
+impl BusyTpuPolicy {
+    /// Policy for a name such as `skip` or `warn`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "skip" => Some(BusyTpuPolicy::Skip),
+            "warn" => Some(BusyTpuPolicy::Warn),
+            "run" => Some(BusyTpuPolicy::Run),
+            _ => None,
+        }
+    }
+}
+
+/// How long the sustained-load thermal test (PERF-008) keeps the TPU busy
+pub const SOAK_DURATION_SECS: u64 = 600;
+
+/// How hard the performance checks push the TPU
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PerfLevel {
+    /// Small sizes and few iterations, and only the MXU, HBM, memory
+    /// pressure, and collective checks, for gating a node at boot
+    Smoke = 0,
+    /// The default sizes and iterations
+    #[default]
+    Standard = 1,
+    /// Large sizes and many iterations, the training step without opting
+    /// in, and the sustained-load thermal test, for burn-in qualification
+    Soak = 2,
+}
+
+impl PerfLevel {
+    /// Level for a name such as `smoke` or `soak`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "smoke" => Some(PerfLevel::Smoke),
+            "standard" => Some(PerfLevel::Standard),
+            "soak" => Some(PerfLevel::Soak),
+            _ => None,
+        }
+    }
+
+    /// Why the performance check `id` does not run at this level, or None
+    /// when it does
+    pub fn skip_reason(&self, id: &str) -> Option<String> {
+        match (self, id) {
+            (PerfLevel::Smoke, "PERF-003" | "PERF-004" | "PERF-007") => {
+                Some("Not run at --perf-level smoke".to_string())
+            }
+            (PerfLevel::Smoke | PerfLevel::Standard, "PERF-008") => {
+                Some("Soak-level check - run with --perf-level soak to enable".to_string())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PerfLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PerfLevel::Smoke => write!(f, "smoke"),
+            PerfLevel::Standard => write!(f, "standard"),
+            PerfLevel::Soak => write!(f, "soak"),
+        }
+    }
+}
+
+/// Size of the array the HBM bandwidth benchmark streams
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HbmWorkingSet {
+    /// A fixed size in MB
+    Megabytes(u32),
+    /// A percentage of the chip's HBM
+    Percent(u32),
+}
+
+impl HbmWorkingSet {
+    /// Parse a size such as `512MB`, `4GB`, or `10%`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let lower = s.to_ascii_lowercase();
+        let parsed = if let Some(pct) = lower.strip_suffix('%') {
+            pct.trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|pct| (1..=MAX_HBM_WORKING_SET_PERCENT).contains(pct))
+                .map(HbmWorkingSet::Percent)
+        } else if let Some(gb) = lower.strip_suffix("gb") {
+            gb.trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|gb| *gb > 0)
+                .and_then(|gb| gb.checked_mul(1024))
+                .map(HbmWorkingSet::Megabytes)
+        } else if let Some(mb) = lower.strip_suffix("mb") {
+            mb.trim().parse::<u32>().ok().filter(|mb| *mb > 0).map(HbmWorkingSet::Megabytes)
+        } else {
+            None
+        };
+        parsed.ok_or_else(|| {
+            format!(
+                "invalid HBM working set '{}' (expected a size such as 512MB or 4GB, or 1% to {}% of HBM)",
+                s, MAX_HBM_WORKING_SET_PERCENT
+            )
+        })
+    }
+
+    /// The working set in MB on a chip with `hbm_per_chip_gb` of HBM
+    pub fn size_mb(&self, hbm_per_chip_gb: u32) -> u32 {
+        match self {
+            HbmWorkingSet::Megabytes(mb) => *mb,
+            HbmWorkingSet::Percent(pct) => (hbm_per_chip_gb as u64 * 1024 * *pct as u64 / 100) as u32,
+        }
+    }
+}
+
+impl Default for HbmWorkingSet {
+    fn default() -> Self {
+        HbmWorkingSet::Percent(DEFAULT_HBM_WORKING_SET_PERCENT)
+    }
+}
+
+impl fmt::Display for HbmWorkingSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HbmWorkingSet::Megabytes(mb) => write!(f, "{} MB", mb),
+            HbmWorkingSet::Percent(pct) => write!(f, "{}% of HBM", pct),
+        }
+    }
+}
+
+/// Sizes, iteration counts, and seed for the benchmarks
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkParams {
+    /// Side of the square bf16 matrices the MXU benchmark multiplies
+    pub matmul_size: u32,
+    /// Size of the array the HBM bandwidth benchmark streams
+    pub hbm_working_set: HbmWorkingSet,
+    /// Timed iterations of the MXU, HBM, and collective benchmarks
+    pub iterations: u32,
+    /// Timed transfers of the chip-to-chip latency benchmark
+    pub latency_iterations: u32,
+    /// Training steps timed by the smoke test
+    pub training_steps: u32,
+    /// Warm-up iterations before timing starts
+    pub warmup: WarmupIterations,
+    /// Keep warming up until iteration times settle
+    pub adaptive_warmup: bool,
+    /// Seed for randomly initialized inputs
+    pub seed: u64,
+}
+
+impl BenchmarkParams {
+    /// The fixed reference parameters `--deterministic` runs with at the
+    /// standard perf level
+    pub fn reference() -> Self {
+        BenchmarkParams {
+            matmul_size: 4096,
+            hbm_working_set: HbmWorkingSet::Megabytes(1024),
+            iterations: 10,
+            latency_iterations: 100,
+            training_steps: 5,
+            warmup: WarmupIterations::default(),
+            adaptive_warmup: false,
+            seed: 0,
+        }
+    }
+
+    /// The fixed reference parameters at `level`
+    pub fn reference_at(level: PerfLevel) -> Self {
+        match level {
+            PerfLevel::Smoke => BenchmarkParams {
+                matmul_size: 2048,
+                hbm_working_set: HbmWorkingSet::Megabytes(256),
+                iterations: 3,
+                latency_iterations: 20,
+                training_steps: 2,
+                ..BenchmarkParams::reference()
+            },
+            PerfLevel::Standard => BenchmarkParams::reference(),
+            PerfLevel::Soak => BenchmarkParams {
+                matmul_size: 8192,
+                hbm_working_set: HbmWorkingSet::Megabytes(4096),
+                iterations: 100,
+                latency_iterations: 1000,
+                training_steps: 50,
+                ..BenchmarkParams::reference()
+            },
+        }
+    }
+
+    /// Warm-up of `iterations` as passed to the benchmark scripts: the
+    /// iteration count, then 1 for adaptive warm-up or 0
+    pub fn warmup_args(&self, iterations: u32) -> [String; 2] {
+        [iterations.to_string(), u8::from(self.adaptive_warmup).to_string()]
+    }
+
+    /// How a benchmark with `iterations` of warm-up warmed up, e.g.
+    /// `1 iteration, adaptive`
+    pub fn warmup_label(&self, iterations: u32) -> String {
+        let count = match iterations {
+            1 => "1 iteration".to_string(),
+            n => format!("{} iterations", n),
+        };
+        if self.adaptive_warmup {
+            format!("{}, adaptive", count)
+        } else {
+            count
+        }
+    }
+}
+
+/// Whether the benchmarks run with the fixed reference parameters
+pub fn deterministic() -> bool {
+    settings::current().deterministic
+}
+
+/// How hard the performance checks push the TPU
+pub fn perf_level() -> PerfLevel {
+    settings::current().perf_level
+}
+
+/// The result a performance check reports in place of running when this
+/// run's perf level leaves it out, or None to go ahead
+pub fn perf_level_result(id: &str) -> Option<CheckResult> {
+    perf_level().skip_reason(id).map(|reason| CheckResult::Skip { reason })
+}
+
+/// The configured warm-up iterations for each benchmark, from the
+/// configuration file; deterministic mode ignores them
+pub fn warmup_iterations() -> WarmupIterations {
+    settings::current().warmup
+}
+
+/// The configured HBM bandwidth working set, from the configuration file;
+/// deterministic mode ignores it
+pub fn hbm_working_set() -> HbmWorkingSet {
+    settings::current().hbm_working_set
+}
+
+/// The parameters for this run's benchmarks at its perf level. Outside
+/// deterministic mode the configured HBM working set applies at the
+/// standard level; smoke and soak use their own.
+pub fn params() -> BenchmarkParams {
+    let level = perf_level();
+    let reference = BenchmarkParams::reference_at(level);
+    if deterministic() {
+        reference
+    } else {
+        BenchmarkParams {
+            hbm_working_set: match level {
+                PerfLevel::Standard => hbm_working_set(),
+                _ => reference.hbm_working_set,
+            },
+            warmup: warmup_iterations(),
+            adaptive_warmup: true,
+            ..reference
+        }
+    }
+}
+
+/// Result data recording `measured` (name and value pairs describing what
+/// a benchmark ran, e.g. its iterations and warm-up), the benchmark mode,
+/// and the perf level
+pub fn result_data(measured: &[(&str, String)]) -> Vec<(String, String)> {
+    let mut data: Vec<(String, String)> =
+        measured.iter().map(|(key, value)| (key.to_string(), value.clone())).collect();
+    let mode = if deterministic() { "deterministic" } else { "default" };
+    data.push(("benchmark mode".to_string(), mode.to_string()));
+    data.push(("perf level".to_string(), perf_level().to_string()));
+    data
+}
+
+/// What the performance checks do when the TPU is busy
+pub fn busy_tpu_policy() -> BusyTpuPolicy {
+    settings::current().busy_tpu
+}
+
+/// The workload using the TPU, e.g. `held by PID 4242; duty cycle 87.5%,
+/// HBM 62.0% used`, or None when the TPU is idle
+pub fn describe_workload(activity: &TpuActivity) -> Option<String> {
+    let mut parts = Vec::new();
+    if !activity.holders.is_empty() {
+        let pids: Vec<String> = activity.holders.iter().map(|pid| pid.to_string()).collect();
+        let label = if pids.len() == 1 { "PID" } else { "PIDs" };
+        parts.push(format!("held by {} {}", label, pids.join(", ")));
+    }
+    if let Some(metrics) = &activity.metrics {
+        let duty = metrics.mean_duty_cycle_percent().filter(|v| *v >= BUSY_DUTY_CYCLE_PERCENT);
+        let hbm = metrics.hbm_utilization_percent().filter(|v| *v >= BUSY_HBM_PERCENT);
+        let usage: Vec<String> = [
+            duty.map(|v| format!("duty cycle {:.1}%", v)),
+            hbm.map(|v| format!("HBM {:.1}% used", v)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !usage.is_empty() {
+            parts.push(usage.join(", "));
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("; "))
+}
+
+/// The result a performance check reports in place of running its
+/// benchmark when another workload is using the TPU, or None to go ahead
+pub fn busy_tpu_result() -> Option<CheckResult> {
+    let policy = busy_tpu_policy();
+    if policy == BusyTpuPolicy::Run {
+        return None;
+    }
+
+    let start = Instant::now();
+    let workload = describe_workload(&tpu::get_tpu_activity())?;
+    match policy {
+        BusyTpuPolicy::Warn => Some(CheckResult::Warn {
+            message: format!("TPU busy, benchmark not run ({})", workload),
+            details: "Another workload is using the TPU; benchmarking now would disturb it and measure shared \
+                      hardware. Re-run when the TPU is idle."
+                .to_string(),
+            duration_ms: start.elapsed().as_millis() as u64,
+            data: vec![("tpu workload".to_string(), workload)],
+        }),
+        _ => Some(CheckResult::Skip {
+            reason: format!("TPU busy, not benchmarking a device another workload is using: {}", workload),
+        }),
+    }
+}
+
+/// Python helper the benchmark scripts start with: `warm_up(step, count,
+/// adaptive)` calls `step` `count` times, or adaptively as described in the
+/// module documentation
+pub(crate) const WARM_UP_PY: &str = r#"
+import time
+
+def warm_up(step, count, adaptive):
+    previous = None
+    for i in range(count * 5 if adaptive else count):
+        start = time.perf_counter()
+        step()
+        elapsed = time.perf_counter() - start
+        if adaptive and i + 1 >= count and previous is not None and abs(elapsed - previous) <= 0.05 * previous:
+            break
+        previous = elapsed
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params_and_data() {
+        let reference = BenchmarkParams::reference();
+        assert!(!reference.adaptive_warmup);
+        assert_eq!(reference.warmup, WarmupIterations::default());
+        assert_eq!(reference.warmup_args(reference.warmup.mxu), ["1".to_string(), "0".to_string()]);
+        assert_eq!(reference.warmup_label(reference.warmup.hbm), "1 iteration");
+
+        let adaptive = BenchmarkParams {
+            adaptive_warmup: true,
+            ..BenchmarkParams::reference()
+        };
+        assert_eq!(adaptive.warmup_args(3), ["3".to_string(), "1".to_string()]);
+        assert_eq!(adaptive.warmup_label(3), "3 iterations, adaptive");
+        assert_eq!(adaptive.warmup_label(0), "0 iterations, adaptive");
+
+        let data = result_data(&[("iterations", "10".to_string()), ("warm-up", reference.warmup_label(1))]);
+        assert_eq!(data[0], ("iterations".to_string(), "10".to_string()));
+        assert_eq!(data[1], ("warm-up".to_string(), "1 iteration".to_string()));
+        assert_eq!(data[2].0, "benchmark mode");
+        assert_eq!(data[3].0, "perf level");
+        assert!(WARM_UP_PY.contains(&format!("count * {}", MAX_ADAPTIVE_WARMUP_FACTOR)));
+    }
+
+    #[test]
+    fn test_perf_levels() {
+        assert_eq!(PerfLevel::from_name("Soak"), Some(PerfLevel::Soak));
+        assert_eq!(PerfLevel::from_name("burn-in"), None);
+        assert_eq!(PerfLevel::default().to_string(), "standard");
+        assert_eq!(BenchmarkParams::reference_at(PerfLevel::Standard), BenchmarkParams::reference());
+
+        let smoke = BenchmarkParams::reference_at(PerfLevel::Smoke);
+        let soak = BenchmarkParams::reference_at(PerfLevel::Soak);
+        assert!(smoke.matmul_size < 4096 && soak.matmul_size > 4096);
+        assert!(smoke.iterations < 10 && soak.iterations > 10);
+        assert_eq!(soak.seed, 0);
+        assert!(!soak.adaptive_warmup);
+
+        for id in ["PERF-001", "PERF-002", "PERF-005", "PERF-006"] {
+            assert_eq!(PerfLevel::Smoke.skip_reason(id), None);
+        }
+        assert!(PerfLevel::Smoke.skip_reason("PERF-004").unwrap().contains("smoke"));
+        assert_eq!(PerfLevel::Standard.skip_reason("PERF-004"), None);
+        assert!(PerfLevel::Standard.skip_reason("PERF-008").unwrap().contains("--perf-level soak"));
+        for id in ["PERF-003", "PERF-007", "PERF-008"] {
+            assert_eq!(PerfLevel::Soak.skip_reason(id), None);
+        }
+    }
+
+    #[test]
+    fn test_describe_workload() {
+        use crate::platform::tpu::{ChipMetrics, RuntimeMetrics};
+
+        assert_eq!(describe_workload(&TpuActivity::default()), None);
+
+        let activity = TpuActivity {
+            holders: vec![4242],
+            metrics: None,
+        };
+        assert_eq!(describe_workload(&activity).as_deref(), Some("held by PID 4242"));
+
+        let chip = |duty: f64, used_gb: u64| ChipMetrics {
+            duty_cycle_percent: Some(duty),
+            hbm_used_bytes: Some(used_gb << 30),
+            hbm_total_bytes: Some(16 << 30),
+            ..Default::default()
+        };
+        let activity = TpuActivity {
+            holders: vec![4242, 4243],
+            metrics: Some(RuntimeMetrics {
+                chips: vec![chip(80.0, 8), chip(95.0, 12)],
+            }),
+        };
+        assert_eq!(
+            describe_workload(&activity).as_deref(),
+            Some("held by PIDs 4242, 4243; duty cycle 87.5%, HBM 62.5% used")
+        );
+
+        // An idle runtime that nothing holds is not a workload
+        let activity = TpuActivity {
+            holders: Vec::new(),
+            metrics: Some(RuntimeMetrics {
+                chips: vec![chip(0.0, 0)],
+            }),
+        };
+        assert_eq!(describe_workload(&activity), None);
+
+        assert_eq!(BusyTpuPolicy::from_name("Warn"), Some(BusyTpuPolicy::Warn));
+        assert_eq!(BusyTpuPolicy::from_name("fail"), None);
+    }
+
+    #[test]
+    fn test_hbm_working_set() {
+        assert_eq!(HbmWorkingSet::parse("512MB"), Ok(HbmWorkingSet::Megabytes(512)));
+        assert_eq!(HbmWorkingSet::parse(" 4gb "), Ok(HbmWorkingSet::Megabytes(4096)));
+        assert_eq!(HbmWorkingSet::parse("10%"), Ok(HbmWorkingSet::Percent(10)));
+        for bad in ["", "4", "0MB", "-1GB", "1.5GB", "0%", "50%", "4TB"] {
+            assert!(HbmWorkingSet::parse(bad).is_err(), "{:?} should be rejected", bad);
+        }
+
+        // Scaled from the chip's HBM: v5e has 16 GB, v5p 95 GB
+        assert_eq!(HbmWorkingSet::default().size_mb(16), 983);
+        assert_eq!(HbmWorkingSet::default().size_mb(95), 5836);
+        assert_eq!(HbmWorkingSet::Megabytes(2048).size_mb(16), 2048);
+        assert_eq!(HbmWorkingSet::Percent(10).to_string(), "10% of HBM");
+    }
+}
@@ -1,7 +1,10 @@
-//! Configuration audit checks (CFG-001 through CFG-005).
+//! Configuration audit checks (CFG-001 through CFG-008).
 //!
 //! Checks XLA, JAX, and system configuration for potential issues.
 
+use crate::checks::env::{self as env_format, ValueFormat, KNOWN_JAX_PLATFORMS};
+use crate::data::flags::{self, FlagConcern, FlagValue};
+use crate::platform::{gcp, linux, tpu};
 use crate::{Check, CheckCategory, CheckResult};
 use std::env;
 use std::time::Instant;
@@ -15,6 +18,8 @@ pub fn get_config_checks() -> Vec<Check> {
             category: CheckCategory::Config,
             description: "Check XLA_FLAGS for potential issues".to_string(),
             result: None,
+            start_ms: None,
+            ownership: Default::default(),
         },
         Check {
             id: "CFG-002".to_string(),
@@ -22,6 +27,8 @@ pub fn get_config_checks() -> Vec<Check> {
             category: CheckCategory::Config,
             description: "Check JAX configuration values".to_string(),
             result: None,
+            start_ms: None,
+            ownership: Default::default(),
         },
         Check {
             id: "CFG-003".to_string(),
@@ -29,6 +36,8 @@ pub fn get_config_checks() -> Vec<Check> {
             category: CheckCategory::Config,
             description: "Check memory preallocation settings".to_string(),
             result: None,
+            start_ms: None,
+            ownership: Default::default(),
         },
         Check {
             id: "CFG-004".to_string(),
@@ -36,6 +45,8 @@ pub fn get_config_checks() -> Vec<Check> {
             category: CheckCategory::Config,
             description: "Check multi-host configuration".to_string(),
             result: None,
+            start_ms: None,
+            ownership: Default::default(),
         },
         Check {
             id: "CFG-005".to_string(),
@@ -43,6 +54,35 @@ pub fn get_config_checks() -> Vec<Check> {
             category: CheckCategory::Config,
             description: "Check logging and debug settings".to_string(),
             result: None,
+            start_ms: None,
+            ownership: Default::default(),
+        },
+        Check {
+            id: "CFG-006".to_string(),
+            name: "Compilation Cache Check".to_string(),
+            category: CheckCategory::Config,
+            description: "Check JAX persistent compilation cache is set, writable, and persistent".to_string(),
+            result: None,
+            start_ms: None,
+            ownership: Default::default(),
+        },
+        Check {
+            id: "CFG-007".to_string(),
+            name: "LIBTPU_INIT_ARGS Lint".to_string(),
+            category: CheckCategory::Config,
+            description: "Check LIBTPU_INIT_ARGS flag syntax and flag dangerous or deprecated flags".to_string(),
+            result: None,
+            start_ms: None,
+            ownership: Default::default(),
+        },
+        Check {
+            id: "CFG-008".to_string(),
+            name: "Configuration Conflicts".to_string(),
+            category: CheckCategory::Config,
+            description: "Check for environment variables whose settings contradict each other or the host".to_string(),
+            result: None,
+            start_ms: None,
+            ownership: Default::default(),
         },
     ]
 }
@@ -51,77 +91,252 @@ pub fn get_config_checks() -> Vec<Check> {
 pub fn check_xla_flags() -> CheckResult {
     let start = Instant::now();
 
-    match env::var("XLA_FLAGS") {
-        Ok(flags) => {
-            let mut issues = Vec::new();
-
-            // Check for debug flags
-            let debug_patterns = [
-                "--xla_dump_to",
-                "--xla_dump_hlo",
-                "--xla_log_all",
-            ];
-
-            for pattern in &debug_patterns {
-                if flags.contains(pattern) {
-                    issues.push(format!("Debug flag {} is set", pattern));
-                }
+    let flags = match env::var("XLA_FLAGS") {
+        Ok(flags) if !flags.trim().is_empty() => flags,
+        _ => {
+            return CheckResult::Pass {
+                message: "XLA_FLAGS not set (using defaults)".to_string(),
+                duration_ms: start.elapsed().as_millis() as u64,
+                data: audit_data(|_| None, &["XLA_FLAGS"], &[]),
             }
+        }
+    };
 
-            // Check for disabled optimizations
-            if flags.contains("--xla_disable_hlo_passes") {
-                issues.push("HLO passes are disabled".to_string());
-            }
+    let findings = lint_xla_flags(&flags);
+    let duration_ms = start.elapsed().as_millis() as u64;
+    let describe = |severities: &[FlagSeverity]| {
+        findings
+            .iter()
+            .filter(|f| severities.contains(&f.severity))
+            .map(|f| f.description.clone())
+            .collect::<Vec<_>>()
+    };
+    let recommendations: Vec<String> = findings.iter().filter_map(|f| f.recommendation.clone()).collect();
+    let data = audit_data(env_value, &["XLA_FLAGS"], &recommendations);
 
-            let duration_ms = start.elapsed().as_millis() as u64;
+    let errors = describe(&[FlagSeverity::Error]);
+    let warnings = describe(&[FlagSeverity::Warning]);
+    if !errors.is_empty() {
+        CheckResult::Fail {
+            message: format!("XLA_FLAGS has {} malformed flag(s)", errors.len()),
+            details: format!("{}. XLA aborts at startup on malformed flags", errors.join("; ")),
+            duration_ms,
+            data,
+        }
+    } else if !warnings.is_empty() {
+        CheckResult::Warn {
+            message: format!("XLA_FLAGS has {} potential issues", warnings.len()),
+            details: describe(&[FlagSeverity::Warning, FlagSeverity::Info]).join("; "),
+            duration_ms,
+            data,
+        }
+    } else {
+        CheckResult::Pass {
+            message: "XLA_FLAGS configuration is optimal".to_string(),
+            duration_ms,
+            data,
+        }
+    }
+}
 
-            if !issues.is_empty() {
-                CheckResult::Warn {
-                    message: format!("XLA_FLAGS has {} potential issues", issues.len()),
-                    details: issues.join("; "),
-                    duration_ms,
-                }
-            } else {
-                CheckResult::Pass {
-                    message: "XLA_FLAGS configuration is optimal".to_string(),
-                    duration_ms,
-                }
-            }
+/// How serious a finding about a flag is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum FlagSeverity {
+    /// The flag is rejected at startup
+    Error,
+    Warning,
+    Info,
+}
+
+/// A finding about one flag, or a combination of flags, in a flag string
+#[derive(Debug, Clone)]
+pub(crate) struct FlagFinding {
+    pub severity: FlagSeverity,
+    pub description: String,
+    pub recommendation: Option<String>,
+}
+
+fn finding(severity: FlagSeverity, description: String, recommendation: Option<String>) -> FlagFinding {
+    FlagFinding {
+        severity,
+        description,
+        recommendation,
+    }
+}
+
+/// Whether `flag` is on: a boolean flag given without `=false`
+fn flag_enabled(flag: &Flag) -> bool {
+    !matches!(flag.value.as_deref().map(str::to_ascii_lowercase).as_deref(), Some("false" | "f" | "no" | "n" | "0"))
+}
+
+/// Check an `XLA_FLAGS` value: flag syntax and values, duplicates, flags
+/// that belong in `LIBTPU_INIT_ARGS` or to another backend, probable typos,
+/// known-problem flags, and contradictory combinations
+pub(crate) fn lint_xla_flags(text: &str) -> Vec<FlagFinding> {
+    let (parsed, errors) = parse_flags(text);
+    let mut findings: Vec<FlagFinding> = errors
+        .into_iter()
+        .map(|e| finding(FlagSeverity::Error, e, Some("Write each flag as --name or --name=value".to_string())))
+        .collect();
+
+    // Later occurrences override earlier ones
+    let mut flags: Vec<&Flag> = Vec::new();
+    for flag in parsed.iter().rev() {
+        if !flags.iter().any(|f| f.name == flag.name) {
+            flags.push(flag);
         }
-        Err(_) => {
-            let duration_ms = start.elapsed().as_millis() as u64;
-            CheckResult::Pass {
-                message: "XLA_FLAGS not set (using defaults)".to_string(),
-                duration_ms,
-            }
+    }
+    flags.reverse();
+
+    for flag in &flags {
+        let all: Vec<&Flag> = parsed.iter().filter(|f| f.name == flag.name).collect();
+        if all.len() > 1 {
+            let same = all.iter().all(|f| f.value == flag.value);
+            findings.push(finding(
+                if same { FlagSeverity::Info } else { FlagSeverity::Warning },
+                if same {
+                    format!("--{} is repeated {} times", flag.name, all.len())
+                } else {
+                    format!("--{} is set {} times with different values; the last one wins", flag.name, all.len())
+                },
+                Some(format!("Set --{} once", flag.name)),
+            ));
+        }
+
+        let name = flag.name.as_str();
+        if flags::OTHER_BACKEND_FLAG_PREFIXES.iter().any(|p| name.starts_with(p)) {
+            findings.push(finding(
+                FlagSeverity::Info,
+                format!("--{} is for another backend and has no effect on TPU", name),
+                Some(format!("Remove --{} on TPU hosts", name)),
+            ));
+            continue;
+        }
+        if flags::LIBTPU_FLAG_PREFIXES.iter().any(|p| name.starts_with(p)) {
+            findings.push(finding(
+                FlagSeverity::Warning,
+                format!("--{} is a libtpu flag; XLA does not recognize it in XLA_FLAGS", name),
+                Some(format!("Move --{} to LIBTPU_INIT_ARGS", name)),
+            ));
+            continue;
+        }
+
+        let Some(known) = flags::find_xla_flag(name) else {
+            findings.push(match flags::suggest_flag(name) {
+                Some(suggestion) => finding(
+                    FlagSeverity::Warning,
+                    format!("--{} is not a known XLA flag; probable typo of --{}", name, suggestion),
+                    Some(format!("Replace --{} with --{}", name, suggestion)),
+                ),
+                None => finding(
+                    FlagSeverity::Warning,
+                    format!("--{} is not a known XLA flag; XLA aborts on flags it does not recognize", name),
+                    Some(format!("Check --{} against the installed XLA version", name)),
+                ),
+            });
+            continue;
+        };
+
+        let value_ok = match (known.value, &flag.value) {
+            (FlagValue::Bool, None) => true,
+            (FlagValue::Bool, Some(value)) => BOOLEAN_FLAG_VALUES.contains(&value.to_ascii_lowercase().as_str()),
+            (FlagValue::Int, Some(value)) => value.parse::<i64>().is_ok(),
+            (FlagValue::Text, Some(_)) => true,
+            (_, None) => false,
+        };
+        if !value_ok {
+            let expected = match known.value {
+                FlagValue::Bool => "true or false",
+                FlagValue::Int => "an integer",
+                FlagValue::Text => "a value",
+            };
+            findings.push(finding(
+                FlagSeverity::Error,
+                format!("--{}{}: expects {}", name, flag.value.as_ref().map(|v| format!("={}", v)).unwrap_or_default(), expected),
+                Some(format!("Give --{} {}", name, expected)),
+            ));
+            continue;
+        }
+
+        if let Some(rule) = flags::find_xla_rule(name).filter(|_| flag_enabled(flag)) {
+            let (severity, concern) = match rule.concern {
+                FlagConcern::Dangerous => (FlagSeverity::Warning, "dangerous"),
+                FlagConcern::Deprecated => (FlagSeverity::Warning, "deprecated"),
+                FlagConcern::Experimental => (FlagSeverity::Info, "experimental"),
+            };
+            findings.push(finding(
+                severity,
+                format!("--{} is {}: {}", name, concern, rule.reason),
+                Some(format!("--{}: {}", name, rule.remediation)),
+            ));
+        }
+    }
+
+    let enabled = |name: &str| flags.iter().any(|f| f.name == name && flag_enabled(f));
+    for (a, b, reason) in flags::CONFLICTING_XLA_FLAGS {
+        if enabled(a) && enabled(b) {
+            findings.push(finding(
+                FlagSeverity::Warning,
+                format!("--{} and --{} {}", a, b, reason),
+                Some(format!("Keep only one of --{} and --{}", a, b)),
+            ));
         }
     }
+
+    findings
 }
 
 /// Run CFG-002: JAX Configuration Audit
 pub fn check_jax_config() -> CheckResult {
     let start = Instant::now();
     let mut issues = Vec::new();
+    let mut recommendations = Vec::new();
+
+    // JAX refuses to start with an unknown backend or a non-boolean flag
+    let malformed = malformed_vars(&[
+        ("JAX_PLATFORMS", ValueFormat::ListOf(KNOWN_JAX_PLATFORMS)),
+        ("JAX_ENABLE_X64", ValueFormat::Boolean),
+    ]);
 
     // Check JAX_PLATFORMS
     if let Ok(platforms) = env::var("JAX_PLATFORMS") {
         if !platforms.contains("tpu") {
             issues.push("JAX_PLATFORMS does not include 'tpu'".to_string());
+            recommendations.push("Set JAX_PLATFORMS=tpu,cpu for TPU workloads".to_string());
         }
     }
 
+    // x64 mode doubles the memory of every float array
+    if env::var("JAX_ENABLE_X64").is_ok_and(|x64| matches!(x64.trim().to_ascii_lowercase().as_str(), "true" | "1")) {
+        recommendations.push("Use float32 for TPU efficiency unless float64 is required".to_string());
+    }
+
     let duration_ms = start.elapsed().as_millis() as u64;
+    let data = audit_data(
+        env_value,
+        &["JAX_PLATFORMS", "JAX_ENABLE_X64", "JAX_DEFAULT_MATMUL_PRECISION"],
+        &recommendations,
+    );
 
-    if !issues.is_empty() {
+    if !malformed.is_empty() {
+        CheckResult::Fail {
+            message: "Malformed JAX configuration".to_string(),
+            details: malformed.join("; "),
+            duration_ms,
+            data,
+        }
+    } else if !issues.is_empty() {
         CheckResult::Warn {
             message: "JAX configuration has potential issues".to_string(),
             details: issues.join("; "),
             duration_ms,
+            data,
         }
     } else {
         CheckResult::Pass {
             message: "JAX configuration appears correct".to_string(),
             duration_ms,
+            data,
         }
     }
 }
@@ -130,28 +345,58 @@ pub fn check_jax_config() -> CheckResult {
 pub fn check_memory_config() -> CheckResult {
     let start = Instant::now();
     let mut issues = Vec::new();
+    let mut recommendations = Vec::new();
+
+    let malformed = malformed_vars(&[
+        ("XLA_PYTHON_CLIENT_MEM_FRACTION", ValueFormat::Fraction),
+        ("XLA_PYTHON_CLIENT_PREALLOCATE", ValueFormat::Boolean),
+    ]);
 
     // Check memory fraction
     if let Ok(fraction) = env::var("XLA_PYTHON_CLIENT_MEM_FRACTION") {
-        if let Ok(f) = fraction.parse::<f64>() {
-            if f > 0.95 {
+        if let Ok(f) = fraction.trim().parse::<f64>() {
+            if f > 0.95 && f <= 1.0 {
                 issues.push(format!("High memory fraction: {} (risk of OOM)", f));
+                recommendations.push("Consider lowering XLA_PYTHON_CLIENT_MEM_FRACTION to 0.9".to_string());
+            } else if f > 0.0 && f < 0.5 {
+                recommendations.push(format!(
+                    "XLA_PYTHON_CLIENT_MEM_FRACTION={} leaves most of HBM unused; consider increasing it for larger models",
+                    f
+                ));
             }
         }
     }
 
+    if env::var("XLA_PYTHON_CLIENT_PREALLOCATE").is_ok_and(|p| p.trim().eq_ignore_ascii_case("false")) {
+        recommendations.push("Preallocation can improve performance for fixed-size models".to_string());
+    }
+
     let duration_ms = start.elapsed().as_millis() as u64;
+    let data = audit_data(
+        env_value,
+        &["XLA_PYTHON_CLIENT_PREALLOCATE", "XLA_PYTHON_CLIENT_MEM_FRACTION"],
+        &recommendations,
+    );
 
-    if !issues.is_empty() {
+    if !malformed.is_empty() {
+        CheckResult::Fail {
+            message: "Malformed memory configuration".to_string(),
+            details: malformed.join("; "),
+            duration_ms,
+            data,
+        }
+    } else if !issues.is_empty() {
         CheckResult::Warn {
             message: "Memory configuration may cause issues".to_string(),
             details: issues.join("; "),
             duration_ms,
+            data,
         }
     } else {
         CheckResult::Pass {
             message: "Memory configuration is appropriate".to_string(),
             duration_ms,
+            data,
         }
     }
 }
@@ -168,18 +413,42 @@ pub fn check_distributed_config() -> CheckResult {
         worker_hostnames.as_ref().map(|h| h.contains(',')).unwrap_or(false);
 
     let duration_ms = start.elapsed().as_millis() as u64;
+    let vars = ["JAX_COORDINATOR_ADDRESS", "CLOUD_TPU_TASK_ID", "TPU_WORKER_HOSTNAMES"];
+
+    if let Some(problem) = coordinator
+        .as_deref()
+        .and_then(|address| env_format::malformed("JAX_COORDINATOR_ADDRESS", address, ValueFormat::HostPort))
+    {
+        return CheckResult::Fail {
+            message: "Malformed coordinator address".to_string(),
+            details: problem,
+            duration_ms,
+            data: audit_data(env_value, &vars, &[]),
+        };
+    }
 
     if is_multi_host {
+        let mut recommendations = Vec::new();
+        if coordinator.is_none() {
+            recommendations.push("Set JAX_COORDINATOR_ADDRESS for distributed training".to_string());
+        }
+        if env::var("CLOUD_TPU_TASK_ID").is_err() {
+            recommendations.push("Ensure CLOUD_TPU_TASK_ID is set on every host".to_string());
+        }
+        let data = audit_data(env_value, &vars, &recommendations);
+
         if coordinator.is_none() {
             CheckResult::Fail {
                 message: "Multi-host detected but JAX_COORDINATOR_ADDRESS not set".to_string(),
                 details: "Set JAX_COORDINATOR_ADDRESS for distributed training".to_string(),
                 duration_ms,
+                data,
             }
         } else {
             CheckResult::Pass {
                 message: "Distributed configuration is correct".to_string(),
                 duration_ms,
+                data,
             }
         }
     } else {
@@ -193,11 +462,23 @@ pub fn check_distributed_config() -> CheckResult {
 pub fn check_logging_config() -> CheckResult {
     let start = Instant::now();
     let mut issues = Vec::new();
+    let mut recommendations = Vec::new();
+
+    // Unrecognized values are ignored by TensorFlow and JAX, so only warn
+    let malformed = malformed_vars(&[
+        ("TF_CPP_MIN_LOG_LEVEL", ValueFormat::Integer { min: 0, max: 3 }),
+        ("JAX_DEBUG_NANS", ValueFormat::Boolean),
+    ]);
 
     // Check TensorFlow log level
     if let Ok(level) = env::var("TF_CPP_MIN_LOG_LEVEL") {
-        if level == "0" {
-            issues.push("TF_CPP_MIN_LOG_LEVEL=0 (verbose logging)".to_string());
+        match level.trim() {
+            "0" => {
+                issues.push("TF_CPP_MIN_LOG_LEVEL=0 (verbose logging)".to_string());
+                recommendations.push("Set TF_CPP_MIN_LOG_LEVEL=2 for production".to_string());
+            }
+            "3" => recommendations.push("TF_CPP_MIN_LOG_LEVEL=3 hides errors; lower it when debugging".to_string()),
+            _ => {}
         }
     }
 
@@ -205,21 +486,589 @@ pub fn check_logging_config() -> CheckResult {
     if let Ok(debug_nans) = env::var("JAX_DEBUG_NANS") {
         if debug_nans == "True" || debug_nans == "1" {
             issues.push("JAX_DEBUG_NANS is enabled (performance impact)".to_string());
+            recommendations.push("Disable JAX_DEBUG_NANS for production".to_string());
         }
     }
 
+    if env::var("JAX_TRACEBACK_FILTERING").is_ok_and(|filtering| filtering == "off") {
+        recommendations.push("JAX traceback filtering is off; unset JAX_TRACEBACK_FILTERING outside debugging".to_string());
+    }
+
     let duration_ms = start.elapsed().as_millis() as u64;
+    let data = audit_data(
+        env_value,
+        &["TF_CPP_MIN_LOG_LEVEL", "JAX_DEBUG_NANS", "JAX_TRACEBACK_FILTERING"],
+        &recommendations,
+    );
 
     if !issues.is_empty() {
+        issues.extend(malformed);
         CheckResult::Warn {
             message: "Debug logging may impact performance".to_string(),
             details: issues.join("; "),
             duration_ms,
+            data,
+        }
+    } else if !malformed.is_empty() {
+        CheckResult::Warn {
+            message: "Logging configuration has malformed values".to_string(),
+            details: malformed.join("; "),
+            duration_ms,
+            data,
         }
     } else {
         CheckResult::Pass {
             message: "Logging configuration is production-appropriate".to_string(),
             duration_ms,
+            data,
+        }
+    }
+}
+
+/// The variables in `vars` that are set to a malformed value, described
+fn malformed_vars(vars: &[(&str, ValueFormat)]) -> Vec<String> {
+    vars.iter()
+        .filter_map(|(name, format)| {
+            env::var(name)
+                .ok()
+                .and_then(|value| env_format::malformed(name, &value, *format))
+        })
+        .collect()
+}
+
+/// Data key under which the configuration checks report their
+/// recommendations, joined with "; "
+pub const RECOMMENDATION_DATA_KEY: &str = "recommendation";
+
+/// Structured data for a configuration check: the value of each variable in
+/// `vars` as `get` reads it, then the recommendations, if any
+fn audit_data(get: impl Fn(&str) -> Option<String>, vars: &[&str], recommendations: &[String]) -> Vec<(String, String)> {
+    let mut data: Vec<(String, String)> = vars
+        .iter()
+        .map(|name| (name.to_string(), get(name).unwrap_or_else(|| "(not set)".to_string())))
+        .collect();
+    if !recommendations.is_empty() {
+        data.push((RECOMMENDATION_DATA_KEY.to_string(), recommendations.join("; ")));
+    }
+    data
+}
+
+/// The current value of environment variable `name`
+fn env_value(name: &str) -> Option<String> {
+    env::var(name).ok()
+}
+
+/// Filesystems whose contents do not survive a VM or container restart
+const EPHEMERAL_FILESYSTEMS: &[&str] = &["tmpfs", "ramfs", "overlay", "devtmpfs"];
+
+/// Run CFG-006: Compilation Cache Check
+pub fn check_compilation_cache() -> CheckResult {
+    let start = Instant::now();
+
+    let cache_dir = match env::var("JAX_COMPILATION_CACHE_DIR")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .or_else(detect_jax_cache_dir)
+    {
+        Some(dir) => dir,
+        None => {
+            return CheckResult::Warn {
+                message: "JAX persistent compilation cache not configured".to_string(),
+                details: "Every restart recompiles from scratch. Set JAX_COMPILATION_CACHE_DIR \
+                          (or jax.config.update('jax_compilation_cache_dir', ...)) to a persistent path"
+                    .to_string(),
+                duration_ms: start.elapsed().as_millis() as u64,
+                data: Vec::new(),
+            };
+        }
+    };
+
+    // GCS-backed caches are persistent and shared; local checks do not apply
+    if cache_dir.starts_with("gs://") {
+        return CheckResult::Pass {
+            message: format!("Compilation cache on GCS: {}", cache_dir),
+            duration_ms: start.elapsed().as_millis() as u64,
+            data: Vec::new(),
+        };
+    }
+
+    let path = std::path::Path::new(&cache_dir);
+    if !path.is_dir() {
+        return CheckResult::Fail {
+            message: "Compilation cache directory does not exist".to_string(),
+            details: format!("Create it with: mkdir -p {}", cache_dir),
+            duration_ms: start.elapsed().as_millis() as u64,
+            data: Vec::new(),
+        };
+    }
+
+    let test_file = path.join(".tpu-doc-test");
+    let can_write = std::fs::write(&test_file, "test").is_ok();
+    let _ = std::fs::remove_file(&test_file);
+
+    if !can_write {
+        return CheckResult::Fail {
+            message: "Compilation cache directory is not writable".to_string(),
+            details: format!("Path: {}", cache_dir),
+            duration_ms: start.elapsed().as_millis() as u64,
+            data: Vec::new(),
+        };
+    }
+
+    let mut issues = Vec::new();
+
+    if let Ok(fs_type) = linux::get_filesystem_type(&cache_dir) {
+        if EPHEMERAL_FILESYSTEMS.contains(&fs_type.as_str()) {
+            issues.push(format!(
+                "Cache is on {} and will be lost on restart; use a persistent disk or gs:// path",
+                fs_type
+            ));
+        }
+    }
+
+    if let Ok(disk) = linux::get_disk_space(&cache_dir) {
+        let available_gb = disk.available_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+        let used_pct = if disk.total_bytes > 0 {
+            100.0 - (disk.available_bytes as f64 / disk.total_bytes as f64) * 100.0
+        } else {
+            0.0
+        };
+        if available_gb < 1.0 || used_pct > 95.0 {
+            issues.push(format!(
+                "Cache volume nearly full: {:.1} GB available ({:.0}% used)",
+                available_gb, used_pct
+            ));
+        }
+    }
+
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    if !issues.is_empty() {
+        CheckResult::Warn {
+            message: "Compilation cache may not be effective".to_string(),
+            details: issues.join("; "),
+            duration_ms,
+            data: Vec::new(),
+        }
+    } else {
+        CheckResult::Pass {
+            message: format!("Compilation cache configured: {}", cache_dir),
+            duration_ms,
+            data: Vec::new(),
+        }
+    }
+}
+
+/// Ask JAX for its configured cache directory (set via jax.config or a sitecustomize)
+fn detect_jax_cache_dir() -> Option<String> {
+    let output = std::process::Command::new("python3")
+        .args([
+            "-c",
+            "import jax; print(jax.config.jax_compilation_cache_dir or '')",
+        ])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if dir.is_empty() || dir == "None" {
+        None
+    } else {
+        Some(dir)
+    }
+}
+
+/// Values absl accepts for a boolean flag
+const BOOLEAN_FLAG_VALUES: &[&str] = &["true", "false", "t", "f", "yes", "no", "y", "n", "1", "0"];
+
+/// A flag from a flag string such as `LIBTPU_INIT_ARGS`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Flag {
+    /// Name without the leading `--`
+    pub name: String,
+    /// Value after `=`, None for a bare `--name`
+    pub value: Option<String>,
+}
+
+/// Split a whitespace-separated `--name=value` flag string into flags and
+/// descriptions of the tokens that are not well-formed flags
+pub(crate) fn parse_flags(text: &str) -> (Vec<Flag>, Vec<String>) {
+    let mut parsed = Vec::new();
+    let mut errors = Vec::new();
+
+    for token in text.split_whitespace() {
+        if token.contains(['"', '\'']) {
+            errors.push(format!("{}: quotes inside the variable are passed through literally", token));
+            continue;
+        }
+        let Some(body) = token.strip_prefix("--") else {
+            errors.push(if token.starts_with('-') {
+                format!("{}: flags start with two dashes", token)
+            } else {
+                format!("{}: not a flag (flags start with --)", token)
+            });
+            continue;
+        };
+
+        let (name, value) = match body.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (body, None),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            errors.push(format!("{}: invalid flag name (use letters, digits, and _)", token));
+        } else if value == Some("") {
+            errors.push(format!("{}: missing value after '='", token));
+        } else {
+            parsed.push(Flag {
+                name: name.to_string(),
+                value: value.map(str::to_string),
+            });
+        }
+    }
+    (parsed, errors)
+}
+
+/// Run CFG-007: LIBTPU_INIT_ARGS Lint
+pub fn check_libtpu_init_args() -> CheckResult {
+    let start = Instant::now();
+    let args = env::var("LIBTPU_INIT_ARGS").unwrap_or_default();
+    lint_libtpu_init_args(&args, start.elapsed().as_millis() as u64)
+}
+
+/// CFG-007's result for the `LIBTPU_INIT_ARGS` value `args`
+fn lint_libtpu_init_args(args: &str, duration_ms: u64) -> CheckResult {
+    if args.trim().is_empty() {
+        return CheckResult::Pass {
+            message: "LIBTPU_INIT_ARGS not set (using defaults)".to_string(),
+            duration_ms,
+            data: Vec::new(),
+        };
+    }
+
+    let (parsed, mut errors) = parse_flags(args);
+
+    // absl rejects a boolean flag with any other value
+    for flag in &parsed {
+        let boolean = flag.name.starts_with("enable_") || flag.name.contains("_enable_");
+        if let (true, Some(value)) = (boolean, &flag.value) {
+            if !BOOLEAN_FLAG_VALUES.contains(&value.to_ascii_lowercase().as_str()) {
+                errors.push(format!("--{}={}: expects true or false", flag.name, value));
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return CheckResult::Fail {
+            message: format!("LIBTPU_INIT_ARGS has {} malformed flag(s)", errors.len()),
+            details: format!("{}. libtpu rejects its arguments at TPU initialization", errors.join("; ")),
+            duration_ms,
+            data: Vec::new(),
+        };
+    }
+
+    let mut issues = Vec::new();
+    for (i, flag) in parsed.iter().enumerate() {
+        let count = parsed.iter().filter(|f| f.name == flag.name).count();
+        if count > 1 && !parsed[..i].iter().any(|f| f.name == flag.name) {
+            issues.push(format!("--{} is set {} times; the last value wins", flag.name, count));
+        }
+        if let Some(rule) = flags::find_libtpu_rule(&flag.name) {
+            let concern = match rule.concern {
+                FlagConcern::Dangerous => "dangerous",
+                FlagConcern::Deprecated => "deprecated",
+                FlagConcern::Experimental => "experimental",
+            };
+            issues.push(format!("--{} is {}: {}. Fix: {}", flag.name, concern, rule.reason, rule.remediation));
+        }
+    }
+
+    if !issues.is_empty() {
+        CheckResult::Warn {
+            message: format!("LIBTPU_INIT_ARGS has {} potential issue(s)", issues.len()),
+            details: issues.join("; "),
+            duration_ms,
+            data: Vec::new(),
+        }
+    } else {
+        CheckResult::Pass {
+            message: format!("LIBTPU_INIT_ARGS: {} flag(s) look valid", parsed.len()),
+            duration_ms,
+            data: Vec::new(),
+        }
+    }
+}
+
+/// What CFG-008 knows about the host
+struct HostFacts {
+    /// TPU chips or a TPU VM were detected
+    on_tpu: bool,
+    /// Hosts in the slice, when known
+    hosts: Option<usize>,
+}
+
+/// Run CFG-008: Configuration Conflicts
+pub fn check_config_conflicts() -> CheckResult {
+    let start = Instant::now();
+
+    let get = |name: &str| env::var(name).ok().filter(|v| !v.trim().is_empty());
+    let hosts = get("TPU_WORKER_HOSTNAMES")
+        .or_else(|| gcp::get_instance_attribute("worker-network-endpoints").ok().flatten())
+        .map(|list| list.split(',').filter(|h| !h.trim().is_empty()).count())
+        .filter(|count| *count > 0);
+    let facts = HostFacts {
+        on_tpu: tpu::is_tpu_vm(),
+        hosts,
+    };
+
+    let (conflicts, fatal) = find_config_conflicts(get, &facts);
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    if conflicts.is_empty() {
+        CheckResult::Pass {
+            message: "No conflicting settings".to_string(),
+            duration_ms,
+            data: Vec::new(),
+        }
+    } else if fatal {
+        CheckResult::Fail {
+            message: format!("{} conflicting setting(s)", conflicts.len()),
+            details: conflicts.join("; "),
+            duration_ms,
+            data: Vec::new(),
+        }
+    } else {
+        CheckResult::Warn {
+            message: format!("{} conflicting setting(s)", conflicts.len()),
+            details: conflicts.join("; "),
+            duration_ms,
+            data: Vec::new(),
+        }
+    }
+}
+
+/// Settings that contradict each other or `facts`, reading variables with
+/// `get`, and whether any of them keeps the job from using the TPU correctly
+fn find_config_conflicts(get: impl Fn(&str) -> Option<String>, facts: &HostFacts) -> (Vec<String>, bool) {
+    let mut conflicts = Vec::new();
+    let mut fatal = false;
+
+    let preallocate_off = get("XLA_PYTHON_CLIENT_PREALLOCATE")
+        .is_some_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "false" | "0"));
+    if let (true, Some(fraction)) = (preallocate_off, get("XLA_PYTHON_CLIENT_MEM_FRACTION")) {
+        conflicts.push(format!(
+            "XLA_PYTHON_CLIENT_MEM_FRACTION={} has no effect with XLA_PYTHON_CLIENT_PREALLOCATE=false; \
+             unset one of them",
+            fraction
+        ));
+    }
+
+    if let Some(platforms) = get("JAX_PLATFORMS") {
+        let has_tpu = platforms.split(',').any(|p| p.trim().eq_ignore_ascii_case("tpu"));
+        if facts.on_tpu && !has_tpu {
+            fatal = true;
+            conflicts.push(format!(
+                "JAX_PLATFORMS={} on a TPU VM: JAX will not use the TPU; set JAX_PLATFORMS=tpu,cpu or unset it",
+                platforms
+            ));
+        }
+    }
+
+    if let (Some(address), Some(1)) = (get("JAX_COORDINATOR_ADDRESS"), facts.hosts) {
+        conflicts.push(format!(
+            "JAX_COORDINATOR_ADDRESS={} is set on a single-host slice; jax.distributed.initialize() is not needed",
+            address
+        ));
+    }
+
+    let worker_id = get("TPU_WORKER_ID").and_then(|id| id.trim().parse::<usize>().ok());
+    if let (Some(id), Some(hosts)) = (worker_id, facts.hosts) {
+        if id >= hosts {
+            fatal = true;
+            conflicts.push(format!(
+                "TPU_WORKER_ID={} but the slice has {} host(s) (IDs 0-{})",
+                id,
+                hosts,
+                hosts - 1
+            ));
+        }
+    }
+
+    // A chip subset must be described consistently in both variables
+    let visible = get("TPU_VISIBLE_CHIPS")
+        .and_then(|list| list.split(',').map(|c| c.trim().parse::<u32>().ok()).collect::<Option<Vec<_>>>());
+    let bounds = get("TPU_CHIPS_PER_HOST_BOUNDS").and_then(|b| {
+        b.split(',')
+            .map(|d| d.trim().parse::<usize>().ok())
+            .collect::<Option<Vec<_>>>()
+            .map(|dims| (b.clone(), dims.iter().product::<usize>()))
+    });
+    if let (Some(visible), Some((bounds, chips))) = (visible, bounds) {
+        if visible.len() != chips {
+            fatal = true;
+            conflicts.push(format!(
+                "TPU_VISIBLE_CHIPS lists {} chip(s) but TPU_CHIPS_PER_HOST_BOUNDS={} describes {}",
+                visible.len(),
+                bounds,
+                chips
+            ));
+        }
+    }
+
+    (conflicts, fatal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_data() {
+        let get = |name: &str| (name == "JAX_PLATFORMS").then(|| "cpu".to_string());
+        assert_eq!(
+            audit_data(get, &["JAX_PLATFORMS", "JAX_ENABLE_X64"], &[]),
+            vec![
+                ("JAX_PLATFORMS".to_string(), "cpu".to_string()),
+                ("JAX_ENABLE_X64".to_string(), "(not set)".to_string()),
+            ]
+        );
+        let data = audit_data(get, &["JAX_PLATFORMS"], &["a".to_string(), "b".to_string()]);
+        assert_eq!(data[1], (RECOMMENDATION_DATA_KEY.to_string(), "a; b".to_string()));
+    }
+
+    #[test]
+    fn test_parse_flags() {
+        let (parsed, errors) = parse_flags("--xla_tpu_enable_async_collective_fusion=true  --tpu_megacore -x stray --a-b=1 --k=");
+        assert_eq!(
+            parsed,
+            vec![
+                Flag {
+                    name: "xla_tpu_enable_async_collective_fusion".to_string(),
+                    value: Some("true".to_string())
+                },
+                Flag {
+                    name: "tpu_megacore".to_string(),
+                    value: None
+                },
+            ]
+        );
+        assert_eq!(errors.len(), 4);
+        assert!(errors[0].starts_with("-x: flags start with two dashes"));
+        assert!(errors[1].starts_with("stray: not a flag"));
+        assert!(errors[2].starts_with("--a-b=1: invalid flag name"));
+        assert!(errors[3].starts_with("--k=: missing value"));
+    }
+
+    #[test]
+    fn test_lint_xla_flags() {
+        let descriptions = |text: &str| -> Vec<(FlagSeverity, String)> {
+            lint_xla_flags(text).into_iter().map(|f| (f.severity, f.description)).collect()
+        };
+
+        assert!(lint_xla_flags("--xla_force_host_platform_device_count=8 --xla_hlo_profile=false").is_empty());
+
+        let findings = descriptions(
+            "--xla_dump_tp=/tmp/d --xla_tpu_enable_latency_hiding_scheduler=true --xla_gpu_autotune_level=0 \
+             --xla_backend_optimization_level=high --xla_hlo_profile",
+        );
+        assert_eq!(
+            findings,
+            vec![
+                (
+                    FlagSeverity::Warning,
+                    "--xla_dump_tp is not a known XLA flag; probable typo of --xla_dump_to".to_string()
+                ),
+                (
+                    FlagSeverity::Warning,
+                    "--xla_tpu_enable_latency_hiding_scheduler is a libtpu flag; XLA does not recognize it in XLA_FLAGS"
+                        .to_string()
+                ),
+                (
+                    FlagSeverity::Info,
+                    "--xla_gpu_autotune_level is for another backend and has no effect on TPU".to_string()
+                ),
+                (FlagSeverity::Error, "--xla_backend_optimization_level=high: expects an integer".to_string()),
+                (
+                    FlagSeverity::Warning,
+                    "--xla_hlo_profile is dangerous: instruments every HLO instruction, slowing each step".to_string()
+                ),
+            ]
+        );
+
+        let findings = descriptions(
+            "--xla_disable_hlo_passes=a --xla_enable_hlo_passes_only=b --xla_dump_to=/x --xla_dump_to=/y",
+        );
+        assert!(findings.contains(&(
+            FlagSeverity::Warning,
+            "--xla_dump_to is set 2 times with different values; the last one wins".to_string()
+        )));
+        assert!(findings.iter().any(|(severity, d)| *severity == FlagSeverity::Warning
+            && d.starts_with("--xla_disable_hlo_passes and --xla_enable_hlo_passes_only are mutually exclusive")));
+    }
+
+    #[test]
+    fn test_find_config_conflicts() {
+        let env_of = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+        let tpu_host = |hosts| HostFacts { on_tpu: true, hosts };
+
+        let (conflicts, fatal) = find_config_conflicts(
+            env_of(&[("XLA_PYTHON_CLIENT_PREALLOCATE", "false"), ("XLA_PYTHON_CLIENT_MEM_FRACTION", "0.8")]),
+            &tpu_host(None),
+        );
+        assert_eq!(conflicts.len(), 1);
+        assert!(conflicts[0].starts_with("XLA_PYTHON_CLIENT_MEM_FRACTION=0.8 has no effect"));
+        assert!(!fatal);
+
+        let vars = &[
+            ("JAX_PLATFORMS", "cpu"),
+            ("JAX_COORDINATOR_ADDRESS", "10.0.0.2:8476"),
+            ("TPU_WORKER_ID", "1"),
+            ("TPU_VISIBLE_CHIPS", "0,1"),
+            ("TPU_CHIPS_PER_HOST_BOUNDS", "2,2,1"),
+        ];
+        let (conflicts, fatal) = find_config_conflicts(env_of(vars), &tpu_host(Some(1)));
+        assert!(fatal);
+        assert_eq!(conflicts.len(), 4);
+        assert!(conflicts[0].starts_with("JAX_PLATFORMS=cpu on a TPU VM"));
+        assert!(conflicts[1].contains("single-host slice"));
+        assert_eq!(conflicts[2], "TPU_WORKER_ID=1 but the slice has 1 host(s) (IDs 0-0)");
+        assert_eq!(conflicts[3], "TPU_VISIBLE_CHIPS lists 2 chip(s) but TPU_CHIPS_PER_HOST_BOUNDS=2,2,1 describes 4");
+
+        // Off TPU, CPU-only JAX and a multi-host coordinator are consistent
+        let vars = &[("JAX_PLATFORMS", "cpu"), ("JAX_COORDINATOR_ADDRESS", "10.0.0.2:8476"), ("TPU_WORKER_ID", "1")];
+        let off_tpu = HostFacts { on_tpu: false, hosts: Some(4) };
+        assert_eq!(find_config_conflicts(env_of(vars), &off_tpu), (Vec::new(), false));
+    }
+
+    #[test]
+    fn test_lint_libtpu_init_args() {
+        assert!(matches!(lint_libtpu_init_args("", 0), CheckResult::Pass { .. }));
+        assert!(matches!(
+            lint_libtpu_init_args("--xla_tpu_enable_data_parallel_all_reduce_opt=true", 0),
+            CheckResult::Pass { message, .. } if message == "LIBTPU_INIT_ARGS: 1 flag(s) look valid"
+        ));
+
+        match lint_libtpu_init_args("--xla_tpu_enable_latency_hiding_scheduler=ture \"--xla_dump_to=/tmp\"", 0) {
+            CheckResult::Fail { message, details, .. } => {
+                assert_eq!(message, "LIBTPU_INIT_ARGS has 2 malformed flag(s)");
+                assert!(details.contains("--xla_tpu_enable_latency_hiding_scheduler=ture: expects true or false"));
+                assert!(details.contains("quotes inside the variable"));
+            }
+            other => panic!("expected fail, got {:?}", other),
+        }
+
+        match lint_libtpu_init_args("--tpu_megacore=megacore_dense --xla_dump_to=/tmp/x --xla_dump_to=/tmp/y", 0) {
+            CheckResult::Warn { message, details, .. } => {
+                assert_eq!(message, "LIBTPU_INIT_ARGS has 4 potential issue(s)");
+                assert!(details.starts_with("--tpu_megacore is deprecated: forces a megacore mode"));
+                assert!(details.contains("Fix: remove the flag and let libtpu choose"));
+                assert!(details.contains("--xla_dump_to is set 2 times; the last value wins"));
+            }
+            other => panic!("expected warn, got {:?}", other),
         }
     }
 }
@@ -0,0 +1,207 @@
+//! Custom shell-command checks.
+//!
+//! Runs checks declared as `[[checks.custom]]` in the configuration file.
+//! Each runs its command with `sh -c`; the check passes when the command
+//! exits with one of its `pass_exit_codes` and its output does not match
+//! its `fail_regex`.
+
+use crate::cli::config::CustomCheckConfig;
+use crate::engine::orchestrator::RegisteredCheck;
+use crate::platform::linux;
+use crate::{CheckResult, CheckSeverity, TpuDocError};
+use std::process::Command;
+use std::time::Instant;
+
+/// Longest command output line quoted in a check message
+const MAX_MESSAGE_CHARS: usize = 200;
+
+/// Output lines kept in a failing check's details
+const DETAIL_LINES: usize = 20;
+
+/// Registered checks for the custom checks in a configuration file
+pub fn create_custom_checks(configs: &[CustomCheckConfig]) -> Vec<RegisteredCheck> {
+    configs
+        .iter()
+        .map(|config| {
+            let owned = config.clone();
+            RegisteredCheck {
+                id: config.id.clone(),
+                name: config.name.clone(),
+                category: config.category.clone(),
+                description: if config.description.is_empty() {
+                    format!("Custom check: {}", config.command)
+                } else {
+                    config.description.clone()
+                },
+                check_fn: Box::new(move || run_custom_check(&owned)),
+                dependencies: vec![],
+                estimated_duration_ms: config.timeout_ms.min(5000),
+                tags: vec![],
+                severity: CheckSeverity::Major,
+                ownership: Default::default(),
+            }
+        })
+        .collect()
+}
+
+/// Execute a custom shell-command check
+pub fn run_custom_check(config: &CustomCheckConfig) -> CheckResult {
+    let start = Instant::now();
+
+    let mut command = Command::new("sh");
+    command.args(["-c", &config.command]);
+    let output = match linux::run_command_in_own_group(&mut command, config.timeout_ms) {
+        Ok(output) => output,
+        Err(TpuDocError::Timeout { timeout_ms, .. }) => {
+            return CheckResult::Fail {
+                message: format!("Command timed out after {}ms", timeout_ms),
+                details: format!("Command: {}", config.command),
+                duration_ms: start.elapsed().as_millis() as u64,
+                data: Vec::new(),
+            };
+        }
+        Err(e) => {
+            return CheckResult::Fail {
+                message: "Could not run check command".to_string(),
+                details: format!("Command: {}\n{}", config.command, e),
+                duration_ms: start.elapsed().as_millis() as u64,
+                data: Vec::new(),
+            };
+        }
+    };
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let combined = format!("{}{}", stdout, stderr);
+
+    let code = match output.status.code() {
+        Some(code) => code,
+        None => {
+            return CheckResult::Fail {
+                message: "Command was terminated by a signal".to_string(),
+                details: output_details(&config.command, &combined),
+                duration_ms,
+                data: Vec::new(),
+            };
+        }
+    };
+
+    if !config.pass_exit_codes.contains(&code) {
+        let summary = first_line(&stderr).or_else(|| first_line(&stdout));
+        return CheckResult::Fail {
+            message: match summary {
+                Some(line) => format!("Command exited with status {}: {}", code, line),
+                None => format!("Command exited with status {}", code),
+            },
+            details: output_details(&config.command, &combined),
+            duration_ms,
+            data: Vec::new(),
+        };
+    }
+
+    if let Some(pattern) = &config.fail_regex {
+        if let Some(line) = combined.lines().find(|line| pattern.is_match(line)) {
+            return CheckResult::Fail {
+                message: format!("Output matched fail pattern: {}", truncate(line.trim())),
+                details: format!("Pattern: {}\n{}", pattern.as_str(), output_details(&config.command, &combined)),
+                duration_ms,
+                data: Vec::new(),
+            };
+        }
+    }
+
+    CheckResult::Pass {
+        message: first_line(&stdout).unwrap_or_else(|| format!("Command succeeded (exit {})", code)),
+        duration_ms,
+        data: Vec::new(),
+    }
+}
+
+/// First non-empty line of command output, shortened for a message
+fn first_line(output: &str) -> Option<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(truncate)
+}
+
+fn truncate(line: &str) -> String {
+    if line.chars().count() <= MAX_MESSAGE_CHARS {
+        line.to_string()
+    } else {
+        format!("{}...", line.chars().take(MAX_MESSAGE_CHARS).collect::<String>())
+    }
+}
+
+/// The command and the tail of its output
+fn output_details(command: &str, output: &str) -> String {
+    let lines: Vec<&str> = output.lines().collect();
+    let tail = &lines[lines.len().saturating_sub(DETAIL_LINES)..];
+    if tail.is_empty() {
+        format!("Command: {}\n(no output)", command)
+    } else {
+        format!("Command: {}\n{}", command, tail.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::regex::Regex;
+    use crate::CheckCategory;
+
+    fn custom(command: &str) -> CustomCheckConfig {
+        CustomCheckConfig {
+            id: "ORG-001".to_string(),
+            name: "Org check".to_string(),
+            category: CheckCategory::Config,
+            description: String::new(),
+            command: command.to_string(),
+            timeout_ms: 5000,
+            pass_exit_codes: vec![0],
+            fail_regex: None,
+        }
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        match run_custom_check(&custom("echo quota ok")) {
+            CheckResult::Pass { message, .. } => assert_eq!(message, "quota ok"),
+            other => panic!("expected pass, got {:?}", other),
+        }
+
+        match run_custom_check(&custom("echo 'no quota' >&2; exit 3")) {
+            CheckResult::Fail { message, .. } => assert_eq!(message, "Command exited with status 3: no quota"),
+            other => panic!("expected fail, got {:?}", other),
+        }
+
+        let mut allowed = custom("exit 3");
+        allowed.pass_exit_codes = vec![0, 3];
+        match run_custom_check(&allowed) {
+            CheckResult::Pass { message, .. } => assert_eq!(message, "Command succeeded (exit 3)"),
+            other => panic!("expected pass, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_fail_regex_and_timeout() {
+        let mut check = custom("printf 'loading\\nWARNING: mount is read-only\\n'");
+        check.fail_regex = Some(Regex::new("read-only").unwrap());
+        match run_custom_check(&check) {
+            CheckResult::Fail { message, details, .. } => {
+                assert_eq!(message, "Output matched fail pattern: WARNING: mount is read-only");
+                assert!(details.starts_with("Pattern: read-only"));
+            }
+            other => panic!("expected fail, got {:?}", other),
+        }
+
+        let mut slow = custom("sleep 5");
+        slow.timeout_ms = 100;
+        assert!(matches!(
+            run_custom_check(&slow),
+            CheckResult::Fail { message, .. } if message == "Command timed out after 100ms"
+        ));
+    }
+}
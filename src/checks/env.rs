@@ -0,0 +1,139 @@
+//! Environment variable value formats.
+//!
+//! A variable that is set but malformed (a two-element
+//! `TPU_CHIPS_PER_HOST_BOUNDS`, `JAX_PLATFORMS=tpuu`, a non-numeric worker ID)
+//! passes a presence check and then breaks the job when libtpu or JAX reads
+//! it. STK-007 and the CFG checks use these formats to report such values.
+
+use std::fmt;
+
+/// JAX backends accepted in `JAX_PLATFORMS`
+pub const KNOWN_JAX_PLATFORMS: &[&str] = &["tpu", "cpu", "gpu", "cuda", "rocm", "metal"];
+
+/// The shape a variable's value must have
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueFormat {
+    /// An integer in an inclusive range
+    Integer { min: i64, max: i64 },
+    /// A number in (0, 1]
+    Fraction,
+    /// `true`/`false` or `1`/`0`, in any case
+    Boolean,
+    /// Three positive integers, e.g. `2,2,1`
+    Bounds,
+    /// Comma-separated non-negative integers, e.g. `0,1,2,3`
+    IntegerList,
+    /// `host:port`
+    HostPort,
+    /// Comma-separated entries from a fixed list, in any case
+    ListOf(&'static [&'static str]),
+}
+
+impl ValueFormat {
+    /// Check `value`, returning what was expected when it is malformed
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        let value = value.trim();
+        let valid = match self {
+            ValueFormat::Integer { min, max } => value.parse::<i64>().is_ok_and(|n| (*min..=*max).contains(&n)),
+            ValueFormat::Fraction => value.parse::<f64>().is_ok_and(|f| f > 0.0 && f <= 1.0),
+            ValueFormat::Boolean => {
+                matches!(value.to_ascii_lowercase().as_str(), "true" | "false" | "1" | "0")
+            }
+            ValueFormat::Bounds => {
+                let dims: Vec<&str> = value.split(',').collect();
+                dims.len() == 3 && dims.iter().all(|d| d.trim().parse::<u32>().is_ok_and(|n| n > 0))
+            }
+            ValueFormat::IntegerList => value.split(',').all(|n| n.trim().parse::<u32>().is_ok()),
+            ValueFormat::HostPort => match value.rsplit_once(':') {
+                Some((host, port)) => !host.is_empty() && port.parse::<u16>().is_ok_and(|p| p > 0),
+                None => false,
+            },
+            ValueFormat::ListOf(allowed) => {
+                let unknown: Vec<&str> = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|entry| !allowed.iter().any(|a| a.eq_ignore_ascii_case(entry)))
+                    .collect();
+                if !unknown.is_empty() {
+                    return Err(format!("unknown {}; expected {}", unknown.join(", "), self));
+                }
+                true
+            }
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("expected {}", self))
+        }
+    }
+}
+
+impl fmt::Display for ValueFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueFormat::Integer { min, max } if *max == i64::MAX => write!(f, "an integer >= {}", min),
+            ValueFormat::Integer { min, max } => write!(f, "an integer from {} to {}", min, max),
+            ValueFormat::Fraction => write!(f, "a number greater than 0 and at most 1"),
+            ValueFormat::Boolean => write!(f, "true, false, 1, or 0"),
+            ValueFormat::Bounds => write!(f, "three positive integers, e.g. 2,2,1"),
+            ValueFormat::IntegerList => write!(f, "comma-separated integers, e.g. 0,1,2,3"),
+            ValueFormat::HostPort => write!(f, "host:port"),
+            ValueFormat::ListOf(allowed) => write!(f, "a comma-separated list of {}", allowed.join(", ")),
+        }
+    }
+}
+
+/// `name`'s value described as malformed, or None when it is well-formed
+pub fn malformed(name: &str, value: &str, format: ValueFormat) -> Option<String> {
+    format
+        .validate(value)
+        .err()
+        .map(|expected| format!("{}={:?} ({})", name, value, expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_formats() {
+        let worker = ValueFormat::Integer { min: 0, max: i64::MAX };
+        assert!(worker.validate("3").is_ok());
+        assert_eq!(worker.validate("three"), Err("expected an integer >= 0".to_string()));
+        assert!(ValueFormat::Integer { min: 0, max: 3 }.validate("4").is_err());
+
+        assert!(ValueFormat::Fraction.validate("0.9").is_ok());
+        assert!(ValueFormat::Fraction.validate("90%").is_err());
+        assert!(ValueFormat::Fraction.validate("1.5").is_err());
+        assert!(ValueFormat::Boolean.validate("False").is_ok());
+        assert!(ValueFormat::Boolean.validate("yes").is_err());
+
+        assert!(ValueFormat::Bounds.validate("2,2,1").is_ok());
+        assert!(ValueFormat::Bounds.validate("2,2").is_err());
+        assert!(ValueFormat::Bounds.validate("2,0,1").is_err());
+        assert!(ValueFormat::Bounds.validate("2x2x1").is_err());
+        assert!(ValueFormat::IntegerList.validate("0,1,2,3").is_ok());
+        assert!(ValueFormat::IntegerList.validate("0,1,").is_err());
+
+        assert!(ValueFormat::HostPort.validate("10.0.0.2:8476").is_ok());
+        assert!(ValueFormat::HostPort.validate("10.0.0.2").is_err());
+        assert!(ValueFormat::HostPort.validate(":8476").is_err());
+
+        let platforms = ValueFormat::ListOf(KNOWN_JAX_PLATFORMS);
+        assert!(platforms.validate("tpu,cpu").is_ok());
+        assert!(platforms.validate("METAL").is_ok());
+        assert_eq!(
+            platforms.validate("tpuu,cpu").unwrap_err(),
+            "unknown tpuu; expected a comma-separated list of tpu, cpu, gpu, cuda, rocm, metal"
+        );
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert_eq!(
+            malformed("TPU_CHIPS_PER_HOST_BOUNDS", "2,2", ValueFormat::Bounds),
+            Some("TPU_CHIPS_PER_HOST_BOUNDS=\"2,2\" (expected three positive integers, e.g. 2,2,1)".to_string())
+        );
+        assert_eq!(malformed("TPU_WORKER_ID", "0", ValueFormat::Integer { min: 0, max: i64::MAX }), None);
+    }
+}
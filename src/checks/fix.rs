@@ -0,0 +1,341 @@
+//! Safe remediation for `check --fix`.
+//!
+//! A short whitelist of findings has a fix that is cheap, local and easy to
+//! undo:
+//! - STK-007: `TPU_WORKER_ID` missing (set from the instance metadata)
+//! - CFG-005: verbose TensorFlow logging or JAX NaN debugging enabled
+//! - CFG-006: no compilation cache directory, or one that does not exist
+//! - HW-001: a stale libtpu lockfile left behind by a crashed run
+//!
+//! Each fix is applied only after the user confirms it. Environment changes
+//! cannot reach the parent shell, so they are written to an env file (see
+//! [`DEFAULT_ENV_FILE`]) to be sourced before training, and set in this
+//! process so that re-running the fixed checks sees them.
+
+use crate::engine::result::ValidationReport;
+use crate::platform::{gcp, tpu};
+use crate::{Check, CheckResult, TpuDocError};
+use std::env;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// Env file environment fixes are written to, relative to the working directory
+pub const DEFAULT_ENV_FILE: &str = "tpu-doc.env";
+
+/// One change made by a fix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixAction {
+    /// Set environment variables (in the env file and this process)
+    SetEnv(Vec<(String, String)>),
+    /// Create a directory and its parents
+    CreateDir(PathBuf),
+    /// Remove the libtpu lockfile, if it is still stale
+    RemoveStaleLockfile(PathBuf),
+}
+
+/// A fix for one check's finding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub check_id: String,
+    pub description: String,
+    pub actions: Vec<FixAction>,
+}
+
+/// Fixes for the findings in `report` that have one
+pub fn available_fixes(report: &ValidationReport) -> Vec<Fix> {
+    let worker_id = || gcp::get_instance_attribute("agent-worker-number").ok().flatten();
+    plan_fixes(report, &|var| env::var(var).ok(), &worker_id, tpu::get_stale_libtpu_lockfile())
+}
+
+/// Fixes for `report`, reading the environment through `env_var`
+fn plan_fixes(
+    report: &ValidationReport,
+    env_var: &dyn Fn(&str) -> Option<String>,
+    worker_id: &dyn Fn() -> Option<String>,
+    stale_lockfile: Option<&Path>,
+) -> Vec<Fix> {
+    let mut fixes = Vec::new();
+    let set = |var: &str| env_var(var).is_some_and(|v| !v.is_empty());
+
+    for check in &report.checks {
+        if !matches!(check.result, Some(CheckResult::Warn { .. } | CheckResult::Fail { .. })) {
+            continue;
+        }
+        let fix = |description: String, actions: Vec<FixAction>| Fix {
+            check_id: check.id.clone(),
+            description,
+            actions,
+        };
+
+        match check.id.as_str() {
+            "STK-007" if !set("TPU_WORKER_ID") => {
+                if let Some(id) = worker_id() {
+                    fixes.push(fix(
+                        format!("Set TPU_WORKER_ID={} (from instance metadata)", id),
+                        vec![FixAction::SetEnv(vec![("TPU_WORKER_ID".to_string(), id)])],
+                    ));
+                }
+            }
+            "CFG-005" => {
+                let mut vars = Vec::new();
+                if env_var("TF_CPP_MIN_LOG_LEVEL").as_deref() == Some("0") {
+                    vars.push(("TF_CPP_MIN_LOG_LEVEL".to_string(), "2".to_string()));
+                }
+                if matches!(env_var("JAX_DEBUG_NANS").as_deref(), Some("True" | "1")) {
+                    vars.push(("JAX_DEBUG_NANS".to_string(), "0".to_string()));
+                }
+                if !vars.is_empty() {
+                    let names: Vec<String> = vars.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+                    fixes.push(fix(format!("Set {}", names.join(", ")), vec![FixAction::SetEnv(vars)]));
+                }
+            }
+            "CFG-006" => match env_var("JAX_COMPILATION_CACHE_DIR").filter(|d| !d.is_empty()) {
+                Some(dir) if !dir.starts_with("gs://") && !Path::new(&dir).exists() => {
+                    fixes.push(fix(format!("Create {}", dir), vec![FixAction::CreateDir(PathBuf::from(dir))]));
+                }
+                None if check_message(check).contains("not configured") => {
+                    if let Some(home) = env_var("HOME").filter(|h| !h.is_empty()) {
+                        let dir = Path::new(&home).join(".cache").join("jax_compilation_cache");
+                        fixes.push(fix(
+                            format!("Set JAX_COMPILATION_CACHE_DIR={} and create it", dir.display()),
+                            vec![
+                                FixAction::SetEnv(vec![(
+                                    "JAX_COMPILATION_CACHE_DIR".to_string(),
+                                    dir.display().to_string(),
+                                )]),
+                                FixAction::CreateDir(dir),
+                            ],
+                        ));
+                    }
+                }
+                _ => {}
+            },
+            "HW-001" => {
+                if let Some(lockfile) = stale_lockfile {
+                    fixes.push(fix(
+                        format!("Remove stale {}", lockfile.display()),
+                        vec![FixAction::RemoveStaleLockfile(lockfile.to_path_buf())],
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fixes
+}
+
+fn check_message(check: &Check) -> &str {
+    match &check.result {
+        Some(CheckResult::Warn { message, .. } | CheckResult::Fail { message, .. }) => message,
+        _ => "",
+    }
+}
+
+/// Ask about each fix on `output` and apply the ones confirmed on `input`.
+/// Returns the IDs of the checks whose fix was applied.
+pub fn confirm_and_apply(fixes: &[Fix], env_file: &Path, input: &mut dyn BufRead, output: &mut dyn Write) -> Vec<String> {
+    let mut applied = Vec::new();
+    let mut wrote_env = false;
+
+    for fix in fixes {
+        let _ = write!(output, "[{}] {}? [y/N] ", fix.check_id, fix.description);
+        let _ = output.flush();
+
+        let mut answer = String::new();
+        if input.read_line(&mut answer).unwrap_or(0) == 0 {
+            // No more input (e.g. stdin is not a terminal): nothing else is confirmed
+            let _ = writeln!(output);
+            break;
+        }
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            continue;
+        }
+
+        match apply(fix, env_file) {
+            Ok(()) => {
+                wrote_env |= fix.actions.iter().any(|a| matches!(a, FixAction::SetEnv(_)));
+                applied.push(fix.check_id.clone());
+            }
+            Err(e) => {
+                let _ = writeln!(output, "  Could not apply fix: {}", e);
+            }
+        }
+    }
+
+    if wrote_env {
+        let _ = writeln!(
+            output,
+            "Environment changes were written to {}; load them with: source {}",
+            env_file.display(),
+            env_file.display()
+        );
+    }
+    applied
+}
+
+/// Apply every action of `fix`
+pub fn apply(fix: &Fix, env_file: &Path) -> Result<(), TpuDocError> {
+    for action in &fix.actions {
+        match action {
+            FixAction::SetEnv(vars) => {
+                write_env_file(env_file, vars)?;
+                for (key, value) in vars {
+                    env::set_var(key, value);
+                }
+            }
+            FixAction::CreateDir(dir) => {
+                fs::create_dir_all(dir).map_err(|e| TpuDocError::io(&format!("creating {}", dir.display()), e))?;
+            }
+            FixAction::RemoveStaleLockfile(path) => {
+                // A run may have started since the check; never remove a live lock
+                if tpu::get_stale_libtpu_lockfile() != Some(path.as_path()) {
+                    return Err(TpuDocError::CheckFailed {
+                        check_id: fix.check_id.clone(),
+                        reason: format!("{} is no longer stale", path.display()),
+                    });
+                }
+                fs::remove_file(path).map_err(|e| TpuDocError::io(&format!("removing {}", path.display()), e))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Add `vars` to the env file at `path` as `export KEY=value` lines,
+/// replacing earlier lines for the same variables and keeping the rest
+fn write_env_file(path: &Path, vars: &[(String, String)]) -> Result<(), TpuDocError> {
+    let existing = fs::read_to_string(path).unwrap_or_default();
+    let mut lines: Vec<String> = existing
+        .lines()
+        .filter(|line| {
+            let assignment = line.trim_start().trim_start_matches("export ");
+            !vars.iter().any(|(key, _)| assignment.starts_with(&format!("{}=", key)))
+        })
+        .map(str::to_string)
+        .collect();
+    lines.extend(vars.iter().map(|(key, value)| format!("export {}={}", key, shell_quote(value))));
+
+    fs::write(path, format!("{}\n", lines.join("\n")))
+        .map_err(|e| TpuDocError::io(&format!("writing {}", path.display()), e))
+}
+
+/// `value` quoted for a POSIX shell when it contains anything unusual
+fn shell_quote(value: &str) -> String {
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || "/._-:".contains(c)) {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CheckCategory;
+    use std::io::Cursor;
+
+    fn warn(id: &str, message: &str) -> Check {
+        Check {
+            id: id.to_string(),
+            name: id.to_string(),
+            category: CheckCategory::Config,
+            description: String::new(),
+            result: Some(CheckResult::Warn {
+                message: message.to_string(),
+                details: String::new(),
+                duration_ms: 1,
+                data: Vec::new(),
+            }),
+            start_ms: None,
+            ownership: Default::default(),
+        }
+    }
+
+    fn env_of(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |name| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+    }
+
+    #[test]
+    fn test_plan_fixes() {
+        let report = ValidationReport {
+            checks: vec![
+                warn("STK-007", "Missing recommended variable(s): TPU_WORKER_ID"),
+                warn("CFG-005", "Debug logging may impact performance"),
+                warn("CFG-006", "JAX persistent compilation cache not configured"),
+                warn("HW-001", "4 chips detected, but a stale libtpu lockfile is present"),
+                warn("SEC-001", "Service account has broad scopes"),
+            ],
+            ..Default::default()
+        };
+        let env = env_of(&[("TF_CPP_MIN_LOG_LEVEL", "0"), ("JAX_DEBUG_NANS", "1"), ("HOME", "/home/tpu")]);
+        let fixes = plan_fixes(&report, &env, &|| Some("2".to_string()), Some(Path::new("/tmp/libtpu_lockfile")));
+
+        let ids: Vec<&str> = fixes.iter().map(|f| f.check_id.as_str()).collect();
+        assert_eq!(ids, vec!["STK-007", "CFG-005", "CFG-006", "HW-001"]);
+        assert_eq!(fixes[0].actions, vec![FixAction::SetEnv(vec![("TPU_WORKER_ID".to_string(), "2".to_string())])]);
+        assert_eq!(fixes[1].description, "Set TF_CPP_MIN_LOG_LEVEL=2, JAX_DEBUG_NANS=0");
+        assert_eq!(
+            fixes[2].actions[1],
+            FixAction::CreateDir(PathBuf::from("/home/tpu/.cache/jax_compilation_cache"))
+        );
+
+        // Nothing to derive the fix from: no fix
+        let fixes = plan_fixes(&report, &env_of(&[]), &|| None, None);
+        assert!(fixes.is_empty());
+
+        // A configured but missing cache directory is only created
+        let env = env_of(&[("JAX_COMPILATION_CACHE_DIR", "/nonexistent/tpu-doc-cache")]);
+        let fixes = plan_fixes(&report, &env, &|| None, None);
+        assert_eq!(fixes[0].actions, vec![FixAction::CreateDir(PathBuf::from("/nonexistent/tpu-doc-cache"))]);
+    }
+
+    #[test]
+    fn test_confirm_and_apply() {
+        let dir = env::temp_dir().join(format!("tpu-doc-fix-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let env_file = dir.join("tpu-doc.env");
+        fs::write(&env_file, "export TPU_DOC_FIX_TEST_A=old\nexport OTHER=kept\n").unwrap();
+
+        let fixes = vec![
+            Fix {
+                check_id: "CFG-005".to_string(),
+                description: "Set variables".to_string(),
+                actions: vec![FixAction::SetEnv(vec![
+                    ("TPU_DOC_FIX_TEST_A".to_string(), "1".to_string()),
+                    ("TPU_DOC_FIX_TEST_B".to_string(), "a b".to_string()),
+                ])],
+            },
+            Fix {
+                check_id: "CFG-006".to_string(),
+                description: "Create cache".to_string(),
+                actions: vec![FixAction::CreateDir(dir.join("cache"))],
+            },
+            Fix {
+                check_id: "STK-007".to_string(),
+                description: "Never asked".to_string(),
+                actions: vec![FixAction::CreateDir(dir.join("unused"))],
+            },
+        ];
+
+        // Second fix declined, input ends before the third
+        let mut output = Vec::new();
+        let applied = confirm_and_apply(&fixes, &env_file, &mut Cursor::new("y\nn\n"), &mut output);
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(applied, vec!["CFG-005"]);
+        assert!(output.contains("[CFG-005] Set variables? [y/N] "));
+        assert!(output.contains("load them with: source"));
+        assert_eq!(
+            fs::read_to_string(&env_file).unwrap(),
+            "export OTHER=kept\nexport TPU_DOC_FIX_TEST_A=1\nexport TPU_DOC_FIX_TEST_B='a b'\n"
+        );
+        assert_eq!(env::var("TPU_DOC_FIX_TEST_A").unwrap(), "1");
+        assert!(!dir.join("cache").exists());
+        assert!(!dir.join("unused").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,12 +1,42 @@
 //! Hardware validation checks.
 //!
 //! Checks for TPU device detection, memory, thermal status, error counters,
-//! interconnect status, and driver status.
+//! interconnect status, driver status, and live utilization.
 
+use crate::checks::{benchmark, probe};
+use crate::platform::linux;
 use crate::platform::tpu::{self};
-use crate::{Check, CheckCategory, CheckResult};
+use crate::{Check, CheckCategory, CheckResult, TpuDocError};
+use std::process::Command;
 use std::time::Instant;
 
+/// Chip temperature at or above which HW-003 and PERF-008 fail
+pub(crate) const THERMAL_CRITICAL_C: f64 = 85.0;
+
+/// Chip temperature at or above which HW-003 and PERF-008 warn
+pub(crate) const THERMAL_WARN_C: f64 = 75.0;
+
+/// Per-chip HBM use above which a chip may be holding a leaked allocation
+const ANOMALOUS_CHIP_USED_PERCENT: f64 = 50.0;
+
+/// How far above the median chip a chip must be to count as anomalous
+const ANOMALOUS_CHIP_MARGIN_PERCENT: f64 = 30.0;
+
+/// Per-chip HBM usage percentage above which a running workload is flagged
+const LIVE_HBM_WARN_PERCENT: f64 = 95.0;
+
+/// Link bandwidth, as a percentage of the median link's, below which a link
+/// is slow (warns) and degraded (fails)
+const ICI_LINK_SLOW_PERCENT: f64 = 75.0;
+const ICI_LINK_DEGRADED_PERCENT: f64 = 50.0;
+
+/// Size of each transfer and transfers timed per link by the link probe
+const ICI_PROBE_TRANSFER_MB: u32 = 16;
+const ICI_PROBE_TRANSFERS: u32 = 5;
+
+/// Limit for the link probe, inside the global check timeout
+const ICI_PROBE_TIMEOUT_MS: u64 = 20_000;
+
 /// Get all hardware checks
 pub fn get_hardware_checks() -> Vec<Check> {
     vec![
@@ -16,6 +46,7 @@ pub fn get_hardware_checks() -> Vec<Check> {
         create_hw004_check(),
         create_hw005_check(),
         create_hw006_check(),
+        create_hw007_check(),
     ]
 }
 
@@ -27,6 +58,8 @@ fn create_hw001_check() -> Check {
         category: CheckCategory::Hardware,
         description: "Verify expected number of TPU chips are present".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -38,6 +71,8 @@ fn create_hw002_check() -> Check {
         category: CheckCategory::Hardware,
         description: "Check total HBM capacity and availability".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -49,6 +84,8 @@ fn create_hw003_check() -> Check {
         category: CheckCategory::Hardware,
         description: "Check temperature of each TPU chip".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -60,6 +97,8 @@ fn create_hw004_check() -> Check {
         category: CheckCategory::Hardware,
         description: "Check for accumulated hardware errors".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -69,8 +108,10 @@ fn create_hw005_check() -> Check {
         id: "HW-005".to_string(),
         name: "ICI Interconnect Status".to_string(),
         category: CheckCategory::Hardware,
-        description: "Verify inter-chip interconnect is functional".to_string(),
+        description: "Verify inter-chip interconnect is functional and measure each chip-to-chip link".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -82,6 +123,21 @@ fn create_hw006_check() -> Check {
         category: CheckCategory::Hardware,
         description: "Verify TPU driver kernel module is loaded".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
+    }
+}
+
+/// HW-007: Live TPU Utilization
+fn create_hw007_check() -> Check {
+    Check {
+        id: "HW-007".to_string(),
+        name: "Live TPU Utilization".to_string(),
+        category: CheckCategory::Hardware,
+        description: "Read duty cycle and HBM usage from the libtpu runtime metrics service".to_string(),
+        result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -109,23 +165,38 @@ pub fn run_hw001() -> CheckResult {
                     message: "No TPU chips detected".to_string(),
                     details: "Expected at least one TPU chip but found none".to_string(),
                     duration_ms,
+                    data: Vec::new(),
                 }
             } else if count < expected {
                 CheckResult::Fail {
                     message: format!("Fewer TPU chips than expected: {} found, {} expected", count, expected),
                     details: "Some TPU chips may be offline or malfunctioning".to_string(),
                     duration_ms,
+                    data: Vec::new(),
                 }
             } else if count > expected {
                 CheckResult::Warn {
                     message: format!("More TPU chips than expected: {} found, {} expected", count, expected),
                     details: "This is unusual but not necessarily an error".to_string(),
                     duration_ms,
+                    data: Vec::new(),
+                }
+            } else if let Some(lockfile) = tpu::get_stale_libtpu_lockfile() {
+                CheckResult::Warn {
+                    message: format!("{} chips detected, but a stale libtpu lockfile is present", count),
+                    details: format!(
+                        "{} exists but no process holds the TPU; it was left behind by a crashed run and \
+                         makes new runs fail with 'TPU in use'. Remove it (or run: tpu-doc check --fix)",
+                        lockfile.display()
+                    ),
+                    duration_ms,
+                    data: Vec::new(),
                 }
             } else {
                 CheckResult::Pass {
                     message: format!("{} chips detected", count),
                     duration_ms,
+                    data: Vec::new(),
                 }
             }
         }
@@ -133,6 +204,7 @@ pub fn run_hw001() -> CheckResult {
             message: "Failed to detect TPU chips".to_string(),
             details: e.to_string(),
             duration_ms: start.elapsed().as_millis() as u64,
+            data: Vec::new(),
         },
     }
 }
@@ -158,23 +230,56 @@ pub fn run_hw002() -> CheckResult {
 
             let total_gb = hbm.total_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
             let available_gb = hbm.available_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+            let source = if hbm.measured { "measured" } else { "estimated" };
+            let details = format!(
+                "{:.1}GB available of {:.1}GB total ({}); {}",
+                available_gb,
+                total_gb,
+                source,
+                format_chip_hbm(&hbm.chips)
+            );
+
+            let anomalous = find_anomalous_hbm_chips(&hbm.chips);
+            if !anomalous.is_empty() {
+                let chips: Vec<String> = anomalous
+                    .iter()
+                    .map(|c| format!("chip {} ({:.0}% used)", c.chip, c.used_percent()))
+                    .collect();
+                return CheckResult::Fail {
+                    message: format!(
+                        "HBM anomalously full on {} - likely leaked by a prior job",
+                        chips.join(", ")
+                    ),
+                    details,
+                    duration_ms,
+                    data: Vec::new(),
+                };
+            }
 
             if availability_pct < 50.0 {
                 CheckResult::Fail {
                     message: format!("HBM availability critically low: {:.1}%", availability_pct),
-                    details: format!("{:.1}GB available of {:.1}GB total", available_gb, total_gb),
+                    details,
                     duration_ms,
+                    data: Vec::new(),
                 }
             } else if availability_pct < 90.0 {
                 CheckResult::Warn {
                     message: format!("HBM availability below threshold: {:.1}%", availability_pct),
-                    details: format!("{:.1}GB available of {:.1}GB total", available_gb, total_gb),
+                    details,
                     duration_ms,
+                    data: Vec::new(),
                 }
             } else {
                 CheckResult::Pass {
-                    message: format!("{:.1}GB available ({:.1}%)", available_gb, availability_pct),
+                    message: format!(
+                        "{:.1}GB available ({:.1}%) across {} chip(s)",
+                        available_gb,
+                        availability_pct,
+                        hbm.chips.len()
+                    ),
                     duration_ms,
+                    data: Vec::new(),
                 }
             }
         }
@@ -184,6 +289,48 @@ pub fn run_hw002() -> CheckResult {
     }
 }
 
+/// Chips whose HBM use stands out from their peers.
+///
+/// A chip is anomalous when it is more than ANOMALOUS_CHIP_USED_PERCENT full
+/// and at least ANOMALOUS_CHIP_MARGIN_PERCENT points above the lower median
+/// chip. The lower median keeps a host with half its chips leaking (including
+/// one of two chips) from using a leaking chip as the baseline. Uniformly
+/// high usage is a running workload, not a leak, and is left to the
+/// aggregate availability thresholds.
+fn find_anomalous_hbm_chips(chips: &[tpu::ChipHbm]) -> Vec<&tpu::ChipHbm> {
+    if chips.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut used: Vec<f64> = chips.iter().map(|c| c.used_percent()).collect();
+    used.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let median = used[(used.len() - 1) / 2];
+
+    chips
+        .iter()
+        .filter(|c| {
+            let pct = c.used_percent();
+            pct > ANOMALOUS_CHIP_USED_PERCENT && pct - median >= ANOMALOUS_CHIP_MARGIN_PERCENT
+        })
+        .collect()
+}
+
+fn format_chip_hbm(chips: &[tpu::ChipHbm]) -> String {
+    const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+    chips
+        .iter()
+        .map(|c| {
+            format!(
+                "chip {}: {:.1}/{:.1}GB free",
+                c.chip,
+                c.free_bytes as f64 / GB,
+                c.total_bytes as f64 / GB
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Execute HW-003: TPU Thermal Status
 pub fn run_hw003() -> CheckResult {
     let start = Instant::now();
@@ -198,23 +345,32 @@ pub fn run_hw003() -> CheckResult {
         Ok(thermal) => {
             let duration_ms = start.elapsed().as_millis() as u64;
             let max_temp = thermal.chip_temperatures.iter().cloned().fold(0.0f64, f64::max);
-
-            if max_temp >= 85.0 {
+            let data: Vec<(String, String)> = thermal
+                .chip_temperatures
+                .iter()
+                .enumerate()
+                .map(|(i, temp)| (format!("chip {}", i), format!("{:.1}C", temp)))
+                .collect();
+
+            if max_temp >= THERMAL_CRITICAL_C {
                 CheckResult::Fail {
                     message: format!("TPU temperature critical: {:.1}C", max_temp),
-                    details: "One or more chips above 85C threshold".to_string(),
+                    details: format!("One or more chips above {:.0}C threshold", THERMAL_CRITICAL_C),
                     duration_ms,
+                    data,
                 }
-            } else if max_temp >= 75.0 {
+            } else if max_temp >= THERMAL_WARN_C {
                 CheckResult::Warn {
                     message: format!("TPU temperature elevated: {:.1}C", max_temp),
-                    details: "One or more chips above 75C warning threshold".to_string(),
+                    details: format!("One or more chips above {:.0}C warning threshold", THERMAL_WARN_C),
                     duration_ms,
+                    data,
                 }
             } else {
                 CheckResult::Pass {
                     message: format!("Max temperature: {:.1}C", max_temp),
                     duration_ms,
+                    data,
                 }
             }
         }
@@ -237,23 +393,30 @@ pub fn run_hw004() -> CheckResult {
     match tpu::get_error_counters() {
         Ok(errors) => {
             let duration_ms = start.elapsed().as_millis() as u64;
+            let data = vec![
+                ("correctable".to_string(), errors.correctable.to_string()),
+                ("uncorrectable".to_string(), errors.uncorrectable.to_string()),
+            ];
 
             if errors.uncorrectable > 0 {
                 CheckResult::Fail {
                     message: format!("{} uncorrectable errors detected", errors.uncorrectable),
                     details: "Uncorrectable errors indicate hardware issues".to_string(),
                     duration_ms,
+                    data,
                 }
             } else if errors.correctable > 0 {
                 CheckResult::Warn {
                     message: format!("{} correctable errors detected", errors.correctable),
                     details: "Correctable errors are handled but may indicate degradation".to_string(),
                     duration_ms,
+                    data,
                 }
             } else {
                 CheckResult::Pass {
                     message: "No hardware errors".to_string(),
                     duration_ms,
+                    data,
                 }
             }
         }
@@ -263,7 +426,32 @@ pub fn run_hw004() -> CheckResult {
     }
 }
 
+/// Measured transfer bandwidth from one local chip to another
+#[derive(Debug, Clone, PartialEq)]
+pub struct IciLink {
+    pub from: u32,
+    pub to: u32,
+    /// GB/s, or None when the transfer failed
+    pub bandwidth_gbps: Option<f64>,
+}
+
+/// State of an ICI link compared with the other links of the host
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IciLinkState {
+    Ok,
+    /// Below [`ICI_LINK_SLOW_PERCENT`] of the median link
+    Slow,
+    /// Below [`ICI_LINK_DEGRADED_PERCENT`] of the median link
+    Degraded,
+    /// The transfer failed
+    Down,
+}
+
 /// Execute HW-005: ICI Interconnect Status
+///
+/// Beyond the interconnect status, measures every chip-to-chip link so a
+/// single degraded link is reported by name instead of being averaged away.
+/// The link matrix is left out when the TPU is busy or JAX is unavailable.
 pub fn run_hw005() -> CheckResult {
     let start = Instant::now();
 
@@ -288,27 +476,209 @@ pub fn run_hw005() -> CheckResult {
         _ => {}
     }
 
-    match tpu::get_ici_status() {
-        Ok(status) => {
-            let duration_ms = start.elapsed().as_millis() as u64;
+    let status = match tpu::get_ici_status() {
+        Ok(status) => status,
+        Err(e) => {
+            return CheckResult::Skip {
+                reason: format!("ICI status unavailable: {}", e),
+            };
+        }
+    };
+    if !status.healthy {
+        return CheckResult::Fail {
+            message: "ICI interconnect errors detected".to_string(),
+            details: status.details,
+            duration_ms: start.elapsed().as_millis() as u64,
+            data: Vec::new(),
+        };
+    }
 
-            if !status.healthy {
-                CheckResult::Fail {
-                    message: "ICI interconnect errors detected".to_string(),
-                    details: status.details,
-                    duration_ms,
-                }
+    let links = match benchmark::describe_workload(&tpu::get_tpu_activity()) {
+        Some(workload) => Err(format!("TPU busy ({})", workload)),
+        None => measure_ici_links(),
+    };
+    let duration_ms = start.elapsed().as_millis() as u64;
+    let links = match links {
+        Ok(links) if !links.is_empty() => links,
+        other => {
+            let reason = other.err().unwrap_or_else(|| "no links measured".to_string());
+            return CheckResult::Pass {
+                message: format!("ICI healthy, bandwidth: {:.1} GB/s", status.bandwidth_gbps),
+                duration_ms,
+                data: vec![("link matrix".to_string(), format!("not measured: {}", reason))],
+            };
+        }
+    };
+
+    let median = median_link_bandwidth(&links);
+    let states = ici_link_states(&links);
+    let mut data: Vec<(String, String)> = links
+        .iter()
+        .zip(&states)
+        .map(|(link, state)| {
+            let value = match (link.bandwidth_gbps, state) {
+                (None, _) => "down".to_string(),
+                (Some(bw), IciLinkState::Ok) => format!("{:.1} GB/s", bw),
+                (Some(bw), IciLinkState::Slow) => format!("{:.1} GB/s (slow)", bw),
+                (Some(bw), _) => format!("{:.1} GB/s (degraded)", bw),
+            };
+            (format!("chip {} -> chip {}", link.from, link.to), value)
+        })
+        .collect();
+    data.push(("median link bandwidth".to_string(), format!("{:.1} GB/s", median)));
+
+    let named = |wanted: &[IciLinkState]| -> Vec<String> {
+        links
+            .iter()
+            .zip(&states)
+            .filter(|(_, state)| wanted.contains(state))
+            .map(|(link, _)| format!("chip {} -> chip {}", link.from, link.to))
+            .collect()
+    };
+    let failing = named(&[IciLinkState::Down, IciLinkState::Degraded]);
+    let slow = named(&[IciLinkState::Slow]);
+    let details = format_link_matrix(&links);
+
+    if !failing.is_empty() {
+        CheckResult::Fail {
+            message: format!("{} ICI link(s) down or degraded: {}", failing.len(), failing.join(", ")),
+            details,
+            duration_ms,
+            data,
+        }
+    } else if !slow.is_empty() {
+        CheckResult::Warn {
+            message: format!("{} ICI link(s) slower than their peers: {}", slow.len(), slow.join(", ")),
+            details,
+            duration_ms,
+            data,
+        }
+    } else {
+        CheckResult::Pass {
+            message: format!("ICI healthy: {} links, median {:.1} GB/s", links.len(), median),
+            duration_ms,
+            data,
+        }
+    }
+}
+
+/// Time transfers between every ordered pair of local chips
+fn measure_ici_links() -> Result<Vec<IciLink>, String> {
+    let script = r#"
+import sys
+import time
+import jax
+import jax.numpy as jnp
+
+size_mb, transfers = int(sys.argv[1]), int(sys.argv[2])
+devices = jax.local_devices()
+x = jnp.ones(size_mb * 1024 * 1024 // 4, dtype=jnp.float32)
+for i, src in enumerate(devices):
+    a = jax.device_put(x, src).block_until_ready()
+    for j, dst in enumerate(devices):
+        if i == j:
+            continue
+        try:
+            jax.device_put(a, dst).block_until_ready()
+            start = time.perf_counter()
+            for _ in range(transfers):
+                jax.device_put(a, dst).block_until_ready()
+            elapsed = time.perf_counter() - start
+            print(f"{i} {j} {size_mb / 1024 * transfers / elapsed:.2f}")
+        except Exception:
+            print(f"{i} {j} down")
+"#;
+
+    let mut command = Command::new("python3");
+    command
+        .args(["-c", script])
+        .arg(ICI_PROBE_TRANSFER_MB.to_string())
+        .arg(ICI_PROBE_TRANSFERS.to_string());
+    match linux::run_command_with_timeout(&mut command, ICI_PROBE_TIMEOUT_MS) {
+        Ok(output) if output.status.success() => Ok(parse_ici_links(&String::from_utf8_lossy(&output.stdout))),
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("No module named 'jax'") {
+                Err("JAX not installed".to_string())
             } else {
-                CheckResult::Pass {
-                    message: format!("ICI healthy, bandwidth: {:.1} GB/s", status.bandwidth_gbps),
-                    duration_ms,
-                }
+                Err(probe::describe_failure(
+                    format!("link probe failed: {}", stderr.lines().next().unwrap_or("unknown error")),
+                    &output,
+                ))
             }
         }
-        Err(e) => CheckResult::Skip {
-            reason: format!("ICI status unavailable: {}", e),
-        },
+        Err(TpuDocError::Timeout { .. }) => Err(format!("link probe timed out after {}ms", ICI_PROBE_TIMEOUT_MS)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Parse the link probe's `FROM TO GBPS` (or `FROM TO down`) lines
+fn parse_ici_links(output: &str) -> Vec<IciLink> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                [from, to, bandwidth] => Some(IciLink {
+                    from: from.parse().ok()?,
+                    to: to.parse().ok()?,
+                    bandwidth_gbps: match bandwidth {
+                        "down" => None,
+                        bw => Some(bw.parse().ok()?),
+                    },
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Median bandwidth of the links that transferred, or 0 when none did
+fn median_link_bandwidth(links: &[IciLink]) -> f64 {
+    let mut measured: Vec<f64> = links.iter().filter_map(|link| link.bandwidth_gbps).collect();
+    measured.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    measured.get(measured.len() / 2).copied().unwrap_or(0.0)
+}
+
+/// State of each link, compared with the median link
+fn ici_link_states(links: &[IciLink]) -> Vec<IciLinkState> {
+    let median = median_link_bandwidth(links);
+    links
+        .iter()
+        .map(|link| match link.bandwidth_gbps {
+            None => IciLinkState::Down,
+            Some(bw) if bw < median * ICI_LINK_DEGRADED_PERCENT / 100.0 => IciLinkState::Degraded,
+            Some(bw) if bw < median * ICI_LINK_SLOW_PERCENT / 100.0 => IciLinkState::Slow,
+            Some(_) => IciLinkState::Ok,
+        })
+        .collect()
+}
+
+/// The links as a grid of GB/s, a row per sending chip and a column per
+/// receiving chip
+fn format_link_matrix(links: &[IciLink]) -> String {
+    let chips = links.iter().map(|link| link.from.max(link.to) + 1).max().unwrap_or(0);
+    let mut lines = vec![format!(
+        "GB/s from (rows) to (columns):\n{:>8}{}",
+        "",
+        (0..chips).map(|to| format!("{:>8}", format!("chip {}", to))).collect::<String>()
+    )];
+    for from in 0..chips {
+        let cells: String = (0..chips)
+            .map(|to| {
+                let link = links.iter().find(|link| link.from == from && link.to == to);
+                let cell = match link.map(|link| link.bandwidth_gbps) {
+                    _ if from == to => "-".to_string(),
+                    Some(Some(bw)) => format!("{:.1}", bw),
+                    Some(None) => "down".to_string(),
+                    None => "?".to_string(),
+                };
+                format!("{:>8}", cell)
+            })
+            .collect();
+        lines.push(format!("{:>8}{}", format!("chip {}", from), cells));
     }
+    lines.join("\n")
 }
 
 /// Execute HW-006: Driver Status
@@ -323,21 +693,173 @@ pub fn run_hw006() -> CheckResult {
             message: "TPU driver not loaded".to_string(),
             details: "The TPU kernel module is not loaded".to_string(),
             duration_ms,
+            data: Vec::new(),
         };
     }
 
     match tpu::get_driver_version() {
-        Ok(version) => {
-            // Check version compatibility (simplified)
+        Ok(version) => super::with_known_issues(
+            "driver",
+            &version,
             CheckResult::Pass {
                 message: format!("Driver version: {}", version),
                 duration_ms,
-            }
-        }
+                data: Vec::new(),
+            },
+        ),
         Err(e) => CheckResult::Warn {
             message: "Driver loaded but version unknown".to_string(),
             details: e.to_string(),
             duration_ms,
+            data: Vec::new(),
         },
     }
 }
+
+/// Execute HW-007: Live TPU Utilization
+pub fn run_hw007() -> CheckResult {
+    let start = Instant::now();
+
+    if !tpu::is_tpu_vm() {
+        return CheckResult::Skip {
+            reason: "Not running on a TPU VM".to_string(),
+        };
+    }
+
+    let metrics = match tpu::get_runtime_metrics() {
+        Ok(m) => m,
+        Err(e) => {
+            return CheckResult::Skip {
+                reason: format!(
+                    "libtpu metrics service not available at {} (no running workload?): {}",
+                    tpu::get_metrics_addr(),
+                    e
+                ),
+            };
+        }
+    };
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    let per_chip: Vec<String> = metrics
+        .chips
+        .iter()
+        .map(|c| {
+            let fmt = |v: Option<f64>| v.map(|v| format!("{:.1}%", v)).unwrap_or_else(|| "n/a".to_string());
+            format!(
+                "chip {}: duty {}, HBM {}",
+                c.chip,
+                fmt(c.duty_cycle_percent),
+                fmt(c.hbm_utilization_percent())
+            )
+        })
+        .collect();
+    let details = per_chip.join("; ");
+
+    let summary = format!(
+        "{} chip(s), mean duty cycle {}, HBM {}",
+        metrics.chips.len(),
+        metrics
+            .mean_duty_cycle_percent()
+            .map(|v| format!("{:.1}%", v))
+            .unwrap_or_else(|| "n/a".to_string()),
+        metrics
+            .hbm_utilization_percent()
+            .map(|v| format!("{:.1}%", v))
+            .unwrap_or_else(|| "n/a".to_string())
+    );
+
+    let full_chips: Vec<u32> = metrics
+        .chips
+        .iter()
+        .filter(|c| c.hbm_utilization_percent().is_some_and(|v| v > LIVE_HBM_WARN_PERCENT))
+        .map(|c| c.chip)
+        .collect();
+    if !full_chips.is_empty() {
+        return CheckResult::Warn {
+            message: format!(
+                "HBM above {:.0}% on chip(s) {:?}; {}",
+                LIVE_HBM_WARN_PERCENT, full_chips, summary
+            ),
+            details,
+            duration_ms,
+            data: Vec::new(),
+        };
+    }
+
+    CheckResult::Pass {
+        message: summary,
+        duration_ms,
+        data: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chip(chip: u32, used_gb: u64) -> tpu::ChipHbm {
+        let gib = 1024 * 1024 * 1024;
+        tpu::ChipHbm {
+            chip,
+            total_bytes: 16 * gib,
+            free_bytes: (16 - used_gb) * gib,
+        }
+    }
+
+    #[test]
+    fn test_find_anomalous_hbm_chips() {
+        // One chip holding memory while its peers are idle
+        let chips = vec![chip(0, 0), chip(1, 15), chip(2, 1), chip(3, 0)];
+        let anomalous = find_anomalous_hbm_chips(&chips);
+        assert_eq!(anomalous.len(), 1);
+        assert_eq!(anomalous[0].chip, 1);
+
+        // Uniformly busy chips are a running workload, not a leak
+        let chips = vec![chip(0, 14), chip(1, 15), chip(2, 14), chip(3, 14)];
+        assert!(find_anomalous_hbm_chips(&chips).is_empty());
+
+        // A single chip has no peers to compare against
+        assert!(find_anomalous_hbm_chips(&[chip(0, 15)]).is_empty());
+
+        // One of two chips holding memory
+        let chips = vec![chip(0, 0), chip(1, 15)];
+        let anomalous = find_anomalous_hbm_chips(&chips);
+        assert_eq!(anomalous.len(), 1);
+        assert_eq!(anomalous[0].chip, 1);
+
+        // Half the host leaking
+        let chips = vec![chip(0, 15), chip(1, 0), chip(2, 14), chip(3, 1)];
+        let anomalous = find_anomalous_hbm_chips(&chips);
+        let leaked: Vec<u32> = anomalous.iter().map(|c| c.chip).collect();
+        assert_eq!(leaked, vec![0, 2]);
+    }
+
+    #[test]
+    fn test_ici_link_matrix() {
+        let output = "0 1 45.20\n0 2 44.80\n1 0 45.00\n1 2 30.00\n2 0 12.00\n2 1 down\nWARNING: noise\n";
+        let links = parse_ici_links(output);
+        assert_eq!(links.len(), 6);
+        assert_eq!(links[0], IciLink { from: 0, to: 1, bandwidth_gbps: Some(45.2) });
+        assert_eq!(links[5].bandwidth_gbps, None);
+
+        // One slow and one degraded link among healthy ones are not averaged away
+        assert_eq!(median_link_bandwidth(&links), 44.8);
+        assert_eq!(
+            ici_link_states(&links),
+            [
+                IciLinkState::Ok,
+                IciLinkState::Ok,
+                IciLinkState::Ok,
+                IciLinkState::Slow,
+                IciLinkState::Degraded,
+                IciLinkState::Down
+            ]
+        );
+
+        let matrix = format_link_matrix(&links);
+        let rows: Vec<&str> = matrix.lines().collect();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[1], "          chip 0  chip 1  chip 2");
+        assert_eq!(rows[4], "  chip 2    12.0    down       -");
+    }
+}
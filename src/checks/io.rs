@@ -1,9 +1,16 @@
 //! I/O throughput validation checks.
 //!
 //! Checks for GCS read throughput, local disk throughput, GCS connectivity,
-//! checkpoint directory access, network latency, and DNS resolution.
+//! checkpoint directory access, network latency, DNS resolution, end-to-end
+//! input pipeline throughput, co-location of the training data bucket, and
+//! access to the container registry images are pulled from.
 
-use crate::platform::{gcp, linux, network};
+use crate::checks::timeouts;
+use crate::data::baselines;
+use crate::platform::registry::{self, RegistryRef};
+use crate::platform::storage::{self, BucketInfo};
+use crate::platform::{gcp, linux, network, tpu};
+use crate::util;
 use crate::{Check, CheckCategory, CheckResult};
 use std::time::Instant;
 
@@ -16,6 +23,9 @@ pub fn get_io_checks() -> Vec<Check> {
         create_io004_check(),
         create_io005_check(),
         create_io006_check(),
+        create_io007_check(),
+        create_io008_check(),
+        create_io009_check(),
     ]
 }
 
@@ -27,6 +37,8 @@ fn create_io001_check() -> Check {
         category: CheckCategory::Io,
         description: "Measure read throughput from Google Cloud Storage".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -38,6 +50,8 @@ fn create_io002_check() -> Check {
         category: CheckCategory::Io,
         description: "Measure sequential read/write to local SSD".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -49,6 +63,8 @@ fn create_io003_check() -> Check {
         category: CheckCategory::Io,
         description: "Verify connectivity to storage.googleapis.com".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -60,6 +76,8 @@ fn create_io004_check() -> Check {
         category: CheckCategory::Io,
         description: "Verify checkpoint directory access and space".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -71,6 +89,8 @@ fn create_io005_check() -> Check {
         category: CheckCategory::Io,
         description: "Measure latency to GCP services".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -82,9 +102,53 @@ fn create_io006_check() -> Check {
         category: CheckCategory::Io,
         description: "Verify DNS resolution is working".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
+/// IO-007: Input Pipeline Throughput
+fn create_io007_check() -> Check {
+    Check {
+        id: "IO-007".to_string(),
+        name: "Input Pipeline Throughput".to_string(),
+        category: CheckCategory::Io,
+        description: "Stream records from a GCS prefix through tf.data to the device".to_string(),
+        result: None,
+        start_ms: None,
+        ownership: Default::default(),
+    }
+}
+
+/// IO-008: Bucket Co-location
+fn create_io008_check() -> Check {
+    Check {
+        id: "IO-008".to_string(),
+        name: "Bucket Co-location".to_string(),
+        category: CheckCategory::Io,
+        description: "Compare the training data bucket's location and storage class with the TPU's zone".to_string(),
+        result: None,
+        start_ms: None,
+        ownership: Default::default(),
+    }
+}
+
+/// IO-009: Container Registry Access
+fn create_io009_check() -> Check {
+    Check {
+        id: "IO-009".to_string(),
+        name: "Container Registry Access".to_string(),
+        category: CheckCategory::Io,
+        description: "Verify DNS, connectivity, and pull access for the container registry".to_string(),
+        result: None,
+        start_ms: None,
+        ownership: Default::default(),
+    }
+}
+
+/// Default number of records streamed by IO-007
+const DEFAULT_PIPELINE_RECORDS: u64 = 1000;
+
 /// Execute IO-001: GCS Read Throughput
 pub fn run_io001() -> CheckResult {
     let _start = Instant::now();
@@ -150,16 +214,22 @@ pub fn run_io002() -> CheckResult {
 
             match throughput_gbps {
                 Some(throughput) => {
+                    let data = vec![
+                        ("path".to_string(), test_file.to_string()),
+                        ("write throughput".to_string(), format!("{:.2} GB/s", throughput)),
+                    ];
                     if throughput < 0.5 {
                         CheckResult::Warn {
                             message: format!("Local disk throughput low: {:.2} GB/s", throughput),
                             details: "Expected at least 1 GB/s for NVMe SSD".to_string(),
                             duration_ms,
+                            data,
                         }
                     } else {
                         CheckResult::Pass {
                             message: format!("Local disk throughput: {:.2} GB/s", throughput),
                             duration_ms,
+                            data,
                         }
                     }
                 }
@@ -167,6 +237,7 @@ pub fn run_io002() -> CheckResult {
                     message: "Could not measure disk throughput".to_string(),
                     details: "dd output parsing failed".to_string(),
                     duration_ms,
+                    data: Vec::new(),
                 },
             }
         }
@@ -180,7 +251,7 @@ pub fn run_io002() -> CheckResult {
 pub fn run_io003() -> CheckResult {
     let start = Instant::now();
 
-    match network::check_tcp_connectivity("storage.googleapis.com", 443, 5000) {
+    match network::check_tcp_connectivity("storage.googleapis.com", 443, timeouts::net_timeout_ms()) {
         Ok(result) => {
             let duration_ms = start.elapsed().as_millis() as u64;
 
@@ -188,12 +259,14 @@ pub fn run_io003() -> CheckResult {
                 CheckResult::Pass {
                     message: format!("GCS connectivity OK, latency: {}ms", result.latency_ms),
                     duration_ms,
+                    data: Vec::new(),
                 }
             } else {
                 CheckResult::Fail {
                     message: "Cannot connect to storage.googleapis.com".to_string(),
                     details: "TCP connection to port 443 failed".to_string(),
                     duration_ms,
+                    data: Vec::new(),
                 }
             }
         }
@@ -201,12 +274,62 @@ pub fn run_io003() -> CheckResult {
             message: "GCS connectivity check failed".to_string(),
             details: e.to_string(),
             duration_ms: start.elapsed().as_millis() as u64,
+            data: Vec::new(),
         },
     }
 }
 
+/// Free space IO-004 recommends when no checkpoint plan is given
+const RECOMMENDED_CHECKPOINT_SPACE_GB: f64 = 100.0;
+
+/// Checkpoints kept when a checkpoint size is given without a count
+pub const DEFAULT_CHECKPOINT_KEEP: u32 = 1;
+
+/// Expected checkpoint size and retention, from `--checkpoint-size` and
+/// `--keep` or the `[checkpoint]` table of the configuration file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CheckpointPlan {
+    /// Size of one checkpoint in bytes
+    pub size_bytes: u64,
+    /// Checkpoints kept before the oldest is deleted
+    pub keep: u32,
+}
+
+impl CheckpointPlan {
+    /// Space the checkpoints need at their peak: `keep` checkpoints plus
+    /// the one being written before the oldest is deleted
+    pub fn footprint_bytes(&self) -> u64 {
+        self.size_bytes.saturating_mul(self.keep as u64 + 1)
+    }
+}
+
+/// Parse a checkpoint size such as `250GB`, `1.5TB`, or `800MB` into bytes
+/// (binary multiples, as `df` reports them)
+pub fn parse_checkpoint_size(s: &str) -> Result<u64, String> {
+    let lower = s.trim().to_ascii_lowercase();
+    let units: &[(&str, f64)] = &[("tb", 1024f64.powi(4)), ("gb", 1024f64.powi(3)), ("mb", 1024f64.powi(2))];
+    units
+        .iter()
+        .find_map(|(suffix, multiplier)| {
+            let number = lower.strip_suffix(suffix)?.trim().parse::<f64>().ok()?;
+            (number.is_finite() && number > 0.0).then(|| (number * multiplier) as u64)
+        })
+        .ok_or_else(|| format!("Invalid checkpoint size: '{}' (expected e.g. 800MB, 250GB, or 1.5TB)", s))
+}
+
+/// Bytes as gigabytes for messages
+fn gb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+}
+
 /// Execute IO-004: Checkpoint Directory Access
 pub fn run_io004() -> CheckResult {
+    run_io004_with(None)
+}
+
+/// Execute IO-004, checking that the directory or bucket can hold the
+/// footprint of `plan` rather than a fixed recommended amount
+pub fn run_io004_with(plan: Option<&CheckpointPlan>) -> CheckResult {
     let start = Instant::now();
 
     // Check if CHECKPOINT_DIR is set
@@ -219,6 +342,25 @@ pub fn run_io004() -> CheckResult {
         }
     };
 
+    // Buckets have no capacity limit; what can stop rotation is a
+    // retention policy
+    if checkpoint_dir.starts_with("gs://") {
+        let Some(bucket) = storage::bucket_of(&checkpoint_dir) else {
+            return CheckResult::Fail {
+                message: "Invalid checkpoint bucket".to_string(),
+                details: format!("CHECKPOINT_DIR has no bucket name: {}", checkpoint_dir),
+                duration_ms: start.elapsed().as_millis() as u64,
+                data: Vec::new(),
+            };
+        };
+        return match storage::get_bucket(bucket) {
+            Ok(info) => evaluate_checkpoint_bucket(&info, plan, start.elapsed().as_millis() as u64),
+            Err(e) => CheckResult::Skip {
+                reason: format!("Could not read bucket gs://{} metadata: {}", bucket, e),
+            },
+        };
+    }
+
     // Check if directory exists
     let path = std::path::Path::new(&checkpoint_dir);
 
@@ -229,6 +371,7 @@ pub fn run_io004() -> CheckResult {
                 message: "Cannot create checkpoint directory".to_string(),
                 details: format!("Path: {}", checkpoint_dir),
                 duration_ms: start.elapsed().as_millis() as u64,
+                data: Vec::new(),
             };
         }
     }
@@ -243,89 +386,274 @@ pub fn run_io004() -> CheckResult {
             message: "No write permission for checkpoint directory".to_string(),
             details: format!("Path: {}", checkpoint_dir),
             duration_ms: start.elapsed().as_millis() as u64,
+            data: Vec::new(),
         };
     }
 
     // Check available space
     match linux::get_disk_space(&checkpoint_dir) {
         Ok(disk_info) => {
-            let duration_ms = start.elapsed().as_millis() as u64;
-            let available_gb = disk_info.available_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
-
-            if available_gb < 100.0 {
-                CheckResult::Warn {
-                    message: format!("Checkpoint directory space low: {:.1} GB available", available_gb),
-                    details: "Recommended at least 100GB for checkpoints".to_string(),
-                    duration_ms,
-                }
-            } else {
-                CheckResult::Pass {
-                    message: format!("Checkpoint directory OK, {:.1} GB available", available_gb),
-                    duration_ms,
-                }
-            }
+            evaluate_checkpoint_space(disk_info.available_bytes, plan, start.elapsed().as_millis() as u64)
         }
         Err(e) => CheckResult::Warn {
             message: "Could not check checkpoint directory space".to_string(),
             details: e.to_string(),
             duration_ms: start.elapsed().as_millis() as u64,
+            data: Vec::new(),
+        },
+    }
+}
+
+/// The result data describing `plan`
+fn checkpoint_plan_data(plan: &CheckpointPlan) -> Vec<(String, String)> {
+    vec![
+        ("checkpoint size".to_string(), format!("{:.1} GB", gb(plan.size_bytes))),
+        ("checkpoints kept".to_string(), plan.keep.to_string()),
+        ("projected footprint".to_string(), format!("{:.1} GB", gb(plan.footprint_bytes()))),
+    ]
+}
+
+/// IO-004's result for a local directory with `available_bytes` free: too
+/// little for `plan`'s footprint fails, less than the recommended amount
+/// without a plan warns
+fn evaluate_checkpoint_space(available_bytes: u64, plan: Option<&CheckpointPlan>, duration_ms: u64) -> CheckResult {
+    let available_gb = gb(available_bytes);
+    let Some(plan) = plan else {
+        let data = vec![("available".to_string(), format!("{:.1} GB", available_gb))];
+        return if available_gb < RECOMMENDED_CHECKPOINT_SPACE_GB {
+            CheckResult::Warn {
+                message: format!("Checkpoint directory space low: {:.1} GB available", available_gb),
+                details: "Recommended at least 100GB for checkpoints".to_string(),
+                duration_ms,
+                data,
+            }
+        } else {
+            CheckResult::Pass {
+                message: format!("Checkpoint directory OK, {:.1} GB available", available_gb),
+                duration_ms,
+                data,
+            }
+        };
+    };
+
+    let mut data = checkpoint_plan_data(plan);
+    data.push(("available".to_string(), format!("{:.1} GB", available_gb)));
+    let footprint_gb = gb(plan.footprint_bytes());
+    if available_bytes < plan.footprint_bytes() {
+        CheckResult::Fail {
+            message: format!(
+                "Checkpoint directory too small: {:.1} GB projected, {:.1} GB available",
+                footprint_gb, available_gb
+            ),
+            details: format!(
+                "{} kept checkpoints plus the one being written, {:.1} GB each, need {:.1} GB; \
+                 free space, keep fewer checkpoints, or checkpoint to a gs:// bucket",
+                plan.keep,
+                gb(plan.size_bytes),
+                footprint_gb
+            ),
+            duration_ms,
+            data,
+        }
+    } else {
+        CheckResult::Pass {
+            message: format!(
+                "Checkpoint directory OK, {:.1} GB available for {:.1} GB projected",
+                available_gb, footprint_gb
+            ),
+            duration_ms,
+            data,
+        }
+    }
+}
+
+/// Seconds as whole days for messages
+fn days(secs: u64) -> u64 {
+    secs.div_ceil(86400)
+}
+
+/// IO-004's result for checkpoint bucket `info`: a retention policy keeps
+/// rotated-out checkpoints, so with a plan the footprint grows past it
+fn evaluate_checkpoint_bucket(info: &BucketInfo, plan: Option<&CheckpointPlan>, duration_ms: u64) -> CheckResult {
+    let mut data = plan.map(checkpoint_plan_data).unwrap_or_default();
+    data.push(("bucket".to_string(), format!("gs://{}", info.name)));
+    if let Some(secs) = info.retention_period_secs {
+        data.push(("retention policy".to_string(), format!("{}d", days(secs))));
+    }
+    if let Some(secs) = info.soft_delete_secs {
+        data.push(("soft delete".to_string(), format!("{}d", days(secs))));
+    }
+
+    match (plan, info.retention_period_secs) {
+        (Some(plan), Some(secs)) => CheckResult::Warn {
+            message: format!(
+                "Checkpoint bucket gs://{} retains objects for {}d; keeping {} checkpoint(s) cannot free space",
+                info.name,
+                days(secs),
+                plan.keep
+            ),
+            details: format!(
+                "Checkpoints rotated out stay (and are billed) until the retention period ends, so storage grows \
+                 by {:.1} GB per checkpoint instead of staying at {:.1} GB",
+                gb(plan.size_bytes),
+                gb(plan.footprint_bytes())
+            ),
+            duration_ms,
+            data,
+        },
+        (Some(plan), None) => CheckResult::Pass {
+            message: format!(
+                "Checkpoint bucket gs://{} OK, no capacity limit for {:.1} GB projected{}",
+                info.name,
+                gb(plan.footprint_bytes()),
+                info.soft_delete_secs
+                    .map(|secs| format!(" (deleted checkpoints billed for {}d by soft delete)", days(secs)))
+                    .unwrap_or_default()
+            ),
+            duration_ms,
+            data,
+        },
+        (None, _) => CheckResult::Pass {
+            message: format!("Checkpoint bucket gs://{} accessible", info.name),
+            duration_ms,
+            data,
         },
     }
 }
 
+/// Connection latency above which IO-005 warns, unless an endpoint sets
+/// its own threshold
+pub const DEFAULT_LATENCY_WARN_MS: u64 = 10;
+
+/// A service IO-005 measures connection latency to. Endpoints come from the
+/// `[[network.endpoints]]` tables of the configuration file, replacing the
+/// built-in set ([`default_latency_endpoints`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyEndpoint {
+    /// Label in the report (defaults to the host)
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    /// Latency in milliseconds above which the check warns
+    pub warn_ms: u64,
+    /// Latency in milliseconds above which the check fails (None = never)
+    pub fail_ms: Option<u64>,
+    /// An unreachable required endpoint fails the check; others warn
+    pub required: bool,
+}
+
+impl LatencyEndpoint {
+    fn builtin(host: &str, port: u16) -> Self {
+        LatencyEndpoint {
+            name: host.to_string(),
+            host: host.to_string(),
+            port,
+            warn_ms: DEFAULT_LATENCY_WARN_MS,
+            fail_ms: None,
+            required: false,
+        }
+    }
+}
+
+/// The services IO-005 measures when the configuration lists none
+pub fn default_latency_endpoints() -> Vec<LatencyEndpoint> {
+    vec![
+        LatencyEndpoint::builtin("metadata.google.internal", 80),
+        LatencyEndpoint::builtin("storage.googleapis.com", 443),
+        LatencyEndpoint::builtin("compute.googleapis.com", 443),
+    ]
+}
+
 /// Execute IO-005: Network Latency to GCP Services
 pub fn run_io005() -> CheckResult {
+    run_io005_with(&default_latency_endpoints())
+}
+
+/// Execute IO-005 against `endpoints` instead of the built-in set
+pub fn run_io005_with(endpoints: &[LatencyEndpoint]) -> CheckResult {
     let start = Instant::now();
 
-    let services = [
-        ("metadata.google.internal", 80),
-        ("storage.googleapis.com", 443),
-        ("compute.googleapis.com", 443),
-    ];
+    let latencies: Vec<Result<u64, String>> = endpoints
+        .iter()
+        .map(|endpoint| {
+            match network::check_tcp_connectivity(&endpoint.host, endpoint.port, timeouts::net_timeout_ms()) {
+                Ok(result) if result.success => Ok(result.latency_ms),
+                Ok(_) => Err("connection failed".to_string()),
+                Err(e) => Err(e.to_string()),
+            }
+        })
+        .collect();
 
-    let mut latencies = Vec::new();
-    let mut failures = Vec::new();
+    let duration_ms = start.elapsed().as_millis() as u64;
+    evaluate_latencies(endpoints, &latencies, duration_ms)
+}
 
-    for (host, port) in services.iter() {
-        match network::check_tcp_connectivity(host, *port, 5000) {
-            Ok(result) if result.success => {
-                latencies.push((host.to_string(), result.latency_ms));
-            }
-            Ok(_) => {
-                failures.push(format!("{}:{} - connection failed", host, port));
+/// IO-005's result for the measured `latencies` (in milliseconds, or why
+/// the endpoint could not be reached), in the same order as `endpoints`
+fn evaluate_latencies(
+    endpoints: &[LatencyEndpoint],
+    latencies: &[Result<u64, String>],
+    duration_ms: u64,
+) -> CheckResult {
+    let mut failing = Vec::new();
+    let mut unreachable = Vec::new();
+    let mut slow = Vec::new();
+    let mut data = Vec::new();
+
+    for (endpoint, latency) in endpoints.iter().zip(latencies) {
+        match latency {
+            Ok(ms) => {
+                data.push((endpoint.name.clone(), format!("{}ms", ms)));
+                match endpoint.fail_ms {
+                    Some(fail_ms) if *ms > fail_ms => {
+                        failing.push(format!("{}: {}ms (fails above {}ms)", endpoint.name, ms, fail_ms))
+                    }
+                    _ if *ms > endpoint.warn_ms => {
+                        slow.push(format!("{}: {}ms (warns above {}ms)", endpoint.name, ms, endpoint.warn_ms))
+                    }
+                    _ => {}
+                }
             }
             Err(e) => {
-                failures.push(format!("{}:{} - {}", host, port, e));
+                data.push((endpoint.name.clone(), "unreachable".to_string()));
+                let description = format!("{} ({}:{}) - {}", endpoint.name, endpoint.host, endpoint.port, e);
+                if endpoint.required {
+                    failing.push(format!("required service unreachable: {}", description));
+                } else {
+                    unreachable.push(description);
+                }
             }
         }
     }
 
-    let duration_ms = start.elapsed().as_millis() as u64;
+    let max_latency = latencies.iter().filter_map(|l| l.as_ref().ok()).max().copied().unwrap_or(0);
 
-    if !failures.is_empty() {
-        return CheckResult::Warn {
-            message: format!("{} service(s) unreachable", failures.len()),
-            details: failures.join("; "),
+    if !failing.is_empty() {
+        CheckResult::Fail {
+            message: format!("{} endpoint(s) failed latency checks", failing.len()),
+            details: failing.into_iter().chain(unreachable).collect::<Vec<_>>().join("; "),
             duration_ms,
-        };
-    }
-
-    let max_latency = latencies.iter().map(|(_, l)| *l).max().unwrap_or(0);
-
-    if max_latency > 10 {
+            data,
+        }
+    } else if !unreachable.is_empty() {
+        CheckResult::Warn {
+            message: format!("{} service(s) unreachable", unreachable.len()),
+            details: unreachable.join("; "),
+            duration_ms,
+            data,
+        }
+    } else if !slow.is_empty() {
         CheckResult::Warn {
             message: format!("Network latency elevated: max {}ms", max_latency),
-            details: latencies
-                .iter()
-                .map(|(h, l)| format!("{}: {}ms", h, l))
-                .collect::<Vec<_>>()
-                .join(", "),
+            details: slow.join(", "),
             duration_ms,
+            data,
         }
     } else {
         CheckResult::Pass {
             message: format!("Network latency OK, max {}ms", max_latency),
             duration_ms,
+            data,
         }
     }
 }
@@ -363,17 +691,318 @@ pub fn run_io006() -> CheckResult {
             message: "DNS resolution failed".to_string(),
             details: failures.join("; "),
             duration_ms,
+            data: Vec::new(),
         }
     } else {
         CheckResult::Pass {
             message: format!("DNS resolution OK, max {}ms", slowest),
             duration_ms,
+            data: Vec::new(),
+        }
+    }
+}
+
+/// Execute IO-007: Input Pipeline Throughput
+///
+/// Optional: only runs when `TPU_PREFLIGHT_GCS_PREFIX` points at training data.
+pub fn run_io007() -> CheckResult {
+    let start = Instant::now();
+
+    let prefix = match util::env_setting("TPU_PREFLIGHT_GCS_PREFIX") {
+        Some(p) if p.starts_with("gs://") => p,
+        Some(p) => {
+            return CheckResult::Fail {
+                message: "Invalid input pipeline prefix".to_string(),
+                details: format!("TPU_PREFLIGHT_GCS_PREFIX must start with gs:// (got '{}')", p),
+                duration_ms: start.elapsed().as_millis() as u64,
+                data: Vec::new(),
+            };
+        }
+        None => {
+            return CheckResult::Skip {
+                reason: "TPU_PREFLIGHT_GCS_PREFIX environment variable not set".to_string(),
+            };
+        }
+    };
+
+    let records = match util::env_setting("TPU_PREFLIGHT_PIPELINE_RECORDS") {
+        Some(value) => match value.parse::<u64>() {
+            Ok(n) if n > 0 => n,
+            _ => {
+                return CheckResult::Fail {
+                    message: "Invalid input pipeline record count".to_string(),
+                    details: format!("TPU_PREFLIGHT_PIPELINE_RECORDS must be a positive integer (got '{}')", value),
+                    duration_ms: start.elapsed().as_millis() as u64,
+                    data: Vec::new(),
+                };
+            }
+        },
+        None => DEFAULT_PIPELINE_RECORDS,
+    };
+
+    match run_pipeline_benchmark(&prefix, records) {
+        Ok(result) => {
+            let duration_ms = start.elapsed().as_millis() as u64;
+            let mb_per_sec = result.bytes as f64 / (1024.0 * 1024.0) / result.elapsed_secs.max(0.001);
+            let examples_per_sec = result.records as f64 / result.elapsed_secs.max(0.001);
+            let vs_expected = expected_gcs_mbps()
+                .map(|expected| {
+                    format!(
+                        ", {:.0}% of {:.0} MB/s expected",
+                        baselines::percent_of_expected(mb_per_sec, expected),
+                        expected
+                    )
+                })
+                .unwrap_or_default();
+
+            if result.records == 0 {
+                CheckResult::Fail {
+                    message: "Input pipeline produced no records".to_string(),
+                    details: format!("No readable TFRecord files under {}", prefix),
+                    duration_ms,
+                    data: Vec::new(),
+                }
+            } else if result.records < records {
+                CheckResult::Warn {
+                    message: format!(
+                        "Input pipeline: {:.0} examples/sec, {:.1} MB/s{} ({} of {} records available)",
+                        examples_per_sec, mb_per_sec, vs_expected, result.records, records
+                    ),
+                    details: format!("Prefix {} holds fewer records than requested", prefix),
+                    duration_ms,
+                    data: Vec::new(),
+                }
+            } else {
+                CheckResult::Pass {
+                    message: format!(
+                        "Input pipeline: {:.0} examples/sec, {:.1} MB/s{} over {} records",
+                        examples_per_sec, mb_per_sec, vs_expected, result.records
+                    ),
+                    duration_ms,
+                    data: Vec::new(),
+                }
+            }
         }
+        Err(e) => CheckResult::Skip {
+            reason: format!("Input pipeline test unavailable: {}", e),
+        },
     }
 }
 
 // Helper functions
 
+/// Expected GCS read throughput for this host: the per-vCPU baseline for
+/// its TPU type and topology times the vCPU count
+fn expected_gcs_mbps() -> Option<f64> {
+    let tpu_type = tpu::get_tpu_type().ok()?;
+    let shape = tpu::get_tpu_topology().map(|t| t.shape).unwrap_or_default();
+    let per_vcpu = baselines::BaselineDb::load().lookup(&tpu_type, &shape)?.gcs_mbps_per_vcpu;
+    let vcpus = linux::get_cpu_info().ok()?.cores;
+    Some(per_vcpu * vcpus as f64)
+}
+
+/// Outcome of streaming records through the input pipeline
+struct PipelineMeasurement {
+    records: u64,
+    bytes: u64,
+    elapsed_secs: f64,
+}
+
+fn run_pipeline_benchmark(prefix: &str, records: u64) -> Result<PipelineMeasurement, String> {
+    // Read TFRecords from GCS with tf.data and transfer each batch to the device
+    let script = r#"
+import sys
+import time
+
+try:
+    import tensorflow as tf
+except ImportError:
+    print("NO_LOADER")
+    sys.exit(0)
+import jax
+import numpy as np
+
+tf.config.set_visible_devices([], "GPU")
+prefix, limit = sys.argv[1], int(sys.argv[2])
+pattern = prefix if "*" in prefix else prefix.rstrip("/") + "/*"
+files = tf.io.gfile.glob(pattern)
+
+records = 0
+total_bytes = 0
+start = time.time()
+if files:
+    ds = tf.data.TFRecordDataset(files, num_parallel_reads=tf.data.AUTOTUNE)
+    ds = ds.take(limit).batch(32).prefetch(tf.data.AUTOTUNE)
+    for batch in ds.as_numpy_iterator():
+        raw = b"".join(batch)
+        jax.device_put(np.frombuffer(raw, dtype=np.uint8)).block_until_ready()
+        records += len(batch)
+        total_bytes += len(raw)
+elapsed = time.time() - start
+print(f"{records} {total_bytes} {elapsed:.3f}")
+"#;
+
+    match std::process::Command::new("python3")
+        .args(["-c", script, prefix, &records.to_string()])
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if stdout == "NO_LOADER" {
+                return Err("TensorFlow (tf.data) not installed".to_string());
+            }
+            let fields: Vec<&str> = stdout.split_whitespace().collect();
+            match (
+                fields.first().and_then(|f| f.parse::<u64>().ok()),
+                fields.get(1).and_then(|f| f.parse::<u64>().ok()),
+                fields.get(2).and_then(|f| f.parse::<f64>().ok()),
+            ) {
+                (Some(records), Some(bytes), Some(elapsed_secs)) => Ok(PipelineMeasurement {
+                    records,
+                    bytes,
+                    elapsed_secs,
+                }),
+                _ => Err("Could not parse pipeline output".to_string()),
+            }
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("No module named 'jax'") {
+                Err("JAX not installed".to_string())
+            } else {
+                Err(format!(
+                    "Pipeline failed: {}",
+                    stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("unknown error")
+                ))
+            }
+        }
+        Err(e) => Err(format!("Could not run Python: {}", e)),
+    }
+}
+
+/// Execute IO-008: Bucket Co-location
+///
+/// Optional: only runs when `TPU_PREFLIGHT_GCS_PREFIX` names the training data
+/// bucket, as for IO-007.
+pub fn run_io008() -> CheckResult {
+    let start = Instant::now();
+
+    let bucket = match util::env_setting("TPU_PREFLIGHT_GCS_PREFIX") {
+        Some(prefix) => match storage::bucket_of(&prefix) {
+            Some(bucket) => bucket.to_string(),
+            None => {
+                return CheckResult::Skip {
+                    reason: format!("TPU_PREFLIGHT_GCS_PREFIX is not a gs:// URL: '{}'", prefix),
+                }
+            }
+        },
+        None => {
+            return CheckResult::Skip {
+                reason: "TPU_PREFLIGHT_GCS_PREFIX environment variable not set".to_string(),
+            };
+        }
+    };
+    let zone = match gcp::get_zone() {
+        Ok(zone) => zone,
+        Err(e) => {
+            return CheckResult::Skip {
+                reason: format!("TPU zone unavailable: {}", e),
+            }
+        }
+    };
+    match storage::get_bucket(&bucket) {
+        Ok(info) => evaluate_colocation(&info, &zone, start.elapsed().as_millis() as u64),
+        Err(e) => CheckResult::Skip {
+            reason: format!("Could not read bucket gs://{} metadata: {}", bucket, e),
+        },
+    }
+}
+
+/// Storage classes whose retrieval latency and per-read fees make them
+/// unsuitable for training reads
+const COLD_STORAGE_CLASSES: &[&str] = &["COLDLINE", "ARCHIVE"];
+
+/// Continent of a GCP region (`us-central2`), zone, dual-region (`NAM4`),
+/// or multi-region (`US`)
+fn continent(location: &str) -> Option<&'static str> {
+    let location = location.to_lowercase();
+    let prefixes: &[(&str, &str)] = &[
+        ("northamerica", "North America"),
+        ("southamerica", "South America"),
+        ("us", "North America"),
+        ("nam", "North America"),
+        ("europe", "Europe"),
+        ("eur", "Europe"),
+        ("eu", "Europe"),
+        ("asia", "Asia"),
+        ("australia", "Oceania"),
+        ("me", "Middle East"),
+        ("africa", "Africa"),
+    ];
+    prefixes
+        .iter()
+        .find(|(prefix, _)| location.starts_with(prefix))
+        .map(|(_, continent)| *continent)
+}
+
+/// IO-008's result for bucket `info` read from a TPU in `zone`: crossing
+/// continents or a cold storage class warns
+fn evaluate_colocation(info: &BucketInfo, zone: &str, duration_ms: u64) -> CheckResult {
+    let region = zone.rsplit_once('-').map_or(zone, |(region, _)| region);
+    let location = info.location.to_lowercase();
+    let data = vec![
+        ("bucket".to_string(), format!("gs://{}", info.name)),
+        ("bucket location".to_string(), format!("{} ({})", location, info.location_type)),
+        ("storage class".to_string(), info.storage_class.to_lowercase()),
+        ("tpu zone".to_string(), zone.to_string()),
+    ];
+
+    let mut problems = Vec::new();
+    match (continent(&location), continent(region)) {
+        (Some(bucket), Some(tpu)) if bucket != tpu => problems.push(format!(
+            "Bucket in {} ({}) is on another continent than the TPU in {} ({}): every read crosses continents, \
+             adding latency and inter-continental egress charges",
+            location, bucket, zone, tpu
+        )),
+        _ => {}
+    }
+    if COLD_STORAGE_CLASSES.contains(&info.storage_class.as_str()) {
+        problems.push(format!(
+            "Default storage class {} has per-GB retrieval fees and minimum storage durations meant for data read \
+             rarely; use STANDARD for training data",
+            info.storage_class
+        ));
+    }
+
+    if !problems.is_empty() {
+        return CheckResult::Warn {
+            message: format!(
+                "Training bucket gs://{} is {}",
+                info.name,
+                match (problems.len(), COLD_STORAGE_CLASSES.contains(&info.storage_class.as_str())) {
+                    (2, _) => format!("on another continent and in {} storage", info.storage_class.to_lowercase()),
+                    (_, true) => format!("in {} storage", info.storage_class.to_lowercase()),
+                    _ => format!("in {}, on another continent than {}", location, zone),
+                }
+            ),
+            details: problems.join("\n"),
+            duration_ms,
+            data,
+        };
+    }
+
+    let placement = if location == region {
+        "same region".to_string()
+    } else {
+        format!("{} {}", location, info.location_type)
+    };
+    CheckResult::Pass {
+        message: format!("Training bucket gs://{} co-located with {} ({})", info.name, zone, placement),
+        duration_ms,
+        data,
+    }
+}
+
 fn parse_dd_throughput(stderr: &str) -> Option<f64> {
     // Parse various dd output formats:
     // Linux: "104857600 bytes (105 MB, 100 MiB) copied, 0.0831556 s, 1.3 GB/s"
@@ -474,3 +1103,375 @@ fn calculate_throughput_from_line(line: &str) -> Option<f64> {
     }
     None
 }
+
+/// Whether IO-009 could ask the registry for pull access, and its answer
+#[derive(Debug, Clone, PartialEq)]
+enum RegistryAccess {
+    /// HTTP status of the pull-token request (Google registries) or of the
+    /// v2 API root (other registries)
+    Status(u16),
+    /// The request failed
+    Error(String),
+    /// Not asked, and why
+    NotChecked(String),
+}
+
+/// What IO-009 found out about the container registry
+#[derive(Debug, Clone, PartialEq)]
+struct RegistryProbe {
+    registry: RegistryRef,
+    /// Addresses the host resolved to, or the DNS error
+    resolved: Result<Vec<String>, String>,
+    /// TCP connection latency to port 443 in ms, or why it failed; None
+    /// when the host did not resolve
+    connect: Option<Result<u64, String>>,
+    access: RegistryAccess,
+    /// The service account pulls authenticate as, for Google registries
+    service_account: Option<String>,
+}
+
+/// Execute IO-009: Container Registry Access
+///
+/// Optional: only runs when `TPU_PREFLIGHT_REGISTRY` names the registry images
+/// are pulled from, e.g. `us-docker.pkg.dev/my-project/images`.
+pub fn run_io009() -> CheckResult {
+    let start = Instant::now();
+
+    let Some(value) = util::env_setting("TPU_PREFLIGHT_REGISTRY") else {
+        return CheckResult::Skip {
+            reason: "TPU_PREFLIGHT_REGISTRY environment variable not set".to_string(),
+        };
+    };
+    let Some(registry) = RegistryRef::parse(&value) else {
+        return CheckResult::Skip {
+            reason: format!("TPU_PREFLIGHT_REGISTRY is not a registry host or repository: '{}'", value),
+        };
+    };
+
+    let resolved = network::check_dns_resolution(&registry.host)
+        .map(|dns| dns.addresses)
+        .map_err(|e| e.to_string());
+    let connect = resolved.is_ok().then(|| {
+        match network::check_tcp_connectivity(&registry.host, 443, timeouts::net_timeout_ms()) {
+            Ok(result) if result.success => Ok(result.latency_ms),
+            Ok(_) => Err("TCP connection to port 443 failed".to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+    });
+    let connected = matches!(connect, Some(Ok(_)));
+    let service_account = registry.is_google().then(|| gcp::get_service_account().ok()).flatten();
+    let access = if !connected {
+        RegistryAccess::NotChecked("not connected".to_string())
+    } else if !cfg!(feature = "ai") {
+        RegistryAccess::NotChecked("HTTPS support not built in; rebuild with --features ai".to_string())
+    } else if registry.is_google() {
+        match gcp::get_access_token() {
+            Ok(token) => registry::pull_token_status(&registry, &token)
+                .map_or_else(|e| RegistryAccess::Error(e.to_string()), RegistryAccess::Status),
+            Err(e) => RegistryAccess::NotChecked(format!("no service account token: {}", e)),
+        }
+    } else {
+        registry::v2_status(&registry).map_or_else(|e| RegistryAccess::Error(e.to_string()), RegistryAccess::Status)
+    };
+
+    let probe = RegistryProbe {
+        registry,
+        resolved,
+        connect,
+        access,
+        service_account,
+    };
+    evaluate_registry_access(&probe, start.elapsed().as_millis() as u64)
+}
+
+/// IO-009's result for `probe`: a host that does not resolve or connect,
+/// or a service account refused a pull token, fails; a registry answering
+/// unexpectedly warns
+fn evaluate_registry_access(probe: &RegistryProbe, duration_ms: u64) -> CheckResult {
+    let registry = &probe.registry;
+    let mut data = vec![("registry".to_string(), registry.to_string())];
+    if let Ok(addresses) = &probe.resolved {
+        data.push(("resolved".to_string(), addresses.iter().take(3).cloned().collect::<Vec<_>>().join(", ")));
+    }
+    if let Some(Ok(latency_ms)) = &probe.connect {
+        data.push(("connect latency".to_string(), format!("{}ms", latency_ms)));
+    }
+    let auth = match (&probe.service_account, registry.is_google()) {
+        (Some(account), true) => account.clone(),
+        (None, true) => "service account".to_string(),
+        (_, false) => "anonymous".to_string(),
+    };
+    data.push(("authentication".to_string(), auth.clone()));
+
+    if let Err(e) = &probe.resolved {
+        return CheckResult::Fail {
+            message: format!("Cannot resolve registry host {}", registry.host),
+            details: format!(
+                "{}\nImage pulls fail until the host resolves. On a VPC without internet access, *.pkg.dev and \
+                 gcr.io must resolve to private.googleapis.com through a private DNS zone (Private Google Access).",
+                e
+            ),
+            duration_ms,
+            data,
+        };
+    }
+    let latency_ms = match &probe.connect {
+        Some(Ok(latency_ms)) => *latency_ms,
+        Some(Err(e)) => {
+            return CheckResult::Fail {
+                message: format!("Cannot connect to registry {}:443", registry.host),
+                details: format!(
+                    "{}\nAllow egress to tcp:443 in the VPC firewall, and give nodes without external IPs Cloud NAT \
+                     or Private Google Access.",
+                    e
+                ),
+                duration_ms,
+                data,
+            };
+        }
+        None => 0,
+    };
+
+    match &probe.access {
+        RegistryAccess::NotChecked(reason) => CheckResult::Pass {
+            message: format!(
+                "Registry {} reachable ({}ms); pull access not checked ({})",
+                registry.host, latency_ms, reason
+            ),
+            duration_ms,
+            data,
+        },
+        RegistryAccess::Error(e) => CheckResult::Warn {
+            message: format!("Could not verify pull access to {}", registry),
+            details: e.clone(),
+            duration_ms,
+            data,
+        },
+        RegistryAccess::Status(status) => {
+            data.push(("http status".to_string(), status.to_string()));
+            match (registry.is_google(), status) {
+                (true, 200) => CheckResult::Pass {
+                    message: format!("Pull access to {} OK as {} ({}ms)", registry, auth, latency_ms),
+                    duration_ms,
+                    data,
+                },
+                (true, 401 | 403) => CheckResult::Fail {
+                    message: format!("{} cannot pull from {}", auth, registry),
+                    details: format!(
+                        "Grant the service account Artifact Registry Reader on the repository: gcloud artifacts \
+                         repositories add-iam-policy-binding REPOSITORY --location=LOCATION \
+                         --member=serviceAccount:{} --role=roles/artifactregistry.reader\n\
+                         The VM also needs an access scope that covers it (cloud-platform or devstorage.read_only).",
+                        probe.service_account.as_deref().unwrap_or("SERVICE_ACCOUNT")
+                    ),
+                    duration_ms,
+                    data,
+                },
+                (false, 200 | 401) => CheckResult::Pass {
+                    message: format!(
+                        "Registry {} reachable ({}ms), v2 API answered HTTP {}",
+                        registry.host, latency_ms, status
+                    ),
+                    duration_ms,
+                    data,
+                },
+                _ => CheckResult::Warn {
+                    message: format!("Registry {} answered HTTP {}", registry.host, status),
+                    details: "A working registry answers the v2 API with 200, or 401 asking for a login; the host may \
+                              not be a container registry, or may be failing or behind a proxy"
+                        .to_string(),
+                    duration_ms,
+                    data,
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(name: &str, warn_ms: u64, fail_ms: Option<u64>, required: bool) -> LatencyEndpoint {
+        LatencyEndpoint {
+            name: name.to_string(),
+            host: format!("{}.example.com", name),
+            port: 443,
+            warn_ms,
+            fail_ms,
+            required,
+        }
+    }
+
+    fn bucket(location: &str, location_type: &str, storage_class: &str) -> BucketInfo {
+        BucketInfo {
+            name: "ckpts".to_string(),
+            location: location.to_string(),
+            location_type: location_type.to_string(),
+            storage_class: storage_class.to_string(),
+            retention_period_secs: None,
+            soft_delete_secs: None,
+        }
+    }
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn test_parse_checkpoint_size() {
+        assert_eq!(parse_checkpoint_size("250GB"), Ok(250 * GIB));
+        assert_eq!(parse_checkpoint_size("1.5tb"), Ok(1536 * GIB));
+        assert_eq!(parse_checkpoint_size(" 512 MB "), Ok(GIB / 2));
+        for invalid in ["250", "0GB", "-1GB", "GB", "1.5PB", "nanGB"] {
+            assert!(parse_checkpoint_size(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_evaluate_checkpoint_space() {
+        let plan = CheckpointPlan {
+            size_bytes: 250 * GIB,
+            keep: 5,
+        };
+        assert_eq!(plan.footprint_bytes(), 1500 * GIB);
+
+        let result = evaluate_checkpoint_space(2000 * GIB, Some(&plan), 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. }
+            if message == "Checkpoint directory OK, 2000.0 GB available for 1500.0 GB projected"));
+        assert_eq!(result.data()[2], ("projected footprint".to_string(), "1500.0 GB".to_string()));
+
+        // Room for the kept checkpoints but not the one being written
+        let result = evaluate_checkpoint_space(1300 * GIB, Some(&plan), 0);
+        assert!(matches!(&result, CheckResult::Fail { message, details, .. }
+            if message == "Checkpoint directory too small: 1500.0 GB projected, 1300.0 GB available"
+                && details.starts_with("5 kept checkpoints plus the one being written, 250.0 GB each")));
+
+        // Without a plan the fixed recommendation applies
+        assert!(matches!(evaluate_checkpoint_space(50 * GIB, None, 0), CheckResult::Warn { .. }));
+        assert!(matches!(evaluate_checkpoint_space(150 * GIB, None, 0), CheckResult::Pass { .. }));
+    }
+
+    #[test]
+    fn test_evaluate_checkpoint_bucket() {
+        let plan = CheckpointPlan {
+            size_bytes: 250 * GIB,
+            keep: 5,
+        };
+        let mut info = bucket("US-CENTRAL2", "region", "STANDARD");
+        info.soft_delete_secs = Some(7 * 86400);
+        let result = evaluate_checkpoint_bucket(&info, Some(&plan), 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. }
+            if message.ends_with("1500.0 GB projected (deleted checkpoints billed for 7d by soft delete)")));
+
+        info.retention_period_secs = Some(30 * 86400);
+        let result = evaluate_checkpoint_bucket(&info, Some(&plan), 0);
+        assert!(matches!(&result, CheckResult::Warn { message, .. }
+            if message.starts_with("Checkpoint bucket gs://ckpts retains objects for 30d; keeping 5 checkpoint(s)")));
+        assert!(result.data().contains(&("retention policy".to_string(), "30d".to_string())));
+
+        let result = evaluate_checkpoint_bucket(&info, None, 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. }
+            if message == "Checkpoint bucket gs://ckpts accessible"));
+    }
+
+    #[test]
+    fn test_evaluate_colocation() {
+        let result = evaluate_colocation(&bucket("US-CENTRAL2", "region", "STANDARD"), "us-central2-b", 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. }
+            if message == "Training bucket gs://ckpts co-located with us-central2-b (same region)"));
+        assert_eq!(result.data()[1], ("bucket location".to_string(), "us-central2 (region)".to_string()));
+
+        // Multi- and dual-regions on the TPU's continent are fine
+        let result = evaluate_colocation(&bucket("US", "multi-region", "STANDARD"), "us-east5-a", 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. } if message.ends_with("(us multi-region)")));
+        let result = evaluate_colocation(&bucket("EUR4", "dual-region", "STANDARD"), "europe-west4-a", 0);
+        assert!(matches!(result, CheckResult::Pass { .. }));
+
+        let result = evaluate_colocation(&bucket("EU", "multi-region", "STANDARD"), "us-central2-b", 0);
+        assert!(matches!(&result, CheckResult::Warn { message, details, .. }
+            if message == "Training bucket gs://ckpts is in eu, on another continent than us-central2-b"
+                && details.contains("(Europe) is on another continent than the TPU in us-central2-b (North America)")));
+
+        let result = evaluate_colocation(&bucket("US-CENTRAL2", "region", "COLDLINE"), "us-central2-b", 0);
+        assert!(matches!(&result, CheckResult::Warn { message, .. }
+            if message == "Training bucket gs://ckpts is in coldline storage"));
+        let result = evaluate_colocation(&bucket("ASIA-SOUTHEAST1", "region", "ARCHIVE"), "us-central2-b", 0);
+        assert!(matches!(&result, CheckResult::Warn { message, .. } if message.ends_with("in archive storage")));
+    }
+
+    #[test]
+    fn test_evaluate_registry_access() {
+        let gsa = "trainer@p.iam.gserviceaccount.com";
+        let probe = |registry: &str, access: RegistryAccess| RegistryProbe {
+            registry: RegistryRef::parse(registry).unwrap(),
+            resolved: Ok(vec!["199.36.153.8".to_string()]),
+            connect: Some(Ok(3)),
+            access,
+            service_account: Some(gsa.to_string()),
+        };
+        let artifact_registry = "us-docker.pkg.dev/p/images";
+
+        let result = evaluate_registry_access(&probe(artifact_registry, RegistryAccess::Status(200)), 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. }
+            if message == &format!("Pull access to us-docker.pkg.dev/p/images OK as {} (3ms)", gsa)));
+        assert_eq!(result.data()[3], ("authentication".to_string(), gsa.to_string()));
+
+        let result = evaluate_registry_access(&probe(artifact_registry, RegistryAccess::Status(403)), 0);
+        assert!(matches!(&result, CheckResult::Fail { message, details, .. }
+            if message == &format!("{} cannot pull from us-docker.pkg.dev/p/images", gsa)
+                && details.contains("--role=roles/artifactregistry.reader")));
+
+        // Other registries are asked anonymously; a login prompt means the registry works
+        let result = evaluate_registry_access(&probe("mirror.example.com", RegistryAccess::Status(401)), 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. } if message.ends_with("v2 API answered HTTP 401")));
+        let result = evaluate_registry_access(&probe("mirror.example.com", RegistryAccess::Status(404)), 0);
+        assert!(matches!(result, CheckResult::Warn { .. }));
+
+        let not_checked = RegistryAccess::NotChecked("HTTPS support not built in".to_string());
+        let result = evaluate_registry_access(&probe(artifact_registry, not_checked), 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. }
+            if message.ends_with("reachable (3ms); pull access not checked (HTTPS support not built in)")));
+
+        let mut unresolved = probe(artifact_registry, RegistryAccess::NotChecked("not connected".to_string()));
+        unresolved.resolved = Err("DNS resolution for us-docker.pkg.dev failed".to_string());
+        unresolved.connect = None;
+        let result = evaluate_registry_access(&unresolved, 0);
+        assert!(matches!(&result, CheckResult::Fail { message, .. }
+            if message == "Cannot resolve registry host us-docker.pkg.dev"));
+
+        let mut blocked = probe(artifact_registry, RegistryAccess::NotChecked("not connected".to_string()));
+        blocked.connect = Some(Err("TCP connection to port 443 failed".to_string()));
+        let result = evaluate_registry_access(&blocked, 0);
+        assert!(matches!(&result, CheckResult::Fail { message, .. }
+            if message == "Cannot connect to registry us-docker.pkg.dev:443"));
+    }
+
+    #[test]
+    fn test_evaluate_latencies() {
+        let endpoints = vec![endpoint("storage", 10, None, false), endpoint("mirror", 50, Some(200), true)];
+
+        let result = evaluate_latencies(&endpoints, &[Ok(4), Ok(40)], 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. } if message == "Network latency OK, max 40ms"));
+        assert_eq!(result.data()[1], ("mirror".to_string(), "40ms".to_string()));
+
+        // Each endpoint has its own thresholds
+        let result = evaluate_latencies(&endpoints, &[Ok(25), Ok(40)], 0);
+        assert!(matches!(&result, CheckResult::Warn { details, .. } if details == "storage: 25ms (warns above 10ms)"));
+        let result = evaluate_latencies(&endpoints, &[Ok(4), Ok(250)], 0);
+        assert!(matches!(&result, CheckResult::Fail { details, .. } if details == "mirror: 250ms (fails above 200ms)"));
+
+        // An unreachable endpoint warns unless it is required
+        let result = evaluate_latencies(&endpoints, &[Err("timed out".to_string()), Ok(40)], 0);
+        assert!(matches!(&result, CheckResult::Warn { message, .. } if message == "1 service(s) unreachable"));
+        assert_eq!(result.data()[0], ("storage".to_string(), "unreachable".to_string()));
+        let result = evaluate_latencies(&endpoints, &[Ok(4), Err("connection failed".to_string())], 0);
+        assert!(matches!(&result, CheckResult::Fail { details, .. }
+            if details == "required service unreachable: mirror (mirror.example.com:443) - connection failed"));
+    }
+
+    #[test]
+    fn test_default_latency_endpoints() {
+        let endpoints = default_latency_endpoints();
+        assert_eq!(endpoints.len(), 3);
+        assert!(endpoints.iter().all(|e| e.warn_ms == DEFAULT_LATENCY_WARN_MS && !e.required));
+    }
+}
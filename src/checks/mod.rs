@@ -6,7 +6,16 @@
 //! - Performance: Performance baseline checks
 //! - I/O: Storage and network throughput checks
 //! - Security: Security posture checks
+//! - Settings: Per-run options the checks read (timeouts, benchmark parameters)
 //! - Config: Configuration audit checks
+//! - Benchmark: Sizes, iteration counts, and seeds for the benchmarks
+//! - Custom: Shell-command checks declared in the configuration file
+//! - Env: Value formats for the environment variables checks validate
+//! - Fix: Safe remediation for a few findings (`check --fix`)
+//! - Plugins: External executables in a plugin directory
+//! - Probe: Output capture for the subprocesses checks run
+//! - Timeouts: Limits for network probes and benchmarks
+//! - Wasm: Sandboxed WebAssembly plugins (`wasm-plugins` feature)
 //!
 //! # Graceful Degradation
 //!
@@ -20,14 +29,24 @@
 //! Checks never panic. All error conditions are converted to appropriate
 //! CheckResult variants for the caller to handle.
 
+pub mod benchmark;
 pub mod config;
+pub mod custom;
+pub mod env;
+pub mod fix;
 pub mod hardware;
 pub mod io;
 pub mod performance;
+pub mod plugins;
+pub mod probe;
 pub mod security;
+pub mod settings;
 pub mod stack;
+pub mod timeouts;
+pub mod wasm;
 
-use crate::{Check, CheckCategory};
+use crate::data::known_issues::{self, KnownIssueDb};
+use crate::{Check, CheckCategory, CheckResult};
 
 /// Get all registered checks
 pub fn get_all_checks() -> Vec<Check> {
@@ -52,3 +71,32 @@ pub fn get_checks_by_category(category: CheckCategory) -> Vec<Check> {
         CheckCategory::Config => config::get_config_checks(),
     }
 }
+
+/// Downgrade a version check's result to a warning when the detected
+/// `version` of `package` has entries in the known-issues database.
+/// Failures and skips are returned unchanged.
+pub(crate) fn with_known_issues(package: &str, version: &str, result: CheckResult) -> CheckResult {
+    let db = KnownIssueDb::load();
+    let issues = db.find(package, version);
+    if issues.is_empty() {
+        return result;
+    }
+
+    let ids: Vec<&str> = issues.iter().map(|i| i.id.as_str()).collect();
+    let issue_details = known_issues::format_check_details(&issues);
+    match result {
+        CheckResult::Pass { message, duration_ms, data } => CheckResult::Warn {
+            message: format!("{} (known issues: {})", message, ids.join(", ")),
+            details: issue_details,
+            duration_ms,
+            data,
+        },
+        CheckResult::Warn { message, details, duration_ms, data } => CheckResult::Warn {
+            message: format!("{} (known issues: {})", message, ids.join(", ")),
+            details: format!("{}\n{}", details, issue_details),
+            duration_ms,
+            data,
+        },
+        other => other,
+    }
+}
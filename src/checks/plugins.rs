@@ -0,0 +1,571 @@
+//! External executable plugin checks.
+//!
+//! Executables in a plugin directory (`/etc/tpu-preflight/checks.d` and
+//! `~/.config/tpu-preflight/checks.d`, or the directories in
+//! `TPU_PREFLIGHT_PLUGIN_DIR`) are loaded as checks at startup. The contract
+//! is JSON on stdout:
+//!
+//! - `<plugin> --metadata` prints
+//!   `{"id": "ORG-010", "name": "...", "category": "io", "description": "...",
+//!   "dependencies": ["IO-003"], "timeout_ms": 10000}`
+//!   (`description`, `dependencies`, and `timeout_ms` are optional)
+//! - `<plugin>` runs the check and prints
+//!   `{"status": "pass|warn|fail|skip", "message": "...", "details": "...",
+//!   "data": {"quota": 2}}`
+//!   (`data` is optional; its values must be strings, numbers, or booleans)
+//!
+//! A plugin that does not print JSON when run is judged Nagios-style by its
+//! exit code (0 pass, 1 warn, 2 fail, 3 skip), with the first line of
+//! stdout as the message.
+//!
+//! `.wasm` files in the same directories are sandboxed WebAssembly plugins
+//! using the same JSON schemas (see [`super::wasm`]). They need the
+//! `wasm-plugins` feature.
+
+use super::wasm;
+use crate::cli::config::{expand_home, parse_category};
+use crate::engine::orchestrator::RegisteredCheck;
+use crate::platform::linux;
+use crate::util::json::{self, JsonValue};
+use crate::util;
+use crate::{CheckCategory, CheckResult, CheckSeverity, TpuDocError};
+use std::fs;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+
+/// Environment variable overriding the plugin directories (colon-separated)
+pub const PLUGIN_DIR_ENV: &str = "TPU_PREFLIGHT_PLUGIN_DIR";
+
+/// Plugin directories searched when `TPU_PREFLIGHT_PLUGIN_DIR` is not set
+pub const DEFAULT_PLUGIN_DIRS: &[&str] = &["/etc/tpu-preflight/checks.d", "~/.config/tpu-preflight/checks.d"];
+
+/// Plugin directories renamed to `tpu-preflight`, as (directory, deprecated
+/// directory); the deprecated one is searched when the new one does not exist
+pub const DEPRECATED_PLUGIN_DIRS: [(&str, &str); 2] = [
+    ("/etc/tpu-preflight/checks.d", "/etc/tpu-doc/checks.d"),
+    ("~/.config/tpu-preflight/checks.d", "~/.config/tpu-doc/checks.d"),
+];
+
+/// Time a plugin has to print its metadata
+const METADATA_TIMEOUT_MS: u64 = 5000;
+
+/// Default time a plugin has to run its check
+pub const DEFAULT_PLUGIN_TIMEOUT_MS: u64 = 30000;
+
+/// How a plugin is run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluginKind {
+    /// An executable run as a child process
+    Executable,
+    /// A WebAssembly module run in the sandbox
+    Wasm,
+}
+
+/// A check provided by a plugin executable or WebAssembly module
+#[derive(Debug, Clone)]
+pub struct PluginCheck {
+    pub path: PathBuf,
+    pub kind: PluginKind,
+    pub id: String,
+    pub name: String,
+    pub category: CheckCategory,
+    pub description: String,
+    pub dependencies: Vec<String>,
+    pub timeout_ms: u64,
+}
+
+/// Plugins found in the plugin directories, and those that failed to load
+#[derive(Debug, Default)]
+pub struct PluginLoad {
+    pub plugins: Vec<PluginCheck>,
+    /// One message per executable that was ignored
+    pub errors: Vec<String>,
+}
+
+/// The plugin directories to search
+pub fn plugin_dirs() -> Vec<PathBuf> {
+    match util::env_setting(PLUGIN_DIR_ENV) {
+        Some(dirs) => dirs
+            .split(':')
+            .filter(|d| !d.trim().is_empty())
+            .map(|d| expand_home(d.trim()))
+            .collect(),
+        None => DEFAULT_PLUGIN_DIRS
+            .iter()
+            .map(|dir| match DEPRECATED_PLUGIN_DIRS.iter().find(|(current, _)| current == dir) {
+                Some((_, deprecated)) => resolve_plugin_dir(&expand_home(dir), &expand_home(deprecated)),
+                None => expand_home(dir),
+            })
+            .collect(),
+    }
+}
+
+/// `current`, or `deprecated` when only the deprecated directory exists
+fn resolve_plugin_dir(current: &Path, deprecated: &Path) -> PathBuf {
+    if !current.exists() && deprecated.is_dir() {
+        deprecated.to_path_buf()
+    } else {
+        current.to_path_buf()
+    }
+}
+
+/// A deprecation warning for each of `dirs` that is a deprecated plugin directory
+pub fn deprecated_plugin_dir_warnings(dirs: &[PathBuf]) -> Vec<String> {
+    DEPRECATED_PLUGIN_DIRS
+        .iter()
+        .filter(|(_, deprecated)| dirs.contains(&expand_home(deprecated)))
+        .map(|(current, deprecated)| format!("plugin directory {} is deprecated; use {} instead", deprecated, current))
+        .collect()
+}
+
+/// Load every plugin in `dirs`.
+///
+/// Executables and `.wasm` modules are loaded in name order; hidden files
+/// and other non-executables are ignored. A plugin whose ID is in `taken_ids`, is a built-in ID, or
+/// was already loaded is rejected. A plugin, or a whole directory, that is
+/// not owned by root or the current user, or is writable by group or
+/// others, is skipped rather than run.
+pub fn discover(dirs: &[PathBuf], taken_ids: &[String]) -> PluginLoad {
+    let builtin_ids: Vec<String> = crate::engine::orchestrator::create_all_checks()
+        .into_iter()
+        .map(|c| c.id)
+        .collect();
+    let mut load = PluginLoad::default();
+
+    for dir in dirs {
+        if !dir.is_dir() {
+            continue;
+        }
+        if let Err(reason) = check_ownership(dir) {
+            load.errors.push(format!("{}: directory {}", dir.display(), reason));
+            continue;
+        }
+        let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+            Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+            Err(_) => continue,
+        };
+        paths.sort();
+
+        for path in paths {
+            let load_fn: fn(&Path) -> Result<PluginCheck, String> = if is_wasm_module(&path) {
+                load_wasm_plugin
+            } else if is_plugin_executable(&path) {
+                load_plugin
+            } else {
+                continue;
+            };
+            // Ownership is checked before the plugin is run for its metadata
+            let loaded = check_ownership(&path).and_then(|_| load_fn(&path));
+            let plugin = match loaded {
+                Ok(plugin) => plugin,
+                Err(e) => {
+                    load.errors.push(format!("{}: {}", path.display(), e));
+                    continue;
+                }
+            };
+
+            let duplicate = builtin_ids
+                .iter()
+                .chain(taken_ids)
+                .chain(load.plugins.iter().map(|p| &p.id))
+                .any(|id| id.eq_ignore_ascii_case(&plugin.id));
+            if duplicate {
+                load.errors
+                    .push(format!("{}: check ID '{}' is already in use", path.display(), plugin.id));
+            } else {
+                load.plugins.push(plugin);
+            }
+        }
+    }
+
+    load
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_none_or(|name| name.to_string_lossy().starts_with('.'))
+}
+
+fn is_plugin_executable(path: &Path) -> bool {
+    !is_hidden(path)
+        && fs::metadata(path)
+            .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+}
+
+/// Reject a plugin or plugin directory that someone other than root or the
+/// current user could have written
+fn check_ownership(path: &Path) -> Result<(), String> {
+    let metadata = fs::metadata(path).map_err(|e| format!("cannot be inspected: {}", e))?;
+    let owner = metadata.uid();
+    if owner != 0 && Some(owner) != current_uid() {
+        return Err(format!("is owned by uid {}, not root or the current user", owner));
+    }
+    if metadata.mode() & 0o022 != 0 {
+        return Err(format!("is writable by group or others (mode {:o})", metadata.mode() & 0o777));
+    }
+    Ok(())
+}
+
+/// The effective user ID of this process (the owner of `/proc/self`)
+fn current_uid() -> Option<u32> {
+    fs::metadata("/proc/self").map(|m| m.uid()).ok()
+}
+
+fn is_wasm_module(path: &Path) -> bool {
+    !is_hidden(path) && path.extension().is_some_and(|ext| ext == "wasm") && path.is_file()
+}
+
+/// Run `<plugin> --metadata` and parse its description of the check
+pub fn load_plugin(path: &Path) -> Result<PluginCheck, String> {
+    let output = linux::run_command_with_timeout(Command::new(path).arg("--metadata"), METADATA_TIMEOUT_MS)
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!("--metadata exited with {}", output.status));
+    }
+    let metadata = json::parse(String::from_utf8_lossy(&output.stdout).trim())
+        .map_err(|e| format!("--metadata did not print JSON: {}", e))?;
+    parse_metadata(&metadata, path, PluginKind::Executable)
+}
+
+/// Call a WebAssembly module's metadata export and parse its description of the check
+pub fn load_wasm_plugin(path: &Path) -> Result<PluginCheck, String> {
+    if !wasm::is_enabled() {
+        return Err("WebAssembly plugins not enabled. Build with: cargo build --features wasm-plugins".to_string());
+    }
+    let text = wasm::call_json_export(path, wasm::METADATA_EXPORT)?;
+    let metadata = json::parse(text.trim())
+        .map_err(|e| format!("{} did not return JSON: {}", wasm::METADATA_EXPORT, e))?;
+    parse_metadata(&metadata, path, PluginKind::Wasm)
+}
+
+fn parse_metadata(metadata: &JsonValue, path: &Path, kind: PluginKind) -> Result<PluginCheck, String> {
+    let string = |key: &str| metadata.get(key).and_then(|v| v.as_str()).map(|s| s.trim().to_string());
+    let id = string("id")
+        .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .ok_or("metadata needs an \"id\" of letters, digits, '-' and '_'")?;
+    let category_name = string("category").ok_or("metadata needs a \"category\"")?;
+    let category = parse_category(&category_name).ok_or_else(|| format!("unknown category '{}'", category_name))?;
+
+    let timeout_ms = match metadata.get("timeout_ms") {
+        None => DEFAULT_PLUGIN_TIMEOUT_MS,
+        Some(value) => match value.as_f64() {
+            Some(ms) if ms >= 1.0 => ms as u64,
+            _ => return Err("\"timeout_ms\" must be a positive number".to_string()),
+        },
+    };
+
+    let dependencies = match metadata.get("dependencies") {
+        None => Vec::new(),
+        Some(value) => value
+            .as_array()
+            .and_then(|deps| deps.iter().map(|d| d.as_str().map(str::to_string)).collect())
+            .ok_or("\"dependencies\" must be an array of check IDs")?,
+    };
+
+    Ok(PluginCheck {
+        path: path.to_path_buf(),
+        kind,
+        name: string("name").filter(|n| !n.is_empty()).unwrap_or_else(|| id.clone()),
+        description: string("description").unwrap_or_default(),
+        id,
+        category,
+        dependencies,
+        timeout_ms,
+    })
+}
+
+/// Registered checks for loaded plugins
+pub fn create_plugin_checks(plugins: &[PluginCheck]) -> Vec<RegisteredCheck> {
+    plugins
+        .iter()
+        .map(|plugin| {
+            let owned = plugin.clone();
+            RegisteredCheck {
+                id: plugin.id.clone(),
+                name: plugin.name.clone(),
+                category: plugin.category.clone(),
+                description: if plugin.description.is_empty() {
+                    format!("Plugin check: {}", plugin.path.display())
+                } else {
+                    plugin.description.clone()
+                },
+                check_fn: Box::new(move || run_plugin_check(&owned)),
+                dependencies: plugin.dependencies.clone(),
+                estimated_duration_ms: plugin.timeout_ms.min(5000),
+                tags: vec![],
+                severity: CheckSeverity::Major,
+                ownership: Default::default(),
+            }
+        })
+        .collect()
+}
+
+/// Execute a plugin check
+pub fn run_plugin_check(plugin: &PluginCheck) -> CheckResult {
+    if plugin.kind == PluginKind::Wasm {
+        return run_wasm_plugin_check(plugin);
+    }
+    let start = Instant::now();
+
+    let output = match linux::run_command_with_timeout(&mut Command::new(&plugin.path), plugin.timeout_ms) {
+        Ok(output) => output,
+        Err(TpuDocError::Timeout { timeout_ms, .. }) => {
+            return CheckResult::Fail {
+                message: format!("Plugin timed out after {}ms", timeout_ms),
+                details: format!("Plugin: {}", plugin.path.display()),
+                duration_ms: start.elapsed().as_millis() as u64,
+                data: Vec::new(),
+            };
+        }
+        Err(e) => {
+            return CheckResult::Fail {
+                message: "Could not run plugin".to_string(),
+                details: format!("Plugin: {}\n{}", plugin.path.display(), e),
+                duration_ms: start.elapsed().as_millis() as u64,
+                data: Vec::new(),
+            };
+        }
+    };
+    let duration_ms = start.elapsed().as_millis() as u64;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    match json::parse(stdout.trim()) {
+        Ok(result) => parse_plugin_result(&result, duration_ms).unwrap_or_else(|e| CheckResult::Fail {
+            message: "Plugin printed an invalid result".to_string(),
+            details: format!("Plugin: {}\n{}", plugin.path.display(), e),
+            duration_ms,
+            data: Vec::new(),
+        }),
+        Err(_) => {
+            // Not JSON: fall back to Nagios exit-code conventions
+            let message = stdout
+                .lines()
+                .chain(stderr.lines())
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .unwrap_or("")
+                .to_string();
+            let details = stderr.trim().to_string();
+            match output.status.code() {
+                Some(0) => CheckResult::Pass {
+                    message: if message.is_empty() { "Plugin passed".to_string() } else { message },
+                    duration_ms,
+                    data: Vec::new(),
+                },
+                Some(1) => CheckResult::Warn { message, details, duration_ms, data: Vec::new() },
+                Some(3) => CheckResult::Skip {
+                    reason: if message.is_empty() { "Plugin reported UNKNOWN".to_string() } else { message },
+                },
+                code => CheckResult::Fail {
+                    message: if message.is_empty() {
+                        match code {
+                            Some(code) => format!("Plugin exited with status {}", code),
+                            None => "Plugin was terminated by a signal".to_string(),
+                        }
+                    } else {
+                        message
+                    },
+                    details,
+                    duration_ms,
+                    data: Vec::new(),
+                },
+            }
+        }
+    }
+}
+
+/// Execute a WebAssembly plugin check in the sandbox
+fn run_wasm_plugin_check(plugin: &PluginCheck) -> CheckResult {
+    let start = Instant::now();
+    let output = wasm::call_json_export(&plugin.path, wasm::RUN_EXPORT);
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    let result = output.and_then(|text| {
+        json::parse(text.trim()).map_err(|e| format!("{} did not return JSON: {}", wasm::RUN_EXPORT, e))
+    });
+    match result.and_then(|result| parse_plugin_result(&result, duration_ms)) {
+        Ok(result) => result,
+        Err(e) => CheckResult::Fail {
+            message: "WebAssembly plugin failed".to_string(),
+            details: format!("Plugin: {}\n{}", plugin.path.display(), e),
+            duration_ms,
+            data: Vec::new(),
+        },
+    }
+}
+
+/// Convert a plugin's JSON result into a CheckResult
+fn parse_plugin_result(result: &JsonValue, duration_ms: u64) -> Result<CheckResult, String> {
+    let field = |key: &str| result.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let status = result
+        .get("status")
+        .and_then(|v| v.as_str())
+        .ok_or("result needs a \"status\"")?;
+    let message = field("message");
+    let details = field("details");
+    let data = match result.get("data") {
+        None | Some(JsonValue::Null) => Vec::new(),
+        Some(value) => {
+            let members = value.as_object().ok_or("result \"data\" must be an object")?;
+            members
+                .iter()
+                .map(|(key, value)| {
+                    value
+                        .to_scalar_string()
+                        .map(|value| (key.clone(), value))
+                        .ok_or_else(|| format!("result \"data.{}\" must be a string, number, or boolean", key))
+                })
+                .collect::<Result<Vec<_>, String>>()?
+        }
+    };
+
+    match status.to_lowercase().as_str() {
+        "pass" => Ok(CheckResult::Pass { message, duration_ms, data }),
+        "warn" => Ok(CheckResult::Warn { message, details, duration_ms, data }),
+        "fail" => Ok(CheckResult::Fail { message, details, duration_ms, data }),
+        "skip" => Ok(CheckResult::Skip { reason: message }),
+        other => Err(format!("unknown status '{}' (expected pass, warn, fail, or skip)", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_plugin(dir: &Path, name: &str, script: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_discover_and_run_plugins() {
+        let dir = std::env::temp_dir().join(format!("tpu-doc-plugins-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+
+        write_plugin(
+            &dir,
+            "10-quota",
+            r#"if [ "$1" = "--metadata" ]; then
+  echo '{"id": "ORG-010", "name": "Quota", "category": "config", "dependencies": ["CFG-001"]}'
+else
+  echo '{"status": "warn", "message": "quota low", "details": "2 chips left"}'
+fi"#,
+        );
+        write_plugin(
+            &dir,
+            "20-nagios",
+            r#"if [ "$1" = "--metadata" ]; then echo '{"id": "ORG-020", "category": "io"}'; exit 0; fi
+echo "CRITICAL - mount missing"; exit 2"#,
+        );
+        write_plugin(&dir, "30-taken", r#"echo '{"id": "HW-001", "category": "hardware"}'"#);
+        write_plugin(&dir, "40-broken", "echo not json");
+        fs::write(dir.join("50-not-executable"), "#!/bin/sh\n").unwrap();
+
+        let load = discover(std::slice::from_ref(&dir), &[]);
+        let ids: Vec<&str> = load.plugins.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["ORG-010", "ORG-020"]);
+        assert_eq!(load.errors.len(), 2);
+        assert_eq!(load.plugins[0].dependencies, vec!["CFG-001"]);
+        assert_eq!(load.plugins[1].name, "ORG-020");
+
+        match run_plugin_check(&load.plugins[0]) {
+            CheckResult::Warn { message, details, .. } => {
+                assert_eq!(message, "quota low");
+                assert_eq!(details, "2 chips left");
+            }
+            other => panic!("expected warn, got {:?}", other),
+        }
+        assert!(matches!(
+            run_plugin_check(&load.plugins[1]),
+            CheckResult::Fail { message, .. } if message == "CRITICAL - mount missing"
+        ));
+
+        let taken = discover(std::slice::from_ref(&dir), &["org-010".to_string()]);
+        assert_eq!(taken.plugins.len(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_deprecated_plugin_dir_alias() {
+        let root = std::env::temp_dir().join(format!("tpu-doc-plugin-dirs-{}", std::process::id()));
+        let (current, deprecated) = (root.join("tpu-preflight"), root.join("tpu-doc"));
+        let _ = fs::remove_dir_all(&root);
+
+        // Neither exists: the new directory is the one searched
+        assert_eq!(resolve_plugin_dir(&current, &deprecated), current);
+        fs::create_dir_all(&deprecated).unwrap();
+        assert_eq!(resolve_plugin_dir(&current, &deprecated), deprecated);
+        fs::create_dir_all(&current).unwrap();
+        assert_eq!(resolve_plugin_dir(&current, &deprecated), current);
+
+        assert_eq!(
+            deprecated_plugin_dir_warnings(&[PathBuf::from("/etc/tpu-doc/checks.d")]),
+            vec!["plugin directory /etc/tpu-doc/checks.d is deprecated; use /etc/tpu-preflight/checks.d instead"]
+        );
+        assert!(deprecated_plugin_dir_warnings(&[PathBuf::from("/etc/tpu-preflight/checks.d")]).is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_discover_skips_writable_plugins() {
+        let dir = std::env::temp_dir().join(format!("tpu-doc-plugins-writable-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        let marker = dir.join("ran");
+
+        write_plugin(&dir, "10-ok", r#"echo '{"id": "ORG-010", "category": "config"}'"#);
+        let writable = write_plugin(&dir, "20-writable", &format!("touch {}", marker.display()));
+        fs::set_permissions(&writable, fs::Permissions::from_mode(0o777)).unwrap();
+
+        let load = discover(std::slice::from_ref(&dir), &[]);
+        assert_eq!(load.plugins.len(), 1);
+        assert_eq!(load.errors.len(), 1);
+        assert!(load.errors[0].contains("20-writable: is writable by group or others (mode 777)"));
+        assert!(!marker.exists(), "a writable plugin must not be run");
+
+        // A group-writable directory is skipped as a whole
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o775)).unwrap();
+        let load = discover(std::slice::from_ref(&dir), &[]);
+        assert!(load.plugins.is_empty());
+        assert_eq!(load.errors.len(), 1);
+        assert!(load.errors[0].contains("directory is writable by group or others (mode 775)"));
+
+        assert!(check_ownership(Path::new("/")).is_ok());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_plugin_result() {
+        let result = json::parse(r#"{"status": "SKIP", "message": "not applicable"}"#).unwrap();
+        assert!(matches!(
+            parse_plugin_result(&result, 5),
+            Ok(CheckResult::Skip { reason }) if reason == "not applicable"
+        ));
+        let bad = json::parse(r#"{"status": "ok"}"#).unwrap();
+        assert!(parse_plugin_result(&bad, 5).is_err());
+
+        let result = json::parse(r#"{"status": "pass", "data": {"quota": 2, "region": "us-central2", "ok": true}}"#).unwrap();
+        let parsed = parse_plugin_result(&result, 5).unwrap();
+        assert_eq!(
+            parsed.data(),
+            &[
+                ("quota".to_string(), "2".to_string()),
+                ("region".to_string(), "us-central2".to_string()),
+                ("ok".to_string(), "true".to_string()),
+            ]
+        );
+        let nested = json::parse(r#"{"status": "pass", "data": {"chips": [1, 2]}}"#).unwrap();
+        assert!(parse_plugin_result(&nested, 5).unwrap_err().contains("data.chips"));
+    }
+}
@@ -0,0 +1,113 @@
+//! Output capture for subprocess probes.
+//!
+//! Performance benchmarks and stack version queries run Python in a child
+//! process. A failure is normally summarized by the first line of stderr;
+//! when output capture is enabled (`check --verbose`), the child's full
+//! stdout and stderr are attached as well, capped at
+//! [`MAX_CAPTURED_BYTES`] per stream, so a saved JSON report carries the
+//! traceback that explains the failure.
+
+use crate::checks::settings;
+use std::process::Output;
+
+/// Most output kept from each stream. The end of the output is kept, since
+/// that is where Python puts the exception.
+pub const MAX_CAPTURED_BYTES: usize = 8 * 1024;
+
+/// Whether probe output is attached to failures in this run
+pub fn capture_output() -> bool {
+    settings::current().capture_output
+}
+
+/// `summary` of a failed probe, followed by its captured output when
+/// capture is enabled
+pub fn describe_failure(summary: String, output: &Output) -> String {
+    if !capture_output() {
+        return summary;
+    }
+    match captured(output) {
+        Some(captured) => format!("{}\n{}", summary, captured),
+        None => summary,
+    }
+}
+
+/// The child's stdout and stderr, each capped, or None if both are empty
+pub fn captured(output: &Output) -> Option<String> {
+    let sections: Vec<String> = [("stdout", &output.stdout), ("stderr", &output.stderr)]
+        .into_iter()
+        .filter_map(|(name, bytes)| {
+            let text = String::from_utf8_lossy(bytes);
+            let text = text.trim_end();
+            (!text.is_empty()).then(|| format!("--- {} ---\n{}", name, tail(text, MAX_CAPTURED_BYTES)))
+        })
+        .collect();
+    (!sections.is_empty()).then(|| sections.join("\n"))
+}
+
+/// The last `max_bytes` of `text`, noting how much was dropped
+fn tail(text: &str, max_bytes: usize) -> String {
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+    let mut start = text.len() - max_bytes;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("[... {} bytes omitted]\n{}", start, &text[start..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+
+    fn output(stdout: &str, stderr: &str) -> Output {
+        Output {
+            status: ExitStatus::from_raw(1 << 8),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: stderr.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_captured_output() {
+        assert_eq!(
+            captured(&output("step 1\n", "Traceback:\nRuntimeError: TPU busy\n")).unwrap(),
+            "--- stdout ---\nstep 1\n--- stderr ---\nTraceback:\nRuntimeError: TPU busy"
+        );
+        assert_eq!(captured(&output("", "oops")).unwrap(), "--- stderr ---\noops");
+        assert!(captured(&output("", "  \n")).is_none());
+
+        let long = format!("{}RuntimeError: end", "x".repeat(MAX_CAPTURED_BYTES));
+        let capped = captured(&output("", &long)).unwrap();
+        assert!(capped.contains("[... 17 bytes omitted]"));
+        assert!(capped.ends_with("RuntimeError: end"));
+    }
+
+    #[test]
+    fn test_describe_failure_from_real_probe() {
+        let output = std::process::Command::new("sh")
+            .args(["-c", "echo partial; echo 'ImportError: libtpu.so' >&2; exit 1"])
+            .output()
+            .unwrap();
+
+        let capture = settings::RunSettings {
+            capture_output: true,
+            ..Default::default()
+        };
+        let described = capture.scope(|| describe_failure("Benchmark failed: ImportError".to_string(), &output));
+
+        assert_eq!(
+            described,
+            "Benchmark failed: ImportError\n--- stdout ---\npartial\n--- stderr ---\nImportError: libtpu.so"
+        );
+        assert_eq!(describe_failure("Benchmark failed".to_string(), &output), "Benchmark failed");
+    }
+
+    #[test]
+    fn test_tail_respects_char_boundaries() {
+        assert_eq!(tail("ééé", 3), "[... 4 bytes omitted]\né");
+        assert_eq!(tail("short", 10), "short");
+    }
+}
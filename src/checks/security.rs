@@ -1,12 +1,23 @@
 //! Security posture validation checks.
 //!
 //! Checks for service account permissions, network exposure, workload identity,
-//! encryption status, metadata access, SSH key management, and firewall rules.
+//! encryption status, metadata access, SSH key management, firewall rules,
+//! SSH opened to the internet by firewall rules, and egress control.
 
+use crate::checks::{settings, timeouts};
+use crate::platform::compute::{self, AttachedDisk, CloudNat, DiskKey, FirewallRule, FirewallTarget};
+use crate::platform::gcloud::DetectionMethod;
+use crate::platform::kubernetes::{self, ServiceAccount};
 use crate::platform::{gcp, network};
 use crate::{Check, CheckCategory, CheckResult};
 use std::time::Instant;
 
+/// Whether SEC-009 warns about egress that is open to the internet or not
+/// logged, for fleets whose policy requires locked-down egress
+pub fn require_locked_egress() -> bool {
+    settings::current().require_locked_egress
+}
+
 /// Get all security checks
 pub fn get_security_checks() -> Vec<Check> {
     vec![
@@ -17,6 +28,8 @@ pub fn get_security_checks() -> Vec<Check> {
         create_sec005_check(),
         create_sec006_check(),
         create_sec007_check(),
+        create_sec008_check(),
+        create_sec009_check(),
     ]
 }
 
@@ -28,6 +41,8 @@ fn create_sec001_check() -> Check {
         category: CheckCategory::Security,
         description: "Identify service account and check for overly permissive roles".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -39,6 +54,8 @@ fn create_sec002_check() -> Check {
         category: CheckCategory::Security,
         description: "Check for services listening on all interfaces".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -48,8 +65,10 @@ fn create_sec003_check() -> Check {
         id: "SEC-003".to_string(),
         name: "Workload Identity Status".to_string(),
         category: CheckCategory::Security,
-        description: "Check if workload identity is configured".to_string(),
+        description: "Check workload identity, and in GKE pods the KSA to GSA binding".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -59,8 +78,10 @@ fn create_sec004_check() -> Check {
         id: "SEC-004".to_string(),
         name: "Encryption Status".to_string(),
         category: CheckCategory::Security,
-        description: "Verify data encryption settings".to_string(),
+        description: "Report whether each attached disk uses a Google-managed, CMEK, or CSEK key".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -70,8 +91,10 @@ fn create_sec005_check() -> Check {
         id: "SEC-005".to_string(),
         name: "Instance Metadata Access".to_string(),
         category: CheckCategory::Security,
-        description: "Verify metadata server access configuration".to_string(),
+        description: "Verify metadata header enforcement, legacy endpoints, and token scopes".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -83,6 +106,8 @@ fn create_sec006_check() -> Check {
         category: CheckCategory::Security,
         description: "Check for OS Login vs legacy SSH keys".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -94,6 +119,34 @@ fn create_sec007_check() -> Check {
         category: CheckCategory::Security,
         description: "Provide guidance on firewall configuration".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
+    }
+}
+
+/// SEC-008: SSH Open to the Internet
+fn create_sec008_check() -> Check {
+    Check {
+        id: "SEC-008".to_string(),
+        name: "SSH Open to the Internet".to_string(),
+        category: CheckCategory::Security,
+        description: "Check firewall rules targeting this instance for tcp:22 allowed from 0.0.0.0/0".to_string(),
+        result: None,
+        start_ms: None,
+        ownership: Default::default(),
+    }
+}
+
+/// SEC-009: Egress Control
+fn create_sec009_check() -> Check {
+    Check {
+        id: "SEC-009".to_string(),
+        name: "Egress Control".to_string(),
+        category: CheckCategory::Security,
+        description: "Check for default-allow egress to the internet and Cloud NAT logging".to_string(),
+        result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -123,17 +176,20 @@ pub fn run_sec001() -> CheckResult {
                             message: format!("Service account {} has broad scopes", sa),
                             details: "Consider using more restrictive scopes".to_string(),
                             duration_ms,
+                            data: Vec::new(),
                         }
                     } else {
                         CheckResult::Pass {
                             message: format!("Service account: {}", sa),
                             duration_ms,
+                            data: Vec::new(),
                         }
                     }
                 }
                 Err(_) => CheckResult::Pass {
                     message: format!("Service account: {} (scopes not checked)", sa),
                     duration_ms,
+                    data: Vec::new(),
                 },
             }
         }
@@ -143,23 +199,87 @@ pub fn run_sec001() -> CheckResult {
     }
 }
 
+/// Ports whose services are commonly exposed by accident
+const CONCERNING_PORTS: &[u16] = &[22, 80, 443, 8080, 8888, 3389, 5432, 3306, 6379, 27017];
+
+/// Source range of IAP TCP forwarding, the usual way to reach SSH without
+/// opening it to the internet
+const IAP_SOURCE_RANGE: &str = "35.235.240.0/20";
+
+/// What SEC-002 found out about the host's exposure
+#[derive(Debug, Clone, PartialEq)]
+struct ExposureProbe {
+    /// Ports with a socket listening on all interfaces
+    listening: Vec<u16>,
+    /// External IPs attached to the instance (empty off GCP)
+    external: Vec<gcp::ExternalAccess>,
+    /// Instance name and zone, for the remediation commands
+    instance: Option<(String, String)>,
+    /// Whether this is a TPU VM, whose external IP cannot be removed in place
+    tpu_vm: bool,
+}
+
 /// Execute SEC-002: Network Exposure
 pub fn run_sec002() -> CheckResult {
     let start = Instant::now();
 
     // Check for services listening on 0.0.0.0
-    let exposed_ports = check_exposed_ports();
-    let duration_ms = start.elapsed().as_millis() as u64;
+    let listening = check_exposed_ports();
+
+    // Only look up access configs when there is something to expose
+    let on_gcp = !listening.is_empty() && gcp::is_on_gcp();
+    let external = if on_gcp {
+        gcp::get_external_access().unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let exposed = !external.is_empty();
+    let probe = ExposureProbe {
+        instance: exposed
+            .then(|| gcp::get_instance_name().ok().zip(gcp::get_zone().ok()))
+            .flatten(),
+        tpu_vm: exposed && matches!(gcp::get_instance_attribute("accelerator-type"), Ok(Some(_))),
+        listening,
+        external,
+    };
+    evaluate_exposure(&probe, start.elapsed().as_millis() as u64)
+}
+
+/// SEC-002's result for `probe`: services on all interfaces of a VM with an
+/// external IP fail with the commands that close the exposure, concerning
+/// ports on all interfaces otherwise warn
+fn evaluate_exposure(probe: &ExposureProbe, duration_ms: u64) -> CheckResult {
+    let port_list = |ports: &[u16]| ports.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ");
+    let mut data = vec![("listening on all interfaces".to_string(), port_list(&probe.listening))];
+    if !probe.external.is_empty() {
+        let ips: Vec<String> = probe
+            .external
+            .iter()
+            .map(|access| format!("{} ({})", access.external_ip, access.interface))
+            .collect();
+        data.push(("external ip".to_string(), ips.join(", ")));
+    }
+
+    if !probe.external.is_empty() && !probe.listening.is_empty() {
+        data.push(("severity".to_string(), "high".to_string()));
+        return CheckResult::Fail {
+            message: format!(
+                "External IP {} with {} port(s) listening on all interfaces: {}",
+                probe.external[0].external_ip,
+                probe.listening.len(),
+                port_list(&probe.listening)
+            ),
+            details: exposure_remediation(probe).join("\n"),
+            duration_ms,
+            data,
+        };
+    }
 
-    // Common ports that might indicate exposure issues
-    let concerning_ports: Vec<_> = exposed_ports
+    let concerning_ports: Vec<u16> = probe
+        .listening
         .iter()
-        .filter(|p| {
-            matches!(
-                **p,
-                22 | 80 | 443 | 8080 | 8888 | 3389 | 5432 | 3306 | 6379 | 27017
-            )
-        })
+        .copied()
+        .filter(|p| CONCERNING_PORTS.contains(p))
         .collect();
 
     if !concerning_ports.is_empty() {
@@ -167,30 +287,92 @@ pub fn run_sec002() -> CheckResult {
             message: format!(
                 "{} potentially exposed port(s): {}",
                 concerning_ports.len(),
-                concerning_ports
-                    .iter()
-                    .map(|p| p.to_string())
-                    .collect::<Vec<_>>()
-                    .join(", ")
+                port_list(&concerning_ports)
             ),
             details: "Services bound to 0.0.0.0 are accessible from any interface".to_string(),
             duration_ms,
+            data,
         }
-    } else if !exposed_ports.is_empty() {
+    } else if !probe.listening.is_empty() {
         CheckResult::Pass {
-            message: format!("{} port(s) listening on all interfaces (none concerning)", exposed_ports.len()),
+            message: format!("{} port(s) listening on all interfaces (none concerning)", probe.listening.len()),
             duration_ms,
+            data,
         }
     } else {
         CheckResult::Pass {
             message: "No services exposed on all interfaces".to_string(),
             duration_ms,
+            data,
+        }
+    }
+}
+
+/// The commands that remove `probe`'s external IP or close its listening
+/// ports at the firewall, one per line
+fn exposure_remediation(probe: &ExposureProbe) -> Vec<String> {
+    let (name, zone) = match &probe.instance {
+        Some((name, zone)) => (name.as_str(), zone.as_str()),
+        None => ("INSTANCE", "ZONE"),
+    };
+    let mut lines =
+        vec!["Services listening on 0.0.0.0 are reachable from the internet wherever the firewall allows it.".to_string()];
+
+    if probe.tpu_vm {
+        lines.push(
+            "TPU VM external IPs cannot be removed in place; recreate the TPU with \
+             gcloud compute tpus tpu-vm create ... --internal-ips"
+                .to_string(),
+        );
+    } else {
+        for access in &probe.external {
+            lines.push(format!(
+                "Remove the external IP: gcloud compute instances delete-access-config {} --zone={} \
+                 --network-interface={} --access-config-name=\"external-nat\"",
+                name, zone, access.interface
+            ));
         }
     }
+
+    // Denying SSH from everywhere would also cut off IAP, so SSH is narrowed
+    // to the IAP range instead
+    let others: Vec<String> = probe
+        .listening
+        .iter()
+        .filter(|&&p| p != 22)
+        .map(|p| format!("tcp:{}", p))
+        .collect();
+    let network = probe
+        .external
+        .first()
+        .map(|access| access.network.as_str())
+        .filter(|network| !network.is_empty())
+        .unwrap_or("NETWORK");
+    if !others.is_empty() {
+        lines.push(format!(
+            "Or block the ports at the firewall: gcloud compute firewall-rules create deny-public-listeners \
+             --network={} --direction=INGRESS --action=DENY --rules={} --source-ranges=0.0.0.0/0 --priority=900",
+            network,
+            others.join(",")
+        ));
+    }
+    if probe.listening.contains(&22) {
+        lines.push(format!(
+            "Restrict SSH to IAP: gcloud compute firewall-rules update default-allow-ssh --source-ranges={}",
+            IAP_SOURCE_RANGE
+        ));
+    }
+    lines
 }
 
 /// Execute SEC-003: Workload Identity Status
 pub fn run_sec003() -> CheckResult {
+    run_sec003_with(None)
+}
+
+/// Execute SEC-003, in a GKE pod also checking that the pod's Kubernetes
+/// service account is bound to Google service account `expected`
+pub fn run_sec003_with(expected: Option<&str>) -> CheckResult {
     let start = Instant::now();
 
     if !gcp::is_on_gcp() {
@@ -199,6 +381,21 @@ pub fn run_sec003() -> CheckResult {
         };
     }
 
+    if kubernetes::in_pod() {
+        // The node's own metadata server serves kube-env; GKE's Workload
+        // Identity metadata server conceals it
+        let probe = WorkloadIdentityProbe {
+            ksa: kubernetes::pod_service_account(),
+            node_metadata: matches!(gcp::get_instance_attribute("kube-env"), Ok(Some(_))),
+            identity: gcp::get_service_account()
+                .and_then(|email| gcp::get_access_token().map(|_| email))
+                .map_err(|e| e.to_string()),
+            project: gcp::get_project_id().ok(),
+            expected: expected.map(str::to_string),
+        };
+        return evaluate_workload_identity(&probe, start.elapsed().as_millis() as u64);
+    }
+
     // Check for workload identity indicators
     // Workload identity uses the metadata server differently
     match gcp::get_instance_attribute("gke-cluster-name") {
@@ -207,6 +404,7 @@ pub fn run_sec003() -> CheckResult {
             CheckResult::Pass {
                 message: "Running in GKE with potential workload identity".to_string(),
                 duration_ms,
+                data: Vec::new(),
             }
         }
         _ => {
@@ -218,10 +416,12 @@ pub fn run_sec003() -> CheckResult {
                     message: "Using default Compute Engine service account".to_string(),
                     details: "Consider using a custom service account with minimal permissions".to_string(),
                     duration_ms,
+                    data: Vec::new(),
                 },
                 Ok(sa) => CheckResult::Pass {
                     message: format!("Using custom service account: {}", sa),
                     duration_ms,
+                    data: Vec::new(),
                 },
                 Err(_) => CheckResult::Skip {
                     reason: "Could not determine service account configuration".to_string(),
@@ -231,6 +431,126 @@ pub fn run_sec003() -> CheckResult {
     }
 }
 
+/// What SEC-003 found out about a GKE pod's identity
+#[derive(Debug, Clone, PartialEq)]
+struct WorkloadIdentityProbe {
+    /// The pod's Kubernetes service account, if its token is mounted
+    ksa: Option<ServiceAccount>,
+    /// Whether the node's metadata server answers, so Workload Identity is
+    /// off and the pod acts as the node's service account
+    node_metadata: bool,
+    /// The Google identity the metadata server hands out, or why it could
+    /// not issue a token
+    identity: Result<String, String>,
+    project: Option<String>,
+    /// The Google service account the pod should be bound to
+    expected: Option<String>,
+}
+
+/// Suffix of the identity a Kubernetes service account bound to no Google
+/// service account gets from the workload identity pool
+const WORKLOAD_POOL_SUFFIX: &str = ".svc.id.goog";
+
+/// SEC-003's result for a GKE pod: node service-account fallback warns, a
+/// token the metadata server cannot issue or a binding to other than the
+/// expected Google service account fails
+fn evaluate_workload_identity(probe: &WorkloadIdentityProbe, duration_ms: u64) -> CheckResult {
+    let ksa = probe.ksa.as_ref().map(ServiceAccount::to_string);
+    let ksa_label = ksa.as_deref().unwrap_or("the pod's service account");
+    let (namespace, name) = match &probe.ksa {
+        Some(account) => (account.namespace.as_str(), account.name.as_str()),
+        None => ("NAMESPACE", "KSA"),
+    };
+    let project = probe.project.as_deref().unwrap_or("PROJECT");
+    let expected = probe.expected.as_deref();
+
+    let mut data = vec![
+        ("kubernetes service account".to_string(), ksa.clone().unwrap_or_else(|| "unknown".to_string())),
+        (
+            "metadata server".to_string(),
+            if probe.node_metadata { "node" } else { "GKE Workload Identity" }.to_string(),
+        ),
+    ];
+    if let Ok(email) = &probe.identity {
+        data.push(("google service account".to_string(), email.clone()));
+    }
+    if let Some(expected) = expected {
+        data.push(("expected service account".to_string(), expected.to_string()));
+    }
+
+    let annotate = |gsa: &str| {
+        format!(
+            "Bind it: kubectl annotate serviceaccount {} --namespace {} iam.gke.io/gcp-service-account={}\n\
+             Allow it: gcloud iam service-accounts add-iam-policy-binding {} --role roles/iam.workloadIdentityUser \
+             --member \"serviceAccount:{}{}[{}/{}]\"",
+            name, namespace, gsa, gsa, project, WORKLOAD_POOL_SUFFIX, namespace, name
+        )
+    };
+
+    if probe.node_metadata {
+        return CheckResult::Warn {
+            message: format!(
+                "Pod {} falls back to the node's service account{}",
+                ksa_label,
+                probe.identity.as_ref().map(|email| format!(" {}", email)).unwrap_or_default()
+            ),
+            details: format!(
+                "Workload Identity is off for this node pool, so every pod on the node acts as the node's service \
+                 account and can read kube-env. Enable it: gcloud container clusters update CLUSTER \
+                 --workload-pool={}{} and gcloud container node-pools update POOL --cluster=CLUSTER \
+                 --workload-metadata=GKE_METADATA",
+                project, WORKLOAD_POOL_SUFFIX
+            ),
+            duration_ms,
+            data,
+        };
+    }
+
+    let email = match &probe.identity {
+        Ok(email) => email,
+        Err(e) => {
+            return CheckResult::Fail {
+                message: format!("Workload Identity cannot issue a token for {}", ksa_label),
+                details: format!(
+                    "{}\nThe Kubernetes service account is usually bound to a Google service account it may not \
+                     impersonate.\n{}",
+                    e,
+                    annotate(expected.unwrap_or("GSA"))
+                ),
+                duration_ms,
+                data,
+            };
+        }
+    };
+
+    let federated = email.ends_with(WORKLOAD_POOL_SUFFIX);
+    match expected {
+        Some(expected) if email != expected => CheckResult::Fail {
+            message: if federated {
+                format!("{} is not bound to a Google service account (expected {})", ksa_label, expected)
+            } else {
+                format!("{} is bound to {}, expected {}", ksa_label, email, expected)
+            },
+            details: annotate(expected),
+            duration_ms,
+            data,
+        },
+        _ if federated => CheckResult::Pass {
+            message: format!(
+                "Workload Identity active; {} authenticates as itself (no Google service account)",
+                ksa_label
+            ),
+            duration_ms,
+            data,
+        },
+        _ => CheckResult::Pass {
+            message: format!("Workload Identity active; {} bound to {}", ksa_label, email),
+            duration_ms,
+            data,
+        },
+    }
+}
+
 /// Execute SEC-004: Encryption Status
 pub fn run_sec004() -> CheckResult {
     let start = Instant::now();
@@ -241,17 +561,132 @@ pub fn run_sec004() -> CheckResult {
         };
     }
 
-    // GCP encrypts data at rest by default
-    // Check for CMEK indicators
-    let duration_ms = start.elapsed().as_millis() as u64;
+    match attached_disks() {
+        Ok((disks, method)) => evaluate_disk_encryption(&disks, method, start.elapsed().as_millis() as u64),
+        Err(e) => CheckResult::Skip {
+            reason: format!("Could not read disk encryption keys: {}", e),
+        },
+    }
+}
+
+/// The disks attached to this VM with their encryption keys, from the
+/// Cloud TPU API on a TPU VM and the Compute API otherwise
+fn attached_disks() -> Result<(Vec<AttachedDisk>, DetectionMethod), crate::TpuDocError> {
+    let project = gcp::get_project_id()?;
+    let zone = gcp::get_zone()?;
+    match gcp::get_tpu_node_name()? {
+        Some(node) => compute::list_tpu_node_disks(&project, &zone, &node),
+        None => compute::list_instance_disks(&project, &zone, &gcp::get_instance_name()?),
+    }
+}
+
+/// SEC-004's result for `disks`, read with `method`: each disk's key
+/// management in the data, and a warning when some persistent disks use
+/// customer-managed keys and others do not
+fn evaluate_disk_encryption(disks: &[AttachedDisk], method: DetectionMethod, duration_ms: u64) -> CheckResult {
+    let mut data: Vec<(String, String)> = disks
+        .iter()
+        .map(|disk| {
+            let label = if disk.boot { format!("disk {} (boot)", disk.name) } else { format!("disk {}", disk.name) };
+            let key = if disk.local { "local SSD, Google-managed".to_string() } else { disk.key.to_string() };
+            (label, key)
+        })
+        .collect();
+    data.push(("detection method".to_string(), method.to_string()));
 
-    // Informational check - GCP always encrypts at rest
+    // Local SSDs cannot use customer-managed keys, so they are left out
+    let persistent: Vec<&AttachedDisk> = disks.iter().filter(|disk| !disk.local).collect();
+    if persistent.is_empty() {
+        return CheckResult::Pass {
+            message: format!("No persistent disks; {} local SSD(s) use Google-managed keys", disks.len()),
+            duration_ms,
+            data,
+        };
+    }
+
+    let google_managed: Vec<&str> = persistent
+        .iter()
+        .filter(|disk| !disk.key.customer_managed())
+        .map(|disk| disk.name.as_str())
+        .collect();
+    if !google_managed.is_empty() && google_managed.len() < persistent.len() {
+        let example_key = persistent
+            .iter()
+            .find_map(|disk| match &disk.key {
+                DiskKey::Cmek(key) => Some(key.as_str()),
+                _ => None,
+            })
+            .unwrap_or("KEY");
+        return CheckResult::Warn {
+            message: format!(
+                "{} of {} persistent disk(s) use Google-managed keys while the rest use customer-managed keys: {}",
+                google_managed.len(),
+                persistent.len(),
+                google_managed.join(", ")
+            ),
+            details: format!(
+                "Data on these disks is outside the customer key's rotation, access control, and revocation. \
+                 Recreate each from a snapshot with the key: gcloud compute disks create DISK \
+                 --source-snapshot=SNAPSHOT --kms-key={}",
+                example_key
+            ),
+            duration_ms,
+            data,
+        };
+    }
+
+    let cmek = persistent.iter().filter(|disk| matches!(disk.key, DiskKey::Cmek(_))).count();
+    let csek = persistent.iter().filter(|disk| disk.key == DiskKey::Csek).count();
+    let kinds: Vec<String> = [(cmek, "CMEK"), (csek, "CSEK"), (google_managed.len(), "Google-managed")]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, kind)| format!("{} {}", n, kind))
+        .collect();
     CheckResult::Pass {
-        message: "GCP default encryption at rest enabled".to_string(),
+        message: format!("{} persistent disk(s) encrypted at rest: {}", persistent.len(), kinds.join(", ")),
         duration_ms,
+        data,
     }
 }
 
+/// Metadata root probed without the `Metadata-Flavor: Google` header
+const METADATA_V1_URL: &str = "http://metadata.google.internal/computeMetadata/v1/";
+
+/// Legacy metadata endpoints, which answer without the `Metadata-Flavor`
+/// header unless the `disable-legacy-endpoints` attribute is set
+const LEGACY_METADATA_URLS: &[(&str, &str)] = &[
+    ("v0.1", "http://metadata.google.internal/computeMetadata/v0.1/"),
+    ("v1beta1", "http://metadata.google.internal/computeMetadata/v1beta1/"),
+];
+
+/// Prefix of OAuth scope URLs, dropped when reporting scopes
+const SCOPE_PREFIX: &str = "https://www.googleapis.com/auth/";
+
+/// Scopes a TPU training workload needs: reading datasets and writing
+/// checkpoints to Cloud Storage, and shipping logs, metrics, and traces
+const WORKLOAD_SCOPES: &[&str] = &[
+    "devstorage.read_only",
+    "devstorage.read_write",
+    "logging.write",
+    "monitoring",
+    "monitoring.write",
+    "trace.append",
+    "servicecontrol",
+    "service.management.readonly",
+    "userinfo.email",
+];
+
+/// What SEC-005 found out about the metadata server
+#[derive(Debug, Clone, PartialEq)]
+struct MetadataProbe {
+    /// Status of a request to the v1 root without the `Metadata-Flavor` header
+    headerless_status: u16,
+    /// Legacy endpoints that served a request, by version
+    legacy_enabled: Vec<String>,
+    /// Access scopes of the default service account's token, if readable
+    scopes: Option<Vec<String>>,
+}
+
 /// Execute SEC-005: Instance Metadata Access
 pub fn run_sec005() -> CheckResult {
     let start = Instant::now();
@@ -262,29 +697,110 @@ pub fn run_sec005() -> CheckResult {
         };
     }
 
-    // Try to access metadata server
-    match network::check_http_endpoint("http://metadata.google.internal/computeMetadata/v1/", 5000) {
-        Ok(result) => {
-            let duration_ms = start.elapsed().as_millis() as u64;
+    let timeout_ms = timeouts::net_timeout_ms();
+    let headerless_status = match network::check_http_endpoint(METADATA_V1_URL, timeout_ms) {
+        Ok(result) => result.status_code,
+        Err(e) => {
+            return CheckResult::Skip {
+                reason: format!("Could not check metadata access: {}", e),
+            }
+        }
+    };
 
-            // A 403 might indicate metadata protection is configured
-            // A 200 (or 404 for specific paths) indicates metadata is accessible
-            if result.status_code == 403 {
-                CheckResult::Pass {
-                    message: "Metadata access requires proper headers".to_string(),
-                    duration_ms,
-                }
+    // Legacy endpoints that refuse the connection are as good as disabled
+    let legacy_enabled = LEGACY_METADATA_URLS
+        .iter()
+        .filter(|(_, url)| {
+            network::check_http_endpoint(url, timeout_ms)
+                .map(|result| result.status_code == 200)
+                .unwrap_or(false)
+        })
+        .map(|(version, _)| version.to_string())
+        .collect();
+
+    let probe = MetadataProbe {
+        headerless_status,
+        legacy_enabled,
+        scopes: gcp::get_access_scopes().ok(),
+    };
+    evaluate_metadata_access(&probe, start.elapsed().as_millis() as u64)
+}
+
+/// SEC-005's result for `probe`: an unprotected or legacy endpoint fails,
+/// token scopes broader than a training workload needs warn
+fn evaluate_metadata_access(probe: &MetadataProbe, duration_ms: u64) -> CheckResult {
+    let header_enforced = (400..500).contains(&probe.headerless_status);
+    let broad_scopes: Vec<String> = probe
+        .scopes
+        .iter()
+        .flatten()
+        .map(|scope| scope.trim_start_matches(SCOPE_PREFIX).to_string())
+        .filter(|scope| !WORKLOAD_SCOPES.contains(&scope.as_str()))
+        .collect();
+
+    let mut data = vec![
+        ("header enforced".to_string(), if header_enforced { "yes" } else { "no" }.to_string()),
+        (
+            "legacy endpoints".to_string(),
+            if probe.legacy_enabled.is_empty() {
+                "disabled".to_string()
             } else {
-                CheckResult::Warn {
-                    message: "Metadata server accessible without protection headers".to_string(),
-                    details: "Consider enabling metadata concealment".to_string(),
-                    duration_ms,
-                }
-            }
+                probe.legacy_enabled.join(", ")
+            },
+        ),
+    ];
+    if let Some(scopes) = &probe.scopes {
+        let names: Vec<&str> = scopes.iter().map(|s| s.trim_start_matches(SCOPE_PREFIX)).collect();
+        data.push(("token scopes".to_string(), names.join(", ")));
+    }
+
+    let mut failures = Vec::new();
+    if !header_enforced {
+        failures.push(format!(
+            "Request without Metadata-Flavor: Google header was answered with HTTP {}; \
+             any process or SSRF-able service on the host can read metadata and tokens",
+            probe.headerless_status
+        ));
+    }
+    if !probe.legacy_enabled.is_empty() {
+        failures.push(format!(
+            "Legacy metadata endpoints enabled ({}); they serve requests without the Metadata-Flavor header. \
+             Disable them: gcloud compute instances add-metadata INSTANCE --metadata=disable-legacy-endpoints=true",
+            probe.legacy_enabled.join(", ")
+        ));
+    }
+    if !failures.is_empty() {
+        return CheckResult::Fail {
+            message: "Metadata server accepts unprotected requests".to_string(),
+            details: failures.join("\n"),
+            duration_ms,
+            data,
+        };
+    }
+
+    if !broad_scopes.is_empty() {
+        return CheckResult::Warn {
+            message: format!(
+                "Metadata token scopes broader than a training workload needs: {}",
+                broad_scopes.join(", ")
+            ),
+            details: format!(
+                "A token stolen from the metadata server carries these scopes. A training workload needs at most: {}",
+                WORKLOAD_SCOPES.join(", ")
+            ),
+            duration_ms,
+            data,
+        };
+    }
+
+    CheckResult::Pass {
+        message: match probe.scopes {
+            Some(_) => "Metadata access requires proper headers, legacy endpoints disabled, token scopes minimal",
+            None => "Metadata access requires proper headers, legacy endpoints disabled (scopes not checked)",
         }
-        Err(e) => CheckResult::Skip {
-            reason: format!("Could not check metadata access: {}", e),
-        },
+        .to_string(),
+        duration_ms,
+        data,
     }
 }
 
@@ -305,6 +821,7 @@ pub fn run_sec006() -> CheckResult {
             CheckResult::Pass {
                 message: "OS Login enabled".to_string(),
                 duration_ms,
+                data: Vec::new(),
             }
         }
         Ok(_) => {
@@ -313,6 +830,7 @@ pub fn run_sec006() -> CheckResult {
                 message: "OS Login not enabled".to_string(),
                 details: "Consider enabling OS Login for centralized SSH key management".to_string(),
                 duration_ms,
+                data: Vec::new(),
             }
         }
         Err(_) => {
@@ -321,6 +839,7 @@ pub fn run_sec006() -> CheckResult {
                 message: "Could not determine OS Login status".to_string(),
                 details: "Unable to query instance metadata".to_string(),
                 duration_ms,
+                data: Vec::new(),
             }
         }
     }
@@ -336,6 +855,313 @@ pub fn run_sec007() -> CheckResult {
     CheckResult::Pass {
         message: "Firewall rules must be verified via GCP Console or gcloud".to_string(),
         duration_ms,
+        data: Vec::new(),
+    }
+}
+
+/// What SEC-008 looks at: the project's firewall rules and how they
+/// select this instance
+#[derive(Debug, Clone, PartialEq)]
+struct SshExposureProbe {
+    rules: Vec<FirewallRule>,
+    target: FirewallTarget,
+    /// Whether the instance has an external IP, without which an open rule
+    /// has nothing to reach yet
+    external_ip: bool,
+    /// How the firewall rules were read
+    method: DetectionMethod,
+}
+
+/// Execute SEC-008: SSH Open to the Internet
+pub fn run_sec008() -> CheckResult {
+    let start = Instant::now();
+
+    if !gcp::is_on_gcp() {
+        return CheckResult::Skip {
+            reason: "Not running on GCP".to_string(),
+        };
+    }
+
+    let (rules, method) = match gcp::get_project_id().and_then(|project| compute::list_firewall_rules(&project)) {
+        Ok(listed) => listed,
+        Err(e) => {
+            return CheckResult::Skip {
+                reason: format!("Could not list firewall rules: {}", e),
+            }
+        }
+    };
+    let probe = SshExposureProbe {
+        rules,
+        target: FirewallTarget {
+            networks: gcp::get_networks().unwrap_or_default(),
+            tags: gcp::get_network_tags().unwrap_or_default(),
+            service_account: gcp::get_service_account().ok(),
+        },
+        external_ip: !gcp::get_external_access().unwrap_or_default().is_empty(),
+        method,
+    };
+    evaluate_ssh_exposure(&probe, start.elapsed().as_millis() as u64)
+}
+
+/// SEC-008's result for `probe`: a rule opening tcp:22 to any address fails
+/// when the instance has an external IP and warns otherwise
+fn evaluate_ssh_exposure(probe: &SshExposureProbe, duration_ms: u64) -> CheckResult {
+    let open = compute::open_to_anywhere(&probe.rules, &probe.target, "tcp", 22);
+    let names: Vec<&str> = open.iter().map(|rule| rule.name.as_str()).collect();
+
+    let mut data = vec![
+        ("network tags".to_string(), probe.target.tags.join(", ")),
+        ("external ip".to_string(), if probe.external_ip { "yes" } else { "no" }.to_string()),
+        ("detection method".to_string(), probe.method.to_string()),
+    ];
+    if open.is_empty() {
+        return CheckResult::Pass {
+            message: "No firewall rule opens SSH to the internet".to_string(),
+            duration_ms,
+            data,
+        };
+    }
+    data.push(("open rules".to_string(), names.join(", ")));
+
+    let mut details: Vec<String> = open
+        .iter()
+        .map(|rule| {
+            format!(
+                "{} (network {}, priority {}, applies to {}) allows tcp:22 from {}",
+                rule.name,
+                rule.network,
+                rule.priority,
+                rule.describe_targets(),
+                if rule.source_ranges.is_empty() { "any source".to_string() } else { rule.source_ranges.join(", ") }
+            )
+        })
+        .collect();
+    for rule in &open {
+        details.push(format!(
+            "Restrict to IAP TCP forwarding: gcloud compute firewall-rules update {} --source-ranges={}",
+            rule.name, IAP_SOURCE_RANGE
+        ));
+    }
+
+    if probe.external_ip {
+        data.push(("severity".to_string(), "high".to_string()));
+        CheckResult::Fail {
+            message: format!("SSH open to the internet via firewall rule(s): {}", names.join(", ")),
+            details: details.join("\n"),
+            duration_ms,
+            data,
+        }
+    } else {
+        CheckResult::Warn {
+            message: format!(
+                "Firewall rule(s) allow SSH from anywhere: {} (no external IP, not reachable yet)",
+                names.join(", ")
+            ),
+            details: details.join("\n"),
+            duration_ms,
+            data,
+        }
+    }
+}
+
+/// What SEC-009 looks at: the egress firewall rules that apply to this
+/// instance and its routes to the internet
+#[derive(Debug, Clone, PartialEq)]
+struct EgressProbe {
+    rules: Vec<FirewallRule>,
+    target: FirewallTarget,
+    external_ip: bool,
+    /// Cloud NAT gateways on the instance's networks in its region, by
+    /// router, or None when the routers could not be listed
+    nats: Option<Vec<(String, CloudNat)>>,
+    /// Instance region, for the remediation commands
+    region: String,
+    require_locked: bool,
+    /// How the firewall rules and routers were read; `gcloud` when either
+    /// needed the fallback
+    method: DetectionMethod,
+}
+
+/// Execute SEC-009: Egress Control
+pub fn run_sec009() -> CheckResult {
+    let start = Instant::now();
+
+    if !gcp::is_on_gcp() {
+        return CheckResult::Skip {
+            reason: "Not running on GCP".to_string(),
+        };
+    }
+
+    let project = match gcp::get_project_id() {
+        Ok(project) => project,
+        Err(e) => {
+            return CheckResult::Skip {
+                reason: format!("Project ID unavailable: {}", e),
+            }
+        }
+    };
+    let (rules, rules_method) = match compute::list_firewall_rules(&project) {
+        Ok(listed) => listed,
+        Err(e) => {
+            return CheckResult::Skip {
+                reason: format!("Could not list firewall rules: {}", e),
+            }
+        }
+    };
+    let target = FirewallTarget {
+        networks: gcp::get_networks().unwrap_or_default(),
+        tags: gcp::get_network_tags().unwrap_or_default(),
+        service_account: gcp::get_service_account().ok(),
+    };
+    // Region from the zone, e.g. us-central2 for us-central2-b
+    let region = gcp::get_zone()
+        .ok()
+        .and_then(|zone| zone.rsplit_once('-').map(|(region, _)| region.to_string()))
+        .unwrap_or_default();
+    let routers = compute::list_routers(&project, &region).ok();
+    let method = match routers {
+        Some((_, DetectionMethod::Gcloud)) => DetectionMethod::Gcloud,
+        _ => rules_method,
+    };
+    let nats = routers.map(|(routers, _)| {
+        routers
+            .into_iter()
+            .filter(|router| target.networks.contains(&router.network))
+            .flat_map(|router| router.nats.into_iter().map(move |nat| (router.name.clone(), nat)))
+            .collect()
+    });
+    let probe = EgressProbe {
+        rules,
+        external_ip: !gcp::get_external_access().unwrap_or_default().is_empty(),
+        nats,
+        region,
+        target,
+        require_locked: require_locked_egress(),
+        method,
+    };
+    evaluate_egress(&probe, start.elapsed().as_millis() as u64)
+}
+
+/// SEC-009's result for `probe`. Egress is locked down when an applicable
+/// rule denies all traffic to any address ahead of any rule allowing it;
+/// without one, the VPC's implied allow-all egress rule is in effect.
+/// Default-allow egress with a route to the internet, and Cloud NAT without
+/// logging, only warn when locked-down egress is required.
+fn evaluate_egress(probe: &EgressProbe, duration_ms: u64) -> CheckResult {
+    let egress: Vec<&FirewallRule> = probe
+        .rules
+        .iter()
+        .filter(|rule| rule.direction == "EGRESS" && rule.applies_to(&probe.target) && rule.to_anywhere())
+        .collect();
+    // Deny wins over allow at the same priority
+    let deny_all = egress
+        .iter()
+        .filter(|rule| rule.denied.iter().any(|p| p.covers_all()))
+        .min_by_key(|rule| rule.priority);
+    let ahead_of_deny = |rule: &&&FirewallRule| deny_all.is_none_or(|deny| rule.priority < deny.priority);
+    let allow_all = egress
+        .iter()
+        .filter(ahead_of_deny)
+        .any(|rule| rule.allowed.iter().any(|p| p.covers_all()));
+    let locked = deny_all.is_some() && !allow_all;
+    let exceptions: Vec<&str> = egress
+        .iter()
+        .filter(ahead_of_deny)
+        .filter(|rule| !rule.allowed.is_empty())
+        .map(|rule| rule.name.as_str())
+        .collect();
+
+    let nats = probe.nats.as_deref().unwrap_or_default();
+    let unlogged: Vec<&str> = nats.iter().filter(|(_, nat)| !nat.logging).map(|(_, nat)| nat.name.as_str()).collect();
+    let route = if probe.external_ip {
+        "external IP".to_string()
+    } else if !nats.is_empty() {
+        format!("Cloud NAT {}", nats.iter().map(|(_, nat)| nat.name.as_str()).collect::<Vec<_>>().join(", "))
+    } else if probe.nats.is_some() {
+        "none".to_string()
+    } else {
+        "unknown (routers not listed)".to_string()
+    };
+
+    let mut data = vec![
+        (
+            "egress firewall".to_string(),
+            match (locked, deny_all) {
+                (true, Some(deny)) => format!("denied by {}", deny.name),
+                _ => "default allow".to_string(),
+            },
+        ),
+        ("internet route".to_string(), route.clone()),
+    ];
+    if locked && !exceptions.is_empty() {
+        data.push(("egress exceptions".to_string(), exceptions.join(", ")));
+    }
+    if !nats.is_empty() {
+        let logging = if unlogged.is_empty() { "on".to_string() } else { format!("off for {}", unlogged.join(", ")) };
+        data.push(("nat logging".to_string(), logging));
+    }
+    data.push(("detection method".to_string(), probe.method.to_string()));
+
+    let mut findings = Vec::new();
+    let mut remediation = Vec::new();
+    if !locked && route != "none" {
+        let network = probe.target.networks.first().map(String::as_str).unwrap_or("NETWORK");
+        findings.push(format!("Default-allow egress to the internet via {}", route));
+        remediation.push(format!(
+            "Deny egress by default: gcloud compute firewall-rules create deny-all-egress --network={} \
+             --direction=EGRESS --action=DENY --rules=all --destination-ranges=0.0.0.0/0 --priority=65000",
+            network
+        ));
+        remediation.push(
+            "Then allow only what training needs at a higher priority, e.g. Google APIs through \
+             Private Google Access (restricted.googleapis.com, 199.36.153.4/30)"
+                .to_string(),
+        );
+    }
+    let nat_router = |name: &str| nats.iter().find(|(_, nat)| nat.name == name).map(|(router, _)| router.as_str());
+    for name in &unlogged {
+        findings.push(format!("Cloud NAT {} does not log connections", name));
+        remediation.push(format!(
+            "Enable NAT logging: gcloud compute routers nats update {} --router={} --region={} --enable-logging",
+            name,
+            nat_router(name).unwrap_or("ROUTER"),
+            if probe.region.is_empty() { "REGION" } else { &probe.region }
+        ));
+    }
+
+    if findings.is_empty() {
+        let message = if locked {
+            match exceptions.len() {
+                0 => format!("Egress to the internet denied by firewall rule {}", deny_all.map_or("", |d| &d.name)),
+                n => format!(
+                    "Egress to the internet denied by firewall rule {} ({} exception(s))",
+                    deny_all.map_or("", |d| &d.name),
+                    n
+                ),
+            }
+        } else {
+            "No route to the internet (no external IP or Cloud NAT)".to_string()
+        };
+        return CheckResult::Pass {
+            message,
+            duration_ms,
+            data,
+        };
+    }
+
+    if probe.require_locked {
+        CheckResult::Warn {
+            message: findings.join("; "),
+            details: format!("Policy requires locked-down egress.\n{}", remediation.join("\n")),
+            duration_ms,
+            data,
+        }
+    } else {
+        CheckResult::Pass {
+            message: format!("{} (locked-down egress not required)", findings.join("; ")),
+            duration_ms,
+            data,
+        }
     }
 }
 
@@ -380,3 +1206,310 @@ fn check_exposed_ports() -> Vec<u16> {
 
     exposed
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exposure(listening: &[u16], external_ip: Option<&str>, tpu_vm: bool) -> ExposureProbe {
+        ExposureProbe {
+            listening: listening.to_vec(),
+            external: external_ip
+                .map(|ip| gcp::ExternalAccess {
+                    interface: "nic0".to_string(),
+                    network: "default".to_string(),
+                    external_ip: ip.to_string(),
+                })
+                .into_iter()
+                .collect(),
+            instance: Some(("trainer-1".to_string(), "us-central2-b".to_string())),
+            tpu_vm,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_exposure() {
+        let result = evaluate_exposure(&exposure(&[], None, false), 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. }
+            if message == "No services exposed on all interfaces"));
+        let result = evaluate_exposure(&exposure(&[9000], None, false), 0);
+        assert!(matches!(result, CheckResult::Pass { .. }));
+        let result = evaluate_exposure(&exposure(&[8888, 9000], None, false), 0);
+        assert!(matches!(&result, CheckResult::Warn { message, .. }
+            if message == "1 potentially exposed port(s): 8888"));
+
+        // An external IP makes any listener on all interfaces a finding
+        let result = evaluate_exposure(&exposure(&[22, 8888], Some("34.1.2.3"), false), 0);
+        let CheckResult::Fail { message, details, .. } = &result else {
+            panic!("expected a failure, got {:?}", result);
+        };
+        assert_eq!(message, "External IP 34.1.2.3 with 2 port(s) listening on all interfaces: 22, 8888");
+        assert!(details.contains(
+            "gcloud compute instances delete-access-config trainer-1 --zone=us-central2-b \
+             --network-interface=nic0 --access-config-name=\"external-nat\""
+        ));
+        assert!(details.contains("--network=default --direction=INGRESS --action=DENY --rules=tcp:8888 "));
+        assert!(details.contains("firewall-rules update default-allow-ssh --source-ranges=35.235.240.0/20"));
+        assert!(result.data().contains(&("severity".to_string(), "high".to_string())));
+        assert!(result.data().contains(&("external ip".to_string(), "34.1.2.3 (nic0)".to_string())));
+
+        // TPU VMs have to be recreated without an external IP
+        let result = evaluate_exposure(&exposure(&[22], Some("34.1.2.3"), true), 0);
+        assert!(matches!(&result, CheckResult::Fail { details, .. }
+            if details.contains("--internal-ips") && !details.contains("delete-access-config")
+                && !details.contains("--action=DENY")));
+    }
+
+    fn probe(headerless_status: u16, legacy_enabled: &[&str], scopes: Option<&[&str]>) -> MetadataProbe {
+        MetadataProbe {
+            headerless_status,
+            legacy_enabled: legacy_enabled.iter().map(|v| v.to_string()).collect(),
+            scopes: scopes.map(|s| s.iter().map(|scope| format!("{}{}", SCOPE_PREFIX, scope)).collect()),
+        }
+    }
+
+    fn ssh_rule(name: &str, source: &str, target_tags: &[&str]) -> FirewallRule {
+        FirewallRule {
+            name: name.to_string(),
+            network: "default".to_string(),
+            direction: "INGRESS".to_string(),
+            priority: 1000,
+            source_ranges: vec![source.to_string()],
+            source_tags: Vec::new(),
+            source_service_accounts: Vec::new(),
+            destination_ranges: Vec::new(),
+            target_tags: target_tags.iter().map(|t| t.to_string()).collect(),
+            target_service_accounts: Vec::new(),
+            allowed: vec![compute::FirewallPermission {
+                protocol: "tcp".to_string(),
+                ports: vec!["22".to_string()],
+            }],
+            denied: Vec::new(),
+            disabled: false,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_ssh_exposure() {
+        let mut probe = SshExposureProbe {
+            rules: vec![ssh_rule("iap-ssh", IAP_SOURCE_RANGE, &[]), ssh_rule("dev-ssh", "0.0.0.0/0", &["dev"])],
+            target: FirewallTarget {
+                networks: vec!["default".to_string()],
+                tags: vec!["tpu".to_string()],
+                service_account: None,
+            },
+            external_ip: true,
+            method: DetectionMethod::Api,
+        };
+        let result = evaluate_ssh_exposure(&probe, 0);
+        assert!(matches!(&result, CheckResult::Pass { .. }), "{:?}", result);
+
+        // The instance's tags bring the open rule into effect
+        probe.target.tags.push("dev".to_string());
+        let result = evaluate_ssh_exposure(&probe, 0);
+        let CheckResult::Fail { message, details, .. } = &result else {
+            panic!("expected a failure, got {:?}", result);
+        };
+        assert_eq!(message, "SSH open to the internet via firewall rule(s): dev-ssh");
+        assert!(details.starts_with("dev-ssh (network default, priority 1000, applies to tags dev) allows tcp:22"));
+        assert!(details.contains("gcloud compute firewall-rules update dev-ssh --source-ranges=35.235.240.0/20"));
+        assert!(result.data().contains(&("open rules".to_string(), "dev-ssh".to_string())));
+
+        probe.external_ip = false;
+        let result = evaluate_ssh_exposure(&probe, 0);
+        assert!(matches!(&result, CheckResult::Warn { message, .. }
+            if message.ends_with("(no external IP, not reachable yet)")));
+    }
+
+    fn egress_rule(name: &str, priority: u32, deny: bool, protocol: &str) -> FirewallRule {
+        let permission = compute::FirewallPermission {
+            protocol: protocol.to_string(),
+            ports: Vec::new(),
+        };
+        FirewallRule {
+            name: name.to_string(),
+            direction: "EGRESS".to_string(),
+            priority,
+            source_ranges: Vec::new(),
+            destination_ranges: vec!["0.0.0.0/0".to_string()],
+            allowed: if deny { Vec::new() } else { vec![permission.clone()] },
+            denied: if deny { vec![permission] } else { Vec::new() },
+            ..ssh_rule(name, "", &[])
+        }
+    }
+
+    #[test]
+    fn test_evaluate_egress() {
+        let nat = |logging| ("nat-router".to_string(), CloudNat { name: "nat-1".to_string(), logging });
+        let mut probe = EgressProbe {
+            rules: Vec::new(),
+            target: FirewallTarget {
+                networks: vec!["default".to_string()],
+                tags: Vec::new(),
+                service_account: None,
+            },
+            external_ip: false,
+            nats: Some(vec![nat(false)]),
+            region: "us-central2".to_string(),
+            require_locked: true,
+            method: DetectionMethod::Api,
+        };
+
+        // The implied allow-all rule with a NAT route, unlogged
+        let result = evaluate_egress(&probe, 0);
+        let CheckResult::Warn { message, details, .. } = &result else {
+            panic!("expected a warning, got {:?}", result);
+        };
+        assert_eq!(
+            message,
+            "Default-allow egress to the internet via Cloud NAT nat-1; Cloud NAT nat-1 does not log connections"
+        );
+        assert!(details.contains("--network=default --direction=EGRESS --action=DENY --rules=all"));
+        assert!(details.contains("gcloud compute routers nats update nat-1 --router=nat-router --region=us-central2"));
+        assert!(result.data().contains(&("nat logging".to_string(), "off for nat-1".to_string())));
+
+        // Without the policy the posture is only reported
+        probe.require_locked = false;
+        let result = evaluate_egress(&probe, 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. }
+            if message.ends_with("(locked-down egress not required)")));
+        probe.require_locked = true;
+
+        // A deny-all rule locks egress down; allow rules ahead of it are exceptions
+        probe.nats = Some(vec![nat(true)]);
+        probe.rules = vec![
+            egress_rule("deny-all-egress", 65000, true, "all"),
+            egress_rule("allow-https", 1000, false, "tcp"),
+        ];
+        let result = evaluate_egress(&probe, 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. }
+            if message == "Egress to the internet denied by firewall rule deny-all-egress (1 exception(s))"));
+        assert_eq!(result.data()[2], ("egress exceptions".to_string(), "allow-https".to_string()));
+
+        // An allow-all rule ahead of the deny reopens egress
+        probe.rules.push(egress_rule("allow-all", 100, false, "all"));
+        assert!(matches!(evaluate_egress(&probe, 0), CheckResult::Warn { .. }));
+
+        // No external IP and no NAT: nothing reaches the internet
+        probe.nats = Some(Vec::new());
+        let result = evaluate_egress(&probe, 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. }
+            if message.starts_with("No route to the internet")));
+    }
+
+    #[test]
+    fn test_evaluate_workload_identity() {
+        let gsa = "trainer@p.iam.gserviceaccount.com";
+        let probe = |node_metadata: bool, identity: Result<&str, &str>, expected: Option<&str>| WorkloadIdentityProbe {
+            ksa: Some(ServiceAccount {
+                namespace: "ml".to_string(),
+                name: "trainer".to_string(),
+            }),
+            node_metadata,
+            identity: identity.map(str::to_string).map_err(str::to_string),
+            project: Some("p".to_string()),
+            expected: expected.map(str::to_string),
+        };
+
+        let result = evaluate_workload_identity(&probe(false, Ok(gsa), Some(gsa)), 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. }
+            if message == "Workload Identity active; ml/trainer bound to trainer@p.iam.gserviceaccount.com"));
+        assert_eq!(result.data()[1], ("metadata server".to_string(), "GKE Workload Identity".to_string()));
+
+        // Bound to no Google service account: fine unless one is expected
+        let result = evaluate_workload_identity(&probe(false, Ok("p.svc.id.goog"), None), 0);
+        assert!(matches!(result, CheckResult::Pass { .. }));
+        let result = evaluate_workload_identity(&probe(false, Ok("p.svc.id.goog"), Some(gsa)), 0);
+        assert!(matches!(&result, CheckResult::Fail { message, details, .. }
+            if message == &format!("ml/trainer is not bound to a Google service account (expected {})", gsa)
+                && details.contains("kubectl annotate serviceaccount trainer --namespace ml")
+                && details.contains("--member \"serviceAccount:p.svc.id.goog[ml/trainer]\"")));
+
+        let other = "other@p.iam.gserviceaccount.com";
+        let result = evaluate_workload_identity(&probe(false, Ok(other), Some(gsa)), 0);
+        assert!(matches!(&result, CheckResult::Fail { message, .. }
+            if message.starts_with("ml/trainer is bound to other@p.iam.gserviceaccount.com, expected")));
+
+        let result = evaluate_workload_identity(&probe(false, Err("HTTP 403"), Some(gsa)), 0);
+        assert!(matches!(&result, CheckResult::Fail { message, details, .. }
+            if message == "Workload Identity cannot issue a token for ml/trainer" && details.starts_with("HTTP 403")));
+
+        let node_sa = "123-compute@developer.gserviceaccount.com";
+        let result = evaluate_workload_identity(&probe(true, Ok(node_sa), Some(gsa)), 0);
+        assert!(matches!(&result, CheckResult::Warn { message, details, .. }
+            if message == &format!("Pod ml/trainer falls back to the node's service account {}", node_sa)
+                && details.contains("--workload-metadata=GKE_METADATA")));
+    }
+
+    #[test]
+    fn test_evaluate_disk_encryption() {
+        let key = "projects/p/locations/us/keyRings/r/cryptoKeys/k";
+        let disk = |name: &str, boot: bool, key: DiskKey| AttachedDisk {
+            name: name.to_string(),
+            boot,
+            local: false,
+            key,
+        };
+        let local_ssd = AttachedDisk {
+            local: true,
+            ..disk("local-ssd-0", false, DiskKey::GoogleManaged)
+        };
+
+        let disks = [
+            disk("vm-1", true, DiskKey::Cmek(key.to_string())),
+            disk("data-1", false, DiskKey::Csek),
+            local_ssd,
+        ];
+        let result = evaluate_disk_encryption(&disks, DetectionMethod::Api, 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. }
+            if message == "2 persistent disk(s) encrypted at rest: 1 CMEK, 1 CSEK"));
+        assert_eq!(result.data()[0], ("disk vm-1 (boot)".to_string(), format!("CMEK ({})", key)));
+        assert_eq!(result.data()[2], ("disk local-ssd-0".to_string(), "local SSD, Google-managed".to_string()));
+        assert_eq!(result.data()[3], ("detection method".to_string(), "API".to_string()));
+
+        let result = evaluate_disk_encryption(&disks[..1], DetectionMethod::Gcloud, 0);
+        assert!(result.data().contains(&("detection method".to_string(), "gcloud CLI".to_string())));
+
+        let disks = [disk("vm-1", true, DiskKey::GoogleManaged), disk("data-1", false, DiskKey::GoogleManaged)];
+        let result = evaluate_disk_encryption(&disks, DetectionMethod::Api, 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. }
+            if message == "2 persistent disk(s) encrypted at rest: 2 Google-managed"));
+
+        // A data disk left on a Google-managed key next to a CMEK boot disk
+        let disks = [disk("vm-1", true, DiskKey::Cmek(key.to_string())), disk("data-1", false, DiskKey::GoogleManaged)];
+        let result = evaluate_disk_encryption(&disks, DetectionMethod::Api, 0);
+        assert!(matches!(&result, CheckResult::Warn { message, details, .. }
+            if message.starts_with("1 of 2 persistent disk(s) use Google-managed keys")
+                && message.ends_with(": data-1")
+                && details.ends_with(&format!("--kms-key={}", key))));
+    }
+
+    #[test]
+    fn test_evaluate_metadata_access() {
+        let minimal: &[&str] = &["devstorage.read_write", "logging.write", "monitoring.write"];
+        let result = evaluate_metadata_access(&probe(403, &[], Some(minimal)), 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. } if message.ends_with("token scopes minimal")));
+        assert_eq!(
+            result.data()[2],
+            ("token scopes".to_string(), "devstorage.read_write, logging.write, monitoring.write".to_string())
+        );
+
+        let result = evaluate_metadata_access(&probe(403, &[], None), 0);
+        assert!(matches!(&result, CheckResult::Pass { message, .. } if message.ends_with("(scopes not checked)")));
+
+        // Scopes beyond what training needs warn
+        let broad: &[&str] = &["cloud-platform", "devstorage.read_only", "compute"];
+        let result = evaluate_metadata_access(&probe(403, &[], Some(broad)), 0);
+        assert!(matches!(&result, CheckResult::Warn { message, .. } if message.ends_with(": cloud-platform, compute")));
+
+        // A header-less request being served, or a legacy endpoint, fails
+        let result = evaluate_metadata_access(&probe(200, &[], Some(minimal)), 0);
+        assert!(matches!(&result, CheckResult::Fail { details, .. } if details.contains("answered with HTTP 200")));
+        assert_eq!(result.data()[0], ("header enforced".to_string(), "no".to_string()));
+
+        let result = evaluate_metadata_access(&probe(403, &["v0.1", "v1beta1"], Some(broad)), 0);
+        assert!(matches!(&result, CheckResult::Fail { details, .. }
+            if details.contains("(v0.1, v1beta1)") && details.contains("disable-legacy-endpoints=true")));
+        assert_eq!(result.data()[1], ("legacy endpoints".to_string(), "v0.1, v1beta1".to_string()));
+    }
+}
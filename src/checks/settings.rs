@@ -0,0 +1,123 @@
+//! Per-run settings the checks read.
+//!
+//! Options that change how checks behave (the network and benchmark
+//! timeouts, the benchmark parameters, the busy-TPU policy, and so on) are
+//! given per run in [`RunSettings`], on the run's
+//! [`OrchestratorConfig`](crate::engine::orchestrator::OrchestratorConfig).
+//! While a check runs, the orchestrator makes its run's settings current on
+//! the check's thread ([`RunSettings::scope`]), and accessors such as
+//! [`super::timeouts::net_timeout_ms`] read them from there, so two runs in
+//! one process (e.g. in a service embedding the library) never see each
+//! other's settings.
+//!
+//! Outside a check (or on threads a check spawns) the defaults apply.
+
+use crate::checks::benchmark::{BusyTpuPolicy, HbmWorkingSet, PerfLevel, WarmupIterations};
+use crate::checks::timeouts::{DEFAULT_BENCH_TIMEOUT_MS, DEFAULT_NET_TIMEOUT_MS};
+use std::cell::Cell;
+
+/// Settings for the checks of one run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunSettings {
+    /// Limit for each network connection or request
+    pub net_timeout_ms: u64,
+    /// Limit for each benchmark
+    pub bench_timeout_ms: u64,
+    /// Attach the full output of failed probes to their results
+    pub capture_output: bool,
+    /// Run the benchmarks with the fixed reference parameters
+    pub deterministic: bool,
+    /// How hard the performance checks push the TPU
+    pub perf_level: PerfLevel,
+    /// Warm-up iterations for each benchmark; deterministic mode ignores them
+    pub warmup: WarmupIterations,
+    /// HBM bandwidth benchmark working set; deterministic mode ignores it
+    pub hbm_working_set: HbmWorkingSet,
+    /// What the performance checks do when the TPU is busy
+    pub busy_tpu: BusyTpuPolicy,
+    /// Whether SEC-009 warns about egress that is open to the internet or
+    /// not logged
+    pub require_locked_egress: bool,
+    /// Whether GCP API requests fall back to the `gcloud` CLI
+    pub gcloud_fallback: bool,
+}
+
+impl Default for RunSettings {
+    fn default() -> Self {
+        RunSettings {
+            net_timeout_ms: DEFAULT_NET_TIMEOUT_MS,
+            bench_timeout_ms: DEFAULT_BENCH_TIMEOUT_MS,
+            capture_output: false,
+            deterministic: false,
+            perf_level: PerfLevel::default(),
+            warmup: WarmupIterations::default(),
+            hbm_working_set: HbmWorkingSet::default(),
+            busy_tpu: BusyTpuPolicy::default(),
+            require_locked_egress: false,
+            gcloud_fallback: false,
+        }
+    }
+}
+
+impl RunSettings {
+    /// Run `f` with these as the current settings on this thread
+    pub fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        let previous = CURRENT.with(|current| current.replace(Some(*self)));
+        // Restore the previous settings even if `f` panics
+        let _restore = Restore(previous);
+        f()
+    }
+}
+
+thread_local! {
+    /// The settings of the run whose check is on this thread
+    static CURRENT: Cell<Option<RunSettings>> = const { Cell::new(None) };
+}
+
+struct Restore(Option<RunSettings>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.set(self.0));
+    }
+}
+
+/// The settings of the run whose check is on this thread, or the defaults
+pub fn current() -> RunSettings {
+    CURRENT.with(|current| current.get()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope() {
+        assert_eq!(current(), RunSettings::default());
+
+        let soak = RunSettings {
+            net_timeout_ms: 100,
+            perf_level: PerfLevel::Soak,
+            ..Default::default()
+        };
+        let smoke = RunSettings {
+            perf_level: PerfLevel::Smoke,
+            ..Default::default()
+        };
+        soak.scope(|| {
+            assert_eq!(current().net_timeout_ms, 100);
+            smoke.scope(|| assert_eq!(current().perf_level, PerfLevel::Smoke));
+            assert_eq!(current().perf_level, PerfLevel::Soak);
+
+            // Another run's thread keeps its own settings
+            let other = std::thread::spawn(move || smoke.scope(|| (current().perf_level, current().net_timeout_ms)));
+            assert_eq!(other.join().unwrap(), (PerfLevel::Smoke, DEFAULT_NET_TIMEOUT_MS));
+            assert_eq!(current().perf_level, PerfLevel::Soak);
+        });
+
+        // The scope ends with the check, even one that panics
+        let panicked = std::panic::catch_unwind(|| soak.scope(|| panic!("check panicked")));
+        assert!(panicked.is_err());
+        assert_eq!(current(), RunSettings::default());
+    }
+}
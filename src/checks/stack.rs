@@ -1,10 +1,15 @@
 //! Software stack validation checks.
 //!
 //! Checks for JAX, libtpu, XLA, Python versions, PJRT plugin status,
-//! dependency conflicts, and environment variables.
+//! dependency conflicts, environment variables, and the pairing of the TPU
+//! driver with libtpu.
 
+use crate::checks::env::{self, ValueFormat};
+use crate::checks::probe;
+use crate::data::compatibility::CompatibilityMatrix;
 use crate::platform::{linux, tpu};
-use crate::{Check, CheckCategory, CheckResult};
+use crate::util::regex::Regex;
+use crate::{Check, CheckCategory, CheckResult, TpuDocError};
 use std::time::Instant;
 
 /// Get all stack checks
@@ -17,6 +22,7 @@ pub fn get_stack_checks() -> Vec<Check> {
         create_stk005_check(),
         create_stk006_check(),
         create_stk007_check(),
+        create_stk008_check(),
     ]
 }
 
@@ -28,6 +34,8 @@ fn create_stk001_check() -> Check {
         category: CheckCategory::Stack,
         description: "Detect and validate installed JAX version".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -39,6 +47,8 @@ fn create_stk002_check() -> Check {
         category: CheckCategory::Stack,
         description: "Detect and validate libtpu version".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -50,6 +60,8 @@ fn create_stk003_check() -> Check {
         category: CheckCategory::Stack,
         description: "Detect XLA compiler version".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -61,6 +73,8 @@ fn create_stk004_check() -> Check {
         category: CheckCategory::Stack,
         description: "Check Python version compatibility".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -70,8 +84,10 @@ fn create_stk005_check() -> Check {
         id: "STK-005".to_string(),
         name: "PJRT Plugin Status".to_string(),
         category: CheckCategory::Stack,
-        description: "Verify PJRT TPU plugin is available".to_string(),
+        description: "Verify the PJRT TPU plugin loads and reports a C API version jaxlib accepts".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -83,6 +99,8 @@ fn create_stk006_check() -> Check {
         category: CheckCategory::Stack,
         description: "Check for known conflicting package versions".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -94,6 +112,22 @@ fn create_stk007_check() -> Check {
         category: CheckCategory::Stack,
         description: "Verify required environment variables are set".to_string(),
         result: None,
+        start_ms: None,
+        ownership: Default::default(),
+    }
+}
+
+/// STK-008: Driver/libtpu Pairing
+fn create_stk008_check() -> Check {
+    Check {
+        id: "STK-008".to_string(),
+        name: "Driver/libtpu Pairing".to_string(),
+        category: CheckCategory::Stack,
+        description: "Cross-check the TPU driver version against the libtpu version in the compatibility matrix"
+            .to_string(),
+        result: None,
+        start_ms: None,
+        ownership: Default::default(),
     }
 }
 
@@ -119,18 +153,25 @@ pub fn run_stk001() -> CheckResult {
                                 min_version.0, min_version.1, min_version.2
                             ),
                             duration_ms,
+                            data: Vec::new(),
                         }
                     } else {
-                        CheckResult::Pass {
-                            message: format!("JAX version {}", version),
-                            duration_ms,
-                        }
+                        super::with_known_issues(
+                            "jax",
+                            &version,
+                            CheckResult::Pass {
+                                message: format!("JAX version {}", version),
+                                duration_ms,
+                                data: Vec::new(),
+                            },
+                        )
                     }
                 }
                 None => CheckResult::Warn {
                     message: format!("JAX version {} (unparseable)", version),
                     details: "Could not parse version for compatibility check".to_string(),
                     duration_ms,
+                    data: Vec::new(),
                 },
             }
         }
@@ -149,18 +190,21 @@ pub fn run_stk002() -> CheckResult {
             let duration_ms = start.elapsed().as_millis() as u64;
 
             // Check if it's a development/nightly build
-            if version.contains("dev") || version.contains("nightly") {
+            let result = if version.contains("dev") || version.contains("nightly") {
                 CheckResult::Warn {
                     message: format!("libtpu version {}", version),
                     details: "Using development/nightly build".to_string(),
                     duration_ms,
+                    data: Vec::new(),
                 }
             } else {
                 CheckResult::Pass {
                     message: format!("libtpu version {}", version),
                     duration_ms,
+                    data: Vec::new(),
                 }
-            }
+            };
+            super::with_known_issues("libtpu", &version, result)
         }
         Err(e) => CheckResult::Skip {
             reason: format!("libtpu version unavailable: {}", e),
@@ -168,6 +212,70 @@ pub fn run_stk002() -> CheckResult {
     }
 }
 
+/// Execute STK-008: Driver/libtpu Pairing
+///
+/// HW-006 and STK-002 check the driver and libtpu on their own; this fails
+/// on pairings of the two that the compatibility matrix records as failing
+/// TPU initialization.
+pub fn run_stk008() -> CheckResult {
+    let start = Instant::now();
+
+    let driver = match tpu::get_driver_version() {
+        Ok(version) => version,
+        Err(e) => {
+            return CheckResult::Skip {
+                reason: format!("Driver version unavailable: {}", e),
+            };
+        }
+    };
+    let libtpu = match tpu::get_libtpu_version() {
+        Ok(version) if version.trim_start_matches('v').starts_with(|c: char| c.is_ascii_digit()) => version,
+        Ok(version) => {
+            return CheckResult::Skip {
+                reason: format!("libtpu version not known: {}", version),
+            };
+        }
+        Err(e) => {
+            return CheckResult::Skip {
+                reason: format!("libtpu version unavailable: {}", e),
+            };
+        }
+    };
+
+    let matrix = CompatibilityMatrix::load();
+    evaluate_driver_pairing(matrix, &driver, &libtpu, start.elapsed().as_millis() as u64)
+}
+
+/// STK-008's result for `driver` with `libtpu`
+fn evaluate_driver_pairing(matrix: &CompatibilityMatrix, driver: &str, libtpu: &str, duration_ms: u64) -> CheckResult {
+    let data = vec![
+        ("driver".to_string(), driver.to_string()),
+        ("libtpu".to_string(), libtpu.to_string()),
+        ("matrix version".to_string(), matrix.version.clone()),
+    ];
+    let bad = matrix.bad_driver_pairings(driver, libtpu);
+    if bad.is_empty() {
+        return CheckResult::Pass {
+            message: format!("Driver {} with libtpu {}: no known incompatibility", driver, libtpu),
+            duration_ms,
+            data,
+        };
+    }
+
+    CheckResult::Fail {
+        message: format!("Driver {} with libtpu {} is a known-bad pairing", driver, libtpu),
+        details: bad
+            .iter()
+            .map(|pairing| {
+                format!("{}: {}. Fix: {}", pairing.describe_ranges(), pairing.description, pairing.resolution)
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        duration_ms,
+        data,
+    }
+}
+
 /// Execute STK-003: XLA Compiler Version
 pub fn run_stk003() -> CheckResult {
     let start = Instant::now();
@@ -175,9 +283,14 @@ pub fn run_stk003() -> CheckResult {
     match detect_xla_version() {
         Ok(version) => {
             let duration_ms = start.elapsed().as_millis() as u64;
-            CheckResult::Pass {
+            let result = CheckResult::Pass {
                 message: format!("XLA version {}", version),
                 duration_ms,
+                data: Vec::new(),
+            };
+            match version.strip_prefix("jaxlib ") {
+                Some(jaxlib) => super::with_known_issues("jaxlib", jaxlib, result),
+                None => result,
             }
         }
         Err(_) => CheckResult::Skip {
@@ -207,11 +320,13 @@ pub fn run_stk004() -> CheckResult {
                                 min_version.0, min_version.1, min_version.2
                             ),
                             duration_ms,
+                            data: Vec::new(),
                         }
                     } else {
                         CheckResult::Pass {
                             message: format!("Python version {}", version),
                             duration_ms,
+                            data: Vec::new(),
                         }
                     }
                 }
@@ -219,6 +334,7 @@ pub fn run_stk004() -> CheckResult {
                     message: format!("Python version {} (unparseable)", version),
                     details: "Could not parse version for compatibility check".to_string(),
                     duration_ms,
+                    data: Vec::new(),
                 },
             }
         }
@@ -228,51 +344,199 @@ pub fn run_stk004() -> CheckResult {
     }
 }
 
+/// How long STK-005 waits for the handshake to load libtpu
+const PJRT_HANDSHAKE_TIMEOUT_MS: u64 = 15000;
+
+/// What loading a PJRT plugin and asking for its C API version found
+#[derive(Debug, PartialEq)]
+enum PjrtHandshake {
+    /// The plugin reported its C API version; `jaxlib` is the installed
+    /// jaxlib release, if any
+    Api { major: u32, minor: u32, jaxlib: Option<String> },
+    /// dlopen failed
+    LoadFailed(String),
+    /// The library loaded but is not a usable PJRT plugin
+    NotAPlugin(String),
+}
+
 /// Execute STK-005: PJRT Plugin Status
 pub fn run_stk005() -> CheckResult {
     let start = Instant::now();
 
-    // Check TPU_LIBRARY_PATH environment variable
-    let tpu_lib_path = linux::get_environment_variable("TPU_LIBRARY_PATH");
-    let duration_ms = start.elapsed().as_millis() as u64;
-
-    match tpu_lib_path {
+    // Check TPU_LIBRARY_PATH environment variable, then the standard locations
+    let path = match linux::get_environment_variable("TPU_LIBRARY_PATH") {
+        Some(path) if std::path::Path::new(&path).exists() => path,
         Some(path) => {
-            // Verify the path exists
-            if std::path::Path::new(&path).exists() {
-                CheckResult::Pass {
-                    message: format!("PJRT plugin found at {}", path),
-                    duration_ms,
-                }
-            } else {
-                CheckResult::Fail {
-                    message: "TPU_LIBRARY_PATH points to non-existent location".to_string(),
-                    details: format!("Path {} does not exist", path),
-                    duration_ms,
-                }
-            }
+            return CheckResult::Fail {
+                message: "TPU_LIBRARY_PATH points to non-existent location".to_string(),
+                details: format!("Path {} does not exist", path),
+                duration_ms: start.elapsed().as_millis() as u64,
+                data: Vec::new(),
+            };
         }
         None => {
-            // Try to find libtpu.so in standard locations
             let standard_paths = [
                 "/usr/local/lib/libtpu.so",
                 "/usr/lib/libtpu.so",
             ];
 
-            for path in standard_paths.iter() {
-                if std::path::Path::new(path).exists() {
-                    return CheckResult::Pass {
-                        message: format!("PJRT plugin found at {}", path),
-                        duration_ms,
+            match standard_paths.iter().find(|path| std::path::Path::new(path).exists()) {
+                Some(path) => path.to_string(),
+                None => {
+                    return CheckResult::Warn {
+                        message: "TPU_LIBRARY_PATH not set".to_string(),
+                        details: "PJRT plugin location not specified".to_string(),
+                        duration_ms: start.elapsed().as_millis() as u64,
+                        data: Vec::new(),
                     };
                 }
             }
+        }
+    };
 
-            CheckResult::Warn {
-                message: "TPU_LIBRARY_PATH not set".to_string(),
-                details: "PJRT plugin location not specified".to_string(),
-                duration_ms,
+    let handshake = pjrt_handshake(&path);
+    let matrix = CompatibilityMatrix::load();
+    evaluate_pjrt_handshake(matrix, &path, handshake, start.elapsed().as_millis() as u64)
+}
+
+/// dlopen the plugin at `path` and read the PJRT C API version its
+/// `GetPjrtApi` reports, along with the installed jaxlib release. Err when
+/// the handshake could not be attempted at all.
+fn pjrt_handshake(path: &str) -> Result<PjrtHandshake, String> {
+    let script = r#"
+import ctypes
+import sys
+
+try:
+    lib = ctypes.CDLL(sys.argv[1])
+except OSError as e:
+    print(f"load-error {e}")
+    sys.exit(0)
+try:
+    get_api = lib.GetPjrtApi
+except AttributeError:
+    print("not-plugin GetPjrtApi is not exported")
+    sys.exit(0)
+get_api.restype = ctypes.c_void_p
+api = get_api()
+if not api:
+    print("not-plugin GetPjrtApi returned null")
+    sys.exit(0)
+# PJRT_Api opens with struct_size and extension_start, then PJRT_Api_Version:
+# struct_size, extension_start, major_version, minor_version
+major, minor = (ctypes.c_int * 2).from_address(api + 4 * ctypes.sizeof(ctypes.c_void_p))
+print(f"api {major} {minor}")
+try:
+    from importlib.metadata import version
+    print(f"jaxlib {version('jaxlib')}")
+except Exception:
+    pass
+"#;
+
+    let mut command = std::process::Command::new("python3");
+    command.args(["-c", script, path]);
+    match linux::run_command_with_timeout(&mut command, PJRT_HANDSHAKE_TIMEOUT_MS) {
+        Ok(output) if output.status.success() => parse_pjrt_handshake(&String::from_utf8_lossy(&output.stdout))
+            .ok_or_else(|| probe::describe_failure("unexpected handshake output".to_string(), &output)),
+        Ok(output) => Err(probe::describe_failure("handshake probe failed".to_string(), &output)),
+        Err(TpuDocError::Timeout { .. }) => {
+            Err(format!("loading the plugin timed out after {}ms", PJRT_HANDSHAKE_TIMEOUT_MS))
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Parse the handshake probe's `api MAJOR MINOR` and `jaxlib VERSION` lines,
+/// or its `load-error` / `not-plugin` line. Anything else the plugin prints
+/// to stdout while loading is skipped.
+fn parse_pjrt_handshake(output: &str) -> Option<PjrtHandshake> {
+    let mut api = None;
+    let mut jaxlib = None;
+    for line in output.lines() {
+        let Some((kind, rest)) = line.split_once(' ') else {
+            continue;
+        };
+        match kind {
+            "load-error" => return Some(PjrtHandshake::LoadFailed(rest.to_string())),
+            "not-plugin" => return Some(PjrtHandshake::NotAPlugin(rest.to_string())),
+            "api" => {
+                let (major, minor) = rest.split_once(' ')?;
+                api = Some((major.parse().ok()?, minor.parse().ok()?));
             }
+            "jaxlib" => jaxlib = Some(rest.trim().to_string()),
+            _ => {}
+        }
+    }
+    let (major, minor) = api?;
+    Some(PjrtHandshake::Api { major, minor, jaxlib })
+}
+
+/// STK-005's result for the plugin at `path` once it has been found
+fn evaluate_pjrt_handshake(
+    matrix: &CompatibilityMatrix,
+    path: &str,
+    handshake: Result<PjrtHandshake, String>,
+    duration_ms: u64,
+) -> CheckResult {
+    let mut data = vec![("plugin".to_string(), path.to_string())];
+    let (major, minor, jaxlib) = match handshake {
+        Ok(PjrtHandshake::Api { major, minor, jaxlib }) => (major, minor, jaxlib),
+        Ok(PjrtHandshake::LoadFailed(error)) => {
+            return CheckResult::Fail {
+                message: format!("PJRT plugin at {} could not be loaded", path),
+                details: error,
+                duration_ms,
+                data,
+            };
+        }
+        Ok(PjrtHandshake::NotAPlugin(reason)) => {
+            return CheckResult::Fail {
+                message: format!("{} is not a PJRT plugin", path),
+                details: reason,
+                duration_ms,
+                data,
+            };
+        }
+        Err(reason) => {
+            data.push(("pjrt c api".to_string(), format!("not queried: {}", reason)));
+            return CheckResult::Pass {
+                message: format!("PJRT plugin found at {}", path),
+                duration_ms,
+                data,
+            };
+        }
+    };
+
+    data.push(("pjrt c api".to_string(), format!("{}.{}", major, minor)));
+    let message = format!("PJRT plugin found at {} (C API {}.{})", path, major, minor);
+    let Some(jaxlib) = jaxlib else {
+        data.push(("jaxlib".to_string(), "not installed".to_string()));
+        return CheckResult::Pass { message, duration_ms, data };
+    };
+    data.push(("jaxlib".to_string(), jaxlib.clone()));
+    let Some(range) = matrix.pjrt_api_range(&jaxlib) else {
+        data.push(("expected c api".to_string(), format!("not in matrix {}", matrix.version)));
+        return CheckResult::Pass { message, duration_ms, data };
+    };
+    data.push(("expected c api".to_string(), range.describe()));
+
+    if range.accepts(major, minor) {
+        CheckResult::Pass { message, duration_ms, data }
+    } else {
+        CheckResult::Fail {
+            message: format!("PJRT C API {}.{} is outside the range jaxlib {} expects", major, minor, jaxlib),
+            details: format!(
+                "jaxlib {} loads plugins with C API {}, but {} reports {}.{}; the mismatch surfaces as errors \
+                 at backend initialization. Fix: install the libtpu release that matches jaxlib: \
+                 pip install -U \"jax[tpu]\"",
+                jaxlib,
+                range.describe(),
+                path,
+                major,
+                minor
+            ),
+            duration_ms,
+            data,
         }
     }
 }
@@ -289,57 +553,146 @@ pub fn run_stk006() -> CheckResult {
         CheckResult::Pass {
             message: "No known dependency conflicts".to_string(),
             duration_ms,
+            data: Vec::new(),
         }
     } else {
         CheckResult::Warn {
             message: format!("{} potential conflict(s) detected", conflicts.len()),
             details: conflicts.join("; "),
             duration_ms,
+            data: Vec::new(),
+        }
+    }
+}
+
+/// TPU runtime variables STK-007 validates the values of when they are set
+const TPU_VALUE_FORMATS: &[(&str, ValueFormat)] = &[
+    ("TPU_WORKER_ID", ValueFormat::Integer { min: 0, max: i64::MAX }),
+    ("TPU_CHIPS_PER_HOST_BOUNDS", ValueFormat::Bounds),
+    ("TPU_HOST_BOUNDS", ValueFormat::Bounds),
+    ("TPU_PROCESS_BOUNDS", ValueFormat::Bounds),
+    ("TPU_VISIBLE_CHIPS", ValueFormat::IntegerList),
+    ("TPU_PROCESS_PORT", ValueFormat::Integer { min: 1, max: 65535 }),
+];
+
+/// An environment variable STK-007 checks, beyond the built-in ones. Rules
+/// come from the `[[env.required]]` and `[[env.recommended]]` tables of the
+/// configuration file.
+#[derive(Debug, Clone)]
+pub struct EnvVarRule {
+    pub name: String,
+    /// A missing or mismatching required variable fails the check; a
+    /// recommended one only warns
+    pub required: bool,
+    /// The value must match this pattern when set
+    pub pattern: Option<Regex>,
+    /// Shown in the details when the variable is missing or mismatches
+    pub description: String,
+}
+
+impl EnvVarRule {
+    fn builtin(name: &str, required: bool) -> Self {
+        EnvVarRule {
+            name: name.to_string(),
+            required,
+            pattern: None,
+            description: String::new(),
         }
     }
 }
 
 /// Execute STK-007: Environment Variables
 pub fn run_stk007() -> CheckResult {
+    run_stk007_with(&[])
+}
+
+/// Execute STK-007 with additional `rules`. A rule for a built-in variable
+/// replaces the built-in one, so a configuration can make TPU_WORKER_ID
+/// required or give TPU_NAME a pattern.
+pub fn run_stk007_with(rules: &[EnvVarRule]) -> CheckResult {
     let start = Instant::now();
 
-    let mut missing_required = Vec::new();
-    let mut missing_recommended = Vec::new();
+    // Required for TPU operation, then recommended but not strictly required
+    let mut all_rules = vec![
+        EnvVarRule::builtin("TPU_NAME", true),
+        EnvVarRule::builtin("TPU_WORKER_ID", false),
+        EnvVarRule::builtin("PYTHONPATH", false),
+    ];
+    all_rules.retain(|builtin| !rules.iter().any(|rule| rule.name == builtin.name));
+    all_rules.extend(rules.iter().cloned());
 
-    // Required for TPU operation
-    let required_vars = ["TPU_NAME"];
-    for var in required_vars.iter() {
-        if linux::get_environment_variable(var).is_none() {
-            missing_required.push(*var);
-        }
-    }
+    let duration_ms = start.elapsed().as_millis() as u64;
+    evaluate_env_rules(&all_rules, linux::get_environment_variable, duration_ms)
+}
 
-    // Recommended but not strictly required
-    let recommended_vars = ["TPU_WORKER_ID", "PYTHONPATH"];
-    for var in recommended_vars.iter() {
-        if linux::get_environment_variable(var).is_none() {
-            missing_recommended.push(*var);
+/// STK-007's result for `rules`, reading variables with `get`. A malformed
+/// TPU runtime variable fails the check like an invalid required one.
+fn evaluate_env_rules(rules: &[EnvVarRule], get: impl Fn(&str) -> Option<String>, duration_ms: u64) -> CheckResult {
+    let mut missing = (Vec::new(), Vec::new());
+    let mut invalid = (Vec::new(), Vec::new());
+    let mut notes = Vec::new();
+
+    let malformed: Vec<String> = TPU_VALUE_FORMATS
+        .iter()
+        .filter_map(|(name, format)| get(name).and_then(|value| env::malformed(name, &value, *format)))
+        .collect();
+
+    for rule in rules {
+        let (list, entry) = match (get(&rule.name), &rule.pattern) {
+            (None, _) => (&mut missing, rule.name.clone()),
+            (Some(value), Some(pattern)) if !pattern.is_match(&value) => {
+                (&mut invalid, format!("{} (does not match {})", rule.name, pattern.as_str()))
+            }
+            _ => continue,
+        };
+        if rule.required {
+            list.0.push(entry);
+        } else {
+            list.1.push(entry);
+        }
+        if !rule.description.is_empty() {
+            notes.push(format!("{}: {}", rule.name, rule.description));
         }
     }
 
-    let duration_ms = start.elapsed().as_millis() as u64;
-
-    if !missing_required.is_empty() {
+    let describe = |kind: &str, missing: &[String], invalid: &[String]| {
+        let mut parts = Vec::new();
+        if !missing.is_empty() {
+            parts.push(format!("Missing {} variable(s): {}", kind, missing.join(", ")));
+        }
+        if !invalid.is_empty() {
+            parts.push(format!("Invalid {} variable(s): {}", kind, invalid.join(", ")));
+        }
+        parts.join("; ")
+    };
+    let details = |default: &str| if notes.is_empty() { default.to_string() } else { notes.join("; ") };
+
+    if !missing.0.is_empty() || !invalid.0.is_empty() || !malformed.is_empty() {
+        let mut message = describe("required environment", &missing.0, &invalid.0);
+        if !malformed.is_empty() {
+            if !message.is_empty() {
+                message.push_str("; ");
+            }
+            message.push_str(&format!("Malformed variable(s): {}", malformed.join(", ")));
+        }
         CheckResult::Fail {
-            message: format!("Missing required environment variable(s): {}", missing_required.join(", ")),
-            details: "These variables are required for TPU operation".to_string(),
+            message,
+            details: details("These variables are required for TPU operation"),
             duration_ms,
+            data: Vec::new(),
         }
-    } else if !missing_recommended.is_empty() {
+    } else if !missing.1.is_empty() || !invalid.1.is_empty() {
         CheckResult::Warn {
-            message: format!("Missing recommended variable(s): {}", missing_recommended.join(", ")),
-            details: "These variables are recommended for optimal operation".to_string(),
+            message: describe("recommended", &missing.1, &invalid.1),
+            details: details("These variables are recommended for optimal operation"),
             duration_ms,
+            data: Vec::new(),
         }
     } else {
         CheckResult::Pass {
             message: "All environment variables set".to_string(),
             duration_ms,
+            data: Vec::new(),
         }
     }
 }
@@ -353,6 +706,7 @@ fn detect_jax_version() -> Result<String, String> {
     }
 
     // Try to query JAX version via Python
+    let mut failed_probe = None;
     match std::process::Command::new("python3")
         .args(["-c", "import jax; print(jax.__version__)"])
         .output()
@@ -363,7 +717,9 @@ fn detect_jax_version() -> Result<String, String> {
                 return Ok(version);
             }
         }
-        _ => {}
+        // Kept to explain the failure if pip cannot find JAX either
+        Ok(output) => failed_probe = Some(output),
+        Err(_) => {}
     }
 
     // Try pip show as fallback
@@ -384,7 +740,11 @@ fn detect_jax_version() -> Result<String, String> {
         _ => {}
     }
 
-    Err("JAX not installed or not detectable".to_string())
+    let summary = "JAX not installed or not detectable".to_string();
+    Err(match failed_probe {
+        Some(output) => probe::describe_failure(summary, &output),
+        None => summary,
+    })
 }
 
 fn detect_xla_version() -> Result<String, String> {
@@ -403,7 +763,8 @@ fn detect_xla_version() -> Result<String, String> {
                 return Ok(format!("jaxlib {}", version));
             }
         }
-        _ => {}
+        Ok(output) => return Err(probe::describe_failure("XLA version not found".to_string(), &output)),
+        Err(_) => {}
     }
 
     Err("XLA version not found".to_string())
@@ -533,3 +894,130 @@ fn check_known_conflicts() -> Vec<String> {
 fn parse_major_version(version: &str) -> Option<u32> {
     version.split('.').next()?.parse().ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, required: bool, pattern: Option<&str>) -> EnvVarRule {
+        EnvVarRule {
+            name: name.to_string(),
+            required,
+            pattern: pattern.map(|p| Regex::new(p).unwrap()),
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_pjrt_handshake() {
+        assert_eq!(
+            parse_pjrt_handshake("api 0 54\njaxlib 0.4.35\n"),
+            Some(PjrtHandshake::Api { major: 0, minor: 54, jaxlib: Some("0.4.35".to_string()) })
+        );
+        assert_eq!(
+            parse_pjrt_handshake("load-error libtpu.so: undefined symbol: foo\n"),
+            Some(PjrtHandshake::LoadFailed("libtpu.so: undefined symbol: foo".to_string()))
+        );
+        assert_eq!(
+            parse_pjrt_handshake("\nlibtpu_init\nI0000 plugin loaded\napi 0 54\n"),
+            Some(PjrtHandshake::Api { major: 0, minor: 54, jaxlib: None })
+        );
+        assert_eq!(parse_pjrt_handshake("api 0\n"), None);
+        assert_eq!(parse_pjrt_handshake(""), None);
+
+        let matrix = CompatibilityMatrix::embedded();
+        let api = |minor: u32, jaxlib: Option<&str>| {
+            Ok(PjrtHandshake::Api { major: 0, minor, jaxlib: jaxlib.map(|j| j.to_string()) })
+        };
+        let path = "/usr/lib/libtpu.so";
+        assert!(matches!(
+            evaluate_pjrt_handshake(&matrix, path, api(54, Some("0.4.35")), 0),
+            CheckResult::Pass { message, .. } if message == "PJRT plugin found at /usr/lib/libtpu.so (C API 0.54)"
+        ));
+        match evaluate_pjrt_handshake(&matrix, path, api(40, Some("0.4.35")), 0) {
+            CheckResult::Fail { message, details, data, .. } => {
+                assert_eq!(message, "PJRT C API 0.40 is outside the range jaxlib 0.4.35 expects");
+                assert!(details.starts_with("jaxlib 0.4.35 loads plugins with C API 0.51 to 0.58"));
+                assert_eq!(data[3], ("expected c api".to_string(), "0.51 to 0.58".to_string()));
+            }
+            other => panic!("expected fail, got {:?}", other),
+        }
+
+        // Without jaxlib or a matrix entry there is nothing to compare against
+        assert!(matches!(evaluate_pjrt_handshake(&matrix, path, api(40, None), 0), CheckResult::Pass { .. }));
+        assert!(matches!(
+            evaluate_pjrt_handshake(&matrix, path, api(40, Some("0.4.10")), 0),
+            CheckResult::Pass { .. }
+        ));
+        assert!(matches!(
+            evaluate_pjrt_handshake(&matrix, path, Err("python3 not found".to_string()), 0),
+            CheckResult::Pass { data, .. } if data[1].1 == "not queried: python3 not found"
+        ));
+        assert!(matches!(
+            evaluate_pjrt_handshake(&matrix, path, Ok(PjrtHandshake::LoadFailed("bad ELF".to_string())), 0),
+            CheckResult::Fail { message, .. } if message == "PJRT plugin at /usr/lib/libtpu.so could not be loaded"
+        ));
+    }
+
+    #[test]
+    fn test_driver_pairing() {
+        let matrix = CompatibilityMatrix::embedded();
+        assert!(matches!(
+            evaluate_driver_pairing(&matrix, "1.8.1", "0.0.8", 0),
+            CheckResult::Pass { message, .. } if message == "Driver 1.8.1 with libtpu 0.0.8: no known incompatibility"
+        ));
+        match evaluate_driver_pairing(&matrix, "1.7.0", "0.0.8", 0) {
+            CheckResult::Fail { message, details, data, .. } => {
+                assert_eq!(message, "Driver 1.7.0 with libtpu 0.0.8 is a known-bad pairing");
+                assert!(details.starts_with("driver <1.8.0 with libtpu >=0.0.6: "));
+                assert!(details.contains("Fix: Recreate the TPU VM"));
+                assert_eq!(data[2], ("matrix version".to_string(), matrix.version.clone()));
+            }
+            other => panic!("expected fail, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_env_rules() {
+        let env = |name: &str| match name {
+            "TPU_NAME" => Some("my-tpu".to_string()),
+            "JOB_ID" => Some("job-x".to_string()),
+            _ => None,
+        };
+        let rules = [rule("TPU_NAME", true, None), rule("WANDB_PROJECT", false, None)];
+        assert!(matches!(
+            evaluate_env_rules(&rules, env, 0),
+            CheckResult::Warn { message, .. } if message == "Missing recommended variable(s): WANDB_PROJECT"
+        ));
+
+        let mut job = rule("JOB_ID", true, Some("^job-[0-9]+$"));
+        job.description = "Set by the launcher".to_string();
+        match evaluate_env_rules(&[job, rule("WANDB_PROJECT", false, None)], env, 0) {
+            CheckResult::Fail { message, details, .. } => {
+                assert_eq!(message, "Invalid required environment variable(s): JOB_ID (does not match ^job-[0-9]+$)");
+                assert_eq!(details, "JOB_ID: Set by the launcher");
+            }
+            other => panic!("expected fail, got {:?}", other),
+        }
+
+        let rules = [rule("TPU_NAME", true, Some("^my-")), rule("JOB_ID", false, Some("^job-"))];
+        assert!(matches!(evaluate_env_rules(&rules, env, 0), CheckResult::Pass { .. }));
+    }
+
+    #[test]
+    fn test_malformed_tpu_variables() {
+        let env = |name: &str| match name {
+            "TPU_NAME" => Some("my-tpu".to_string()),
+            "TPU_WORKER_ID" => Some("0".to_string()),
+            "TPU_CHIPS_PER_HOST_BOUNDS" => Some("2,2".to_string()),
+            _ => None,
+        };
+        match evaluate_env_rules(&[rule("TPU_NAME", true, None), rule("PYTHONPATH", false, None)], env, 0) {
+            CheckResult::Fail { message, .. } => assert_eq!(
+                message,
+                "Malformed variable(s): TPU_CHIPS_PER_HOST_BOUNDS=\"2,2\" (expected three positive integers, e.g. 2,2,1)"
+            ),
+            other => panic!("expected fail, got {:?}", other),
+        }
+    }
+}
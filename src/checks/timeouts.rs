@@ -0,0 +1,31 @@
+//! Timeouts for network probes and benchmarks.
+//!
+//! A connection attempt and a benchmark differ in duration by orders of
+//! magnitude, so each family has its own limit instead of sharing the
+//! global check timeout:
+//! - The network timeout (`--net-timeout`) bounds each connection or HTTP
+//!   request the network checks make (IO-003, IO-005, SEC-002).
+//! - The benchmark timeout (`--bench-timeout`) bounds each benchmark the
+//!   performance checks run; the benchmark process is killed when it
+//!   expires, and the orchestrator applies it to performance checks in
+//!   place of the global timeout.
+//!
+//! Both come from the running check's [`RunSettings`](super::settings::RunSettings).
+
+use crate::checks::settings;
+
+/// Default limit for one network connection or request
+pub const DEFAULT_NET_TIMEOUT_MS: u64 = 5000;
+
+/// Default limit for one benchmark, which includes XLA compilation
+pub const DEFAULT_BENCH_TIMEOUT_MS: u64 = 120_000;
+
+/// The limit for each network connection or request
+pub fn net_timeout_ms() -> u64 {
+    settings::current().net_timeout_ms
+}
+
+/// The limit for each benchmark
+pub fn bench_timeout_ms() -> u64 {
+    settings::current().bench_timeout_ms
+}
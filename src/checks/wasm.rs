@@ -0,0 +1,573 @@
+//! Sandboxed WebAssembly plugin checks.
+//!
+//! A `.wasm` file in a plugin directory is loaded as a check when tpu-doc is
+//! built with the `wasm-plugins` feature. Unlike executable plugins, a
+//! WebAssembly module runs inside an interpreter with read-only access to the
+//! host: it may import only the host functions below from the `tpu_doc`
+//! module (anything else, such as WASI, is rejected), and each call is
+//! bounded by a fuel budget and a memory limit.
+//!
+//! A module must export:
+//!
+//! - `memory`: its linear memory
+//! - `tpu_doc_metadata() -> i64`: the check's metadata JSON
+//! - `tpu_doc_run() -> i64`: the check's result JSON
+//!
+//! Each function returns the location of a UTF-8 JSON string in `memory`,
+//! packed as `(offset << 32) | length`, unless it called `emit_result`. The
+//! JSON uses the same schemas as executable plugins (see [`super::plugins`]).
+//!
+//! Host functions take `i32` offsets and lengths into `memory`:
+//!
+//! - `env_get(name_ptr, name_len, buf_ptr, buf_len) -> i32`: copy an
+//!   environment variable's value into the buffer and return its length, or
+//!   -1 when it is unset or not visible to modules (only names starting with
+//!   one of [`ENV_PREFIXES`] are, so API keys and tokens stay on the host).
+//!   A value longer than the buffer is not copied; its length tells the
+//!   module how large a buffer to retry with.
+//! - `read_file(path_ptr, path_len, buf_ptr, buf_len) -> i32`: the same for
+//!   a file under `/proc` or `/sys` (checked after resolving symlinks); -1
+//!   for other paths, unreadable files, other processes' `/proc/<pid>`
+//!   entries and tpu-doc's own [`DENIED_PROC_FILES`]
+//! - `emit_result(ptr, len)`: report the JSON string at `ptr` as the call's
+//!   result, in place of the one the export returns
+
+use std::path::Path;
+
+/// Export returning the metadata JSON
+pub const METADATA_EXPORT: &str = "tpu_doc_metadata";
+
+/// Export running the check and returning the result JSON
+pub const RUN_EXPORT: &str = "tpu_doc_run";
+
+/// Fuel (roughly, instructions) a module may use per call
+pub const FUEL_LIMIT: u64 = 2_000_000_000;
+
+/// Largest linear memory a module may grow to
+pub const MEMORY_LIMIT_BYTES: usize = 64 * 1024 * 1024;
+
+/// Largest JSON string read back from a module, and largest file
+/// `read_file` returns
+pub const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
+/// Module the host functions are imported from
+pub const HOST_MODULE: &str = "tpu_doc";
+
+/// Host functions a module may import from [`HOST_MODULE`]
+pub const HOST_FUNCTIONS: &[&str] = &["env_get", "read_file", "emit_result"];
+
+/// Directories `read_file` may read under
+pub const READABLE_ROOTS: &[&str] = &["/proc", "/sys"];
+
+/// Prefixes of the environment variables `env_get` exposes
+pub const ENV_PREFIXES: &[&str] = &["TPU_", "JAX_", "XLA_", "LIBTPU_"];
+
+/// Files under tpu-doc's own `/proc/<pid>` that `read_file` refuses, as they
+/// hold its environment, memory or command line (which may carry secrets)
+pub const DENIED_PROC_FILES: &[&str] = &["environ", "mem", "cmdline"];
+
+/// Whether WebAssembly plugins are supported by this build
+pub fn is_enabled() -> bool {
+    cfg!(feature = "wasm-plugins")
+}
+
+/// Instantiate the module at `path`, call `export`, and return the JSON
+/// string it points to
+#[cfg(feature = "wasm-plugins")]
+pub fn call_json_export(path: &Path, export: &str) -> Result<String, String> {
+    use wasmi::{Config, Engine, Linker, Module, Store, StoreLimitsBuilder};
+
+    let bytes = std::fs::read(path).map_err(|e| format!("cannot read module: {}", e))?;
+
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config);
+    let module = Module::new(&engine, &bytes[..]).map_err(|e| format!("invalid WebAssembly module: {}", e))?;
+
+    if let Some(import) = module
+        .imports()
+        .find(|import| import.module() != HOST_MODULE || !HOST_FUNCTIONS.contains(&import.name()))
+    {
+        return Err(format!(
+            "module imports '{}::{}'; WebAssembly plugins may only import {}::{{{}}}",
+            import.module(),
+            import.name(),
+            HOST_MODULE,
+            HOST_FUNCTIONS.join(", ")
+        ));
+    }
+
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MEMORY_LIMIT_BYTES)
+        .instances(1)
+        .build();
+    let mut store = Store::new(&engine, HostState { limits, emitted: None });
+    store.limiter(|state| &mut state.limits);
+    store.set_fuel(FUEL_LIMIT).map_err(|e| e.to_string())?;
+
+    let mut linker = Linker::<HostState>::new(&engine);
+    link_host_functions(&mut linker).map_err(|e| e.to_string())?;
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .and_then(|pre| pre.start(&mut store))
+        .map_err(|e| format!("cannot instantiate module: {}", e))?;
+
+    let func = instance
+        .get_typed_func::<(), i64>(&store, export)
+        .map_err(|_| format!("module does not export '{}: () -> i64'", export))?;
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or("module does not export 'memory'")?;
+
+    let packed = func.call(&mut store, ()).map_err(|e| {
+        if matches!(e.as_trap_code(), Some(wasmi::core::TrapCode::OutOfFuel)) {
+            format!("'{}' exceeded its execution budget", export)
+        } else {
+            format!("'{}' trapped: {}", export, e)
+        }
+    })? as u64;
+
+    if let Some(emitted) = store.data_mut().emitted.take() {
+        return String::from_utf8(emitted).map_err(|_| format!("'{}' emitted invalid UTF-8", export));
+    }
+    let offset = (packed >> 32) as usize;
+    let len = (packed & 0xffff_ffff) as usize;
+    if len > MAX_OUTPUT_BYTES {
+        return Err(format!("'{}' returned {} bytes (limit {})", export, len, MAX_OUTPUT_BYTES));
+    }
+    let mut buffer = vec![0u8; len];
+    memory
+        .read(&store, offset, &mut buffer)
+        .map_err(|_| format!("'{}' returned a string outside module memory", export))?;
+    String::from_utf8(buffer).map_err(|_| format!("'{}' returned invalid UTF-8", export))
+}
+
+/// Per-call state the host functions share
+#[cfg(feature = "wasm-plugins")]
+struct HostState {
+    limits: wasmi::StoreLimits,
+    /// The JSON a module passed to `emit_result`
+    emitted: Option<Vec<u8>>,
+}
+
+/// Define the read-only host functions in `linker`
+#[cfg(feature = "wasm-plugins")]
+fn link_host_functions(linker: &mut wasmi::Linker<HostState>) -> Result<(), wasmi::errors::LinkerError> {
+    use wasmi::{Caller, Error};
+
+    linker.func_wrap(
+        HOST_MODULE,
+        "env_get",
+        |mut caller: Caller<'_, HostState>, name_ptr: i32, name_len: i32, buf_ptr: i32, buf_len: i32| {
+            let name = read_guest_string(&caller, name_ptr, name_len)?;
+            let value = env_name_allowed(&name).then(|| std::env::var(name).ok()).flatten().map(String::into_bytes);
+            copy_to_guest(&mut caller, value, buf_ptr, buf_len)
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "read_file",
+        |mut caller: Caller<'_, HostState>, path_ptr: i32, path_len: i32, buf_ptr: i32, buf_len: i32| {
+            let path = read_guest_string(&caller, path_ptr, path_len)?;
+            let contents = readable_host_path(&path).and_then(|path| read_limited(&path));
+            copy_to_guest(&mut caller, contents, buf_ptr, buf_len)
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "emit_result",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> Result<(), Error> {
+            let json = read_guest(&caller, ptr, len)?;
+            caller.data_mut().emitted = Some(json);
+            Ok(())
+        },
+    )?;
+    Ok(())
+}
+
+/// The file `path` resolves to, when it is under one of [`READABLE_ROOTS`]
+#[cfg(feature = "wasm-plugins")]
+fn readable_host_path(path: &str) -> Option<std::path::PathBuf> {
+    let resolved = std::fs::canonicalize(path).ok()?;
+    let allowed = READABLE_ROOTS.iter().any(|root| resolved.starts_with(root)) && proc_path_allowed(&resolved);
+    allowed.then_some(resolved)
+}
+
+/// Whether `env_get` exposes the variable `name`
+#[cfg(feature = "wasm-plugins")]
+fn env_name_allowed(name: &str) -> bool {
+    ENV_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+}
+
+/// Whether a resolved path may be read as far as `/proc/<pid>` goes: only
+/// tpu-doc's own entries, minus [`DENIED_PROC_FILES`] at any depth (such as
+/// `/proc/<pid>/task/<tid>/environ`)
+#[cfg(feature = "wasm-plugins")]
+fn proc_path_allowed(resolved: &Path) -> bool {
+    let Ok(rest) = resolved.strip_prefix("/proc") else {
+        return true;
+    };
+    let mut components = rest.components().map(|c| c.as_os_str().to_string_lossy());
+    match components.next() {
+        Some(pid) if pid.bytes().all(|b| b.is_ascii_digit()) => {
+            pid == std::process::id().to_string() && !components.any(|c| DENIED_PROC_FILES.contains(&c.as_ref()))
+        }
+        _ => true,
+    }
+}
+
+/// Up to [`MAX_OUTPUT_BYTES`] of the file at `path`
+#[cfg(feature = "wasm-plugins")]
+fn read_limited(path: &Path) -> Option<Vec<u8>> {
+    use std::io::Read;
+
+    let mut contents = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(MAX_OUTPUT_BYTES as u64)
+        .read_to_end(&mut contents)
+        .ok()?;
+    Some(contents)
+}
+
+#[cfg(feature = "wasm-plugins")]
+fn guest_memory(caller: &wasmi::Caller<'_, HostState>) -> Result<wasmi::Memory, wasmi::Error> {
+    caller
+        .get_export("memory")
+        .and_then(wasmi::Extern::into_memory)
+        .ok_or_else(|| wasmi::Error::new("module does not export 'memory'"))
+}
+
+/// The `len` bytes at `ptr` in the module's memory
+#[cfg(feature = "wasm-plugins")]
+fn read_guest(caller: &wasmi::Caller<'_, HostState>, ptr: i32, len: i32) -> Result<Vec<u8>, wasmi::Error> {
+    let size = usize::try_from(len)
+        .ok()
+        .filter(|size| *size <= MAX_OUTPUT_BYTES)
+        .ok_or_else(|| wasmi::Error::new(format!("invalid length {}", len)))?;
+    let mut buffer = vec![0u8; size];
+    guest_memory(caller)?
+        .read(caller, ptr as u32 as usize, &mut buffer)
+        .map_err(|_| wasmi::Error::new("string outside module memory"))?;
+    Ok(buffer)
+}
+
+#[cfg(feature = "wasm-plugins")]
+fn read_guest_string(caller: &wasmi::Caller<'_, HostState>, ptr: i32, len: i32) -> Result<String, wasmi::Error> {
+    String::from_utf8(read_guest(caller, ptr, len)?).map_err(|_| wasmi::Error::new("string is not valid UTF-8"))
+}
+
+/// Copy `value` into the module's buffer when it fits, and return its length
+/// (-1 without a value)
+#[cfg(feature = "wasm-plugins")]
+fn copy_to_guest(
+    caller: &mut wasmi::Caller<'_, HostState>,
+    value: Option<Vec<u8>>,
+    buf_ptr: i32,
+    buf_len: i32,
+) -> Result<i32, wasmi::Error> {
+    let Some(value) = value else { return Ok(-1) };
+    if value.len() <= buf_len.max(0) as usize {
+        guest_memory(caller)?
+            .write(&mut *caller, buf_ptr as u32 as usize, &value)
+            .map_err(|_| wasmi::Error::new("buffer outside module memory"))?;
+    }
+    Ok(value.len() as i32)
+}
+
+/// Instantiate the module at `path`, call `export`, and return the JSON
+/// string it points to
+#[cfg(not(feature = "wasm-plugins"))]
+pub fn call_json_export(_path: &Path, _export: &str) -> Result<String, String> {
+    Err("WebAssembly plugins not enabled. Build with: cargo build --features wasm-plugins".to_string())
+}
+
+#[cfg(all(test, feature = "wasm-plugins"))]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn leb_u32(mut value: u32, out: &mut Vec<u8>) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn leb_i64(mut value: i64, out: &mut Vec<u8>) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+            out.push(if done { byte } else { byte | 0x80 });
+            if done {
+                return;
+            }
+        }
+    }
+
+    fn section(id: u8, body: Vec<u8>, out: &mut Vec<u8>) {
+        out.push(id);
+        leb_u32(body.len() as u32, out);
+        out.extend(body);
+    }
+
+    fn name(s: &str, out: &mut Vec<u8>) {
+        leb_u32(s.len() as u32, out);
+        out.extend(s.as_bytes());
+    }
+
+    /// Build a module exporting `memory` (with `data` at offset 16) and the
+    /// given functions, each `() -> i64` returning a constant
+    fn module_bytes(data: &[u8], exports: &[(&str, i64)]) -> Vec<u8> {
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        // type 0: () -> i64
+        section(1, vec![1, 0x60, 0, 1, 0x7e], &mut wasm);
+        let mut funcs = Vec::new();
+        leb_u32(exports.len() as u32, &mut funcs);
+        funcs.extend(std::iter::repeat_n(0, exports.len()));
+        section(3, funcs, &mut wasm);
+        // one memory, min 1 page
+        section(5, vec![1, 0, 1], &mut wasm);
+
+        let mut export_section = Vec::new();
+        leb_u32(exports.len() as u32 + 1, &mut export_section);
+        name("memory", &mut export_section);
+        export_section.extend([2, 0]);
+        for (i, (export, _)) in exports.iter().enumerate() {
+            name(export, &mut export_section);
+            export_section.push(0);
+            leb_u32(i as u32, &mut export_section);
+        }
+        section(7, export_section, &mut wasm);
+
+        let mut code = Vec::new();
+        leb_u32(exports.len() as u32, &mut code);
+        for (_, value) in exports {
+            let mut body = vec![0, 0x42];
+            leb_i64(*value, &mut body);
+            body.push(0x0b);
+            leb_u32(body.len() as u32, &mut code);
+            code.extend(body);
+        }
+        section(10, code, &mut wasm);
+
+        let mut data_section = vec![1, 0, 0x41, 16, 0x0b];
+        leb_u32(data.len() as u32, &mut data_section);
+        data_section.extend(data);
+        section(11, data_section, &mut wasm);
+        wasm
+    }
+
+    /// Build a module whose `tpu_doc_run` calls the imported
+    /// `(ptr, len, buf_ptr, buf_len) -> i32` function `module::field` with
+    /// `arg` (stored at offset 16) and a 1 KiB buffer at offset 256, then
+    /// passes the buffer to `tpu_doc::emit_result`
+    fn host_call_module(module: &str, field: &str, arg: &str) -> Vec<u8> {
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        // types: () -> i64, (i32, i32, i32, i32) -> i32, (i32, i32) -> ()
+        let types = vec![3, 0x60, 0, 1, 0x7e, 0x60, 4, 0x7f, 0x7f, 0x7f, 0x7f, 1, 0x7f, 0x60, 2, 0x7f, 0x7f, 0];
+        section(1, types, &mut wasm);
+
+        let mut imports = vec![2];
+        name(module, &mut imports);
+        name(field, &mut imports);
+        imports.extend([0, 1]);
+        name(HOST_MODULE, &mut imports);
+        name("emit_result", &mut imports);
+        imports.extend([0, 2]);
+        section(2, imports, &mut wasm);
+        section(3, vec![1, 0], &mut wasm);
+        section(5, vec![1, 0, 1], &mut wasm);
+
+        let mut exports = vec![2];
+        name("memory", &mut exports);
+        exports.extend([2, 0]);
+        name(RUN_EXPORT, &mut exports);
+        exports.extend([0, 2]);
+        section(7, exports, &mut wasm);
+
+        // emit_result(256, field(16, len(arg), 256, 1024)); return 0
+        let mut body = vec![0];
+        for value in [256, 16, arg.len() as i64, 256, 1024] {
+            body.push(0x41);
+            leb_i64(value, &mut body);
+        }
+        body.extend([0x10, 0, 0x10, 1, 0x42, 0, 0x0b]);
+        let mut code = vec![1];
+        leb_u32(body.len() as u32, &mut code);
+        code.extend(body);
+        section(10, code, &mut wasm);
+
+        let mut data_section = vec![1, 0, 0x41, 16, 0x0b];
+        name(arg, &mut data_section);
+        section(11, data_section, &mut wasm);
+        wasm
+    }
+
+    /// Packed pointer to `len` bytes at the data offset used by `module_bytes`
+    fn packed(offset: usize, len: usize) -> i64 {
+        (((16 + offset) as i64) << 32) | len as i64
+    }
+
+    #[test]
+    fn test_call_json_export() {
+        let dir = std::env::temp_dir().join(format!("tpu-doc-wasm-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let json = br#"{"status": "pass"}"#;
+        let path = dir.join("check.wasm");
+        fs::write(
+            &path,
+            module_bytes(json, &[(RUN_EXPORT, packed(0, json.len())), ("out_of_bounds", packed(70000, 4))]),
+        )
+        .unwrap();
+
+        assert_eq!(call_json_export(&path, RUN_EXPORT).unwrap(), r#"{"status": "pass"}"#);
+        assert!(call_json_export(&path, METADATA_EXPORT).unwrap_err().contains("does not export"));
+        assert!(call_json_export(&path, "out_of_bounds").unwrap_err().contains("outside module memory"));
+
+        fs::write(dir.join("bad.wasm"), b"not wasm").unwrap();
+        assert!(call_json_export(&dir.join("bad.wasm"), RUN_EXPORT).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_host_functions() {
+        let dir = std::env::temp_dir().join(format!("tpu-doc-wasm-host-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let run = |name: &str, wasm: Vec<u8>| {
+            let path = dir.join(name);
+            fs::write(&path, wasm).unwrap();
+            call_json_export(&path, RUN_EXPORT)
+        };
+
+        std::env::set_var("TPU_DOC_WASM_TEST_RESULT", r#"{"status": "pass"}"#);
+        let env = host_call_module(HOST_MODULE, "env_get", "TPU_DOC_WASM_TEST_RESULT");
+        assert_eq!(run("env.wasm", env).unwrap(), r#"{"status": "pass"}"#);
+
+        let ostype = fs::read_to_string("/proc/sys/kernel/ostype").unwrap();
+        let proc_file = host_call_module(HOST_MODULE, "read_file", "/proc/sys/kernel/ostype");
+        assert_eq!(run("proc.wasm", proc_file).unwrap(), ostype);
+
+        // Files outside /proc and /sys read as missing (-1), which emit_result rejects
+        let escape = host_call_module(HOST_MODULE, "read_file", "/proc/self/root/etc/passwd");
+        assert!(run("escape.wasm", escape).unwrap_err().contains("invalid length -1"));
+
+        let wasi = host_call_module("wasi_snapshot_preview1", "fd_write", "");
+        let err = run("wasi.wasm", wasi).unwrap_err();
+        assert!(err.contains("imports 'wasi_snapshot_preview1::fd_write'"), "{}", err);
+
+        assert!(readable_host_path("/proc/self/status").is_some());
+        assert!(readable_host_path("/etc/passwd").is_none());
+        assert!(readable_host_path("/proc/self/root/etc/passwd").is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_host_function_denials() {
+        let dir = std::env::temp_dir().join(format!("tpu-doc-wasm-deny-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let run = |name: &str, wasm: Vec<u8>| {
+            let path = dir.join(name);
+            fs::write(&path, wasm).unwrap();
+            call_json_export(&path, RUN_EXPORT)
+        };
+
+        // Variables outside the allowed prefixes read as unset, even when set
+        std::env::set_var("TPU_DOC_WASM_TEST_SECRET", "exposed");
+        std::env::set_var("ANTHROPIC_API_KEY_TPU_DOC_WASM_TEST", "sk-secret");
+        assert!(env_name_allowed("TPU_DOC_WASM_TEST_SECRET"));
+        for name in ["JAX_PLATFORMS", "XLA_FLAGS", "LIBTPU_INIT_ARGS"] {
+            assert!(env_name_allowed(name), "{}", name);
+        }
+        for name in ["ANTHROPIC_API_KEY_TPU_DOC_WASM_TEST", "HOME", "PATH", "tpu_lowercase", "XTPU_FOO", ""] {
+            assert!(!env_name_allowed(name), "{}", name);
+        }
+        let secret = host_call_module(HOST_MODULE, "env_get", "ANTHROPIC_API_KEY_TPU_DOC_WASM_TEST");
+        assert!(run("secret.wasm", secret).unwrap_err().contains("invalid length -1"));
+
+        // Our own environment, memory and command line, directly or via a task
+        let tid = fs::read_dir("/proc/self/task").unwrap().next().unwrap().unwrap().file_name();
+        let task = format!("/proc/self/task/{}", tid.to_string_lossy());
+        for file in DENIED_PROC_FILES {
+            assert!(readable_host_path(&format!("/proc/self/{}", file)).is_none(), "{}", file);
+            assert!(readable_host_path(&format!("/proc/{}/{}", std::process::id(), file)).is_none(), "{}", file);
+            assert!(readable_host_path(&format!("{}/{}", task, file)).is_none(), "{}", file);
+        }
+        let environ = host_call_module(HOST_MODULE, "read_file", "/proc/self/environ");
+        assert!(run("environ.wasm", environ).unwrap_err().contains("invalid length -1"));
+
+        // Another process's entries, including otherwise harmless ones
+        let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+        let other = format!("/proc/{}", child.id());
+        for file in ["status", "environ", "mem", "cmdline"] {
+            assert!(readable_host_path(&format!("{}/{}", other, file)).is_none(), "{}", file);
+        }
+        assert!(readable_host_path("/proc/1/status").is_none());
+        let status = host_call_module(HOST_MODULE, "read_file", &format!("{}/status", other));
+        assert!(run("other.wasm", status).unwrap_err().contains("invalid length -1"));
+        let _ = child.kill();
+        let _ = child.wait();
+
+        // System-wide and own non-sensitive entries stay readable
+        assert!(readable_host_path("/proc/self/status").is_some());
+        assert!(readable_host_path(&format!("{}/status", task)).is_some());
+        assert!(readable_host_path("/proc/meminfo").is_some());
+        assert!(readable_host_path("/proc/sys/kernel/ostype").is_some());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_discover_and_run_wasm_plugin() {
+        use crate::checks::plugins::{self, PluginKind};
+        use crate::CheckResult;
+
+        let dir = std::env::temp_dir().join(format!("tpu-doc-wasm-plugins-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::set_permissions(&dir, std::os::unix::fs::PermissionsExt::from_mode(0o755)).unwrap();
+
+        let metadata = br#"{"id": "ORG-030", "name": "Sandboxed", "category": "security"}"#;
+        let result = br#"{"status": "fail", "message": "policy violated", "details": "rule 7"}"#;
+        let data = [&metadata[..], &result[..]].concat();
+        fs::write(
+            dir.join("30-sandboxed.wasm"),
+            module_bytes(
+                &data,
+                &[
+                    (METADATA_EXPORT, packed(0, metadata.len())),
+                    (RUN_EXPORT, packed(metadata.len(), result.len())),
+                ],
+            ),
+        )
+        .unwrap();
+
+        let load = plugins::discover(std::slice::from_ref(&dir), &[]);
+        assert!(load.errors.is_empty(), "{:?}", load.errors);
+        assert_eq!(load.plugins.len(), 1);
+        assert_eq!(load.plugins[0].kind, PluginKind::Wasm);
+        assert_eq!(load.plugins[0].name, "Sandboxed");
+
+        match plugins::run_plugin_check(&load.plugins[0]) {
+            CheckResult::Fail { message, details, .. } => {
+                assert_eq!(message, "policy violated");
+                assert_eq!(details, "rule 7");
+            }
+            other => panic!("expected fail, got {:?}", other),
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
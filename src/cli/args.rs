@@ -2,6 +2,12 @@
 //!
 //! Handles command line argument parsing without external dependencies.
 
+use crate::checks::benchmark::PerfLevel;
+use crate::checks::io::parse_checkpoint_size;
+use crate::cli::output::{self, StatusGlyphs};
+use crate::commands::snapshot::AlertThreshold;
+use crate::util;
+use crate::CheckTag;
 use std::env;
 
 #[cfg(feature = "ai")]
@@ -14,16 +20,20 @@ pub enum AiProvider {
     #[default]
     Anthropic,
     Google,
+    Vertex,
 }
 
 #[cfg(not(feature = "ai"))]
-impl AiProvider {
-    pub fn from_str(s: &str) -> Result<Self, String> {
+impl std::str::FromStr for AiProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
         match s.to_lowercase().as_str() {
             "anthropic" | "claude" => Ok(AiProvider::Anthropic),
             "google" | "gemini" => Ok(AiProvider::Google),
+            "vertex" | "vertex-ai" | "vertexai" => Ok(AiProvider::Vertex),
             _ => Err(format!(
-                "Unknown AI provider: '{}'. Valid providers: anthropic, google",
+                "Unknown AI provider: '{}'. Valid providers: anthropic, google, vertex",
                 s
             )),
         }
@@ -52,6 +62,12 @@ pub enum Command {
     Audit,
     /// AI-powered log analysis
     Analyze,
+    /// Look up known issues for the installed stack
+    KnownIssues,
+    /// Run checks under a live dashboard
+    Tui,
+    /// Verify the signature of a signed JSON report
+    Verify,
 }
 
 /// Output format selection
@@ -64,16 +80,127 @@ pub enum OutputFormat {
     Json,
     /// JUnit XML for CI/CD integration
     Junit,
+    /// A formatter registered with [`register_formatter`](crate::cli::output::register_formatter),
+    /// by its lower-case name
+    Custom(String),
 }
 
+/// Names of the output formats tpu-doc provides
+pub const BUILTIN_FORMATS: [&str; 3] = ["text", "json", "junit"];
+
 impl OutputFormat {
     fn from_str(s: &str) -> Result<Self, String> {
-        match s.to_lowercase().as_str() {
+        let name = s.to_lowercase();
+        match name.as_str() {
             "text" => Ok(OutputFormat::Text),
             "json" => Ok(OutputFormat::Json),
             "junit" => Ok(OutputFormat::Junit),
-            _ => Err(format!("Unknown output format: '{}'. Valid formats: text, json, junit", s)),
+            _ => {
+                let registered = output::registered_formatters();
+                if registered.contains(&name) {
+                    return Ok(OutputFormat::Custom(name));
+                }
+                let valid: Vec<String> = BUILTIN_FORMATS.iter().map(|f| f.to_string()).chain(registered).collect();
+                Err(format!("Unknown output format: '{}'. Valid formats: {}", s, valid.join(", ")))
+            }
+        }
+    }
+}
+
+/// How the text report groups checks
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum GroupBy {
+    /// One section per check category
+    #[default]
+    Category,
+    /// Failures first, then warnings, passes, and skipped checks
+    Status,
+}
+
+impl GroupBy {
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "category" => Ok(GroupBy::Category),
+            "status" => Ok(GroupBy::Status),
+            _ => Err(format!("Unknown grouping: '{}'. Valid groupings: category, status", s)),
+        }
+    }
+}
+
+/// The lowest audit status that makes `audit` exit non-zero
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FailOn {
+    /// Exit non-zero on warnings and failures
+    #[default]
+    Warning,
+    /// Exit non-zero on failures only
+    Error,
+}
+
+impl FailOn {
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "warning" | "warn" => Ok(FailOn::Warning),
+            "error" | "fail" => Ok(FailOn::Error),
+            _ => Err(format!("Unknown fail level: '{}'. Valid levels: warning, error", s)),
+        }
+    }
+}
+
+/// Color scheme for the text report
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Theme {
+    /// Colored status labels
+    #[default]
+    Default,
+    /// Bold status labels on solid backgrounds
+    HighContrast,
+}
+
+impl Theme {
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "default" => Ok(Theme::Default),
+            "high-contrast" | "high_contrast" => Ok(Theme::HighContrast),
+            _ => Err(format!("Unknown theme: '{}'. Valid themes: default, high-contrast", s)),
+        }
+    }
+}
+
+/// How errors that stop a command are written to stderr
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ErrorFormat {
+    /// A human-readable message and hint
+    #[default]
+    Text,
+    /// One JSON object, for orchestration tooling
+    Json,
+}
+
+impl ErrorFormat {
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(ErrorFormat::Text),
+            "json" => Ok(ErrorFormat::Json),
+            _ => Err(format!("Unknown error format: '{}'. Valid formats: text, json", s)),
+        }
+    }
+
+    /// The error format `args` selects, found without parsing the other
+    /// arguments, so that invalid arguments are reported in it too
+    pub fn scan(args: &[String]) -> Self {
+        let mut format = ErrorFormat::default();
+        for (i, arg) in args.iter().enumerate() {
+            let value = match arg.strip_prefix("--errors=") {
+                Some(value) => Some(value),
+                None if arg == "--errors" => args.get(i + 1).map(String::as_str),
+                None => None,
+            };
+            if let Some(parsed) = value.and_then(|v| Self::from_str(v).ok()) {
+                format = parsed;
+            }
         }
+        format
     }
 }
 
@@ -97,13 +224,32 @@ pub enum CategoryFilter {
     Config,
 }
 
+impl CategoryFilter {
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "all" => Ok(CategoryFilter::All),
+            "hardware" => Ok(CategoryFilter::Hardware),
+            "stack" => Ok(CategoryFilter::Stack),
+            "performance" => Ok(CategoryFilter::Performance),
+            "io" | "i/o" => Ok(CategoryFilter::Io),
+            "security" => Ok(CategoryFilter::Security),
+            "config" => Ok(CategoryFilter::Config),
+            _ => Err(format!(
+                "Unknown category: '{}'. Valid categories: hardware, stack, performance, io, security, config",
+                s
+            )),
+        }
+    }
+}
+
 /// Parsed command line arguments
 #[derive(Debug, Clone)]
 pub struct Args {
     /// Command to execute
     pub command: Command,
-    /// Category filter
-    pub category: CategoryFilter,
+    /// Categories to run, from `--category` lists and the category flags;
+    /// empty runs every category
+    pub categories: Vec<CategoryFilter>,
     /// Specific checks to skip (by ID)
     pub skip: Vec<String>,
     /// Specific checks to run (by ID)
@@ -116,22 +262,94 @@ pub struct Args {
     pub verbose: bool,
     /// Disable colored output
     pub no_color: bool,
+    /// Deprecated environment variables that were set, one message each
+    pub env_warnings: Vec<String>,
+    /// Format of errors that stop the command
+    pub errors: ErrorFormat,
+    /// Show when each check ran after the report
+    pub timeline: bool,
+    /// Grouping of checks in the text report
+    pub group_by: GroupBy,
+    /// Color scheme for the text report
+    pub theme: Theme,
+    /// Status labels for the text report, replacing `[PASS]`, `[WARN]`, ...
+    pub glyphs: Option<StatusGlyphs>,
+    /// Restrict text output to ASCII characters
+    pub ascii: bool,
+    /// Print only the summary counts and exit code
+    pub summary_only: bool,
+    /// Write the JSON report on a single line (implies `--format json`)
+    pub json_compact: bool,
+    /// File to write a shields.io badge of the overall result to
+    pub badge_file: Option<String>,
     /// Global timeout in milliseconds
     pub timeout_ms: u64,
+    /// Timeout in milliseconds for each network connection or request
+    /// (None = configuration file or default)
+    pub net_timeout_ms: Option<u64>,
+    /// Timeout in milliseconds for each benchmark, also applied to the
+    /// performance checks in place of `timeout_ms` (None = configuration
+    /// file or default)
+    pub bench_timeout_ms: Option<u64>,
+    /// Budget in seconds for the whole check run
+    pub max_duration_secs: Option<u64>,
+    /// Reuse cacheable check results younger than this many seconds
+    pub cached_ttl_secs: Option<u64>,
+    /// Offer fixes for whitelisted findings and re-run the fixed checks
+    pub fix: bool,
+    /// Only list checks with all of these tags
+    pub tags: Vec<CheckTag>,
     /// Run checks in parallel
     pub parallel: bool,
     /// Stop on first failure
     pub fail_fast: bool,
+    /// Run the benchmarks with fixed reference parameters
+    pub deterministic: bool,
+    /// How hard the performance checks push the TPU (None = standard)
+    pub perf_level: Option<PerfLevel>,
+    /// Read GCP resources with `gcloud` when the API requests fail
+    pub gcloud_fallback: bool,
+    /// Expected size of one checkpoint in bytes, for IO-004
+    pub checkpoint_size_bytes: Option<u64>,
+    /// Checkpoints kept before the oldest is deleted, for IO-004
+    pub checkpoint_keep: Option<u32>,
+    /// ID to report the run under instead of a generated one
+    pub run_id: Option<String>,
+    /// Sign the JSON report with the `[signing]` key
+    pub sign: bool,
+    /// Lowest audit status that fails the audit command (None = warning)
+    pub fail_on: Option<FailOn>,
     /// Configuration file path
     pub config: Option<String>,
     /// Baseline file path for comparison
     pub baseline: Option<String>,
     /// Show help
     pub help: bool,
+    /// Include installed Python packages (for info command)
+    pub packages: bool,
+    /// Saved info snapshot to compare (for info command)
+    pub diff: Option<String>,
+    /// Second saved snapshot to compare against instead of the current
+    /// environment (for info command)
+    pub diff_against: Option<String>,
     /// Show compatibility matrix (for stack command)
     pub show_matrix: bool,
+    /// URL of a newer compatibility matrix to fetch (for stack command)
+    pub matrix_url: Option<String>,
     /// Continuous refresh interval in seconds (for snapshot command)
     pub continuous: u32,
+    /// Disable the in-place terminal view for `--continuous` (plain scrolling output)
+    pub no_tty: bool,
+    /// Sampling window in seconds (for snapshot command)
+    pub duration_secs: Option<u64>,
+    /// Seconds between samples within the sampling window
+    pub interval_secs: Option<u64>,
+    /// File to append sampled records to (JSONL)
+    pub output_file: Option<String>,
+    /// Alert thresholds overriding the defaults (for snapshot command)
+    pub alerts: Vec<AlertThreshold>,
+    /// Take one snapshot and exit with its alert status (for snapshot command)
+    pub once: bool,
     /// Enable AI-powered analysis (for analyze command)
     pub ai_enabled: bool,
     /// AI provider to use
@@ -140,38 +358,212 @@ pub struct Args {
     pub ai_model: Option<String>,
     /// User question for AI analysis
     pub ai_question: Option<String>,
+    /// Maximum tokens in the final AI response
+    pub ai_max_tokens: Option<u32>,
+    /// Base URL replacing the AI provider's API host (e.g. an internal gateway)
+    pub ai_base_url: Option<String>,
+    /// Bypass the on-disk AI response cache
+    pub ai_no_cache: bool,
+    /// Time-to-live for cached AI responses, in seconds
+    pub ai_cache_ttl_secs: Option<u64>,
+    /// Send content to the AI provider without redacting secrets, IPs, etc.
+    pub ai_no_redact: bool,
+    /// Interactive session (check picker for check, follow-up questions for analyze)
+    pub interactive: bool,
     /// Log file path (for analyze command)
     pub log_file: Option<String>,
+    /// Signed report to check (for verify command)
+    pub report_file: Option<String>,
+    /// Ed25519 key file to verify with instead of the `[signing]` key
+    pub key_file: Option<String>,
+    /// Run `cache clean` instead of analysis
+    pub cache_clean: bool,
+    /// Minimum entry age in seconds for `cache clean`
+    pub older_than_secs: Option<u64>,
+    /// Report what would change without modifying anything
+    pub dry_run: bool,
 }
 
 impl Default for Args {
     fn default() -> Self {
         Args {
             command: Command::default(),
-            category: CategoryFilter::default(),
+            categories: Vec::new(),
             skip: Vec::new(),
             only: Vec::new(),
             format: OutputFormat::default(),
             quiet: false,
             verbose: false,
             no_color: false,
+            env_warnings: Vec::new(),
+            errors: ErrorFormat::default(),
+            timeline: false,
+            group_by: GroupBy::default(),
+            theme: Theme::default(),
+            glyphs: None,
+            ascii: false,
+            summary_only: false,
+            json_compact: false,
+            badge_file: None,
             timeout_ms: 30000,
+            net_timeout_ms: None,
+            bench_timeout_ms: None,
+            max_duration_secs: None,
+            cached_ttl_secs: None,
+            fix: false,
+            tags: Vec::new(),
             parallel: false,
             fail_fast: false,
+            deterministic: false,
+            perf_level: None,
+            gcloud_fallback: false,
+            checkpoint_size_bytes: None,
+            checkpoint_keep: None,
+            run_id: None,
+            sign: false,
+            fail_on: None,
             config: None,
             baseline: None,
             help: false,
+            packages: false,
+            diff: None,
+            diff_against: None,
             show_matrix: false,
+            matrix_url: None,
             continuous: 0,
+            no_tty: false,
+            duration_secs: None,
+            alerts: Vec::new(),
+            once: false,
+            interval_secs: None,
+            output_file: None,
             ai_enabled: false,
             ai_provider: None,
             ai_model: None,
             ai_question: None,
+            ai_max_tokens: None,
+            ai_base_url: None,
+            ai_no_cache: false,
+            ai_no_redact: false,
+            interactive: false,
+            ai_cache_ttl_secs: None,
             log_file: None,
+            report_file: None,
+            key_file: None,
+            cache_clean: false,
+            older_than_secs: None,
+            dry_run: false,
         }
     }
 }
 
+/// Parse a positive token count for `--max-tokens`
+fn parse_max_tokens(s: &str) -> Result<u32, String> {
+    match s.parse::<u32>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("Invalid max tokens value: '{}'", s)),
+    }
+}
+
+/// Add `category` to the selected categories; `All` clears the selection
+fn add_category(categories: &mut Vec<CategoryFilter>, category: CategoryFilter) {
+    if category == CategoryFilter::All {
+        categories.clear();
+    } else if !categories.contains(&category) {
+        categories.push(category);
+    }
+}
+
+/// Add each category in a comma-separated `--category` list
+fn add_category_list(categories: &mut Vec<CategoryFilter>, list: &str) -> Result<(), String> {
+    for name in list.split(',').map(str::trim) {
+        add_category(categories, CategoryFilter::from_str(name)?);
+    }
+    Ok(())
+}
+
+/// Whether `arg` selects categories, replacing `TPU_PREFLIGHT_CATEGORIES`
+fn is_category_flag(arg: &str) -> bool {
+    matches!(
+        arg,
+        "--all" | "--hardware" | "--stack" | "--performance" | "--io" | "--security" | "--config-audit" | "--category"
+    ) || arg.starts_with("--category=")
+}
+
+/// Parse a benchmark intensity for `--perf-level`
+fn parse_perf_level(s: &str) -> Result<PerfLevel, String> {
+    PerfLevel::from_name(s)
+        .ok_or_else(|| format!("Unknown perf level: '{}'. Valid levels: smoke, standard, soak", s))
+}
+
+/// Parse a check tag for `--tag`
+fn parse_tag(s: &str) -> Result<CheckTag, String> {
+    CheckTag::from_name(s).ok_or_else(|| {
+        format!("Unknown tag: '{}'. Valid tags: cpu, network, tpu-exclusive, cacheable, sustained", s)
+    })
+}
+
+/// Parse a non-zero timeout in milliseconds for `--net-timeout` and
+/// `--bench-timeout`
+fn parse_timeout_ms(s: &str) -> Result<u64, String> {
+    match s.trim().parse::<u64>() {
+        Ok(ms) if ms > 0 => Ok(ms),
+        _ => Err(format!("Invalid timeout value: '{}' (expected milliseconds, at least 1)", s)),
+    }
+}
+
+/// Parse a non-zero run budget for `--max-duration`
+fn parse_max_duration(s: &str) -> Result<u64, String> {
+    match parse_duration_secs(s)? {
+        0 => Err(format!("Invalid max duration: '{}' (must be at least 1s)", s)),
+        secs => Ok(secs),
+    }
+}
+
+/// Parse a duration such as `90s`, `15m`, `1h`, `30d`, or `2w` into seconds.
+///
+/// A bare number is interpreted as seconds.
+/// Parse the number of checkpoints `--keep` retains (at least 1)
+fn parse_checkpoint_keep(s: &str) -> Result<u32, String> {
+    s.parse::<u32>()
+        .ok()
+        .filter(|keep| *keep >= 1)
+        .ok_or_else(|| format!("Invalid --keep value: '{}' (expected a whole number of at least 1)", s))
+}
+
+/// Longest `--run-id` accepted
+const MAX_RUN_ID_LEN: usize = 128;
+
+/// Parse a `--run-id`: up to 128 printable ASCII characters without spaces,
+/// so it can be passed on unquoted and used in file names and labels
+fn parse_run_id(s: &str) -> Result<String, String> {
+    if s.is_empty() || s.len() > MAX_RUN_ID_LEN || !s.bytes().all(|b| b.is_ascii_graphic()) {
+        return Err(format!(
+            "Invalid run ID: '{}' (expected 1 to {} printable ASCII characters without spaces)",
+            s, MAX_RUN_ID_LEN
+        ));
+    }
+    Ok(s.to_string())
+}
+
+pub fn parse_duration_secs(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (number, multiplier) = match s.chars().last() {
+        Some('s') => (&s[..s.len() - 1], 1),
+        Some('m') => (&s[..s.len() - 1], 60),
+        Some('h') => (&s[..s.len() - 1], 3600),
+        Some('d') => (&s[..s.len() - 1], 86400),
+        Some('w') => (&s[..s.len() - 1], 7 * 86400),
+        _ => (s, 1),
+    };
+
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| format!("Invalid duration: '{}' (expected e.g. 30s, 15m, 1h, 30d)", s))
+}
+
 impl Args {
     /// Parse command line arguments from std::env::args()
     pub fn parse() -> Result<Self, String> {
@@ -181,24 +573,69 @@ impl Args {
 
     /// Parse command line arguments from a slice (for testing)
     pub fn parse_from(args: &[String]) -> Result<Self, String> {
+        Self::parse_with_env(args, &|var| env::var(var).ok())
+    }
+
+    /// Parse command line arguments over the settings `env` looks up.
+    ///
+    /// Each `TPU_PREFLIGHT_*` variable sets the default for its option and
+    /// the command line overrides it; a deprecated `TPU_DOC_*` name is read
+    /// when the new one is unset.
+    fn parse_with_env(args: &[String], env: &dyn Fn(&str) -> Option<String>) -> Result<Self, String> {
         let mut result = Args::default();
         let mut i = 0;
 
         // Check for NO_COLOR environment variable
-        if env::var("NO_COLOR").is_ok() {
+        if env("NO_COLOR").is_some() {
             result.no_color = true;
         }
 
         // Check for environment variable overrides
-        if let Ok(format) = env::var("TPU_DOC_FORMAT") {
-            result.format = OutputFormat::from_str(&format)?;
+        let setting = |name: &str| util::env_setting_with(name, env);
+        if let Some(format) = setting("TPU_PREFLIGHT_FORMAT") {
+            result.format = OutputFormat::from_str(&format).map_err(|e| format!("TPU_PREFLIGHT_FORMAT: {}", e))?;
         }
-        if env::var("TPU_DOC_VERBOSE").is_ok() {
+        if env("TPU_PREFLIGHT_VERBOSE").map_or(env("TPU_DOC_VERBOSE").is_some(), |v| util::parse_env_flag(&v)) {
             result.verbose = true;
         }
-        if let Ok(config) = env::var("TPU_DOC_CONFIG") {
+        if let Some(config) = setting("TPU_PREFLIGHT_CONFIG") {
             result.config = Some(config);
         }
+        if let Some(timeout) = setting("TPU_PREFLIGHT_TIMEOUT") {
+            result.timeout_ms = timeout
+                .trim()
+                .parse()
+                .map_err(|_| format!("TPU_PREFLIGHT_TIMEOUT: Invalid timeout value: '{}'", timeout))?;
+        }
+        if let Some(timeout) = setting("TPU_PREFLIGHT_NET_TIMEOUT") {
+            result.net_timeout_ms =
+                Some(parse_timeout_ms(&timeout).map_err(|e| format!("TPU_PREFLIGHT_NET_TIMEOUT: {}", e))?);
+        }
+        if let Some(timeout) = setting("TPU_PREFLIGHT_BENCH_TIMEOUT") {
+            result.bench_timeout_ms =
+                Some(parse_timeout_ms(&timeout).map_err(|e| format!("TPU_PREFLIGHT_BENCH_TIMEOUT: {}", e))?);
+        }
+        if let Some(parallel) = setting("TPU_PREFLIGHT_PARALLEL") {
+            result.parallel = util::parse_env_flag(&parallel);
+        }
+        let mut env_categories = Vec::new();
+        if let Some(categories) = setting("TPU_PREFLIGHT_CATEGORIES") {
+            add_category_list(&mut env_categories, &categories)
+                .map_err(|e| format!("TPU_PREFLIGHT_CATEGORIES: {}", e))?;
+        }
+        if let Some(output) = setting("TPU_PREFLIGHT_OUTPUT") {
+            result.output_file = Some(output);
+        }
+        if let Some(url) = setting(crate::data::compatibility::MATRIX_URL_ENV) {
+            if !url.trim().is_empty() {
+                result.matrix_url = Some(url.trim().to_string());
+            }
+        }
+        for (current, deprecated) in util::DEPRECATED_ENV_VARS {
+            if env(deprecated).is_some() {
+                result.env_warnings.push(format!("{} is deprecated; use {} instead", deprecated, current));
+            }
+        }
 
         while i < args.len() {
             let arg = &args[i];
@@ -214,19 +651,36 @@ impl Args {
                 "snapshot" => result.command = Command::Snapshot,
                 "audit" => result.command = Command::Audit,
                 "analyze" => result.command = Command::Analyze,
+                "known-issues" => result.command = Command::KnownIssues,
+                "tui" => result.command = Command::Tui,
+                "verify" => result.command = Command::Verify,
 
                 // Help flags
                 "-h" | "--help" => result.help = true,
                 "-V" | "--version" => result.command = Command::Version,
 
                 // Category filters
-                "--all" => result.category = CategoryFilter::All,
-                "--hardware" => result.category = CategoryFilter::Hardware,
-                "--stack" => result.category = CategoryFilter::Stack,
-                "--performance" => result.category = CategoryFilter::Performance,
-                "--io" => result.category = CategoryFilter::Io,
-                "--security" => result.category = CategoryFilter::Security,
-                "--config-audit" => result.category = CategoryFilter::Config,
+                "--all" => add_category(&mut result.categories, CategoryFilter::All),
+                "--hardware" => add_category(&mut result.categories, CategoryFilter::Hardware),
+                "--stack" => add_category(&mut result.categories, CategoryFilter::Stack),
+                "--performance" => add_category(&mut result.categories, CategoryFilter::Performance),
+                "--io" => add_category(&mut result.categories, CategoryFilter::Io),
+                "--security" => add_category(&mut result.categories, CategoryFilter::Security),
+                "--config-audit" => add_category(&mut result.categories, CategoryFilter::Config),
+                "--category" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--category requires a category name".to_string());
+                    }
+                    add_category_list(&mut result.categories, &args[i])?;
+                }
+                "--tag" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--tag requires a tag name".to_string());
+                    }
+                    result.tags.push(parse_tag(&args[i])?);
+                }
 
                 // Skip/only with value
                 "--skip" => {
@@ -255,6 +709,46 @@ impl Args {
                 "-q" | "--quiet" => result.quiet = true,
                 "-v" | "--verbose" => result.verbose = true,
                 "--no-color" => result.no_color = true,
+                "--timeline" => result.timeline = true,
+                "--fix" => result.fix = true,
+                "--errors" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--errors requires a format (text or json)".to_string());
+                    }
+                    result.errors = ErrorFormat::from_str(&args[i])?;
+                }
+                "--group-by" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--group-by requires a grouping (category or status)".to_string());
+                    }
+                    result.group_by = GroupBy::from_str(&args[i])?;
+                }
+                "--fail-on" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--fail-on requires a level (warning or error)".to_string());
+                    }
+                    result.fail_on = Some(FailOn::from_str(&args[i])?);
+                }
+                "--theme" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--theme requires a theme name (default or high-contrast)".to_string());
+                    }
+                    result.theme = Theme::from_str(&args[i])?;
+                }
+                "--glyphs" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--glyphs requires four comma-separated labels".to_string());
+                    }
+                    result.glyphs = Some(StatusGlyphs::parse(&args[i])?);
+                }
+                "--ascii" => result.ascii = true,
+                "--summary-only" => result.summary_only = true,
+                "--json-compact" => result.json_compact = true,
 
                 // Behavior options
                 "--timeout" => {
@@ -266,8 +760,67 @@ impl Args {
                         .parse()
                         .map_err(|_| format!("Invalid timeout value: '{}'", args[i]))?;
                 }
+                "--net-timeout" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--net-timeout requires a value in milliseconds".to_string());
+                    }
+                    result.net_timeout_ms = Some(parse_timeout_ms(&args[i])?);
+                }
+                "--bench-timeout" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--bench-timeout requires a value in milliseconds".to_string());
+                    }
+                    result.bench_timeout_ms = Some(parse_timeout_ms(&args[i])?);
+                }
+                "--max-duration" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--max-duration requires a duration (e.g. 120s)".to_string());
+                    }
+                    result.max_duration_secs = Some(parse_max_duration(&args[i])?);
+                }
+                "--cached" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--cached requires a maximum age (e.g. 1h)".to_string());
+                    }
+                    result.cached_ttl_secs = Some(parse_duration_secs(&args[i])?);
+                }
                 "--parallel" => result.parallel = true,
                 "--fail-fast" => result.fail_fast = true,
+                "--deterministic" => result.deterministic = true,
+                "--gcloud-fallback" => result.gcloud_fallback = true,
+                "--perf-level" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--perf-level requires a level (smoke, standard, or soak)".to_string());
+                    }
+                    result.perf_level = Some(parse_perf_level(&args[i])?);
+                }
+                "--checkpoint-size" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--checkpoint-size requires a size (e.g. 250GB)".to_string());
+                    }
+                    result.checkpoint_size_bytes = Some(parse_checkpoint_size(&args[i])?);
+                }
+                "--keep" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--keep requires a number of checkpoints".to_string());
+                    }
+                    result.checkpoint_keep = Some(parse_checkpoint_keep(&args[i])?);
+                }
+                "--run-id" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--run-id requires an ID".to_string());
+                    }
+                    result.run_id = Some(parse_run_id(&args[i])?);
+                }
+                "--sign" => result.sign = true,
 
                 // Configuration options
                 "--config" => {
@@ -277,6 +830,13 @@ impl Args {
                     }
                     result.config = Some(args[i].clone());
                 }
+                "--key" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--key requires a key file path".to_string());
+                    }
+                    result.key_file = Some(args[i].clone());
+                }
                 "--baseline" => {
                     i += 1;
                     if i >= args.len() {
@@ -285,8 +845,25 @@ impl Args {
                     result.baseline = Some(args[i].clone());
                 }
 
+                // Info command options
+                "--packages" => result.packages = true,
+                "--diff" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--diff requires a saved info snapshot (info --format json)".to_string());
+                    }
+                    result.diff = Some(args[i].clone());
+                }
+
                 // Stack command options
                 "--matrix" => result.show_matrix = true,
+                "--matrix-url" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--matrix-url requires a URL".to_string());
+                    }
+                    result.matrix_url = Some(args[i].clone());
+                }
 
                 // Snapshot command options
                 "--continuous" => {
@@ -299,14 +876,91 @@ impl Args {
                         .map_err(|_| format!("Invalid continuous value: '{}'", args[i]))?;
                 }
 
+                "--no-tty" => result.no_tty = true,
+
+                "--duration" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--duration requires a duration (e.g. 10m)".to_string());
+                    }
+                    result.duration_secs = Some(parse_duration_secs(&args[i])?);
+                }
+                "--interval" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--interval requires a duration (e.g. 5s)".to_string());
+                    }
+                    result.interval_secs = Some(parse_duration_secs(&args[i])?);
+                }
+                "--badge-file" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--badge-file requires a file path".to_string());
+                    }
+                    result.badge_file = Some(args[i].clone());
+                }
+                "--output" | "-o" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--output requires a file path".to_string());
+                    }
+                    result.output_file = Some(args[i].clone());
+                }
+                "--alert" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--alert requires a threshold (e.g. hbm=95:99)".to_string());
+                    }
+                    result.alerts.push(AlertThreshold::parse(&args[i])?);
+                }
+                "--once" => result.once = true,
+
+                // Cache command options
+                "--older-than" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--older-than requires a duration (e.g. 30d)".to_string());
+                    }
+                    result.older_than_secs = Some(parse_duration_secs(&args[i])?);
+                }
+                "--dry-run" => result.dry_run = true,
+
                 // AI analyze command options
                 "--ai" => result.ai_enabled = true,
+                "--ai-base-url" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--ai-base-url requires a URL".to_string());
+                    }
+                    result.ai_base_url = Some(args[i].clone());
+                }
+
+                "--no-cache" => result.ai_no_cache = true,
+                "--no-redact" => result.ai_no_redact = true,
+                "--interactive" => result.interactive = true,
+
+                "--cache-ttl" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--cache-ttl requires a duration (e.g. 1h, 24h)".to_string());
+                    }
+                    result.ai_cache_ttl_secs = Some(parse_duration_secs(&args[i])?);
+                }
+
+                "--max-tokens" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err("--max-tokens requires a token count".to_string());
+                    }
+                    result.ai_max_tokens = Some(parse_max_tokens(&args[i])?);
+                }
+
                 "--provider" => {
                     i += 1;
                     if i >= args.len() {
-                        return Err("--provider requires a provider name (anthropic, google)".to_string());
+                        return Err("--provider requires a provider name (anthropic, google, vertex)".to_string());
                     }
-                    result.ai_provider = Some(AiProvider::from_str(&args[i])?);
+                    result.ai_provider = Some(args[i].parse::<AiProvider>()?);
                 }
                 "--model" => {
                     i += 1;
@@ -339,9 +993,36 @@ impl Args {
                         .parse()
                         .map_err(|_| format!("Invalid timeout value: '{}'", &arg[10..]))?;
                 }
+                _ if arg.starts_with("--net-timeout=") => {
+                    result.net_timeout_ms = Some(parse_timeout_ms(&arg[14..])?);
+                }
+                _ if arg.starts_with("--bench-timeout=") => {
+                    result.bench_timeout_ms = Some(parse_timeout_ms(&arg[16..])?);
+                }
+                _ if arg.starts_with("--max-duration=") => {
+                    result.max_duration_secs = Some(parse_max_duration(&arg[15..])?);
+                }
+                _ if arg.starts_with("--perf-level=") => {
+                    result.perf_level = Some(parse_perf_level(&arg[13..])?);
+                }
+                _ if arg.starts_with("--cached=") => {
+                    result.cached_ttl_secs = Some(parse_duration_secs(&arg[9..])?);
+                }
+                _ if arg.starts_with("--checkpoint-size=") => {
+                    result.checkpoint_size_bytes = Some(parse_checkpoint_size(&arg[18..])?);
+                }
+                _ if arg.starts_with("--keep=") => {
+                    result.checkpoint_keep = Some(parse_checkpoint_keep(&arg[7..])?);
+                }
+                _ if arg.starts_with("--run-id=") => {
+                    result.run_id = Some(parse_run_id(&arg[9..])?);
+                }
                 _ if arg.starts_with("--config=") => {
                     result.config = Some(arg[9..].to_string());
                 }
+                _ if arg.starts_with("--key=") => {
+                    result.key_file = Some(arg[6..].to_string());
+                }
                 _ if arg.starts_with("--baseline=") => {
                     result.baseline = Some(arg[11..].to_string());
                 }
@@ -350,8 +1031,65 @@ impl Args {
                         .parse()
                         .map_err(|_| format!("Invalid continuous value: '{}'", &arg[13..]))?;
                 }
+                _ if arg.starts_with("--alert=") => {
+                    result.alerts.push(AlertThreshold::parse(&arg[8..])?);
+                }
+                _ if arg.starts_with("--diff=") => {
+                    result.diff = Some(arg[7..].to_string());
+                }
+                _ if arg.starts_with("--matrix-url=") => {
+                    result.matrix_url = Some(arg[13..].to_string());
+                }
+                _ if arg.starts_with("--fail-on=") => {
+                    result.fail_on = Some(FailOn::from_str(&arg[10..])?);
+                }
+                _ if arg.starts_with("--category=") => {
+                    add_category_list(&mut result.categories, &arg[11..])?;
+                }
+                _ if arg.starts_with("--tag=") => {
+                    result.tags.push(parse_tag(&arg[6..])?);
+                }
+                _ if arg.starts_with("--duration=") => {
+                    result.duration_secs = Some(parse_duration_secs(&arg[11..])?);
+                }
+                _ if arg.starts_with("--interval=") => {
+                    result.interval_secs = Some(parse_duration_secs(&arg[11..])?);
+                }
+                _ if arg.starts_with("--errors=") => {
+                    result.errors = ErrorFormat::from_str(&arg[9..])?;
+                }
+                _ if arg.starts_with("--group-by=") => {
+                    result.group_by = GroupBy::from_str(&arg[11..])?;
+                }
+                _ if arg.starts_with("--theme=") => {
+                    result.theme = Theme::from_str(&arg[8..])?;
+                }
+                _ if arg.starts_with("--glyphs=") => {
+                    result.glyphs = Some(StatusGlyphs::parse(&arg[9..])?);
+                }
+                _ if arg.starts_with("--badge-file=") => {
+                    result.badge_file = Some(arg[13..].to_string());
+                }
+                _ if arg.starts_with("--output=") => {
+                    result.output_file = Some(arg[9..].to_string());
+                }
+                _ if arg.starts_with("--older-than=") => {
+                    result.older_than_secs = Some(parse_duration_secs(&arg[13..])?);
+                }
+                _ if arg.starts_with("--ai-base-url=") => {
+                    result.ai_base_url = Some(arg[14..].to_string());
+                }
+
+                _ if arg.starts_with("--cache-ttl=") => {
+                    result.ai_cache_ttl_secs = Some(parse_duration_secs(&arg[12..])?);
+                }
+
+                _ if arg.starts_with("--max-tokens=") => {
+                    result.ai_max_tokens = Some(parse_max_tokens(&arg[13..])?);
+                }
+
                 _ if arg.starts_with("--provider=") => {
-                    result.ai_provider = Some(AiProvider::from_str(&arg[11..])?);
+                    result.ai_provider = Some(arg[11..].parse::<AiProvider>()?);
                 }
                 _ if arg.starts_with("--model=") => {
                     result.ai_model = Some(arg[8..].to_string());
@@ -368,6 +1106,12 @@ impl Args {
                 _ => {
                     if result.command == Command::Analyze && result.log_file.is_none() {
                         result.log_file = Some(arg.clone());
+                    } else if result.command == Command::Info && result.diff.is_some() && result.diff_against.is_none() {
+                        result.diff_against = Some(arg.clone());
+                    } else if result.command == Command::Cache && arg == "clean" {
+                        result.cache_clean = true;
+                    } else if result.command == Command::Verify && result.report_file.is_none() {
+                        result.report_file = Some(arg.clone());
                     } else {
                         return Err(format!("Unexpected argument: '{}'", arg));
                     }
@@ -377,6 +1121,88 @@ impl Args {
             i += 1;
         }
 
+        if !args.iter().any(|arg| is_category_flag(arg)) {
+            result.categories = env_categories;
+        }
+
+        if result.json_compact {
+            let format_given = args.iter().any(|arg| arg == "--format" || arg.starts_with("--format="));
+            if format_given && result.format != OutputFormat::Json {
+                return Err("--json-compact writes json output and cannot be combined with another --format".to_string());
+            }
+            result.format = OutputFormat::Json;
+        }
+        if result.summary_only && result.format == OutputFormat::Junit {
+            return Err("--summary-only supports text and json output, not junit".to_string());
+        }
+        if let OutputFormat::Custom(name) = &result.format {
+            if !matches!(result.command, Command::Check | Command::Tui) {
+                return Err(format!("--format {} applies to the check command only", name));
+            }
+            if result.summary_only {
+                return Err(format!("--summary-only supports text and json output, not {}", name));
+            }
+        }
+        if !result.tags.is_empty() && result.command != Command::List {
+            return Err("--tag filters the list command only".to_string());
+        }
+        if result.packages && result.command != Command::Info {
+            return Err("--packages applies to the info command only".to_string());
+        }
+        if result.diff.is_some() && result.command != Command::Info {
+            return Err("--diff applies to the info command only".to_string());
+        }
+        if result.badge_file.is_some() && !matches!(result.command, Command::Check | Command::Tui) {
+            return Err("--badge-file applies to the check command only".to_string());
+        }
+        if result.deterministic && !matches!(result.command, Command::Check | Command::Tui) {
+            return Err("--deterministic applies to the check command only".to_string());
+        }
+        if result.deterministic && result.cached_ttl_secs.is_some() {
+            return Err("--deterministic cannot be combined with --cached, which may reuse results of other runs".to_string());
+        }
+        if result.gcloud_fallback && !matches!(result.command, Command::Check | Command::Tui) {
+            return Err("--gcloud-fallback applies to the check command only".to_string());
+        }
+        if result.perf_level.is_some() && !matches!(result.command, Command::Check | Command::Tui) {
+            return Err("--perf-level applies to the check command only".to_string());
+        }
+        if result.perf_level.is_some() && result.cached_ttl_secs.is_some() {
+            return Err("--perf-level cannot be combined with --cached, which may reuse results of another level".to_string());
+        }
+        if (result.checkpoint_size_bytes.is_some() || result.checkpoint_keep.is_some())
+            && !matches!(result.command, Command::Check | Command::Tui)
+        {
+            return Err("--checkpoint-size and --keep apply to the check command only".to_string());
+        }
+        if result.run_id.is_some() && !matches!(result.command, Command::Check | Command::Tui) {
+            return Err("--run-id applies to the check command only".to_string());
+        }
+        if result.sign && !matches!(result.command, Command::Check | Command::Tui) {
+            return Err("--sign applies to the check command only".to_string());
+        }
+        if result.sign && result.format != OutputFormat::Json {
+            return Err("--sign signs json reports; use it with --format json".to_string());
+        }
+        if result.key_file.is_some() && result.command != Command::Verify {
+            return Err("--key applies to the verify command only".to_string());
+        }
+        if result.command == Command::Verify && result.report_file.is_none() && !result.help {
+            return Err("verify requires the path of a signed report".to_string());
+        }
+        if result.fail_on.is_some() && result.command != Command::Audit {
+            return Err("--fail-on sets the audit command's exit code only".to_string());
+        }
+        if (result.once || !result.alerts.is_empty()) && result.command != Command::Snapshot {
+            return Err("--alert and --once apply to the snapshot command only".to_string());
+        }
+        if result.once && result.duration_secs.is_some() {
+            return Err("--once takes a single snapshot and cannot be combined with --duration".to_string());
+        }
+        if result.fix && result.format != OutputFormat::Text {
+            return Err("--fix asks for confirmation and requires text output".to_string());
+        }
+
         Ok(result)
     }
 }
@@ -389,7 +1215,7 @@ mod tests {
     fn test_default_args() {
         let args = Args::default();
         assert_eq!(args.command, Command::Check);
-        assert_eq!(args.category, CategoryFilter::All);
+        assert!(args.categories.is_empty());
         assert_eq!(args.format, OutputFormat::Text);
         assert!(!args.quiet);
         assert!(!args.verbose);
@@ -420,12 +1246,61 @@ mod tests {
         assert_eq!(args.command, Command::Stack);
     }
 
+    #[test]
+    fn test_parse_cache_clean() {
+        let args = Args::parse_from(&[
+            "cache".to_string(),
+            "clean".to_string(),
+            "--older-than".to_string(),
+            "30d".to_string(),
+            "--dry-run".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(args.command, Command::Cache);
+        assert!(args.cache_clean);
+        assert!(args.dry_run);
+        assert_eq!(args.older_than_secs, Some(30 * 86400));
+    }
+
+    #[test]
+    fn test_parse_snapshot_sampling() {
+        let args = Args::parse_from(&[
+            "snapshot".to_string(),
+            "--duration".to_string(),
+            "10m".to_string(),
+            "--interval=5s".to_string(),
+            "--output".to_string(),
+            "samples.jsonl".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(args.command, Command::Snapshot);
+        assert_eq!(args.duration_secs, Some(600));
+        assert_eq!(args.interval_secs, Some(5));
+        assert_eq!(args.output_file, Some("samples.jsonl".to_string()));
+    }
+
+    #[test]
+    fn test_parse_duration_secs() {
+        assert_eq!(parse_duration_secs("90"), Ok(90));
+        assert_eq!(parse_duration_secs("15m"), Ok(900));
+        assert_eq!(parse_duration_secs("1h"), Ok(3600));
+        assert_eq!(parse_duration_secs("2w"), Ok(14 * 86400));
+        assert!(parse_duration_secs("soon").is_err());
+        assert!(parse_duration_secs("d").is_err());
+    }
+
     #[test]
     fn test_parse_cache_command() {
         let args = Args::parse_from(&["cache".to_string()]).unwrap();
         assert_eq!(args.command, Command::Cache);
     }
 
+    #[test]
+    fn test_parse_known_issues_command() {
+        let args = Args::parse_from(&["known-issues".to_string()]).unwrap();
+        assert_eq!(args.command, Command::KnownIssues);
+    }
+
     #[test]
     fn test_parse_snapshot_command() {
         let args = Args::parse_from(&["snapshot".to_string()]).unwrap();
@@ -438,16 +1313,129 @@ mod tests {
         assert_eq!(args.command, Command::Audit);
     }
 
+    #[test]
+    fn test_parse_packages_option() {
+        let args = Args::parse_from(&["info".to_string(), "--packages".to_string()]).unwrap();
+        assert!(args.packages);
+        assert!(Args::parse_from(&["check".to_string(), "--packages".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_diff_option() {
+        let parse = |args: &[&str]| Args::parse_from(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>());
+        let args = parse(&["info", "--diff", "old.json"]).unwrap();
+        assert_eq!(args.diff.as_deref(), Some("old.json"));
+        assert_eq!(args.diff_against, None);
+        let args = parse(&["info", "--diff=old.json", "new.json"]).unwrap();
+        assert_eq!(args.diff_against.as_deref(), Some("new.json"));
+        assert!(parse(&["info", "new.json"]).is_err());
+        assert!(parse(&["check", "--diff", "old.json"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_fail_on() {
+        let parse = |args: &[&str]| Args::parse_from(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>());
+        assert_eq!(parse(&["audit"]).unwrap().fail_on, None);
+        assert_eq!(parse(&["audit", "--fail-on", "error"]).unwrap().fail_on, Some(FailOn::Error));
+        assert_eq!(parse(&["audit", "--fail-on=Warning"]).unwrap().fail_on, Some(FailOn::Warning));
+        assert!(parse(&["audit", "--fail-on", "info"]).is_err());
+        assert!(parse(&["check", "--fail-on", "error"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_family_timeouts() {
+        let args = Args::parse_from(&["--net-timeout".to_string(), "2000".to_string()]).unwrap();
+        assert_eq!((args.net_timeout_ms, args.bench_timeout_ms), (Some(2000), None));
+        let args = Args::parse_from(&["--bench-timeout=300000".to_string()]).unwrap();
+        assert_eq!(args.bench_timeout_ms, Some(300000));
+        assert!(Args::parse_from(&["--net-timeout=0".to_string()]).is_err());
+        assert!(Args::parse_from(&["--bench-timeout".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_env_settings() {
+        let parse = |flags: &[&str], vars: &[(&str, &str)]| {
+            let flags: Vec<String> = flags.iter().map(|f| f.to_string()).collect();
+            let lookup = |name: &str| vars.iter().find(|(var, _)| *var == name).map(|(_, value)| value.to_string());
+            Args::parse_with_env(&flags, &lookup)
+        };
+        let vars = [
+            ("TPU_PREFLIGHT_FORMAT", "json"),
+            ("TPU_PREFLIGHT_TIMEOUT", "60000"),
+            ("TPU_PREFLIGHT_PARALLEL", "1"),
+            ("TPU_PREFLIGHT_CATEGORIES", "hardware,io"),
+            ("TPU_PREFLIGHT_OUTPUT", "samples.jsonl"),
+            ("TPU_DOC_FORMAT", "junit"),
+        ];
+
+        let args = parse(&["check"], &vars).unwrap();
+        assert_eq!(args.format, OutputFormat::Json);
+        assert_eq!(args.timeout_ms, 60000);
+        assert!(args.parallel);
+        assert_eq!(args.categories, vec![CategoryFilter::Hardware, CategoryFilter::Io]);
+        assert_eq!(args.output_file.as_deref(), Some("samples.jsonl"));
+        assert_eq!(args.env_warnings, vec!["TPU_DOC_FORMAT is deprecated; use TPU_PREFLIGHT_FORMAT instead"]);
+
+        // The command line overrides the environment
+        let args = parse(&["check", "--format", "text", "--timeout=5000", "--all"], &vars).unwrap();
+        assert_eq!(args.format, OutputFormat::Text);
+        assert_eq!(args.timeout_ms, 5000);
+        assert!(args.categories.is_empty());
+        assert_eq!(parse(&["--security"], &vars).unwrap().categories, vec![CategoryFilter::Security]);
+
+        // Deprecated names still apply when the new name is unset
+        let args = parse(&[], &[("TPU_DOC_FORMAT", "junit"), ("TPU_DOC_VERBOSE", "")]).unwrap();
+        assert_eq!(args.format, OutputFormat::Junit);
+        assert!(args.verbose);
+        assert!(!parse(&[], &[("TPU_PREFLIGHT_PARALLEL", "false")]).unwrap().parallel);
+        let flags = [("1", true), ("true", true), ("0", false), ("false", false), ("Off", false), ("", false)];
+        for (value, expected) in flags {
+            assert_eq!(util::parse_env_flag(value), expected, "{:?}", value);
+        }
+        let lookup = |var: &str| (var == "TPU_DOC_GCS_PREFIX").then(|| "gs://bucket/train/".to_string());
+        assert_eq!(
+            util::env_setting_with("TPU_PREFLIGHT_GCS_PREFIX", &lookup).as_deref(),
+            Some("gs://bucket/train/")
+        );
+        let args = parse(&[], &[("TPU_DOC_TRAINING_SMOKE", "1")]).unwrap();
+        assert_eq!(
+            args.env_warnings,
+            vec!["TPU_DOC_TRAINING_SMOKE is deprecated; use TPU_PREFLIGHT_TRAINING_SMOKE instead"]
+        );
+
+        let err = parse(&[], &[("TPU_PREFLIGHT_CATEGORIES", "gpu")]).unwrap_err();
+        assert!(err.starts_with("TPU_PREFLIGHT_CATEGORIES: Unknown category"));
+    }
+
     #[test]
     fn test_parse_category_filter() {
         let args = Args::parse_from(&["--hardware".to_string()]).unwrap();
-        assert_eq!(args.category, CategoryFilter::Hardware);
+        assert_eq!(args.categories, vec![CategoryFilter::Hardware]);
+    }
+
+    #[test]
+    fn test_parse_multiple_categories() {
+        let parse = |flags: &[&str]| Args::parse_from(&flags.iter().map(|f| f.to_string()).collect::<Vec<_>>());
+
+        let args = parse(&["check", "--category", "hardware, io,security", "--category=io", "--stack"]).unwrap();
+        assert_eq!(
+            args.categories,
+            vec![
+                CategoryFilter::Hardware,
+                CategoryFilter::Io,
+                CategoryFilter::Security,
+                CategoryFilter::Stack
+            ]
+        );
+        assert!(parse(&["--hardware", "--all"]).unwrap().categories.is_empty());
+        assert!(parse(&["--category", "hardware,"]).unwrap_err().contains("Unknown category: ''"));
+        assert!(parse(&["--category", "hardware,gpu"]).is_err());
     }
 
     #[test]
     fn test_parse_config_audit_filter() {
         let args = Args::parse_from(&["--config-audit".to_string()]).unwrap();
-        assert_eq!(args.category, CategoryFilter::Config);
+        assert_eq!(args.categories, vec![CategoryFilter::Config]);
     }
 
     #[test]
@@ -462,6 +1450,141 @@ mod tests {
         assert_eq!(args.format, OutputFormat::Json);
     }
 
+    #[test]
+    fn test_parse_error_format() {
+        let args = Args::parse_from(&["--errors".to_string(), "json".to_string()]).unwrap();
+        assert_eq!(args.errors, ErrorFormat::Json);
+        assert_eq!(Args::default().errors, ErrorFormat::Text);
+        assert!(Args::parse_from(&["--errors=xml".to_string()]).is_err());
+
+        // Found even when other arguments are invalid
+        let raw = ["--bogus".to_string(), "--errors=json".to_string()];
+        assert!(Args::parse_from(&raw).is_err());
+        assert_eq!(ErrorFormat::scan(&raw), ErrorFormat::Json);
+        assert_eq!(ErrorFormat::scan(&["--errors".to_string()]), ErrorFormat::Text);
+    }
+
+    #[test]
+    fn test_parse_badge_file() {
+        let args = Args::parse_from(&["--badge-file".to_string(), "badge.json".to_string()]).unwrap();
+        assert_eq!(args.badge_file, Some("badge.json".to_string()));
+
+        let args = Args::parse_from(&["tui".to_string(), "--badge-file=badge.json".to_string()]).unwrap();
+        assert_eq!(args.badge_file, Some("badge.json".to_string()));
+
+        let err = Args::parse_from(&["audit".to_string(), "--badge-file".to_string(), "badge.json".to_string()]);
+        assert!(err.unwrap_err().contains("check command only"));
+    }
+
+    #[test]
+    fn test_parse_deterministic() {
+        let args = Args::parse_from(&["check".to_string(), "--deterministic".to_string()]).unwrap();
+        assert!(args.deterministic);
+        assert!(crate::TpuDocConfig::from_args(&args).deterministic);
+
+        let err = Args::parse_from(&["--deterministic".to_string(), "--cached".to_string(), "1h".to_string()]);
+        assert!(err.unwrap_err().contains("--cached"));
+        let err = Args::parse_from(&["info".to_string(), "--deterministic".to_string()]);
+        assert!(err.unwrap_err().contains("check command only"));
+    }
+
+    #[test]
+    fn test_parse_gcloud_fallback() {
+        let args = Args::parse_from(&["check".to_string(), "--gcloud-fallback".to_string()]).unwrap();
+        assert!(args.gcloud_fallback);
+        assert!(crate::TpuDocConfig::from_args(&args).gcloud_fallback);
+        assert!(!Args::parse_from(&["check".to_string()]).unwrap().gcloud_fallback);
+        let err = Args::parse_from(&["audit".to_string(), "--gcloud-fallback".to_string()]);
+        assert!(err.unwrap_err().contains("check command only"));
+    }
+
+    #[test]
+    fn test_parse_perf_level() {
+        let args = Args::parse_from(&["check".to_string(), "--perf-level".to_string(), "soak".to_string()]).unwrap();
+        assert_eq!(args.perf_level, Some(PerfLevel::Soak));
+        assert_eq!(crate::TpuDocConfig::from_args(&args).perf_level, PerfLevel::Soak);
+        let args = Args::parse_from(&["--perf-level=smoke".to_string()]).unwrap();
+        assert_eq!(args.perf_level, Some(PerfLevel::Smoke));
+        assert_eq!(crate::TpuDocConfig::from_args(&Args::default()).perf_level, PerfLevel::Standard);
+
+        let err = Args::parse_from(&["--perf-level=burn-in".to_string()]);
+        assert!(err.unwrap_err().contains("Valid levels: smoke, standard, soak"));
+        let err = Args::parse_from(&["--perf-level=soak".to_string(), "--cached=1h".to_string()]);
+        assert!(err.unwrap_err().contains("--cached"));
+        let err = Args::parse_from(&["audit".to_string(), "--perf-level=smoke".to_string()]);
+        assert!(err.unwrap_err().contains("check command only"));
+    }
+
+    #[test]
+    fn test_parse_checkpoint_plan() {
+        let args = Args::parse_from(&["--checkpoint-size".to_string(), "250GB".to_string(), "--keep=5".to_string()]);
+        let args = args.unwrap();
+        assert_eq!(args.checkpoint_size_bytes, Some(250 << 30));
+        assert_eq!(args.checkpoint_keep, Some(5));
+        let plan = crate::TpuDocConfig::from_args(&args).checkpoint_plan.unwrap();
+        assert_eq!(plan.footprint_bytes(), 1500 << 30);
+
+        // The size alone keeps one checkpoint
+        let args = Args::parse_from(&["--checkpoint-size=1.5TB".to_string()]).unwrap();
+        assert_eq!(crate::TpuDocConfig::from_args(&args).checkpoint_plan.unwrap().keep, 1);
+        assert_eq!(crate::TpuDocConfig::from_args(&Args::default()).checkpoint_plan, None);
+
+        let err = Args::parse_from(&["--keep".to_string(), "0".to_string()]);
+        assert!(err.unwrap_err().contains("Invalid --keep value: '0'"));
+        let err = Args::parse_from(&["--checkpoint-size".to_string(), "250".to_string()]);
+        assert!(err.unwrap_err().contains("Invalid checkpoint size"));
+        let err = Args::parse_from(&["audit".to_string(), "--keep".to_string(), "3".to_string()]);
+        assert!(err.unwrap_err().contains("check command only"));
+    }
+
+    #[test]
+    fn test_parse_run_id() {
+        let args = Args::parse_from(&["--run-id".to_string(), "train-7f3a/attempt-2".to_string()]).unwrap();
+        assert_eq!(args.run_id.as_deref(), Some("train-7f3a/attempt-2"));
+        assert_eq!(crate::TpuDocConfig::from_args(&args).run_id.as_deref(), Some("train-7f3a/attempt-2"));
+        let args = Args::parse_from(&["tui".to_string(), "--run-id=job-42".to_string()]).unwrap();
+        assert_eq!(args.run_id.as_deref(), Some("job-42"));
+
+        let err = Args::parse_from(&["--run-id=two words".to_string()]);
+        assert!(err.unwrap_err().contains("Invalid run ID"));
+        assert!(Args::parse_from(&["--run-id=".to_string()]).is_err());
+        assert!(Args::parse_from(&[format!("--run-id={}", "x".repeat(129))]).is_err());
+        let err = Args::parse_from(&["audit".to_string(), "--run-id".to_string(), "job-42".to_string()]);
+        assert!(err.unwrap_err().contains("check command only"));
+    }
+
+    #[test]
+    fn test_parse_sign_and_verify() {
+        let args = Args::parse_from(&["--json-compact".to_string(), "--sign".to_string()]).unwrap();
+        assert!(args.sign);
+        let err = Args::parse_from(&["--sign".to_string()]);
+        assert!(err.unwrap_err().contains("--format json"));
+        let err = Args::parse_from(&["audit".to_string(), "--sign".to_string()]);
+        assert!(err.unwrap_err().contains("check command only"));
+
+        let args = ["verify", "report.json", "--key=pub.pem"].map(String::from);
+        let args = Args::parse_from(&args).unwrap();
+        assert_eq!(args.command, Command::Verify);
+        assert_eq!(args.report_file.as_deref(), Some("report.json"));
+        assert_eq!(args.key_file.as_deref(), Some("pub.pem"));
+        assert!(Args::parse_from(&["verify".to_string()]).unwrap_err().contains("signed report"));
+        let err = Args::parse_from(&["--key".to_string(), "pub.pem".to_string()]);
+        assert!(err.unwrap_err().contains("verify command only"));
+    }
+
+    #[test]
+    fn test_parse_json_compact() {
+        let args = Args::parse_from(&["--json-compact".to_string()]).unwrap();
+        assert!(args.json_compact);
+        assert_eq!(args.format, OutputFormat::Json);
+
+        let args = Args::parse_from(&["--format=json".to_string(), "--json-compact".to_string()]).unwrap();
+        assert_eq!(args.format, OutputFormat::Json);
+
+        let err = Args::parse_from(&["--json-compact".to_string(), "--format".to_string(), "junit".to_string()]);
+        assert!(err.unwrap_err().contains("--json-compact"));
+    }
+
     #[test]
     fn test_parse_timeout_option() {
         let args = Args::parse_from(&["--timeout".to_string(), "60000".to_string()]).unwrap();
@@ -473,6 +1596,19 @@ mod tests {
         let args = Args::parse_from(&["stack".to_string(), "--matrix".to_string()]).unwrap();
         assert_eq!(args.command, Command::Stack);
         assert!(args.show_matrix);
+
+        let args = Args::parse_from(&[
+            "stack".to_string(),
+            "--matrix-url".to_string(),
+            "https://example.com/matrix.json".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(args.matrix_url.as_deref(), Some("https://example.com/matrix.json"));
+        assert!(Args::parse_from(&["stack".to_string(), "--matrix-url".to_string()]).is_err());
+
+        let url_arg = "--matrix-url=https://example.com/m.json".to_string();
+        let args = Args::parse_from(&["stack".to_string(), url_arg]).unwrap();
+        assert_eq!(args.matrix_url.as_deref(), Some("https://example.com/m.json"));
     }
 
     #[test]
@@ -480,6 +1616,67 @@ mod tests {
         let args = Args::parse_from(&["snapshot".to_string(), "--continuous".to_string(), "5".to_string()]).unwrap();
         assert_eq!(args.command, Command::Snapshot);
         assert_eq!(args.continuous, 5);
+        assert!(!args.no_tty);
+
+        let args = Args::parse_from(&[
+            "snapshot".to_string(),
+            "--continuous=2".to_string(),
+            "--no-tty".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(args.continuous, 2);
+        assert!(args.no_tty);
+    }
+
+    #[test]
+    fn test_parse_alert_options() {
+        let parse = |flags: &[&str]| Args::parse_from(&flags.iter().map(|f| f.to_string()).collect::<Vec<_>>());
+
+        let args = parse(&["snapshot", "--once", "--alert", "hbm=90:98", "--alert=temp=80"]).unwrap();
+        assert!(args.once);
+        assert_eq!(args.alerts.len(), 2);
+        assert_eq!((args.alerts[0].warn, args.alerts[0].crit), (90.0, 98.0));
+        assert_eq!((args.alerts[1].warn, args.alerts[1].crit), (80.0, 90.0));
+
+        assert!(parse(&["snapshot", "--alert", "disk=90"]).is_err());
+        assert!(parse(&["snapshot", "--alert", "mem=95:90"]).is_err());
+        assert!(parse(&["snapshot", "--alert"]).is_err());
+        assert!(parse(&["check", "--once"]).is_err());
+        assert!(parse(&["snapshot", "--once", "--duration", "10m"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_max_tokens() {
+        let args = Args::parse_from(&[
+            "analyze".to_string(),
+            "train.log".to_string(),
+            "--max-tokens=2048".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(args.ai_max_tokens, Some(2048));
+
+        assert!(Args::parse_from(&["analyze".to_string(), "--max-tokens".to_string(), "0".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_ai_cache_options() {
+        let args = Args::parse_from(&["analyze".to_string(), "--cache-ttl=2h".to_string()]).unwrap();
+        assert_eq!(args.ai_cache_ttl_secs, Some(7200));
+        assert!(!args.ai_no_cache);
+
+        let args = Args::parse_from(&["analyze".to_string(), "--no-cache".to_string()]).unwrap();
+        assert!(args.ai_no_cache);
+
+        let args = Args::parse_from(&["analyze".to_string(), "--ai-base-url=https://gw.corp/v1".to_string()]).unwrap();
+        assert_eq!(args.ai_base_url.as_deref(), Some("https://gw.corp/v1"));
+
+        let args = Args::parse_from(&["analyze".to_string(), "x.log".to_string(), "--interactive".to_string()]).unwrap();
+        assert!(args.interactive);
+        assert!(!args.ai_no_redact);
+        assert_eq!(args.log_file.as_deref(), Some("x.log"));
+
+        let args = Args::parse_from(&["analyze".to_string(), "--no-redact".to_string()]).unwrap();
+        assert!(args.ai_no_redact);
     }
 
     #[test]
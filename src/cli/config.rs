@@ -0,0 +1,1063 @@
+//! Configuration file loading.
+//!
+//! Reads the TOML configuration file described in docs/configuration.md.
+//! The file is taken from `--config` / `TPU_PREFLIGHT_CONFIG` when given, or the
+//! first of [`DEFAULT_CONFIG_PATHS`] that exists.
+//!
+//! Currently this loads the custom shell-command checks declared as
+//! `[[checks.custom]]` tables, the `[policy]` downgrade rules, the
+//! `[ownership]` owners and links of checks, the
+//! environment variables STK-007 requires from `[[env.required]]` and
+//! `[[env.recommended]]`, the endpoints IO-005 measures from
+//! `[[network.endpoints]]` and SEC-009's egress requirement from
+//! `[network]`, the `gcloud` fallback for GCP API probes from `[gcp]`, the network and benchmark timeouts from
+//! `[timeouts]`, and the benchmark warm-up iterations, HBM working set, and
+//! busy-TPU policy from `[benchmarks]`, and the report signing keys from
+//! `[signing]`.
+
+use crate::checks::benchmark::{BusyTpuPolicy, HbmWorkingSet, WarmupIterations};
+use crate::checks::io::{parse_checkpoint_size, LatencyEndpoint, DEFAULT_LATENCY_WARN_MS};
+use crate::checks::stack::EnvVarRule;
+use crate::engine::ownership::Ownership;
+use crate::engine::policy::{Policy, PolicyAction, PolicyTarget};
+use crate::engine::signing::{self, SigningKey, VerifyingKey};
+use crate::util::regex::Regex;
+use crate::util::toml::{self, TomlValue};
+use crate::{CheckCategory, CheckOwnership, TpuDocError};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Locations searched, in order, when no configuration file is given.
+/// `~` is the user's home directory.
+pub const DEFAULT_CONFIG_PATHS: &[&str] = &["./tpu-doc.toml", "~/.config/tpu-doc/config.toml", "/etc/tpu-doc/config.toml"];
+
+/// Default timeout for a custom check command
+pub const DEFAULT_CUSTOM_CHECK_TIMEOUT_MS: u64 = 30000;
+
+/// Most warm-up iterations a benchmark may be configured with
+pub const MAX_WARMUP_ITERATIONS: i64 = 100;
+
+/// Shortest `signing.hmac_key` accepted, in bytes
+pub const MIN_HMAC_KEY_LEN: usize = 16;
+
+/// Report signing keys from `[signing]`: an HMAC key, or Ed25519 key files
+#[derive(Clone, Default)]
+pub struct SigningConfig {
+    /// Shared secret for HMAC-SHA256
+    pub hmac_key: Option<String>,
+    /// Ed25519 private key PEM file reports are signed with
+    pub ed25519_key_file: Option<PathBuf>,
+    /// Ed25519 public key PEM file signatures are verified with, where the
+    /// private key is not at hand
+    pub ed25519_public_key_file: Option<PathBuf>,
+}
+
+// Keep the shared secret out of debug output
+impl fmt::Debug for SigningConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningConfig")
+            .field("hmac_key", &self.hmac_key.as_ref().map(|_| "<redacted>"))
+            .field("ed25519_key_file", &self.ed25519_key_file)
+            .field("ed25519_public_key_file", &self.ed25519_public_key_file)
+            .finish()
+    }
+}
+
+/// A check declared in the configuration file that runs a shell command
+#[derive(Debug, Clone)]
+pub struct CustomCheckConfig {
+    pub id: String,
+    pub name: String,
+    pub category: CheckCategory,
+    pub description: String,
+    /// Command line run with `sh -c`
+    pub command: String,
+    pub timeout_ms: u64,
+    /// Exit codes that count as passing (default: 0)
+    pub pass_exit_codes: Vec<i32>,
+    /// The check fails if its output matches this pattern, even on a passing exit code
+    pub fail_regex: Option<Regex>,
+}
+
+/// A loaded configuration file
+#[derive(Debug, Clone)]
+pub struct ConfigFile {
+    pub path: PathBuf,
+    pub custom_checks: Vec<CustomCheckConfig>,
+    /// Failure downgrade rules from `[policy]`
+    pub policy: Policy,
+    /// Owners and links of checks, from `[ownership]`
+    pub ownership: Ownership,
+    /// Environment variables checked by STK-007, from `[env]`
+    pub env_vars: Vec<EnvVarRule>,
+    /// Endpoints IO-005 measures instead of the built-in set, from
+    /// `[[network.endpoints]]`
+    pub latency_endpoints: Vec<LatencyEndpoint>,
+    /// Timeout for each network connection or request, from
+    /// `timeouts.network_ms` (`--net-timeout` overrides it)
+    pub net_timeout_ms: Option<u64>,
+    /// Timeout for each benchmark, from `timeouts.benchmark_ms`
+    /// (`--bench-timeout` overrides it)
+    pub bench_timeout_ms: Option<u64>,
+    /// Warm-up iterations for each benchmark, from `[benchmarks.warmup]`
+    pub warmup: WarmupIterations,
+    /// HBM bandwidth working set, from `benchmarks.hbm_working_set`
+    pub hbm_working_set: Option<HbmWorkingSet>,
+    /// What the performance checks do when the TPU is busy, from
+    /// `benchmarks.busy_tpu`
+    pub busy_tpu: Option<BusyTpuPolicy>,
+    /// Whether SEC-009 warns about egress open to the internet, from
+    /// `network.require_locked_egress`
+    pub require_locked_egress: bool,
+    /// Whether GCP API probes fall back to `gcloud`, from
+    /// `gcp.gcloud_fallback` (`--gcloud-fallback` turns it on too)
+    pub gcloud_fallback: bool,
+    /// Expected size of one checkpoint in bytes, from `checkpoint.size`
+    /// (`--checkpoint-size` overrides it)
+    pub checkpoint_size_bytes: Option<u64>,
+    /// Checkpoints kept, from `checkpoint.keep` (`--keep` overrides it)
+    pub checkpoint_keep: Option<u32>,
+    /// Google service account SEC-003 expects a GKE pod to be bound to,
+    /// from `gke.expected_service_account`
+    pub expected_gke_service_account: Option<String>,
+    /// Report signing keys, from `[signing]`
+    pub signing: Option<SigningConfig>,
+}
+
+impl ConfigFile {
+    /// Find and load the configuration file.
+    ///
+    /// An explicit path must exist. Without one, the default locations are
+    /// searched and Ok(None) is returned when none exists.
+    pub fn discover(explicit: Option<&str>) -> Result<Option<Self>, TpuDocError> {
+        if let Some(path) = explicit {
+            return Self::load(&expand_home(path)).map(Some);
+        }
+        DEFAULT_CONFIG_PATHS
+            .iter()
+            .map(|p| expand_home(p))
+            .find(|p| p.is_file())
+            .map(|p| Self::load(&p))
+            .transpose()
+    }
+
+    /// Load and validate a configuration file
+    pub fn load(path: &Path) -> Result<Self, TpuDocError> {
+        let text = fs::read_to_string(path).map_err(|e| TpuDocError::ConfigError {
+            path: path.display().to_string(),
+            message: e.to_string(),
+            source: Some(Arc::new(e)),
+        })?;
+        Self::parse(&text, path)
+    }
+
+    /// Parse and validate configuration text read from `path`
+    pub fn parse(text: &str, path: &Path) -> Result<Self, TpuDocError> {
+        let error = |message: String| TpuDocError::ConfigError {
+            path: path.display().to_string(),
+            message,
+            source: None,
+        };
+        let root = toml::parse(text).map_err(error)?;
+
+        let custom_checks = match root.get("checks").and_then(|c| c.get("custom")) {
+            None => Vec::new(),
+            Some(TomlValue::Array(entries)) => entries
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| parse_custom_check(entry).map_err(|e| error(format!("[[checks.custom]] #{}: {}", i + 1, e))))
+                .collect::<Result<Vec<_>, _>>()?,
+            Some(other) => {
+                return Err(error(format!(
+                    "checks.custom must be an array of tables, not a {}",
+                    other.type_name()
+                )))
+            }
+        };
+
+        for (i, check) in custom_checks.iter().enumerate() {
+            if custom_checks[..i].iter().any(|c| c.id.eq_ignore_ascii_case(&check.id)) {
+                return Err(error(format!("duplicate custom check ID '{}'", check.id)));
+            }
+        }
+
+        let policy = match root.get("policy") {
+            None => Policy::default(),
+            Some(TomlValue::Table(entries)) => parse_policy(entries).map_err(|e| error(format!("[policy]: {}", e)))?,
+            Some(other) => return Err(error(format!("policy must be a table, not a {}", other.type_name()))),
+        };
+
+        let ownership = match root.get("ownership") {
+            None => Ownership::default(),
+            Some(TomlValue::Table(entries)) => {
+                parse_ownership(entries).map_err(|e| error(format!("[ownership]: {}", e)))?
+            }
+            Some(other) => return Err(error(format!("ownership must be a table, not a {}", other.type_name()))),
+        };
+
+        let env_vars = match root.get("env") {
+            None => Vec::new(),
+            Some(TomlValue::Table(_)) => parse_env_vars(&root).map_err(error)?,
+            Some(other) => return Err(error(format!("env must be a table, not a {}", other.type_name()))),
+        };
+
+        let latency_endpoints = match root.get("network").and_then(|n| n.get("endpoints")) {
+            None => Vec::new(),
+            Some(TomlValue::Array(entries)) => entries
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| {
+                    parse_latency_endpoint(entry).map_err(|e| error(format!("[[network.endpoints]] #{}: {}", i + 1, e)))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            Some(other) => {
+                return Err(error(format!(
+                    "network.endpoints must be an array of tables, not a {}",
+                    other.type_name()
+                )))
+            }
+        };
+        for (i, endpoint) in latency_endpoints.iter().enumerate() {
+            if latency_endpoints[..i].iter().any(|e| e.name == endpoint.name) {
+                return Err(error(format!("duplicate network endpoint '{}'", endpoint.name)));
+            }
+        }
+
+        let timeout = |key: &str| match root.get("timeouts").and_then(|t| t.get(key)) {
+            None => Ok(None),
+            Some(TomlValue::Integer(ms)) if *ms > 0 => Ok(Some(*ms as u64)),
+            Some(other) => Err(error(format!("timeouts.{} must be a positive integer, not {:?}", key, other))),
+        };
+        let net_timeout_ms = timeout("network_ms")?;
+        let bench_timeout_ms = timeout("benchmark_ms")?;
+
+        let warmup = match root.get("benchmarks").and_then(|b| b.get("warmup")) {
+            None => WarmupIterations::default(),
+            Some(TomlValue::Table(entries)) => {
+                parse_warmup(entries).map_err(|e| error(format!("[benchmarks.warmup]: {}", e)))?
+            }
+            Some(other) => {
+                return Err(error(format!(
+                    "benchmarks.warmup must be a table, not a {}",
+                    other.type_name()
+                )))
+            }
+        };
+
+        let hbm_working_set = match root.get("benchmarks").and_then(|b| b.get("hbm_working_set")) {
+            None => None,
+            Some(TomlValue::String(size)) => {
+                Some(HbmWorkingSet::parse(size).map_err(|e| error(format!("benchmarks.hbm_working_set: {}", e)))?)
+            }
+            Some(other) => {
+                return Err(error(format!(
+                    "benchmarks.hbm_working_set must be a string such as \"4GB\" or \"10%\", not a {}",
+                    other.type_name()
+                )))
+            }
+        };
+
+        let busy_tpu = match root.get("benchmarks").and_then(|b| b.get("busy_tpu")) {
+            None => None,
+            Some(value) => Some(value.as_str().and_then(BusyTpuPolicy::from_name).ok_or_else(|| {
+                error(format!(
+                    "benchmarks.busy_tpu must be \"skip\", \"warn\", or \"run\", not {:?}",
+                    value
+                ))
+            })?),
+        };
+
+        let require_locked_egress = match root.get("network").and_then(|n| n.get("require_locked_egress")) {
+            None => false,
+            Some(value) => value.as_bool().ok_or_else(|| {
+                error(format!(
+                    "network.require_locked_egress must be a boolean, not a {}",
+                    value.type_name()
+                ))
+            })?,
+        };
+
+        let gcloud_fallback = match root.get("gcp").and_then(|g| g.get("gcloud_fallback")) {
+            None => false,
+            Some(value) => value.as_bool().ok_or_else(|| {
+                error(format!("gcp.gcloud_fallback must be a boolean, not a {}", value.type_name()))
+            })?,
+        };
+
+        let checkpoint_size_bytes = match root.get("checkpoint").and_then(|c| c.get("size")) {
+            None => None,
+            Some(TomlValue::String(size)) => {
+                Some(parse_checkpoint_size(size).map_err(|e| error(format!("checkpoint.size: {}", e)))?)
+            }
+            Some(other) => {
+                return Err(error(format!(
+                    "checkpoint.size must be a string such as \"250GB\", not a {}",
+                    other.type_name()
+                )))
+            }
+        };
+        let checkpoint_keep = match root.get("checkpoint").and_then(|c| c.get("keep")) {
+            None => None,
+            Some(TomlValue::Integer(keep)) if (1..=u32::MAX as i64).contains(keep) => Some(*keep as u32),
+            Some(other) => return Err(error(format!("checkpoint.keep must be a positive integer, not {:?}", other))),
+        };
+
+        let expected_gke_service_account = match root.get("gke").and_then(|g| g.get("expected_service_account")) {
+            None => None,
+            Some(TomlValue::String(email)) if email.contains('@') => Some(email.clone()),
+            Some(other) => {
+                return Err(error(format!(
+                    "gke.expected_service_account must be a service account email, not {:?}",
+                    other
+                )))
+            }
+        };
+
+        let signing = match root.get("signing") {
+            None => None,
+            Some(TomlValue::Table(entries)) => {
+                let base = path.parent().unwrap_or(Path::new(""));
+                Some(parse_signing(entries, base).map_err(|e| error(format!("[signing]: {}", e)))?)
+            }
+            Some(other) => return Err(error(format!("signing must be a table, not a {}", other.type_name()))),
+        };
+
+        Ok(ConfigFile {
+            path: path.to_path_buf(),
+            custom_checks,
+            policy,
+            ownership,
+            env_vars,
+            latency_endpoints,
+            net_timeout_ms,
+            bench_timeout_ms,
+            warmup,
+            hbm_working_set,
+            busy_tpu,
+            require_locked_egress,
+            gcloud_fallback,
+            checkpoint_size_bytes,
+            checkpoint_keep,
+            expected_gke_service_account,
+            signing,
+        })
+    }
+
+    /// The key `check --sign` signs reports with
+    pub fn signing_key(&self) -> Result<SigningKey, TpuDocError> {
+        match &self.signing {
+            Some(SigningConfig { hmac_key: Some(secret), .. }) => Ok(SigningKey::Hmac(secret.as_bytes().to_vec())),
+            Some(SigningConfig { ed25519_key_file: Some(path), .. }) => signing::load_signing_key(path),
+            _ => Err(TpuDocError::ConfigError {
+                path: self.path.display().to_string(),
+                message: "signing reports needs signing.hmac_key or signing.ed25519_key_file".to_string(),
+                source: None,
+            }),
+        }
+    }
+
+    /// The key `verify` checks report signatures with: the HMAC key, or the
+    /// Ed25519 public key, read from the public key file if one is set and
+    /// derived from the private key otherwise
+    pub fn verifying_key(&self) -> Result<VerifyingKey, TpuDocError> {
+        match &self.signing {
+            Some(SigningConfig { hmac_key: Some(secret), .. }) => Ok(VerifyingKey::Hmac(secret.as_bytes().to_vec())),
+            Some(SigningConfig {
+                ed25519_public_key_file: Some(path),
+                ..
+            })
+            | Some(SigningConfig {
+                ed25519_key_file: Some(path),
+                ..
+            }) => signing::load_verifying_key(path),
+            _ => Err(TpuDocError::ConfigError {
+                path: self.path.display().to_string(),
+                message: "verifying reports needs a [signing] key".to_string(),
+                source: None,
+            }),
+        }
+    }
+}
+
+fn parse_custom_check(entry: &TomlValue) -> Result<CustomCheckConfig, String> {
+    let string = |key: &str| -> Result<Option<String>, String> {
+        match entry.get(key) {
+            None => Ok(None),
+            Some(TomlValue::String(s)) => Ok(Some(s.clone())),
+            Some(other) => Err(format!("'{}' must be a string, not a {}", key, other.type_name())),
+        }
+    };
+    let required = |key: &str| -> Result<String, String> {
+        string(key)?
+            .filter(|s| !s.trim().is_empty())
+            .ok_or_else(|| format!("missing required key '{}'", key))
+    };
+
+    let id = required("id")?;
+    if !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("invalid check ID '{}' (use letters, digits, '-' and '_')", id));
+    }
+    if crate::engine::orchestrator::create_all_checks()
+        .iter()
+        .any(|c| c.id.eq_ignore_ascii_case(&id))
+    {
+        return Err(format!("check ID '{}' is already used by a built-in check", id));
+    }
+
+    let category_name = required("category")?;
+    let category = parse_category(&category_name).ok_or_else(|| {
+        format!(
+            "unknown category '{}' (expected hardware, stack, performance, io, security, or config)",
+            category_name
+        )
+    })?;
+
+    let timeout_ms = match entry.get("timeout_ms") {
+        None => DEFAULT_CUSTOM_CHECK_TIMEOUT_MS,
+        Some(TomlValue::Integer(ms)) if *ms > 0 => *ms as u64,
+        Some(other) => return Err(format!("'timeout_ms' must be a positive integer, not {:?}", other)),
+    };
+
+    let pass_exit_codes = match entry.get("pass_exit_codes") {
+        None => vec![0],
+        Some(TomlValue::Array(codes)) if !codes.is_empty() => codes
+            .iter()
+            .map(|c| {
+                c.as_integer()
+                    .and_then(|c| i32::try_from(c).ok())
+                    .ok_or_else(|| "'pass_exit_codes' must contain integers".to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        Some(_) => return Err("'pass_exit_codes' must be a non-empty array of integers".to_string()),
+    };
+
+    let fail_regex = string("fail_regex")?
+        .map(|pattern| Regex::new(&pattern).map_err(|e| format!("invalid fail_regex '{}': {}", pattern, e)))
+        .transpose()?;
+
+    Ok(CustomCheckConfig {
+        name: string("name")?.unwrap_or_else(|| id.clone()),
+        description: string("description")?.unwrap_or_default(),
+        command: required("command")?,
+        id,
+        category,
+        timeout_ms,
+        pass_exit_codes,
+        fail_regex,
+    })
+}
+
+/// The `[[env.required]]` and `[[env.recommended]]` entries of `root`. An
+/// entry is a table with `name` and optional `pattern` and `description`, or
+/// just the variable name as a string.
+fn parse_env_vars(root: &TomlValue) -> Result<Vec<EnvVarRule>, String> {
+    let mut rules: Vec<EnvVarRule> = Vec::new();
+    for (key, required) in [("required", true), ("recommended", false)] {
+        let entries = match root.get("env").and_then(|env| env.get(key)) {
+            None => continue,
+            Some(TomlValue::Array(entries)) => entries,
+            Some(other) => return Err(format!("env.{} must be an array, not a {}", key, other.type_name())),
+        };
+
+        for (i, entry) in entries.iter().enumerate() {
+            let rule = parse_env_var(entry, required).map_err(|e| format!("[[env.{}]] #{}: {}", key, i + 1, e))?;
+            if rules.iter().any(|r| r.name == rule.name) {
+                return Err(format!("environment variable '{}' is declared twice", rule.name));
+            }
+            rules.push(rule);
+        }
+    }
+    Ok(rules)
+}
+
+fn parse_env_var(entry: &TomlValue, required: bool) -> Result<EnvVarRule, String> {
+    let string = |key: &str| -> Result<Option<String>, String> {
+        match entry.get(key) {
+            None => Ok(None),
+            Some(TomlValue::String(s)) => Ok(Some(s.clone())),
+            Some(other) => Err(format!("'{}' must be a string, not a {}", key, other.type_name())),
+        }
+    };
+
+    let (name, pattern, description) = match entry {
+        TomlValue::String(name) => (name.clone(), None, None),
+        TomlValue::Table(_) => (
+            string("name")?.ok_or_else(|| "missing required key 'name'".to_string())?,
+            string("pattern")?,
+            string("description")?,
+        ),
+        other => return Err(format!("expected a table or a variable name, not a {}", other.type_name())),
+    };
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("invalid environment variable name '{}'", name));
+    }
+
+    let pattern = pattern
+        .map(|p| Regex::new(&p).map_err(|e| format!("invalid pattern '{}': {}", p, e)))
+        .transpose()?;
+
+    Ok(EnvVarRule {
+        name,
+        required,
+        pattern,
+        description: description.unwrap_or_default(),
+    })
+}
+
+fn parse_latency_endpoint(entry: &TomlValue) -> Result<LatencyEndpoint, String> {
+    if !matches!(entry, TomlValue::Table(_)) {
+        return Err(format!("expected a table, not a {}", entry.type_name()));
+    }
+    let string = |key: &str| -> Result<Option<String>, String> {
+        match entry.get(key) {
+            None => Ok(None),
+            Some(TomlValue::String(s)) => Ok(Some(s.clone())),
+            Some(other) => Err(format!("'{}' must be a string, not a {}", key, other.type_name())),
+        }
+    };
+    let millis = |key: &str| -> Result<Option<u64>, String> {
+        match entry.get(key) {
+            None => Ok(None),
+            Some(TomlValue::Integer(ms)) if *ms > 0 => Ok(Some(*ms as u64)),
+            Some(other) => Err(format!("'{}' must be a positive integer, not {:?}", key, other)),
+        }
+    };
+
+    let host = string("host")?
+        .filter(|h| !h.trim().is_empty())
+        .ok_or_else(|| "missing required key 'host'".to_string())?;
+    if host.contains(|c: char| c.is_whitespace() || c == '/' || c == ':') {
+        return Err(format!("invalid host '{}' (give the port with 'port')", host));
+    }
+    let port = match entry.get("port") {
+        None => 443,
+        Some(TomlValue::Integer(port)) if (1..=65535).contains(port) => *port as u16,
+        Some(other) => return Err(format!("'port' must be an integer from 1 to 65535, not {:?}", other)),
+    };
+    let warn_ms = millis("warn_ms")?.unwrap_or(DEFAULT_LATENCY_WARN_MS);
+    let fail_ms = millis("fail_ms")?;
+    if fail_ms.is_some_and(|fail_ms| fail_ms < warn_ms) {
+        return Err(format!("'fail_ms' must not be below 'warn_ms' ({}ms)", warn_ms));
+    }
+    let required = match entry.get("required") {
+        None => false,
+        Some(value) => value
+            .as_bool()
+            .ok_or_else(|| format!("'required' must be a boolean, not a {}", value.type_name()))?,
+    };
+
+    Ok(LatencyEndpoint {
+        name: string("name")?.unwrap_or_else(|| host.clone()),
+        host,
+        port,
+        warn_ms,
+        fail_ms,
+        required,
+    })
+}
+
+fn parse_policy(entries: &[(String, TomlValue)]) -> Result<Policy, String> {
+    let rules = entries
+        .iter()
+        .map(|(key, value)| {
+            let action = value
+                .as_str()
+                .and_then(PolicyAction::from_name)
+                .ok_or_else(|| format!("'{}' must be \"warn\" or \"ignore\", not {:?}", key, value))?;
+            Ok((parse_target(key)?, action))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(Policy { rules })
+}
+
+/// Ownership entries from `[ownership]`: a table of `owner`, `docs_url`,
+/// and `runbook_url` for each check ID or category
+fn parse_ownership(entries: &[(String, TomlValue)]) -> Result<Ownership, String> {
+    let entries = entries
+        .iter()
+        .map(|(key, value)| {
+            let TomlValue::Table(fields) = value else {
+                return Err(format!("'{}' must be a table, not a {}", key, value.type_name()));
+            };
+            let mut ownership = CheckOwnership::default();
+            for (field, value) in fields {
+                let text = match value {
+                    TomlValue::String(s) if !s.trim().is_empty() => s.trim().to_string(),
+                    other => return Err(format!("{}.{} must be a non-empty string, not {:?}", key, field, other)),
+                };
+                let is_url = text.starts_with("https://") || text.starts_with("http://");
+                match field.as_str() {
+                    "owner" => ownership.owner = Some(text),
+                    "docs_url" | "runbook_url" if !is_url => {
+                        return Err(format!("{}.{} must be an http(s) URL, not '{}'", key, field, text))
+                    }
+                    "docs_url" => ownership.docs_url = Some(text),
+                    "runbook_url" => ownership.runbook_url = Some(text),
+                    _ => {
+                        return Err(format!(
+                            "unknown key '{}.{}' (expected owner, docs_url, runbook_url)",
+                            key, field
+                        ))
+                    }
+                }
+            }
+            Ok((parse_target(key)?, ownership))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(Ownership { entries })
+}
+
+/// What a `[policy]` or `[ownership]` key applies to: a category name or
+/// a check ID
+fn parse_target(key: &str) -> Result<PolicyTarget, String> {
+    match parse_category(key) {
+        Some(category) => Ok(PolicyTarget::Category(category)),
+        None if key.contains('-') && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') => {
+            Ok(PolicyTarget::Check(key.to_string()))
+        }
+        None => Err(format!(
+            "'{}' is neither a check ID nor a category (hardware, stack, performance, io, security, config)",
+            key
+        )),
+    }
+}
+
+/// Signing keys from `[signing]`. Relative key file paths are taken from
+/// `base`, the directory of the configuration file.
+fn parse_signing(entries: &[(String, TomlValue)], base: &Path) -> Result<SigningConfig, String> {
+    let mut signing = SigningConfig::default();
+    for (key, value) in entries {
+        let text = match value {
+            TomlValue::String(s) if !s.trim().is_empty() => s.trim(),
+            other => return Err(format!("'{}' must be a non-empty string, not a {}", key, other.type_name())),
+        };
+        match key.as_str() {
+            "hmac_key" if text.len() < MIN_HMAC_KEY_LEN => {
+                return Err(format!("'hmac_key' must be at least {} bytes long", MIN_HMAC_KEY_LEN))
+            }
+            "hmac_key" => signing.hmac_key = Some(text.to_string()),
+            "ed25519_key_file" => signing.ed25519_key_file = Some(base.join(expand_home(text))),
+            "ed25519_public_key_file" => signing.ed25519_public_key_file = Some(base.join(expand_home(text))),
+            _ => {
+                return Err(format!(
+                    "unknown key '{}' (expected hmac_key, ed25519_key_file, ed25519_public_key_file)",
+                    key
+                ))
+            }
+        }
+    }
+    let ed25519 = signing.ed25519_key_file.is_some() || signing.ed25519_public_key_file.is_some();
+    match (&signing.hmac_key, ed25519) {
+        (Some(_), true) => Err("set either hmac_key or the ed25519 key files, not both".to_string()),
+        (None, false) => Err("set hmac_key, ed25519_key_file, or ed25519_public_key_file".to_string()),
+        _ => Ok(signing),
+    }
+}
+
+/// Warm-up iterations from `[benchmarks.warmup]`, keyed by benchmark;
+/// benchmarks not listed keep the default
+fn parse_warmup(entries: &[(String, TomlValue)]) -> Result<WarmupIterations, String> {
+    let mut warmup = WarmupIterations::default();
+    for (key, value) in entries {
+        let iterations = match value {
+            TomlValue::Integer(n) if (0..=MAX_WARMUP_ITERATIONS).contains(n) => *n as u32,
+            other => {
+                return Err(format!(
+                    "'{}' must be an integer from 0 to {}, not {:?}",
+                    key, MAX_WARMUP_ITERATIONS, other
+                ))
+            }
+        };
+        match key.as_str() {
+            "mxu" => warmup.mxu = iterations,
+            "hbm" => warmup.hbm = iterations,
+            "latency" => warmup.latency = iterations,
+            "collective" => warmup.collective = iterations,
+            _ => return Err(format!("unknown benchmark '{}' (expected mxu, hbm, latency, or collective)", key)),
+        }
+    }
+    Ok(warmup)
+}
+
+/// Category for a name such as `io` or `config`
+pub(crate) fn parse_category(name: &str) -> Option<CheckCategory> {
+    match name.to_lowercase().as_str() {
+        "hardware" => Some(CheckCategory::Hardware),
+        "stack" => Some(CheckCategory::Stack),
+        "performance" => Some(CheckCategory::Performance),
+        "io" | "i/o" => Some(CheckCategory::Io),
+        "security" => Some(CheckCategory::Security),
+        "config" => Some(CheckCategory::Config),
+        _ => None,
+    }
+}
+
+/// Expand a leading `~/` to the home directory
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) if !home.is_empty() => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<ConfigFile, TpuDocError> {
+        ConfigFile::parse(text, Path::new("test.toml"))
+    }
+
+    #[test]
+    fn test_parse_custom_checks() {
+        let config = parse(
+            r#"
+[checks]
+skip = ["SEC-007"]
+
+[[checks.custom]]
+id = "ORG-001"
+name = "NFS mounted"
+category = "io"
+command = "mountpoint -q /mnt/data"
+
+[[checks.custom]]
+id = "ORG-002"
+category = "Config"
+command = "cat /etc/launcher.conf"
+timeout_ms = 5000
+pass_exit_codes = [0, 3]
+fail_regex = "(?i)deprecated"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.custom_checks.len(), 2);
+        let first = &config.custom_checks[0];
+        assert_eq!(first.name, "NFS mounted");
+        assert_eq!(first.category, CheckCategory::Io);
+        assert_eq!(first.timeout_ms, DEFAULT_CUSTOM_CHECK_TIMEOUT_MS);
+        assert_eq!(first.pass_exit_codes, vec![0]);
+        assert!(first.fail_regex.is_none());
+
+        let second = &config.custom_checks[1];
+        assert_eq!(second.name, "ORG-002");
+        assert_eq!(second.pass_exit_codes, vec![0, 3]);
+        assert!(second.fail_regex.as_ref().unwrap().is_match("Option DEPRECATED"));
+
+        assert!(parse("[output]\nformat = \"json\"\n").unwrap().custom_checks.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_custom_checks() {
+        let entry = |body: &str| format!("[[checks.custom]]\n{}\n", body);
+        for bad in [
+            entry("id = \"ORG-1\"\ncategory = \"io\""),
+            entry("id = \"ORG-1\"\ncategory = \"disk\"\ncommand = \"true\""),
+            entry("id = \"HW-001\"\ncategory = \"io\"\ncommand = \"true\""),
+            entry("id = \"ORG 1\"\ncategory = \"io\"\ncommand = \"true\""),
+            entry("id = \"ORG-1\"\ncategory = \"io\"\ncommand = \"true\"\ntimeout_ms = 0"),
+            entry("id = \"ORG-1\"\ncategory = \"io\"\ncommand = \"true\"\nfail_regex = \"(\""),
+            entry("id = \"ORG-1\"\ncategory = \"io\"\ncommand = \"true\"\npass_exit_codes = [\"0\"]"),
+            format!(
+                "{}{}",
+                entry("id = \"ORG-1\"\ncategory = \"io\"\ncommand = \"true\""),
+                entry("id = \"org-1\"\ncategory = \"io\"\ncommand = \"true\"")
+            ),
+            "[checks]\ncustom = \"x\"\n".to_string(),
+        ] {
+            assert!(parse(&bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_policy() {
+        let config = parse("[policy]\n\"SEC-006\" = \"warn\"\nperformance = \"Ignore\"\n").unwrap();
+        assert_eq!(
+            config.policy.rules,
+            vec![
+                (PolicyTarget::Check("SEC-006".to_string()), PolicyAction::Warn),
+                (PolicyTarget::Category(CheckCategory::Performance), PolicyAction::Ignore),
+            ]
+        );
+        assert!(parse("").unwrap().policy.is_empty());
+
+        for bad in [
+            "[policy]\n\"SEC-006\" = \"pass\"\n",
+            "[policy]\n\"SEC-006\" = 1\n",
+            "[policy]\ndisk = \"warn\"\n",
+            "policy = \"warn\"\n",
+        ] {
+            assert!(parse(bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_ownership() {
+        let config = parse(
+            r#"
+[ownership.performance]
+owner = "perf-oncall"
+
+[ownership."HW-003"]
+owner = "fleet-hw"
+runbook_url = "https://runbooks.example.com/thermal"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.ownership.entries,
+            vec![
+                (
+                    PolicyTarget::Category(CheckCategory::Performance),
+                    CheckOwnership {
+                        owner: Some("perf-oncall".to_string()),
+                        ..Default::default()
+                    }
+                ),
+                (
+                    PolicyTarget::Check("HW-003".to_string()),
+                    CheckOwnership {
+                        owner: Some("fleet-hw".to_string()),
+                        docs_url: None,
+                        runbook_url: Some("https://runbooks.example.com/thermal".to_string()),
+                    }
+                ),
+            ]
+        );
+        assert!(parse("").unwrap().ownership.is_empty());
+
+        for bad in [
+            "[ownership]\n\"HW-003\" = \"fleet-hw\"\n",
+            "[ownership.\"HW-003\"]\nrunbook_url = \"wiki/thermal\"\n",
+            "[ownership.\"HW-003\"]\nowner = \"\"\n",
+            "[ownership.\"HW-003\"]\nteam = \"fleet-hw\"\n",
+            "[ownership.disk]\nowner = \"fleet-hw\"\n",
+        ] {
+            assert!(parse(bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_signing() {
+        let config = ConfigFile::parse(
+            "[signing]\ned25519_key_file = \"keys/report.pem\"\ned25519_public_key_file = \"/etc/tpu-doc/pub.pem\"\n",
+            Path::new("/etc/tpu-doc/config.toml"),
+        )
+        .unwrap();
+        let signing = config.signing.unwrap();
+        assert_eq!(signing.ed25519_key_file, Some(PathBuf::from("/etc/tpu-doc/keys/report.pem")));
+        assert_eq!(signing.ed25519_public_key_file, Some(PathBuf::from("/etc/tpu-doc/pub.pem")));
+
+        let config = parse("[signing]\nhmac_key = \"0123456789abcdef\"\n").unwrap();
+        assert!(matches!(config.signing_key(), Ok(SigningKey::Hmac(key)) if key == b"0123456789abcdef"));
+        assert!(!format!("{:?}", config).contains("0123456789abcdef"));
+
+        // A public key verifies but cannot sign
+        let config = parse("[signing]\ned25519_public_key_file = \"pub.pem\"\n").unwrap();
+        assert!(matches!(config.signing_key(), Err(TpuDocError::ConfigError { .. })));
+        assert!(parse("").unwrap().signing.is_none());
+
+        for bad in [
+            "[signing]\n",
+            "[signing]\nhmac_key = \"short\"\n",
+            "[signing]\nhmac_key = \"0123456789abcdef\"\ned25519_key_file = \"key.pem\"\n",
+            "[signing]\nkey_file = \"key.pem\"\n",
+            "signing = \"key.pem\"\n",
+        ] {
+            assert!(parse(bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_env_vars() {
+        let config = parse(
+            r#"
+[env]
+recommended = ["WANDB_PROJECT"]
+
+[[env.required]]
+name = "LAUNCHER_JOB_ID"
+pattern = "^job-[0-9]+$"
+description = "Exported by the launcher"
+
+[[env.required]]
+name = "TPU_WORKER_ID"
+"#,
+        )
+        .unwrap();
+
+        let names: Vec<(&str, bool)> = config.env_vars.iter().map(|r| (r.name.as_str(), r.required)).collect();
+        assert_eq!(names, vec![("LAUNCHER_JOB_ID", true), ("TPU_WORKER_ID", true), ("WANDB_PROJECT", false)]);
+        assert!(config.env_vars[0].pattern.as_ref().unwrap().is_match("job-42"));
+        assert_eq!(config.env_vars[0].description, "Exported by the launcher");
+        assert!(config.env_vars[1].pattern.is_none());
+        assert!(parse("").unwrap().env_vars.is_empty());
+
+        for bad in [
+            "env = \"x\"\n",
+            "[env]\nrequired = \"JOB_ID\"\n",
+            "[env]\nrequired = [1]\n",
+            "[env]\nrequired = [\"JOB ID\"]\n",
+            "[[env.required]]\npattern = \"x\"\n",
+            "[[env.required]]\nname = \"JOB_ID\"\npattern = \"(\"\n",
+            "[env]\nrequired = [\"JOB_ID\"]\nrecommended = [\"JOB_ID\"]\n",
+        ] {
+            assert!(parse(bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_latency_endpoints() {
+        let config = parse(
+            r#"
+[[network.endpoints]]
+host = "us-docker.pkg.dev"
+
+[[network.endpoints]]
+name = "mirror"
+host = "pypi.internal.example.com"
+port = 8443
+warn_ms = 50
+fail_ms = 200
+required = true
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.latency_endpoints.len(), 2);
+        let first = &config.latency_endpoints[0];
+        assert_eq!((first.name.as_str(), first.port), ("us-docker.pkg.dev", 443));
+        assert_eq!(first.warn_ms, DEFAULT_LATENCY_WARN_MS);
+        assert_eq!((first.fail_ms, first.required), (None, false));
+        let second = &config.latency_endpoints[1];
+        assert_eq!((second.name.as_str(), second.port), ("mirror", 8443));
+        assert_eq!((second.warn_ms, second.fail_ms, second.required), (50, Some(200), true));
+        assert!(parse("").unwrap().latency_endpoints.is_empty());
+
+        let entry = |body: &str| format!("[[network.endpoints]]\n{}\n", body);
+        for bad in [
+            entry("name = \"x\""),
+            entry("host = \"storage.googleapis.com:443\""),
+            entry("host = \"a.example.com\"\nport = 0"),
+            entry("host = \"a.example.com\"\nwarn_ms = 0"),
+            entry("host = \"a.example.com\"\nwarn_ms = 50\nfail_ms = 20"),
+            entry("host = \"a.example.com\"\nrequired = \"yes\""),
+            format!("{}{}", entry("host = \"a.example.com\""), entry("host = \"a.example.com\"")),
+            "[network]\nendpoints = \"a.example.com\"\n".to_string(),
+        ] {
+            assert!(parse(&bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_timeouts() {
+        let config = ConfigFile::parse("[timeouts]\nglobal_ms = 30000\nnetwork_ms = 2000\n", Path::new("t.toml")).unwrap();
+        assert_eq!((config.net_timeout_ms, config.bench_timeout_ms), (Some(2000), None));
+        let config = ConfigFile::parse("[timeouts]\nbenchmark_ms = 300000\n", Path::new("t.toml")).unwrap();
+        assert_eq!(config.bench_timeout_ms, Some(300000));
+
+        let err = ConfigFile::parse("[timeouts]\nnetwork_ms = 0\n", Path::new("t.toml")).unwrap_err();
+        assert!(err.to_string().contains("timeouts.network_ms must be a positive integer"));
+    }
+
+    #[test]
+    fn test_parse_warmup() {
+        let config = parse("[benchmarks.warmup]\nmxu = 5\nlatency = 0\n").unwrap();
+        assert_eq!(
+            config.warmup,
+            WarmupIterations {
+                mxu: 5,
+                latency: 0,
+                ..WarmupIterations::default()
+            }
+        );
+        assert_eq!(parse("").unwrap().warmup, WarmupIterations::default());
+
+        for bad in [
+            "[benchmarks.warmup]\nmxu = -1\n",
+            "[benchmarks.warmup]\nmxu = 1000\n",
+            "[benchmarks.warmup]\nhbm = \"3\"\n",
+            "[benchmarks.warmup]\ntraining = 2\n",
+            "[benchmarks]\nwarmup = 3\n",
+        ] {
+            assert!(parse(bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_hbm_working_set() {
+        let config = parse("[benchmarks]\nhbm_working_set = \"8GB\"\n").unwrap();
+        assert_eq!(config.hbm_working_set, Some(HbmWorkingSet::Megabytes(8192)));
+        let config = parse("[benchmarks]\nhbm_working_set = \"20%\"\n").unwrap();
+        assert_eq!(config.hbm_working_set, Some(HbmWorkingSet::Percent(20)));
+        assert_eq!(parse("").unwrap().hbm_working_set, None);
+
+        let err = parse("[benchmarks]\nhbm_working_set = \"80%\"\n").unwrap_err();
+        assert!(err.to_string().contains("benchmarks.hbm_working_set: invalid HBM working set '80%'"));
+        assert!(parse("[benchmarks]\nhbm_working_set = 4096\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_busy_tpu() {
+        let config = parse("[benchmarks]\nbusy_tpu = \"warn\"\n").unwrap();
+        assert_eq!(config.busy_tpu, Some(BusyTpuPolicy::Warn));
+        assert_eq!(parse("").unwrap().busy_tpu, None);
+
+        let err = parse("[benchmarks]\nbusy_tpu = \"fail\"\n").unwrap_err();
+        assert!(err.to_string().contains("benchmarks.busy_tpu must be"));
+        assert!(parse("[benchmarks]\nbusy_tpu = true\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_require_locked_egress() {
+        let text = "[network]\nrequire_locked_egress = true\n\n[[network.endpoints]]\nhost = \"a.example.com\"\n";
+        let config = parse(text).unwrap();
+        assert!(config.require_locked_egress);
+        assert_eq!(config.latency_endpoints.len(), 1);
+        assert!(!parse("").unwrap().require_locked_egress);
+        let err = parse("[network]\nrequire_locked_egress = \"yes\"\n").unwrap_err();
+        assert!(err.to_string().contains("network.require_locked_egress must be a boolean, not a string"));
+    }
+
+    #[test]
+    fn test_parse_gcloud_fallback() {
+        assert!(parse("[gcp]\ngcloud_fallback = true\n").unwrap().gcloud_fallback);
+        assert!(!parse("").unwrap().gcloud_fallback);
+        let err = parse("[gcp]\ngcloud_fallback = 1\n").unwrap_err();
+        assert!(err.to_string().contains("gcp.gcloud_fallback must be a boolean"));
+    }
+
+    #[test]
+    fn test_parse_checkpoint() {
+        let config = parse("[checkpoint]\nsize = \"250GB\"\nkeep = 5\n").unwrap();
+        assert_eq!(config.checkpoint_size_bytes, Some(250 << 30));
+        assert_eq!(config.checkpoint_keep, Some(5));
+        let config = parse("").unwrap();
+        assert_eq!((config.checkpoint_size_bytes, config.checkpoint_keep), (None, None));
+
+        let err = parse("[checkpoint]\nsize = \"250\"\n").unwrap_err();
+        assert!(err.to_string().contains("checkpoint.size: Invalid checkpoint size"));
+        assert!(parse("[checkpoint]\nsize = 250\n").is_err());
+        assert!(parse("[checkpoint]\nkeep = 0\n").is_err());
+        assert!(parse("[checkpoint]\nkeep = \"5\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_expected_gke_service_account() {
+        let config = parse("[gke]\nexpected_service_account = \"trainer@p.iam.gserviceaccount.com\"\n").unwrap();
+        assert_eq!(config.expected_gke_service_account.as_deref(), Some("trainer@p.iam.gserviceaccount.com"));
+        assert_eq!(parse("").unwrap().expected_gke_service_account, None);
+        assert!(parse("[gke]\nexpected_service_account = \"trainer\"\n").is_err());
+    }
+
+    #[test]
+    fn test_discover_explicit_missing_file() {
+        let result = ConfigFile::discover(Some("/nonexistent/tpu-doc.toml"));
+        let err = result.unwrap_err();
+        assert!(matches!(err, TpuDocError::ConfigError { .. }));
+        assert_eq!(err.exit_code(), crate::EXIT_USAGE_ERROR);
+    }
+}
@@ -0,0 +1,137 @@
+//! Reporting of errors that stop a command.
+//!
+//! Check failures are results and go into the report; this covers the run
+//! itself failing: invalid arguments, a broken configuration file, a
+//! command that cannot complete. With `--errors json` the error is written
+//! to stderr as one JSON object, so orchestration tooling does not have to
+//! scrape the text message:
+//!
+//! ```json
+//! {"error": {"code": "unknown_check_ids", "message": "Unknown check ID: HW-01 (did you mean HW-001?)",
+//!   "context": "running checks", "hint": "Run 'tpu-doc list' to see the available checks.", "exit_code": 4}}
+//! ```
+//!
+//! `code` is stable across releases (see [`TpuDocError::code`]); `hint` is
+//! null when there is no usual remedy.
+
+use crate::cli::args::ErrorFormat;
+use crate::util::json;
+use crate::{TpuDocError, EXIT_USAGE_ERROR};
+
+/// An error that stopped a command, with what was being done at the time
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorReport {
+    pub code: &'static str,
+    pub message: String,
+    /// What the command was doing, e.g. `running checks`
+    pub context: String,
+    pub hint: Option<String>,
+    pub exit_code: u8,
+}
+
+impl ErrorReport {
+    /// Report `error`, which stopped the command while `context`
+    pub fn from_error(context: &str, error: &TpuDocError) -> Self {
+        ErrorReport {
+            code: error.code(),
+            message: error.to_string(),
+            context: context.to_string(),
+            hint: error.hint().map(str::to_string),
+            exit_code: error.exit_code(),
+        }
+    }
+
+    /// Report invalid command-line arguments
+    pub fn usage(message: &str) -> Self {
+        ErrorReport {
+            code: "invalid_arguments",
+            message: message.to_string(),
+            context: "parsing arguments".to_string(),
+            hint: Some("Run 'tpu-doc --help' for usage information.".to_string()),
+            exit_code: EXIT_USAGE_ERROR,
+        }
+    }
+
+    /// Replace the hint
+    pub fn with_hint(mut self, hint: &str) -> Self {
+        self.hint = Some(hint.to_string());
+        self
+    }
+
+    /// The error as text: the message, then the hint on its own line
+    pub fn to_text(&self) -> String {
+        let message = match self.code {
+            "invalid_arguments" => format!("Error: {}", self.message),
+            _ => format!("Error {}: {}", self.context, self.message),
+        };
+        match &self.hint {
+            Some(hint) => format!("{}\n{}", message, hint),
+            None => message,
+        }
+    }
+
+    /// The error as a single-line JSON object
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"error\": {{\"code\": {}, \"message\": {}, \"context\": {}, \"hint\": {}, \"exit_code\": {}}}}}",
+            json::quote(self.code),
+            json::quote(&self.message),
+            json::quote(&self.context),
+            self.hint.as_deref().map(json::quote).unwrap_or_else(|| "null".to_string()),
+            self.exit_code
+        )
+    }
+
+    /// Write the error to stderr in `format`
+    pub fn print(&self, format: ErrorFormat) {
+        match format {
+            ErrorFormat::Text => eprintln!("{}", self.to_text()),
+            ErrorFormat::Json => eprintln!("{}", self.to_json()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::json::{self, JsonValue};
+
+    #[test]
+    fn test_error_report_formats() {
+        let error = TpuDocError::UnknownCheckIds {
+            ids: vec![("HW-01".to_string(), Some("HW-001".to_string()))],
+        };
+        let report = ErrorReport::from_error("running checks", &error);
+        assert_eq!(
+            report.to_text(),
+            "Error running checks: Unknown check ID: HW-01 (did you mean HW-001?)\n\
+             Run 'tpu-doc list' to see the available checks."
+        );
+
+        let parsed = json::parse(&report.to_json()).unwrap();
+        let error = parsed.get("error").unwrap();
+        assert_eq!(error.get("code").and_then(JsonValue::as_str), Some("unknown_check_ids"));
+        assert_eq!(error.get("context").and_then(JsonValue::as_str), Some("running checks"));
+        assert_eq!(error.get("exit_code").and_then(JsonValue::as_f64), Some(4.0));
+        assert!(!report.to_json().contains('\n'));
+
+        let error = TpuDocError::IoError {
+            context: "reading \"log\"".to_string(),
+            message: "line 1\nline 2".to_string(),
+            source: None,
+        };
+        let report = ErrorReport::from_error("analyzing log", &error);
+        assert_eq!(report.to_text(), "Error analyzing log: I/O error in reading \"log\": line 1\nline 2");
+        let parsed = json::parse(&report.to_json()).unwrap();
+        let error = parsed.get("error").unwrap();
+        assert!(error.get("hint").unwrap().is_null());
+        assert_eq!(error.get("exit_code").and_then(JsonValue::as_f64), Some(3.0));
+
+        let usage = ErrorReport::usage("Unknown option: '--bogus'");
+        assert_eq!(
+            usage.to_text(),
+            "Error: Unknown option: '--bogus'\nRun 'tpu-doc --help' for usage information."
+        );
+        assert_eq!(usage.code, "invalid_arguments");
+    }
+}
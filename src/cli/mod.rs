@@ -1,7 +1,11 @@
 //! CLI module for argument parsing and output formatting.
 //!
-//! This module provides zero-dependency command line argument parsing
-//! and output format selection.
+//! This module provides zero-dependency command line argument parsing,
+//! configuration file loading, and output format selection.
 
 pub mod args;
+pub mod config;
+pub mod errors;
 pub mod output;
+pub mod select;
+pub mod terminal;
@@ -2,10 +2,16 @@
 //!
 //! Provides terminal, JSON, and JUnit XML output formatters.
 //!
+//! Library consumers can add their own: implement [`OutputFormatter`] and
+//! register a factory for it by name with [`register_formatter`]. The check
+//! command then accepts that name as `--format <name>` (or in
+//! `TPU_PREFLIGHT_FORMAT`) alongside the built-in formats.
+//!
 //! # Graceful Degradation
 //!
 //! This module handles errors gracefully:
-//! - Non-TTY output: Color disabled automatically via NO_COLOR or --no-color
+//! - Non-TTY output: Color disabled automatically when stdout is not a
+//!   terminal, or via NO_COLOR or --no-color
 //! - Non-UTF8 data: Uses lossy conversion for any string output
 //! - Empty reports: Produces valid output with zero checks
 //! - Missing fields: Uses sensible defaults (0, empty string)
@@ -14,8 +20,15 @@
 //! All formatters produce valid output for any ValidationReport input.
 //! No function in this module will panic.
 
-use crate::cli::args::OutputFormat;
-use crate::engine::result::ValidationReport;
+use crate::cli::args::{Args, GroupBy, OutputFormat, Theme, BUILTIN_FORMATS};
+use crate::cli::terminal;
+use crate::engine::result::{ResultSummary, ValidationReport};
+use crate::util::time;
+use crate::{Check, CheckCategory, CheckResult};
+use std::sync::{Arc, Mutex};
+
+/// Most checks listed in the verbose text report's estimate drift table
+const MAX_DRIFT_ROWS: usize = 10;
 
 /// Trait for output formatters
 pub trait OutputFormatter {
@@ -28,6 +41,52 @@ pub struct TerminalFormatter {
     color: bool,
     verbose: bool,
     quiet: bool,
+    group_by: GroupBy,
+    theme: Theme,
+    glyphs: StatusGlyphs,
+    ascii: bool,
+    width: Option<usize>,
+    exit_status: Option<(u8, &'static str)>,
+}
+
+/// Labels printed before each check in the text report
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusGlyphs {
+    pub pass: String,
+    pub warn: String,
+    pub fail: String,
+    pub skip: String,
+}
+
+impl Default for StatusGlyphs {
+    fn default() -> Self {
+        StatusGlyphs {
+            pass: "[PASS]".to_string(),
+            warn: "[WARN]".to_string(),
+            fail: "[FAIL]".to_string(),
+            skip: "[SKIP]".to_string(),
+        }
+    }
+}
+
+impl StatusGlyphs {
+    /// Parse four comma-separated labels in pass, warn, fail, skip order
+    /// (e.g. `OK,WARN,FAIL,SKIP`)
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let labels: Vec<&str> = s.split(',').map(str::trim).collect();
+        match labels[..] {
+            [pass, warn, fail, skip] if labels.iter().all(|l| !l.is_empty()) => Ok(StatusGlyphs {
+                pass: pass.to_string(),
+                warn: warn.to_string(),
+                fail: fail.to_string(),
+                skip: skip.to_string(),
+            }),
+            _ => Err(format!(
+                "Invalid status glyphs: '{}'. Expected four comma-separated labels: pass,warn,fail,skip",
+                s
+            )),
+        }
+    }
 }
 
 impl TerminalFormatter {
@@ -36,6 +95,106 @@ impl TerminalFormatter {
             color,
             verbose,
             quiet,
+            group_by: GroupBy::Category,
+            theme: Theme::Default,
+            glyphs: StatusGlyphs::default(),
+            ascii: false,
+            width: None,
+            exit_status: None,
+        }
+    }
+
+    /// Report `status` as the exit code and its description instead of the
+    /// one the results imply, for commands with their own exit policy
+    pub fn with_exit_status(mut self, status: Option<(u8, &'static str)>) -> Self {
+        self.exit_status = status;
+        self
+    }
+
+    /// Fit check lines to `width` columns: long lines are cut short, or
+    /// wrapped in verbose mode so no detail is lost
+    pub fn with_width(mut self, width: Option<usize>) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// A check's report line, fitted to the width when one is set
+    fn fit_line(&self, status: &str, text: &str) -> String {
+        let indent = 2 + visible_len(status) + 1;
+        match self.width {
+            Some(width) if indent + text.chars().count() > width => {
+                let available = width.saturating_sub(indent).max(terminal::MIN_WIDTH / 2);
+                if self.verbose {
+                    let continuation = format!("\n{}", " ".repeat(indent));
+                    format!("  {} {}\n", status, terminal::wrap(text, available).join(&continuation))
+                } else {
+                    format!("  {} {}\n", status, terminal::truncate(text, available))
+                }
+            }
+            _ => format!("  {} {}\n", status, text),
+        }
+    }
+
+    /// Structured result data as `key  value` rows, aligned under the
+    /// check's name
+    fn data_table(&self, status: &str, data: &[(String, String)]) -> String {
+        let indent = " ".repeat(2 + visible_len(status) + 1);
+        let key_width = data.iter().map(|(key, _)| key.chars().count()).max().unwrap_or(0);
+        data.iter()
+            .map(|(key, value)| format!("{}  {:<width$}  {}\n", indent, key, value, width = key_width))
+            .collect()
+    }
+
+    /// Use `theme`'s colors for statuses
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Label statuses with `glyphs` instead of `[PASS]`, `[WARN]`, ...
+    pub fn with_glyphs(mut self, glyphs: StatusGlyphs) -> Self {
+        self.glyphs = glyphs;
+        self
+    }
+
+    /// Replace any non-ASCII characters in the output, including those in
+    /// check messages and custom glyphs
+    pub fn with_ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    /// Group checks by `group_by` instead of by category
+    pub fn with_group_by(mut self, group_by: GroupBy) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    /// Section headers and their checks, in display order
+    fn groups<'a>(&self, report: &'a ValidationReport) -> Vec<(&'static str, Vec<&'a Check>)> {
+        let matching = |f: fn(&Check) -> bool| report.checks.iter().filter(|c| f(c)).collect::<Vec<_>>();
+        match self.group_by {
+            GroupBy::Category => [
+                ("HARDWARE CHECKS", CheckCategory::Hardware),
+                ("STACK CHECKS", CheckCategory::Stack),
+                ("PERFORMANCE CHECKS", CheckCategory::Performance),
+                ("I/O CHECKS", CheckCategory::Io),
+                ("SECURITY CHECKS", CheckCategory::Security),
+                ("CONFIGURATION AUDIT CHECKS", CheckCategory::Config),
+            ]
+            .into_iter()
+            .map(|(header, category)| {
+                let checks = report.checks.iter().filter(|c| c.category == category).collect();
+                (header, checks)
+            })
+            .collect(),
+            // Most severe first, so problems are at the top when triaging
+            GroupBy::Status => vec![
+                ("FAILURES", matching(|c| matches!(c.result, Some(CheckResult::Fail { .. })))),
+                ("WARNINGS", matching(|c| matches!(c.result, Some(CheckResult::Warn { .. })))),
+                ("PASSED", matching(|c| matches!(c.result, Some(CheckResult::Pass { .. })))),
+                ("SKIPPED", matching(|c| matches!(c.result, Some(CheckResult::Skip { .. }) | None))),
+            ],
         }
     }
 
@@ -47,20 +206,57 @@ impl TerminalFormatter {
         }
     }
 
+    // The high-contrast theme uses bold text on solid backgrounds, which
+    // stays readable on dim displays and in log viewers' color palettes
+
     fn green(&self, text: &str) -> String {
-        self.colorize(text, "32")
+        self.colorize(text, match self.theme {
+            Theme::Default => "32",
+            Theme::HighContrast => "1;30;42",
+        })
     }
 
     fn yellow(&self, text: &str) -> String {
-        self.colorize(text, "33")
+        self.colorize(text, match self.theme {
+            Theme::Default => "33",
+            Theme::HighContrast => "1;30;43",
+        })
     }
 
     fn red(&self, text: &str) -> String {
-        self.colorize(text, "31")
+        self.colorize(text, match self.theme {
+            Theme::Default => "31",
+            Theme::HighContrast => "1;97;41",
+        })
     }
 
     fn gray(&self, text: &str) -> String {
-        self.colorize(text, "90")
+        self.colorize(text, match self.theme {
+            Theme::Default => "90",
+            Theme::HighContrast => "1;97",
+        })
+    }
+
+    /// The closing banner: the overall status as a badge (bold on a solid
+    /// background in every theme, so it stands out at the end of a long
+    /// log), followed by the counts, most severe first
+    fn banner(&self, summary: &ResultSummary) -> String {
+        let (label, color_code) = if summary.failed > 0 {
+            ("FAILED", "1;97;41")
+        } else if summary.warned > 0 {
+            ("WARNINGS", "1;30;43")
+        } else {
+            ("PASSED", "1;30;42")
+        };
+        let badge = if self.color {
+            self.colorize(&format!(" {} ", label), color_code)
+        } else {
+            format!("[ {} ]", label)
+        };
+        format!(
+            "{} {} failed, {} warnings, {} passed, {} skipped",
+            badge, summary.failed, summary.warned, summary.passed, summary.skipped
+        )
     }
 }
 
@@ -75,32 +271,22 @@ impl OutputFormatter for TerminalFormatter {
         if let Some(ref tpu_type) = report.tpu_type {
             output.push_str(&format!("TPU Type: {}\n", tpu_type));
         }
-        output.push_str(&format!("Timestamp: {}\n", format_timestamp(report.timestamp)));
+        // The run's start keeps its milliseconds; older saved reports only have seconds
+        let started_at_ms = if report.started_at_ms > 0 { report.started_at_ms } else { report.timestamp * 1000 };
+        output.push_str(&format!("Timestamp: {}\n", time::rfc3339(started_at_ms)));
+        if !report.run_id.is_empty() {
+            output.push_str(&format!("Run ID: {}\n", report.run_id));
+        }
         output.push_str("--------------------------------------------------------------------------------\n\n");
 
-        // Group checks by category
-        let categories = [
-            ("HARDWARE CHECKS", "Hardware"),
-            ("STACK CHECKS", "Stack"),
-            ("PERFORMANCE CHECKS", "Performance"),
-            ("I/O CHECKS", "Io"),
-            ("SECURITY CHECKS", "Security"),
-        ];
-
-        for (header, category) in categories.iter() {
-            let category_checks: Vec<_> = report
-                .checks
-                .iter()
-                .filter(|c| format!("{:?}", c.category) == *category)
-                .collect();
-
-            if category_checks.is_empty() {
+        for (header, group_checks) in self.groups(report) {
+            if group_checks.is_empty() {
                 continue;
             }
 
-            // Skip category if quiet mode and no failures/warnings
+            // Skip group if quiet mode and no failures/warnings
             if self.quiet {
-                let has_issues = category_checks
+                let has_issues = group_checks
                     .iter()
                     .any(|c| matches!(&c.result, Some(crate::CheckResult::Fail { .. }) | Some(crate::CheckResult::Warn { .. })));
                 if !has_issues {
@@ -110,7 +296,7 @@ impl OutputFormatter for TerminalFormatter {
 
             output.push_str(&format!("{}\n", header));
 
-            for check in category_checks {
+            for check in group_checks {
                 // Skip passing checks in quiet mode
                 if self.quiet {
                     if let Some(ref result) = check.result {
@@ -121,8 +307,8 @@ impl OutputFormatter for TerminalFormatter {
                 }
 
                 let (status, message) = match &check.result {
-                    Some(crate::CheckResult::Pass { message, duration_ms }) => {
-                        let status = self.green("[PASS]");
+                    Some(crate::CheckResult::Pass { message, duration_ms, .. }) => {
+                        let status = self.green(&self.glyphs.pass);
                         let msg = if self.verbose {
                             format!("{} ({}ms)", message, duration_ms)
                         } else {
@@ -130,8 +316,8 @@ impl OutputFormatter for TerminalFormatter {
                         };
                         (status, msg)
                     }
-                    Some(crate::CheckResult::Warn { message, details, duration_ms }) => {
-                        let status = self.yellow("[WARN]");
+                    Some(crate::CheckResult::Warn { message, details, duration_ms, .. }) => {
+                        let status = self.yellow(&self.glyphs.warn);
                         let msg = if self.verbose {
                             format!("{} - {} ({}ms)", message, details, duration_ms)
                         } else {
@@ -139,8 +325,8 @@ impl OutputFormatter for TerminalFormatter {
                         };
                         (status, msg)
                     }
-                    Some(crate::CheckResult::Fail { message, details, duration_ms }) => {
-                        let status = self.red("[FAIL]");
+                    Some(crate::CheckResult::Fail { message, details, duration_ms, .. }) => {
+                        let status = self.red(&self.glyphs.fail);
                         let msg = if self.verbose {
                             format!("{} - {} ({}ms)", message, details, duration_ms)
                         } else {
@@ -149,7 +335,7 @@ impl OutputFormatter for TerminalFormatter {
                         (status, msg)
                     }
                     Some(crate::CheckResult::Skip { reason }) => {
-                        let status = self.gray("[SKIP]");
+                        let status = self.gray(&self.glyphs.skip);
                         (status, reason.clone())
                     }
                     None => {
@@ -158,7 +344,18 @@ impl OutputFormatter for TerminalFormatter {
                     }
                 };
 
-                output.push_str(&format!("  {} {}: {} ({})\n", status, check.id, check.name, message));
+                output.push_str(&self.fit_line(&status, &format!("{}: {} ({})", check.id, check.name, message)));
+                if self.verbose {
+                    if let Some(result) = &check.result {
+                        output.push_str(&self.data_table(&status, result.data()));
+                    }
+                }
+                // Where to route findings, so whoever reads the report knows who can act on them
+                let finding =
+                    matches!(&check.result, Some(crate::CheckResult::Fail { .. } | crate::CheckResult::Warn { .. }));
+                if finding || self.verbose {
+                    output.push_str(&self.data_table(&status, &check.ownership.entries()));
+                }
             }
 
             output.push('\n');
@@ -175,36 +372,108 @@ impl OutputFormatter for TerminalFormatter {
             "Total time: {:.1}s\n",
             report.total_duration_ms as f64 / 1000.0
         ));
+        let downgraded = report.checks.iter().filter(|c| crate::engine::policy::is_downgraded(c)).count();
+        if downgraded > 0 {
+            output.push_str(&format!("Downgraded by policy: {}\n", downgraded));
+        }
+        let cached = report.checks.iter().filter(|c| crate::engine::history::is_cached(c)).count();
+        if cached > 0 {
+            output.push_str(&format!("Cached results: {} (reused from earlier runs)\n", cached));
+        }
+        if summary.total_duration_ms > summary.wall_clock_ms {
+            output.push_str(&format!(
+                "Check time: {:.1}s (sum of check durations; checks ran in parallel)\n",
+                summary.total_duration_ms as f64 / 1000.0
+            ));
+        }
 
-        let exit_desc = if summary.failed > 0 {
-            "failures detected"
-        } else if summary.warned > 0 {
-            "warnings detected"
-        } else {
-            "all checks passed"
-        };
-        let exit_code = if summary.failed > 0 {
-            1
-        } else if summary.warned > 0 {
-            2
-        } else {
-            0
-        };
+        // Where the time went, longest category first
+        if self.verbose && !summary.categories.is_empty() {
+            let mut categories: Vec<_> = summary.categories.iter().collect();
+            categories.sort_by_key(|c| std::cmp::Reverse(c.total_duration_ms));
+            output.push_str("Time by category:\n");
+            for category in categories {
+                output.push_str(&format!(
+                    "  {:<12} {:>3} checks {:>8.1}s {:>4}%\n",
+                    category.category.to_string(),
+                    category.total,
+                    category.total_duration_ms as f64 / 1000.0,
+                    category.total_duration_ms * 100 / summary.total_duration_ms.max(1)
+                ));
+            }
+        }
+
+        // How far the duration estimates used for planning were off
+        let drift = report.estimate_drift();
+        if self.verbose && !drift.is_empty() {
+            output.push_str("Estimate drift (largest first):\n");
+            for entry in drift.iter().take(MAX_DRIFT_ROWS) {
+                output.push_str(&format!(
+                    "  {:<12} {:>7.1}s actual {:>7.1}s estimated {:>+8.1}s {:>5}%\n",
+                    entry.id,
+                    entry.actual_ms as f64 / 1000.0,
+                    entry.estimated_ms as f64 / 1000.0,
+                    entry.drift_ms() as f64 / 1000.0,
+                    entry.percent_of_estimate()
+                ));
+            }
+            if drift.len() > MAX_DRIFT_ROWS {
+                output.push_str(&format!(
+                    "  ... {} more (all checks are in the JSON report)\n",
+                    drift.len() - MAX_DRIFT_ROWS
+                ));
+            }
+        }
+
+        // Issues the checks worked around, which explain fallbacks and skips
+        if self.verbose && !report.diagnostics.is_empty() {
+            output.push_str("Diagnostics:\n");
+            for diagnostic in &report.diagnostics {
+                output.push_str(&format!(
+                    "  {:<12} {}: {}\n",
+                    diagnostic.check_id, diagnostic.source, diagnostic.message
+                ));
+            }
+        }
+
+        let (exit_code, exit_desc) = self.exit_status.unwrap_or_else(|| exit_status(&summary));
         output.push_str(&format!("Exit code: {} ({})\n", exit_code, exit_desc));
-        output.push_str("--------------------------------------------------------------------------------");
+        output.push_str("--------------------------------------------------------------------------------\n");
+        output.push_str(&self.banner(&summary));
 
-        output
+        if self.ascii {
+            to_ascii(&output)
+        } else {
+            output
+        }
     }
 }
 
-/// JSON formatter
+/// Version of tpu-doc recorded in JSON reports
+const TOOL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// JSON formatter. Keys are always written in the same order: the report
+/// fields, then each check's fields, with a check's `data` entries in the
+/// order the check recorded them. Without pretty-printing the report is a
+/// single line, for log shippers that read one record per line.
 pub struct JsonFormatter {
     pretty: bool,
+    failures_only: bool,
 }
 
 impl JsonFormatter {
     pub fn new(pretty: bool) -> Self {
-        JsonFormatter { pretty }
+        JsonFormatter {
+            pretty,
+            failures_only: false,
+        }
+    }
+
+    /// List only the checks that failed or warned (`--quiet`); the summary
+    /// still counts every check
+    pub fn with_failures_only(mut self, failures_only: bool) -> Self {
+        self.failures_only = failures_only;
+        self
     }
 
     fn escape_json_string(s: &str) -> String {
@@ -236,6 +505,16 @@ impl OutputFormatter for JsonFormatter {
         output.push('{');
         output.push_str(newline);
 
+        // Producer and run, for deduplicating and joining reports downstream
+        output.push_str(&format!("{}\"tool_version\":{}\"{}\",{}", indent, space, TOOL_VERSION, newline));
+        output.push_str(&format!(
+            "{}\"run_id\":{}\"{}\",{}",
+            indent,
+            space,
+            Self::escape_json_string(&report.run_id),
+            newline
+        ));
+
         // Timestamp
         output.push_str(&format!("{}\"timestamp\":{}{},{}", indent, space, report.timestamp, newline));
 
@@ -279,16 +558,61 @@ impl OutputFormatter for JsonFormatter {
         output.push_str(newline);
         output.push_str(&format!("{}{}\"skipped\":{}{},", indent, indent, space, summary.skipped));
         output.push_str(newline);
-        output.push_str(&format!("{}{}\"total\":{}{}", indent, indent, space, summary.total));
+        output.push_str(&format!("{}{}\"total\":{}{},", indent, indent, space, summary.total));
+        output.push_str(newline);
+        output.push_str(&format!(
+            "{}{}\"check_duration_ms\":{}{},",
+            indent, indent, space, summary.total_duration_ms
+        ));
+        output.push_str(newline);
+        output.push_str(&format!("{}{}\"categories\":{}{{", indent, indent, space));
+        output.push_str(newline);
+        for (i, category) in summary.categories.iter().enumerate() {
+            output.push_str(&format!(
+                "{}{}{}\"{:?}\":{}{{\"passed\":{}{},{}\"warned\":{}{},{}\"failed\":{}{},{}\"skipped\":{}{},{}\"total\":{}{},{}\"duration_ms\":{}{}}}{}",
+                indent, indent, indent, category.category, space,
+                space, category.passed, space,
+                space, category.warned, space,
+                space, category.failed, space,
+                space, category.skipped, space,
+                space, category.total, space,
+                space, category.total_duration_ms,
+                if i < summary.categories.len() - 1 { "," } else { "" }
+            ));
+            output.push_str(newline);
+        }
+        output.push_str(&format!("{}{}}}", indent, indent));
         output.push_str(newline);
         output.push_str(&format!("{}}},", indent));
         output.push_str(newline);
 
+        // Actual against estimated durations, largest drift first
+        let drift = report.estimate_drift();
+        if !drift.is_empty() {
+            output.push_str(&format!("{}\"estimate_drift\":{}[", indent, space));
+            output.push_str(newline);
+            for (i, entry) in drift.iter().enumerate() {
+                output.push_str(&format!(
+                    "{}{}{{\"id\":{}\"{}\",{}\"estimated_ms\":{}{},{}\"actual_ms\":{}{},{}\"drift_ms\":{}{}}}{}",
+                    indent, indent,
+                    space, Self::escape_json_string(&entry.id), space,
+                    space, entry.estimated_ms, space,
+                    space, entry.actual_ms, space,
+                    space, entry.drift_ms(),
+                    if i < drift.len() - 1 { "," } else { "" }
+                ));
+                output.push_str(newline);
+            }
+            output.push_str(&format!("{}],", indent));
+            output.push_str(newline);
+        }
+
         // Checks array
         output.push_str(&format!("{}\"checks\":{}[", indent, space));
         output.push_str(newline);
 
-        for (i, check) in report.checks.iter().enumerate() {
+        let checks: Vec<&Check> = report.checks.iter().filter(|c| !self.failures_only || is_finding(c)).collect();
+        for (i, check) in checks.iter().enumerate() {
             output.push_str(&format!("{}{}{{", indent, indent));
             output.push_str(newline);
 
@@ -324,12 +648,46 @@ impl OutputFormatter for JsonFormatter {
             ));
             output.push_str(newline);
 
+            // Who to route the check to, when known
+            for (key, value) in check.ownership.entries() {
+                output.push_str(&format!(
+                    "{}{}{}\"{}\":{}\"{}\",",
+                    indent, indent, indent, key, space,
+                    Self::escape_json_string(&value)
+                ));
+                output.push_str(newline);
+            }
+
+            if let Some(start_ms) = check.start_ms {
+                output.push_str(&format!(
+                    "{}{}{}\"start_ms\":{}{},",
+                    indent, indent, indent, space, start_ms
+                ));
+                output.push_str(newline);
+
+                // Wall-clock start and end, when the run's start is known
+                if report.started_at_ms > 0 {
+                    let started_at = report.started_at_ms + start_ms;
+                    let duration_ms = check.result.as_ref().map(result_duration_ms).unwrap_or(0);
+                    output.push_str(&format!(
+                        "{}{}{}\"started_at\":{}\"{}\",",
+                        indent, indent, indent, space, time::rfc3339(started_at)
+                    ));
+                    output.push_str(newline);
+                    output.push_str(&format!(
+                        "{}{}{}\"finished_at\":{}\"{}\",",
+                        indent, indent, indent, space, time::rfc3339(started_at + duration_ms)
+                    ));
+                    output.push_str(newline);
+                }
+            }
+
             // Result
             output.push_str(&format!("{}{}{}\"result\":{}{{", indent, indent, indent, space));
             output.push_str(newline);
 
             match &check.result {
-                Some(crate::CheckResult::Pass { message, duration_ms }) => {
+                Some(crate::CheckResult::Pass { message, duration_ms, .. }) => {
                     output.push_str(&format!(
                         "{}{}{}{}\"status\":{}\"pass\",",
                         indent, indent, indent, indent, space
@@ -347,7 +705,7 @@ impl OutputFormatter for JsonFormatter {
                         indent, indent, indent, indent, space, duration_ms
                     ));
                 }
-                Some(crate::CheckResult::Warn { message, details, duration_ms }) => {
+                Some(crate::CheckResult::Warn { message, details, duration_ms, .. }) => {
                     output.push_str(&format!(
                         "{}{}{}{}\"status\":{}\"warn\",",
                         indent, indent, indent, indent, space
@@ -372,7 +730,7 @@ impl OutputFormatter for JsonFormatter {
                         indent, indent, indent, indent, space, duration_ms
                     ));
                 }
-                Some(crate::CheckResult::Fail { message, details, duration_ms }) => {
+                Some(crate::CheckResult::Fail { message, details, duration_ms, .. }) => {
                     output.push_str(&format!(
                         "{}{}{}{}\"status\":{}\"fail\",",
                         indent, indent, indent, indent, space
@@ -417,12 +775,34 @@ impl OutputFormatter for JsonFormatter {
                 }
             }
 
+            let data = check.result.as_ref().map(|result| result.data()).unwrap_or_default();
+            if !data.is_empty() {
+                let entries: Vec<String> = data
+                    .iter()
+                    .map(|(key, value)| {
+                        format!(
+                            "\"{}\":{}\"{}\"",
+                            Self::escape_json_string(key),
+                            space,
+                            Self::escape_json_string(value)
+                        )
+                    })
+                    .collect();
+                output.push(',');
+                output.push_str(newline);
+                output.push_str(&format!(
+                    "{}{}{}{}\"data\":{}{{{}}}",
+                    indent, indent, indent, indent, space,
+                    entries.join(&format!(",{}", space))
+                ));
+            }
+
             output.push_str(newline);
             output.push_str(&format!("{}{}{}}}", indent, indent, indent));
             output.push_str(newline);
 
             output.push_str(&format!("{}{}}}", indent, indent));
-            if i < report.checks.len() - 1 {
+            if i < checks.len() - 1 {
                 output.push(',');
             }
             output.push_str(newline);
@@ -437,11 +817,20 @@ impl OutputFormatter for JsonFormatter {
 }
 
 /// JUnit XML formatter
-pub struct JunitFormatter;
+pub struct JunitFormatter {
+    failures_only: bool,
+}
 
 impl JunitFormatter {
     pub fn new() -> Self {
-        JunitFormatter
+        JunitFormatter { failures_only: false }
+    }
+
+    /// Write testcases only for the checks that failed or warned
+    /// (`--quiet`); the suite counts still include every check
+    pub fn with_failures_only(mut self, failures_only: bool) -> Self {
+        self.failures_only = failures_only;
+        self
     }
 
     fn escape_xml(s: &str) -> String {
@@ -458,6 +847,42 @@ impl JunitFormatter {
         }
         result
     }
+
+    /// `<properties>` for a testcase: the check's owner and links, the
+    /// failure or warning details, then each measured value from the
+    /// result's data, with the configuration audit's recommendation as
+    /// `remediation`; empty when there are none
+    fn properties(check: &Check) -> String {
+        let Some(result) = &check.result else {
+            return String::new();
+        };
+        let ownership = check.ownership.entries();
+        let mut properties: Vec<(&str, &str)> = ownership.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        if let crate::CheckResult::Warn { details, .. } | crate::CheckResult::Fail { details, .. } = result {
+            if !details.is_empty() {
+                properties.push(("details", details));
+            }
+        }
+        for (key, value) in result.data() {
+            let name = if key == crate::checks::config::RECOMMENDATION_DATA_KEY { "remediation" } else { key };
+            properties.push((name, value));
+        }
+        if properties.is_empty() {
+            return String::new();
+        }
+
+        // Line breaks are escaped so XML parsers keep multi-line details intact
+        let mut output = String::from("      <properties>\n");
+        for (name, value) in properties {
+            output.push_str(&format!(
+                "        <property name=\"{}\" value=\"{}\" />\n",
+                Self::escape_xml(name),
+                Self::escape_xml(value).replace('\n', "&#10;")
+            ));
+        }
+        output.push_str("      </properties>\n");
+        output
+    }
 }
 
 impl Default for JunitFormatter {
@@ -487,6 +912,7 @@ impl OutputFormatter for JunitFormatter {
             ("Performance", "performance"),
             ("Io", "io"),
             ("Security", "security"),
+            ("Config", "config"),
         ];
 
         for (category, suite_name) in categories.iter() {
@@ -496,7 +922,12 @@ impl OutputFormatter for JunitFormatter {
                 .filter(|c| format!("{:?}", c.category) == *category)
                 .collect();
 
-            if category_checks.is_empty() {
+            let shown: Vec<&Check> = category_checks
+                .iter()
+                .copied()
+                .filter(|c| !self.failures_only || is_finding(c))
+                .collect();
+            if shown.is_empty() {
                 continue;
             }
 
@@ -526,8 +957,14 @@ impl OutputFormatter for JunitFormatter {
                 suite_skipped,
                 suite_time as f64 / 1000.0
             ));
+            if !report.run_id.is_empty() {
+                output.push_str(&format!(
+                    "    <properties>\n      <property name=\"run_id\" value=\"{}\" />\n    </properties>\n",
+                    Self::escape_xml(&report.run_id)
+                ));
+            }
 
-            for check in category_checks {
+            for check in shown {
                 let time = match &check.result {
                     Some(crate::CheckResult::Pass { duration_ms, .. }) => *duration_ms,
                     Some(crate::CheckResult::Warn { duration_ms, .. }) => *duration_ms,
@@ -545,6 +982,7 @@ impl OutputFormatter for JunitFormatter {
                 match &check.result {
                     Some(crate::CheckResult::Pass { message, .. }) => {
                         output.push_str(">\n");
+                        output.push_str(&Self::properties(check));
                         output.push_str(&format!(
                             "      <system-out>{}</system-out>\n",
                             Self::escape_xml(message)
@@ -553,6 +991,7 @@ impl OutputFormatter for JunitFormatter {
                     }
                     Some(crate::CheckResult::Warn { message, details, .. }) => {
                         output.push_str(">\n");
+                        output.push_str(&Self::properties(check));
                         output.push_str(&format!(
                             "      <system-out>WARNING: {} - {}</system-out>\n",
                             Self::escape_xml(message),
@@ -562,6 +1001,7 @@ impl OutputFormatter for JunitFormatter {
                     }
                     Some(crate::CheckResult::Fail { message, details, .. }) => {
                         output.push_str(">\n");
+                        output.push_str(&Self::properties(check));
                         output.push_str(&format!(
                             "      <failure message=\"{}\">{}</failure>\n",
                             Self::escape_xml(message),
@@ -571,6 +1011,7 @@ impl OutputFormatter for JunitFormatter {
                     }
                     Some(crate::CheckResult::Skip { reason }) => {
                         output.push_str(">\n");
+                        output.push_str(&Self::properties(check));
                         output.push_str(&format!(
                             "      <skipped message=\"{}\" />\n",
                             Self::escape_xml(reason)
@@ -591,6 +1032,86 @@ impl OutputFormatter for JunitFormatter {
     }
 }
 
+/// Verdict-only formatter for `--summary-only`: the summary counts and the
+/// exit code, as text or JSON
+pub struct SummaryFormatter {
+    json: bool,
+    exit_status: Option<(u8, &'static str)>,
+}
+
+impl SummaryFormatter {
+    pub fn new(json: bool) -> Self {
+        SummaryFormatter { json, exit_status: None }
+    }
+
+    /// Report `status` as the exit code and verdict instead of the one the
+    /// results imply
+    pub fn with_exit_status(mut self, status: Option<(u8, &'static str)>) -> Self {
+        self.exit_status = status;
+        self
+    }
+}
+
+impl OutputFormatter for SummaryFormatter {
+    fn format(&self, report: &ValidationReport) -> String {
+        let summary = report.summary();
+        let (exit_code, exit_desc) = self.exit_status.unwrap_or_else(|| exit_status(&summary));
+
+        if self.json {
+            format!(
+                "{{\"tool_version\": \"{}\", \"run_id\": \"{}\", \"passed\": {}, \"warned\": {}, \"failed\": {}, \"skipped\": {}, \"total\": {}, \"total_duration_ms\": {}, \"exit_code\": {}, \"verdict\": \"{}\"}}",
+                TOOL_VERSION,
+                JsonFormatter::escape_json_string(&report.run_id),
+                summary.passed,
+                summary.warned,
+                summary.failed,
+                summary.skipped,
+                summary.total,
+                report.total_duration_ms,
+                exit_code,
+                exit_desc
+            )
+        } else {
+            let mut output = format!(
+                "SUMMARY: {} passed, {} warnings, {} failed, {} skipped\nExit code: {} ({})",
+                summary.passed, summary.warned, summary.failed, summary.skipped, exit_code, exit_desc
+            );
+            if !report.run_id.is_empty() {
+                output.push_str(&format!("\nRun ID: {}", report.run_id));
+            }
+            output
+        }
+    }
+}
+
+/// The run's overall result as a shields.io endpoint badge
+/// (<https://shields.io/badges/endpoint-badge>), for status dashboards
+pub fn format_badge(report: &ValidationReport) -> String {
+    let summary = report.summary();
+    let (message, color) = if summary.failed > 0 {
+        (format!("{} failed", summary.failed), "red")
+    } else if summary.warned > 0 {
+        (format!("{} warnings", summary.warned), "yellow")
+    } else {
+        (format!("{} passed", summary.passed), "brightgreen")
+    };
+    format!(
+        "{{\"schemaVersion\": 1, \"label\": \"tpu-doc\", \"message\": \"{}\", \"color\": \"{}\"}}\n",
+        message, color
+    )
+}
+
+/// The process exit code for a run's results and its description
+pub fn exit_status(summary: &ResultSummary) -> (u8, &'static str) {
+    if summary.failed > 0 {
+        (1, "failures detected")
+    } else if summary.warned > 0 {
+        (2, "warnings detected")
+    } else {
+        (0, "all checks passed")
+    }
+}
+
 /// Get a formatter based on the output format
 pub fn get_formatter(
     format: &OutputFormat,
@@ -600,73 +1121,203 @@ pub fn get_formatter(
 ) -> Box<dyn OutputFormatter> {
     match format {
         OutputFormat::Text => Box::new(TerminalFormatter::new(!no_color, verbose, quiet)),
-        OutputFormat::Json => Box::new(JsonFormatter::new(true)),
-        OutputFormat::Junit => Box::new(JunitFormatter::new()),
+        OutputFormat::Json => Box::new(JsonFormatter::new(true).with_failures_only(quiet)),
+        OutputFormat::Junit => Box::new(JunitFormatter::new().with_failures_only(quiet)),
+        OutputFormat::Custom(name) => {
+            let args = Args {
+                no_color,
+                verbose,
+                quiet,
+                ..Args::default()
+            };
+            // A format name is only parsed once registered; fall back to text
+            // should a caller construct an unregistered one
+            custom_formatter(name, &args).unwrap_or_else(|| Box::new(TerminalFormatter::new(!no_color, verbose, quiet)))
+        }
     }
 }
 
-/// Format a Unix timestamp as ISO 8601
-fn format_timestamp(timestamp: u64) -> String {
-    // Simple ISO 8601 formatting without external dependencies
-    // This is a basic implementation that works for recent timestamps
-    let secs = timestamp;
-    let days_since_epoch = secs / 86400;
-    let time_of_day = secs % 86400;
+/// Builds a registered formatter, honoring the output options in the
+/// `Args` it is given (`no_color`, `verbose`, `quiet`, ...) as it sees fit
+pub type FormatterFactory = Arc<dyn Fn(&Args) -> Box<dyn OutputFormatter> + Send + Sync>;
+
+/// Formatters registered with [`register_formatter`], by name
+static CUSTOM_FORMATTERS: Mutex<Vec<(String, FormatterFactory)>> = Mutex::new(Vec::new());
+
+/// Register `factory` as the formatter for `--format <name>`, replacing an
+/// earlier registration of `name`.
+///
+/// Names are case-insensitive and made of letters, digits, `-`, and `_`;
+/// the built-in format names cannot be registered.
+pub fn register_formatter(
+    name: &str,
+    factory: impl Fn(&Args) -> Box<dyn OutputFormatter> + Send + Sync + 'static,
+) -> Result<(), String> {
+    let name = name.to_lowercase();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("Invalid formatter name: '{}' (use letters, digits, '-', and '_')", name));
+    }
+    if BUILTIN_FORMATS.contains(&name.as_str()) {
+        return Err(format!("'{}' is a built-in output format and cannot be replaced", name));
+    }
+    let mut formatters = CUSTOM_FORMATTERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    formatters.retain(|(registered, _)| *registered != name);
+    formatters.push((name, Arc::new(factory)));
+    Ok(())
+}
 
-    let hours = time_of_day / 3600;
-    let minutes = (time_of_day % 3600) / 60;
-    let seconds = time_of_day % 60;
+/// Names of the registered formatters, in registration order
+pub fn registered_formatters() -> Vec<String> {
+    let formatters = CUSTOM_FORMATTERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    formatters.iter().map(|(name, _)| name.clone()).collect()
+}
 
-    // Calculate year, month, day from days since epoch
-    // Using a simplified algorithm
-    let mut year = 1970;
-    let mut remaining_days = days_since_epoch;
+/// The registered formatter `name`, built for `args`
+fn custom_formatter(name: &str, args: &Args) -> Option<Box<dyn OutputFormatter>> {
+    let factory = {
+        let formatters = CUSTOM_FORMATTERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        formatters.iter().find(|(registered, _)| registered == name).map(|(_, factory)| factory.clone())
+    };
+    // The factory runs unlocked, so it may look up or register formatters itself
+    factory.map(|factory| factory(args))
+}
 
-    loop {
-        let days_in_year = if is_leap_year(year) { 366 } else { 365 };
-        if remaining_days < days_in_year {
-            break;
-        }
-        remaining_days -= days_in_year;
-        year += 1;
+/// Get the formatter for the output options in `args`
+pub fn formatter_for_args(args: &Args) -> Box<dyn OutputFormatter> {
+    formatter_with_exit_status(args, None)
+}
+
+/// Get the formatter for the output options in `args`, reporting `status`
+/// as the exit code when set instead of the one the results imply
+pub fn formatter_with_exit_status(args: &Args, status: Option<(u8, &'static str)>) -> Box<dyn OutputFormatter> {
+    if args.summary_only {
+        return Box::new(SummaryFormatter::new(args.format == OutputFormat::Json).with_exit_status(status));
+    }
+    match &args.format {
+        OutputFormat::Text => Box::new(
+            TerminalFormatter::new(terminal::use_color(args.no_color), args.verbose, args.quiet)
+                .with_group_by(args.group_by.clone())
+                .with_theme(args.theme.clone())
+                .with_glyphs(args.glyphs.clone().unwrap_or_default())
+                .with_ascii(args.ascii)
+                .with_width(terminal::width())
+                .with_exit_status(status),
+        ),
+        OutputFormat::Json => Box::new(JsonFormatter::new(!args.json_compact).with_failures_only(args.quiet)),
+        OutputFormat::Custom(name) => custom_formatter(name, args)
+            .unwrap_or_else(|| get_formatter(&OutputFormat::Text, args.no_color, args.verbose, args.quiet)),
+        _ => get_formatter(&args.format, args.no_color, args.verbose, args.quiet),
     }
+}
 
-    let mut month = 1;
-    loop {
-        let days_in_month = days_in_month(year, month);
-        if remaining_days < days_in_month {
-            break;
+/// Whether `check` is listed in quiet output: it failed, warned, or did
+/// not run
+fn is_finding(check: &Check) -> bool {
+    !matches!(&check.result, Some(crate::CheckResult::Pass { .. }) | Some(crate::CheckResult::Skip { .. }))
+}
+
+/// Characters in `text` that are displayed, skipping ANSI color sequences
+fn visible_len(text: &str) -> usize {
+    let mut len = 0;
+    let mut in_escape = false;
+    for c in text.chars() {
+        match c {
+            '\x1b' => in_escape = true,
+            'm' if in_escape => in_escape = false,
+            _ if in_escape => {}
+            _ => len += 1,
         }
-        remaining_days -= days_in_month;
-        month += 1;
     }
+    len
+}
 
-    let day = remaining_days + 1;
-
-    format!(
-        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
-        year, month, day, hours, minutes, seconds
-    )
+/// Replace non-ASCII characters with close ASCII equivalents, or `?`
+pub fn to_ascii(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            c if c.is_ascii() => output.push(c),
+            '\u{2018}' | '\u{2019}' | '\u{2032}' => output.push('\''),
+            '\u{201c}' | '\u{201d}' | '\u{2033}' => output.push('"'),
+            '\u{2010}'..='\u{2015}' | '\u{2212}' | '\u{2500}' | '\u{2501}' => output.push('-'),
+            '\u{2502}' | '\u{2503}' => output.push('|'),
+            '\u{250c}'..='\u{254b}' => output.push('+'),
+            '\u{2026}' => output.push_str("..."),
+            '\u{2713}' | '\u{2714}' | '\u{2705}' => output.push_str("ok"),
+            '\u{2717}' | '\u{2718}' | '\u{274c}' => output.push('x'),
+            '\u{00b0}' => output.push_str("deg"),
+            '\u{00b5}' | '\u{03bc}' => output.push('u'),
+            '\u{00a0}' | '\u{2002}'..='\u{200a}' => output.push(' '),
+            // Zero-width characters and emoji variation selectors
+            '\u{200b}'..='\u{200d}' | '\u{fe0e}' | '\u{fe0f}' => {}
+            _ => output.push('?'),
+        }
+    }
+    output
 }
 
-fn is_leap_year(year: u64) -> bool {
-    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+/// Width of the bar area in the timeline view
+const TIMELINE_WIDTH: usize = 50;
+
+/// Format when each check ran, relative to the start of the run.
+///
+/// Checks are listed in start order with a bar spanning their run time,
+/// scaled to the whole run: overlapping bars ran in parallel, and a long bar
+/// that others wait behind is the check holding the run up. Checks without
+/// a recorded start time are left out.
+pub fn format_timeline(report: &ValidationReport) -> String {
+    let mut checks: Vec<_> = report
+        .checks
+        .iter()
+        .filter_map(|c| Some((c, c.start_ms?, c.result.as_ref()?)))
+        .collect();
+    checks.sort_by_key(|(c, start_ms, _)| (*start_ms, c.id.clone()));
+
+    let span_ms = checks
+        .iter()
+        .map(|(_, start_ms, result)| start_ms + result_duration_ms(result))
+        .max()
+        .unwrap_or(0)
+        .max(report.total_duration_ms)
+        .max(1);
+    let id_width = checks.iter().map(|(c, _, _)| c.id.len()).max().unwrap_or(0);
+
+    let mut output = String::new();
+    output.push_str("--------------------------------------------------------------------------------\n");
+    output.push_str(&format!("TIMELINE (0-{}ms)\n", span_ms));
+    for (check, start_ms, result) in checks {
+        let duration_ms = result_duration_ms(result);
+        let column = |ms: u64| ((ms as u128 * TIMELINE_WIDTH as u128) / span_ms as u128) as usize;
+        let begin = column(start_ms).min(TIMELINE_WIDTH - 1);
+        let end = column(start_ms + duration_ms).clamp(begin + 1, TIMELINE_WIDTH);
+        let bar: String = (0..TIMELINE_WIDTH)
+            .map(|i| if i >= begin && i < end { '#' } else { ' ' })
+            .collect();
+        let status = match result {
+            crate::CheckResult::Pass { .. } => "PASS",
+            crate::CheckResult::Warn { .. } => "WARN",
+            crate::CheckResult::Fail { .. } => "FAIL",
+            crate::CheckResult::Skip { .. } => "SKIP",
+        };
+        output.push_str(&format!(
+            "  {:<width$} |{}| {:>7}ms +{}ms {}\n",
+            check.id,
+            bar,
+            start_ms,
+            duration_ms,
+            status,
+            width = id_width
+        ));
+    }
+    output.push_str("--------------------------------------------------------------------------------");
+    output
 }
 
-fn days_in_month(year: u64, month: u64) -> u64 {
-    match month {
-        1 => 31,
-        2 => if is_leap_year(year) { 29 } else { 28 },
-        3 => 31,
-        4 => 30,
-        5 => 31,
-        6 => 30,
-        7 => 31,
-        8 => 31,
-        9 => 30,
-        10 => 31,
-        11 => 30,
-        12 => 31,
-        _ => 30,
+fn result_duration_ms(result: &crate::CheckResult) -> u64 {
+    match result {
+        crate::CheckResult::Pass { duration_ms, .. }
+        | crate::CheckResult::Warn { duration_ms, .. }
+        | crate::CheckResult::Fail { duration_ms, .. } => *duration_ms,
+        crate::CheckResult::Skip { .. } => 0,
     }
 }
@@ -0,0 +1,378 @@
+//! Interactive check selection for `check --interactive`.
+//!
+//! Lists the registered checks grouped by category, with their estimated
+//! durations, and lets the user toggle them from the keyboard before the
+//! run starts. The picker is drawn on stderr so that stdout only carries the
+//! report, and reads single key presses by putting the terminal in
+//! non-canonical no-echo mode with `stty`.
+
+use crate::cli::terminal;
+use crate::engine::orchestrator::RegisteredCheck;
+use crate::{CheckCategory, TpuDocConfig, TpuDocError};
+use std::io::{IsTerminal, Read, Write};
+
+/// Lines used by the header and footer around the check list
+const CHROME_LINES: usize = 5;
+
+/// A key press understood by the picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    /// Toggle the check under the cursor
+    Toggle,
+    /// Toggle every check in the cursor's category
+    ToggleCategory,
+    SelectAll,
+    SelectNone,
+    /// Run the selection
+    Run,
+    Quit,
+}
+
+/// Keys in the bytes read from the terminal. Arrow keys arrive as
+/// `ESC [ A` / `ESC [ B`; a lone ESC quits.
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let key = match bytes[i] {
+            0x1B if bytes.get(i + 1) == Some(&b'[') => {
+                i += 2;
+                match bytes.get(i) {
+                    Some(b'A') => Some(Key::Up),
+                    Some(b'B') => Some(Key::Down),
+                    _ => None,
+                }
+            }
+            0x1B | 0x03 | b'q' | b'Q' => Some(Key::Quit),
+            b'k' => Some(Key::Up),
+            b'j' => Some(Key::Down),
+            b' ' | b'x' => Some(Key::Toggle),
+            b'c' => Some(Key::ToggleCategory),
+            b'a' => Some(Key::SelectAll),
+            b'n' => Some(Key::SelectNone),
+            b'\r' | b'\n' => Some(Key::Run),
+            _ => None,
+        };
+        keys.extend(key);
+        i += 1;
+    }
+    keys
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    id: String,
+    name: String,
+    category: CheckCategory,
+    estimated_duration_ms: u64,
+    selected: bool,
+}
+
+/// Selection state for the picker
+#[derive(Debug, Clone)]
+pub struct CheckPicker {
+    /// Entries in category order; categories appear in the order their
+    /// first check was registered
+    entries: Vec<Entry>,
+    cursor: usize,
+    notice: Option<String>,
+}
+
+impl CheckPicker {
+    /// Picker over `checks`, starting with the checks `config` selects
+    pub fn new<'a>(checks: impl IntoIterator<Item = &'a RegisteredCheck>, config: &TpuDocConfig) -> Self {
+        let mut entries: Vec<Entry> = checks
+            .into_iter()
+            .map(|check| Entry {
+                id: check.id.clone(),
+                name: check.name.clone(),
+                category: check.category.clone(),
+                estimated_duration_ms: check.estimated_duration_ms,
+                selected: config.selects(&check.id, &check.category),
+            })
+            .collect();
+
+        let mut order: Vec<CheckCategory> = Vec::new();
+        for entry in &entries {
+            if !order.contains(&entry.category) {
+                order.push(entry.category.clone());
+            }
+        }
+        entries.sort_by_key(|entry| order.iter().position(|c| *c == entry.category));
+
+        CheckPicker {
+            entries,
+            cursor: 0,
+            notice: None,
+        }
+    }
+
+    /// IDs of the selected checks, in list order
+    pub fn selected_ids(&self) -> Vec<String> {
+        self.entries.iter().filter(|e| e.selected).map(|e| e.id.clone()).collect()
+    }
+
+    /// Sum of the estimated durations of the selected checks
+    pub fn estimated_duration_ms(&self) -> u64 {
+        self.entries.iter().filter(|e| e.selected).map(|e| e.estimated_duration_ms).sum()
+    }
+
+    /// Apply `key`. Returns the selection once the user asks to run it, or
+    /// an empty list when they quit; None while picking continues.
+    pub fn handle(&mut self, key: Key) -> Option<Vec<String>> {
+        self.notice = None;
+        match key {
+            Key::Up => self.cursor = self.cursor.saturating_sub(1),
+            Key::Down => self.cursor = (self.cursor + 1).min(self.entries.len().saturating_sub(1)),
+            Key::Toggle => {
+                if let Some(entry) = self.entries.get_mut(self.cursor) {
+                    entry.selected = !entry.selected;
+                }
+            }
+            Key::ToggleCategory => {
+                if let Some(category) = self.entries.get(self.cursor).map(|e| e.category.clone()) {
+                    let in_category = |e: &Entry| e.category == category;
+                    let select = !self.entries.iter().filter(|e| in_category(e)).all(|e| e.selected);
+                    for entry in self.entries.iter_mut().filter(|e| in_category(e)) {
+                        entry.selected = select;
+                    }
+                }
+            }
+            Key::SelectAll => self.entries.iter_mut().for_each(|e| e.selected = true),
+            Key::SelectNone => self.entries.iter_mut().for_each(|e| e.selected = false),
+            Key::Run => {
+                let selected = self.selected_ids();
+                if !selected.is_empty() {
+                    return Some(selected);
+                }
+                self.notice = Some("Select at least one check, or press q to quit".to_string());
+            }
+            Key::Quit => return Some(Vec::new()),
+        }
+        None
+    }
+
+    /// The picker screen, showing at most `height` lines and keeping the
+    /// cursor in view
+    pub fn render(&self, height: usize) -> String {
+        let mut lines = Vec::new();
+        let mut cursor_line = 0;
+        let mut category = None;
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            if category != Some(&entry.category) {
+                if category.is_some() {
+                    lines.push(String::new());
+                }
+                let in_category: Vec<&Entry> = self.entries.iter().filter(|e| e.category == entry.category).collect();
+                lines.push(format!(
+                    "{} ({}/{})",
+                    entry.category,
+                    in_category.iter().filter(|e| e.selected).count(),
+                    in_category.len()
+                ));
+                category = Some(&entry.category);
+            }
+            if index == self.cursor {
+                cursor_line = lines.len();
+            }
+            lines.push(format!(
+                "{} [{}] {:8} {:36} {:>7}",
+                if index == self.cursor { ">" } else { " " },
+                if entry.selected { "x" } else { " " },
+                entry.id,
+                terminal::truncate(&entry.name, 36),
+                format!("~{}", format_estimate(entry.estimated_duration_ms))
+            ));
+        }
+
+        // Scroll so the cursor stays on screen
+        let visible = height.saturating_sub(CHROME_LINES).max(1);
+        let first = cursor_line.saturating_sub(visible / 2).min(lines.len().saturating_sub(visible));
+        let shown = &lines[first..(first + visible).min(lines.len())];
+
+        let mut screen = String::from(
+            "Select checks: up/down or j/k move, space toggles, c toggles category,\n\
+             a selects all, n selects none, enter runs, q quits\n\n",
+        );
+        screen.push_str(&shown.join("\n"));
+        screen.push_str(&format!(
+            "\n\n{} of {} checks selected, ~{} estimated",
+            self.entries.iter().filter(|e| e.selected).count(),
+            self.entries.len(),
+            format_estimate(self.estimated_duration_ms())
+        ));
+        if let Some(notice) = &self.notice {
+            screen.push_str(&format!(" - {}", notice));
+        }
+        screen
+    }
+}
+
+/// Let the user pick from `checks`, starting with the checks `config`
+/// selects. Returns the chosen IDs, or an empty list if the user quit.
+pub fn pick_checks<'a>(
+    checks: impl IntoIterator<Item = &'a RegisteredCheck>,
+    config: &TpuDocConfig,
+) -> Result<Vec<String>, TpuDocError> {
+    if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        return Err(TpuDocError::CommandError {
+            command: "check --interactive".to_string(),
+            message: "a terminal is required to pick checks".to_string(),
+            source: None,
+        });
+    }
+
+    let mut picker = CheckPicker::new(checks, config);
+    let _screen = PickerScreen::enter()?;
+    let height = terminal::height().unwrap_or(24);
+    let mut stderr = std::io::stderr();
+    let mut stdin = std::io::stdin();
+    let mut buf = [0u8; 16];
+
+    loop {
+        let _ = write!(stderr, "\x1B[H{}\x1B[J", picker.render(height).replace('\n', "\r\n"));
+        let _ = stderr.flush();
+
+        let n = stdin.read(&mut buf).map_err(|e| TpuDocError::io("reading key presses", e))?;
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        for key in parse_keys(&buf[..n]) {
+            if let Some(selected) = picker.handle(key) {
+                return Ok(selected);
+            }
+        }
+    }
+}
+
+/// Alternate screen on stderr and a terminal reading single key presses,
+/// restored on drop
+struct PickerScreen {
+    saved_stty: String,
+}
+
+impl PickerScreen {
+    fn enter() -> Result<Self, TpuDocError> {
+        let saved_stty = terminal::stty(&["-g"])
+            .filter(|_| terminal::stty(&["-icanon", "-echo", "-isig", "min", "1", "time", "0"]).is_some())
+            .ok_or_else(|| TpuDocError::CommandError {
+                command: "stty".to_string(),
+                message: "could not switch the terminal to key-press input".to_string(),
+                source: None,
+            })?;
+
+        // Alternate screen, hide cursor
+        eprint!("\x1B[?1049h\x1B[?25l");
+        Ok(PickerScreen { saved_stty })
+    }
+}
+
+impl Drop for PickerScreen {
+    fn drop(&mut self) {
+        terminal::stty(&[self.saved_stty.as_str()]);
+        // Show cursor, leave alternate screen
+        eprint!("\x1B[?25h\x1B[?1049l");
+        let _ = std::io::stderr().flush();
+    }
+}
+
+/// Rough duration for the picker, e.g. "<1s", "45s", "2m10s"
+pub(crate) fn format_estimate(ms: u64) -> String {
+    let secs = ms.div_ceil(1000);
+    if ms < 1000 {
+        "<1s".to_string()
+    } else if secs < 60 {
+        format!("{}s", secs)
+    } else if secs.is_multiple_of(60) {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}m{}s", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CheckResult;
+
+    fn registered(id: &str, category: CheckCategory, estimated_duration_ms: u64) -> RegisteredCheck {
+        let mut check = RegisteredCheck::new(id, id, category, || CheckResult::Skip {
+            reason: String::new(),
+        });
+        check.estimated_duration_ms = estimated_duration_ms;
+        check
+    }
+
+    fn checks() -> Vec<RegisteredCheck> {
+        vec![
+            registered("HW-001", CheckCategory::Hardware, 500),
+            registered("PERF-001", CheckCategory::Performance, 30000),
+            registered("HW-002", CheckCategory::Hardware, 1000),
+            registered("PERF-002", CheckCategory::Performance, 45000),
+        ]
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(
+            parse_keys(b"\x1B[A\x1B[Bjk x"),
+            vec![Key::Up, Key::Down, Key::Down, Key::Up, Key::Toggle, Key::Toggle]
+        );
+        assert_eq!(parse_keys(b"can\r"), vec![Key::ToggleCategory, Key::SelectAll, Key::SelectNone, Key::Run]);
+        assert_eq!(parse_keys(b"\x1B"), vec![Key::Quit]);
+        assert_eq!(parse_keys(b"\x1B[C?"), vec![]);
+    }
+
+    #[test]
+    fn test_picker_selection() {
+        let checks = checks();
+        let config = TpuDocConfig {
+            categories: Some(vec![CheckCategory::Hardware]),
+            ..Default::default()
+        };
+        let mut picker = CheckPicker::new(&checks, &config);
+
+        // Grouped by category, preselected from the config
+        assert_eq!(picker.selected_ids(), vec!["HW-001", "HW-002"]);
+        assert_eq!(picker.estimated_duration_ms(), 1500);
+
+        // Cursor on HW-002: deselect it, then toggle the performance category on
+        assert_eq!(picker.handle(Key::Down), None);
+        picker.handle(Key::Toggle);
+        picker.handle(Key::Down);
+        picker.handle(Key::ToggleCategory);
+        assert_eq!(picker.selected_ids(), vec!["HW-001", "PERF-001", "PERF-002"]);
+
+        // Running nothing is refused
+        picker.handle(Key::SelectNone);
+        assert_eq!(picker.handle(Key::Run), None);
+        assert!(picker.render(40).contains("Select at least one check"));
+
+        picker.handle(Key::SelectAll);
+        assert_eq!(picker.handle(Key::Run).unwrap().len(), 4);
+        assert_eq!(picker.handle(Key::Quit), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_render() {
+        let checks = checks();
+        let picker = CheckPicker::new(&checks, &TpuDocConfig::default());
+        let screen = picker.render(40);
+
+        assert!(screen.contains("Hardware (2/2)\n> [x] HW-001"));
+        assert!(screen.contains("\n\nPerformance (2/2)\n"));
+        assert!(screen.lines().any(|line| line.starts_with("  [x] PERF-002") && line.ends_with(" ~45s")));
+        assert!(screen.ends_with("4 of 4 checks selected, ~1m17s estimated"));
+
+        // A short terminal shows the part of the list around the cursor
+        let short = picker.render(CHROME_LINES + 2);
+        assert!(short.contains("HW-001") && !short.contains("PERF-002"));
+
+        assert_eq!(format_estimate(300), "<1s");
+        assert_eq!(format_estimate(45000), "45s");
+        assert_eq!(format_estimate(120000), "2m");
+    }
+}
@@ -0,0 +1,215 @@
+//! Terminal detection for text output.
+//!
+//! Decides whether stdout gets ANSI colors and how wide the text report may
+//! be. Color is only used when stdout is a terminal (or `CLICOLOR_FORCE` is
+//! set), so piping the report into a file or log collector produces plain
+//! text without needing `--no-color`.
+
+use std::fs::File;
+use std::io::{IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+
+/// Narrowest width the report is wrapped to, however small the terminal
+pub const MIN_WIDTH: usize = 40;
+
+/// Whether stdout is attached to a terminal
+pub fn stdout_is_terminal() -> bool {
+    std::io::stdout().is_terminal()
+}
+
+/// Whether to color output, given whether color was disabled by
+/// `--no-color` or `NO_COLOR`
+pub fn use_color(no_color: bool) -> bool {
+    if no_color {
+        return false;
+    }
+    if std::env::var("CLICOLOR_FORCE").is_ok_and(|v| !v.is_empty() && v != "0") {
+        return true;
+    }
+    stdout_is_terminal() && std::env::var("TERM").map_or(true, |term| term != "dumb")
+}
+
+/// Width of the terminal stdout is attached to, or None when stdout is not
+/// a terminal.
+///
+/// `COLUMNS` takes precedence when set; otherwise the terminal is asked via
+/// `stty size`.
+pub fn width() -> Option<usize> {
+    if !stdout_is_terminal() {
+        return None;
+    }
+    if let Some(columns) = std::env::var("COLUMNS").ok().and_then(|c| c.trim().parse::<usize>().ok()) {
+        return Some(columns.max(MIN_WIDTH));
+    }
+
+    parse_stty_size(&stty_size()?).map(|columns| columns.max(MIN_WIDTH))
+}
+
+/// Rows of the controlling terminal, or None without one. `LINES` takes
+/// precedence when set.
+pub fn height() -> Option<usize> {
+    if let Some(lines) = std::env::var("LINES").ok().and_then(|l| l.trim().parse::<usize>().ok()) {
+        return Some(lines);
+    }
+    parse_stty_rows(&stty_size()?)
+}
+
+/// `stty size` output for the controlling terminal
+fn stty_size() -> Option<String> {
+    // stty reports the size of the terminal on its stdin
+    let tty = File::open("/dev/tty").ok()?;
+    let output = Command::new("stty")
+        .arg("size")
+        .stdin(Stdio::from(tty))
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Columns from `stty size` output (`<rows> <columns>`)
+fn parse_stty_size(output: &str) -> Option<usize> {
+    let mut fields = output.split_whitespace();
+    let _rows = fields.next()?;
+    fields.next()?.parse().ok().filter(|&columns| columns > 0)
+}
+
+/// Rows from `stty size` output
+fn parse_stty_rows(output: &str) -> Option<usize> {
+    output.split_whitespace().next()?.parse().ok().filter(|&rows| rows > 0)
+}
+
+/// Run `stty` against the terminal on stdin, returning trimmed stdout on success
+pub fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
+/// Split `text` into lines of at most `width` characters, breaking at
+/// whitespace where possible. Existing line breaks are kept.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut line_len = 0;
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            if line_len > 0 && line_len + 1 + word.len() > width {
+                lines.push(std::mem::take(&mut line));
+                line_len = 0;
+            }
+            // Hard-split words longer than a whole line
+            while word.len() > width {
+                lines.push(word.drain(..width).collect());
+            }
+            if line_len > 0 {
+                line.push(' ');
+                line_len += 1;
+            }
+            line_len += word.len();
+            line.extend(word);
+        }
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Shorten `text` to at most `width` characters, ending in `...` when cut
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(width.saturating_sub(3)).collect();
+    format!("{}...", kept.trim_end())
+}
+
+/// Terminal state for a full-screen live view (`snapshot --continuous`, `tui`).
+///
+/// Switches to the alternate screen and, when stdin is a terminal, puts it
+/// in non-canonical no-echo mode via `stty` so single key presses can be
+/// read without blocking. Everything is restored on drop.
+pub struct LiveTerminal {
+    saved_stty: Option<String>,
+}
+
+impl LiveTerminal {
+    pub fn enter() -> Self {
+        let saved_stty = if std::io::stdin().is_terminal() {
+            stty(&["-g"]).filter(|_| stty(&["-icanon", "-echo", "-isig", "min", "0", "time", "0"]).is_some())
+        } else {
+            None
+        };
+
+        // Alternate screen, hide cursor
+        print!("\x1B[?1049h\x1B[?25l");
+        let _ = std::io::stdout().flush();
+
+        LiveTerminal { saved_stty }
+    }
+
+    pub fn keys_enabled(&self) -> bool {
+        self.saved_stty.is_some()
+    }
+
+    /// True when `q`, `Q`, Esc, or Ctrl+C has been pressed
+    pub fn quit_requested(&self) -> bool {
+        if !self.keys_enabled() {
+            return false;
+        }
+        let mut buf = [0u8; 16];
+        match std::io::stdin().read(&mut buf) {
+            Ok(n) => buf[..n].iter().any(|b| matches!(b, b'q' | b'Q' | 0x1B | 0x03)),
+            Err(_) => false,
+        }
+    }
+}
+
+impl Drop for LiveTerminal {
+    fn drop(&mut self) {
+        if let Some(ref saved) = self.saved_stty {
+            stty(&[saved.as_str()]);
+        }
+        // Show cursor, leave alternate screen
+        print!("\x1B[?25h\x1B[?1049l");
+        let _ = std::io::stdout().flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stty_size() {
+        assert_eq!(parse_stty_size("50 120\n"), Some(120));
+        assert_eq!(parse_stty_size("0 0"), None);
+        assert_eq!(parse_stty_size(""), None);
+        assert_eq!(parse_stty_rows("50 120\n"), Some(50));
+        assert_eq!(parse_stty_rows(""), None);
+    }
+
+    #[test]
+    fn test_wrap_and_truncate() {
+        assert_eq!(wrap("one two three four", 9), vec!["one two", "three", "four"]);
+        assert_eq!(wrap("a\nb c", 10), vec!["a", "b c"]);
+        assert_eq!(wrap("abcdefghij kl", 4), vec!["abcd", "efgh", "ij", "kl"]);
+        assert_eq!(wrap("", 10), vec![""]);
+
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("a much longer message", 10), "a much...");
+    }
+}
@@ -1,31 +1,54 @@
-//! AI-powered log analysis command.
+//! Log analysis command.
 //!
-//! This command uses AI to analyze log files and provide diagnostic insights.
-//! It requires the "ai" feature to be enabled and an API key to be set.
+//! Without `--ai`, logs are matched offline against the built-in error
+//! signature database (no network or API key needed). With `--ai`, an AI
+//! provider analyzes the log; this requires the "ai" feature and an API key.
 //!
 //! # Usage
 //!
 //! ```sh
+//! # Match known error signatures offline
+//! tpu-doc analyze error.log
+//!
 //! # Analyze a log file with Anthropic (default)
 //! tpu-doc analyze error.log --ai
 //!
 //! # Use Google Gemini instead
 //! tpu-doc analyze error.log --ai --provider google
 //!
+//! # Use Gemini on Vertex AI with the VM's service account (no API key)
+//! tpu-doc analyze error.log --ai --provider vertex
+//!
 //! # Ask a specific question
 //! tpu-doc analyze error.log --ai --question "Why is my training hanging?"
+//!
+//! # Keep the log loaded and ask follow-up questions
+//! tpu-doc analyze error.log --ai --interactive
 //! ```
+//!
+//! Logs too large for one prompt are pre-filtered, split into chunks that
+//! are analyzed separately, and the findings synthesized into one answer
+//! within `--max-tokens`.
 
-use crate::cli::args::Args;
+use crate::cli::args::{Args, OutputFormat};
+use crate::data::signatures::{SignatureDb, SignatureMatch};
 use crate::TpuDocError;
+use crate::util::json;
 use std::fs;
+#[cfg(feature = "ai")]
+use std::io::{self, BufRead, Write};
+use std::sync::Arc;
 
 #[cfg(feature = "ai")]
 use crate::ai::{
     anthropic::AnthropicClient,
+    cache::{self, AnalysisKey, ResponseCache},
+    chunking,
     google::GeminiClient,
     prompt::PromptBuilder,
-    AiProvider,
+    redact,
+    vertex::VertexClient,
+    AiError, AiProvider, AnalysisRequest, AnalysisResponse, ChatMessage,
 };
 
 #[cfg(feature = "ai")]
@@ -34,25 +57,33 @@ use crate::commands::info;
 /// Maximum log file size to read (10MB)
 const MAX_LOG_FILE_SIZE: u64 = 10 * 1024 * 1024;
 
+/// Response budget for each per-chunk analysis of a large log
+#[cfg(feature = "ai")]
+const CHUNK_RESPONSE_TOKENS: u32 = 1024;
+
+/// Changed lines shown per source when previewing redactions
+#[cfg(feature = "ai")]
+const MAX_REDACTION_DIFF_LINES: usize = 10;
+
+/// Conversation size at which `--interactive` starts dropping old follow-ups
+#[cfg(feature = "ai")]
+const MAX_CONVERSATION_TOKENS: usize = 100_000;
+
 /// Run the analyze command
 pub fn run(args: &Args) -> Result<String, TpuDocError> {
-    // Check if AI is enabled
+    // Without --ai, match known error signatures offline
     if !args.ai_enabled {
-        return Err(TpuDocError::CommandError {
-            command: "analyze".to_string(),
-            message: "The --ai flag is required for the analyze command. \
-                     This enables AI-powered analysis using your API key."
-                .to_string(),
-        });
+        return run_offline_analysis(args);
     }
 
     #[cfg(not(feature = "ai"))]
     {
-        return Err(TpuDocError::CommandError {
+        Err(TpuDocError::CommandError {
             command: "analyze".to_string(),
             message: "AI features are not enabled. Rebuild with: cargo build --features ai"
                 .to_string(),
-        });
+            source: None,
+        })
     }
 
     #[cfg(feature = "ai")]
@@ -66,6 +97,7 @@ fn run_ai_analysis(args: &Args) -> Result<String, TpuDocError> {
     let log_path = args.log_file.as_ref().ok_or_else(|| TpuDocError::CommandError {
         command: "analyze".to_string(),
         message: "Log file path is required. Usage: tpu-doc analyze <log_file> --ai".to_string(),
+        source: None,
     })?;
 
     // Read the log file
@@ -74,43 +106,108 @@ fn run_ai_analysis(args: &Args) -> Result<String, TpuDocError> {
     // Gather environment context
     let env_info = info::gather_environment_info_internal();
 
-    // Build the prompt
-    let mut prompt_builder = PromptBuilder::new()
-        .with_environment(&env_info)
-        .with_log_content(&log_content);
-
-    if let Some(ref question) = args.ai_question {
-        prompt_builder = prompt_builder.with_question(question);
-    }
-
-    let prompt = prompt_builder.build();
+    // Drop noise and split into budget-sized chunks
+    let plan = chunking::plan_chunks(&log_content, chunking::DEFAULT_CHUNK_TOKENS);
     let system_prompt = PromptBuilder::system_prompt();
+    let max_tokens = args.ai_max_tokens.unwrap_or(AnalysisRequest::default().max_tokens);
 
-    // Get the AI provider
+    // Reuse a recent answer for the same log, question, and model
     let provider = args.ai_provider.clone().unwrap_or_default();
+    let model = args
+        .ai_model
+        .clone()
+        .unwrap_or_else(|| provider.default_model().to_string());
+    let cache = if args.ai_no_cache {
+        None
+    } else {
+        ResponseCache::open_default(args.ai_cache_ttl_secs.unwrap_or(cache::DEFAULT_TTL_SECS))
+    };
+    let environment = PromptBuilder::new().with_environment(&env_info).build();
+    let cache_key = cache::cache_key(&AnalysisKey {
+        provider: &provider,
+        model: &model,
+        base_url: args.ai_base_url.as_deref(),
+        max_tokens,
+        environment: &environment,
+        content: &log_content,
+        question: args.ai_question.as_deref(),
+    });
+    let cached = cache.as_ref().and_then(|c| c.get(&cache_key));
 
-    // Call the appropriate AI provider
-    let response = match provider {
-        AiProvider::Anthropic => {
-            let client = AnthropicClient::new()?;
-            let client = if let Some(ref model) = args.ai_model {
-                client.with_model(model)
-            } else {
-                client
-            };
-            client.send_message(&prompt, Some(system_prompt))?
-        }
-        AiProvider::Google => {
-            let client = GeminiClient::new()?;
-            let client = if let Some(ref model) = args.ai_model {
-                client.with_model(model)
-            } else {
-                client
-            };
-            client.send_message(&prompt, Some(system_prompt))?
+    // Nothing leaves the machine for a cached answer, so only preview on a miss
+    let redacted = if cached.is_none() && !args.ai_no_redact {
+        let mut sources = vec![(log_path.as_str(), log_content.as_str()), ("environment", environment.as_str())];
+        if let Some(ref question) = args.ai_question {
+            sources.push(("question", question.as_str()));
         }
+        Some(preview_redactions(&sources, args.quiet))
+    } else {
+        None
     };
 
+    let mut usage = TokenUsage::default();
+    // First user turn of the conversation, kept for --interactive follow-ups
+    let mut context_prompt = None;
+    let response = if let Some(ref hit) = cached {
+        hit.response.clone()
+    } else if plan.is_single() {
+        let mut prompt_builder = PromptBuilder::new()
+            .with_environment(&env_info)
+            .with_log_content(plan.chunks.first().map(String::as_str).unwrap_or(""));
+
+        if let Some(ref question) = args.ai_question {
+            prompt_builder = prompt_builder.with_question(question);
+        }
+
+        let prompt = prompt_builder.build();
+        let client = ProviderClient::new(args, max_tokens)?;
+        let response = client.send_message(&prompt, Some(system_prompt))?;
+        usage.add(&response);
+        context_prompt = Some(prompt);
+        response
+    } else {
+        // Per-chunk findings are kept short; the synthesis gets the full budget
+        let chunk_client = ProviderClient::new(args, max_tokens.min(CHUNK_RESPONSE_TOKENS))?;
+        let total = plan.chunks.len();
+        let mut findings = Vec::with_capacity(total);
+
+        for (i, chunk) in plan.chunks.iter().enumerate() {
+            let mut prompt_builder = PromptBuilder::new()
+                .with_log_content(chunk)
+                .with_chunk(i + 1, total);
+            if let Some(ref question) = args.ai_question {
+                prompt_builder = prompt_builder.with_question(question);
+            }
+
+            let response = chunk_client.send_message(&prompt_builder.build(), Some(system_prompt))?;
+            usage.add(&response);
+            findings.push(response.content);
+        }
+
+        let mut prompt_builder = PromptBuilder::new()
+            .with_environment(&env_info)
+            .with_partial_findings(&findings);
+        if let Some(ref question) = args.ai_question {
+            prompt_builder = prompt_builder.with_question(question);
+        }
+
+        let prompt = prompt_builder.build();
+        let client = ProviderClient::new(args, max_tokens)?;
+        let response = client.send_message(&prompt, Some(system_prompt))?;
+        usage.add(&response);
+        context_prompt = Some(prompt);
+        response
+    };
+
+    if cached.is_none() {
+        if let Some(ref cache) = cache {
+            let mut entry = response.clone();
+            entry.prompt_tokens = usage.prompt;
+            entry.completion_tokens = usage.completion;
+            cache.put(&cache_key, &entry);
+        }
+    }
+
     // Format the output
     let mut output = String::new();
     output.push_str("================================================================================\n");
@@ -120,9 +217,39 @@ fn run_ai_analysis(args: &Args) -> Result<String, TpuDocError> {
     output.push_str(&format!("Log File: {}\n", log_path));
     output.push_str(&format!("Model: {}\n", response.model));
 
-    if let (Some(prompt_tokens), Some(completion_tokens)) =
-        (response.prompt_tokens, response.completion_tokens)
-    {
+    if plan.stats.removed_lines() > 0 || !plan.is_single() {
+        output.push_str(&format!(
+            "Log Lines: {} total, {} sent ({} blank, {} progress, {} repeated filtered)\n",
+            plan.stats.total_lines,
+            plan.stats.kept_lines,
+            plan.stats.blank_lines,
+            plan.stats.progress_lines,
+            plan.stats.repeated_lines
+        ));
+    }
+    if !plan.is_single() {
+        output.push_str(&format!(
+            "Chunks: {} analyzed of {} (~{} tokens each)\n",
+            plan.chunks.len(),
+            plan.total_chunks,
+            chunking::DEFAULT_CHUNK_TOKENS
+        ));
+    }
+
+    if let Some(ref redacted) = redacted {
+        if !redacted.redactions.is_empty() {
+            output.push_str(&format!("Redacted: {}\n", redacted.summary()));
+        }
+    } else if args.ai_no_redact {
+        output.push_str("Redacted: no (--no-redact)\n");
+    }
+
+    if let Some(ref hit) = cached {
+        output.push_str(&format!(
+            "Cached: yes ({} old, no tokens used; --no-cache to refresh)\n",
+            format_age(hit.age_secs)
+        ));
+    } else if let (Some(prompt_tokens), Some(completion_tokens)) = (usage.prompt, usage.completion) {
         output.push_str(&format!(
             "Tokens: {} prompt + {} completion = {} total\n",
             prompt_tokens,
@@ -137,14 +264,394 @@ fn run_ai_analysis(args: &Args) -> Result<String, TpuDocError> {
     output.push_str(&response.content);
     output.push_str("\n\n================================================================================\n");
 
+    if !args.interactive {
+        return Ok(output);
+    }
+
+    // A cached answer has no prompt of its own; rebuild the context it answered
+    let context_prompt = context_prompt.unwrap_or_else(|| {
+        let builder = PromptBuilder::new().with_environment(&env_info);
+        if plan.is_single() {
+            builder
+                .with_log_content(plan.chunks.first().map(String::as_str).unwrap_or(""))
+                .build()
+        } else {
+            builder
+                .with_partial_findings(std::slice::from_ref(&response.content))
+                .build()
+        }
+    });
+
+    print!("{}", output);
+    let history = vec![
+        ChatMessage::user(&context_prompt),
+        ChatMessage::assistant(&response.content),
+    ];
+    run_interactive(args, max_tokens, history, usage)
+}
+
+/// Follow-up question loop for `analyze --interactive`.
+///
+/// The whole conversation, starting with the log and environment context, is
+/// resent on every turn so the model can refer back to earlier answers.
+#[cfg(feature = "ai")]
+fn run_interactive(
+    args: &Args,
+    max_tokens: u32,
+    mut history: Vec<ChatMessage>,
+    mut usage: TokenUsage,
+) -> Result<String, TpuDocError> {
+    let client = ProviderClient::new(args, max_tokens)?;
+    let system_prompt = PromptBuilder::system_prompt();
+    let stdin = io::stdin();
+    let mut answered = 0;
+
+    println!("\nAsk follow-up questions about this log. Type 'exit' or press Ctrl+D to quit.");
+
+    loop {
+        print!("\n> ");
+        let _ = io::stdout().flush();
+
+        let mut line = String::new();
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) => {
+                println!();
+                break;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                return Err(TpuDocError::IoError {
+                    context: "analyze --interactive".to_string(),
+                    message: format!("Failed to read input: {}", e),
+                    source: Some(Arc::new(e)),
+                })
+            }
+        }
+
+        let question = line.trim();
+        match question {
+            "" => continue,
+            "exit" | "quit" | ":q" => break,
+            _ => {}
+        }
+
+        if !args.ai_no_redact {
+            preview_redactions(&[("question", question)], args.quiet);
+        }
+
+        history.push(ChatMessage::user(question));
+        trim_history(&mut history, MAX_CONVERSATION_TOKENS);
+
+        match client.send_conversation(&history, Some(system_prompt)) {
+            Ok(response) => {
+                usage.add(&response);
+                println!("\n{}", response.content.trim_end());
+                history.push(ChatMessage::assistant(&response.content));
+                answered += 1;
+            }
+            Err(e) => {
+                // Keep the session alive; drop the unanswered question
+                history.pop();
+                eprintln!("\nError: {}", e);
+            }
+        }
+    }
+
+    // Counted separately: trim_history drops old exchanges from `history`
+    let mut output = format!("Session ended after {} follow-up question(s).\n", answered);
+    if let (Some(prompt_tokens), Some(completion_tokens)) = (usage.prompt, usage.completion) {
+        output.push_str(&format!(
+            "Tokens (whole session): {} prompt + {} completion = {} total\n",
+            prompt_tokens,
+            completion_tokens,
+            prompt_tokens + completion_tokens
+        ));
+    }
     Ok(output)
 }
 
+/// Drop the oldest follow-up exchanges until the conversation fits `max_tokens`.
+///
+/// The first exchange (log context and initial analysis) and the latest
+/// question are always kept.
+#[cfg(feature = "ai")]
+fn trim_history(history: &mut Vec<ChatMessage>, max_tokens: usize) {
+    let tokens =
+        |h: &[ChatMessage]| h.iter().map(|m| chunking::estimate_tokens(&m.content)).sum::<usize>();
+
+    while history.len() > 3 && tokens(history) > max_tokens {
+        history.drain(2..4);
+    }
+}
+
+/// A configured client for whichever provider was selected.
+///
+/// Unless `--no-redact` was given, every message is redacted before it is
+/// handed to the provider's HTTP client.
+#[cfg(feature = "ai")]
+struct ProviderClient {
+    api: ProviderApi,
+    redact: bool,
+}
+
+#[cfg(feature = "ai")]
+enum ProviderApi {
+    Anthropic(AnthropicClient),
+    Google(GeminiClient),
+    Vertex(VertexClient),
+}
+
+#[cfg(feature = "ai")]
+impl ProviderClient {
+    fn new(args: &Args, max_tokens: u32) -> Result<Self, AiError> {
+        let provider = args.ai_provider.clone().unwrap_or_default();
+        let model = args.ai_model.as_deref();
+
+        let api = match provider {
+            AiProvider::Anthropic => {
+                let mut client = AnthropicClient::new()?.with_max_tokens(max_tokens);
+                if let Some(ref url) = args.ai_base_url {
+                    client = client.with_base_url(url)?;
+                }
+                ProviderApi::Anthropic(match model {
+                    Some(m) => client.with_model(m),
+                    None => client,
+                })
+            }
+            AiProvider::Google => {
+                let mut client = GeminiClient::new()?.with_max_tokens(max_tokens);
+                if let Some(ref url) = args.ai_base_url {
+                    client = client.with_base_url(url)?;
+                }
+                ProviderApi::Google(match model {
+                    Some(m) => client.with_model(m),
+                    None => client,
+                })
+            }
+            AiProvider::Vertex => {
+                let mut client = VertexClient::new()?.with_max_tokens(max_tokens);
+                if let Some(ref url) = args.ai_base_url {
+                    client = client.with_base_url(url)?;
+                }
+                ProviderApi::Vertex(match model {
+                    Some(m) => client.with_model(m),
+                    None => client,
+                })
+            }
+        };
+
+        Ok(ProviderClient {
+            api,
+            redact: !args.ai_no_redact,
+        })
+    }
+
+    fn send_message(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+    ) -> Result<AnalysisResponse, AiError> {
+        self.send_conversation(&[ChatMessage::user(prompt)], system_prompt)
+    }
+
+    fn send_conversation(
+        &self,
+        messages: &[ChatMessage],
+        system_prompt: Option<&str>,
+    ) -> Result<AnalysisResponse, AiError> {
+        let redacted: Vec<ChatMessage>;
+        let messages = if self.redact {
+            redacted = messages
+                .iter()
+                .map(|m| ChatMessage {
+                    role: m.role,
+                    content: redact::redact(&m.content).text,
+                })
+                .collect();
+            &redacted[..]
+        } else {
+            messages
+        };
+
+        match &self.api {
+            ProviderApi::Anthropic(client) => client.send_conversation(messages, system_prompt),
+            ProviderApi::Google(client) => client.send_conversation(messages, system_prompt),
+            ProviderApi::Vertex(client) => client.send_conversation(messages, system_prompt),
+        }
+    }
+}
+
+/// Show what redaction will remove from each named source before anything
+/// is sent, and return the combined result for the summary line
+#[cfg(feature = "ai")]
+fn preview_redactions(sources: &[(&str, &str)], quiet: bool) -> redact::Redacted {
+    let mut combined = redact::Redacted::default();
+    let mut diff = String::new();
+
+    for (name, text) in sources {
+        let redacted = redact::redact(text);
+        if redacted.redactions.is_empty() {
+            continue;
+        }
+        diff.push_str(&format!("{}:\n", name));
+        diff.push_str(&redact::format_diff(text, &redacted, MAX_REDACTION_DIFF_LINES));
+        combined.redactions.extend(redacted.redactions);
+    }
+
+    if !quiet && !combined.redactions.is_empty() {
+        eprintln!(
+            "Redacting {} before sending (use --no-redact to send as-is):\n{}",
+            combined.summary(),
+            diff
+        );
+    }
+    combined
+}
+
+#[cfg(feature = "ai")]
+fn format_age(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        _ => format!("{}h{}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+/// Token usage summed over every request in a run
+#[cfg(feature = "ai")]
+#[derive(Default)]
+struct TokenUsage {
+    prompt: Option<u32>,
+    completion: Option<u32>,
+}
+
+#[cfg(feature = "ai")]
+impl TokenUsage {
+    fn add(&mut self, response: &AnalysisResponse) {
+        if let Some(tokens) = response.prompt_tokens {
+            self.prompt = Some(self.prompt.unwrap_or(0) + tokens);
+        }
+        if let Some(tokens) = response.completion_tokens {
+            self.completion = Some(self.completion.unwrap_or(0) + tokens);
+        }
+    }
+}
+
+/// Match the log against the known-error signature database
+fn run_offline_analysis(args: &Args) -> Result<String, TpuDocError> {
+    let log_path = args.log_file.as_ref().ok_or_else(|| TpuDocError::CommandError {
+        command: "analyze".to_string(),
+        message: "Log file path is required. Usage: tpu-doc analyze <log_file>".to_string(),
+        source: None,
+    })?;
+
+    let log_content = read_log_file(log_path)?;
+    let db = SignatureDb::load();
+    let matches = db.match_log(&log_content);
+
+    Ok(match args.format {
+        OutputFormat::Json => format_offline_json(log_path, &db, &matches),
+        _ => format_offline_text(log_path, &db, &matches),
+    })
+}
+
+fn format_offline_text(log_path: &str, db: &SignatureDb, matches: &[SignatureMatch]) -> String {
+    let mut output = String::new();
+    output.push_str("================================================================================\n");
+    output.push_str("                         LOG ANALYSIS (offline)\n");
+    output.push_str("================================================================================\n\n");
+
+    output.push_str(&format!("Log File: {}\n", log_path));
+    output.push_str(&format!(
+        "Signatures: {} checked, {} matched\n\n",
+        db.signatures.len(),
+        matches.len()
+    ));
+
+    if matches.is_empty() {
+        output.push_str("No known error signatures matched.\n");
+        output.push_str("For a deeper analysis, run with --ai (requires an API key).\n");
+        return output;
+    }
+
+    for (i, m) in matches.iter().enumerate() {
+        let signature = m.signature;
+        output.push_str("--------------------------------------------------------------------------------\n");
+        output.push_str(&format!(
+            "[{}] {} ({}, {})\n",
+            i + 1,
+            signature.title,
+            signature.id,
+            signature.severity.as_str()
+        ));
+        output.push_str("--------------------------------------------------------------------------------\n");
+        output.push_str(&format!(
+            "First seen: line {} ({} matching line{})\n",
+            m.first_line,
+            m.occurrences,
+            if m.occurrences == 1 { "" } else { "s" }
+        ));
+        output.push_str(&format!("  > {}\n\n", truncate_line(&m.line, 160)));
+        output.push_str(&format!("Diagnosis: {}\n\n", signature.diagnosis));
+        output.push_str("Remediation:\n");
+        for step in &signature.remediation {
+            output.push_str(&format!("  - {}\n", step));
+        }
+        output.push('\n');
+    }
+
+    output.push_str("================================================================================\n");
+    output
+}
+
+fn format_offline_json(log_path: &str, db: &SignatureDb, matches: &[SignatureMatch]) -> String {
+    let mut json = String::from("{\n");
+    json.push_str(&format!("  \"log_file\": \"{}\",\n", json::escape(log_path)));
+    json.push_str(&format!("  \"signatures_checked\": {},\n", db.signatures.len()));
+    json.push_str(&format!("  \"signature_db_version\": \"{}\",\n", json::escape(&db.version)));
+    json.push_str("  \"matches\": [");
+
+    for (i, m) in matches.iter().enumerate() {
+        let signature = m.signature;
+        let remediation: Vec<String> = signature
+            .remediation
+            .iter()
+            .map(|r| json::quote(r))
+            .collect();
+
+        json.push_str(if i == 0 { "\n" } else { ",\n" });
+        json.push_str("    {\n");
+        json.push_str(&format!("      \"id\": \"{}\",\n", json::escape(&signature.id)));
+        json.push_str(&format!("      \"title\": \"{}\",\n", json::escape(&signature.title)));
+        json.push_str(&format!("      \"severity\": \"{}\",\n", signature.severity.as_str()));
+        json.push_str(&format!("      \"first_line\": {},\n", m.first_line));
+        json.push_str(&format!("      \"occurrences\": {},\n", m.occurrences));
+        json.push_str(&format!("      \"line\": \"{}\",\n", json::escape(&m.line)));
+        json.push_str(&format!("      \"diagnosis\": \"{}\",\n", json::escape(&signature.diagnosis)));
+        json.push_str(&format!("      \"remediation\": [{}]\n", remediation.join(", ")));
+        json.push_str("    }");
+    }
+
+    json.push_str(if matches.is_empty() { "]\n" } else { "\n  ]\n" });
+    json.push('}');
+    json
+}
+
+fn truncate_line(line: &str, max: usize) -> String {
+    if line.chars().count() <= max {
+        line.to_string()
+    } else {
+        let truncated: String = line.chars().take(max - 3).collect();
+        format!("{}...", truncated)
+    }
+}
+
 fn read_log_file(path: &str) -> Result<String, TpuDocError> {
     // Check file exists
     let metadata = fs::metadata(path).map_err(|e| TpuDocError::IoError {
         context: "read_log_file".to_string(),
         message: format!("Cannot access log file '{}': {}", path, e),
+        source: Some(Arc::new(e)),
     })?;
 
     // Check file size
@@ -156,6 +663,7 @@ fn read_log_file(path: &str) -> Result<String, TpuDocError> {
                 metadata.len() as f64 / (1024.0 * 1024.0),
                 MAX_LOG_FILE_SIZE / (1024 * 1024)
             ),
+            source: None,
         });
     }
 
@@ -163,6 +671,7 @@ fn read_log_file(path: &str) -> Result<String, TpuDocError> {
     fs::read_to_string(path).map_err(|e| TpuDocError::IoError {
         context: "read_log_file".to_string(),
         message: format!("Failed to read log file '{}': {}", path, e),
+        source: Some(Arc::new(e)),
     })
 }
 
@@ -175,4 +684,39 @@ mod tests {
         let result = read_log_file("/nonexistent/path/to/file.log");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_offline_output() {
+        let db = SignatureDb::load();
+        let log = "E0101 RuntimeError: Unable to initialize backend 'tpu'\t(\"retry\")\n";
+        let matches = db.match_log(log);
+
+        let text = format_offline_text("train.log", &db, &matches);
+        assert!(text.contains("TPU backend failed to initialize (SIG-001, error)"));
+        assert!(text.contains("First seen: line 1 (1 matching line)"));
+
+        let json = format_offline_json("train.log", &db, &matches);
+        assert!(json.contains("\"id\": \"SIG-001\""));
+        assert!(json.contains("'tpu'\\t(\\\"retry\\\")"));
+
+        let none = format_offline_json("ok.log", &db, &[]);
+        assert!(none.contains("\"matches\": []"));
+    }
+
+    #[cfg(feature = "ai")]
+    #[test]
+    fn test_trim_history_keeps_context_and_latest() {
+        let mut history = vec![
+            ChatMessage::user(&"log ".repeat(100)),
+            ChatMessage::assistant("initial analysis"),
+            ChatMessage::user("first follow-up"),
+            ChatMessage::assistant(&"long answer ".repeat(100)),
+            ChatMessage::user("second follow-up"),
+        ];
+        trim_history(&mut history, 200);
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[1].content, "initial analysis");
+        assert_eq!(history[2].content, "second follow-up");
+    }
 }
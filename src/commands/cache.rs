@@ -1,12 +1,33 @@
 //! XLA cache analysis command
 //!
-//! Analyzes the XLA compilation cache status and health.
+//! Analyzes the XLA compilation cache status and health, breaks down its
+//! size by entry age and compilation key, estimates the hit rate against
+//! the installed jaxlib/libtpu, and removes stale entries with
+//! `cache clean --older-than <AGE>`.
 
 use crate::cli::args::{Args, OutputFormat};
+use crate::platform::linux;
 use crate::TpuDocError;
+use crate::util::json;
+use std::collections::HashMap;
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Age buckets used for the size breakdown (label, upper bound in seconds)
+const AGE_BUCKETS: &[(&str, u64)] = &[
+    ("< 1 day", 86400),
+    ("1-7 days", 7 * 86400),
+    ("7-30 days", 30 * 86400),
+    ("> 30 days", u64::MAX),
+];
+
+/// Number of compilation keys shown in the breakdown
+const TOP_KEYS: usize = 10;
+
+/// Time limit for the python3 probe of the installed packages
+const STACK_PROBE_TIMEOUT_MS: u64 = 10_000;
 
 /// XLA cache analysis result
 #[derive(Debug)]
@@ -19,11 +40,80 @@ pub struct CacheAnalysis {
     pub total_size_mb: f64,
     pub oldest_entry: Option<String>,
     pub newest_entry: Option<String>,
+    pub age_buckets: Vec<AgeBucket>,
+    pub top_keys: Vec<KeyUsage>,
+    pub corrupted_entries: Vec<String>,
+    pub hit_rate: Option<HitRateEstimate>,
     pub health_status: CacheHealth,
     pub issues: Vec<CacheIssue>,
     pub recommendations: Vec<String>,
 }
 
+/// A single file in the compilation cache
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub size_bytes: u64,
+    pub age_secs: Option<u64>,
+}
+
+/// Cache usage for one age range
+#[derive(Debug, Clone)]
+pub struct AgeBucket {
+    pub label: &'static str,
+    pub entry_count: usize,
+    pub size_bytes: u64,
+}
+
+/// Cache usage attributed to one compilation key (module name)
+#[derive(Debug, Clone)]
+pub struct KeyUsage {
+    pub key: String,
+    pub entry_count: usize,
+    pub size_bytes: u64,
+}
+
+/// An installed package that is part of every cache key
+#[derive(Debug, Clone)]
+pub struct StackPackage {
+    pub name: String,
+    pub version: String,
+    /// Seconds since the package was installed (mtime of its dist-info METADATA)
+    pub installed_age_secs: u64,
+}
+
+/// Estimated cache hit rate for the currently installed stack.
+///
+/// JAX cache keys embed the jaxlib and backend versions, so entries written
+/// before the current versions were installed can never be hit again. Cache
+/// files do not record those versions, so this is a heuristic: an entry
+/// counts as stale when it was last used (per its `-atime` companion, the
+/// same time `cache clean` goes by) before the newest install. A reinstall
+/// of the same version flags entries until their next use.
+#[derive(Debug, Clone)]
+pub struct HitRateEstimate {
+    pub packages: Vec<StackPackage>,
+    pub valid_entries: usize,
+    pub stale_entries: usize,
+    pub stale_bytes: u64,
+    pub estimated_hit_rate_pct: f64,
+}
+
+/// Estimated hit rate below which a cold-compile storm is predicted
+const LOW_HIT_RATE_PCT: f64 = 50.0;
+
+/// Result of `cache clean`
+#[derive(Debug)]
+pub struct CleanResult {
+    pub cache_path: String,
+    pub older_than_secs: u64,
+    pub dry_run: bool,
+    pub removed: Vec<CacheEntry>,
+    pub failed: Vec<String>,
+    pub freed_bytes: u64,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum CacheHealth {
     Healthy,
@@ -56,6 +146,10 @@ impl CacheAnalysis {
             total_size_mb: 0.0,
             oldest_entry: None,
             newest_entry: None,
+            age_buckets: Vec::new(),
+            top_keys: Vec::new(),
+            corrupted_entries: Vec::new(),
+            hit_rate: None,
             health_status: CacheHealth::NotConfigured,
             issues: vec![CacheIssue {
                 severity: IssueSeverity::Info,
@@ -71,6 +165,14 @@ impl CacheAnalysis {
 
 /// Run the cache command
 pub fn run(args: &Args) -> Result<String, TpuDocError> {
+    if args.cache_clean {
+        let result = clean_cache(args)?;
+        return match args.format {
+            OutputFormat::Json => Ok(format_clean_json(&result)),
+            _ => Ok(format_clean_text(&result)),
+        };
+    }
+
     let analysis = analyze_cache();
 
     match args.format {
@@ -104,6 +206,10 @@ fn analyze_cache() -> CacheAnalysis {
             total_size_mb: 0.0,
             oldest_entry: None,
             newest_entry: None,
+            age_buckets: Vec::new(),
+            top_keys: Vec::new(),
+            corrupted_entries: Vec::new(),
+            hit_rate: None,
             health_status: CacheHealth::NotConfigured,
             issues,
             recommendations,
@@ -135,6 +241,10 @@ fn analyze_cache() -> CacheAnalysis {
             total_size_mb: 0.0,
             oldest_entry: None,
             newest_entry: None,
+            age_buckets: Vec::new(),
+            top_keys: Vec::new(),
+            corrupted_entries: Vec::new(),
+            hit_rate: None,
             health_status: CacheHealth::Warning,
             issues,
             recommendations,
@@ -151,8 +261,13 @@ fn analyze_cache() -> CacheAnalysis {
     }
 
     // Analyze cache contents
-    let (entry_count, total_size_bytes, oldest, newest) = analyze_cache_contents(&cache_path_str);
+    let entries = collect_cache_entries(path);
+    let (entry_count, total_size_bytes, oldest, newest) = summarize_entries(&entries);
     let total_size_mb = total_size_bytes as f64 / (1024.0 * 1024.0);
+    let age_buckets = bucket_by_age(&entries);
+    let top_keys = usage_by_key(&entries);
+    let corrupted_entries = find_corrupted_entries(&entries);
+    let hit_rate = estimate_hit_rate(&entries, &detect_stack_packages(), SystemTime::now());
 
     // Check for potential issues
     if total_size_mb > 10240.0 {
@@ -164,6 +279,25 @@ fn analyze_cache() -> CacheAnalysis {
         recommendations.push("Consider clearing old cache entries to free disk space".to_string());
     }
 
+    if !corrupted_entries.is_empty() {
+        issues.push(CacheIssue {
+            severity: IssueSeverity::Warning,
+            description: format!(
+                "{} corrupted or incomplete cache entries (empty or leftover temp files)",
+                corrupted_entries.len()
+            ),
+        });
+        recommendations.push("Remove corrupted entries; JAX will recompile them on next use".to_string());
+    }
+
+    let stale = age_buckets.last().map(|b| b.size_bytes).unwrap_or(0);
+    if stale > 0 && stale as f64 > total_size_bytes as f64 * 0.5 {
+        recommendations.push(format!(
+            "Over half the cache ({:.1} MB) is older than 30 days: tpu-doc cache clean --older-than 30d --dry-run",
+            stale as f64 / (1024.0 * 1024.0)
+        ));
+    }
+
     if entry_count == 0 && cache_exists {
         issues.push(CacheIssue {
             severity: IssueSeverity::Info,
@@ -182,6 +316,35 @@ fn analyze_cache() -> CacheAnalysis {
         }
     }
 
+    if let Some(ref estimate) = hit_rate {
+        if estimate.stale_entries > 0 && estimate.estimated_hit_rate_pct < LOW_HIT_RATE_PCT {
+            let newest = estimate
+                .packages
+                .iter()
+                .min_by_key(|p| p.installed_age_secs)
+                .map(|p| format!("{} {}", p.name, p.version))
+                .unwrap_or_default();
+            issues.push(CacheIssue {
+                severity: IssueSeverity::Warning,
+                description: format!(
+                    "Heuristic hit rate {:.0}%: {} of {} entries unused since {} was installed; expect cold compiles",
+                    estimate.estimated_hit_rate_pct,
+                    estimate.stale_entries,
+                    estimate.stale_entries + estimate.valid_entries,
+                    newest
+                ),
+            });
+            if let Some(age) = estimate.packages.iter().map(|p| p.installed_age_secs).min() {
+                recommendations.push(format!(
+                    "Reclaim {:.1} MB of unreachable entries: tpu-doc cache clean --older-than {}",
+                    estimate.stale_bytes as f64 / (1024.0 * 1024.0),
+                    format_age(age + 1)
+                ));
+            }
+            recommendations.push("Warm the cache with a short compile-only run before scaling out".to_string());
+        }
+    }
+
     // Determine health status
     let health_status = if issues.iter().any(|i| matches!(i.severity, IssueSeverity::Error)) {
         CacheHealth::Error
@@ -200,6 +363,10 @@ fn analyze_cache() -> CacheAnalysis {
         total_size_mb,
         oldest_entry: oldest,
         newest_entry: newest,
+        age_buckets,
+        top_keys,
+        corrupted_entries,
+        hit_rate,
         health_status,
         issues,
         recommendations,
@@ -215,8 +382,8 @@ fn get_cache_path() -> Option<String> {
     // Check XLA_FLAGS for --xla_dump_to
     if let Ok(flags) = env::var("XLA_FLAGS") {
         for part in flags.split_whitespace() {
-            if part.starts_with("--xla_dump_to=") {
-                return Some(part[14..].to_string());
+            if let Some(dir) = part.strip_prefix("--xla_dump_to=") {
+                return Some(dir.to_string());
             }
         }
     }
@@ -241,57 +408,308 @@ fn check_writable(path: &str) -> bool {
     }
 }
 
-fn analyze_cache_contents(path: &str) -> (usize, u64, Option<String>, Option<String>) {
-    let mut entry_count = 0;
-    let mut total_size: u64 = 0;
-    let mut oldest_time: Option<std::time::SystemTime> = None;
-    let mut newest_time: Option<std::time::SystemTime> = None;
-    let mut oldest_name: Option<String> = None;
-    let mut newest_name: Option<String> = None;
+/// Recursively collect every file in the cache directory
+fn collect_cache_entries(path: &Path) -> Vec<CacheEntry> {
+    let mut entries = Vec::new();
+    let now = SystemTime::now();
 
-    if let Ok(entries) = fs::read_dir(path) {
-        for entry in entries.flatten() {
+    if let Ok(dir) = fs::read_dir(path) {
+        for entry in dir.flatten() {
             let metadata = match entry.metadata() {
                 Ok(m) => m,
                 Err(_) => continue,
             };
 
             if metadata.is_file() {
-                entry_count += 1;
-                total_size += metadata.len();
-
-                if let Ok(modified) = metadata.modified() {
-                    let entry_name = entry.file_name().to_string_lossy().to_string();
-
-                    let is_oldest = match oldest_time {
-                        None => true,
-                        Some(t) => modified < t,
-                    };
-                    if is_oldest {
-                        oldest_time = Some(modified);
-                        oldest_name = Some(entry_name.clone());
-                    }
-
-                    let is_newest = match newest_time {
-                        None => true,
-                        Some(t) => modified > t,
-                    };
-                    if is_newest {
-                        newest_time = Some(modified);
-                        newest_name = Some(entry_name);
-                    }
-                }
+                let age_secs = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|m| now.duration_since(m).ok())
+                    .map(|d| d.as_secs());
+                entries.push(CacheEntry {
+                    path: entry.path(),
+                    name: entry.file_name().to_string_lossy().to_string(),
+                    size_bytes: metadata.len(),
+                    age_secs,
+                });
             } else if metadata.is_dir() {
-                // Recursively count subdirectory contents
-                let subpath = entry.path();
-                let (sub_count, sub_size, _, _) = analyze_cache_contents(subpath.to_str().unwrap_or(""));
-                entry_count += sub_count;
-                total_size += sub_size;
+                entries.extend(collect_cache_entries(&entry.path()));
+            }
+        }
+    }
+
+    entries
+}
+
+/// Entry count, total size, and oldest/newest entry names
+fn summarize_entries(entries: &[CacheEntry]) -> (usize, u64, Option<String>, Option<String>) {
+    let total_size = entries.iter().map(|e| e.size_bytes).sum();
+    let dated = entries.iter().filter(|e| e.age_secs.is_some());
+    let oldest = dated.clone().max_by_key(|e| e.age_secs).map(|e| e.name.clone());
+    let newest = dated.min_by_key(|e| e.age_secs).map(|e| e.name.clone());
+
+    (entries.len(), total_size, oldest, newest)
+}
+
+fn bucket_by_age(entries: &[CacheEntry]) -> Vec<AgeBucket> {
+    let mut buckets: Vec<AgeBucket> = AGE_BUCKETS
+        .iter()
+        .map(|(label, _)| AgeBucket {
+            label,
+            entry_count: 0,
+            size_bytes: 0,
+        })
+        .collect();
+
+    for entry in entries {
+        let age = entry.age_secs.unwrap_or(0);
+        if let Some(idx) = AGE_BUCKETS.iter().position(|(_, max)| age < *max) {
+            buckets[idx].entry_count += 1;
+            buckets[idx].size_bytes += entry.size_bytes;
+        }
+    }
+
+    buckets
+}
+
+fn usage_by_key(entries: &[CacheEntry]) -> Vec<KeyUsage> {
+    let mut usage: Vec<KeyUsage> = Vec::new();
+
+    for entry in entries {
+        let key = compilation_key(&entry.name);
+        match usage.iter_mut().find(|u| u.key == key) {
+            Some(u) => {
+                u.entry_count += 1;
+                u.size_bytes += entry.size_bytes;
             }
+            None => usage.push(KeyUsage {
+                key,
+                entry_count: 1,
+                size_bytes: entry.size_bytes,
+            }),
+        }
+    }
+
+    usage.sort_by_key(|u| std::cmp::Reverse(u.size_bytes));
+    usage.truncate(TOP_KEYS);
+    usage
+}
+
+/// Derive the compilation key (module name) from a cache file name.
+///
+/// JAX names entries `<module>-<hash>` with an optional `-atime`/`-cache`
+/// suffix; the hash is dropped so all variants of a module group together.
+fn compilation_key(name: &str) -> String {
+    let base = name
+        .trim_end_matches("-atime")
+        .trim_end_matches("-cache");
+
+    match base.rsplit_once('-') {
+        Some((module, hash))
+            if !module.is_empty() && hash.len() >= 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) =>
+        {
+            module.to_string()
         }
+        _ => base.to_string(),
     }
+}
+
+/// Entries that cannot be valid cache payloads: empty files and leftovers of interrupted writes
+fn find_corrupted_entries(entries: &[CacheEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .filter(|e| {
+            e.size_bytes == 0
+                || e.name.ends_with(".tmp")
+                || e.name.ends_with(".partial")
+                || e.name.starts_with(".tmp")
+        })
+        .filter(|e| !e.name.ends_with("-atime"))
+        .map(|e| e.path.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Detect the installed jaxlib/libtpu packages and when they were installed
+fn detect_stack_packages() -> Vec<StackPackage> {
+    let script = r#"
+import importlib.metadata as md
+import os
+import time
+
+now = time.time()
+for name in ("jaxlib", "libtpu", "libtpu-nightly"):
+    try:
+        dist = md.distribution(name)
+    except md.PackageNotFoundError:
+        continue
+    metadata = [f for f in dist.files or [] if f.name == "METADATA" and f.parent.name.endswith(".dist-info")]
+    if not metadata:
+        continue
+    installed = os.path.getmtime(dist.locate_file(metadata[0]))
+    print(name, dist.version, int(max(0, now - installed)))
+"#;
+
+    let output = match linux::run_command_with_timeout(
+        std::process::Command::new("python3").args(["-c", script]),
+        STACK_PROBE_TIMEOUT_MS,
+    ) {
+        Ok(o) if o.status.success() => o,
+        _ => return Vec::new(),
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts.as_slice() {
+                [name, version, age] => Some(StackPackage {
+                    name: name.to_string(),
+                    version: version.to_string(),
+                    installed_age_secs: age.parse().ok()?,
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Estimate what fraction of the cache is reachable with the installed stack
+fn estimate_hit_rate(entries: &[CacheEntry], packages: &[StackPackage], now: SystemTime) -> Option<HitRateEstimate> {
+    let installed_age = packages.iter().map(|p| p.installed_age_secs).min()?;
+
+    let (payloads, _) = pair_companions(entries);
+    if payloads.is_empty() {
+        return None;
+    }
+
+    let total = payloads.len();
+    let stale: Vec<&CacheEntry> = payloads
+        .into_iter()
+        .filter(|(payload, companion)| {
+            last_used_age_secs(payload, *companion, now).is_some_and(|age| age > installed_age)
+        })
+        .map(|(payload, _)| payload)
+        .collect();
+
+    Some(HitRateEstimate {
+        packages: packages.to_vec(),
+        valid_entries: total - stale.len(),
+        stale_entries: stale.len(),
+        stale_bytes: stale.iter().map(|e| e.size_bytes).sum(),
+        estimated_hit_rate_pct: (total - stale.len()) as f64 / total as f64 * 100.0,
+    })
+}
+
+fn clean_cache(args: &Args) -> Result<CleanResult, TpuDocError> {
+    let older_than_secs = args.older_than_secs.ok_or_else(|| TpuDocError::CommandError {
+        command: "cache clean".to_string(),
+        message: "--older-than <AGE> is required (e.g. --older-than 30d)".to_string(),
+        source: None,
+    })?;
+
+    let cache_path = get_cache_path().ok_or_else(|| TpuDocError::CommandError {
+        command: "cache clean".to_string(),
+        message: "XLA cache is not configured".to_string(),
+        source: None,
+    })?;
+
+    if cache_path.starts_with("gs://") || !Path::new(&cache_path).is_dir() {
+        return Err(TpuDocError::CommandError {
+            command: "cache clean".to_string(),
+            message: format!("Cache directory is not a local directory: {}", cache_path),
+            source: None,
+        });
+    }
+
+    let mut result = CleanResult {
+        cache_path: cache_path.clone(),
+        older_than_secs,
+        dry_run: args.dry_run,
+        removed: Vec::new(),
+        failed: Vec::new(),
+        freed_bytes: 0,
+    };
+
+    let entries = collect_cache_entries(Path::new(&cache_path));
+    for group in stale_groups(&entries, older_than_secs, SystemTime::now()) {
+        for entry in group {
+            if !args.dry_run {
+                if let Err(e) = fs::remove_file(&entry.path) {
+                    result.failed.push(format!("{}: {}", entry.path.display(), e));
+                    // Keep the companion so the payload still has an access time
+                    break;
+                }
+            }
+
+            result.freed_bytes += entry.size_bytes;
+            result.removed.push(entry.clone());
+        }
+    }
+
+    Ok(result)
+}
+
+/// Entries not used for `older_than_secs`, each payload grouped with its `-atime` companion.
+///
+/// JAX's LRU cache stores `<key>-cache` next to a `<key>-atime` file that it
+/// rewrites on every read, so that file (not the payload's mtime) says when
+/// the entry was last used. Companions whose payload is gone go by their own age.
+fn stale_groups(entries: &[CacheEntry], older_than_secs: u64, now: SystemTime) -> Vec<Vec<&CacheEntry>> {
+    let (payloads, orphans) = pair_companions(entries);
+
+    let mut groups = Vec::new();
+    for (payload, companion) in payloads {
+        if last_used_age_secs(payload, companion, now).is_some_and(|age| age >= older_than_secs) {
+            groups.push(std::iter::once(payload).chain(companion).collect());
+        }
+    }
+
+    for orphan in orphans {
+        if orphan.age_secs.is_some_and(|age| age >= older_than_secs) {
+            groups.push(vec![orphan]);
+        }
+    }
+
+    groups
+}
+
+/// Each payload with its `-atime` companion, and the companions (sorted by
+/// path) whose payload is gone
+fn pair_companions(entries: &[CacheEntry]) -> (Vec<(&CacheEntry, Option<&CacheEntry>)>, Vec<&CacheEntry>) {
+    let mut companions: HashMap<&Path, &CacheEntry> = entries
+        .iter()
+        .filter(|e| e.name.ends_with("-atime"))
+        .map(|e| (e.path.as_path(), e))
+        .collect();
+
+    let payloads = entries
+        .iter()
+        .filter(|e| !e.name.ends_with("-atime"))
+        .map(|payload| {
+            let base = payload.name.strip_suffix("-cache").unwrap_or(&payload.name);
+            (payload, companions.remove(payload.path.with_file_name(format!("{}-atime", base)).as_path()))
+        })
+        .collect();
+
+    let mut orphans: Vec<&CacheEntry> = companions.into_values().collect();
+    orphans.sort_by(|a, b| a.path.cmp(&b.path));
+    (payloads, orphans)
+}
+
+/// Seconds since a payload was last used.
+///
+/// The companion holds `time.time_ns()` as 8 little-endian bytes; when it is
+/// missing or unreadable, the companion's or else the payload's mtime is used.
+fn last_used_age_secs(payload: &CacheEntry, companion: Option<&CacheEntry>, now: SystemTime) -> Option<u64> {
+    let recorded = companion
+        .and_then(|c| fs::read(&c.path).ok())
+        .and_then(|bytes| <[u8; 8]>::try_from(bytes.as_slice()).ok())
+        .map(|bytes| UNIX_EPOCH + Duration::from_nanos(u64::from_le_bytes(bytes)));
 
-    (entry_count, total_size, oldest_name, newest_name)
+    match recorded {
+        Some(at) => Some(now.duration_since(at).map(|d| d.as_secs()).unwrap_or(0)),
+        None => companion.and_then(|c| c.age_secs).or(payload.age_secs),
+    }
 }
 
 fn get_available_disk_space(path: &str) -> Option<f64> {
@@ -359,6 +777,64 @@ fn format_text(analysis: &CacheAnalysis, verbose: bool) -> String {
             }
         }
         output.push('\n');
+
+        if analysis.entry_count > 0 {
+            output.push_str("SIZE BY AGE\n");
+            output.push_str("-----------\n");
+            for bucket in &analysis.age_buckets {
+                output.push_str(&format!(
+                    "  {:<16} {:>6} entries  {:>10.2} MB\n",
+                    bucket.label,
+                    bucket.entry_count,
+                    bucket.size_bytes as f64 / (1024.0 * 1024.0)
+                ));
+            }
+            output.push('\n');
+
+            output.push_str("SIZE BY COMPILATION KEY\n");
+            output.push_str("-----------------------\n");
+            for usage in &analysis.top_keys {
+                output.push_str(&format!(
+                    "  {:<40} {:>6} entries  {:>10.2} MB\n",
+                    truncate_key(&usage.key, 40),
+                    usage.entry_count,
+                    usage.size_bytes as f64 / (1024.0 * 1024.0)
+                ));
+            }
+            output.push('\n');
+        }
+
+        if let Some(ref estimate) = analysis.hit_rate {
+            output.push_str("HIT-RATE ESTIMATE (heuristic)\n");
+            output.push_str("-----------------------------\n");
+            output.push_str("  Entries written before the newest install count as unreachable; cache\n");
+            output.push_str("  files do not record the versions they were compiled for.\n");
+            for package in &estimate.packages {
+                output.push_str(&format!(
+                    "  {:<19} {} (installed {} ago)\n",
+                    package.name,
+                    package.version,
+                    format_age(package.installed_age_secs)
+                ));
+            }
+            output.push_str(&format!("  Likely Reachable:   {} entries\n", estimate.valid_entries));
+            output.push_str(&format!(
+                "  Likely Unreachable: {} entries ({:.2} MB)\n",
+                estimate.stale_entries,
+                estimate.stale_bytes as f64 / (1024.0 * 1024.0)
+            ));
+            output.push_str(&format!("  Estimated Hits:     {:.0}%\n", estimate.estimated_hit_rate_pct));
+            output.push('\n');
+        }
+
+        if verbose && !analysis.corrupted_entries.is_empty() {
+            output.push_str("CORRUPTED ENTRIES\n");
+            output.push_str("-----------------\n");
+            for entry in &analysis.corrupted_entries {
+                output.push_str(&format!("  {}\n", entry));
+            }
+            output.push('\n');
+        }
     }
 
     // Issues
@@ -398,21 +874,83 @@ fn format_json(analysis: &CacheAnalysis) -> String {
     json.push_str(&format!("  \"health_status\": \"{:?}\",\n", analysis.health_status));
     json.push_str(&format!("  \"cache_configured\": {},\n", analysis.cache_configured));
     json.push_str(&format!("  \"cache_path\": {},\n",
-        analysis.cache_path.as_ref().map(|p| format!("\"{}\"", p)).unwrap_or_else(|| "null".to_string())));
+        analysis.cache_path.as_deref().map(json::quote).unwrap_or_else(|| "null".to_string())));
     json.push_str(&format!("  \"cache_exists\": {},\n", analysis.cache_exists));
     json.push_str(&format!("  \"cache_writable\": {},\n", analysis.cache_writable));
     json.push_str(&format!("  \"entry_count\": {},\n", analysis.entry_count));
     json.push_str(&format!("  \"total_size_mb\": {:.2},\n", analysis.total_size_mb));
     json.push_str(&format!("  \"oldest_entry\": {},\n",
-        analysis.oldest_entry.as_ref().map(|e| format!("\"{}\"", e)).unwrap_or_else(|| "null".to_string())));
+        analysis.oldest_entry.as_deref().map(json::quote).unwrap_or_else(|| "null".to_string())));
     json.push_str(&format!("  \"newest_entry\": {},\n",
-        analysis.newest_entry.as_ref().map(|e| format!("\"{}\"", e)).unwrap_or_else(|| "null".to_string())));
+        analysis.newest_entry.as_deref().map(json::quote).unwrap_or_else(|| "null".to_string())));
+
+    json.push_str("  \"age_buckets\": [\n");
+    for (i, bucket) in analysis.age_buckets.iter().enumerate() {
+        json.push_str(&format!(
+            "    {{\"age\": \"{}\", \"entry_count\": {}, \"size_bytes\": {}}}",
+            bucket.label, bucket.entry_count, bucket.size_bytes
+        ));
+        if i < analysis.age_buckets.len() - 1 {
+            json.push(',');
+        }
+        json.push('\n');
+    }
+    json.push_str("  ],\n");
+
+    json.push_str("  \"top_keys\": [\n");
+    for (i, usage) in analysis.top_keys.iter().enumerate() {
+        json.push_str(&format!(
+            "    {{\"key\": \"{}\", \"entry_count\": {}, \"size_bytes\": {}}}",
+            json::escape(&usage.key), usage.entry_count, usage.size_bytes
+        ));
+        if i < analysis.top_keys.len() - 1 {
+            json.push(',');
+        }
+        json.push('\n');
+    }
+    json.push_str("  ],\n");
+
+    match analysis.hit_rate {
+        Some(ref estimate) => {
+            json.push_str("  \"hit_rate\": {\n");
+            json.push_str("    \"packages\": [");
+            for (i, package) in estimate.packages.iter().enumerate() {
+                if i > 0 {
+                    json.push_str(", ");
+                }
+                json.push_str(&format!(
+                    "{{\"name\": \"{}\", \"version\": \"{}\", \"installed_age_secs\": {}}}",
+                    json::escape(&package.name),
+                    json::escape(&package.version),
+                    package.installed_age_secs
+                ));
+            }
+            json.push_str("],\n");
+            json.push_str(&format!("    \"valid_entries\": {},\n", estimate.valid_entries));
+            json.push_str(&format!("    \"stale_entries\": {},\n", estimate.stale_entries));
+            json.push_str(&format!("    \"stale_bytes\": {},\n", estimate.stale_bytes));
+            json.push_str(&format!("    \"estimated_hit_rate_pct\": {:.1},\n", estimate.estimated_hit_rate_pct));
+            json.push_str("    \"method\": \"install_time_heuristic\"\n");
+            json.push_str("  },\n");
+        }
+        None => json.push_str("  \"hit_rate\": null,\n"),
+    }
+
+    json.push_str("  \"corrupted_entries\": [\n");
+    for (i, entry) in analysis.corrupted_entries.iter().enumerate() {
+        json.push_str(&format!("    {}", json::quote(entry)));
+        if i < analysis.corrupted_entries.len() - 1 {
+            json.push(',');
+        }
+        json.push('\n');
+    }
+    json.push_str("  ],\n");
 
     json.push_str("  \"issues\": [\n");
     for (i, issue) in analysis.issues.iter().enumerate() {
         json.push_str("    {\n");
         json.push_str(&format!("      \"severity\": \"{:?}\",\n", issue.severity));
-        json.push_str(&format!("      \"description\": \"{}\"\n", issue.description));
+        json.push_str(&format!("      \"description\": {}\n", json::quote(&issue.description)));
         json.push_str("    }");
         if i < analysis.issues.len() - 1 {
             json.push(',');
@@ -423,7 +961,7 @@ fn format_json(analysis: &CacheAnalysis) -> String {
 
     json.push_str("  \"recommendations\": [\n");
     for (i, rec) in analysis.recommendations.iter().enumerate() {
-        json.push_str(&format!("    \"{}\"", rec));
+        json.push_str(&format!("    {}", json::quote(rec)));
         if i < analysis.recommendations.len() - 1 {
             json.push(',');
         }
@@ -434,3 +972,222 @@ fn format_json(analysis: &CacheAnalysis) -> String {
     json.push_str("}\n");
     json
 }
+
+fn format_clean_text(result: &CleanResult) -> String {
+    let mut output = String::new();
+
+    output.push_str("================================================================================\n");
+    if result.dry_run {
+        output.push_str("                      XLA CACHE CLEAN (DRY RUN)\n");
+    } else {
+        output.push_str("                           XLA CACHE CLEAN\n");
+    }
+    output.push_str("================================================================================\n\n");
+
+    output.push_str(&format!("  Cache Path:      {}\n", result.cache_path));
+    output.push_str(&format!("  Older Than:      {}\n", format_age(result.older_than_secs)));
+    output.push('\n');
+
+    let verb = if result.dry_run { "Would remove" } else { "Removed" };
+    for entry in &result.removed {
+        output.push_str(&format!(
+            "  {} {} ({:.2} MB, {})\n",
+            verb,
+            entry.path.display(),
+            entry.size_bytes as f64 / (1024.0 * 1024.0),
+            entry.age_secs.map(format_age).unwrap_or_default()
+        ));
+    }
+    for failure in &result.failed {
+        output.push_str(&format!("  [ERROR] Could not remove {}\n", failure));
+    }
+    if !result.removed.is_empty() || !result.failed.is_empty() {
+        output.push('\n');
+    }
+
+    output.push_str(&format!(
+        "{} {} entries, {:.2} MB\n",
+        verb,
+        result.removed.len(),
+        result.freed_bytes as f64 / (1024.0 * 1024.0)
+    ));
+    if result.dry_run && !result.removed.is_empty() {
+        output.push_str("Re-run without --dry-run to delete these entries.\n");
+    }
+    output.push_str("\n================================================================================\n");
+
+    output
+}
+
+fn format_clean_json(result: &CleanResult) -> String {
+    let mut json = String::new();
+    json.push_str("{\n");
+    json.push_str(&format!("  \"cache_path\": \"{}\",\n", json::escape(&result.cache_path)));
+    json.push_str(&format!("  \"older_than_secs\": {},\n", result.older_than_secs));
+    json.push_str(&format!("  \"dry_run\": {},\n", result.dry_run));
+    json.push_str(&format!("  \"freed_bytes\": {},\n", result.freed_bytes));
+
+    json.push_str("  \"removed\": [\n");
+    for (i, entry) in result.removed.iter().enumerate() {
+        json.push_str(&format!(
+            "    {{\"path\": \"{}\", \"size_bytes\": {}, \"age_secs\": {}}}",
+            json::escape(&entry.path.to_string_lossy()),
+            entry.size_bytes,
+            entry.age_secs.map(|a| a.to_string()).unwrap_or_else(|| "null".to_string())
+        ));
+        if i < result.removed.len() - 1 {
+            json.push(',');
+        }
+        json.push('\n');
+    }
+    json.push_str("  ],\n");
+
+    json.push_str("  \"failed\": [\n");
+    for (i, failure) in result.failed.iter().enumerate() {
+        json.push_str(&format!("    \"{}\"", json::escape(failure)));
+        if i < result.failed.len() - 1 {
+            json.push(',');
+        }
+        json.push('\n');
+    }
+    json.push_str("  ]\n");
+
+    json.push_str("}\n");
+    json
+}
+
+/// Render an age in seconds as the largest whole unit (e.g. "45d", "3h")
+fn format_age(secs: u64) -> String {
+    if secs >= 86400 {
+        format!("{}d", secs / 86400)
+    } else if secs >= 3600 {
+        format!("{}h", secs / 3600)
+    } else if secs >= 60 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+fn truncate_key(key: &str, max: usize) -> String {
+    if key.chars().count() <= max {
+        key.to_string()
+    } else {
+        let truncated: String = key.chars().take(max - 3).collect();
+        format!("{}...", truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compilation_key() {
+        assert_eq!(compilation_key("jit_train_step-8f3a9c0d1e2b4a5f6c7d8e9f"), "jit_train_step");
+        assert_eq!(compilation_key("jit_train_step-8f3a9c0d1e2b4a5f6c7d8e9f-atime"), "jit_train_step");
+        assert_eq!(compilation_key("module-v2"), "module-v2");
+    }
+
+    #[test]
+    fn test_bucket_by_age() {
+        let entry = |age_days: u64, size: u64| CacheEntry {
+            path: PathBuf::from("x"),
+            name: "x".to_string(),
+            size_bytes: size,
+            age_secs: Some(age_days * 86400),
+        };
+        let buckets = bucket_by_age(&[entry(0, 1), entry(3, 2), entry(10, 4), entry(45, 8), entry(60, 16)]);
+
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(buckets[0].size_bytes, 1);
+        assert_eq!(buckets[1].size_bytes, 2);
+        assert_eq!(buckets[2].size_bytes, 4);
+        assert_eq!(buckets[3].entry_count, 2);
+        assert_eq!(buckets[3].size_bytes, 24);
+    }
+
+    #[test]
+    fn test_estimate_hit_rate() {
+        let entry = |name: &str, age_days: u64| CacheEntry {
+            path: PathBuf::from(name),
+            name: name.to_string(),
+            size_bytes: 10,
+            age_secs: Some(age_days * 86400),
+        };
+        let packages = vec![StackPackage {
+            name: "jaxlib".to_string(),
+            version: "0.4.35".to_string(),
+            installed_age_secs: 5 * 86400,
+        }];
+        let entries = vec![entry("a", 1), entry("b", 10), entry("c", 20), entry("c-atime", 20)];
+
+        let estimate = estimate_hit_rate(&entries, &packages, SystemTime::now()).unwrap();
+        assert_eq!(estimate.valid_entries, 1);
+        assert_eq!(estimate.stale_entries, 2);
+        assert_eq!(estimate.stale_bytes, 20);
+        assert!((estimate.estimated_hit_rate_pct - 33.3).abs() < 0.1);
+
+        assert!(estimate_hit_rate(&entries, &[], SystemTime::now()).is_none());
+    }
+
+    #[test]
+    fn test_estimate_hit_rate_uses_atime_companion() {
+        let dir = env::temp_dir().join(format!("tpu-doc-cache-hit-rate-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let now = SystemTime::now();
+        let ns_ago = |days: u64| {
+            let at = now - Duration::from_secs(days * 86400);
+            (at.duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64).to_le_bytes()
+        };
+        // Both payloads were just written; only the companions say when they were last read
+        fs::write(dir.join("reused-cache"), b"payload").unwrap();
+        fs::write(dir.join("reused-atime"), ns_ago(1)).unwrap();
+        fs::write(dir.join("abandoned-cache"), b"payload").unwrap();
+        fs::write(dir.join("abandoned-atime"), ns_ago(20)).unwrap();
+
+        let packages = vec![StackPackage {
+            name: "libtpu".to_string(),
+            version: "0.0.5".to_string(),
+            installed_age_secs: 5 * 86400,
+        }];
+        let estimate = estimate_hit_rate(&collect_cache_entries(&dir), &packages, now).unwrap();
+        assert_eq!(estimate.valid_entries, 1);
+        assert_eq!(estimate.stale_entries, 1);
+        assert_eq!(estimate.stale_bytes, 7);
+
+        // The same age as `cache clean` would use for these entries
+        let entries = collect_cache_entries(&dir);
+        assert_eq!(stale_groups(&entries, 5 * 86400 + 1, now).len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stale_groups_use_atime_companion() {
+        let dir = env::temp_dir().join(format!("tpu-doc-cache-clean-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let now = SystemTime::now();
+        let ns_ago = |days: u64| {
+            let at = now - Duration::from_secs(days * 86400);
+            (at.duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64).to_le_bytes()
+        };
+        fs::write(dir.join("unused-cache"), b"payload").unwrap();
+        fs::write(dir.join("unused-atime"), ns_ago(40)).unwrap();
+        fs::write(dir.join("recent-cache"), b"payload").unwrap();
+        fs::write(dir.join("recent-atime"), ns_ago(1)).unwrap();
+        fs::write(dir.join("legacy"), b"payload").unwrap();
+
+        let entries = collect_cache_entries(&dir);
+        let names = |groups: Vec<Vec<&CacheEntry>>| -> Vec<Vec<String>> {
+            groups.iter().map(|g| g.iter().map(|e| e.name.clone()).collect()).collect()
+        };
+        assert_eq!(
+            names(stale_groups(&entries, 30 * 86400, now)),
+            vec![vec!["unused-cache".to_string(), "unused-atime".to_string()]]
+        );
+        assert_eq!(stale_groups(&entries, 0, now).iter().map(|g| g.len()).sum::<usize>(), 5);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
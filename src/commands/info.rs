@@ -1,9 +1,13 @@
 //! Environment info command
 //!
 //! Displays complete environment fingerprint without making pass/fail judgments.
+//! With `--diff`, compares a saved fingerprint against the current
+//! environment or another saved one instead (see [`info_diff`]).
 
 use crate::cli::args::{Args, OutputFormat};
+use crate::commands::info_diff;
 use crate::platform::{gcp, linux, tpu};
+use crate::util;
 use crate::TpuDocError;
 use std::env;
 
@@ -25,6 +29,7 @@ pub struct TpuInfo {
     pub topology: Option<String>,
     pub hbm_capacity_gb: Option<u32>,
     pub machine_type: Option<String>,
+    pub driver_version: Option<String>,
 }
 
 #[derive(Debug)]
@@ -35,6 +40,19 @@ pub struct SoftwareInfo {
     pub libtpu_version: Option<String>,
     pub numpy_version: Option<String>,
     pub env_vars: Vec<(String, String)>,
+    /// Installed packages, gathered with `--packages`
+    pub packages: Option<PackageInventory>,
+}
+
+/// Everything installed in the Python environment, as the package managers
+/// report it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageInventory {
+    /// `pip freeze` lines, e.g. `jax==0.4.35`
+    pub pip: Vec<String>,
+    /// `conda list --export` lines, e.g. `numpy=1.26.4=py311h64a7726_0`,
+    /// when a conda environment is active
+    pub conda: Option<Vec<String>>,
 }
 
 #[derive(Debug)]
@@ -62,7 +80,14 @@ pub struct NetworkInfo {
 
 /// Run the info command
 pub fn run(args: &Args) -> Result<String, TpuDocError> {
-    let info = gather_environment_info();
+    if let Some(old) = &args.diff {
+        return info_diff::run(args, old);
+    }
+
+    let mut info = gather_environment_info();
+    if args.packages {
+        info.software.packages = Some(detect_packages());
+    }
 
     match args.format {
         OutputFormat::Json => Ok(format_json(&info)),
@@ -77,7 +102,7 @@ pub fn gather_environment_info_internal() -> EnvironmentInfo {
 
 fn gather_environment_info() -> EnvironmentInfo {
     // Get current timestamp
-    let timestamp = get_iso_timestamp();
+    let timestamp = util::time::now_rfc3339();
 
     // Gather TPU information
     let tpu_type_result = tpu::get_tpu_type();
@@ -87,6 +112,7 @@ fn gather_environment_info() -> EnvironmentInfo {
         topology: tpu::get_tpu_topology().ok().map(|t| t.shape),
         hbm_capacity_gb: tpu::get_hbm_info().ok().map(|h| (h.total_bytes / (1024 * 1024 * 1024)) as u32),
         machine_type: gcp::get_machine_type().ok(),
+        driver_version: tpu::get_driver_version().ok(),
     };
 
     // Gather software information
@@ -97,6 +123,7 @@ fn gather_environment_info() -> EnvironmentInfo {
         libtpu_version: detect_libtpu_version(),
         numpy_version: detect_numpy_version(),
         env_vars: get_relevant_env_vars(),
+        packages: None,
     };
 
     // Gather system information
@@ -135,57 +162,6 @@ fn gather_environment_info() -> EnvironmentInfo {
     }
 }
 
-fn get_iso_timestamp() -> String {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let duration = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default();
-    let secs = duration.as_secs();
-
-    // Simple ISO 8601 format (UTC)
-    let days_since_1970 = secs / 86400;
-    let time_of_day = secs % 86400;
-    let hours = time_of_day / 3600;
-    let minutes = (time_of_day % 3600) / 60;
-    let seconds = time_of_day % 60;
-
-    // Calculate year, month, day (simplified)
-    let mut year = 1970;
-    let mut remaining_days = days_since_1970;
-
-    loop {
-        let days_in_year = if is_leap_year(year) { 366 } else { 365 };
-        if remaining_days < days_in_year {
-            break;
-        }
-        remaining_days -= days_in_year;
-        year += 1;
-    }
-
-    let days_in_months: [u64; 12] = if is_leap_year(year) {
-        [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    } else {
-        [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    };
-
-    let mut month = 1;
-    for days in days_in_months.iter() {
-        if remaining_days < *days {
-            break;
-        }
-        remaining_days -= days;
-        month += 1;
-    }
-    let day = remaining_days + 1;
-
-    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
-            year, month, day, hours, minutes, seconds)
-}
-
-fn is_leap_year(year: u64) -> bool {
-    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
-}
-
 fn detect_python_version() -> Option<String> {
     use std::process::Command;
     let output = Command::new("python3")
@@ -196,8 +172,8 @@ fn detect_python_version() -> Option<String> {
     if output.status.success() {
         let version = String::from_utf8_lossy(&output.stdout);
         let version = version.trim();
-        if version.starts_with("Python ") {
-            return Some(version[7..].to_string());
+        if let Some(v) = version.strip_prefix("Python ") {
+            return Some(v.to_string());
         }
         Some(version.to_string())
     } else {
@@ -205,7 +181,7 @@ fn detect_python_version() -> Option<String> {
     }
 }
 
-fn detect_jax_version() -> Option<String> {
+pub(crate) fn detect_jax_version() -> Option<String> {
     // Try environment variable first
     if let Ok(version) = env::var("JAX_VERSION") {
         return Some(version);
@@ -226,7 +202,7 @@ fn detect_jax_version() -> Option<String> {
     }
 }
 
-fn detect_jaxlib_version() -> Option<String> {
+pub(crate) fn detect_jaxlib_version() -> Option<String> {
     use std::process::Command;
     let output = Command::new("python3")
         .args(["-c", "import jaxlib; print(jaxlib.__version__)"])
@@ -241,7 +217,7 @@ fn detect_jaxlib_version() -> Option<String> {
     }
 }
 
-fn detect_libtpu_version() -> Option<String> {
+pub(crate) fn detect_libtpu_version() -> Option<String> {
     // Check environment variable
     if let Ok(version) = env::var("LIBTPU_VERSION") {
         return Some(version);
@@ -265,6 +241,36 @@ fn detect_numpy_version() -> Option<String> {
     }
 }
 
+/// The packages pip sees for `python3`, and conda's when a conda
+/// environment is active
+pub(crate) fn detect_packages() -> PackageInventory {
+    use std::process::Command;
+    let lines = |program: &str, args: &[&str]| {
+        Command::new(program)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| parse_package_lines(&String::from_utf8_lossy(&output.stdout)))
+    };
+
+    PackageInventory {
+        pip: lines("python3", &["-m", "pip", "freeze"]).unwrap_or_default(),
+        conda: env::var_os("CONDA_PREFIX").and_then(|_| lines("conda", &["list", "--export"])),
+    }
+}
+
+/// Package lines from `pip freeze` or `conda list --export` output, without
+/// blank lines and comments
+fn parse_package_lines(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
 fn get_relevant_env_vars() -> Vec<(String, String)> {
     let relevant_vars = [
         "TPU_NAME",
@@ -310,6 +316,8 @@ fn format_text(info: &EnvironmentInfo, verbose: bool) -> String {
         info.tpu.hbm_capacity_gb.map(|c| c.to_string()).unwrap_or_else(|| "N/A".to_string())));
     output.push_str(&format!("  Machine Type:    {}\n",
         info.tpu.machine_type.as_deref().unwrap_or("N/A")));
+    output.push_str(&format!("  Driver:          {}\n",
+        info.tpu.driver_version.as_deref().unwrap_or("N/A")));
     output.push('\n');
 
     // Software Stack
@@ -342,6 +350,23 @@ fn format_text(info: &EnvironmentInfo, verbose: bool) -> String {
         output.push('\n');
     }
 
+    // Installed Packages
+    if let Some(packages) = &info.software.packages {
+        output.push_str(&format!("INSTALLED PACKAGES (pip freeze, {})\n", packages.pip.len()));
+        output.push_str("---------------------------------\n");
+        for line in &packages.pip {
+            output.push_str(&format!("  {}\n", line));
+        }
+        if let Some(conda) = &packages.conda {
+            output.push_str(&format!("\nCONDA PACKAGES (conda list, {})\n", conda.len()));
+            output.push_str("-----------------------------\n");
+            for line in conda {
+                output.push_str(&format!("  {}\n", line));
+            }
+        }
+        output.push('\n');
+    }
+
     // System Information
     output.push_str("SYSTEM INFORMATION\n");
     output.push_str("------------------\n");
@@ -384,7 +409,7 @@ fn format_text(info: &EnvironmentInfo, verbose: bool) -> String {
     output
 }
 
-fn format_json(info: &EnvironmentInfo) -> String {
+pub(crate) fn format_json(info: &EnvironmentInfo) -> String {
     let mut json = String::new();
     json.push_str("{\n");
     json.push_str(&format!("  \"timestamp\": \"{}\",\n", info.timestamp));
@@ -398,8 +423,10 @@ fn format_json(info: &EnvironmentInfo) -> String {
         info.tpu.topology.as_ref().map(|t| format!("\"{}\"", t)).unwrap_or_else(|| "null".to_string())));
     json.push_str(&format!("    \"hbm_capacity_gb\": {},\n",
         info.tpu.hbm_capacity_gb.map(|c| c.to_string()).unwrap_or_else(|| "null".to_string())));
-    json.push_str(&format!("    \"machine_type\": {}\n",
+    json.push_str(&format!("    \"machine_type\": {},\n",
         info.tpu.machine_type.as_ref().map(|t| format!("\"{}\"", t)).unwrap_or_else(|| "null".to_string())));
+    json.push_str(&format!("    \"driver_version\": {}\n",
+        info.tpu.driver_version.as_ref().map(|v| format!("\"{}\"", v)).unwrap_or_else(|| "null".to_string())));
     json.push_str("  },\n");
 
     // Software
@@ -420,6 +447,15 @@ fn format_json(info: &EnvironmentInfo) -> String {
         let comma = if i < info.software.env_vars.len() - 1 { "," } else { "" };
         json.push_str(&format!("      \"{}\": \"{}\"{}\n", name, escaped_value, comma));
     }
+    if let Some(packages) = &info.software.packages {
+        json.push_str("    },\n");
+        json.push_str("    \"packages\": {\n");
+        json.push_str(&format!("      \"pip\": {},\n", json_string_array(&packages.pip)));
+        json.push_str(&format!(
+            "      \"conda\": {}\n",
+            packages.conda.as_deref().map(json_string_array).unwrap_or_else(|| "null".to_string())
+        ));
+    }
     json.push_str("    }\n");
     json.push_str("  },\n");
 
@@ -460,3 +496,38 @@ fn format_json(info: &EnvironmentInfo) -> String {
     json.push_str("}\n");
     json
 }
+
+/// `items` as a JSON array of strings on one line
+fn json_string_array(items: &[String]) -> String {
+    let items: Vec<String> = items.iter().map(|item| util::json::quote(item)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_package_lines() {
+        let freeze = "jax==0.4.35\njaxlib==0.4.35\n\n-e git+https://github.com/org/model.git@abc#egg=model\n";
+        assert_eq!(
+            parse_package_lines(freeze),
+            vec![
+                "jax==0.4.35".to_string(),
+                "jaxlib==0.4.35".to_string(),
+                "-e git+https://github.com/org/model.git@abc#egg=model".to_string(),
+            ]
+        );
+        let conda = "# This file may be used to create an environment using:\n# platform: linux-64\nnumpy=1.26.4=py311h64a7726_0\n";
+        assert_eq!(parse_package_lines(conda), vec!["numpy=1.26.4=py311h64a7726_0".to_string()]);
+    }
+
+    #[test]
+    fn test_json_string_array() {
+        assert_eq!(json_string_array(&[]), "[]");
+        assert_eq!(
+            json_string_array(&["a==1".to_string(), "b @ \"c\"".to_string()]),
+            "[\"a==1\", \"b @ \\\"c\\\"\"]"
+        );
+    }
+}
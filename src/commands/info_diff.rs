@@ -0,0 +1,370 @@
+//! Environment fingerprint diff (`info --diff`)
+//!
+//! Compares a snapshot saved with `info --format json` against the current
+//! environment, or against a second saved snapshot, and reports what
+//! changed: TPU, driver, and kernel versions, the software stack,
+//! environment variables, and installed packages. "It worked yesterday"
+//! usually comes down to one of these.
+//!
+//! Snapshots are flattened into dotted fields (`software.jax_version`,
+//! `software.env_vars.XLA_FLAGS`), with `--packages` inventories split into
+//! one field per package (`software.packages.pip.jax`). Packages are only
+//! compared when both snapshots recorded them.
+
+use crate::cli::args::{Args, OutputFormat};
+use crate::commands::info;
+use crate::util::json::{self, JsonValue};
+use crate::TpuDocError;
+
+/// Fields that differ between any two snapshots and are not reported
+const IGNORED_FIELDS: &[&str] = &["timestamp"];
+
+/// Prefix of the package inventory fields
+const PACKAGES_PREFIX: &str = "software.packages.";
+
+/// A field whose value differs between the snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub field: String,
+    /// Value in the old snapshot; None when the field was added
+    pub old: Option<String>,
+    /// Value in the new snapshot; None when the field was removed
+    pub new: Option<String>,
+}
+
+/// Run `info --diff old_path [new]`
+pub fn run(args: &Args, old_path: &str) -> Result<String, TpuDocError> {
+    let old = load(old_path)?;
+    let (new, new_label) = match &args.diff_against {
+        Some(path) => (load(path)?, path.clone()),
+        None => {
+            // Gather packages only when there is an inventory to compare them to
+            let mut current = info::gather_environment_info_internal();
+            if has_packages(&old) {
+                current.software.packages = Some(info::detect_packages());
+            }
+            let snapshot = json::parse(&info::format_json(&current)).map_err(|e| TpuDocError::ParseError {
+                context: "info --diff".to_string(),
+                message: e,
+                source: None,
+            })?;
+            (snapshot, "current environment".to_string())
+        }
+    };
+
+    let (changes, notes) = diff_snapshots(&old, &new);
+    let labels = (label(old_path, &old), label(&new_label, &new));
+    match args.format {
+        OutputFormat::Json => Ok(format_json(&labels, &changes, &notes)),
+        _ => Ok(format_text(&labels, &changes, &notes)),
+    }
+}
+
+fn load(path: &str) -> Result<JsonValue, TpuDocError> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| TpuDocError::io(&format!("reading info snapshot {}", path), e))?;
+    json::parse(&text).map_err(|e| TpuDocError::ParseError {
+        context: format!("info snapshot {}", path),
+        message: e,
+        source: None,
+    })
+}
+
+/// `name` with the snapshot's timestamp, when it has one
+fn label(name: &str, snapshot: &JsonValue) -> String {
+    match snapshot.get("timestamp").and_then(JsonValue::as_str) {
+        Some(timestamp) => format!("{} ({})", name, timestamp),
+        None => name.to_string(),
+    }
+}
+
+fn has_packages(snapshot: &JsonValue) -> bool {
+    snapshot
+        .get("software")
+        .and_then(|software| software.get("packages"))
+        .is_some_and(|packages| !packages.is_null())
+}
+
+/// The fields that differ between `old` and `new`, grouped by top-level
+/// section: in `old`'s field order, then fields only `new` has; and notes
+/// on what was not compared
+pub fn diff_snapshots(old: &JsonValue, new: &JsonValue) -> (Vec<Change>, Vec<String>) {
+    let mut old_fields = flatten(old);
+    let mut new_fields = flatten(new);
+    let mut notes = Vec::new();
+
+    if has_packages(old) != has_packages(new) {
+        let missing = if has_packages(old) { "new" } else { "old" };
+        notes.push(format!(
+            "Packages not compared: the {} snapshot has no inventory (save it with info --packages --format json)",
+            missing
+        ));
+        old_fields.retain(|(field, _)| !field.starts_with(PACKAGES_PREFIX));
+        new_fields.retain(|(field, _)| !field.starts_with(PACKAGES_PREFIX));
+    }
+
+    let lookup = |fields: &[(String, String)], field: &str| {
+        fields.iter().find(|(f, _)| f == field).map(|(_, value)| value.clone())
+    };
+    let mut changes: Vec<Change> = old_fields
+        .iter()
+        .filter_map(|(field, value)| {
+            let new_value = lookup(&new_fields, field);
+            (new_value.as_ref() != Some(value)).then(|| Change {
+                field: field.clone(),
+                old: Some(value.clone()),
+                new: new_value,
+            })
+        })
+        .collect();
+    changes.extend(
+        new_fields
+            .iter()
+            .filter(|(field, _)| lookup(&old_fields, field).is_none())
+            .map(|(field, value)| Change {
+                field: field.clone(),
+                old: None,
+                new: Some(value.clone()),
+            }),
+    );
+
+    // Keep each top-level section's changes together
+    let section = |field: &str| field.split('.').next().unwrap_or("").to_string();
+    let mut sections: Vec<String> = Vec::new();
+    for (field, _) in old_fields.iter().chain(&new_fields) {
+        if !sections.contains(&section(field)) {
+            sections.push(section(field));
+        }
+    }
+    changes.sort_by_key(|change| sections.iter().position(|s| *s == section(&change.field)));
+    (changes, notes)
+}
+
+/// A snapshot's fields as `(dotted path, value)`, skipping nulls; package
+/// inventories become one field per package
+fn flatten(snapshot: &JsonValue) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    flatten_into(snapshot, "", &mut fields);
+    fields.retain(|(field, _)| !IGNORED_FIELDS.contains(&field.as_str()));
+    fields
+}
+
+fn flatten_into(value: &JsonValue, path: &str, fields: &mut Vec<(String, String)>) {
+    let join = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+    match value {
+        JsonValue::Null => {}
+        JsonValue::Object(members) => {
+            for (key, member) in members {
+                flatten_into(member, &join(key), fields);
+            }
+        }
+        JsonValue::Array(items) => {
+            let lines: Vec<String> = items.iter().filter_map(JsonValue::to_scalar_string).collect();
+            match path.strip_prefix(PACKAGES_PREFIX) {
+                Some(manager) => {
+                    for line in &lines {
+                        let (name, version) = split_package(manager, line);
+                        fields.push((format!("{}.{}", path, name), version));
+                    }
+                }
+                None => fields.push((path.to_string(), lines.join(", "))),
+            }
+        }
+        scalar => {
+            if let Some(text) = scalar.to_scalar_string() {
+                fields.push((path.to_string(), text));
+            }
+        }
+    }
+}
+
+/// A package line's name and version: `jax==0.4.35` from pip,
+/// `numpy=1.26.4=py311h64a7726_0` from conda. Lines without a version, such
+/// as editable installs, keep the whole line as the name.
+fn split_package(manager: &str, line: &str) -> (String, String) {
+    let split = match manager {
+        "pip" => line.split_once("==").or_else(|| line.split_once(" @ ")),
+        _ => line.split_once('='),
+    };
+    match split {
+        Some((name, version)) => (name.trim().to_lowercase(), version.trim().to_string()),
+        None => (line.to_string(), String::new()),
+    }
+}
+
+/// Top-level section headers for the text report
+fn section_title(section: &str) -> String {
+    match section {
+        "tpu" => "TPU".to_string(),
+        "gcp" => "GCP".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+fn format_text(labels: &(String, String), changes: &[Change], notes: &[String]) -> String {
+    let mut output = String::new();
+    output.push_str("================================================================================\n");
+    output.push_str("                         ENVIRONMENT DIFF\n");
+    output.push_str("================================================================================\n\n");
+    output.push_str(&format!("Old: {}\n", labels.0));
+    output.push_str(&format!("New: {}\n\n", labels.1));
+
+    if changes.is_empty() {
+        output.push_str("No differences\n");
+    }
+
+    let mut section = "";
+    for change in changes {
+        let (head, field) = change.field.split_once('.').unwrap_or(("", &change.field));
+        if head != section {
+            if !section.is_empty() {
+                output.push('\n');
+            }
+            section = head;
+            let title = section_title(section);
+            output.push_str(&format!("{}\n{}\n", title, "-".repeat(title.len())));
+        }
+        let line = match (&change.old, &change.new) {
+            (Some(old), Some(new)) => format!("  ~ {}: {} -> {}", field, old, new),
+            (None, Some(new)) => format!("  + {}: {}", field, new),
+            (Some(old), None) => format!("  - {}: {}", field, old),
+            (None, None) => continue,
+        };
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+
+    for note in notes {
+        output.push_str(&format!("\nNote: {}\n", note));
+    }
+    output.push_str(&format!(
+        "\n{} change(s)\n================================================================================\n",
+        changes.len()
+    ));
+    output
+}
+
+fn format_json(labels: &(String, String), changes: &[Change], notes: &[String]) -> String {
+    let value = |v: &Option<String>| v.as_deref().map(json::quote).unwrap_or_else(|| "null".to_string());
+
+    let mut json = String::from("{\n");
+    json.push_str(&format!("  \"old\": {},\n", json::quote(&labels.0)));
+    json.push_str(&format!("  \"new\": {},\n", json::quote(&labels.1)));
+    json.push_str("  \"changes\": [\n");
+    for (i, change) in changes.iter().enumerate() {
+        json.push_str(&format!(
+            "    {{\"field\": {}, \"old\": {}, \"new\": {}}}{}\n",
+            json::quote(&change.field),
+            value(&change.old),
+            value(&change.new),
+            if i + 1 < changes.len() { "," } else { "" }
+        ));
+    }
+    json.push_str("  ],\n");
+    let notes: Vec<String> = notes.iter().map(|note| json::quote(note)).collect();
+    json.push_str(&format!("  \"notes\": [{}]\n", notes.join(", ")));
+    json.push('}');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = r#"{
+      "timestamp": "2026-10-15T09:00:00Z",
+      "tpu": {"type": "v5e", "chip_count": 4, "driver_version": "1.2.0"},
+      "software": {
+        "jax_version": "0.4.30",
+        "env_vars": {"XLA_FLAGS": "--xla_dump_to=/tmp", "TPU_NAME": "tpu-a"},
+        "packages": {"pip": ["jax==0.4.30", "flax==0.8.0", "-e git+https://example.com/m.git#egg=m"], "conda": null}
+      },
+      "system": {"kernel_version": "5.19.0-1022-gcp"}
+    }"#;
+
+    const NEW: &str = r#"{
+      "timestamp": "2026-10-16T09:00:00Z",
+      "tpu": {"type": "v5e", "chip_count": 4, "driver_version": "1.3.0"},
+      "software": {
+        "jax_version": "0.4.35",
+        "env_vars": {"TPU_NAME": "tpu-a", "JAX_PLATFORMS": "tpu"},
+        "packages": {"pip": ["JAX==0.4.35", "-e git+https://example.com/m.git#egg=m", "optax==0.2.3"], "conda": null}
+      },
+      "system": {"kernel_version": "6.5.0-1017-gcp"}
+    }"#;
+
+    fn change(field: &str, old: Option<&str>, new: Option<&str>) -> Change {
+        Change {
+            field: field.to_string(),
+            old: old.map(str::to_string),
+            new: new.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_diff_snapshots() {
+        let (changes, notes) = diff_snapshots(&json::parse(OLD).unwrap(), &json::parse(NEW).unwrap());
+        assert_eq!(
+            changes,
+            vec![
+                change("tpu.driver_version", Some("1.2.0"), Some("1.3.0")),
+                change("software.jax_version", Some("0.4.30"), Some("0.4.35")),
+                change("software.env_vars.XLA_FLAGS", Some("--xla_dump_to=/tmp"), None),
+                change("software.packages.pip.jax", Some("0.4.30"), Some("0.4.35")),
+                change("software.packages.pip.flax", Some("0.8.0"), None),
+                change("software.env_vars.JAX_PLATFORMS", None, Some("tpu")),
+                change("software.packages.pip.optax", None, Some("0.2.3")),
+                change("system.kernel_version", Some("5.19.0-1022-gcp"), Some("6.5.0-1017-gcp")),
+            ]
+        );
+        assert!(notes.is_empty());
+
+        let text = format_text(&("old.json".to_string(), "new.json".to_string()), &changes, &notes);
+        assert!(text.contains("TPU\n---\n  ~ driver_version: 1.2.0 -> 1.3.0\n"));
+        assert!(text.contains("  - env_vars.XLA_FLAGS: --xla_dump_to=/tmp\n"));
+        assert!(text.contains("  + packages.pip.optax: 0.2.3\n\nSYSTEM\n"));
+        assert_eq!(text.matches("SOFTWARE\n").count(), 1);
+        assert!(text.contains("\n8 change(s)\n"));
+    }
+
+    #[test]
+    fn test_packages_compared_only_when_both_recorded() {
+        let new = NEW.replace(
+            r#""packages": {"pip": ["JAX==0.4.35", "-e git+https://example.com/m.git#egg=m", "optax==0.2.3"], "conda": null}"#,
+            r#""packages": null"#,
+        );
+        let (changes, notes) = diff_snapshots(&json::parse(OLD).unwrap(), &json::parse(&new).unwrap());
+        assert!(changes.iter().all(|c| !c.field.starts_with(PACKAGES_PREFIX)));
+        assert_eq!(notes.len(), 1);
+        assert!(notes[0].contains("the new snapshot has no inventory"));
+
+        let (changes, _) = diff_snapshots(&json::parse(OLD).unwrap(), &json::parse(OLD).unwrap());
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_split_package() {
+        assert_eq!(split_package("pip", "Flax==0.8.0"), ("flax".to_string(), "0.8.0".to_string()));
+        assert_eq!(
+            split_package("pip", "model @ file:///src/model"),
+            ("model".to_string(), "file:///src/model".to_string())
+        );
+        assert_eq!(
+            split_package("conda", "numpy=1.26.4=py311h64a7726_0"),
+            ("numpy".to_string(), "1.26.4=py311h64a7726_0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_json() {
+        let json = format_json(
+            &("old.json".to_string(), "current environment".to_string()),
+            &[change("software.jax_version", Some("0.4.30"), None)],
+            &[],
+        );
+        let parsed = json::parse(&json).unwrap();
+        let first = &parsed.get("changes").unwrap().as_array().unwrap()[0];
+        assert_eq!(first.get("field").and_then(JsonValue::as_str), Some("software.jax_version"));
+        assert!(first.get("new").unwrap().is_null());
+    }
+}
@@ -0,0 +1,190 @@
+//! Known-issues command
+//!
+//! Looks up the installed jax, jaxlib, libtpu, and TPU driver versions in
+//! the known-issues database and prints the bugs that apply, with
+//! workarounds and the release that fixes each one.
+
+use crate::cli::args::{Args, OutputFormat};
+use crate::commands::info;
+use crate::data::known_issues::{KnownIssue, KnownIssueDb};
+use crate::platform::tpu;
+use crate::TpuDocError;
+use crate::util::json;
+
+/// A detected package version and the issues that apply to it
+#[derive(Debug)]
+pub struct PackageIssues<'a> {
+    pub package: String,
+    pub version: Option<String>,
+    pub issues: Vec<&'a KnownIssue>,
+}
+
+/// Run the known-issues command
+pub fn run(args: &Args) -> Result<String, TpuDocError> {
+    let db = KnownIssueDb::load();
+    let versions = vec![
+        ("jax", info::detect_jax_version()),
+        ("jaxlib", info::detect_jaxlib_version()),
+        ("libtpu", info::detect_libtpu_version()),
+        ("driver", tpu::get_driver_version().ok()),
+    ];
+    let packages = lookup(&db, versions);
+
+    match args.format {
+        OutputFormat::Json => Ok(format_json(&db, &packages)),
+        _ => Ok(format_text(&db, &packages)),
+    }
+}
+
+/// Find the issues affecting each detected version
+pub fn lookup<'a>(db: &'a KnownIssueDb, versions: Vec<(&str, Option<String>)>) -> Vec<PackageIssues<'a>> {
+    versions
+        .into_iter()
+        .map(|(package, version)| PackageIssues {
+            package: package.to_string(),
+            issues: version.as_deref().map(|v| db.find(package, v)).unwrap_or_default(),
+            version,
+        })
+        .collect()
+}
+
+fn format_text(db: &KnownIssueDb, packages: &[PackageIssues]) -> String {
+    let mut output = String::new();
+    output.push_str("================================================================================\n");
+    output.push_str("                              KNOWN ISSUES\n");
+    output.push_str("================================================================================\n\n");
+
+    output.push_str("DETECTED VERSIONS\n");
+    output.push_str("-----------------\n");
+    for p in packages {
+        output.push_str(&format!(
+            "  {:12} {}\n",
+            p.package,
+            p.version.as_deref().unwrap_or("Not found")
+        ));
+    }
+    output.push('\n');
+
+    let total: usize = packages.iter().map(|p| p.issues.len()).sum();
+    output.push_str(&format!(
+        "Known issues: {} applicable ({} entries in database v{})\n\n",
+        total,
+        db.issues.len(),
+        db.version
+    ));
+
+    if total == 0 {
+        output.push_str("No known issues for the detected versions.\n");
+        return output;
+    }
+
+    for p in packages {
+        for issue in &p.issues {
+            output.push_str("--------------------------------------------------------------------------------\n");
+            output.push_str(&format!(
+                "[{}] {} ({} {})\n",
+                issue.id,
+                issue.title,
+                p.package,
+                p.version.as_deref().unwrap_or("")
+            ));
+            output.push_str("--------------------------------------------------------------------------------\n");
+            output.push_str(&format!("{}\n\n", issue.description));
+            output.push_str(&format!("Workaround: {}\n", issue.workaround));
+            output.push_str(&format!(
+                "Fixed in:   {}\n\n",
+                issue
+                    .fixed_in
+                    .as_deref()
+                    .map(|v| format!("{} {}", issue.package, v))
+                    .unwrap_or_else(|| "not yet fixed".to_string())
+            ));
+        }
+    }
+
+    output.push_str("================================================================================\n");
+    output
+}
+
+fn format_json(db: &KnownIssueDb, packages: &[PackageIssues]) -> String {
+    let optional = |v: Option<&str>| v.map(json::quote).unwrap_or_else(|| "null".to_string());
+
+    let mut json = String::from("{\n");
+    json.push_str(&format!("  \"database_version\": {},\n", json::quote(&db.version)));
+
+    json.push_str("  \"versions\": [\n");
+    for (i, p) in packages.iter().enumerate() {
+        json.push_str(&format!(
+            "    {{\"package\": {}, \"version\": {}}}",
+            json::quote(&p.package),
+            optional(p.version.as_deref())
+        ));
+        json.push_str(if i + 1 < packages.len() { ",\n" } else { "\n" });
+    }
+    json.push_str("  ],\n");
+
+    let issues: Vec<(&PackageIssues, &&KnownIssue)> = packages
+        .iter()
+        .flat_map(|p| p.issues.iter().map(move |issue| (p, issue)))
+        .collect();
+    json.push_str("  \"issues\": [\n");
+    for (i, (p, issue)) in issues.iter().enumerate() {
+        json.push_str("    {\n");
+        json.push_str(&format!("      \"id\": {},\n", json::quote(&issue.id)));
+        json.push_str(&format!("      \"package\": {},\n", json::quote(&issue.package)));
+        json.push_str(&format!(
+            "      \"installed_version\": {},\n",
+            optional(p.version.as_deref())
+        ));
+        json.push_str(&format!("      \"title\": \"{}\",\n", json::escape(&issue.title)));
+        json.push_str(&format!(
+            "      \"description\": \"{}\",\n",
+            json::escape(&issue.description)
+        ));
+        json.push_str(&format!(
+            "      \"workaround\": \"{}\",\n",
+            json::escape(&issue.workaround)
+        ));
+        json.push_str(&format!("      \"introduced\": {},\n", json::quote(&issue.introduced)));
+        json.push_str(&format!(
+            "      \"fixed_in\": {}\n",
+            optional(issue.fixed_in.as_deref())
+        ));
+        json.push_str("    }");
+        json.push_str(if i + 1 < issues.len() { ",\n" } else { "\n" });
+    }
+    json.push_str("  ]\n");
+    json.push('}');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_and_format() {
+        let db = KnownIssueDb::load();
+        let packages = lookup(
+            &db,
+            vec![
+                ("jax", Some("0.4.32".to_string())),
+                ("jaxlib", Some("0.4.35".to_string())),
+                ("driver", None),
+            ],
+        );
+
+        assert_eq!(packages[0].issues.len(), 1);
+        assert_eq!(packages[0].issues[0].id, "KI-002");
+        assert!(packages[1].issues.is_empty());
+        assert!(packages[2].issues.is_empty());
+
+        let text = format_text(&db, &packages);
+        assert!(text.contains("[KI-002]"));
+        assert!(text.contains("Fixed in:   jax 0.4.33"));
+
+        let json = format_json(&db, &packages);
+        assert!(json.contains("\"installed_version\": \"0.4.32\""));
+        assert!(json.contains("{\"package\": \"driver\", \"version\": null}"));
+    }
+}
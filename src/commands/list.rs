@@ -0,0 +1,219 @@
+//! List command
+//!
+//! Prints the registered checks, built-in and from the configuration file
+//! and plugin directories, with what the registry knows about each: its
+//! dependencies, estimated duration, severity, tags, and, when known, its
+//! owner and documentation and runbook links. `--category` and `--tag`
+//! narrow the list, so a user can see what a filtered run would include
+//! before starting it.
+
+use crate::cli::args::{Args, OutputFormat};
+use crate::cli::select::format_estimate;
+use crate::engine::orchestrator::{create_all_checks, RegisteredCheck};
+use crate::engine::ownership::Ownership;
+use crate::util::json;
+use crate::{CheckCategory, CheckTag, TpuDocConfig, TpuDocError};
+
+/// Section headers in display order
+const SECTIONS: [(&str, CheckCategory); 6] = [
+    ("HARDWARE CHECKS", CheckCategory::Hardware),
+    ("STACK CHECKS", CheckCategory::Stack),
+    ("PERFORMANCE CHECKS", CheckCategory::Performance),
+    ("I/O CHECKS", CheckCategory::Io),
+    ("SECURITY CHECKS", CheckCategory::Security),
+    ("CONFIGURATION AUDIT CHECKS", CheckCategory::Config),
+];
+
+/// Run the list command over the built-in checks and `extra` checks, with
+/// `ownership` from the configuration file laid over them
+pub fn run(args: &Args, extra: Vec<RegisteredCheck>, ownership: &Ownership) -> Result<String, TpuDocError> {
+    let mut checks = create_all_checks();
+    checks.extend(extra);
+    ownership.apply_to_checks(&mut checks);
+
+    let config = TpuDocConfig::from_args(args);
+    let listed: Vec<&RegisteredCheck> = checks
+        .iter()
+        .filter(|check| config.selects(&check.id, &check.category))
+        .filter(|check| args.tags.iter().all(|tag| has_tag(check, *tag)))
+        .collect();
+
+    match args.format {
+        OutputFormat::Json => Ok(format_json(&listed)),
+        _ => Ok(format_text(&listed)),
+    }
+}
+
+/// Whether `check` has `tag`; untagged checks count as CPU-bound, as they
+/// do for the scheduler
+fn has_tag(check: &RegisteredCheck, tag: CheckTag) -> bool {
+    check.tags.contains(&tag) || (tag == CheckTag::CpuBound && check.tags.is_empty())
+}
+
+fn format_text(checks: &[&RegisteredCheck]) -> String {
+    if checks.is_empty() {
+        return "No checks match the filters.".to_string();
+    }
+
+    let mut output = String::from("Available checks:\n");
+    for (header, category) in SECTIONS {
+        let section: Vec<&&RegisteredCheck> = checks.iter().filter(|c| c.category == category).collect();
+        if section.is_empty() {
+            continue;
+        }
+        output.push_str(&format!("\n{}:\n", header));
+        output.push_str(&format!(
+            "  {:8} {:34} {:>6}  {:8}  {:24} {}\n",
+            "ID", "NAME", "EST", "SEVERITY", "TAGS", "DEPENDS ON"
+        ));
+        for check in section {
+            output.push_str(&format!(
+                "  {:8} {:34} {:>6}  {:8}  {:24} {}\n",
+                check.id,
+                check.name,
+                format_estimate(check.estimated_duration_ms),
+                check.severity.to_string(),
+                or_dash(check.tags.iter().map(|t| t.to_string()).collect()),
+                or_dash(check.dependencies.clone()),
+            ));
+            for (key, value) in check.ownership.entries() {
+                output.push_str(&format!("  {:8} {}: {}\n", "", key, value));
+            }
+        }
+    }
+
+    let total_ms: u64 = checks.iter().map(|c| c.estimated_duration_ms).sum();
+    output.push_str(&format!(
+        "\n{} checks, ~{} estimated when run one after another",
+        checks.len(),
+        format_estimate(total_ms)
+    ));
+    output
+}
+
+fn or_dash(items: Vec<String>) -> String {
+    if items.is_empty() {
+        "-".to_string()
+    } else {
+        items.join(",")
+    }
+}
+
+fn format_json(checks: &[&RegisteredCheck]) -> String {
+    let strings = |items: Vec<String>| {
+        items
+            .iter()
+            .map(|item| json::quote(item))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut json = String::from("{\n  \"checks\": [\n");
+    for (i, check) in checks.iter().enumerate() {
+        json.push_str("    {\n");
+        json.push_str(&format!("      \"id\": \"{}\",\n", json::escape(&check.id)));
+        json.push_str(&format!("      \"name\": \"{}\",\n", json::escape(&check.name)));
+        json.push_str(&format!("      \"category\": \"{:?}\",\n", check.category));
+        json.push_str(&format!("      \"description\": \"{}\",\n", json::escape(&check.description)));
+        json.push_str(&format!("      \"severity\": \"{}\",\n", check.severity));
+        json.push_str(&format!(
+            "      \"tags\": [{}],\n",
+            strings(check.tags.iter().map(|t| t.to_string()).collect())
+        ));
+        json.push_str(&format!("      \"dependencies\": [{}],\n", strings(check.dependencies.clone())));
+        for (key, value) in [
+            ("owner", &check.ownership.owner),
+            ("docs_url", &check.ownership.docs_url),
+            ("runbook_url", &check.ownership.runbook_url),
+        ] {
+            let value = value.as_deref().map_or("null".to_string(), json::quote);
+            json.push_str(&format!("      \"{}\": {},\n", key, value));
+        }
+        json.push_str(&format!("      \"estimated_duration_ms\": {}\n", check.estimated_duration_ms));
+        json.push_str("    }");
+        json.push_str(if i + 1 < checks.len() { ",\n" } else { "\n" });
+    }
+    json.push_str("  ]\n}");
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(flags: &[&str]) -> Args {
+        let mut all = vec!["list".to_string()];
+        all.extend(flags.iter().map(|f| f.to_string()));
+        Args::parse_from(&all).unwrap()
+    }
+
+    #[test]
+    fn test_list_from_registry() {
+        let text = run(&args(&[]), Vec::new(), &Ownership::default()).unwrap();
+        assert!(text.starts_with("Available checks:\n\nHARDWARE CHECKS:\n  ID "));
+        let hw002 = text.lines().find(|l| l.trim_start().starts_with("HW-002")).unwrap();
+        assert!(hw002.contains("critical") && hw002.ends_with("HW-001"));
+        let perf004 = text.lines().find(|l| l.trim_start().starts_with("PERF-004")).unwrap();
+        assert!(perf004.contains("tpu-exclusive,cacheable"));
+        assert!(text.contains("\n49 checks, ~"));
+    }
+
+    #[test]
+    fn test_list_filters() {
+        let text = run(&args(&["--category", "io", "--tag", "network"]), Vec::new(), &Ownership::default()).unwrap();
+        assert!(text.contains("I/O CHECKS:"));
+        assert!(!text.contains("HARDWARE CHECKS:"));
+        assert!(text.contains("IO-003") && !text.contains("IO-002 "));
+
+        // Untagged extra checks count as CPU-bound
+        let extra = RegisteredCheck::new("ORG-001", "Org check", CheckCategory::Config, || {
+            crate::CheckResult::Skip { reason: String::new() }
+        });
+        let text = run(&args(&["--category=config", "--tag=cpu"]), vec![extra], &Ownership::default()).unwrap();
+        assert!(text.contains("ORG-001"));
+        assert_eq!(
+            run(&args(&["--tag", "tpu-exclusive", "--security"]), Vec::new(), &Ownership::default()).unwrap(),
+            "No checks match the filters."
+        );
+
+        let hardware = args(&["--format", "json", "--category", "hardware"]);
+        let json = run(&hardware, Vec::new(), &Ownership::default()).unwrap();
+        assert!(json.contains("\"id\": \"HW-002\""));
+        assert!(json.contains("\"dependencies\": [\"HW-001\"]"));
+        assert!(json.contains("\"severity\": \"critical\""));
+        assert!(json.contains("\"owner\": null"));
+        assert!(!json.contains("STK-001"));
+    }
+
+    #[test]
+    fn test_list_ownership() {
+        let extra = RegisteredCheck::new("ORG-001", "Org check", CheckCategory::Config, || {
+            crate::CheckResult::Skip { reason: String::new() }
+        })
+        .with_owner("platform-team")
+        .with_docs_url("https://docs.example.com/org-001");
+        let ownership = Ownership {
+            entries: vec![(
+                crate::engine::policy::PolicyTarget::Check("ORG-001".to_string()),
+                crate::CheckOwnership {
+                    runbook_url: Some("https://runbooks.example.com/org-001".to_string()),
+                    ..Default::default()
+                },
+            )],
+        };
+
+        let text = run(&args(&["--category", "config"]), vec![extra], &ownership).unwrap();
+        let lines: Vec<&str> = text.lines().skip_while(|l| !l.trim_start().starts_with("ORG-001")).collect();
+        assert_eq!(lines[1].trim(), "owner: platform-team");
+        assert_eq!(lines[2].trim(), "docs_url: https://docs.example.com/org-001");
+        assert_eq!(lines[3].trim(), "runbook_url: https://runbooks.example.com/org-001");
+
+        let extra = RegisteredCheck::new("ORG-001", "Org check", CheckCategory::Config, || {
+            crate::CheckResult::Skip { reason: String::new() }
+        })
+        .with_owner("platform-team");
+        let json = run(&args(&["--format=json", "--category=config"]), vec![extra], &ownership).unwrap();
+        assert!(json.contains("\"owner\": \"platform-team\",\n      \"docs_url\": null,"));
+        assert!(json.contains("\"runbook_url\": \"https://runbooks.example.com/org-001\","));
+    }
+}
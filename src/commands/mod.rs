@@ -1,16 +1,26 @@
 //! Command handlers for tpu-doc
 //!
 //! This module contains implementations for all tpu-doc commands:
-//! - `info`: Display complete environment information
+//! - `info`: Display complete environment information, or diff saved fingerprints (`info_diff`)
 //! - `stack`: Analyze software stack compatibility
 //! - `cache`: Analyze XLA compilation cache
 //! - `snapshot`: Capture resource utilization snapshot
 //! - `audit`: Run configuration audit
-//! - `analyze`: AI-powered log analysis (requires --ai flag)
+//! - `analyze`: Log analysis (offline signature matching, or AI with --ai)
+//! - `known-issues`: Known bugs for the installed jax/jaxlib/libtpu/driver versions
+//! - `list`: Registered checks with their dependencies, durations, severity, and tags
+//! - `tui`: Live dashboard of a check run ("tui" feature)
+//! - `verify`: Check the signature of a signed JSON report
 
 pub mod analyze;
 pub mod audit;
 pub mod cache;
 pub mod info;
+pub mod info_diff;
+pub mod known_issues;
+pub mod list;
 pub mod snapshot;
 pub mod stack;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod verify;
@@ -1,13 +1,248 @@
 //! Resource snapshot command
 //!
-//! Captures a point-in-time view of resource utilization.
+//! Captures a point-in-time view of resource utilization, refreshes it in
+//! place like `top` with `--continuous`, or samples it over a `--duration`
+//! window as JSONL for later graphing.
+//!
+//! Snapshots are compared against alert thresholds (HBM, chip temperature,
+//! host memory; see [`DEFAULT_ALERT_THRESHOLDS`], overridden with
+//! `--alert`), and crossed thresholds are reported as WARN or CRIT lines.
+//! With `--once` a single snapshot is taken and the exit code reflects the
+//! worst alert, so cron jobs can use it as a simple monitor.
 
 use crate::cli::args::{Args, OutputFormat};
+use crate::cli::terminal::LiveTerminal;
 use crate::platform::tpu;
+use crate::util;
 use crate::TpuDocError;
 use std::fs;
+use std::io::{IsTerminal, Write};
+use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Default seconds between samples when `--duration` is given without `--interval`
+const DEFAULT_SAMPLE_INTERVAL_SECS: u64 = 5;
+
+/// How often the live view polls the keyboard while waiting to refresh
+const KEY_POLL_INTERVAL_MS: u64 = 100;
+
+/// Width of the utilization bars in the live view
+const BAR_WIDTH: usize = 20;
+
+/// Processes listed under the per-chip table in the live view
+const LIVE_TOP_PROCESSES: usize = 5;
+
+/// A resource alert thresholds apply to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlertMetric {
+    /// HBM utilization per chip, in percent
+    Hbm,
+    /// Chip temperature, in degrees Celsius
+    Temperature,
+    /// Host memory utilization, in percent
+    HostMemory,
+}
+
+impl AlertMetric {
+    /// Name used in `--alert` and in alert lines
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertMetric::Hbm => "hbm",
+            AlertMetric::Temperature => "temp",
+            AlertMetric::HostMemory => "mem",
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self {
+            AlertMetric::Temperature => "C",
+            _ => "%",
+        }
+    }
+}
+
+/// When a metric raises a WARN or CRIT alert
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlertThreshold {
+    pub metric: AlertMetric,
+    /// Values above this raise a WARN alert
+    pub warn: f64,
+    /// Values above this raise a CRIT alert
+    pub crit: f64,
+}
+
+/// Thresholds used for metrics `--alert` does not set
+pub const DEFAULT_ALERT_THRESHOLDS: [AlertThreshold; 3] = [
+    AlertThreshold {
+        metric: AlertMetric::Hbm,
+        warn: 95.0,
+        crit: 99.0,
+    },
+    AlertThreshold {
+        metric: AlertMetric::Temperature,
+        warn: 85.0,
+        crit: 90.0,
+    },
+    AlertThreshold {
+        metric: AlertMetric::HostMemory,
+        warn: 90.0,
+        crit: 97.0,
+    },
+];
+
+impl AlertThreshold {
+    /// Parse `metric=warn[:crit]` (e.g. `hbm=90`, `temp=80:88`); without a
+    /// critical level the default one is kept, raised to `warn` if needed
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "Invalid alert threshold: '{}'. Expected metric=warn[:crit] with metric hbm, temp, or mem (e.g. hbm=95:99)",
+                s
+            )
+        };
+        let (name, levels) = s.split_once('=').ok_or_else(invalid)?;
+        let name = name.trim().to_lowercase();
+        let default = DEFAULT_ALERT_THRESHOLDS
+            .iter()
+            .find(|t| t.metric.name() == name)
+            .ok_or_else(invalid)?;
+        let level = |v: &str| v.trim().trim_end_matches(['%', 'C']).parse::<f64>().map_err(|_| invalid());
+        let (warn, crit) = match levels.split_once(':') {
+            Some((warn, crit)) => (level(warn)?, level(crit)?),
+            None => {
+                let warn = level(levels)?;
+                (warn, default.crit.max(warn))
+            }
+        };
+        if crit < warn {
+            return Err(format!(
+                "Invalid alert threshold: '{}'. The critical level is below the warning level",
+                s
+            ));
+        }
+        Ok(AlertThreshold {
+            metric: default.metric,
+            warn,
+            crit,
+        })
+    }
+}
+
+/// The thresholds in effect: the last `--alert` for each metric, or its
+/// default
+pub fn alert_thresholds(overrides: &[AlertThreshold]) -> Vec<AlertThreshold> {
+    DEFAULT_ALERT_THRESHOLDS
+        .iter()
+        .map(|default| {
+            overrides
+                .iter()
+                .rev()
+                .find(|t| t.metric == default.metric)
+                .copied()
+                .unwrap_or(*default)
+        })
+        .collect()
+}
+
+/// How serious a crossed threshold is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlertLevel {
+    Warn,
+    Crit,
+}
+
+impl AlertLevel {
+    /// Exit code for `--once` when this is the worst alert, matching `check`:
+    /// 1 for critical, 2 for warnings
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            AlertLevel::Crit => 1,
+            AlertLevel::Warn => 2,
+        }
+    }
+}
+
+/// A threshold a snapshot crossed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub level: AlertLevel,
+    pub metric: AlertMetric,
+    /// What crossed it: `chip 2`, `host`, or `all chips` when only the
+    /// aggregate is known
+    pub subject: String,
+    pub value: f64,
+    pub threshold: f64,
+}
+
+impl std::fmt::Display for Alert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = match self.level {
+            AlertLevel::Warn => "WARN",
+            AlertLevel::Crit => "CRIT",
+        };
+        let unit = self.metric.unit();
+        write!(
+            f,
+            "{} {} {}: {:.1}{} > {}{}",
+            level,
+            self.metric.name(),
+            self.subject,
+            self.value,
+            unit,
+            self.threshold,
+            unit
+        )
+    }
+}
+
+/// The thresholds `snapshot` crosses, per chip where per-chip values are
+/// known
+pub fn evaluate_alerts(snapshot: &ResourceSnapshot, thresholds: &[AlertThreshold]) -> Vec<Alert> {
+    let per_chip = |value: fn(&ChipResources) -> Option<f64>, aggregate: Option<f64>| {
+        let chips: Vec<(String, f64)> = snapshot
+            .tpu
+            .chips
+            .iter()
+            .filter_map(|chip| value(chip).map(|v| (format!("chip {}", chip.chip), v)))
+            .collect();
+        if chips.is_empty() {
+            aggregate.map(|v| vec![("all chips".to_string(), v)]).unwrap_or_default()
+        } else {
+            chips
+        }
+    };
+
+    let mut alerts = Vec::new();
+    for threshold in thresholds {
+        let values = match threshold.metric {
+            AlertMetric::Hbm => per_chip(|c| c.hbm_utilization_percent, snapshot.tpu.hbm_utilization_percent),
+            AlertMetric::Temperature => per_chip(|c| c.temperature_c, snapshot.tpu.temperature_c),
+            AlertMetric::HostMemory if snapshot.system.memory_total_gb > 0.0 => {
+                vec![("host".to_string(), snapshot.system.memory_percent)]
+            }
+            AlertMetric::HostMemory => Vec::new(),
+        };
+
+        for (subject, value) in values {
+            let (level, limit) = if value > threshold.crit {
+                (AlertLevel::Crit, threshold.crit)
+            } else if value > threshold.warn {
+                (AlertLevel::Warn, threshold.warn)
+            } else {
+                continue;
+            };
+            alerts.push(Alert {
+                level,
+                metric: threshold.metric,
+                subject,
+                value,
+                threshold: limit,
+            });
+        }
+    }
+    alerts
+}
 
 /// Resource snapshot
 #[derive(Debug)]
@@ -24,6 +259,16 @@ pub struct TpuResources {
     pub hbm_utilization_percent: Option<f64>,
     pub duty_cycle_percent: Option<f64>,
     pub temperature_c: Option<f64>,
+    pub chips: Vec<ChipResources>,
+}
+
+/// Per-chip view used by the live `--continuous` display
+#[derive(Debug)]
+pub struct ChipResources {
+    pub chip: u32,
+    pub duty_cycle_percent: Option<f64>,
+    pub hbm_utilization_percent: Option<f64>,
+    pub temperature_c: Option<f64>,
 }
 
 #[derive(Debug)]
@@ -52,31 +297,73 @@ pub struct IoStats {
     pub net_tx_mb_s: f64,
 }
 
-/// Run the snapshot command
+/// Run the snapshot command; `--once` goes through [`run_once`] instead
 pub fn run(args: &Args) -> Result<String, TpuDocError> {
-    if args.continuous > 0 {
+    if let Some(duration_secs) = args.duration_secs {
+        run_sampling(args, duration_secs)
+    } else if args.continuous > 0 {
         run_continuous(args)
     } else {
+        run_once(args).map(|(output, _)| output)
+    }
+}
+
+/// Take one snapshot and report it with the alerts it raises, returning the
+/// worst alert level
+pub fn run_once(args: &Args) -> Result<(String, Option<AlertLevel>), TpuDocError> {
+    let snapshot = capture_snapshot();
+    let alerts = evaluate_alerts(&snapshot, &alert_thresholds(&args.alerts));
+    let worst = alerts.iter().map(|alert| alert.level).max();
+    let output = match args.format {
+        OutputFormat::Json => format_json(&snapshot, &alerts),
+        _ => format_text(&snapshot, &alerts),
+    };
+    Ok((output, worst))
+}
+
+fn run_continuous(args: &Args) -> Result<String, TpuDocError> {
+    if args.no_tty || !std::io::stdout().is_terminal() {
+        return run_continuous_plain(args);
+    }
+
+    let interval = Duration::from_secs(args.continuous as u64);
+    let thresholds = alert_thresholds(&args.alerts);
+    let terminal = LiveTerminal::enter();
+
+    loop {
         let snapshot = capture_snapshot();
-        match args.format {
-            OutputFormat::Json => Ok(format_json(&snapshot)),
-            _ => Ok(format_text(&snapshot)),
+        let alerts = evaluate_alerts(&snapshot, &thresholds);
+        print!(
+            "\x1B[H{}\x1B[J",
+            format_live(&snapshot, &alerts, args.continuous, terminal.keys_enabled())
+        );
+        let _ = std::io::stdout().flush();
+
+        // Wait out the interval in short slices so `q` is handled promptly
+        let deadline = Instant::now() + interval;
+        while Instant::now() < deadline {
+            if terminal.quit_requested() {
+                return Ok(String::new());
+            }
+            thread::sleep(Duration::from_millis(KEY_POLL_INTERVAL_MS));
         }
     }
 }
 
-fn run_continuous(args: &Args) -> Result<String, TpuDocError> {
+/// Scrolling output for `--no-tty` or when stdout is not a terminal
+fn run_continuous_plain(args: &Args) -> Result<String, TpuDocError> {
     let interval = Duration::from_secs(args.continuous as u64);
+    let thresholds = alert_thresholds(&args.alerts);
     let mut iteration = 0;
 
     loop {
-        // Clear screen (simple approach)
-        print!("\x1B[2J\x1B[1;1H");
-
         let snapshot = capture_snapshot();
-        let output = format_text(&snapshot);
-        println!("{}", output);
-        println!("\nRefreshing every {} seconds... (Ctrl+C to stop)", args.continuous);
+        let alerts = evaluate_alerts(&snapshot, &thresholds);
+        let mut stdout = std::io::stdout();
+        if writeln!(stdout, "{}", format_text(&snapshot, &alerts)).and_then(|_| stdout.flush()).is_err() {
+            // Reader went away (e.g. piped into `head`)
+            return Ok(String::new());
+        }
 
         iteration += 1;
         if iteration >= 1000 {
@@ -90,9 +377,63 @@ fn run_continuous(args: &Args) -> Result<String, TpuDocError> {
     Ok("Continuous monitoring stopped".to_string())
 }
 
+/// Sample resources every interval for the duration, one JSONL record per sample
+fn run_sampling(args: &Args, duration_secs: u64) -> Result<String, TpuDocError> {
+    let interval_secs = args.interval_secs.unwrap_or(DEFAULT_SAMPLE_INTERVAL_SECS).max(1);
+
+    let mut file = match args.output_file {
+        Some(ref path) => Some(
+            fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| TpuDocError::IoError {
+                    context: "snapshot --output".to_string(),
+                    message: format!("Cannot open '{}': {}", path, e),
+                    source: Some(Arc::new(e)),
+                })?,
+        ),
+        None => None,
+    };
+
+    let start = Instant::now();
+    let mut samples: u64 = 0;
+
+    loop {
+        let snapshot = capture_snapshot();
+        let record = format_jsonl_record(&snapshot, start.elapsed().as_secs_f64());
+
+        match file {
+            Some(ref mut f) => writeln!(f, "{}", record).map_err(|e| TpuDocError::io("snapshot --output", e))?,
+            None => {
+                println!("{}", record);
+                let _ = std::io::stdout().flush();
+            }
+        }
+        samples += 1;
+
+        let next_sample = Duration::from_secs(samples * interval_secs);
+        if next_sample > Duration::from_secs(duration_secs) {
+            break;
+        }
+        if let Some(wait) = next_sample.checked_sub(start.elapsed()) {
+            thread::sleep(wait);
+        }
+    }
+
+    match args.output_file {
+        Some(ref path) => Ok(format!(
+            "Captured {} samples over {}s (every {}s) to {}",
+            samples, duration_secs, interval_secs, path
+        )),
+        // Records were already streamed to stdout
+        None => Ok(String::new()),
+    }
+}
+
 fn capture_snapshot() -> ResourceSnapshot {
     // Get timestamp
-    let timestamp = get_timestamp();
+    let timestamp = util::time::now_rfc3339();
 
     // Capture TPU resources using function-based API
     let thermal_info = tpu::get_thermal_info().ok();
@@ -104,11 +445,22 @@ fn capture_snapshot() -> ResourceSnapshot {
         }
     });
 
-    // HBM utilization is not directly available without libtpu, use None
+    // HBM usage and duty cycle come from the libtpu metrics service, which
+    // is only up while a workload holds the TPU
+    let runtime_metrics = tpu::get_runtime_metrics().ok();
+    let chips = collect_chip_resources(
+        thermal_info.as_ref().map(|t| t.chip_temperatures.as_slice()).unwrap_or(&[]),
+        runtime_metrics.as_ref(),
+    );
     let tpu_resources = TpuResources {
-        hbm_utilization_percent: None, // Would need libtpu for actual values
-        duty_cycle_percent: None,      // Would need libtpu for actual values
+        hbm_utilization_percent: runtime_metrics
+            .as_ref()
+            .and_then(|m| m.hbm_utilization_percent()),
+        duty_cycle_percent: runtime_metrics
+            .as_ref()
+            .and_then(|m| m.mean_duty_cycle_percent()),
         temperature_c: avg_temp,
+        chips,
     };
 
     // Capture system resources
@@ -140,19 +492,30 @@ fn capture_snapshot() -> ResourceSnapshot {
     }
 }
 
-fn get_timestamp() -> String {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let duration = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default();
-
-    let secs = duration.as_secs();
-    let time_of_day = secs % 86400;
-    let hours = time_of_day / 3600;
-    let minutes = (time_of_day % 3600) / 60;
-    let seconds = time_of_day % 60;
-
-    format!("{:02}:{:02}:{:02} UTC", hours, minutes, seconds)
+/// Merge per-chip temperatures (indexed by position) with runtime metrics (keyed by chip)
+fn collect_chip_resources(
+    temperatures: &[f64],
+    metrics: Option<&tpu::RuntimeMetrics>,
+) -> Vec<ChipResources> {
+    let metric_chips = metrics.map(|m| m.chips.as_slice()).unwrap_or(&[]);
+    let count = metric_chips
+        .iter()
+        .map(|c| c.chip as usize + 1)
+        .max()
+        .unwrap_or(0)
+        .max(temperatures.len());
+
+    (0..count)
+        .map(|i| {
+            let chip_metrics = metric_chips.iter().find(|c| c.chip as usize == i);
+            ChipResources {
+                chip: i as u32,
+                duty_cycle_percent: chip_metrics.and_then(|c| c.duty_cycle_percent),
+                hbm_utilization_percent: chip_metrics.and_then(|c| c.hbm_utilization_percent()),
+                temperature_c: temperatures.get(i).copied(),
+            }
+        })
+        .collect()
 }
 
 fn get_memory_usage() -> (f64, f64) {
@@ -325,7 +688,114 @@ fn get_io_stats() -> IoStats {
     }
 }
 
-fn format_text(snapshot: &ResourceSnapshot) -> String {
+/// Render the top-like live view. Lines end with an erase-to-EOL so shorter
+/// values never leave stale characters from the previous frame.
+fn format_live(snapshot: &ResourceSnapshot, alerts: &[Alert], refresh_secs: u32, keys_enabled: bool) -> String {
+    let mut lines = Vec::new();
+    let quit_hint = if keys_enabled { "q to quit" } else { "Ctrl+C to quit" };
+
+    lines.push(format!(
+        "tpu-doc snapshot - {} - refresh {}s - {}",
+        snapshot.timestamp, refresh_secs, quit_hint
+    ));
+    lines.push(String::new());
+    if !alerts.is_empty() {
+        lines.extend(alerts.iter().map(|alert| alert.to_string()));
+        lines.push(String::new());
+    }
+
+    let sys = &snapshot.system;
+    lines.push(format!(
+        "HOST   CPU {} {:5.1}%   MEM {} {:5.1}% ({:.1}/{:.1} GB)",
+        bar(Some(sys.cpu_utilization_percent)),
+        sys.cpu_utilization_percent,
+        bar(Some(sys.memory_percent)),
+        sys.memory_percent,
+        sys.memory_used_gb,
+        sys.memory_total_gb
+    ));
+    lines.push(format!(
+        "       DISK R {:.1} MB/s  W {:.1} MB/s   NET RX {:.1} MB/s  TX {:.1} MB/s",
+        snapshot.io.disk_read_mb_s, snapshot.io.disk_write_mb_s, snapshot.io.net_rx_mb_s, snapshot.io.net_tx_mb_s
+    ));
+    lines.push(String::new());
+
+    lines.push(format!(
+        "{:<6} {:<width$} {:>6}  {:<width$} {:>6}  {:>7}",
+        "CHIP",
+        "DUTY CYCLE",
+        "",
+        "HBM",
+        "",
+        "TEMP",
+        width = BAR_WIDTH + 2
+    ));
+    if snapshot.tpu.chips.is_empty() {
+        lines.push("  No per-chip data (not on a TPU VM?)".to_string());
+    }
+    for chip in &snapshot.tpu.chips {
+        lines.push(format!(
+            "{:<6} {} {:>6}  {} {:>6}  {:>7}",
+            chip.chip,
+            bar(chip.duty_cycle_percent),
+            percent_or_na(chip.duty_cycle_percent),
+            bar(chip.hbm_utilization_percent),
+            percent_or_na(chip.hbm_utilization_percent),
+            chip.temperature_c
+                .map(|t| format!("{:.1}C", t))
+                .unwrap_or_else(|| "N/A".to_string())
+        ));
+    }
+    if !snapshot.tpu.chips.is_empty()
+        && snapshot.tpu.chips.iter().all(|c| c.duty_cycle_percent.is_none())
+    {
+        lines.push("  (duty cycle/HBM need a running workload exposing the libtpu metrics service)".to_string());
+    }
+    lines.push(String::new());
+
+    lines.push(format!("{:<8} {:<24} {:>10} {:>7}", "PID", "PROCESS", "MEM (MB)", "CPU %"));
+    for proc in snapshot.processes.iter().take(LIVE_TOP_PROCESSES) {
+        lines.push(format!(
+            "{:<8} {:<24} {:>10.1} {:>7.1}",
+            proc.pid,
+            truncate(&proc.name, 24),
+            proc.memory_mb,
+            proc.cpu_percent
+        ));
+    }
+
+    let mut output = String::new();
+    for line in lines {
+        output.push_str(&line);
+        output.push_str("\x1B[K\n");
+    }
+    output
+}
+
+fn bar(percent: Option<f64>) -> String {
+    let filled = percent
+        .map(|p| ((p.clamp(0.0, 100.0) / 100.0) * BAR_WIDTH as f64).round() as usize)
+        .unwrap_or(0);
+    format!("[{}{}]", "#".repeat(filled), " ".repeat(BAR_WIDTH - filled))
+}
+
+fn percent_or_na(value: Option<f64>) -> String {
+    value
+        .map(|v| format!("{:.1}%", v))
+        .unwrap_or_else(|| "N/A".to_string())
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let mut out: String = s.chars().take(max.saturating_sub(3)).collect();
+        out.push_str("...");
+        out
+    }
+}
+
+fn format_text(snapshot: &ResourceSnapshot, alerts: &[Alert]) -> String {
     let mut output = String::new();
 
     output.push_str("================================================================================\n");
@@ -333,6 +803,16 @@ fn format_text(snapshot: &ResourceSnapshot) -> String {
     output.push_str("================================================================================\n\n");
     output.push_str(&format!("Timestamp: {}\n\n", snapshot.timestamp));
 
+    // Alerts first, so they are the first thing a cron mail shows
+    if !alerts.is_empty() {
+        output.push_str("ALERTS\n");
+        output.push_str("------\n");
+        for alert in alerts {
+            output.push_str(&format!("  {}\n", alert));
+        }
+        output.push('\n');
+    }
+
     // TPU Resources
     output.push_str("TPU RESOURCES\n");
     output.push_str("-------------\n");
@@ -393,12 +873,27 @@ fn format_text(snapshot: &ResourceSnapshot) -> String {
     output
 }
 
-fn format_json(snapshot: &ResourceSnapshot) -> String {
+fn format_json(snapshot: &ResourceSnapshot, alerts: &[Alert]) -> String {
     let mut json = String::new();
     json.push_str("{\n");
 
     json.push_str(&format!("  \"timestamp\": \"{}\",\n", snapshot.timestamp));
 
+    // Alerts
+    json.push_str("  \"alerts\": [\n");
+    for (i, alert) in alerts.iter().enumerate() {
+        json.push_str(&format!(
+            "    {{\"level\": \"{}\", \"metric\": \"{}\", \"subject\": \"{}\", \"value\": {:.1}, \"threshold\": {}}}{}\n",
+            if alert.level == AlertLevel::Crit { "crit" } else { "warn" },
+            alert.metric.name(),
+            alert.subject,
+            alert.value,
+            alert.threshold,
+            if i + 1 < alerts.len() { "," } else { "" }
+        ));
+    }
+    json.push_str("  ],\n");
+
     // TPU
     json.push_str("  \"tpu\": {\n");
     json.push_str(&format!("    \"hbm_utilization_percent\": {},\n",
@@ -445,3 +940,112 @@ fn format_json(snapshot: &ResourceSnapshot) -> String {
     json.push_str("}\n");
     json
 }
+
+/// Format a snapshot as a single-line JSON record for JSONL output
+fn format_jsonl_record(snapshot: &ResourceSnapshot, elapsed_secs: f64) -> String {
+    let unix_ms = util::time::now_unix_ms();
+    let optional = |v: Option<f64>| v.map(|v| format!("{:.1}", v)).unwrap_or_else(|| "null".to_string());
+
+    format!(
+        "{{\"unix_ms\": {}, \"elapsed_secs\": {:.1}, \"cpu_utilization_percent\": {:.1}, \
+         \"memory_used_gb\": {:.2}, \"memory_total_gb\": {:.2}, \"memory_percent\": {:.1}, \
+         \"hbm_utilization_percent\": {}, \"duty_cycle_percent\": {}, \"temperature_c\": {}}}",
+        unix_ms,
+        elapsed_secs,
+        snapshot.system.cpu_utilization_percent,
+        snapshot.system.memory_used_gb,
+        snapshot.system.memory_total_gb,
+        snapshot.system.memory_percent,
+        optional(snapshot.tpu.hbm_utilization_percent),
+        optional(snapshot.tpu.duty_cycle_percent),
+        optional(snapshot.tpu.temperature_c)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(chips: Vec<(Option<f64>, Option<f64>)>, memory_percent: f64) -> ResourceSnapshot {
+        ResourceSnapshot {
+            timestamp: String::new(),
+            tpu: TpuResources {
+                hbm_utilization_percent: Some(97.0),
+                duty_cycle_percent: None,
+                temperature_c: Some(60.0),
+                chips: chips
+                    .into_iter()
+                    .enumerate()
+                    .map(|(chip, (hbm, temp))| ChipResources {
+                        chip: chip as u32,
+                        duty_cycle_percent: None,
+                        hbm_utilization_percent: hbm,
+                        temperature_c: temp,
+                    })
+                    .collect(),
+            },
+            system: SystemResources {
+                cpu_utilization_percent: 0.0,
+                memory_used_gb: 0.0,
+                memory_total_gb: 100.0,
+                memory_percent,
+                swap_used_gb: 0.0,
+                swap_total_gb: 0.0,
+            },
+            processes: Vec::new(),
+            io: IoStats {
+                disk_read_mb_s: 0.0,
+                disk_write_mb_s: 0.0,
+                net_rx_mb_s: 0.0,
+                net_tx_mb_s: 0.0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_evaluate_alerts() {
+        let thresholds = alert_thresholds(&[]);
+        let snap = snapshot(vec![(Some(50.0), Some(91.0)), (Some(96.1), Some(70.0))], 92.0);
+        let alerts: Vec<String> = evaluate_alerts(&snap, &thresholds).iter().map(|a| a.to_string()).collect();
+        assert_eq!(
+            alerts,
+            vec![
+                "WARN hbm chip 1: 96.1% > 95%",
+                "CRIT temp chip 0: 91.0C > 90C",
+                "WARN mem host: 92.0% > 90%"
+            ]
+        );
+
+        // Without per-chip values the aggregate is checked
+        let snap = snapshot(Vec::new(), 10.0);
+        let alerts = evaluate_alerts(&snap, &thresholds);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].subject, "all chips");
+        let raised = alert_thresholds(&[AlertThreshold::parse("hbm=98").unwrap()]);
+        assert!(evaluate_alerts(&snap, &raised).is_empty());
+
+        let text = format_text(&snap, &alerts);
+        assert!(text.contains("ALERTS\n------\n  WARN hbm all chips: 97.0% > 95%\n"));
+        let json = crate::util::json::parse(&format_json(&snap, &alerts)).unwrap();
+        assert_eq!(json.get("alerts").unwrap().as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_alert_thresholds() {
+        let hbm = AlertThreshold::parse("HBM=90%:98%").unwrap();
+        assert_eq!((hbm.metric, hbm.warn, hbm.crit), (AlertMetric::Hbm, 90.0, 98.0));
+        // A warning level above the default critical level raises both
+        let temp = AlertThreshold::parse("temp=92C").unwrap();
+        assert_eq!((temp.warn, temp.crit), (92.0, 92.0));
+        assert!(AlertThreshold::parse("hbm").is_err());
+        assert!(AlertThreshold::parse("hbm=high").is_err());
+
+        let thresholds = alert_thresholds(&[temp]);
+        assert_eq!(thresholds[0], DEFAULT_ALERT_THRESHOLDS[0]);
+        assert_eq!(thresholds[1], temp);
+
+        assert!(AlertLevel::Crit > AlertLevel::Warn);
+        assert_eq!(AlertLevel::Crit.exit_code(), 1);
+        assert_eq!(AlertLevel::Warn.exit_code(), 2);
+    }
+}
@@ -3,7 +3,8 @@
 //! Provides detailed software stack analysis and compatibility checking.
 
 use crate::cli::args::{Args, OutputFormat};
-use crate::data::compatibility::{CompatibilityMatrix, CompatibilityStatus};
+use crate::data::compatibility::{CompatibilityMatrix, CompatibilityStatus, MatrixSource};
+use crate::util::json;
 use crate::TpuDocError;
 use std::env;
 use std::process::Command;
@@ -23,6 +24,8 @@ pub struct StackAnalysis {
     pub compatibility_status: CompatibilityStatus,
     pub issues: Vec<StackIssue>,
     pub recommendations: Vec<String>,
+    /// Where the compatibility matrix used for the analysis came from
+    pub matrix_source: MatrixSource,
 }
 
 /// Issue found in the stack
@@ -42,15 +45,18 @@ pub enum IssueSeverity {
 
 /// Run the stack command
 pub fn run(args: &Args) -> Result<String, TpuDocError> {
-    let analysis = analyze_stack();
+    let (matrix, source) = match args.matrix_url {
+        Some(ref url) => CompatibilityMatrix::load_from_url(url),
+        None => (CompatibilityMatrix::embedded(), MatrixSource::Embedded),
+    };
 
     if args.show_matrix {
-        let matrix = CompatibilityMatrix::load();
         match args.format {
-            OutputFormat::Json => Ok(format_matrix_json(&matrix)),
-            _ => Ok(format_matrix_text(&matrix)),
+            OutputFormat::Json => Ok(format_matrix_json(&matrix, &source)),
+            _ => Ok(format_matrix_text(&matrix, &source)),
         }
     } else {
+        let analysis = analyze_stack(&matrix, source);
         match args.format {
             OutputFormat::Json => Ok(format_json(&analysis)),
             _ => Ok(format_text(&analysis, args.verbose)),
@@ -58,7 +64,7 @@ pub fn run(args: &Args) -> Result<String, TpuDocError> {
     }
 }
 
-fn analyze_stack() -> StackAnalysis {
+fn analyze_stack(matrix: &CompatibilityMatrix, matrix_source: MatrixSource) -> StackAnalysis {
     let mut versions = Vec::new();
     let mut issues = Vec::new();
     let mut recommendations = Vec::new();
@@ -72,8 +78,8 @@ fn analyze_stack() -> StackAnalysis {
         if output.status.success() {
             let version = String::from_utf8_lossy(&output.stdout);
             let version = version.trim();
-            if version.starts_with("Python ") {
-                return Some((version[7..].to_string(), "python3 --version"));
+            if let Some(v) = version.strip_prefix("Python ") {
+                return Some((v.to_string(), "python3 --version"));
             }
         }
         None
@@ -124,8 +130,8 @@ fn analyze_stack() -> StackAnalysis {
         if output.status.success() {
             let output_str = String::from_utf8_lossy(&output.stdout);
             for line in output_str.lines() {
-                if line.starts_with("Version: ") {
-                    return Some((line[9..].to_string(), "pip show"));
+                if let Some(v) = line.strip_prefix("Version: ") {
+                    return Some((v.to_string(), "pip show"));
                 }
             }
         }
@@ -250,7 +256,6 @@ fn analyze_stack() -> StackAnalysis {
     }
 
     // Generate recommendations
-    let matrix = CompatibilityMatrix::load();
     if let Some(ref jax_ver) = jax_version.version {
         if let Some(recommended) = matrix.get_recommended_for_jax(jax_ver) {
             if jax_version.version.as_deref() != Some(&recommended.jax_version) {
@@ -282,6 +287,7 @@ fn analyze_stack() -> StackAnalysis {
         compatibility_status,
         issues,
         recommendations,
+        matrix_source,
     }
 }
 
@@ -344,7 +350,8 @@ fn format_text(analysis: &StackAnalysis, verbose: bool) -> String {
         CompatibilityStatus::Incompatible => "INCOMPATIBLE",
         CompatibilityStatus::Unknown => "UNKNOWN",
     };
-    output.push_str(&format!("Stack Status: {}\n\n", status_str));
+    output.push_str(&format!("Stack Status: {}\n", status_str));
+    output.push_str(&format!("Compatibility Matrix: {}\n\n", analysis.matrix_source.describe()));
 
     // Version table
     output.push_str("DETECTED VERSIONS\n");
@@ -398,15 +405,19 @@ fn format_json(analysis: &StackAnalysis) -> String {
 
     // Status
     json.push_str(&format!("  \"status\": \"{:?}\",\n", analysis.compatibility_status));
+    json.push_str(&format!(
+        "  \"matrix_source\": \"{}\",\n",
+        json::escape(&analysis.matrix_source.describe())
+    ));
 
     // Versions
     json.push_str("  \"versions\": [\n");
     for (i, v) in analysis.versions.iter().enumerate() {
         json.push_str("    {\n");
-        json.push_str(&format!("      \"package\": \"{}\",\n", v.package));
+        json.push_str(&format!("      \"package\": {},\n", json::quote(&v.package)));
         json.push_str(&format!("      \"version\": {},\n",
-            v.version.as_ref().map(|v| format!("\"{}\"", v)).unwrap_or_else(|| "null".to_string())));
-        json.push_str(&format!("      \"detection_method\": \"{}\"\n", v.detection_method));
+            v.version.as_deref().map(json::quote).unwrap_or_else(|| "null".to_string())));
+        json.push_str(&format!("      \"detection_method\": {}\n", json::quote(&v.detection_method)));
         json.push_str("    }");
         if i < analysis.versions.len() - 1 {
             json.push(',');
@@ -420,9 +431,9 @@ fn format_json(analysis: &StackAnalysis) -> String {
     for (i, issue) in analysis.issues.iter().enumerate() {
         json.push_str("    {\n");
         json.push_str(&format!("      \"severity\": \"{:?}\",\n", issue.severity));
-        json.push_str(&format!("      \"description\": \"{}\",\n", issue.description));
+        json.push_str(&format!("      \"description\": {},\n", json::quote(&issue.description)));
         json.push_str(&format!("      \"resolution\": {}\n",
-            issue.resolution.as_ref().map(|r| format!("\"{}\"", r)).unwrap_or_else(|| "null".to_string())));
+            issue.resolution.as_deref().map(json::quote).unwrap_or_else(|| "null".to_string())));
         json.push_str("    }");
         if i < analysis.issues.len() - 1 {
             json.push(',');
@@ -434,7 +445,7 @@ fn format_json(analysis: &StackAnalysis) -> String {
     // Recommendations
     json.push_str("  \"recommendations\": [\n");
     for (i, rec) in analysis.recommendations.iter().enumerate() {
-        json.push_str(&format!("    \"{}\"", rec));
+        json.push_str(&format!("    {}", json::quote(rec)));
         if i < analysis.recommendations.len() - 1 {
             json.push(',');
         }
@@ -446,13 +457,16 @@ fn format_json(analysis: &StackAnalysis) -> String {
     json
 }
 
-fn format_matrix_text(matrix: &CompatibilityMatrix) -> String {
+fn format_matrix_text(matrix: &CompatibilityMatrix, source: &MatrixSource) -> String {
     let mut output = String::new();
 
     output.push_str("================================================================================\n");
     output.push_str("                       VERSION COMPATIBILITY MATRIX\n");
     output.push_str("================================================================================\n\n");
 
+    output.push_str(&format!("Matrix Version: {} (updated {})\n", matrix.version, matrix.updated));
+    output.push_str(&format!("Source:         {}\n\n", source.describe()));
+
     output.push_str("JAX VERSIONS\n");
     output.push_str("------------\n");
     for entry in &matrix.jax_versions {
@@ -477,35 +491,59 @@ fn format_matrix_text(matrix: &CompatibilityMatrix) -> String {
         }
     }
 
+    if !matrix.driver_pairings.is_empty() {
+        output.push_str("\n\nINCOMPATIBLE DRIVER/LIBTPU PAIRINGS\n");
+        output.push_str("----------------------------------\n");
+        for pairing in &matrix.driver_pairings {
+            output.push_str(&format!("\n  Versions: {}\n", pairing.describe_ranges()));
+            output.push_str(&format!("  Issue:    {}\n", pairing.description));
+            output.push_str(&format!("  Fix:      {}\n", pairing.resolution));
+        }
+    }
+
+    if !matrix.pjrt_api_ranges.is_empty() {
+        output.push_str("\n\nPJRT C API VERSIONS\n");
+        output.push_str("-------------------\n\n");
+        output.push_str(&format!("  {:<20} {}\n", "jaxlib", "Plugin C API"));
+        for range in &matrix.pjrt_api_ranges {
+            let jaxlib = match &range.jaxlib_until {
+                Some(until) => format!(">={}, <{}", range.jaxlib_from, until),
+                None => format!(">={}", range.jaxlib_from),
+            };
+            output.push_str(&format!("  {:<20} {}\n", jaxlib, range.describe()));
+        }
+    }
+
     output.push_str("\n================================================================================\n");
 
     output
 }
 
-fn format_matrix_json(matrix: &CompatibilityMatrix) -> String {
+fn format_matrix_json(matrix: &CompatibilityMatrix, source: &MatrixSource) -> String {
     let mut json = String::new();
     json.push_str("{\n");
 
-    json.push_str(&format!("  \"version\": \"{}\",\n", matrix.version));
-    json.push_str(&format!("  \"updated\": \"{}\",\n", matrix.updated));
+    json.push_str(&format!("  \"version\": {},\n", json::quote(&matrix.version)));
+    json.push_str(&format!("  \"updated\": {},\n", json::quote(&matrix.updated)));
+    json.push_str(&format!("  \"source\": \"{}\",\n", json::escape(&source.describe())));
 
     json.push_str("  \"jax_versions\": [\n");
     for (i, entry) in matrix.jax_versions.iter().enumerate() {
         json.push_str("    {\n");
-        json.push_str(&format!("      \"version\": \"{}\",\n", entry.version));
-        json.push_str(&format!("      \"python_min\": \"{}\",\n", entry.python_min));
-        json.push_str(&format!("      \"python_max\": \"{}\",\n", entry.python_max));
-        json.push_str(&format!("      \"jaxlib_version\": \"{}\",\n", entry.jaxlib_version));
+        json.push_str(&format!("      \"version\": {},\n", json::quote(&entry.version)));
+        json.push_str(&format!("      \"python_min\": {},\n", json::quote(&entry.python_min)));
+        json.push_str(&format!("      \"python_max\": {},\n", json::quote(&entry.python_max)));
+        json.push_str(&format!("      \"jaxlib_version\": {},\n", json::quote(&entry.jaxlib_version)));
         json.push_str("      \"libtpu_versions\": [");
         for (j, ltv) in entry.libtpu_versions.iter().enumerate() {
-            json.push_str(&format!("\"{}\"", ltv));
+            json.push_str(&json::quote(ltv));
             if j < entry.libtpu_versions.len() - 1 {
                 json.push_str(", ");
             }
         }
         json.push_str("],\n");
         json.push_str(&format!("      \"notes\": {}\n",
-            entry.notes.as_ref().map(|n| format!("\"{}\"", n)).unwrap_or_else(|| "null".to_string())));
+            entry.notes.as_deref().map(json::quote).unwrap_or_else(|| "null".to_string())));
         json.push_str("    }");
         if i < matrix.jax_versions.len() - 1 {
             json.push(',');
@@ -519,22 +557,60 @@ fn format_matrix_json(matrix: &CompatibilityMatrix) -> String {
         json.push_str("    {\n");
         json.push_str("      \"packages\": [");
         for (j, pkg) in conflict.packages.iter().enumerate() {
-            json.push_str(&format!("\"{}\"", pkg));
+            json.push_str(&json::quote(pkg));
             if j < conflict.packages.len() - 1 {
                 json.push_str(", ");
             }
         }
         json.push_str("],\n");
-        json.push_str(&format!("      \"description\": \"{}\",\n", conflict.description));
-        json.push_str(&format!("      \"resolution\": \"{}\"\n", conflict.resolution));
+        json.push_str(&format!("      \"description\": {},\n", json::quote(&conflict.description)));
+        json.push_str(&format!("      \"resolution\": {}\n", json::quote(&conflict.resolution)));
         json.push_str("    }");
         if i < matrix.known_conflicts.len() - 1 {
             json.push(',');
         }
         json.push('\n');
     }
+    json.push_str("  ],\n");
+
+    let optional = |v: &Option<String>| v.as_deref().map_or("null".to_string(), json::quote);
+    json.push_str("  \"driver_pairings\": [\n");
+    for (i, pairing) in matrix.driver_pairings.iter().enumerate() {
+        json.push_str("    {\n");
+        json.push_str(&format!("      \"driver_from\": \"{}\",\n", json::escape(&pairing.driver_from)));
+        json.push_str(&format!("      \"driver_until\": {},\n", optional(&pairing.driver_until)));
+        json.push_str(&format!("      \"libtpu_from\": \"{}\",\n", json::escape(&pairing.libtpu_from)));
+        json.push_str(&format!("      \"libtpu_until\": {},\n", optional(&pairing.libtpu_until)));
+        json.push_str(&format!("      \"description\": \"{}\",\n", json::escape(&pairing.description)));
+        json.push_str(&format!("      \"resolution\": \"{}\"\n", json::escape(&pairing.resolution)));
+        json.push_str("    }");
+        if i < matrix.driver_pairings.len() - 1 {
+            json.push(',');
+        }
+        json.push('\n');
+    }
+    json.push_str("  ],\n");
+
+    json.push_str("  \"pjrt_api_ranges\": [\n");
+    for (i, range) in matrix.pjrt_api_ranges.iter().enumerate() {
+        json.push_str("    {\n");
+        json.push_str(&format!("      \"jaxlib_from\": \"{}\",\n", json::escape(&range.jaxlib_from)));
+        json.push_str(&format!("      \"jaxlib_until\": {},\n", optional(&range.jaxlib_until)));
+        json.push_str(&format!("      \"major\": {},\n", range.major));
+        json.push_str(&format!("      \"min_minor\": {},\n", range.min_minor));
+        json.push_str(&format!(
+            "      \"max_minor\": {}\n",
+            range.max_minor.map_or("null".to_string(), |max| max.to_string())
+        ));
+        json.push_str("    }");
+        if i < matrix.pjrt_api_ranges.len() - 1 {
+            json.push(',');
+        }
+        json.push('\n');
+    }
     json.push_str("  ]\n");
 
     json.push_str("}\n");
     json
 }
+
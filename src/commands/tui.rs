@@ -0,0 +1,338 @@
+//! Full-screen dashboard for `tpu-doc tui` (requires the "tui" feature).
+//!
+//! Runs the same checks as `check` on a background thread and redraws a
+//! dashboard as they start and finish: overall progress, a status table per
+//! category, the checks that are running, and a log of finished checks.
+//! Pressing `q` stops the run early; the partial report is still returned.
+//!
+//! When stdout is not a terminal, or with `--no-tty`, there is no dashboard:
+//! the log lines are written to stderr as checks finish instead.
+
+use crate::cli::args::Args;
+use crate::cli::output::StatusGlyphs;
+use crate::cli::terminal::{self, LiveTerminal};
+use crate::engine::orchestrator::{create_all_checks, CancellationToken, RegisteredCheck};
+use crate::engine::result::ValidationReport;
+use crate::{run_checks_wrapped, CheckCategory, CheckResult, TpuDocConfig, TpuDocError};
+use std::io::{IsTerminal, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the dashboard is redrawn while no check starts or finishes
+const REDRAW_INTERVAL_MS: u64 = 200;
+
+/// Progress bar width in characters
+const BAR_WIDTH: usize = 30;
+
+/// What the background run reports
+enum Event {
+    Started(String),
+    Finished(String, CheckResult),
+    Done(Result<ValidationReport, TpuDocError>),
+}
+
+/// Run the checks selected by `config`, and `extra` checks, under the dashboard
+pub fn run(args: &Args, mut config: TpuDocConfig, extra: Vec<RegisteredCheck>) -> Result<ValidationReport, TpuDocError> {
+    let planned: Vec<(String, String, CheckCategory)> = create_all_checks()
+        .iter()
+        .chain(&extra)
+        .filter(|check| config.selects(&check.id, &check.category))
+        .map(|check| (check.id.clone(), check.name.clone(), check.category.clone()))
+        .collect();
+    let mut dashboard = Dashboard::new(planned, args.glyphs.clone().unwrap_or_default());
+
+    let token = CancellationToken::new();
+    config.cancellation = Some(token.clone());
+    let (sender, events) = mpsc::channel();
+    let runner = thread::spawn(move || {
+        let report = run_checks_wrapped(config, extra, |check| observe(check, sender.clone()));
+        let _ = sender.send(Event::Done(report));
+    });
+
+    let report = if !args.no_tty && std::io::stdout().is_terminal() {
+        run_live(&mut dashboard, &events, &token)
+    } else {
+        run_plain(&mut dashboard, &events)
+    };
+    let _ = runner.join();
+    report
+}
+
+/// `check` reporting its start and result on the observer channel
+fn observe(mut check: RegisteredCheck, sender: Sender<Event>) -> RegisteredCheck {
+    let id = check.id.clone();
+    let run = check.check_fn;
+    check.check_fn = Box::new(move || {
+        let _ = sender.send(Event::Started(id.clone()));
+        let result = run();
+        let _ = sender.send(Event::Finished(id.clone(), result.clone()));
+        result
+    });
+    check
+}
+
+/// Redraw the dashboard until the run finishes or the user quits
+fn run_live(
+    dashboard: &mut Dashboard,
+    events: &Receiver<Event>,
+    token: &CancellationToken,
+) -> Result<ValidationReport, TpuDocError> {
+    let screen = LiveTerminal::enter();
+    let mut stdout = std::io::stdout();
+
+    loop {
+        let width = terminal::width().unwrap_or(80);
+        let height = terminal::height().unwrap_or(24);
+        let _ = write!(stdout, "\x1B[H{}\x1B[J", dashboard.render(width, height, screen.keys_enabled()));
+        let _ = stdout.flush();
+
+        if screen.quit_requested() && !token.is_cancelled() {
+            token.cancel();
+            dashboard.log.push("Stopping after the running checks finish...".to_string());
+        }
+
+        match events.recv_timeout(Duration::from_millis(REDRAW_INTERVAL_MS)) {
+            Ok(Event::Done(report)) => return report,
+            Ok(event) => dashboard.apply(event),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return Err(runner_gone()),
+        }
+    }
+}
+
+/// Log lines on stderr, one per finished check
+fn run_plain(dashboard: &mut Dashboard, events: &Receiver<Event>) -> Result<ValidationReport, TpuDocError> {
+    loop {
+        match events.recv() {
+            Ok(Event::Done(report)) => return report,
+            Ok(event) => {
+                let logged = dashboard.log.len();
+                dashboard.apply(event);
+                for line in &dashboard.log[logged..] {
+                    eprintln!("{}", line);
+                }
+            }
+            Err(_) => return Err(runner_gone()),
+        }
+    }
+}
+
+fn runner_gone() -> TpuDocError {
+    TpuDocError::CommandError {
+        command: "tui".to_string(),
+        message: "the check runner stopped without a report".to_string(),
+        source: None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum State {
+    Pending,
+    Running(Instant),
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+struct Row {
+    id: String,
+    name: String,
+    category: CheckCategory,
+    state: State,
+}
+
+/// Dashboard state, updated from run events
+struct Dashboard {
+    rows: Vec<Row>,
+    log: Vec<String>,
+    glyphs: StatusGlyphs,
+    start: Instant,
+}
+
+impl Dashboard {
+    fn new(planned: Vec<(String, String, CheckCategory)>, glyphs: StatusGlyphs) -> Self {
+        Dashboard {
+            rows: planned
+                .into_iter()
+                .map(|(id, name, category)| Row {
+                    id,
+                    name,
+                    category,
+                    state: State::Pending,
+                })
+                .collect(),
+            log: Vec::new(),
+            glyphs,
+            start: Instant::now(),
+        }
+    }
+
+    fn apply(&mut self, event: Event) {
+        match event {
+            Event::Started(id) => {
+                if let Some(row) = self.rows.iter_mut().find(|r| r.id == id) {
+                    row.state = State::Running(Instant::now());
+                }
+            }
+            Event::Finished(id, result) => {
+                let (state, label, text) = match &result {
+                    CheckResult::Pass { message, .. } => (State::Pass, &self.glyphs.pass, message),
+                    CheckResult::Warn { message, .. } => (State::Warn, &self.glyphs.warn, message),
+                    CheckResult::Fail { message, .. } => (State::Fail, &self.glyphs.fail, message),
+                    CheckResult::Skip { reason } => (State::Skip, &self.glyphs.skip, reason),
+                };
+                self.log.push(format!(
+                    "{:>6.1}s {} {} {}",
+                    self.start.elapsed().as_secs_f64(),
+                    label,
+                    id,
+                    text.lines().next().unwrap_or("")
+                ));
+                if let Some(row) = self.rows.iter_mut().find(|r| r.id == id) {
+                    row.state = state;
+                }
+            }
+            Event::Done(_) => {}
+        }
+    }
+
+    fn finished(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|r| !matches!(r.state, State::Pending | State::Running(_)))
+            .count()
+    }
+
+    /// The dashboard screen, fitted to `width` x `height`
+    fn render(&self, width: usize, height: usize, keys_enabled: bool) -> String {
+        let total = self.rows.len();
+        let done = self.finished();
+        let filled = (done * BAR_WIDTH).checked_div(total).unwrap_or(BAR_WIDTH);
+        let mut lines = vec![
+            format!(
+                "tpu-doc  [{}{}] {}/{} checks  {:.1}s{}",
+                "#".repeat(filled),
+                "-".repeat(BAR_WIDTH - filled),
+                done,
+                total,
+                self.start.elapsed().as_secs_f64(),
+                if keys_enabled { "  (q to stop)" } else { "" }
+            ),
+            String::new(),
+            format!("{:14} {:>5} {:>5} {:>5} {:>5} {:>8} {:>8}", "CATEGORY", "PASS", "WARN", "FAIL", "SKIP", "RUNNING", "PENDING"),
+        ];
+
+        let mut categories: Vec<&CheckCategory> = Vec::new();
+        for row in &self.rows {
+            if !categories.contains(&&row.category) {
+                categories.push(&row.category);
+            }
+        }
+        for category in categories {
+            let count = |f: fn(&State) -> bool| {
+                self.rows.iter().filter(|r| r.category == *category && f(&r.state)).count()
+            };
+            lines.push(format!(
+                "{:14} {:>5} {:>5} {:>5} {:>5} {:>8} {:>8}",
+                category.to_string(),
+                count(|s| *s == State::Pass),
+                count(|s| *s == State::Warn),
+                count(|s| *s == State::Fail),
+                count(|s| *s == State::Skip),
+                count(|s| matches!(s, State::Running(_))),
+                count(|s| *s == State::Pending),
+            ));
+        }
+
+        lines.push(String::new());
+        lines.push("RUNNING".to_string());
+        let running: Vec<String> = self
+            .rows
+            .iter()
+            .filter_map(|r| match r.state {
+                State::Running(since) => Some(format!(
+                    "  {:8} {} ({:.1}s)",
+                    r.id,
+                    r.name,
+                    since.elapsed().as_secs_f64()
+                )),
+                _ => None,
+            })
+            .collect();
+        if running.is_empty() {
+            lines.push("  -".to_string());
+        }
+        lines.extend(running);
+
+        // The newest log lines that fit below
+        lines.push(String::new());
+        lines.push("LOG".to_string());
+        let room = height.saturating_sub(lines.len()).max(1);
+        lines.extend(self.log[self.log.len().saturating_sub(room)..].iter().map(|line| format!("  {}", line)));
+
+        lines
+            .iter()
+            .take(height.max(1))
+            .map(|line| terminal::truncate(line, width))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dashboard() -> Dashboard {
+        Dashboard::new(
+            vec![
+                ("HW-001".to_string(), "TPU Device Detection".to_string(), CheckCategory::Hardware),
+                ("HW-002".to_string(), "HBM Memory Availability".to_string(), CheckCategory::Hardware),
+                ("PERF-001".to_string(), "MXU Utilization Baseline".to_string(), CheckCategory::Performance),
+            ],
+            StatusGlyphs::default(),
+        )
+    }
+
+    #[test]
+    fn test_dashboard_tracks_progress() {
+        let mut dashboard = dashboard();
+        dashboard.apply(Event::Started("HW-001".to_string()));
+        dashboard.apply(Event::Finished(
+            "HW-001".to_string(),
+            CheckResult::Pass {
+                message: "4 chips detected".to_string(),
+                duration_ms: 3,
+                data: Vec::new(),
+            },
+        ));
+        dashboard.apply(Event::Started("PERF-001".to_string()));
+
+        assert_eq!(dashboard.finished(), 1);
+        assert!(dashboard.log[0].ends_with("[PASS] HW-001 4 chips detected"));
+
+        let screen = dashboard.render(100, 40, true);
+        assert!(screen.starts_with("tpu-doc  [##########--------------------] 1/3 checks"));
+        assert!(screen.contains("(q to stop)"));
+        assert!(screen.contains("\nHardware           1     0     0     0        0        1\n"));
+        assert!(screen.contains("\nPerformance        0     0     0     0        1        0\n"));
+        assert!(screen.contains("RUNNING\n  PERF-001 MXU Utilization Baseline"));
+        assert!(screen.contains("LOG\n  "));
+    }
+
+    #[test]
+    fn test_render_fits_terminal() {
+        let mut dashboard = dashboard();
+        for i in 0..50 {
+            dashboard.log.push(format!("line {}", i));
+        }
+        let screen = dashboard.render(40, 20, false);
+        assert_eq!(screen.lines().count(), 20);
+        assert!(screen.lines().all(|line| line.chars().count() <= 40));
+        // Newest log lines are kept
+        assert!(screen.ends_with("  line 49"));
+        assert!(!screen.contains("q to stop"));
+    }
+}
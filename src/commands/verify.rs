@@ -0,0 +1,118 @@
+//! Verify command
+//!
+//! Checks the signature `check --format json --sign` added to a report,
+//! with the key file given by `--key` or the `[signing]` key of the
+//! configuration file. Automation that admits a node to a cluster on its
+//! preflight results can run this before trusting the report.
+
+use crate::cli::args::{Args, OutputFormat};
+use crate::cli::config::{expand_home, ConfigFile, DEFAULT_CONFIG_PATHS};
+use crate::engine::signing::{self, VerifyingKey};
+use crate::util::json::{self, JsonValue};
+use crate::TpuDocError;
+use std::fs;
+
+/// The outcome of checking a report's signature
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verification {
+    /// The signature algorithm, or why the signature is not valid
+    pub result: Result<&'static str, String>,
+    /// Run and host the report says it is from
+    pub run_id: Option<String>,
+    pub hostname: Option<String>,
+}
+
+/// Run the verify command. Returns the output and whether the signature
+/// is valid.
+pub fn run(args: &Args) -> Result<(String, bool), TpuDocError> {
+    let key = verifying_key(args)?;
+    let path = args.report_file.as_deref().unwrap_or_default();
+    let report = fs::read_to_string(path).map_err(|e| TpuDocError::io(&format!("reading report {}", path), e))?;
+    let verification = verify(&report, &key);
+    let output = match args.format {
+        OutputFormat::Json => format_json(path, &verification),
+        _ => format_text(path, &verification),
+    };
+    Ok((output, verification.result.is_ok()))
+}
+
+/// Check the signature of `report` with `key`
+pub fn verify(report: &str, key: &VerifyingKey) -> Verification {
+    let document = json::parse(report).ok();
+    let field = |name: &str| document.as_ref()?.get(name).and_then(JsonValue::as_str).map(str::to_string);
+    Verification {
+        result: signing::verify_report(report, key),
+        run_id: field("run_id"),
+        hostname: field("hostname"),
+    }
+}
+
+/// The `--key` file's public key, or the configuration file's `[signing]` key
+fn verifying_key(args: &Args) -> Result<VerifyingKey, TpuDocError> {
+    if let Some(path) = &args.key_file {
+        return signing::load_verifying_key(&expand_home(path));
+    }
+    match ConfigFile::discover(args.config.as_deref())? {
+        Some(file) => file.verifying_key(),
+        None => Err(TpuDocError::ConfigError {
+            path: DEFAULT_CONFIG_PATHS.join(", "),
+            message: "not found; pass --key, or set the [signing] key in a configuration file".to_string(),
+            source: None,
+        }),
+    }
+}
+
+fn format_text(path: &str, verification: &Verification) -> String {
+    match &verification.result {
+        Ok(algorithm) => format!(
+            "Signature valid ({}): {}, run {}, host {}",
+            algorithm,
+            path,
+            verification.run_id.as_deref().unwrap_or("unknown"),
+            verification.hostname.as_deref().unwrap_or("unknown")
+        ),
+        Err(reason) => format!("Signature NOT valid: {}: {}", path, reason),
+    }
+}
+
+fn format_json(path: &str, verification: &Verification) -> String {
+    let optional = |value: &Option<String>| value.as_deref().map_or("null".to_string(), json::quote);
+    let outcome = match &verification.result {
+        Ok(algorithm) => format!("\"valid\": true, \"algorithm\": \"{}\"", algorithm),
+        Err(reason) => format!("\"valid\": false, \"reason\": {}", json::quote(reason)),
+    };
+    format!(
+        "{{\"report\": {}, {}, \"run_id\": {}, \"hostname\": {}}}",
+        json::quote(path),
+        outcome,
+        optional(&verification.run_id),
+        optional(&verification.hostname)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::signing::{sign_report, SigningKey};
+
+    #[test]
+    fn test_verify_report() {
+        let key = SigningKey::Hmac(b"0123456789abcdef".to_vec());
+        let report = sign_report("{\"run_id\":\"r1\",\"hostname\":\"tpu-vm-0\",\"checks\":[]}", &key);
+
+        let verification = verify(&report, &key.verifying_key());
+        assert_eq!(verification.result, Ok("hmac-sha256"));
+        assert_eq!(
+            format_text("r.json", &verification),
+            "Signature valid (hmac-sha256): r.json, run r1, host tpu-vm-0"
+        );
+        assert!(json::parse(&format_json("r.json", &verification)).is_ok());
+
+        let verification = verify(&report.replace("tpu-vm-0", "tpu-vm-1"), &key.verifying_key());
+        assert!(verification.result.is_err());
+        assert_eq!(verification.hostname.as_deref(), Some("tpu-vm-1"));
+        let output = format_json("r.json", &verification);
+        assert!(output.contains("\"valid\": false, \"reason\": \"the signature does not match"));
+        assert!(json::parse(&output).is_ok());
+    }
+}
@@ -0,0 +1,184 @@
+//! Expected-performance database
+//!
+//! Benchmark results a healthy slice should reach, keyed by TPU type and
+//! topology. The PERF checks compare their measurements against these
+//! figures so every check reports "percent of expected" the same way.
+//!
+//! Figures are per chip and sit somewhat below the datasheet peaks in
+//! [`crate::data::specs`]: they are what the tpu-doc benchmarks achieve on
+//! healthy hardware, not theoretical limits.
+
+use crate::platform::tpu::TpuType;
+
+/// Expected benchmark results for one TPU type and topology
+#[derive(Debug, Clone)]
+pub struct ExpectedPerformance {
+    /// TPU generation (`v5e`, `v6e`, ...)
+    pub tpu_type: String,
+    /// Chip mesh shape (`2x4`, `2x2x1`, ...)
+    pub topology: String,
+    /// Dense BF16 matmul throughput per chip (PERF-001), in TFLOPS
+    pub matmul_tflops: f64,
+    /// HBM copy bandwidth per chip (PERF-002), in GB/s
+    pub hbm_gbps: f64,
+    /// All-reduce bus bandwidth per chip (PERF-006), in GB/s.
+    /// None for single-chip topologies.
+    pub all_reduce_bus_gbps: Option<f64>,
+    /// Cloud Storage read throughput per host vCPU (IO-007), in MB/s
+    pub gcs_mbps_per_vcpu: f64,
+}
+
+impl ExpectedPerformance {
+    /// Number of chips in this topology
+    pub fn chips(&self) -> u32 {
+        topology_chips(&self.topology).unwrap_or(1)
+    }
+}
+
+/// The expected-performance database
+#[derive(Debug)]
+pub struct BaselineDb {
+    pub version: String,
+    pub baselines: Vec<ExpectedPerformance>,
+}
+
+impl BaselineDb {
+    /// Load the embedded expected-performance table
+    pub fn load() -> Self {
+        BaselineDb {
+            version: "1.0".to_string(),
+            baselines: vec![
+                expected("v4", "2x2x1", 235.0, 1050.0, Some(160.0), 20.0),
+                expected("v4", "2x2x2", 235.0, 1050.0, Some(240.0), 20.0),
+                expected("v4", "2x2x4", 235.0, 1050.0, Some(320.0), 20.0),
+                expected("v4", "4x4x4", 235.0, 1050.0, Some(400.0), 20.0),
+                expected("v5e", "1x1", 170.0, 700.0, None, 15.0),
+                expected("v5e", "2x2", 170.0, 700.0, Some(80.0), 15.0),
+                expected("v5e", "2x4", 170.0, 700.0, Some(120.0), 15.0),
+                expected("v5e", "4x4", 170.0, 700.0, Some(160.0), 15.0),
+                expected("v5p", "2x2x1", 390.0, 1400.0, Some(160.0), 20.0),
+                expected("v5p", "2x2x2", 390.0, 1400.0, Some(240.0), 20.0),
+                expected("v5p", "2x2x4", 390.0, 1400.0, Some(320.0), 20.0),
+                expected("v5p", "4x4x4", 390.0, 1400.0, Some(400.0), 20.0),
+                expected("v6e", "1x1", 780.0, 1550.0, None, 20.0),
+                expected("v6e", "2x2", 780.0, 1550.0, Some(180.0), 20.0),
+                expected("v6e", "2x4", 780.0, 1550.0, Some(270.0), 20.0),
+                expected("v6e", "4x4", 780.0, 1550.0, Some(360.0), 20.0),
+                expected("v7", "2x2x1", 1960.0, 1750.0, Some(320.0), 20.0),
+                expected("v7", "2x2x2", 1960.0, 1750.0, Some(480.0), 20.0),
+            ],
+        }
+    }
+
+    /// Expected performance for `tpu_type` on a `topology` mesh.
+    ///
+    /// Uses the exact topology when listed. Otherwise falls back to the
+    /// largest listed topology of that type with no more chips (or the
+    /// smallest one), so per-chip figures are still available; collective
+    /// figures from a fallback row are approximate.
+    pub fn lookup(&self, tpu_type: &TpuType, topology: &str) -> Option<&ExpectedPerformance> {
+        let name = tpu_type.to_string();
+        let rows: Vec<&ExpectedPerformance> = self
+            .baselines
+            .iter()
+            .filter(|b| b.tpu_type.eq_ignore_ascii_case(&name))
+            .collect();
+
+        if let Some(exact) = rows.iter().find(|b| b.topology.eq_ignore_ascii_case(topology.trim())) {
+            return Some(exact);
+        }
+
+        let chips = topology_chips(topology).unwrap_or(1);
+        rows.iter()
+            .filter(|b| b.chips() <= chips)
+            .max_by_key(|b| b.chips())
+            .or_else(|| rows.iter().min_by_key(|b| b.chips()))
+            .copied()
+    }
+
+    /// Whether `topology` has its own row for `tpu_type` (rather than a fallback)
+    pub fn has_exact(&self, tpu_type: &TpuType, topology: &str) -> bool {
+        self.lookup(tpu_type, topology)
+            .is_some_and(|b| b.topology.eq_ignore_ascii_case(topology.trim()))
+    }
+}
+
+/// `measured` as a percentage of `expected`
+pub fn percent_of_expected(measured: f64, expected: f64) -> f64 {
+    if expected <= 0.0 {
+        return 0.0;
+    }
+    measured / expected * 100.0
+}
+
+/// Number of chips in a mesh shape such as `2x2x1`
+pub fn topology_chips(topology: &str) -> Option<u32> {
+    topology
+        .trim()
+        .split('x')
+        .map(|dim| dim.trim().parse::<u32>().ok())
+        .try_fold(1u32, |chips, dim| Some(chips * dim?))
+}
+
+fn expected(
+    tpu_type: &str,
+    topology: &str,
+    matmul_tflops: f64,
+    hbm_gbps: f64,
+    all_reduce_bus_gbps: Option<f64>,
+    gcs_mbps_per_vcpu: f64,
+) -> ExpectedPerformance {
+    ExpectedPerformance {
+        tpu_type: tpu_type.to_string(),
+        topology: topology.to_string(),
+        matmul_tflops,
+        hbm_gbps,
+        all_reduce_bus_gbps,
+        gcs_mbps_per_vcpu,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::specs;
+
+    #[test]
+    fn test_baselines_below_spec_peaks() {
+        let db = BaselineDb::load();
+        let specs = specs::TpuSpecs::load();
+        for b in &db.baselines {
+            let spec = specs.get_spec(&b.tpu_type).unwrap();
+            assert!(b.matmul_tflops < spec.bf16_tflops as f64, "{} {}", b.tpu_type, b.topology);
+            assert!(b.hbm_gbps < spec.hbm_bandwidth_gbps as f64, "{} {}", b.tpu_type, b.topology);
+            assert!(spec.supports_topology(&b.topology), "{} {}", b.tpu_type, b.topology);
+            assert_eq!(b.all_reduce_bus_gbps.is_none(), b.chips() == 1);
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let db = BaselineDb::load();
+
+        let exact = db.lookup(&TpuType::V5e, "2x4").unwrap();
+        assert_eq!(exact.topology, "2x4");
+        assert!(db.has_exact(&TpuType::V5e, "2x4"));
+
+        // 8x8 is not listed; the largest smaller v5e topology is used
+        let fallback = db.lookup(&TpuType::V5e, "8x8").unwrap();
+        assert_eq!(fallback.topology, "4x4");
+        assert!(!db.has_exact(&TpuType::V5e, "8x8"));
+
+        assert_eq!(db.lookup(&TpuType::V4, "8x1").unwrap().topology, "2x2x2");
+        assert!(db.lookup(&TpuType::Unknown, "2x2").is_none());
+    }
+
+    #[test]
+    fn test_percent_and_chips() {
+        assert_eq!(percent_of_expected(85.0, 170.0), 50.0);
+        assert_eq!(percent_of_expected(1.0, 0.0), 0.0);
+        assert_eq!(topology_chips("2x2x4"), Some(16));
+        assert_eq!(topology_chips("8x1"), Some(8));
+        assert_eq!(topology_chips("bad"), None);
+    }
+}
@@ -1,6 +1,30 @@
 //! Compatibility matrix for JAX ecosystem versions
 //!
 //! Provides version compatibility information loaded from embedded data.
+//!
+//! A newer matrix can be published as JSON at an HTTPS URL, so new JAX and
+//! libtpu releases don't require a tpu-doc release. When a URL is configured
+//! (`--matrix-url` or `TPU_PREFLIGHT_MATRIX_URL`), the matrix is downloaded and
+//! cached under `~/.tpu-doc/matrix/`; later runs revalidate the cached copy
+//! with its ETag. If the download fails, the cached copy is used, and
+//! without one the embedded matrix. A remote matrix older than the embedded
+//! one is ignored. Fetching an `https://` URL needs a build with the `ai`
+//! feature, which brings in TLS; without it the embedded matrix is used and
+//! the fallback says why.
+
+use crate::ai::client::{BaseUrl, HttpClient, HttpConfig};
+use crate::data::known_issues::compare_versions;
+use crate::util::{self, hash, json, json::JsonValue};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Environment variable naming the URL of a remote matrix
+pub const MATRIX_URL_ENV: &str = "TPU_PREFLIGHT_MATRIX_URL";
+
+/// Largest remote matrix accepted
+const MAX_MATRIX_BYTES: usize = 1024 * 1024;
 
 /// Compatibility status between package versions
 #[derive(Debug, Clone, PartialEq)]
@@ -34,6 +58,82 @@ pub struct KnownConflict {
     pub resolution: String,
 }
 
+/// A TPU driver and libtpu pairing known to fail TPU initialization.
+///
+/// Each range runs from its `_from` version (inclusive) up to its `_until`
+/// version (exclusive); with no `_until` it covers every later release.
+#[derive(Debug, Clone)]
+pub struct DriverPairing {
+    pub driver_from: String,
+    pub driver_until: Option<String>,
+    pub libtpu_from: String,
+    pub libtpu_until: Option<String>,
+    pub description: String,
+    pub resolution: String,
+}
+
+impl DriverPairing {
+    /// Whether `driver` with `libtpu` is this pairing
+    pub fn matches(&self, driver: &str, libtpu: &str) -> bool {
+        version_in_span(driver, &self.driver_from, self.driver_until.as_deref())
+            && version_in_span(libtpu, &self.libtpu_from, self.libtpu_until.as_deref())
+    }
+
+    /// The pairing's ranges, e.g. `driver <1.8.0 with libtpu >=0.0.6`
+    pub fn describe_ranges(&self) -> String {
+        let range = |from: &str, until: &Option<String>| match until {
+            Some(until) if is_zero_version(from) => format!("<{}", until),
+            Some(until) => format!(">={}, <{}", from, until),
+            None => format!(">={}", from),
+        };
+        format!(
+            "driver {} with libtpu {}",
+            range(&self.driver_from, &self.driver_until),
+            range(&self.libtpu_from, &self.libtpu_until)
+        )
+    }
+}
+
+/// Whether `version` is 0 (or 0.0, ...), so a range from it has no lower bound
+fn is_zero_version(version: &str) -> bool {
+    compare_versions(version, "0") == Ordering::Equal
+}
+
+/// Whether `version` is at least `from` and, when given, below `until`
+fn version_in_span(version: &str, from: &str, until: Option<&str>) -> bool {
+    compare_versions(version, from) != Ordering::Less
+        && until.is_none_or(|until| compare_versions(version, until) == Ordering::Less)
+}
+
+/// The PJRT C API versions a range of jaxlib releases accepts from a plugin.
+///
+/// jaxlib releases from `jaxlib_from` (inclusive) up to `jaxlib_until`
+/// (exclusive) load a plugin reporting major version `major` and a minor
+/// version of at least `min_minor` and, when set, at most `max_minor`.
+#[derive(Debug, Clone)]
+pub struct PjrtApiRange {
+    pub jaxlib_from: String,
+    pub jaxlib_until: Option<String>,
+    pub major: u32,
+    pub min_minor: u32,
+    pub max_minor: Option<u32>,
+}
+
+impl PjrtApiRange {
+    /// Whether jaxlib `major`.`minor` of the C API is in this range
+    pub fn accepts(&self, major: u32, minor: u32) -> bool {
+        major == self.major && minor >= self.min_minor && self.max_minor.is_none_or(|max| minor <= max)
+    }
+
+    /// The accepted C API versions, e.g. `0.51 to 0.58` or `0.55 or later`
+    pub fn describe(&self) -> String {
+        match self.max_minor {
+            Some(max) => format!("{}.{} to {}.{}", self.major, self.min_minor, self.major, max),
+            None => format!("{}.{} or later", self.major, self.min_minor),
+        }
+    }
+}
+
 /// Recommended version set
 #[derive(Debug, Clone)]
 pub struct RecommendedVersions {
@@ -48,6 +148,10 @@ pub struct CompatibilityMatrix {
     pub updated: String,
     pub jax_versions: Vec<JaxVersionEntry>,
     pub known_conflicts: Vec<KnownConflict>,
+    /// Driver and libtpu pairings that fail TPU initialization
+    pub driver_pairings: Vec<DriverPairing>,
+    /// PJRT C API versions each jaxlib release accepts
+    pub pjrt_api_ranges: Vec<PjrtApiRange>,
     pub recommended: RecommendedVersionsMap,
 }
 
@@ -59,14 +163,280 @@ pub struct RecommendedVersionsMap {
     pub v6e: RecommendedVersions,
 }
 
+/// Where a loaded matrix came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatrixSource {
+    /// The matrix built into this binary (no URL configured)
+    Embedded,
+    /// Downloaded from `url` on this run
+    Remote { url: String },
+    /// A previous download from `url`. `revalidated` is true when the
+    /// server confirmed it is current (HTTP 304), false when the download
+    /// failed and the cached copy was used as-is.
+    Cached { url: String, revalidated: bool },
+    /// The embedded matrix, used because the remote one was unavailable
+    Fallback { url: String, reason: String },
+}
+
+impl MatrixSource {
+    /// One-line description for command output
+    pub fn describe(&self) -> String {
+        match self {
+            MatrixSource::Embedded => "embedded".to_string(),
+            MatrixSource::Remote { url } => format!("remote ({})", url),
+            MatrixSource::Cached { url, revalidated: true } => format!("cached, up to date ({})", url),
+            MatrixSource::Cached { url, revalidated: false } => {
+                format!("cached, could not refresh ({})", url)
+            }
+            MatrixSource::Fallback { reason, .. } => format!("embedded ({})", reason),
+        }
+    }
+}
+
 impl CompatibilityMatrix {
-    /// Load the embedded compatibility matrix
-    pub fn load() -> Self {
-        // Embedded compatibility data
-        // In a real implementation, this would parse JSON from include_str!
+    /// Load the compatibility matrix.
+    ///
+    /// Fetches the remote matrix when `TPU_PREFLIGHT_MATRIX_URL` is set (see
+    /// [`CompatibilityMatrix::load_from_url`]); otherwise returns the
+    /// embedded matrix. Loaded once per process, so the checks that use it
+    /// share a single download.
+    pub fn load() -> &'static Self {
+        static MATRIX: OnceLock<CompatibilityMatrix> = OnceLock::new();
+        MATRIX.get_or_init(|| match util::env_setting(MATRIX_URL_ENV) {
+            Some(url) if !url.trim().is_empty() => Self::load_from_url(url.trim()).0,
+            _ => Self::embedded(),
+        })
+    }
+
+    /// Fetch the matrix published at `url`, caching it under
+    /// `~/.tpu-preflight/matrix/` (a copy cached under the old
+    /// `~/.tpu-doc/matrix/` is still read). Falls back to the cached copy,
+    /// then to the embedded matrix, when the download fails.
+    pub fn load_from_url(url: &str) -> (Self, MatrixSource) {
+        let cache_dir = util::state_dir().map(|dir| dir.join("matrix"));
+        let legacy_dir = util::legacy_state_dir().map(|dir| dir.join("matrix"));
+        Self::load_from_url_with(url, cache_dir.as_deref(), legacy_dir.as_deref())
+    }
+
+    /// [`CompatibilityMatrix::load_from_url`] with an explicit cache directory
+    pub fn load_from_url_cached(url: &str, cache_dir: Option<&Path>) -> (Self, MatrixSource) {
+        Self::load_from_url_with(url, cache_dir, None)
+    }
+
+    fn load_from_url_with(url: &str, cache_dir: Option<&Path>, legacy_dir: Option<&Path>) -> (Self, MatrixSource) {
+        let embedded = Self::embedded();
+        let file_name = format!("{}.json", &hash::sha256_hex(url.as_bytes())[..16]);
+        let cache_path = cache_dir.map(|dir| dir.join(&file_name));
+        let cached = cache_path
+            .as_deref()
+            .and_then(read_cache_entry)
+            .or_else(|| legacy_dir.and_then(|dir| read_cache_entry(&dir.join(&file_name))))
+            .and_then(|(etag, body)| Self::from_json(&body).ok().map(|matrix| (etag, body, matrix)));
+        let fallback = |reason: String| {
+            (
+                Self::embedded(),
+                MatrixSource::Fallback {
+                    url: url.to_string(),
+                    reason,
+                },
+            )
+        };
+
+        let etag = cached.as_ref().and_then(|(etag, _, _)| etag.clone());
+        let (matrix, source) = match fetch_matrix(url, etag.as_deref()) {
+            Ok(FetchResult::NotModified) => match cached {
+                Some((_, _, matrix)) => (
+                    matrix,
+                    MatrixSource::Cached {
+                        url: url.to_string(),
+                        revalidated: true,
+                    },
+                ),
+                None => return fallback("server returned 304 with no cached copy".to_string()),
+            },
+            Ok(FetchResult::Modified { body, etag }) => match Self::from_json(&body) {
+                Ok(matrix) => {
+                    if let Some(path) = &cache_path {
+                        write_cache_entry(path, etag.as_deref(), &body);
+                    }
+                    (matrix, MatrixSource::Remote { url: url.to_string() })
+                }
+                Err(e) => return fallback(format!("invalid remote matrix: {}", e)),
+            },
+            Err(e) => match cached {
+                Some((_, _, matrix)) => (
+                    matrix,
+                    MatrixSource::Cached {
+                        url: url.to_string(),
+                        revalidated: false,
+                    },
+                ),
+                None => return fallback(format!("fetch failed: {}", e)),
+            },
+        };
+
+        // Dates are ISO 8601, so they compare as strings
+        if matrix.updated < embedded.updated {
+            return fallback(format!(
+                "remote matrix updated {} is older than embedded {}",
+                matrix.updated, embedded.updated
+            ));
+        }
+        (matrix, source)
+    }
+
+    /// Parse a matrix from its JSON form:
+    ///
+    /// ```json
+    /// {
+    ///   "version": "1.1",
+    ///   "updated": "2025-03-01",
+    ///   "jax_versions": [{"version": "0.5.1", "python_min": "3.10", "python_max": "3.13",
+    ///                     "jaxlib_version": "0.5.1", "libtpu_versions": ["0.0.10"], "notes": null}],
+    ///   "known_conflicts": [{"packages": ["jax", "torch"], "description": "...", "resolution": "..."}],
+    ///   "driver_pairings": [{"driver_from": "0.0.0", "driver_until": "1.8.0", "libtpu_from": "0.0.6",
+    ///                        "libtpu_until": null, "description": "...", "resolution": "..."}],
+    ///   "pjrt_api_ranges": [{"jaxlib_from": "0.4.31", "jaxlib_until": null, "major": 0, "min_minor": 51,
+    ///                        "max_minor": null}],
+    ///   "recommended": {"v4": {"jax_version": "0.5.1", "python_version": "3.11"}, "v5e": ..., "v5p": ..., "v6e": ...}
+    /// }
+    /// ```
+    ///
+    /// `driver_pairings` and `pjrt_api_ranges` are optional, for matrices
+    /// published before they were added.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let root = json::parse(text)?;
+        let string = |value: &JsonValue, key: &str| -> Result<String, String> {
+            value
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .ok_or_else(|| format!("missing string field '{}'", key))
+        };
+        let strings = |value: &JsonValue, key: &str| -> Result<Vec<String>, String> {
+            value
+                .get(key)
+                .and_then(|v| v.as_array())
+                .ok_or_else(|| format!("missing array field '{}'", key))?
+                .iter()
+                .map(|v| v.as_str().map(|s| s.to_string()).ok_or_else(|| format!("non-string in '{}'", key)))
+                .collect()
+        };
+        let array = |key: &str| -> Result<&[JsonValue], String> {
+            root.get(key)
+                .and_then(|v| v.as_array())
+                .ok_or_else(|| format!("missing array field '{}'", key))
+        };
+
+        let jax_versions = array("jax_versions")?
+            .iter()
+            .map(|entry| {
+                Ok(JaxVersionEntry {
+                    version: string(entry, "version")?,
+                    python_min: string(entry, "python_min")?,
+                    python_max: string(entry, "python_max")?,
+                    jaxlib_version: string(entry, "jaxlib_version")?,
+                    libtpu_versions: strings(entry, "libtpu_versions")?,
+                    notes: entry.get("notes").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if jax_versions.is_empty() {
+            return Err("'jax_versions' is empty".to_string());
+        }
+
+        let known_conflicts = array("known_conflicts")?
+            .iter()
+            .map(|entry| {
+                Ok(KnownConflict {
+                    packages: strings(entry, "packages")?,
+                    description: string(entry, "description")?,
+                    resolution: string(entry, "resolution")?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let optional = |value: &JsonValue, key: &str| value.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+        let driver_pairings = match root.get("driver_pairings") {
+            None => Vec::new(),
+            Some(pairings) => pairings
+                .as_array()
+                .ok_or("'driver_pairings' is not an array")?
+                .iter()
+                .map(|entry| {
+                    Ok(DriverPairing {
+                        driver_from: string(entry, "driver_from")?,
+                        driver_until: optional(entry, "driver_until"),
+                        libtpu_from: string(entry, "libtpu_from")?,
+                        libtpu_until: optional(entry, "libtpu_until"),
+                        description: string(entry, "description")?,
+                        resolution: string(entry, "resolution")?,
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?,
+        };
+
+        let number = |value: &JsonValue, key: &str| -> Result<u32, String> {
+            value
+                .get(key)
+                .and_then(|v| v.as_f64())
+                .filter(|n| *n >= 0.0 && n.fract() == 0.0)
+                .map(|n| n as u32)
+                .ok_or_else(|| format!("missing version number field '{}'", key))
+        };
+        let pjrt_api_ranges = match root.get("pjrt_api_ranges") {
+            None => Vec::new(),
+            Some(ranges) => ranges
+                .as_array()
+                .ok_or("'pjrt_api_ranges' is not an array")?
+                .iter()
+                .map(|entry| {
+                    Ok(PjrtApiRange {
+                        jaxlib_from: string(entry, "jaxlib_from")?,
+                        jaxlib_until: optional(entry, "jaxlib_until"),
+                        major: number(entry, "major")?,
+                        min_minor: number(entry, "min_minor")?,
+                        max_minor: match entry.get("max_minor") {
+                            Some(max) if !max.is_null() => Some(number(entry, "max_minor")?),
+                            _ => None,
+                        },
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?,
+        };
+
+        let recommended = root.get("recommended").ok_or("missing object field 'recommended'")?;
+        let recommended_for = |tpu: &str| -> Result<RecommendedVersions, String> {
+            let entry = recommended
+                .get(tpu)
+                .ok_or_else(|| format!("missing recommended versions for {}", tpu))?;
+            Ok(RecommendedVersions {
+                jax_version: string(entry, "jax_version")?,
+                python_version: string(entry, "python_version")?,
+            })
+        };
+
+        Ok(CompatibilityMatrix {
+            version: string(&root, "version")?,
+            updated: string(&root, "updated")?,
+            jax_versions,
+            known_conflicts,
+            driver_pairings,
+            pjrt_api_ranges,
+            recommended: RecommendedVersionsMap {
+                v4: recommended_for("v4")?,
+                v5e: recommended_for("v5e")?,
+                v5p: recommended_for("v5p")?,
+                v6e: recommended_for("v6e")?,
+            },
+        })
+    }
+
+    /// The compatibility matrix built into this binary
+    pub fn embedded() -> Self {
         CompatibilityMatrix {
-            version: "1.0".to_string(),
-            updated: "2025-01-03".to_string(),
+            version: "1.2".to_string(),
+            updated: "2026-10-16".to_string(),
             jax_versions: vec![
                 JaxVersionEntry {
                     version: "0.4.35".to_string(),
@@ -126,6 +496,53 @@ impl CompatibilityMatrix {
                     resolution: "Use JAX_PLATFORMS=tpu to ensure JAX uses TPU exclusively".to_string(),
                 },
             ],
+            driver_pairings: vec![
+                DriverPairing {
+                    driver_from: "0.0.0".to_string(),
+                    driver_until: Some("1.8.0".to_string()),
+                    libtpu_from: "0.0.6".to_string(),
+                    libtpu_until: None,
+                    description: "libtpu 0.0.6 and later open the TPU through the device interface added in \
+                                  driver 1.8.0; with an older driver initialization fails with \"No TPU devices \
+                                  found\""
+                        .to_string(),
+                    resolution: "Recreate the TPU VM with a current runtime version, or pin libtpu<0.0.6"
+                        .to_string(),
+                },
+                DriverPairing {
+                    driver_from: "1.8.0".to_string(),
+                    driver_until: None,
+                    libtpu_from: "0.0.1".to_string(),
+                    libtpu_until: Some("0.0.3".to_string()),
+                    description: "libtpu releases before 0.0.3 predate the driver 1.8.0 device interface and \
+                                  abort during TPU backend initialization"
+                        .to_string(),
+                    resolution: "Upgrade libtpu: pip install -U \"jax[tpu]\"".to_string(),
+                },
+            ],
+            pjrt_api_ranges: vec![
+                PjrtApiRange {
+                    jaxlib_from: "0.4.26".to_string(),
+                    jaxlib_until: Some("0.4.31".to_string()),
+                    major: 0,
+                    min_minor: 40,
+                    max_minor: Some(54),
+                },
+                PjrtApiRange {
+                    jaxlib_from: "0.4.31".to_string(),
+                    jaxlib_until: Some("0.4.36".to_string()),
+                    major: 0,
+                    min_minor: 51,
+                    max_minor: Some(58),
+                },
+                PjrtApiRange {
+                    jaxlib_from: "0.4.36".to_string(),
+                    jaxlib_until: None,
+                    major: 0,
+                    min_minor: 55,
+                    max_minor: None,
+                },
+            ],
             recommended: RecommendedVersionsMap {
                 v4: RecommendedVersions {
                     jax_version: "0.4.35".to_string(),
@@ -168,13 +585,22 @@ impl CompatibilityMatrix {
         }
     }
 
+    /// Pairings of `driver` with `libtpu` known to fail TPU initialization
+    pub fn bad_driver_pairings(&self, driver: &str, libtpu: &str) -> Vec<&DriverPairing> {
+        self.driver_pairings.iter().filter(|pairing| pairing.matches(driver, libtpu)).collect()
+    }
+
+    /// The PJRT C API versions `jaxlib` accepts, if the matrix records them
+    pub fn pjrt_api_range(&self, jaxlib: &str) -> Option<&PjrtApiRange> {
+        self.pjrt_api_ranges
+            .iter()
+            .find(|range| version_in_span(jaxlib, &range.jaxlib_from, range.jaxlib_until.as_deref()))
+    }
+
     /// Get recommended versions for a JAX version
     pub fn get_recommended_for_jax(&self, _jax_ver: &str) -> Option<RecommendedVersions> {
         // Return the general recommendation
-        Some(RecommendedVersions {
-            jax_version: "0.4.35".to_string(),
-            python_version: "3.11".to_string(),
-        })
+        Some(self.recommended.v5e.clone())
     }
 
     /// Get recommended versions for a TPU type
@@ -189,6 +615,75 @@ impl CompatibilityMatrix {
     }
 }
 
+/// Result of a conditional GET
+enum FetchResult {
+    NotModified,
+    Modified { body: String, etag: Option<String> },
+}
+
+/// GET `url`, sending `If-None-Match` when a cached ETag is known
+fn fetch_matrix(url: &str, etag: Option<&str>) -> Result<FetchResult, String> {
+    let base = BaseUrl::parse(url).map_err(|e| e.to_string())?;
+    if base.tls && !cfg!(feature = "ai") {
+        return Err("https URLs need a build with the `ai` feature (TLS support)".to_string());
+    }
+    // BaseUrl keeps the URL's path as a prefix; the root needs an explicit "/"
+    let path = if base.path_prefix.is_empty() { "/" } else { "" };
+
+    // Short timeouts and no retries: an unreachable URL must not stall checks
+    let client = HttpClient::with_config(HttpConfig {
+        connect_timeout_ms: 3000,
+        read_timeout_ms: 10000,
+        max_retries: 0,
+        keep_alive: false,
+        ..HttpConfig::default()
+    })
+    .with_base_url(base);
+
+    let mut headers = vec![("Accept", "application/json")];
+    if let Some(etag) = etag {
+        headers.push(("If-None-Match", etag));
+    }
+    let response = client.get_https("", path, &headers).map_err(|e| e.to_string())?;
+
+    match response.status {
+        304 => Ok(FetchResult::NotModified),
+        200 if response.body.len() > MAX_MATRIX_BYTES => {
+            Err(format!("matrix larger than {} bytes", MAX_MATRIX_BYTES))
+        }
+        200 => Ok(FetchResult::Modified {
+            etag: response.get_header("etag").map(|e| e.to_string()),
+            body: response.body,
+        }),
+        status => Err(format!("HTTP {}", status)),
+    }
+}
+
+/// Cache entries are `etag: <etag>` (possibly empty), a blank line, then the body
+fn read_cache_entry(path: &Path) -> Option<(Option<String>, String)> {
+    let text = fs::read_to_string(path).ok()?;
+    let (header, body) = text.split_once("\n\n")?;
+    let etag = header
+        .strip_prefix("etag:")
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty());
+    Some((etag, body.to_string()))
+}
+
+/// Store a downloaded matrix. Failures are ignored; the cache is best-effort.
+fn write_cache_entry(path: &Path, etag: Option<&str>, body: &str) {
+    let Some(dir) = path.parent() else { return };
+    if fs::create_dir_all(dir).is_err() {
+        return;
+    }
+    // Write then rename so a concurrent reader never sees a partial entry
+    let tmp: PathBuf = path.with_extension("tmp");
+    let entry = format!("etag: {}\n\n{}", etag.unwrap_or(""), body);
+    if fs::write(&tmp, entry).is_ok() && fs::rename(&tmp, path).is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+}
+
 fn is_version_in_range(version: &str, min: &str, max: &str) -> bool {
     let version_parts: Vec<u32> = version
         .split('.')
@@ -234,6 +729,159 @@ mod tests {
         assert!(!is_version_in_range("3.13", "3.9", "3.12"));
     }
 
+    const REMOTE_MATRIX: &str = r#"{
+        "version": "2.0",
+        "updated": "2099-01-01",
+        "jax_versions": [
+            {"version": "0.9.0", "python_min": "3.10", "python_max": "3.13",
+             "jaxlib_version": "0.9.0", "libtpu_versions": ["0.0.20"], "notes": null}
+        ],
+        "known_conflicts": [],
+        "recommended": {
+            "v4": {"jax_version": "0.9.0", "python_version": "3.12"},
+            "v5e": {"jax_version": "0.9.0", "python_version": "3.12"},
+            "v5p": {"jax_version": "0.9.0", "python_version": "3.12"},
+            "v6e": {"jax_version": "0.9.0", "python_version": "3.12"}
+        }
+    }"#;
+
+    #[test]
+    fn test_matrix_from_json() {
+        let matrix = CompatibilityMatrix::from_json(REMOTE_MATRIX).unwrap();
+        assert_eq!(matrix.version, "2.0");
+        assert_eq!(matrix.jax_versions[0].libtpu_versions, vec!["0.0.20"]);
+        assert_eq!(matrix.jax_versions[0].notes, None);
+        assert_eq!(matrix.get_recommended_for_tpu("v6e").unwrap().python_version, "3.12");
+
+        assert!(matrix.driver_pairings.is_empty());
+
+        let with_pairings = REMOTE_MATRIX.replace(
+            "\"known_conflicts\": [],",
+            r#""known_conflicts": [], "driver_pairings": [{"driver_from": "2.0", "driver_until": null,
+               "libtpu_from": "0.0.1", "libtpu_until": "0.0.20", "description": "d", "resolution": "r"}],"#,
+        );
+        let matrix = CompatibilityMatrix::from_json(&with_pairings).unwrap();
+        assert_eq!(matrix.bad_driver_pairings("2.1.0", "0.0.19").len(), 1);
+        assert!(matrix.bad_driver_pairings("2.1.0", "0.0.20").is_empty());
+        assert!(matrix.bad_driver_pairings("1.9.0", "0.0.19").is_empty());
+        assert!(matrix.pjrt_api_ranges.is_empty());
+
+        let with_ranges = REMOTE_MATRIX.replace(
+            "\"known_conflicts\": [],",
+            r#""known_conflicts": [], "pjrt_api_ranges": [{"jaxlib_from": "0.9.0", "jaxlib_until": null,
+               "major": 1, "min_minor": 2, "max_minor": null}],"#,
+        );
+        let matrix = CompatibilityMatrix::from_json(&with_ranges).unwrap();
+        assert!(matrix.pjrt_api_range("0.9.1").unwrap().accepts(1, 7));
+        assert!(matrix.pjrt_api_range("0.8.0").is_none());
+        assert!(CompatibilityMatrix::from_json(&with_ranges.replace("\"min_minor\": 2", "\"min_minor\": -2")).is_err());
+
+        assert!(CompatibilityMatrix::from_json("{}").is_err());
+        assert!(CompatibilityMatrix::from_json(&REMOTE_MATRIX.replace("\"v6e\"", "\"v7\"")).is_err());
+    }
+
+    #[test]
+    fn test_load_from_url_with_etag_cache() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://127.0.0.1:{}/matrix.json", listener.local_addr().unwrap().port());
+        let dir = std::env::temp_dir().join(format!("tpu-doc-matrix-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        // First request gets the matrix and an ETag; the second must revalidate with it
+        let server = std::thread::spawn(move || {
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut writer = stream;
+                let mut request = String::new();
+                while reader.read_line(&mut request).unwrap() > 0 && !request.ends_with("\r\n\r\n") {}
+                assert!(request.starts_with("GET /matrix.json HTTP/1.1"));
+                if request.contains("If-None-Match: \"v2\"") {
+                    write!(writer, "HTTP/1.1 304 Not Modified\r\nContent-Length: 0\r\n\r\n").unwrap();
+                } else {
+                    write!(
+                        writer,
+                        "HTTP/1.1 200 OK\r\nETag: \"v2\"\r\nContent-Length: {}\r\n\r\n{}",
+                        REMOTE_MATRIX.len(),
+                        REMOTE_MATRIX
+                    )
+                    .unwrap();
+                }
+            }
+        });
+
+        let (matrix, source) = CompatibilityMatrix::load_from_url_cached(&url, Some(&dir));
+        assert_eq!(source, MatrixSource::Remote { url: url.clone() });
+        assert_eq!(matrix.version, "2.0");
+
+        let (matrix, source) = CompatibilityMatrix::load_from_url_cached(&url, Some(&dir));
+        assert_eq!(source, MatrixSource::Cached { url: url.clone(), revalidated: true });
+        assert_eq!(matrix.version, "2.0");
+        server.join().unwrap();
+
+        // Server gone: the cached copy is still used, and without it the embedded matrix
+        let (matrix, source) = CompatibilityMatrix::load_from_url_cached(&url, Some(&dir));
+        assert_eq!(source, MatrixSource::Cached { url: url.clone(), revalidated: false });
+        assert_eq!(matrix.version, "2.0");
+
+        // A copy cached under the old state directory is read too
+        let (matrix, source) = CompatibilityMatrix::load_from_url_with(&url, Some(&dir.join("new")), Some(&dir));
+        assert_eq!(source, MatrixSource::Cached { url: url.clone(), revalidated: false });
+        assert_eq!(matrix.version, "2.0");
+
+        let (matrix, source) = CompatibilityMatrix::load_from_url_cached(&url, None);
+        assert!(matches!(source, MatrixSource::Fallback { .. }));
+        assert_eq!(matrix.version, CompatibilityMatrix::embedded().version);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_driver_pairings() {
+        let matrix = CompatibilityMatrix::embedded();
+        let bad = matrix.bad_driver_pairings("1.7.2", "0.0.8");
+        assert_eq!(bad.len(), 1);
+        assert_eq!(bad[0].describe_ranges(), "driver <1.8.0 with libtpu >=0.0.6");
+        assert_eq!(
+            matrix.bad_driver_pairings("1.8.0", "0.0.2")[0].describe_ranges(),
+            "driver >=1.8.0 with libtpu >=0.0.1, <0.0.3"
+        );
+
+        // Nightly builds compare by their release prefix
+        assert_eq!(matrix.bad_driver_pairings("1.6.0", "0.1.dev20241028").len(), 1);
+        assert!(matrix.bad_driver_pairings("1.8.0", "0.0.8").is_empty());
+        assert!(matrix.bad_driver_pairings("1.7.2", "0.0.5").is_empty());
+    }
+
+    #[cfg(not(feature = "ai"))]
+    #[test]
+    fn test_https_matrix_needs_ai_feature() {
+        let (matrix, source) = CompatibilityMatrix::load_from_url_cached("https://example.invalid/matrix.json", None);
+        assert_eq!(matrix.version, CompatibilityMatrix::embedded().version);
+        match source {
+            MatrixSource::Fallback { reason, .. } => assert!(reason.contains("`ai` feature"), "{}", reason),
+            other => panic!("expected fallback, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pjrt_api_ranges() {
+        let matrix = CompatibilityMatrix::embedded();
+        let range = matrix.pjrt_api_range("0.4.35").unwrap();
+        assert_eq!(range.describe(), "0.51 to 0.58");
+        assert!(range.accepts(0, 51));
+        assert!(range.accepts(0, 58));
+        assert!(!range.accepts(0, 50));
+        assert!(!range.accepts(0, 59));
+        assert!(!range.accepts(1, 55));
+
+        assert_eq!(matrix.pjrt_api_range("0.5.1").unwrap().describe(), "0.55 or later");
+        assert!(matrix.pjrt_api_range("0.4.20").is_none());
+    }
+
     #[test]
     fn test_compatibility_check() {
         let matrix = CompatibilityMatrix::load();
@@ -0,0 +1,275 @@
+//! Known runtime flags
+//!
+//! Flags passed to libtpu through `LIBTPU_INIT_ARGS` (CFG-007) and to XLA
+//! through `XLA_FLAGS` (CFG-001) that are dangerous to leave on in
+//! production, deprecated, or experimental, with the fix to suggest; the XLA
+//! flags these checks recognize and the value each takes; and XLA flag pairs
+//! that contradict each other. Flags are named without the leading `--`.
+//!
+//! XLA aborts at startup on a flag in `XLA_FLAGS` it does not know, and
+//! TPU-specific flags (`xla_tpu_*`, `xla_jf_*`) are only known to libtpu, so
+//! they belong in `LIBTPU_INIT_ARGS`.
+
+/// Why a flag is reported
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlagConcern {
+    /// Changes numerics, slows every step, or fails on some hardware
+    Dangerous,
+    /// No longer honored, or replaced by another flag
+    Deprecated,
+    /// Not production-ready; reported for information only
+    Experimental,
+}
+
+/// A flag tpu-doc warns about
+#[derive(Debug, Clone)]
+pub struct FlagRule {
+    /// Flag name, or a name prefix when it ends with `*`
+    pub name: &'static str,
+    pub concern: FlagConcern,
+    pub reason: &'static str,
+    pub remediation: &'static str,
+}
+
+impl FlagRule {
+    /// Whether this rule covers the flag `name`
+    pub fn matches(&self, name: &str) -> bool {
+        match self.name.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == self.name,
+        }
+    }
+}
+
+/// Flags reported in `LIBTPU_INIT_ARGS`
+pub const LIBTPU_FLAG_RULES: &[FlagRule] = &[
+    FlagRule {
+        name: "tpu_megacore",
+        concern: FlagConcern::Deprecated,
+        reason: "forces a megacore mode; libtpu picks one itself on chips that have megacore (v4, v5p), \
+                 and a forced mode fails at initialization on chips that do not",
+        remediation: "remove the flag and let libtpu choose",
+    },
+    FlagRule {
+        name: "xla_tpu_megacore_mode",
+        concern: FlagConcern::Deprecated,
+        reason: "forces a megacore mode; libtpu picks one itself on chips that have megacore (v4, v5p), \
+                 and a forced mode fails at initialization on chips that do not",
+        remediation: "remove the flag and let libtpu choose",
+    },
+    FlagRule {
+        name: "xla_tpu_spmd_rng_bit_generator_unsafe",
+        concern: FlagConcern::Dangerous,
+        reason: "generates random numbers per shard, so results differ from an unsharded run",
+        remediation: "remove the flag unless bit-exact reproducibility across shardings does not matter",
+    },
+    FlagRule {
+        name: "xla_tpu_enable_all_experimental_scheduler_features",
+        concern: FlagConcern::Dangerous,
+        reason: "turns on scheduler features that are not production-ready",
+        remediation: "enable the individual scheduler flags you need instead",
+    },
+    FlagRule {
+        name: "xla_dump_*",
+        concern: FlagConcern::Dangerous,
+        reason: "dumps HLO on every compilation, slowing compiles and filling the disk",
+        remediation: "remove XLA dump flags outside debugging sessions",
+    },
+    FlagRule {
+        name: "tpu_log_*",
+        concern: FlagConcern::Dangerous,
+        reason: "verbose libtpu logging slows the runtime and floods the logs",
+        remediation: "remove libtpu logging flags outside debugging sessions",
+    },
+];
+
+/// The rule reported for flag `name`, if any
+pub fn find_libtpu_rule(name: &str) -> Option<&'static FlagRule> {
+    LIBTPU_FLAG_RULES.iter().find(|rule| rule.matches(name))
+}
+
+/// The value a flag takes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlagValue {
+    /// `--name`, `--name=true`, or `--name=false`
+    Bool,
+    Int,
+    /// Any non-empty text: a path, a regular expression, a pass list
+    Text,
+}
+
+/// An XLA flag CFG-001 recognizes
+#[derive(Debug, Clone)]
+pub struct KnownFlag {
+    pub name: &'static str,
+    pub value: FlagValue,
+}
+
+const fn known(name: &'static str, value: FlagValue) -> KnownFlag {
+    KnownFlag { name, value }
+}
+
+/// Backend-independent XLA flags commonly set in `XLA_FLAGS`
+pub const KNOWN_XLA_FLAGS: &[KnownFlag] = &[
+    known("xla_dump_to", FlagValue::Text),
+    known("xla_dump_hlo_as_text", FlagValue::Bool),
+    known("xla_dump_hlo_as_proto", FlagValue::Bool),
+    known("xla_dump_hlo_as_dot", FlagValue::Bool),
+    known("xla_dump_hlo_as_html", FlagValue::Bool),
+    known("xla_dump_hlo_as_url", FlagValue::Bool),
+    known("xla_dump_hlo_module_re", FlagValue::Text),
+    known("xla_dump_hlo_pass_re", FlagValue::Text),
+    known("xla_dump_hlo_pipeline_re", FlagValue::Text),
+    known("xla_dump_include_timestamp", FlagValue::Bool),
+    known("xla_dump_max_hlo_modules", FlagValue::Int),
+    known("xla_dump_module_metadata", FlagValue::Bool),
+    known("xla_dump_large_constants", FlagValue::Bool),
+    known("xla_dump_disable_metadata", FlagValue::Bool),
+    known("xla_dump_fusion_visualization", FlagValue::Bool),
+    known("xla_disable_hlo_passes", FlagValue::Text),
+    known("xla_enable_hlo_passes_only", FlagValue::Text),
+    known("xla_disable_all_hlo_passes", FlagValue::Bool),
+    known("xla_backend_optimization_level", FlagValue::Int),
+    known("xla_hlo_profile", FlagValue::Bool),
+    known("xla_detailed_logging", FlagValue::Bool),
+    known("xla_enable_dumping", FlagValue::Bool),
+    known("xla_force_host_platform_device_count", FlagValue::Int),
+    known("xla_llvm_disable_expensive_passes", FlagValue::Bool),
+    known("xla_allow_excess_precision", FlagValue::Bool),
+    known("xla_step_marker_location", FlagValue::Text),
+    known("xla_enable_async_all_gather", FlagValue::Bool),
+    known("xla_enable_async_reduce_scatter", FlagValue::Bool),
+    known("xla_enable_async_all_reduce", FlagValue::Bool),
+    known("xla_enable_async_collective_permute", FlagValue::Bool),
+    known("xla_latency_hiding_scheduler_rerun", FlagValue::Int),
+    known("xla_experimental_ignore_channel_id", FlagValue::Bool),
+];
+
+/// TPU flags users put in `XLA_FLAGS` by mistake; used to suggest
+/// corrections for misspelled flags
+pub const KNOWN_LIBTPU_FLAGS: &[&str] = &[
+    "xla_tpu_enable_latency_hiding_scheduler",
+    "xla_tpu_enable_async_collective_fusion",
+    "xla_tpu_enable_async_collective_fusion_fuse_all_gather",
+    "xla_tpu_enable_async_collective_fusion_multiple_steps",
+    "xla_tpu_overlap_compute_collective_tc",
+    "xla_tpu_enable_data_parallel_all_reduce_opt",
+    "xla_tpu_data_parallel_opt_different_sized_ops",
+    "xla_tpu_megacore_fusion_allow_ags",
+    "xla_tpu_enable_windowed_einsum_for_all_gather",
+    "xla_tpu_enable_windowed_einsum_for_reduce_scatter",
+    "xla_jf_spmd_threshold_for_windowed_einsum_mib",
+    "xla_jf_rematerialization_percent_shared_memory_limit",
+];
+
+/// Name prefixes of flags only libtpu understands
+pub const LIBTPU_FLAG_PREFIXES: &[&str] = &["xla_tpu_", "xla_jf_", "xla_sc_", "tpu_"];
+
+/// Name prefixes of flags for other backends, ignored on TPU
+pub const OTHER_BACKEND_FLAG_PREFIXES: &[&str] = &["xla_gpu_", "xla_cpu_"];
+
+/// Flags reported in `XLA_FLAGS`
+pub const XLA_FLAG_RULES: &[FlagRule] = &[
+    FlagRule {
+        name: "xla_dump_*",
+        concern: FlagConcern::Dangerous,
+        reason: "dumps HLO on every compilation, slowing compiles and filling the disk",
+        remediation: "remove XLA dump flags for production workloads",
+    },
+    FlagRule {
+        name: "xla_hlo_profile",
+        concern: FlagConcern::Dangerous,
+        reason: "instruments every HLO instruction, slowing each step",
+        remediation: "profile with jax.profiler instead",
+    },
+    FlagRule {
+        name: "xla_disable_hlo_passes",
+        concern: FlagConcern::Dangerous,
+        reason: "disables HLO optimization passes",
+        remediation: "remove it unless working around a specific compiler bug",
+    },
+    FlagRule {
+        name: "xla_disable_all_hlo_passes",
+        concern: FlagConcern::Dangerous,
+        reason: "disables every HLO optimization pass",
+        remediation: "remove it; programs run unoptimized",
+    },
+    FlagRule {
+        name: "xla_experimental_*",
+        concern: FlagConcern::Experimental,
+        reason: "experimental XLA behavior",
+        remediation: "review experimental flags for stability",
+    },
+];
+
+/// XLA flag pairs that contradict each other when both are set
+pub const CONFLICTING_XLA_FLAGS: &[(&str, &str, &str)] = &[
+    (
+        "xla_disable_hlo_passes",
+        "xla_enable_hlo_passes_only",
+        "are mutually exclusive; XLA rejects the combination",
+    ),
+    (
+        "xla_disable_all_hlo_passes",
+        "xla_enable_hlo_passes_only",
+        "contradict each other: one disables every pass, the other selects passes to run",
+    ),
+    (
+        "xla_disable_all_hlo_passes",
+        "xla_disable_hlo_passes",
+        "overlap: disabling all passes makes the disabled-pass list meaningless",
+    ),
+];
+
+/// The known XLA flag `name`, if any
+pub fn find_xla_flag(name: &str) -> Option<&'static KnownFlag> {
+    KNOWN_XLA_FLAGS.iter().find(|flag| flag.name == name)
+}
+
+/// The rule reported for XLA flag `name`, if any
+pub fn find_xla_rule(name: &str) -> Option<&'static FlagRule> {
+    XLA_FLAG_RULES.iter().find(|rule| rule.matches(name))
+}
+
+/// The known flag `name` is most likely a misspelling of, if any
+pub fn suggest_flag(name: &str) -> Option<&'static str> {
+    KNOWN_XLA_FLAGS
+        .iter()
+        .map(|flag| flag.name)
+        .chain(KNOWN_LIBTPU_FLAGS.iter().copied())
+        .map(|known| (crate::util::edit_distance(name, known), known))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_libtpu_rule() {
+        assert_eq!(find_libtpu_rule("tpu_megacore").unwrap().concern, FlagConcern::Deprecated);
+        assert_eq!(find_libtpu_rule("xla_dump_to").unwrap().concern, FlagConcern::Dangerous);
+        assert!(find_libtpu_rule("xla_tpu_enable_latency_hiding_scheduler").is_none());
+        assert!(find_libtpu_rule("xla_tpu_megacore_fusion_allow_ags").is_none());
+    }
+
+    #[test]
+    fn test_xla_flag_tables() {
+        assert_eq!(find_xla_flag("xla_dump_to").unwrap().value, FlagValue::Text);
+        assert!(find_xla_flag("xla_dump_tp").is_none());
+        assert_eq!(find_xla_rule("xla_dump_hlo_as_text").unwrap().concern, FlagConcern::Dangerous);
+        assert_eq!(find_xla_rule("xla_experimental_ignore_channel_id").unwrap().concern, FlagConcern::Experimental);
+        for (a, b, _) in CONFLICTING_XLA_FLAGS {
+            assert!(find_xla_flag(a).is_some() && find_xla_flag(b).is_some());
+        }
+
+        assert_eq!(suggest_flag("xla_dump_tp"), Some("xla_dump_to"));
+        assert_eq!(
+            suggest_flag("xla_tpu_enable_latency_hidng_scheduler"),
+            Some("xla_tpu_enable_latency_hiding_scheduler")
+        );
+        assert_eq!(suggest_flag("completely_different"), None);
+    }
+}
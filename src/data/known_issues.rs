@@ -0,0 +1,275 @@
+//! Known-issues database for the TPU software stack
+//!
+//! Records bugs in specific jax, jaxlib, libtpu, and TPU driver releases,
+//! with a workaround and the release that fixed them. Used by the
+//! `known-issues` command and surfaced as warnings by the version checks
+//! (STK-001, STK-002, STK-003, HW-006).
+//!
+//! An issue affects versions from `introduced` (inclusive) up to `fixed_in`
+//! (exclusive); an issue with no `fixed_in` affects every later release.
+
+use std::cmp::Ordering;
+
+/// A bug in a specific range of releases of one package
+#[derive(Debug, Clone)]
+pub struct KnownIssue {
+    pub id: String,
+    /// Package the issue is in: `jax`, `jaxlib`, `libtpu`, or `driver`
+    pub package: String,
+    /// First affected version
+    pub introduced: String,
+    /// First version with the fix, if one has been released
+    pub fixed_in: Option<String>,
+    pub title: String,
+    pub description: String,
+    pub workaround: String,
+}
+
+impl KnownIssue {
+    /// Whether `version` of this issue's package is affected
+    pub fn affects(&self, version: &str) -> bool {
+        compare_versions(version, &self.introduced) != Ordering::Less
+            && self
+                .fixed_in
+                .as_deref()
+                .is_none_or(|fixed| compare_versions(version, fixed) == Ordering::Less)
+    }
+
+    /// "fixed in jax 0.4.33" or "not yet fixed"
+    pub fn fix_summary(&self) -> String {
+        match &self.fixed_in {
+            Some(version) => format!("fixed in {} {}", self.package, version),
+            None => "not yet fixed".to_string(),
+        }
+    }
+}
+
+/// The known-issues database
+#[derive(Debug)]
+pub struct KnownIssueDb {
+    pub version: String,
+    pub issues: Vec<KnownIssue>,
+}
+
+impl KnownIssueDb {
+    /// Load the embedded known-issues database
+    pub fn load() -> Self {
+        KnownIssueDb {
+            version: "1.0".to_string(),
+            issues: vec![
+                issue(
+                    "KI-001",
+                    "jax",
+                    "0.4.1",
+                    Some("0.4.14"),
+                    "Persistent compilation cache disabled on TPU by default",
+                    "Releases before 0.4.14 only use the persistent compilation cache when it \
+                     is initialized explicitly, so every process recompiles its programs from \
+                     scratch. Large models pay minutes of compile time on each restart.",
+                    "Call jax.experimental.compilation_cache.compilation_cache.initialize_cache(<dir>) \
+                     at startup, or upgrade JAX",
+                ),
+                issue(
+                    "KI-002",
+                    "jax",
+                    "0.4.32",
+                    Some("0.4.33"),
+                    "jax 0.4.32 yanked for a TPU data corruption bug",
+                    "jax/jaxlib 0.4.32 were yanked from PyPI because of a bug that could \
+                     silently corrupt data on TPU. Environments pinned to this release still \
+                     install it from caches and mirrors.",
+                    "Install a newer release: pip install -U \"jax[tpu]\"",
+                ),
+                issue(
+                    "KI-003",
+                    "jaxlib",
+                    "0.4.32",
+                    Some("0.4.33"),
+                    "jaxlib 0.4.32 yanked for a TPU data corruption bug",
+                    "jaxlib 0.4.32 was yanked together with jax 0.4.32 because of a bug that \
+                     could silently corrupt data on TPU.",
+                    "Install a newer release: pip install -U \"jax[tpu]\"",
+                ),
+                issue(
+                    "KI-004",
+                    "jax",
+                    "0.4.26",
+                    Some("0.4.31"),
+                    "jax.distributed.initialize() can hang when hosts start late",
+                    "On multi-host slices, workers that start more than a few minutes after \
+                     the coordinator can time out in the initialization barrier and hang \
+                     instead of exiting with an error.",
+                    "Start all workers together (--worker=all), or pass a larger \
+                     initialization_timeout to jax.distributed.initialize()",
+                ),
+                issue(
+                    "KI-005",
+                    "jaxlib",
+                    "0.4.1",
+                    Some("0.4.26"),
+                    "jaxlib built against NumPy 1.x crashes with NumPy 2",
+                    "jaxlib releases before 0.4.26 were built against the NumPy 1.x ABI and \
+                     fail to import (or crash) once NumPy 2.0 is installed.",
+                    "Pin numpy<2 or upgrade to jax/jaxlib 0.4.26 or later",
+                ),
+                issue(
+                    "KI-006",
+                    "libtpu",
+                    "0.1.0",
+                    None,
+                    "Nightly libtpu builds are not release-tested",
+                    "libtpu-nightly builds (versioned 0.1.devYYYYMMDD) are unreleased \
+                     snapshots. They regularly ship regressions and are not guaranteed to \
+                     match any released jaxlib.",
+                    "Install the release build matching jaxlib: pip install -U \"jax[tpu]\"",
+                ),
+                issue(
+                    "KI-007",
+                    "libtpu",
+                    "0.0.1",
+                    Some("0.0.3"),
+                    "Megascale multi-slice jobs hang on DCN timeouts",
+                    "Early standalone libtpu releases can hang instead of failing when a \
+                     data-center-network transfer between slices times out, leaving \
+                     multi-slice jobs stuck with no error.",
+                    "Upgrade libtpu, or set a job-level timeout so stuck runs are restarted",
+                ),
+                issue(
+                    "KI-008",
+                    "driver",
+                    "0.0.0",
+                    Some("1.6.0"),
+                    "Old TPU driver reports HBM errors as generic device failures",
+                    "TPU VM images with a driver older than 1.6.0 surface HBM ECC errors as \
+                     an opaque \"device failure\", which makes hardware faults look like \
+                     software crashes.",
+                    "Recreate the TPU VM with a current runtime version (--version / \
+                     --runtime-version)",
+                ),
+            ],
+        }
+    }
+
+    /// Get an issue by ID
+    pub fn get(&self, id: &str) -> Option<&KnownIssue> {
+        self.issues.iter().find(|i| i.id.eq_ignore_ascii_case(id))
+    }
+
+    /// Issues affecting `version` of `package`
+    pub fn find(&self, package: &str, version: &str) -> Vec<&KnownIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.package.eq_ignore_ascii_case(package) && i.affects(version))
+            .collect()
+    }
+}
+
+/// Details text listing `issues`, for a check's warning
+pub fn format_check_details(issues: &[&KnownIssue]) -> String {
+    issues
+        .iter()
+        .map(|i| {
+            format!(
+                "{}: {} ({}). Workaround: {}",
+                i.id,
+                i.title,
+                i.fix_summary(),
+                i.workaround
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn issue(
+    id: &str,
+    package: &str,
+    introduced: &str,
+    fixed_in: Option<&str>,
+    title: &str,
+    description: &str,
+    workaround: &str,
+) -> KnownIssue {
+    KnownIssue {
+        id: id.to_string(),
+        package: package.to_string(),
+        introduced: introduced.to_string(),
+        fixed_in: fixed_in.map(|v| v.to_string()),
+        title: title.to_string(),
+        description: description.to_string(),
+        workaround: workaround.to_string(),
+    }
+}
+
+/// Compare two release versions component by component.
+///
+/// Only the leading numeric components count: `0.1.dev20241028` compares as
+/// `0.1`, and `0.4.35+cuda` as `0.4.35`. Missing components are zero.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (numeric_components(a), numeric_components(b));
+    for i in 0..a.len().max(b.len()) {
+        let ordering = a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+fn numeric_components(version: &str) -> Vec<u64> {
+    let mut components = Vec::new();
+    for part in version.trim().trim_start_matches('v').split(['.', '+', '-']) {
+        let digits: String = part.chars().take_while(|c| c.is_ascii_digit()).collect();
+        match digits.parse() {
+            Ok(n) => components.push(n),
+            Err(_) => break,
+        }
+        if digits.len() < part.len() {
+            break;
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_known_issues() {
+        let db = KnownIssueDb::load();
+        assert!(!db.issues.is_empty());
+        for (i, issue) in db.issues.iter().enumerate() {
+            assert_eq!(issue.id, format!("KI-{:03}", i + 1));
+            assert!(["jax", "jaxlib", "libtpu", "driver"].contains(&issue.package.as_str()));
+        }
+        assert!(db.get("ki-002").is_some());
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("0.4.35", "0.4.4"), Ordering::Greater);
+        assert_eq!(compare_versions("0.4", "0.4.0"), Ordering::Equal);
+        assert_eq!(compare_versions("0.1.dev20241028", "0.1.0"), Ordering::Equal);
+        assert_eq!(compare_versions("0.4.31rc1", "0.4.31"), Ordering::Equal);
+        assert_eq!(compare_versions("1.5.2", "1.6.0"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_find_applicable_issues() {
+        let db = KnownIssueDb::load();
+        let ids = |package, version| {
+            db.find(package, version)
+                .iter()
+                .map(|i| i.id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids("jax", "0.4.32"), vec!["KI-002"]);
+        assert_eq!(ids("jax", "0.4.30"), vec!["KI-004"]);
+        assert!(ids("jax", "0.4.35").is_empty());
+        assert_eq!(ids("libtpu", "0.1.dev20241028"), vec!["KI-006"]);
+        assert!(ids("libtpu", "0.0.5").is_empty());
+        assert_eq!(ids("JAXLIB", "0.4.20"), vec!["KI-005"]);
+    }
+}
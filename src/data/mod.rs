@@ -1,6 +1,12 @@
 //! Data module for tpu-doc
 //!
-//! Contains compatibility matrix, TPU specifications, and known issues database.
+//! Contains compatibility matrix, TPU specifications, expected-performance
+//! baselines, known issues database, known-problem runtime flags, and the
+//! error signature database used for offline log analysis.
 
+pub mod baselines;
 pub mod compatibility;
+pub mod flags;
+pub mod known_issues;
+pub mod signatures;
 pub mod specs;
@@ -0,0 +1,404 @@
+//! Known-error signature database for offline log analysis
+//!
+//! Maps log patterns for common TPU failures to a diagnosis and
+//! remediation steps, so `analyze` works without network access or an API key.
+//!
+//! Patterns are matched case-insensitively against each log line. A `*` in a
+//! pattern matches any run of characters, so `resource_exhausted*hbm` matches
+//! both words in that order on one line.
+
+/// How serious a matched failure is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl IssueSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IssueSeverity::Error => "error",
+            IssueSeverity::Warning => "warning",
+            IssueSeverity::Info => "info",
+        }
+    }
+}
+
+/// A known failure and how to recognize it
+#[derive(Debug, Clone)]
+pub struct ErrorSignature {
+    pub id: String,
+    pub title: String,
+    pub severity: IssueSeverity,
+    /// Any one of these patterns on a line is a match
+    pub patterns: Vec<String>,
+    pub diagnosis: String,
+    pub remediation: Vec<String>,
+}
+
+/// A signature found in a log
+#[derive(Debug, Clone)]
+pub struct SignatureMatch<'a> {
+    pub signature: &'a ErrorSignature,
+    /// 1-based line number of the first matching line
+    pub first_line: usize,
+    /// The first matching line, trimmed
+    pub line: String,
+    /// Number of matching lines
+    pub occurrences: usize,
+}
+
+/// The signature database
+#[derive(Debug)]
+pub struct SignatureDb {
+    pub version: String,
+    pub signatures: Vec<ErrorSignature>,
+}
+
+impl SignatureDb {
+    /// Load the embedded signature database
+    pub fn load() -> Self {
+        SignatureDb {
+            version: "1.0".to_string(),
+            signatures: vec![
+                signature(
+                    "SIG-001",
+                    "TPU backend failed to initialize",
+                    IssueSeverity::Error,
+                    &[
+                        "unable to initialize backend 'tpu'",
+                        "unable to initialize backend \"tpu\"",
+                        "failed to initialize tpu system",
+                        "tpu platform not found",
+                    ],
+                    "JAX could not bring up the TPU runtime. libtpu is missing or \
+                     mismatched, the TPU devices are not visible, or another process \
+                     already holds them.",
+                    &[
+                        "Run `tpu-doc check --category hardware` to confirm the TPU devices are visible",
+                        "Reinstall a matching runtime: pip install -U \"jax[tpu]\" -f https://storage.googleapis.com/jax-releases/libtpu_releases.html",
+                        "Check for another process using the TPU: sudo lsof -w /dev/accel* /dev/vfio/*",
+                    ],
+                ),
+                signature(
+                    "SIG-002",
+                    "TPU locked by another process",
+                    IssueSeverity::Error,
+                    &[
+                        "libtpu_lockfile",
+                        "tpu*already in use",
+                        "tpu*in use by process",
+                        "the tpu is already in use",
+                    ],
+                    "libtpu allows one process per host to own the TPU. Another process \
+                     (often a previous run or a notebook kernel) still holds it, or a stale \
+                     lockfile was left behind by a crashed run.",
+                    &[
+                        "Find the owner: sudo lsof -w /dev/accel* /dev/vfio/*",
+                        "Stop that process (or restart the notebook kernel)",
+                        "If no process holds the TPU, remove the stale lock: rm /tmp/libtpu_lockfile",
+                    ],
+                ),
+                signature(
+                    "SIG-003",
+                    "TPU HBM out of memory",
+                    IssueSeverity::Error,
+                    &[
+                        "resource_exhausted*hbm",
+                        "ran out of memory in memory space hbm",
+                        "attempting to reserve*hbm",
+                        "out of memory while trying to allocate",
+                        "resource_exhausted: xla:tpu_compile",
+                    ],
+                    "The program needs more TPU high-bandwidth memory than one chip has. \
+                     Activations, optimizer state, or a large compiled program exceeded the \
+                     per-chip HBM capacity.",
+                    &[
+                        "Reduce the per-device batch size",
+                        "Enable rematerialization (jax.checkpoint / gradient checkpointing)",
+                        "Shard parameters and optimizer state across chips (FSDP / pjit sharding)",
+                        "Check `tpu-doc check HW-002` for HBM already held by another process",
+                    ],
+                ),
+                signature(
+                    "SIG-004",
+                    "Illegal instruction (SIGILL)",
+                    IssueSeverity::Error,
+                    &["illegal instruction", "sigill", "signal 4 "],
+                    "A binary used a CPU instruction this machine does not support. This \
+                     typically happens when TPU wheels (jaxlib/libtpu) run on a non-TPU VM \
+                     or an older CPU, or when a wheel was built for a newer instruction set (AVX-512).",
+                    &[
+                        "Confirm this is a TPU VM: `tpu-doc info`",
+                        "Install the CPU build of jax on non-TPU machines: pip install -U jax",
+                        "Reinstall jaxlib from the official index rather than a locally built wheel",
+                    ],
+                ),
+                signature(
+                    "SIG-005",
+                    "No TPU devices found",
+                    IssueSeverity::Error,
+                    &[
+                        "no tpu devices found",
+                        "no visible tpu",
+                        "failed to get global tpu topology",
+                        "falling back to cpu",
+                    ],
+                    "The framework started but found no TPU devices and fell back to CPU (or \
+                     gave up). The TPU driver may not be loaded, the container may lack the \
+                     device mounts, or JAX_PLATFORMS excludes tpu.",
+                    &[
+                        "Check the devices exist: ls /dev/accel* /dev/vfio",
+                        "In containers, run with --privileged or mount the TPU devices",
+                        "Unset JAX_PLATFORMS or set it to include tpu",
+                    ],
+                ),
+                signature(
+                    "SIG-006",
+                    "jax/jaxlib version mismatch",
+                    IssueSeverity::Error,
+                    &[
+                        "jaxlib version*is older than",
+                        "jaxlib version*is newer than",
+                        "jax requires jaxlib",
+                        "incompatible with jaxlib",
+                    ],
+                    "The installed jax and jaxlib packages are from different releases; jax \
+                     refuses to run with an incompatible jaxlib.",
+                    &[
+                        "Install matching versions together: pip install -U \"jax[tpu]\"",
+                        "Run `tpu-doc stack` to see the detected versions",
+                    ],
+                ),
+                signature(
+                    "SIG-007",
+                    "Multi-host coordination failure",
+                    IssueSeverity::Error,
+                    &[
+                        "deadline_exceeded*barrier",
+                        "barrier timed out",
+                        "failed to connect to coordination service",
+                        "coordination service*unavailable",
+                        "heartbeat timeout",
+                    ],
+                    "Hosts in a multi-host slice could not reach each other or the \
+                     coordinator. Usually not every worker started the job, one worker \
+                     crashed, or firewall rules block intra-slice traffic.",
+                    &[
+                        "Start the job on all workers: gcloud compute tpus tpu-vm ssh ... --worker=all",
+                        "Check the logs of the other workers for an earlier crash",
+                        "Check that workers can reach the coordinator address and port (JAX default 8476)",
+                    ],
+                ),
+                signature(
+                    "SIG-008",
+                    "Permission denied on TPU device",
+                    IssueSeverity::Error,
+                    &[
+                        "permission denied*/dev/accel",
+                        "permission denied*/dev/vfio",
+                        "/dev/accel*permission denied",
+                        "/dev/vfio*permission denied",
+                    ],
+                    "The process cannot open the TPU device files. It runs as a user without \
+                     access, or inside a container without the devices.",
+                    &[
+                        "Run as a user in the group owning /dev/accel* (or with sudo)",
+                        "In containers, run with --privileged or mount the TPU devices",
+                    ],
+                ),
+                signature(
+                    "SIG-009",
+                    "Host killed by the OOM killer",
+                    IssueSeverity::Error,
+                    &[
+                        "out of memory: killed process",
+                        "oom-kill",
+                        "killed process*total-vm",
+                    ],
+                    "The host (not TPU) ran out of RAM and the kernel killed the process. \
+                     Large input pipelines, host-side checkpoint loading, or XLA compilation \
+                     of very large programs are common causes.",
+                    &[
+                        "Reduce input pipeline prefetch and shuffle buffer sizes",
+                        "Load checkpoints shard-by-shard rather than all at once",
+                        "Check host memory with `tpu-doc snapshot`",
+                    ],
+                ),
+                signature(
+                    "SIG-010",
+                    "Cloud Storage access denied",
+                    IssueSeverity::Error,
+                    &[
+                        "does not have storage.objects",
+                        "403*storage.googleapis.com",
+                        "accessdeniedexception: 403",
+                    ],
+                    "The VM's service account lacks permission on a Cloud Storage bucket \
+                     used for data or checkpoints.",
+                    &[
+                        "Grant the service account roles/storage.objectAdmin (or objectViewer) on the bucket",
+                        "Check the VM has the cloud-platform or storage access scope",
+                        "Run `tpu-doc check SEC-001` to see the active service account",
+                    ],
+                ),
+                signature(
+                    "SIG-011",
+                    "Disk full",
+                    IssueSeverity::Error,
+                    &["no space left on device", "disk quota exceeded"],
+                    "A write failed because the filesystem is full, often from checkpoints \
+                     or the XLA compilation cache accumulating on the boot disk.",
+                    &[
+                        "Free space or write checkpoints to Cloud Storage",
+                        "Clean the compilation cache: `tpu-doc cache clean`",
+                        "Run `tpu-doc check IO-004` to see available space",
+                    ],
+                ),
+                signature(
+                    "SIG-012",
+                    "TPU preempted or under maintenance",
+                    IssueSeverity::Warning,
+                    &[
+                        "preempted",
+                        "maintenance event",
+                        "tpu_preempted",
+                    ],
+                    "The TPU was reclaimed (Spot/preemptible capacity) or a host maintenance \
+                     event interrupted the job. This is not a bug in the workload.",
+                    &[
+                        "Resume from the latest checkpoint",
+                        "Checkpoint more frequently on Spot capacity",
+                        "Use reserved capacity for jobs that cannot tolerate interruption",
+                    ],
+                ),
+                signature(
+                    "SIG-013",
+                    "NaN or Inf in training",
+                    IssueSeverity::Warning,
+                    &[
+                        "loss*nan",
+                        "invalid value (nan)",
+                        "floatingpointerror",
+                        "jax_debug_nans",
+                    ],
+                    "Training produced NaN or infinite values, usually from a too-high \
+                     learning rate, an unstable loss, or bf16 overflow.",
+                    &[
+                        "Lower the learning rate or add warmup",
+                        "Enable gradient clipping",
+                        "Locate the first NaN with JAX_DEBUG_NANS=True",
+                    ],
+                ),
+            ],
+        }
+    }
+
+    /// Get a signature by ID
+    pub fn get(&self, id: &str) -> Option<&ErrorSignature> {
+        self.signatures.iter().find(|s| s.id.eq_ignore_ascii_case(id))
+    }
+
+    /// Find every signature present in `log`, in order of first appearance
+    pub fn match_log(&self, log: &str) -> Vec<SignatureMatch<'_>> {
+        let patterns: Vec<Vec<String>> = self
+            .signatures
+            .iter()
+            .map(|s| s.patterns.iter().map(|p| p.to_lowercase()).collect())
+            .collect();
+        let mut matches: Vec<SignatureMatch> = Vec::new();
+
+        for (index, line) in log.lines().enumerate() {
+            let lower = line.to_lowercase();
+            for (signature, patterns) in self.signatures.iter().zip(&patterns) {
+                if !patterns.iter().any(|p| pattern_matches(p, &lower)) {
+                    continue;
+                }
+                match matches.iter_mut().find(|m| m.signature.id == signature.id) {
+                    Some(existing) => existing.occurrences += 1,
+                    None => matches.push(SignatureMatch {
+                        signature,
+                        first_line: index + 1,
+                        line: line.trim().to_string(),
+                        occurrences: 1,
+                    }),
+                }
+            }
+        }
+
+        matches
+    }
+}
+
+fn signature(
+    id: &str,
+    title: &str,
+    severity: IssueSeverity,
+    patterns: &[&str],
+    diagnosis: &str,
+    remediation: &[&str],
+) -> ErrorSignature {
+    ErrorSignature {
+        id: id.to_string(),
+        title: title.to_string(),
+        severity,
+        patterns: patterns.iter().map(|p| p.to_string()).collect(),
+        diagnosis: diagnosis.to_string(),
+        remediation: remediation.iter().map(|r| r.to_string()).collect(),
+    }
+}
+
+/// Match a lowercase pattern, where `*` matches any run of characters,
+/// anywhere in a lowercase line
+fn pattern_matches(pattern: &str, line: &str) -> bool {
+    let mut rest = line;
+    for part in pattern.split('*').filter(|p| !p.is_empty()) {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_signatures() {
+        let db = SignatureDb::load();
+        assert!(!db.signatures.is_empty());
+        for (i, signature) in db.signatures.iter().enumerate() {
+            assert_eq!(signature.id, format!("SIG-{:03}", i + 1));
+            assert!(!signature.patterns.is_empty());
+            assert!(!signature.remediation.is_empty());
+        }
+        assert!(db.get("sig-003").is_some());
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("resource_exhausted*hbm", "error: resource_exhausted: out of hbm"));
+        assert!(!pattern_matches("resource_exhausted*hbm", "hbm then resource_exhausted"));
+        assert!(pattern_matches("illegal instruction", "illegal instruction (core dumped)"));
+    }
+
+    #[test]
+    fn test_match_log() {
+        let log = "I0101 starting\n\
+                   E0101 RuntimeError: Unable to initialize backend 'tpu': ABORTED: \
+                   The TPU is already in use by process with pid 1234\n\
+                   Fatal Python error: Illegal instruction\n\
+                   Illegal instruction (core dumped)\n";
+        let db = SignatureDb::load();
+        let matches = db.match_log(log);
+
+        let ids: Vec<&str> = matches.iter().map(|m| m.signature.id.as_str()).collect();
+        assert_eq!(ids, vec!["SIG-001", "SIG-002", "SIG-004"]);
+        assert_eq!(matches[0].first_line, 2);
+        assert_eq!(matches[2].occurrences, 2);
+        assert!(db.match_log("step 100 loss=2.31\n").is_empty());
+    }
+}
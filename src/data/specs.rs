@@ -1,16 +1,75 @@
 //! TPU hardware specifications
 //!
-//! Provides TPU hardware specifications for different TPU types.
+//! Provides TPU hardware specifications for different TPU types. This is
+//! the single source of per-generation figures: the platform layer, the
+//! PERF checks, and library consumers all read them from here.
+//!
+//! # Example
+//!
+//! ```
+//! use tpu_doc::data::specs::{self, TpuSpecs};
+//! use tpu_doc::platform::tpu::TpuType;
+//!
+//! let v5e = specs::spec_for(&TpuType::V5e).unwrap();
+//! assert_eq!(v5e.hbm_per_chip_gb, 16);
+//! assert!(v5e.supports_topology("4x4"));
+//!
+//! for spec in &TpuSpecs::load().specs {
+//!     println!("{}: {:.0} TFLOPS bf16 per chip", spec.name, spec.peak_bf16_flops() / 1e12);
+//! }
+//! ```
+
+use crate::platform::tpu::TpuType;
 
-/// TPU type specification
+/// TPU type specification (per chip unless noted)
 #[derive(Debug, Clone)]
 pub struct TpuTypeSpec {
+    /// Generation name as in accelerator types (`v5e`, `v6e`, ...)
     pub name: String,
+    /// HBM capacity per chip in GB
     pub hbm_per_chip_gb: u32,
+    /// Chip counts a single host can have
     pub chips_per_host: Vec<u32>,
+    /// TensorCores per chip
+    pub cores_per_chip: u32,
     pub mxu_count: u32,
+    /// Peak dense BF16 compute in TFLOPS
     pub bf16_tflops: u32,
+    /// HBM bandwidth in GB/s
+    pub hbm_bandwidth_gbps: u32,
+    /// Aggregate ICI bandwidth across all links, in Gbps
     pub ici_bandwidth_gbps: u32,
+    /// ICI links per chip: 6 for 3D torus generations, 4 for 2D
+    pub ici_links_per_chip: u32,
+    /// Slice topologies (chip mesh shapes) that can be provisioned
+    pub supported_topologies: Vec<String>,
+}
+
+impl TpuTypeSpec {
+    /// Peak dense BF16 compute in FLOP/s
+    pub fn peak_bf16_flops(&self) -> f64 {
+        self.bf16_tflops as f64 * 1e12
+    }
+
+    /// HBM capacity per chip in bytes
+    pub fn hbm_bytes(&self) -> u64 {
+        self.hbm_per_chip_gb as u64 * 1024 * 1024 * 1024
+    }
+
+    /// Whether `shape` (e.g. `2x2x1`, `4x4`) is a supported slice topology
+    pub fn supports_topology(&self, shape: &str) -> bool {
+        self.supported_topologies.iter().any(|t| t.eq_ignore_ascii_case(shape.trim()))
+    }
+
+    /// Whether chips are connected in a 3D torus (v4, v5p, v7) rather than a 2D one
+    pub fn is_3d_torus(&self) -> bool {
+        self.ici_links_per_chip == 6
+    }
+}
+
+/// Specification for a detected TPU type, or None for unknown types
+pub fn spec_for(tpu_type: &TpuType) -> Option<TpuTypeSpec> {
+    TpuSpecs::load().for_type(tpu_type).cloned()
 }
 
 /// TPU specifications database
@@ -30,39 +89,72 @@ impl TpuSpecs {
                     name: "v4".to_string(),
                     hbm_per_chip_gb: 32,
                     chips_per_host: vec![4],
+                    cores_per_chip: 2,
                     mxu_count: 2,
                     bf16_tflops: 275,
+                    hbm_bandwidth_gbps: 1200,
                     ici_bandwidth_gbps: 4800,
+                    ici_links_per_chip: 6,
+                    supported_topologies: topologies(&["2x2x1", "2x2x2", "2x2x4", "2x4x4", "4x4x4", "4x4x8", "4x8x8", "8x8x8", "8x8x16", "8x16x16"]),
                 },
                 TpuTypeSpec {
                     name: "v5e".to_string(),
                     hbm_per_chip_gb: 16,
                     chips_per_host: vec![1, 4, 8],
+                    cores_per_chip: 1,
                     mxu_count: 1,
                     bf16_tflops: 197,
+                    hbm_bandwidth_gbps: 800,
                     ici_bandwidth_gbps: 1600,
+                    ici_links_per_chip: 4,
+                    supported_topologies: topologies(&["1x1", "2x2", "2x4", "4x4", "4x8", "8x8", "8x16", "16x16"]),
                 },
                 TpuTypeSpec {
                     name: "v5p".to_string(),
                     hbm_per_chip_gb: 95,
                     chips_per_host: vec![4],
+                    cores_per_chip: 2,
                     mxu_count: 2,
                     bf16_tflops: 459,
+                    hbm_bandwidth_gbps: 1600,
                     ici_bandwidth_gbps: 4800,
+                    ici_links_per_chip: 6,
+                    supported_topologies: topologies(&["2x2x1", "2x2x2", "2x2x4", "2x4x4", "4x4x4", "4x4x8", "4x8x8", "8x8x8", "8x8x16", "8x16x16", "16x16x16"]),
                 },
                 TpuTypeSpec {
                     name: "v6e".to_string(),
                     hbm_per_chip_gb: 32,
                     chips_per_host: vec![1, 4, 8],
+                    cores_per_chip: 1,
                     mxu_count: 1,
                     bf16_tflops: 918,
+                    hbm_bandwidth_gbps: 1800,
                     ici_bandwidth_gbps: 3584,
+                    ici_links_per_chip: 4,
+                    supported_topologies: topologies(&["1x1", "2x2", "2x4", "4x4", "4x8", "8x8", "8x16", "16x16"]),
+                },
+                TpuTypeSpec {
+                    name: "v7".to_string(),
+                    hbm_per_chip_gb: 192,
+                    chips_per_host: vec![4],
+                    cores_per_chip: 2,
+                    mxu_count: 4,
+                    bf16_tflops: 2307,
+                    hbm_bandwidth_gbps: 2000,
+                    ici_bandwidth_gbps: 9600,
+                    ici_links_per_chip: 6,
+                    supported_topologies: topologies(&["2x2x1", "2x2x2", "2x2x4", "2x4x4", "4x4x4", "4x4x8", "4x8x8", "8x8x8"]),
                 },
             ],
         }
     }
 
-    /// Get specification for a TPU type
+    /// Get specification for a detected TPU type
+    pub fn for_type(&self, tpu_type: &TpuType) -> Option<&TpuTypeSpec> {
+        self.get_spec(&tpu_type.to_string())
+    }
+
+    /// Get specification for a TPU type by name
     pub fn get_spec(&self, tpu_type: &str) -> Option<&TpuTypeSpec> {
         self.specs.iter().find(|s| s.name.eq_ignore_ascii_case(tpu_type))
     }
@@ -88,6 +180,15 @@ impl TpuSpecs {
     pub fn get_peak_tflops(&self, tpu_type: &str) -> Option<u32> {
         self.get_spec(tpu_type).map(|s| s.bf16_tflops)
     }
+
+    /// Get expected HBM bandwidth per chip (GB/s) for a TPU type
+    pub fn get_hbm_bandwidth_gbps(&self, tpu_type: &str) -> Option<u32> {
+        self.get_spec(tpu_type).map(|s| s.hbm_bandwidth_gbps)
+    }
+}
+
+fn topologies(shapes: &[&str]) -> Vec<String> {
+    shapes.iter().map(|s| s.to_string()).collect()
 }
 
 #[cfg(test)]
@@ -124,4 +225,31 @@ mod tests {
         assert!(specs.is_valid_chip_count("v5e", 4));
         assert!(specs.is_valid_chip_count("v5e", 8));
     }
+
+    #[test]
+    fn test_spec_for_type() {
+        let v5p = spec_for(&TpuType::V5p).unwrap();
+        assert_eq!(v5p.cores_per_chip, 2);
+        assert!(v5p.is_3d_torus());
+        assert!(v5p.supports_topology("4x4x8"));
+        assert!(!v5p.supports_topology("4x4"));
+        assert_eq!(v5p.hbm_bytes(), 95 * 1024 * 1024 * 1024);
+        assert_eq!(v5p.peak_bf16_flops(), 459e12);
+        assert!(spec_for(&TpuType::Unknown).is_none());
+
+        // Every TpuType except Unknown has a spec
+        for tpu_type in [TpuType::V4, TpuType::V5e, TpuType::V5p, TpuType::V6e, TpuType::V7] {
+            assert!(spec_for(&tpu_type).is_some(), "no spec for {}", tpu_type);
+        }
+    }
+
+    #[test]
+    fn test_peak_figures() {
+        let specs = TpuSpecs::load();
+
+        assert_eq!(specs.get_peak_tflops("v4"), Some(275));
+        assert_eq!(specs.get_peak_tflops("V6E"), Some(918));
+        assert_eq!(specs.get_hbm_bandwidth_gbps("v5p"), Some(1600));
+        assert_eq!(specs.get_hbm_bandwidth_gbps("unknown"), None);
+    }
 }
@@ -0,0 +1,287 @@
+//! Result history between runs.
+//!
+//! After each `check` run the latest result of every executed check is kept
+//! under `~/.tpu-preflight/history/<ID>.json`, in the JSON report format
+//! (results kept under the old `~/.tpu-doc/history` are still read). With
+//! `check --cached <TTL>`, checks tagged [`CheckTag::Cacheable`] (benchmarks
+//! and throughput measurements, which are slow and change slowly) reuse a
+//! result younger than the TTL instead of running again. Cached results say
+//! so in their message and carry a [`CACHED_DATA_KEY`] data entry, and are
+//! never written back, so a result ages out even if it keeps being reused.
+//!
+//! The store is best-effort: unreadable entries are treated as missing and
+//! write failures are ignored.
+
+use crate::cli::output::{JsonFormatter, OutputFormatter};
+use crate::engine::orchestrator::RegisteredCheck;
+use crate::engine::result::{parse_json_report, ValidationReport};
+use crate::util;
+use crate::{Check, CheckResult, CheckTag};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Structured data key marking a result reused from history, e.g. `12m ago`
+pub const CACHED_DATA_KEY: &str = "cached";
+
+/// Latest check results, one file per check ID
+#[derive(Debug, Clone)]
+pub struct ResultHistory {
+    dir: PathBuf,
+    legacy_dir: Option<PathBuf>,
+}
+
+impl ResultHistory {
+    /// History at the default location (`~/.tpu-preflight/history`), falling
+    /// back to `~/.tpu-doc/history` for reads
+    pub fn open_default() -> Option<Self> {
+        let history = Self::new(util::state_dir()?.join("history"));
+        Some(match util::legacy_state_dir() {
+            Some(legacy) => history.with_legacy_dir(legacy.join("history")),
+            None => history,
+        })
+    }
+
+    /// History rooted at `dir`
+    pub fn new(dir: PathBuf) -> Self {
+        ResultHistory { dir, legacy_dir: None }
+    }
+
+    /// Also read results missing from the history directory from `dir`
+    pub fn with_legacy_dir(mut self, dir: PathBuf) -> Self {
+        self.legacy_dir = Some(dir);
+        self
+    }
+
+    /// Directory entries are stored in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The latest result of check `id` and its age in seconds, if it is no
+    /// older than `ttl_secs`
+    pub fn get(&self, id: &str, ttl_secs: u64) -> Option<(CheckResult, u64)> {
+        let path = self.entry_path(id);
+        let path = match &self.legacy_dir {
+            Some(legacy) if !path.exists() => legacy.join(path.file_name()?),
+            _ => path,
+        };
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+        let age_secs = SystemTime::now().duration_since(modified).unwrap_or_default().as_secs();
+        if age_secs > ttl_secs {
+            return None;
+        }
+
+        let report = parse_json_report(&fs::read_to_string(&path).ok()?).ok()?;
+        let check = report.checks.into_iter().find(|c| c.id.eq_ignore_ascii_case(id))?;
+        check.result.map(|result| (result, age_secs))
+    }
+
+    /// Keep the results in `report`. Skipped and cached results are not
+    /// stored, so they never replace a real measurement.
+    pub fn record(&self, report: &ValidationReport) {
+        if fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+
+        let formatter = JsonFormatter::new(true);
+        for check in &report.checks {
+            if is_cached(check) || matches!(check.result, None | Some(CheckResult::Skip { .. })) {
+                continue;
+            }
+            let entry = ValidationReport {
+                timestamp: report.timestamp,
+                run_id: report.run_id.clone(),
+                hostname: report.hostname.clone(),
+                tpu_type: report.tpu_type.clone(),
+                checks: vec![check.clone()],
+                total_duration_ms: 0,
+                started_at_ms: report.started_at_ms,
+                estimates: Vec::new(),
+                diagnostics: Vec::new(),
+            };
+
+            // Write then rename so a concurrent reader never sees a partial entry
+            let path = self.entry_path(&check.id);
+            let tmp = path.with_extension("tmp");
+            if fs::write(&tmp, formatter.format(&entry)).is_ok() && fs::rename(&tmp, &path).is_err() {
+                let _ = fs::remove_file(&tmp);
+            }
+        }
+    }
+
+    /// `check` with its function replaced by a history lookup when it is
+    /// cacheable: a result younger than `ttl_secs` is returned, marked as
+    /// cached, and the check only runs when there is none
+    pub fn cached_check(&self, mut check: RegisteredCheck, ttl_secs: u64) -> RegisteredCheck {
+        if !check.tags.contains(&CheckTag::Cacheable) {
+            return check;
+        }
+
+        let history = self.clone();
+        let id = check.id.clone();
+        let run = check.check_fn;
+        check.check_fn = Box::new(move || match history.get(&id, ttl_secs) {
+            Some((result, age_secs)) => mark_cached(result, age_secs),
+            None => run(),
+        });
+        check
+    }
+
+    fn entry_path(&self, id: &str) -> PathBuf {
+        let name: String = id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c.to_ascii_uppercase() } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.json", name))
+    }
+}
+
+/// Whether `check`'s result was reused from history
+pub fn is_cached(check: &Check) -> bool {
+    check
+        .result
+        .as_ref()
+        .is_some_and(|result| result.data().iter().any(|(key, _)| key == CACHED_DATA_KEY))
+}
+
+/// A stored result presented as cached: no time spent this run, and the
+/// message and data say how old it is
+fn mark_cached(result: CheckResult, age_secs: u64) -> CheckResult {
+    let age = format!("{} ago", format_age(age_secs));
+    let note = |message: String| format!("{} (cached {})", message, age);
+    let noted = |mut data: Vec<(String, String)>| {
+        data.retain(|(key, _)| key != CACHED_DATA_KEY);
+        data.push((CACHED_DATA_KEY.to_string(), age.clone()));
+        data
+    };
+
+    match result {
+        CheckResult::Pass { message, data, .. } => CheckResult::Pass {
+            message: note(message),
+            duration_ms: 0,
+            data: noted(data),
+        },
+        CheckResult::Warn { message, details, data, .. } => CheckResult::Warn {
+            message: note(message),
+            details,
+            duration_ms: 0,
+            data: noted(data),
+        },
+        CheckResult::Fail { message, details, data, .. } => CheckResult::Fail {
+            message: note(message),
+            details,
+            duration_ms: 0,
+            data: noted(data),
+        },
+        skip @ CheckResult::Skip { .. } => skip,
+    }
+}
+
+/// Render an age in seconds as the largest whole unit (e.g. "3h", "12m")
+fn format_age(secs: u64) -> String {
+    if secs >= 86400 {
+        format!("{}d", secs / 86400)
+    } else if secs >= 3600 {
+        format!("{}h", secs / 3600)
+    } else if secs >= 60 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CheckCategory;
+
+    fn report(checks: Vec<Check>) -> ValidationReport {
+        ValidationReport {
+            checks,
+            ..Default::default()
+        }
+    }
+
+    fn check(id: &str, result: CheckResult) -> Check {
+        Check {
+            id: id.to_string(),
+            name: id.to_string(),
+            category: CheckCategory::Performance,
+            description: String::new(),
+            result: Some(result),
+            start_ms: Some(0),
+            ownership: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_record_and_get() {
+        let dir = std::env::temp_dir().join(format!("tpu-doc-history-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let history = ResultHistory::new(dir.clone());
+
+        history.record(&report(vec![
+            check(
+                "PERF-002",
+                CheckResult::Warn {
+                    message: "HBM bandwidth 700 GB/s".to_string(),
+                    details: "Below 85% of expected".to_string(),
+                    duration_ms: 4000,
+                    data: vec![("bandwidth".to_string(), "700 GB/s".to_string())],
+                },
+            ),
+            check("PERF-003", CheckResult::Skip { reason: "single chip".to_string() }),
+        ]));
+
+        let (result, age_secs) = history.get("perf-002", 3600).unwrap();
+        assert!(age_secs < 60);
+        assert!(matches!(&result, CheckResult::Warn { message, duration_ms: 4000, .. } if message == "HBM bandwidth 700 GB/s"));
+        assert_eq!(result.data(), &[("bandwidth".to_string(), "700 GB/s".to_string())]);
+        assert!(history.get("PERF-003", 3600).is_none());
+        assert!(history.get("PERF-001", 3600).is_none());
+
+        // A cached result is not written back
+        let cached = check("PERF-002", mark_cached(result, 0));
+        assert!(is_cached(&cached));
+        history.record(&report(vec![cached]));
+        assert!(!history.get("PERF-002", 3600).unwrap().0.data().iter().any(|(k, _)| k == CACHED_DATA_KEY));
+
+        // Results kept under the old directory are still read
+        let moved = ResultHistory::new(dir.join("new")).with_legacy_dir(dir.clone());
+        assert!(moved.get("PERF-002", 3600).is_some());
+        assert!(moved.get("PERF-001", 3600).is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_cached_check() {
+        let dir = std::env::temp_dir().join(format!("tpu-doc-history-cached-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let history = ResultHistory::new(dir.clone());
+        let pass = |message: &str| CheckResult::Pass {
+            message: message.to_string(),
+            duration_ms: 5,
+            data: Vec::new(),
+        };
+        history.record(&report(vec![check("PERF-001", pass("stored")), check("HW-001", pass("stored"))]));
+
+        let perf = RegisteredCheck::new("PERF-001", "MXU", CheckCategory::Performance, move || pass("fresh"))
+            .with_tags(&[CheckTag::TpuExclusive, CheckTag::Cacheable]);
+        let hw = RegisteredCheck::new("HW-001", "Devices", CheckCategory::Hardware, move || pass("fresh"));
+
+        match (history.cached_check(perf, 3600).check_fn)() {
+            CheckResult::Pass { message, duration_ms, data } => {
+                assert_eq!(message, "stored (cached 0s ago)");
+                assert_eq!(duration_ms, 0);
+                assert_eq!(data, vec![(CACHED_DATA_KEY.to_string(), "0s ago".to_string())]);
+            }
+            other => panic!("expected pass, got {:?}", other),
+        }
+        // Not cacheable: always runs
+        assert!(matches!((history.cached_check(hw, 3600).check_fn)(), CheckResult::Pass { message, .. } if message == "fresh"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,6 +1,12 @@
 //! Validation engine module.
 //!
-//! Provides check orchestration and result aggregation.
+//! Provides check orchestration, result aggregation, the result history
+//! between runs, the failure downgrade policy, the check ownership overlay,
+//! and report signing.
 
+pub mod history;
 pub mod orchestrator;
+pub mod ownership;
+pub mod policy;
 pub mod result;
+pub mod signing;
@@ -6,20 +6,59 @@
 //!
 //! This module handles errors gracefully:
 //! - Check panics: Caught via std::panic::catch_unwind, converted to Fail result
-//! - Check timeout: Returns Fail result with timeout message (when parallel enabled)
-//! - Dependency failure: Continues with remaining checks unless fail_fast
+//! - Check timeout: Returns Fail result with timeout message (when parallel enabled);
+//!   performance checks are held to `bench_timeout_ms` instead of `timeout_ms`,
+//!   and sustained-load checks to their estimated duration on top of it
+//! - Fail-fast: Checks not started when a failure stops the run are
+//!   reported as skipped (`not run due to fail-fast after HW-001`), so the
+//!   report accounts for every selected check
+//! - Dependency failure: Checks that depend on a failed check, directly or
+//!   through checks skipped for the same reason, are skipped with the
+//!   failed check named (`dependency HW-001 failed`); the others continue
+//!   unless fail_fast
+//! - Policy: Each result is downgraded by the `[policy]` rules as it is
+//!   recorded, before fail-fast and dependency skips look at it, so a
+//!   failure the policy accepts does neither
+//! - Circular dependencies: Checks in a cycle are reported as failed with
+//!   the cycle, and not run (see [`CheckOrchestrator::dependency_cycle`])
+//! - Duplicate check IDs: Rejected at registration with an error naming
+//!   them, so every ID selects, skips, and reports exactly one check
 //! - Invalid check ID: Silently skipped in run_specific/run_excluding
 //! - Empty check list: Returns empty report (not an error)
+//! - Cancellation: Stops starting checks and kills the commands the running
+//!   ones started; those checks are reported as skipped and the partial
+//!   report is returned
+//! - Time budget: With `max_duration_ms`, checks whose estimated duration no
+//!   longer fits in the remaining budget are reported as skipped instead of run
+//!
+//! [`CheckOrchestrator::run_all_streamed`] runs on a background thread and
+//! yields each check's result as it completes, for callers that forward
+//! results to their own telemetry instead of waiting for the report.
 //!
 //! The orchestrator ensures all registered checks complete (or are skipped)
 //! regardless of individual check failures, unless fail_fast is enabled.
 //! No function in this module will panic.
 
+use crate::checks::settings::RunSettings;
+use crate::checks::timeouts;
+use crate::engine::policy::Policy;
 use crate::engine::result::{ResultAggregator, ValidationReport};
+use crate::platform::diagnostics::DiagnosticLog;
 use crate::platform::{linux, tpu};
-use crate::{Check, CheckCategory, CheckResult};
-use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use crate::util::time;
+use crate::{Check, CheckCategory, CheckOwnership, CheckResult, CheckSeverity, CheckTag, TpuDocError};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// How often a cancellable run looks at its cancellation token
+const CANCEL_POLL_MS: u64 = 50;
+
+/// How long a cancelled run waits for a running check to return once its
+/// commands have been killed
+const CANCEL_GRACE_MS: u64 = 1000;
 
 /// Orchestrator configuration
 #[derive(Debug, Clone)]
@@ -27,7 +66,32 @@ pub struct OrchestratorConfig {
     pub parallel: bool,
     pub fail_fast: bool,
     pub timeout_ms: u64,
+    /// Timeout for performance checks, which run benchmarks, in place of
+    /// `timeout_ms`
+    pub bench_timeout_ms: u64,
     pub max_parallel: usize,
+    /// Budget for the whole run; checks that would overrun it are skipped
+    pub max_duration_ms: Option<u64>,
+    /// ID to report the run under, such as the training job's (None = a new
+    /// ID for each run)
+    pub run_id: Option<String>,
+    /// Downgrade rules applied to each result as it is recorded
+    pub policy: Policy,
+    /// Settings the checks read while they run
+    pub settings: RunSettings,
+}
+
+impl OrchestratorConfig {
+    /// The timeout `check` is held to, and its name for timeout failures
+    fn timeout_for(&self, check: &RegisteredCheck) -> (u64, &'static str) {
+        if check.tags.contains(&CheckTag::Sustained) {
+            (check.estimated_duration_ms.saturating_add(self.bench_timeout_ms), "benchmark timeout")
+        } else if check.category == CheckCategory::Performance {
+            (self.bench_timeout_ms, "benchmark timeout")
+        } else {
+            (self.timeout_ms, "global timeout")
+        }
+    }
 }
 
 impl Default for OrchestratorConfig {
@@ -36,11 +100,58 @@ impl Default for OrchestratorConfig {
             parallel: false,
             fail_fast: false,
             timeout_ms: 30000,
-            max_parallel: 4,
+            bench_timeout_ms: timeouts::DEFAULT_BENCH_TIMEOUT_MS,
+            max_parallel: default_max_parallel(),
+            max_duration_ms: None,
+            run_id: None,
+            policy: Policy::default(),
+            settings: RunSettings::default(),
         }
     }
 }
 
+/// A handle that stops a check run from another thread.
+///
+/// Clones share the same state. Once cancelled, the orchestrator starts no
+/// further checks, kills the commands the running ones started (through
+/// [`linux::with_cancellation`]), waits briefly for those checks to return
+/// (they are reported as skipped), and returns the partial report.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a token that has not been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel every run using this token
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Run `f` so that commands it starts are killed when the token is cancelled
+    fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        linux::with_cancellation(Arc::clone(&self.cancelled), f)
+    }
+}
+
+/// Parallelism for CPU-bound checks: the CPUs available to this process,
+/// between 2 and 16. Network-bound checks do not count toward the limit.
+pub fn default_max_parallel() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .clamp(2, 16)
+}
+
 /// A registered check with its execution function
 pub struct RegisteredCheck {
     pub id: String,
@@ -50,12 +161,98 @@ pub struct RegisteredCheck {
     pub check_fn: Box<dyn Fn() -> CheckResult + Send + Sync>,
     pub dependencies: Vec<String>,
     pub estimated_duration_ms: u64,
+    /// How the check uses the host; untagged checks are treated as CPU-bound
+    pub tags: Vec<CheckTag>,
+    /// How much a failure of this check matters
+    pub severity: CheckSeverity,
+    /// Who owns the check and where its documentation and runbook are
+    pub ownership: CheckOwnership,
+}
+
+impl RegisteredCheck {
+    /// Create a check with no dependencies, a one-second duration estimate,
+    /// and major severity.
+    ///
+    /// Used by library consumers to add their own checks alongside the
+    /// built-ins (see [`crate::run_checks_with`]).
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        category: CheckCategory,
+        check_fn: impl Fn() -> CheckResult + Send + Sync + 'static,
+    ) -> Self {
+        RegisteredCheck {
+            id: id.into(),
+            name: name.into(),
+            category,
+            description: String::new(),
+            check_fn: Box::new(check_fn),
+            dependencies: Vec::new(),
+            estimated_duration_ms: 1000,
+            tags: Vec::new(),
+            severity: CheckSeverity::Major,
+            ownership: Default::default(),
+        }
+    }
+
+    /// Set the description shown in reports
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Set the IDs of checks that must run before this one
+    pub fn with_dependencies(mut self, dependencies: &[&str]) -> Self {
+        self.dependencies = dependencies.iter().map(|d| d.to_string()).collect();
+        self
+    }
+
+    /// Set how the check uses the host (see [`CheckTag`])
+    pub fn with_tags(mut self, tags: &[CheckTag]) -> Self {
+        self.tags = tags.to_vec();
+        self
+    }
+
+    /// Set how much a failure matters (see [`CheckSeverity`])
+    pub fn with_severity(mut self, severity: CheckSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Set the team or person failures are routed to
+    pub fn with_owner(mut self, owner: impl Into<String>) -> Self {
+        self.ownership.owner = Some(owner.into());
+        self
+    }
+
+    /// Set the URL of the check's documentation
+    pub fn with_docs_url(mut self, docs_url: impl Into<String>) -> Self {
+        self.ownership.docs_url = Some(docs_url.into());
+        self
+    }
+
+    /// Set the URL of the runbook for the check's failures
+    pub fn with_runbook_url(mut self, runbook_url: impl Into<String>) -> Self {
+        self.ownership.runbook_url = Some(runbook_url.into());
+        self
+    }
+
+    /// Whether the check mostly waits on the network
+    pub fn is_network_bound(&self) -> bool {
+        self.tags.contains(&CheckTag::NetworkBound)
+    }
+
+    /// Whether the check drives the TPU devices
+    pub fn is_tpu_exclusive(&self) -> bool {
+        self.tags.contains(&CheckTag::TpuExclusive)
+    }
 }
 
 /// Check orchestrator
 pub struct CheckOrchestrator {
     config: OrchestratorConfig,
-    checks: Vec<RegisteredCheck>,
+    checks: Vec<Arc<RegisteredCheck>>,
+    cancellation: Option<CancellationToken>,
 }
 
 impl CheckOrchestrator {
@@ -64,17 +261,48 @@ impl CheckOrchestrator {
         CheckOrchestrator {
             config,
             checks: Vec::new(),
+            cancellation: None,
         }
     }
 
-    /// Register checks for execution
-    pub fn register_checks(&mut self, checks: Vec<RegisteredCheck>) {
-        self.checks.extend(checks);
+    /// Stop runs when `token` is cancelled.
+    ///
+    /// With a token set, each check runs on its own thread so that the run
+    /// can stop waiting for it.
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation = Some(token);
     }
 
-    /// Register a single check
-    pub fn register_check(&mut self, check: RegisteredCheck) {
-        self.checks.push(check);
+    /// Register checks for execution.
+    ///
+    /// IDs are compared ignoring case. When any of `checks` has the ID of a
+    /// registered check, or of another of `checks`, none is registered and
+    /// the error names the duplicate IDs.
+    pub fn register_checks(&mut self, checks: Vec<RegisteredCheck>) -> Result<(), TpuDocError> {
+        let mut duplicates: Vec<String> = Vec::new();
+        for (i, check) in checks.iter().enumerate() {
+            let taken = self.checks.iter().map(|c| &c.id).chain(checks[..i].iter().map(|c| &c.id));
+            if let Some(first) = taken.into_iter().find(|id| id.eq_ignore_ascii_case(&check.id)) {
+                if !duplicates.iter().any(|d| d.eq_ignore_ascii_case(first)) {
+                    duplicates.push(first.clone());
+                }
+            }
+        }
+        if !duplicates.is_empty() {
+            return Err(TpuDocError::DuplicateCheckIds { ids: duplicates });
+        }
+        self.checks.extend(checks.into_iter().map(Arc::new));
+        Ok(())
+    }
+
+    /// Register a single check; an error when its ID is already registered
+    pub fn register_check(&mut self, check: RegisteredCheck) -> Result<(), TpuDocError> {
+        self.register_checks(vec![check])
+    }
+
+    /// The registered checks, in registration order
+    pub fn checks(&self) -> &[Arc<RegisteredCheck>] {
+        &self.checks
     }
 
     /// Run all registered checks
@@ -82,6 +310,29 @@ impl CheckOrchestrator {
         self.run_checks(&self.checks.iter().map(|c| c.id.clone()).collect::<Vec<_>>())
     }
 
+    /// Run all registered checks on a background thread, yielding each
+    /// check's result as it completes.
+    ///
+    /// Results arrive in completion order, including skip results for
+    /// checks not run, and the iterator ends when the run does.
+    /// [`StreamedRun::report`] waits for the run and returns its report,
+    /// which holds the same results.
+    pub fn run_all_streamed(&self) -> StreamedRun {
+        let check_ids: Vec<String> = self.checks.iter().map(|c| c.id.clone()).collect();
+        let runner = CheckOrchestrator {
+            config: self.config.clone(),
+            checks: self.checks.clone(),
+            cancellation: self.cancellation.clone(),
+        };
+        let (sender, results) = mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            let mut aggregator = runner.new_aggregator();
+            aggregator.stream_to(sender);
+            runner.run_into(&check_ids, aggregator)
+        });
+        StreamedRun { results, handle }
+    }
+
     /// Run checks in a specific category
     pub fn run_category(&self, category: CheckCategory) -> ValidationReport {
         let ids: Vec<String> = self
@@ -120,255 +371,326 @@ impl CheckOrchestrator {
         self.run_checks(&ids)
     }
 
+    /// The first dependency cycle among `check_ids`, as the chain of IDs
+    /// from a check back to itself (e.g. `["A", "B", "A"]`). Dependencies
+    /// on checks outside `check_ids` are ignored, as they are when running.
+    pub fn dependency_cycle(&self, check_ids: &[String]) -> Option<Vec<String>> {
+        fn visit(
+            id: &str,
+            checks: &[Arc<RegisteredCheck>],
+            check_ids: &[String],
+            path: &mut Vec<String>,
+            done: &mut std::collections::HashSet<String>,
+        ) -> Option<Vec<String>> {
+            if let Some(start) = path.iter().position(|p| p == id) {
+                let mut chain = path[start..].to_vec();
+                chain.push(id.to_string());
+                return Some(chain);
+            }
+            if done.contains(id) {
+                return None;
+            }
+
+            path.push(id.to_string());
+            if let Some(check) = checks.iter().find(|c| c.id == id) {
+                for dep in check.dependencies.iter().filter(|dep| check_ids.contains(dep)) {
+                    if let Some(chain) = visit(dep, checks, check_ids, path, done) {
+                        return Some(chain);
+                    }
+                }
+            }
+            path.pop();
+            done.insert(id.to_string());
+            None
+        }
+
+        let mut done = std::collections::HashSet::new();
+        check_ids
+            .iter()
+            .find_map(|id| visit(id, &self.checks, check_ids, &mut Vec::new(), &mut done))
+    }
+
+    /// `check_ids` without the checks in dependency cycles, and a failed
+    /// result for each check left out
+    fn break_cycles(&self, check_ids: &[String]) -> (Vec<String>, Vec<Check>) {
+        let mut runnable = check_ids.to_vec();
+        let mut failed = Vec::new();
+        while let Some(chain) = self.dependency_cycle(&runnable) {
+            let members = &chain[..chain.len() - 1];
+            for check in self.checks.iter().filter(|c| members.contains(&c.id)) {
+                let result = CheckResult::Fail {
+                    message: format!("Circular dependency: {}", chain.join(" -> ")),
+                    details: "These checks depend on each other, so none of them can run first. \
+                              Remove one of the dependencies from the check or plugin that declares it."
+                        .to_string(),
+                    duration_ms: 0,
+                    data: Vec::new(),
+                };
+                failed.push(unstarted_result(check, result));
+            }
+            runnable.retain(|id| !members.contains(id));
+        }
+        (runnable, failed)
+    }
+
+    /// Aggregator for a run, which fixes the run's ID as the run starts
+    fn new_aggregator(&self) -> ResultAggregator {
+        let mut aggregator = ResultAggregator::new();
+        if let Some(run_id) = &self.config.run_id {
+            aggregator.set_run_id(run_id);
+        }
+        aggregator
+    }
+
     /// Execute the specified checks
     fn run_checks(&self, check_ids: &[String]) -> ValidationReport {
+        self.run_into(check_ids, self.new_aggregator())
+    }
+
+    /// Execute the specified checks, adding their results to `aggregator`
+    fn run_into(&self, check_ids: &[String], aggregator: ResultAggregator) -> ValidationReport {
         let start = Instant::now();
-        let aggregator = Arc::new(Mutex::new(ResultAggregator::new()));
+        let aggregator = Arc::new(Mutex::new(aggregator));
+
+        let (check_ids, cyclic) = self.break_cycles(check_ids);
+        if let Ok(mut agg) = aggregator.lock() {
+            for check in cyclic {
+                agg.add_result(check);
+            }
+        }
 
         // Get checks to run in order (respecting dependencies)
-        let ordered_checks = self.resolve_dependencies(check_ids);
+        let ordered_checks = self.resolve_dependencies(&check_ids);
 
+        let diagnostics = DiagnosticLog::default();
         if self.config.parallel {
-            self.run_parallel(&ordered_checks, aggregator.clone());
+            self.run_parallel(&ordered_checks, aggregator.clone(), start, &diagnostics);
         } else {
-            self.run_sequential(&ordered_checks, aggregator.clone());
+            self.run_sequential(&ordered_checks, aggregator.clone(), start, &diagnostics);
         }
+        self.skip_unstarted(&ordered_checks, &aggregator);
 
-        let total_duration_ms = start.elapsed().as_millis() as u64;
-
-        // Build report
-        let hostname = linux::get_hostname().unwrap_or_else(|_| "unknown".to_string());
-        let tpu_type = tpu::get_tpu_type().ok().map(|t| t.to_string());
-
-        // Handle potential mutex poisoning gracefully
-        let report = match aggregator.lock() {
-            Ok(mut agg) => {
-                agg.set_metadata(hostname, tpu_type, total_duration_ms);
-                agg.to_report()
-            }
-            Err(poisoned) => {
-                // If the mutex is poisoned, recover the data anyway
-                let mut agg = poisoned.into_inner();
-                agg.set_metadata(hostname, tpu_type, total_duration_ms);
-                agg.to_report()
-            }
-        };
-        report
+        finish_report(&aggregator, start, &self.checks, &diagnostics)
     }
 
     /// Run checks sequentially
-    fn run_sequential(&self, check_ids: &[String], aggregator: Arc<Mutex<ResultAggregator>>) {
+    fn run_sequential(
+        &self,
+        check_ids: &[String],
+        aggregator: Arc<Mutex<ResultAggregator>>,
+        run_start: Instant,
+        diagnostics: &DiagnosticLog,
+    ) {
+        let mut failures = HashMap::new();
         for check_id in check_ids {
+            if self.is_cancelled() {
+                break;
+            }
             if let Some(check) = self.checks.iter().find(|c| &c.id == check_id) {
-                let result = self.execute_check(check);
-
-                let check_struct = Check {
-                    id: check.id.clone(),
-                    name: check.name.clone(),
-                    category: check.category.clone(),
-                    description: check.description.clone(),
-                    result: Some(result.clone()),
-                };
+                let start_ms = elapsed_ms(run_start);
+                let result = dependency_skip(check, &failures)
+                    .or_else(|| self.over_budget(check, run_start))
+                    .unwrap_or_else(|| self.execute_with_policy(check, diagnostics));
+                record_failure(&mut failures, check, &result);
+                let failed = matches!(result, CheckResult::Fail { .. });
 
                 if let Ok(mut agg) = aggregator.lock() {
-                    agg.add_result(check_struct);
+                    agg.add_result(check_result(check, result, start_ms));
                 }
 
                 // Check for fail-fast
-                if self.config.fail_fast {
-                    if let CheckResult::Fail { .. } = result {
-                        break;
-                    }
+                if self.config.fail_fast && failed {
+                    break;
                 }
             }
         }
     }
 
-    /// Run checks in parallel (where safe)
-    fn run_parallel(&self, check_ids: &[String], aggregator: Arc<Mutex<ResultAggregator>>) {
+    /// Run checks in parallel on a pool of workers.
+    ///
+    /// Workers take checks from a shared ready queue, so a check starts as
+    /// soon as its dependencies have finished and its tags allow rather than
+    /// waiting for the slowest check in a batch. Checks with the most
+    /// estimated work behind them start first.
+    fn run_parallel(
+        &self,
+        check_ids: &[String],
+        aggregator: Arc<Mutex<ResultAggregator>>,
+        run_start: Instant,
+        diagnostics: &DiagnosticLog,
+    ) {
+        use std::sync::Condvar;
         use std::thread;
 
-        // Group checks that can run in parallel (no dependencies between them)
-        let mut remaining: Vec<String> = check_ids.to_vec();
-        let mut completed: Vec<String> = Vec::new();
-
-        while !remaining.is_empty() {
-            // Find checks that can run (all dependencies satisfied)
-            let runnable: Vec<String> = remaining
-                .iter()
-                .filter(|id| {
-                    if let Some(check) = self.checks.iter().find(|c| &c.id == *id) {
-                        check.dependencies.iter().all(|dep| completed.contains(dep))
-                    } else {
-                        false
-                    }
-                })
-                .cloned()
-                .collect();
-
-            if runnable.is_empty() {
-                // No checks can run - might have circular dependencies
-                // Fall back to running remaining sequentially
-                for id in &remaining {
-                    if let Some(check) = self.checks.iter().find(|c| &c.id == id) {
-                        let result = self.execute_check(check);
-                        let check_struct = Check {
-                            id: check.id.clone(),
-                            name: check.name.clone(),
-                            category: check.category.clone(),
-                            description: check.description.clone(),
-                            result: Some(result),
-                        };
-                        if let Ok(mut agg) = aggregator.lock() {
-                            agg.add_result(check_struct);
+        let queue = ReadyQueue::new(self.ordered(check_ids), true, self.config.max_parallel);
+        let workers = queue.max_concurrency();
+        let queue = Mutex::new(queue);
+        let changed = Condvar::new();
+        let fail_fast = self.config.fail_fast;
+
+        thread::scope(|s| {
+            for _ in 0..workers {
+                s.spawn(|| loop {
+                    let (check, skipped) = {
+                        let mut queue = queue.lock().unwrap_or_else(|p| p.into_inner());
+                        loop {
+                            if queue.is_drained() || self.is_cancelled() {
+                                return;
+                            }
+                            if let Some(check) = queue.next() {
+                                let skipped = dependency_skip(&check, &queue.failures);
+                                break (check, skipped);
+                            }
+                            // Wake periodically to notice cancellation
+                            queue = changed
+                                .wait_timeout(queue, Duration::from_millis(CANCEL_POLL_MS))
+                                .map(|(queue, _)| queue)
+                                .unwrap_or_else(|p| p.into_inner().0);
                         }
+                    };
+
+                    let start_ms = elapsed_ms(run_start);
+                    let result = skipped
+                        .or_else(|| self.over_budget(&check, run_start))
+                        .unwrap_or_else(|| self.execute_with_policy(&check, diagnostics));
+                    queue
+                        .lock()
+                        .unwrap_or_else(|p| p.into_inner())
+                        .finish(&check.id, &result, fail_fast);
+                    changed.notify_all();
+
+                    if let Ok(mut agg) = aggregator.lock() {
+                        agg.add_result(check_result(&check, result, start_ms));
                     }
-                }
-                break;
+                });
             }
+        });
+    }
 
-            // Run runnable checks in parallel (up to max_parallel)
-            let batch: Vec<_> = runnable
-                .iter()
-                .take(self.config.max_parallel)
-                .cloned()
-                .collect();
-
-            // Collect check info for parallel execution
-            let batch_checks: Vec<_> = batch
-                .iter()
-                .filter_map(|check_id| {
-                    self.checks.iter().find(|c| &c.id == check_id).map(|c| {
-                        (
-                            c.id.clone(),
-                            c.name.clone(),
-                            c.category.clone(),
-                            c.description.clone(),
-                            c.check_fn.as_ref() as *const (dyn Fn() -> CheckResult + Send + Sync),
-                        )
-                    })
-                })
-                .collect();
-
-            // Execute checks in parallel using scoped threads
-            let timeout_ms = self.config.timeout_ms;
-            let results: Vec<_> = thread::scope(|s| {
-                let handles: Vec<_> = batch_checks
-                    .iter()
-                    .map(|(id, name, category, description, check_fn_ptr)| {
-                        let id = id.clone();
-                        let name = name.clone();
-                        let category = category.clone();
-                        let description = description.clone();
-                        // Safety: check_fn_ptr is valid for the duration of this scope
-                        let check_fn = unsafe { &*(*check_fn_ptr) };
-                        s.spawn(move || {
-                            let start = Instant::now();
-                            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                                check_fn()
-                            }));
-                            let elapsed = start.elapsed().as_millis() as u64;
-
-                            let check_result = match result {
-                                Ok(r) => {
-                                    if elapsed > timeout_ms {
-                                        CheckResult::Fail {
-                                            message: format!("Check timed out after {}ms", elapsed),
-                                            details: "Check exceeded global timeout".to_string(),
-                                            duration_ms: elapsed,
-                                        }
-                                    } else {
-                                        r
-                                    }
-                                }
-                                Err(_) => CheckResult::Fail {
-                                    message: "Check panicked during execution".to_string(),
-                                    details: "An unexpected error occurred".to_string(),
-                                    duration_ms: elapsed,
-                                },
-                            };
-
-                            Check {
-                                id,
-                                name,
-                                category,
-                                description,
-                                result: Some(check_result),
-                            }
-                        })
-                    })
-                    .collect();
-
-                handles
-                    .into_iter()
-                    .filter_map(|h| h.join().ok())
-                    .collect()
-            });
+    fn is_cancelled(&self) -> bool {
+        self.cancellation.as_ref().is_some_and(|t| t.is_cancelled())
+    }
 
-            // Add results to aggregator
-            for check_struct in results {
-                if let Ok(mut agg) = aggregator.lock() {
-                    agg.add_result(check_struct);
-                }
-            }
+    /// After fail-fast stopped the run, add a skip result for each check in
+    /// `check_ids` that has none, naming the failure that stopped it
+    fn skip_unstarted(&self, check_ids: &[String], aggregator: &Mutex<ResultAggregator>) {
+        if !self.config.fail_fast || self.is_cancelled() {
+            return;
+        }
+        let mut agg = aggregator.lock().unwrap_or_else(|p| p.into_inner());
+        // Failures of checks outside `check_ids` (dependency cycles) did not stop the run
+        let Some(stopped_by) = agg
+            .get_failures()
+            .iter()
+            .map(|c| c.id.clone())
+            .find(|id| check_ids.contains(id))
+        else {
+            return;
+        };
 
-            // Mark as completed
-            for id in &batch {
-                completed.push(id.clone());
-                remaining.retain(|r| r != id);
+        for id in check_ids {
+            if agg.has_result(id) {
+                continue;
             }
-
-            // Check fail-fast
-            if self.config.fail_fast {
-                if let Ok(agg) = aggregator.lock() {
-                    if agg.has_failures() {
-                        break;
-                    }
-                }
+            if let Some(check) = self.checks.iter().find(|c| &c.id == id) {
+                let result = CheckResult::Skip {
+                    reason: format!("not run due to fail-fast after {}", stopped_by),
+                };
+                agg.add_result(unstarted_result(check, result));
             }
         }
     }
 
-    /// Execute a single check with timeout handling
-    fn execute_check(&self, check: &RegisteredCheck) -> CheckResult {
-        let start = Instant::now();
+    /// A skip result when `check` is not expected to finish within what is
+    /// left of the run's time budget, otherwise None
+    fn over_budget(&self, check: &RegisteredCheck, run_start: Instant) -> Option<CheckResult> {
+        let budget_ms = self.config.max_duration_ms?;
+        let remaining_ms = budget_ms.saturating_sub(elapsed_ms(run_start));
+        (check.estimated_duration_ms > remaining_ms).then(|| CheckResult::Skip {
+            reason: format!(
+                "Time budget exceeded: needs ~{}ms, {}ms of the {}ms budget left",
+                check.estimated_duration_ms, remaining_ms, budget_ms
+            ),
+        })
+    }
+
+    /// Execute a check and downgrade its result by the policy
+    fn execute_with_policy(&self, check: &Arc<RegisteredCheck>, diagnostics: &DiagnosticLog) -> CheckResult {
+        let result = self.execute(check, diagnostics);
+        self.config.policy.apply_result(&check.id, &check.category, result)
+    }
+
+    /// Execute a check, or with a cancellation token, run it on its own
+    /// thread and, if the token is cancelled, kill the commands it started
+    /// and wait up to [`CANCEL_GRACE_MS`] for it to return
+    fn execute(&self, check: &Arc<RegisteredCheck>, diagnostics: &DiagnosticLog) -> CheckResult {
+        let timeout = self.config.timeout_for(check);
+        let settings = self.config.settings;
+        let Some(token) = &self.cancellation else {
+            return execute_check(check, timeout, &settings, diagnostics);
+        };
 
-        // Execute the check function
-        // In a production implementation, we'd use panic::catch_unwind
-        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            (check.check_fn)()
-        }));
-
-        match result {
-            Ok(check_result) => {
-                // Check if we exceeded timeout
-                let elapsed = start.elapsed().as_millis() as u64;
-                if elapsed > self.config.timeout_ms {
-                    CheckResult::Fail {
-                        message: format!("Check timed out after {}ms", elapsed),
-                        details: "Check exceeded global timeout".to_string(),
-                        duration_ms: elapsed,
+        let (sender, receiver) = mpsc::channel();
+        let task = Arc::clone(check);
+        let task_diagnostics = diagnostics.clone();
+        let task_token = token.clone();
+        let spawned = std::thread::Builder::new()
+            .name(format!("check-{}", check.id))
+            .spawn(move || {
+                let _ = sender.send(task_token.scope(|| execute_check(&task, timeout, &settings, &task_diagnostics)));
+            });
+        let Ok(handle) = spawned else {
+            return token.scope(|| execute_check(check, timeout, &settings, diagnostics));
+        };
+
+        loop {
+            match receiver.recv_timeout(Duration::from_millis(CANCEL_POLL_MS)) {
+                Ok(result) => {
+                    let _ = handle.join();
+                    // A check whose commands were just killed fails for that reason alone
+                    return if token.is_cancelled() { cancelled_result() } else { result };
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) if token.is_cancelled() => {
+                    // The check's commands are being killed; a check stuck in
+                    // something else is left to finish on its own
+                    if receiver.recv_timeout(Duration::from_millis(CANCEL_GRACE_MS)).is_ok() {
+                        let _ = handle.join();
+                    }
+                    return cancelled_result();
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                // execute_check catches panics, so the sender is only dropped
+                // without a result if the thread could not finish
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    let _ = handle.join();
+                    return CheckResult::Fail {
+                        message: "Check panicked during execution".to_string(),
+                        details: "An unexpected error occurred".to_string(),
+                        duration_ms: 0,
+                        data: Vec::new(),
                     }
-                } else {
-                    check_result
                 }
             }
-            Err(_) => CheckResult::Fail {
-                message: "Check panicked during execution".to_string(),
-                details: "An unexpected error occurred".to_string(),
-                duration_ms: start.elapsed().as_millis() as u64,
-            },
         }
     }
 
-    /// Resolve check dependencies and return ordered list
+    /// The registered checks for `check_ids`, in dependency order
+    fn ordered(&self, check_ids: &[String]) -> Vec<Arc<RegisteredCheck>> {
+        self.resolve_dependencies(check_ids)
+            .iter()
+            .filter_map(|id| self.checks.iter().find(|c| &c.id == id).cloned())
+            .collect()
+    }
+
+    /// Resolve check dependencies and return ordered list; `check_ids` must
+    /// not contain a dependency cycle
     fn resolve_dependencies(&self, check_ids: &[String]) -> Vec<String> {
         let mut result = Vec::new();
         let mut visited = std::collections::HashSet::new();
 
         fn visit(
             id: &str,
-            checks: &[RegisteredCheck],
+            checks: &[Arc<RegisteredCheck>],
             check_ids: &[String],
             visited: &mut std::collections::HashSet<String>,
             result: &mut Vec<String>,
@@ -400,7 +722,419 @@ impl CheckOrchestrator {
     }
 }
 
+#[cfg(feature = "async")]
+impl CheckOrchestrator {
+    /// Run the specified checks on the current tokio runtime.
+    ///
+    /// The scheduler is a single task: each check starts as soon as its
+    /// dependencies have finished, with the same limits as the threaded
+    /// parallel runner (one check at a time when `parallel` is off). Check
+    /// functions are synchronous, so each runs on tokio's pooled blocking
+    /// threads rather than a thread of its own. A check still running after
+    /// `timeout_ms` is reported as failed immediately instead of when it
+    /// eventually returns.
+    ///
+    /// Dropping the returned future cancels the run: no further checks are
+    /// started, and results of checks already running are discarded. A
+    /// cancelled [`CancellationToken`] instead returns the partial report.
+    pub async fn run_async(&self, check_ids: &[String]) -> ValidationReport {
+        use tokio::task::JoinSet;
+
+        let start = Instant::now();
+        let (check_ids, cyclic) = self.break_cycles(check_ids);
+        let mut queue = ReadyQueue::new(self.ordered(&check_ids), self.config.parallel, self.config.max_parallel);
+        let mut tasks = JoinSet::new();
+        let aggregator = Mutex::new(self.new_aggregator());
+        if let Ok(mut agg) = aggregator.lock() {
+            for check in cyclic {
+                agg.add_result(check);
+            }
+        }
+        let diagnostics = DiagnosticLog::default();
+        let mut started = HashMap::new();
+
+        loop {
+            while !self.is_cancelled() {
+                let Some(check) = queue.next() else { break };
+                let start_ms = elapsed_ms(start);
+                let skipped = dependency_skip(&check, &queue.failures).or_else(|| self.over_budget(&check, start));
+                if let Some(skipped) = skipped {
+                    queue.finish(&check.id, &skipped, self.config.fail_fast);
+                    if let Ok(mut agg) = aggregator.lock() {
+                        agg.add_result(check_result(&check, skipped, start_ms));
+                    }
+                    continue;
+                }
+                started.insert(check.id.clone(), start_ms);
+                let timeout = self.config.timeout_for(&check);
+                let (timeout_ms, limit) = timeout;
+                let task_diagnostics = diagnostics.clone();
+                let token = self.cancellation.clone();
+                let settings = self.config.settings;
+                tasks.spawn(async move {
+                    let check_start = Instant::now();
+                    let task_check = Arc::clone(&check);
+                    let handle = tokio::task::spawn_blocking(move || {
+                        let run = || execute_check(&task_check, timeout, &settings, &task_diagnostics);
+                        match token {
+                            Some(token) => token.scope(run),
+                            None => run(),
+                        }
+                    });
+                    let result = match tokio::time::timeout(Duration::from_millis(timeout_ms), handle).await {
+                        Ok(Ok(result)) => result,
+                        Ok(Err(_)) => CheckResult::Fail {
+                            message: "Check panicked during execution".to_string(),
+                            details: "An unexpected error occurred".to_string(),
+                            duration_ms: check_start.elapsed().as_millis() as u64,
+                            data: Vec::new(),
+                        },
+                        Err(_) => CheckResult::Fail {
+                            message: format!("Check timed out after {}ms", timeout_ms),
+                            details: format!("Check exceeded {}", limit),
+                            duration_ms: timeout_ms,
+                            data: Vec::new(),
+                        },
+                    };
+                    check_result(&check, result, start_ms)
+                });
+            }
+
+            let joined = match &self.cancellation {
+                None => tasks.join_next().await,
+                // Wake periodically to notice cancellation
+                Some(token) => match tokio::time::timeout(Duration::from_millis(CANCEL_POLL_MS), tasks.join_next()).await {
+                    Ok(joined) => joined,
+                    Err(_) if token.is_cancelled() => {
+                        tasks.abort_all();
+                        if let Ok(mut agg) = aggregator.lock() {
+                            for check in &queue.running {
+                                let start_ms = started.get(&check.id).copied().unwrap_or_default();
+                                agg.add_result(check_result(check, cancelled_result(), start_ms));
+                            }
+                        }
+                        break;
+                    }
+                    Err(_) => continue,
+                },
+            };
+            let mut check = match joined {
+                Some(Ok(check)) => check,
+                // A task only errors when the runtime is shutting down
+                Some(Err(_)) | None => break,
+            };
+            if self.is_cancelled() {
+                check.result = Some(cancelled_result());
+            }
+            if let Some(result) = check.result.take() {
+                check.result = Some(self.config.policy.apply_result(&check.id, &check.category, result));
+            }
+            if let Some(result) = &check.result {
+                queue.finish(&check.id, result, self.config.fail_fast);
+            }
+            if let Ok(mut agg) = aggregator.lock() {
+                agg.add_result(check);
+            }
+        }
+        self.skip_unstarted(&check_ids, &aggregator);
+
+        finish_report(&aggregator, start, &self.checks, &diagnostics)
+    }
+}
+
+/// A run on a background thread, started by
+/// [`CheckOrchestrator::run_all_streamed`].
+///
+/// Iterating yields each check's result as it completes. Dropping the run
+/// stops the iteration but not the checks, which run to the end.
+pub struct StreamedRun {
+    results: mpsc::Receiver<Check>,
+    handle: JoinHandle<ValidationReport>,
+}
+
+impl StreamedRun {
+    /// Wait for the run to finish and return its report, with every result
+    /// whether or not it was iterated over
+    pub fn report(self) -> ValidationReport {
+        self.handle.join().unwrap_or_else(|_| ResultAggregator::new().to_report())
+    }
+}
+
+impl Iterator for StreamedRun {
+    type Item = Check;
+
+    fn next(&mut self) -> Option<Check> {
+        self.results.recv().ok()
+    }
+}
+
+/// Checks waiting to run, in dependency order, and those running.
+///
+/// Shared by the parallel runners to start each check as soon as its
+/// dependencies have finished and its tags allow: at most `max_parallel`
+/// checks run at once, network-bound checks overlap freely when running in
+/// parallel, and only one TPU-exclusive check runs at a time.
+struct ReadyQueue {
+    pending: Vec<Arc<RegisteredCheck>>,
+    running: Vec<Arc<RegisteredCheck>>,
+    stopped: bool,
+    max_parallel: usize,
+    /// Whether network-bound checks run outside the `max_parallel` limit
+    overlap_network: bool,
+    /// Estimated time from starting each pending check until it and every
+    /// check waiting on it have finished, when scheduling long poles first
+    critical_path_ms: Option<HashMap<String, u64>>,
+    /// Failed checks and checks skipped because of them (see [`record_failure`])
+    failures: HashMap<String, String>,
+}
+
+impl ReadyQueue {
+    /// Queue `pending` checks. When `parallel`, the ready check with the
+    /// longest chain of estimated work behind it starts first, so slow
+    /// checks such as PERF-004 (and the checks they wait on) are not left
+    /// until the end of the run. Otherwise checks run one at a time, in order.
+    fn new(pending: Vec<Arc<RegisteredCheck>>, parallel: bool, max_parallel: usize) -> Self {
+        let critical_path_ms = parallel.then(|| {
+            let mut paths: HashMap<String, u64> = HashMap::new();
+            for check in pending.iter().rev() {
+                let longest_dependent = pending
+                    .iter()
+                    .filter(|p| p.dependencies.contains(&check.id))
+                    .filter_map(|p| paths.get(&p.id))
+                    .max()
+                    .copied()
+                    .unwrap_or(0);
+                paths.insert(
+                    check.id.clone(),
+                    check.estimated_duration_ms.saturating_add(longest_dependent),
+                );
+            }
+            paths
+        });
+        ReadyQueue {
+            pending,
+            running: Vec::new(),
+            stopped: false,
+            max_parallel: if parallel { max_parallel.max(1) } else { 1 },
+            overlap_network: parallel,
+            critical_path_ms,
+            failures: HashMap::new(),
+        }
+    }
+
+    /// Most checks that can ever run at once
+    fn max_concurrency(&self) -> usize {
+        let network = if self.overlap_network {
+            self.pending.iter().filter(|c| c.is_network_bound()).count()
+        } else {
+            0
+        };
+        (self.max_parallel + network).min(self.pending.len()).max(1)
+    }
+
+    /// Whether a check counts toward `max_parallel`
+    fn is_limited(&self, check: &RegisteredCheck) -> bool {
+        !(self.overlap_network && check.is_network_bound())
+    }
+
+    /// Whether the check's dependencies have finished and its tags allow it
+    /// to start alongside the running checks
+    fn can_start(&self, check: &RegisteredCheck) -> bool {
+        let dependencies_done = check
+            .dependencies
+            .iter()
+            .all(|dep| !self.running.iter().chain(&self.pending).any(|c| &c.id == dep));
+        let tpu_free = !check.is_tpu_exclusive() || !self.running.iter().any(|c| c.is_tpu_exclusive());
+        let slot_free = !self.is_limited(check)
+            || self.running.iter().filter(|c| self.is_limited(c)).count() < self.max_parallel;
+        dependencies_done && tpu_free && slot_free
+    }
+
+    /// Take the next check that can start, and mark it running. With nothing
+    /// running and nothing ready the remaining checks depend on each other,
+    /// so they are released in order rather than stalling.
+    fn next(&mut self) -> Option<Arc<RegisteredCheck>> {
+        if self.stopped {
+            return None;
+        }
+        let ready = self
+            .pending
+            .iter()
+            .enumerate()
+            .filter(|(_, check)| self.can_start(check));
+        let chosen = match &self.critical_path_ms {
+            // max_by_key keeps the last maximum; reverse so ties go to the earliest check
+            Some(paths) => ready
+                .rev()
+                .max_by_key(|(_, check)| paths.get(&check.id).copied().unwrap_or(0))
+                .map(|(index, _)| index),
+            None => ready.map(|(index, _)| index).next(),
+        };
+        let index = match chosen {
+            Some(index) => Some(index),
+            None if self.running.is_empty() && !self.pending.is_empty() => Some(0),
+            None => None,
+        }?;
+        let check = self.pending.remove(index);
+        self.running.push(Arc::clone(&check));
+        Some(check)
+    }
+
+    /// Record that a check finished; a failure stops scheduling under fail-fast
+    fn finish(&mut self, id: &str, result: &CheckResult, fail_fast: bool) {
+        if let Some(check) = self.running.iter().find(|r| r.id == id) {
+            record_failure(&mut self.failures, check, result);
+        }
+        self.running.retain(|r| r.id != id);
+        if fail_fast && matches!(result, CheckResult::Fail { .. }) {
+            self.stopped = true;
+        }
+    }
+
+    /// Whether no more checks will be started
+    fn is_drained(&self) -> bool {
+        self.stopped || self.pending.is_empty()
+    }
+}
+
+/// A skip result when one of `check`'s dependencies is in `failures`,
+/// naming the check whose failure caused it
+fn dependency_skip(check: &RegisteredCheck, failures: &HashMap<String, String>) -> Option<CheckResult> {
+    let cause = check.dependencies.iter().find_map(|dep| failures.get(dep))?;
+    Some(CheckResult::Skip {
+        reason: format!("dependency {} failed", cause),
+    })
+}
+
+/// Add `check` to `failures` when it failed (as its own cause) or was
+/// skipped for a failed dependency (with that dependency's cause), so
+/// checks further down the chain are skipped for the original failure
+fn record_failure(failures: &mut HashMap<String, String>, check: &RegisteredCheck, result: &CheckResult) {
+    let cause = match result {
+        CheckResult::Fail { .. } => Some(check.id.clone()),
+        CheckResult::Skip { .. } => check.dependencies.iter().find_map(|dep| failures.get(dep)).cloned(),
+        _ => None,
+    };
+    if let Some(cause) = cause {
+        failures.insert(check.id.clone(), cause);
+    }
+}
+
+/// Result for a check that was still running when the run was cancelled
+fn cancelled_result() -> CheckResult {
+    CheckResult::Skip {
+        reason: "Run cancelled before the check finished".to_string(),
+    }
+}
+
+/// The report entry for a registered check, its result, and when it started
+fn check_result(check: &RegisteredCheck, result: CheckResult, start_ms: u64) -> Check {
+    Check {
+        id: check.id.clone(),
+        name: check.name.clone(),
+        category: check.category.clone(),
+        description: check.description.clone(),
+        result: Some(result),
+        start_ms: Some(start_ms),
+        ownership: check.ownership.clone(),
+    }
+}
+
+/// The report entry for a check that was never started
+fn unstarted_result(check: &RegisteredCheck, result: CheckResult) -> Check {
+    Check {
+        start_ms: None,
+        ..check_result(check, result, 0)
+    }
+}
+
+/// Milliseconds since `start`
+fn elapsed_ms(start: Instant) -> u64 {
+    start.elapsed().as_millis() as u64
+}
+
+/// Attach host metadata, the total duration, the checks' duration
+/// estimates, and the issues they ran into to the collected results
+fn finish_report(
+    aggregator: &Mutex<ResultAggregator>,
+    start: Instant,
+    checks: &[Arc<RegisteredCheck>],
+    diagnostics: &DiagnosticLog,
+) -> ValidationReport {
+    let total_duration_ms = start.elapsed().as_millis() as u64;
+
+    // Build report
+    let hostname = linux::get_hostname().unwrap_or_else(|_| "unknown".to_string());
+    let tpu_type = tpu::get_tpu_type().ok().map(|t| t.to_string());
+
+    // Handle potential mutex poisoning gracefully
+    let mut report = match aggregator.lock() {
+        Ok(mut agg) => {
+            agg.set_metadata(hostname, tpu_type, total_duration_ms);
+            agg.to_report()
+        }
+        Err(poisoned) => {
+            // If the mutex is poisoned, recover the data anyway
+            let mut agg = poisoned.into_inner();
+            agg.set_metadata(hostname, tpu_type, total_duration_ms);
+            agg.to_report()
+        }
+    };
+    report.started_at_ms = time::now_unix_ms().saturating_sub(total_duration_ms);
+    report.estimates = report
+        .checks
+        .iter()
+        .filter_map(|check| checks.iter().find(|c| c.id == check.id))
+        .map(|check| (check.id.clone(), check.estimated_duration_ms))
+        .collect();
+    report.diagnostics = diagnostics.entries();
+    report
+}
+
+/// Execute a single check with its run's `settings`, converting panics and
+/// overruns of `timeout` (milliseconds and the limit's name) into failures,
+/// and collecting the issues it records into `diagnostics`
+fn execute_check(
+    check: &RegisteredCheck,
+    timeout: (u64, &'static str),
+    settings: &RunSettings,
+    diagnostics: &DiagnosticLog,
+) -> CheckResult {
+    let (timeout_ms, limit) = timeout;
+    let start = Instant::now();
+
+    // Execute the check function
+    // In a production implementation, we'd use panic::catch_unwind
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        settings.scope(|| diagnostics.scope(&check.id, || (check.check_fn)()))
+    }));
+
+    match result {
+        Ok(check_result) => {
+            // Check if we exceeded timeout
+            let elapsed = start.elapsed().as_millis() as u64;
+            if elapsed > timeout_ms {
+                CheckResult::Fail {
+                    message: format!("Check timed out after {}ms", elapsed),
+                    details: format!("Check exceeded {}", limit),
+                    duration_ms: elapsed,
+                    data: Vec::new(),
+                }
+            } else {
+                check_result
+            }
+        }
+        Err(_) => CheckResult::Fail {
+            message: "Check panicked during execution".to_string(),
+            details: "An unexpected error occurred".to_string(),
+            duration_ms: start.elapsed().as_millis() as u64,
+            data: Vec::new(),
+        },
+    }
+}
+
 /// Create all registered checks with their execution functions
+#[allow(clippy::vec_init_then_push)]
 pub fn create_all_checks() -> Vec<RegisteredCheck> {
     use crate::checks::{config, hardware, io, performance, security, stack};
 
@@ -415,6 +1149,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(hardware::run_hw001),
         dependencies: vec![],
         estimated_duration_ms: 1000,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -425,6 +1162,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(hardware::run_hw002),
         dependencies: vec!["HW-001".to_string()],
         estimated_duration_ms: 1000,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -435,6 +1175,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(hardware::run_hw003),
         dependencies: vec!["HW-001".to_string()],
         estimated_duration_ms: 500,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -445,16 +1188,22 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(hardware::run_hw004),
         dependencies: vec!["HW-001".to_string()],
         estimated_duration_ms: 500,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
         id: "HW-005".to_string(),
         name: "ICI Interconnect Status".to_string(),
         category: CheckCategory::Hardware,
-        description: "Verify inter-chip interconnect is functional".to_string(),
+        description: "Verify inter-chip interconnect is functional and measure each chip-to-chip link".to_string(),
         check_fn: Box::new(hardware::run_hw005),
         dependencies: vec!["HW-001".to_string()],
-        estimated_duration_ms: 1000,
+        estimated_duration_ms: 10000,
+        tags: vec![CheckTag::TpuExclusive],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -465,6 +1214,22 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(hardware::run_hw006),
         dependencies: vec![],
         estimated_duration_ms: 500,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
+    });
+
+    checks.push(RegisteredCheck {
+        id: "HW-007".to_string(),
+        name: "Live TPU Utilization".to_string(),
+        category: CheckCategory::Hardware,
+        description: "Read duty cycle and HBM usage from the libtpu runtime metrics service".to_string(),
+        check_fn: Box::new(hardware::run_hw007),
+        dependencies: vec!["HW-001".to_string()],
+        estimated_duration_ms: 1000,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     // Stack checks
@@ -476,6 +1241,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(stack::run_stk001),
         dependencies: vec![],
         estimated_duration_ms: 1000,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -486,6 +1254,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(stack::run_stk002),
         dependencies: vec![],
         estimated_duration_ms: 500,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -496,6 +1267,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(stack::run_stk003),
         dependencies: vec![],
         estimated_duration_ms: 500,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -506,16 +1280,22 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(stack::run_stk004),
         dependencies: vec![],
         estimated_duration_ms: 500,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
         id: "STK-005".to_string(),
         name: "PJRT Plugin Status".to_string(),
         category: CheckCategory::Stack,
-        description: "Verify PJRT TPU plugin is available".to_string(),
+        description: "Verify the PJRT TPU plugin loads and reports a C API version jaxlib accepts".to_string(),
         check_fn: Box::new(stack::run_stk005),
         dependencies: vec![],
-        estimated_duration_ms: 500,
+        estimated_duration_ms: 2000,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -526,6 +1306,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(stack::run_stk006),
         dependencies: vec![],
         estimated_duration_ms: 1000,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -536,6 +1319,23 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(stack::run_stk007),
         dependencies: vec![],
         estimated_duration_ms: 100,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
+    });
+
+    checks.push(RegisteredCheck {
+        id: "STK-008".to_string(),
+        name: "Driver/libtpu Pairing".to_string(),
+        category: CheckCategory::Stack,
+        description: "Cross-check the TPU driver version against the libtpu version in the compatibility matrix"
+            .to_string(),
+        check_fn: Box::new(stack::run_stk008),
+        dependencies: vec!["HW-006".to_string(), "STK-002".to_string()],
+        estimated_duration_ms: 100,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
     });
 
     // Performance checks
@@ -547,6 +1347,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(performance::run_perf001),
         dependencies: vec!["HW-001".to_string(), "STK-001".to_string()],
         estimated_duration_ms: 10000,
+        tags: vec![CheckTag::TpuExclusive, CheckTag::Cacheable],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -557,6 +1360,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(performance::run_perf002),
         dependencies: vec!["HW-001".to_string(), "HW-002".to_string()],
         estimated_duration_ms: 5000,
+        tags: vec![CheckTag::TpuExclusive, CheckTag::Cacheable],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -567,6 +1373,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(performance::run_perf003),
         dependencies: vec!["HW-001".to_string(), "HW-005".to_string()],
         estimated_duration_ms: 3000,
+        tags: vec![CheckTag::TpuExclusive, CheckTag::Cacheable],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -577,6 +1386,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(performance::run_perf004),
         dependencies: vec!["STK-001".to_string(), "STK-003".to_string()],
         estimated_duration_ms: 60000,
+        tags: vec![CheckTag::TpuExclusive, CheckTag::Cacheable],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -587,6 +1399,49 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(performance::run_perf005),
         dependencies: vec!["HW-002".to_string()],
         estimated_duration_ms: 5000,
+        tags: vec![CheckTag::TpuExclusive, CheckTag::Cacheable],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
+    });
+
+    checks.push(RegisteredCheck {
+        id: "PERF-006".to_string(),
+        name: "Collective Bandwidth".to_string(),
+        category: CheckCategory::Performance,
+        description: "Run all-reduce/all-gather across local chips and compare bus bandwidth to expected ICI".to_string(),
+        check_fn: Box::new(performance::run_perf006),
+        dependencies: vec!["HW-001".to_string(), "HW-005".to_string()],
+        estimated_duration_ms: 10000,
+        tags: vec![CheckTag::TpuExclusive, CheckTag::Cacheable],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
+    });
+
+    checks.push(RegisteredCheck {
+        id: "PERF-007".to_string(),
+        name: "Training Step Smoke Test".to_string(),
+        category: CheckCategory::Performance,
+        description: "Run a few training steps of a tiny transformer layer (opt-in)".to_string(),
+        check_fn: Box::new(performance::run_perf007),
+        dependencies: vec!["HW-001".to_string(), "STK-001".to_string()],
+        estimated_duration_ms: 60000,
+        tags: vec![CheckTag::TpuExclusive, CheckTag::Cacheable],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
+    });
+
+    checks.push(RegisteredCheck {
+        id: "PERF-008".to_string(),
+        name: "Sustained Load Thermal Test".to_string(),
+        category: CheckCategory::Performance,
+        description: "Keep the MXUs busy for 10 minutes and watch for throttling and overheating (soak level)"
+            .to_string(),
+        check_fn: Box::new(performance::run_perf008),
+        dependencies: vec!["HW-001".to_string(), "HW-003".to_string()],
+        estimated_duration_ms: 660000,
+        tags: vec![CheckTag::TpuExclusive, CheckTag::Sustained],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     // I/O checks
@@ -598,6 +1453,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(io::run_io001),
         dependencies: vec!["IO-003".to_string()],
         estimated_duration_ms: 10000,
+        tags: vec![CheckTag::NetworkBound, CheckTag::Cacheable],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -608,6 +1466,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(io::run_io002),
         dependencies: vec![],
         estimated_duration_ms: 5000,
+        tags: vec![CheckTag::CpuBound, CheckTag::Cacheable],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -618,6 +1479,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(io::run_io003),
         dependencies: vec!["IO-006".to_string()],
         estimated_duration_ms: 2000,
+        tags: vec![CheckTag::NetworkBound],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -628,6 +1492,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(io::run_io004),
         dependencies: vec![],
         estimated_duration_ms: 1000,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Critical,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -638,6 +1505,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(io::run_io005),
         dependencies: vec!["IO-006".to_string()],
         estimated_duration_ms: 5000,
+        tags: vec![CheckTag::NetworkBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -648,6 +1518,48 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(io::run_io006),
         dependencies: vec![],
         estimated_duration_ms: 2000,
+        tags: vec![CheckTag::NetworkBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
+    });
+
+    checks.push(RegisteredCheck {
+        id: "IO-007".to_string(),
+        name: "Input Pipeline Throughput".to_string(),
+        category: CheckCategory::Io,
+        description: "Stream records from a GCS prefix through tf.data to the device".to_string(),
+        check_fn: Box::new(io::run_io007),
+        dependencies: vec!["HW-001".to_string(), "IO-003".to_string()],
+        estimated_duration_ms: 30000,
+        tags: vec![CheckTag::TpuExclusive, CheckTag::Cacheable],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
+    });
+
+    checks.push(RegisteredCheck {
+        id: "IO-008".to_string(),
+        name: "Bucket Co-location".to_string(),
+        category: CheckCategory::Io,
+        description: "Compare the training data bucket's location and storage class with the TPU's zone".to_string(),
+        check_fn: Box::new(io::run_io008),
+        dependencies: vec![],
+        estimated_duration_ms: 1000,
+        tags: vec![CheckTag::NetworkBound, CheckTag::Cacheable],
+        severity: CheckSeverity::Minor,
+        ownership: Default::default(),
+    });
+
+    checks.push(RegisteredCheck {
+        id: "IO-009".to_string(),
+        name: "Container Registry Access".to_string(),
+        category: CheckCategory::Io,
+        description: "Verify DNS, connectivity, and pull access for the container registry".to_string(),
+        check_fn: Box::new(io::run_io009),
+        dependencies: vec![],
+        estimated_duration_ms: 2000,
+        tags: vec![CheckTag::NetworkBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     // Security checks
@@ -659,6 +1571,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(security::run_sec001),
         dependencies: vec![],
         estimated_duration_ms: 2000,
+        tags: vec![CheckTag::NetworkBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -669,36 +1584,48 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(security::run_sec002),
         dependencies: vec![],
         estimated_duration_ms: 500,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
         id: "SEC-003".to_string(),
         name: "Workload Identity Status".to_string(),
         category: CheckCategory::Security,
-        description: "Check if workload identity is configured".to_string(),
+        description: "Check workload identity, and in GKE pods the KSA to GSA binding".to_string(),
         check_fn: Box::new(security::run_sec003),
         dependencies: vec!["SEC-001".to_string()],
         estimated_duration_ms: 1000,
+        tags: vec![CheckTag::NetworkBound],
+        severity: CheckSeverity::Minor,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
         id: "SEC-004".to_string(),
         name: "Encryption Status".to_string(),
         category: CheckCategory::Security,
-        description: "Verify data encryption settings".to_string(),
+        description: "Report whether each attached disk uses a Google-managed, CMEK, or CSEK key".to_string(),
         check_fn: Box::new(security::run_sec004),
         dependencies: vec![],
-        estimated_duration_ms: 500,
+        estimated_duration_ms: 2000,
+        tags: vec![CheckTag::NetworkBound],
+        severity: CheckSeverity::Minor,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
         id: "SEC-005".to_string(),
         name: "Instance Metadata Access".to_string(),
         category: CheckCategory::Security,
-        description: "Verify metadata server access configuration".to_string(),
+        description: "Verify metadata header enforcement, legacy endpoints, and token scopes".to_string(),
         check_fn: Box::new(security::run_sec005),
         dependencies: vec![],
         estimated_duration_ms: 1000,
+        tags: vec![CheckTag::NetworkBound],
+        severity: CheckSeverity::Minor,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -709,6 +1636,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(security::run_sec006),
         dependencies: vec![],
         estimated_duration_ms: 1000,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Minor,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -719,6 +1649,35 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(security::run_sec007),
         dependencies: vec![],
         estimated_duration_ms: 100,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
+    });
+
+    checks.push(RegisteredCheck {
+        id: "SEC-008".to_string(),
+        name: "SSH Open to the Internet".to_string(),
+        category: CheckCategory::Security,
+        description: "Check firewall rules targeting this instance for tcp:22 allowed from 0.0.0.0/0".to_string(),
+        check_fn: Box::new(security::run_sec008),
+        dependencies: vec![],
+        estimated_duration_ms: 2000,
+        tags: vec![CheckTag::NetworkBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
+    });
+
+    checks.push(RegisteredCheck {
+        id: "SEC-009".to_string(),
+        name: "Egress Control".to_string(),
+        category: CheckCategory::Security,
+        description: "Check for default-allow egress to the internet and Cloud NAT logging".to_string(),
+        check_fn: Box::new(security::run_sec009),
+        dependencies: vec![],
+        estimated_duration_ms: 3000,
+        tags: vec![CheckTag::NetworkBound],
+        severity: CheckSeverity::Minor,
+        ownership: Default::default(),
     });
 
     // Configuration checks
@@ -730,6 +1689,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(config::check_xla_flags),
         dependencies: vec![],
         estimated_duration_ms: 100,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -740,6 +1702,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(config::check_jax_config),
         dependencies: vec!["STK-001".to_string()],
         estimated_duration_ms: 100,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -750,6 +1715,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(config::check_memory_config),
         dependencies: vec![],
         estimated_duration_ms: 100,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -760,6 +1728,9 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(config::check_distributed_config),
         dependencies: vec!["HW-001".to_string()],
         estimated_duration_ms: 100,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks.push(RegisteredCheck {
@@ -770,6 +1741,48 @@ pub fn create_all_checks() -> Vec<RegisteredCheck> {
         check_fn: Box::new(config::check_logging_config),
         dependencies: vec![],
         estimated_duration_ms: 100,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Minor,
+        ownership: Default::default(),
+    });
+
+    checks.push(RegisteredCheck {
+        id: "CFG-006".to_string(),
+        name: "Compilation Cache Check".to_string(),
+        category: CheckCategory::Config,
+        description: "Check JAX persistent compilation cache is set, writable, and persistent".to_string(),
+        check_fn: Box::new(config::check_compilation_cache),
+        dependencies: vec![],
+        estimated_duration_ms: 2000,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Minor,
+        ownership: Default::default(),
+    });
+
+    checks.push(RegisteredCheck {
+        id: "CFG-007".to_string(),
+        name: "LIBTPU_INIT_ARGS Lint".to_string(),
+        category: CheckCategory::Config,
+        description: "Check LIBTPU_INIT_ARGS flag syntax and flag dangerous or deprecated flags".to_string(),
+        check_fn: Box::new(config::check_libtpu_init_args),
+        dependencies: vec![],
+        estimated_duration_ms: 100,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
+    });
+
+    checks.push(RegisteredCheck {
+        id: "CFG-008".to_string(),
+        name: "Configuration Conflicts".to_string(),
+        category: CheckCategory::Config,
+        description: "Check for environment variables whose settings contradict each other or the host".to_string(),
+        check_fn: Box::new(config::check_config_conflicts),
+        dependencies: vec![],
+        estimated_duration_ms: 500,
+        tags: vec![CheckTag::CpuBound],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     });
 
     checks
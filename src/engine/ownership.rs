@@ -0,0 +1,108 @@
+//! Check ownership overlay.
+//!
+//! An `[ownership]` table in the configuration file says who owns checks and
+//! where their documentation and runbooks are, so a failing check can be
+//! routed to the team that can act on it:
+//!
+//! ```toml
+//! [ownership.performance]
+//! owner = "perf-oncall@example.com"
+//!
+//! [ownership."HW-003"]
+//! owner = "fleet-hardware@example.com"
+//! runbook_url = "https://runbooks.example.com/tpu/thermal"
+//! ```
+//!
+//! Keys are check IDs or category names, as in `[policy]`. The fields a
+//! category entry sets replace those the check was registered with, and the
+//! fields a check ID entry sets replace both.
+
+use crate::engine::orchestrator::RegisteredCheck;
+use crate::engine::policy::PolicyTarget;
+use crate::engine::result::ValidationReport;
+use crate::{CheckCategory, CheckOwnership};
+
+/// Ownership entries, in configuration file order
+#[derive(Debug, Clone, Default)]
+pub struct Ownership {
+    pub entries: Vec<(PolicyTarget, CheckOwnership)>,
+}
+
+impl Ownership {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// `ownership` of check `id` in `category` with the matching entries
+    /// laid over it: its category's first, then its ID's
+    pub fn overlay(&self, id: &str, category: &CheckCategory, ownership: &CheckOwnership) -> CheckOwnership {
+        let mut merged = ownership.clone();
+        for (target, entry) in &self.entries {
+            if matches!(target, PolicyTarget::Category(c) if c == category) {
+                merged.merge(entry);
+            }
+        }
+        for (target, entry) in &self.entries {
+            if matches!(target, PolicyTarget::Check(check) if check.eq_ignore_ascii_case(id)) {
+                merged.merge(entry);
+            }
+        }
+        merged
+    }
+
+    /// Lay the matching entries over the ownership of the checks in `report`
+    pub fn apply(&self, report: &mut ValidationReport) {
+        for check in &mut report.checks {
+            check.ownership = self.overlay(&check.id, &check.category, &check.ownership);
+        }
+    }
+
+    /// Lay the matching entries over the ownership of registered `checks`
+    pub fn apply_to_checks(&self, checks: &mut [RegisteredCheck]) {
+        for check in checks {
+            check.ownership = self.overlay(&check.id, &check.category, &check.ownership);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owned_by(owner: &str) -> CheckOwnership {
+        CheckOwnership {
+            owner: Some(owner.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_overlay() {
+        let ownership = Ownership {
+            entries: vec![
+                (
+                    PolicyTarget::Check("hw-003".to_string()),
+                    CheckOwnership {
+                        runbook_url: Some("https://runbooks.example.com/thermal".to_string()),
+                        ..owned_by("fleet-hw")
+                    },
+                ),
+                (PolicyTarget::Category(CheckCategory::Hardware), owned_by("hw-oncall")),
+            ],
+        };
+        let registered = CheckOwnership {
+            docs_url: Some("https://docs.example.com/hw".to_string()),
+            ..owned_by("tpu-doc")
+        };
+
+        // The ID entry wins over the category entry, field by field
+        let hw003 = ownership.overlay("HW-003", &CheckCategory::Hardware, &registered);
+        assert_eq!(hw003.owner.as_deref(), Some("fleet-hw"));
+        assert_eq!(hw003.docs_url.as_deref(), Some("https://docs.example.com/hw"));
+        assert_eq!(hw003.runbook_url.as_deref(), Some("https://runbooks.example.com/thermal"));
+
+        let hw001 = ownership.overlay("HW-001", &CheckCategory::Hardware, &CheckOwnership::default());
+        assert_eq!(hw001, owned_by("hw-oncall"));
+        assert!(ownership.overlay("IO-001", &CheckCategory::Io, &CheckOwnership::default()).is_empty());
+    }
+}
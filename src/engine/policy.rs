@@ -0,0 +1,237 @@
+//! Failure downgrade policy.
+//!
+//! A `[policy]` table in the configuration file lowers the severity of
+//! findings that are known to be acceptable on a fleet, so they stop failing
+//! CI without disappearing from the report:
+//!
+//! ```toml
+//! [policy]
+//! "SEC-006" = "warn"
+//! performance = "ignore"
+//! ```
+//!
+//! Keys are check IDs or category names; a check ID rule takes precedence
+//! over its category's rule. `warn` turns a failure into a warning and
+//! `ignore` turns a failure or warning into a pass. Downgraded results keep
+//! their message, note the downgrade, and carry a [`POLICY_DATA_KEY`] entry
+//! in their structured data.
+//!
+//! The orchestrator applies the policy as each result is recorded (see
+//! [`OrchestratorConfig::policy`](crate::engine::orchestrator::OrchestratorConfig::policy)),
+//! so a downgraded failure neither stops a fail-fast run nor skips the
+//! checks that depend on it.
+
+use crate::engine::result::ValidationReport;
+use crate::{Check, CheckCategory, CheckResult};
+
+/// Structured data key recording a policy downgrade, e.g. `fail -> warn`
+pub const POLICY_DATA_KEY: &str = "policy";
+
+/// What a policy rule does to a matching result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyAction {
+    /// Report failures as warnings
+    Warn,
+    /// Report failures and warnings as passes
+    Ignore,
+}
+
+impl PolicyAction {
+    /// Action for a policy value such as `warn` or `ignore`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "warn" => Some(PolicyAction::Warn),
+            "ignore" => Some(PolicyAction::Ignore),
+            _ => None,
+        }
+    }
+}
+
+/// What a policy rule applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyTarget {
+    Check(String),
+    Category(CheckCategory),
+}
+
+/// Downgrade rules, in configuration file order
+#[derive(Debug, Clone, Default)]
+pub struct Policy {
+    pub rules: Vec<(PolicyTarget, PolicyAction)>,
+}
+
+impl Policy {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Action for `check`: its ID rule if there is one, otherwise its
+    /// category's rule
+    pub fn action_for(&self, check: &Check) -> Option<PolicyAction> {
+        self.action(&check.id, &check.category)
+    }
+
+    /// Action for the check `check_id` in `check_category`
+    pub fn action(&self, check_id: &str, check_category: &CheckCategory) -> Option<PolicyAction> {
+        let by_id = self.rules.iter().find_map(|(target, action)| match target {
+            PolicyTarget::Check(id) if id.eq_ignore_ascii_case(check_id) => Some(*action),
+            _ => None,
+        });
+        by_id.or_else(|| {
+            self.rules.iter().find_map(|(target, action)| match target {
+                PolicyTarget::Category(category) if category == check_category => Some(*action),
+                _ => None,
+            })
+        })
+    }
+
+    /// `result` of the check `check_id` in `check_category`, downgraded if a
+    /// rule applies to it
+    pub fn apply_result(&self, check_id: &str, check_category: &CheckCategory, result: CheckResult) -> CheckResult {
+        match self.action(check_id, check_category) {
+            Some(action) => downgrade(result, action),
+            None => result,
+        }
+    }
+
+    /// Downgrade the results in `report` that a rule applies to
+    pub fn apply(&self, report: &mut ValidationReport) {
+        for check in &mut report.checks {
+            if let Some(result) = check.result.take() {
+                check.result = Some(self.apply_result(&check.id, &check.category, result));
+            }
+        }
+    }
+}
+
+/// Whether `check`'s result was downgraded by a policy
+pub fn is_downgraded(check: &Check) -> bool {
+    check
+        .result
+        .as_ref()
+        .is_some_and(|result| result.data().iter().any(|(key, _)| key == POLICY_DATA_KEY))
+}
+
+/// `result` with `action` applied
+fn downgrade(result: CheckResult, action: PolicyAction) -> CheckResult {
+    let noted = |mut data: Vec<(String, String)>, change: &str| {
+        data.push((POLICY_DATA_KEY.to_string(), change.to_string()));
+        data
+    };
+    let ignored = |status: &str, message: String, details: String| {
+        if details.is_empty() {
+            format!("{} ({} ignored by policy)", message, status)
+        } else {
+            format!("{} ({} ignored by policy: {})", message, status, details)
+        }
+    };
+
+    match (result, action) {
+        (CheckResult::Fail { message, details, duration_ms, data }, PolicyAction::Warn) => CheckResult::Warn {
+            message: format!("{} (downgraded from FAIL by policy)", message),
+            details,
+            duration_ms,
+            data: noted(data, "fail -> warn"),
+        },
+        (CheckResult::Fail { message, details, duration_ms, data }, PolicyAction::Ignore) => CheckResult::Pass {
+            message: ignored("FAIL", message, details),
+            duration_ms,
+            data: noted(data, "fail -> pass"),
+        },
+        (CheckResult::Warn { message, details, duration_ms, data }, PolicyAction::Ignore) => CheckResult::Pass {
+            message: ignored("WARN", message, details),
+            duration_ms,
+            data: noted(data, "warn -> pass"),
+        },
+        (result, _) => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(id: &str, category: CheckCategory, result: CheckResult) -> Check {
+        Check {
+            id: id.to_string(),
+            name: id.to_string(),
+            category,
+            description: String::new(),
+            result: Some(result),
+            start_ms: None,
+            ownership: Default::default(),
+        }
+    }
+
+    fn fail() -> CheckResult {
+        CheckResult::Fail {
+            message: "bad".to_string(),
+            details: "why".to_string(),
+            duration_ms: 1,
+            data: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_apply_policy() {
+        let policy = Policy {
+            rules: vec![
+                (PolicyTarget::Category(CheckCategory::Security), PolicyAction::Ignore),
+                (PolicyTarget::Check("sec-006".to_string()), PolicyAction::Warn),
+            ],
+        };
+        let mut report = ValidationReport {
+            checks: vec![
+                check("SEC-006", CheckCategory::Security, fail()),
+                check("SEC-002", CheckCategory::Security, fail()),
+                check("HW-001", CheckCategory::Hardware, fail()),
+            ],
+            ..Default::default()
+        };
+        policy.apply(&mut report);
+
+        match &report.checks[0].result {
+            Some(CheckResult::Warn { message, details, data, .. }) => {
+                assert_eq!(message, "bad (downgraded from FAIL by policy)");
+                assert_eq!(details, "why");
+                assert_eq!(data, &[(POLICY_DATA_KEY.to_string(), "fail -> warn".to_string())]);
+            }
+            other => panic!("expected warn, got {:?}", other),
+        }
+        assert!(matches!(
+            &report.checks[1].result,
+            Some(CheckResult::Pass { message, .. }) if message == "bad (FAIL ignored by policy: why)"
+        ));
+        assert!(matches!(report.checks[2].result, Some(CheckResult::Fail { .. })));
+        assert!(is_downgraded(&report.checks[1]));
+        assert!(!is_downgraded(&report.checks[2]));
+        assert_eq!(report.summary().failed, 1);
+    }
+
+    #[test]
+    fn test_warn_policy_leaves_warnings_and_passes() {
+        let warn = CheckResult::Warn {
+            message: "slow".to_string(),
+            details: String::new(),
+            duration_ms: 1,
+            data: Vec::new(),
+        };
+        assert!(matches!(
+            downgrade(warn.clone(), PolicyAction::Warn),
+            CheckResult::Warn { message, data, .. } if message == "slow" && data.is_empty()
+        ));
+        assert!(matches!(
+            downgrade(warn, PolicyAction::Ignore),
+            CheckResult::Pass { message, .. } if message == "slow (WARN ignored by policy)"
+        ));
+        let pass = CheckResult::Pass {
+            message: "ok".to_string(),
+            duration_ms: 1,
+            data: Vec::new(),
+        };
+        assert!(matches!(
+            downgrade(pass, PolicyAction::Ignore),
+            CheckResult::Pass { message, data, .. } if message == "ok" && data.is_empty()
+        ));
+    }
+}
@@ -1,8 +1,23 @@
 //! Result aggregation and reporting.
 //!
 //! Collects check results, generates summaries, and supports baseline comparison.
-
+//! Reports from the orchestrator also carry each check's estimated duration,
+//! so [`ValidationReport::estimate_drift`] can show how far the estimates used
+//! for planning and scheduling are from what the checks actually took.
+//!
+//! Every report gets a run ID so downstream systems can deduplicate and join
+//! reports: a UUID in the version 7 layout, whose leading 48 bits are the
+//! milliseconds since the Unix epoch. IDs sort in the order runs started, and
+//! are strictly increasing within a process even when two runs start in the
+//! same millisecond. A caller can supply its own ID instead (`--run-id`), to
+//! correlate the preflight run with the training job that follows it.
+
+use crate::platform::diagnostics::Diagnostic;
+use crate::util::hash;
 use crate::{Check, CheckCategory, CheckResult};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::Sender;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Result summary statistics
 #[derive(Debug, Clone, Default)]
@@ -12,17 +27,128 @@ pub struct ResultSummary {
     pub failed: u32,
     pub skipped: u32,
     pub total: u32,
+    /// Sum of the durations of all checks. Checks overlap in a parallel
+    /// run, so this can exceed `wall_clock_ms`
+    pub total_duration_ms: u64,
+    /// Elapsed time of the whole run
+    pub wall_clock_ms: u64,
+    /// Breakdown for each category that has checks, in category order
+    pub categories: Vec<CategorySummary>,
+}
+
+/// Summary statistics for the checks in one category
+#[derive(Debug, Clone)]
+pub struct CategorySummary {
+    pub category: CheckCategory,
+    pub passed: u32,
+    pub warned: u32,
+    pub failed: u32,
+    pub skipped: u32,
+    pub total: u32,
+    /// Sum of the durations of the category's checks
     pub total_duration_ms: u64,
 }
 
+impl ResultSummary {
+    /// Summarize `checks` from a run that took `wall_clock_ms`
+    fn from_checks(checks: &[Check], wall_clock_ms: u64) -> Self {
+        let mut categories: Vec<CategorySummary> = CheckCategory::ALL
+            .iter()
+            .map(|category| CategorySummary {
+                category: category.clone(),
+                passed: 0,
+                warned: 0,
+                failed: 0,
+                skipped: 0,
+                total: 0,
+                total_duration_ms: 0,
+            })
+            .collect();
+
+        for check in checks {
+            let Some(entry) = categories.iter_mut().find(|c| c.category == check.category) else {
+                continue;
+            };
+            entry.total += 1;
+
+            match &check.result {
+                Some(CheckResult::Pass { duration_ms, .. }) => {
+                    entry.passed += 1;
+                    entry.total_duration_ms += duration_ms;
+                }
+                Some(CheckResult::Warn { duration_ms, .. }) => {
+                    entry.warned += 1;
+                    entry.total_duration_ms += duration_ms;
+                }
+                Some(CheckResult::Fail { duration_ms, .. }) => {
+                    entry.failed += 1;
+                    entry.total_duration_ms += duration_ms;
+                }
+                Some(CheckResult::Skip { .. }) | None => {
+                    entry.skipped += 1;
+                }
+            }
+        }
+        categories.retain(|c| c.total > 0);
+
+        ResultSummary {
+            passed: categories.iter().map(|c| c.passed).sum(),
+            warned: categories.iter().map(|c| c.warned).sum(),
+            failed: categories.iter().map(|c| c.failed).sum(),
+            skipped: categories.iter().map(|c| c.skipped).sum(),
+            total: categories.iter().map(|c| c.total).sum(),
+            total_duration_ms: categories.iter().map(|c| c.total_duration_ms).sum(),
+            wall_clock_ms,
+            categories,
+        }
+    }
+
+    /// The breakdown for `category`, if it has any checks
+    pub fn category(&self, category: &CheckCategory) -> Option<&CategorySummary> {
+        self.categories.iter().find(|c| &c.category == category)
+    }
+}
+
+/// A check's actual duration compared with its estimate
+#[derive(Debug, Clone, PartialEq)]
+pub struct EstimateDrift {
+    pub id: String,
+    pub estimated_ms: u64,
+    pub actual_ms: u64,
+}
+
+impl EstimateDrift {
+    /// How much longer (positive) or shorter (negative) the check took than estimated
+    pub fn drift_ms(&self) -> i64 {
+        self.actual_ms as i64 - self.estimated_ms as i64
+    }
+
+    /// The actual duration as a percentage of the estimate
+    pub fn percent_of_estimate(&self) -> u64 {
+        self.actual_ms * 100 / self.estimated_ms.max(1)
+    }
+}
+
 /// Validation report containing all check results
 #[derive(Debug, Clone)]
 pub struct ValidationReport {
     pub timestamp: u64,
+    /// Identifier of the run that produced the report (empty for reports
+    /// loaded from files written before run IDs were recorded)
+    pub run_id: String,
     pub hostname: String,
     pub tpu_type: Option<String>,
     pub checks: Vec<Check>,
     pub total_duration_ms: u64,
+    /// Wall-clock time the run started, in milliseconds since the Unix
+    /// epoch; each check's `start_ms` is relative to it (0 when unknown)
+    pub started_at_ms: u64,
+    /// Estimated duration of each reported check, from the registry (empty
+    /// for reports not produced by the orchestrator)
+    pub estimates: Vec<(String, u64)>,
+    /// Non-fatal issues the checks ran into, such as unparseable sysfs files
+    /// or partial metadata, that explain a fallback or a skip
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 impl ValidationReport {
@@ -30,43 +156,47 @@ impl ValidationReport {
     pub fn new() -> Self {
         ValidationReport {
             timestamp: crate::platform::linux::get_unix_timestamp(),
+            run_id: new_run_id(),
             hostname: String::new(),
             tpu_type: None,
             checks: Vec::new(),
             total_duration_ms: 0,
+            started_at_ms: 0,
+            estimates: Vec::new(),
+            diagnostics: Vec::new(),
         }
     }
 
     /// Calculate summary statistics
     pub fn summary(&self) -> ResultSummary {
-        let mut summary = ResultSummary::default();
-
-        for check in &self.checks {
-            summary.total += 1;
-
-            match &check.result {
-                Some(CheckResult::Pass { duration_ms, .. }) => {
-                    summary.passed += 1;
-                    summary.total_duration_ms += duration_ms;
-                }
-                Some(CheckResult::Warn { duration_ms, .. }) => {
-                    summary.warned += 1;
-                    summary.total_duration_ms += duration_ms;
-                }
-                Some(CheckResult::Fail { duration_ms, .. }) => {
-                    summary.failed += 1;
-                    summary.total_duration_ms += duration_ms;
-                }
-                Some(CheckResult::Skip { .. }) => {
-                    summary.skipped += 1;
-                }
-                None => {
-                    summary.skipped += 1;
-                }
-            }
-        }
+        ResultSummary::from_checks(&self.checks, self.total_duration_ms)
+    }
 
-        summary
+    /// Actual against estimated duration for each check that ran this run,
+    /// largest drift first. Skipped checks and results reused from the
+    /// result history are left out, since they took no time.
+    pub fn estimate_drift(&self) -> Vec<EstimateDrift> {
+        let mut drift: Vec<EstimateDrift> = self
+            .checks
+            .iter()
+            .filter(|check| check.start_ms.is_some() && !crate::engine::history::is_cached(check))
+            .filter_map(|check| {
+                let actual_ms = match check.result.as_ref()? {
+                    CheckResult::Pass { duration_ms, .. }
+                    | CheckResult::Warn { duration_ms, .. }
+                    | CheckResult::Fail { duration_ms, .. } => *duration_ms,
+                    CheckResult::Skip { .. } => return None,
+                };
+                let (_, estimated_ms) = self.estimates.iter().find(|(id, _)| *id == check.id)?;
+                Some(EstimateDrift {
+                    id: check.id.clone(),
+                    estimated_ms: *estimated_ms,
+                    actual_ms,
+                })
+            })
+            .collect();
+        drift.sort_by(|a, b| b.drift_ms().abs().cmp(&a.drift_ms().abs()).then_with(|| a.id.cmp(&b.id)));
+        drift
     }
 }
 
@@ -88,23 +218,38 @@ pub struct ComparisonResult {
 
 /// Result aggregator for collecting check results
 pub struct ResultAggregator {
+    run_id: String,
     checks: Vec<Check>,
     hostname: String,
     tpu_type: Option<String>,
     total_duration_ms: u64,
+    /// Where copies of results are sent as they are added
+    stream: Option<Sender<Check>>,
 }
 
 impl ResultAggregator {
-    /// Create a new result aggregator
+    /// Create a new result aggregator for a run with a new run ID
     pub fn new() -> Self {
         ResultAggregator {
+            run_id: new_run_id(),
             checks: Vec::new(),
             hostname: String::new(),
             tpu_type: None,
             total_duration_ms: 0,
+            stream: None,
         }
     }
 
+    /// Send a copy of each result added from now on to `sender`
+    pub fn stream_to(&mut self, sender: Sender<Check>) {
+        self.stream = Some(sender);
+    }
+
+    /// Report `run_id` as the run's ID instead of the generated one
+    pub fn set_run_id(&mut self, run_id: &str) {
+        self.run_id = run_id.to_string();
+    }
+
     /// Set report metadata
     pub fn set_metadata(&mut self, hostname: String, tpu_type: Option<String>, total_duration_ms: u64) {
         self.hostname = hostname;
@@ -114,9 +259,18 @@ impl ResultAggregator {
 
     /// Add a completed check result
     pub fn add_result(&mut self, check: Check) {
+        if let Some(stream) = &self.stream {
+            // The receiver may have stopped listening; the report still gets the result
+            let _ = stream.send(check.clone());
+        }
         self.checks.push(check);
     }
 
+    /// Whether a result for `check_id` has been added
+    pub fn has_result(&self, check_id: &str) -> bool {
+        self.checks.iter().any(|c| c.id == check_id)
+    }
+
     /// Check if there are any failures
     pub fn has_failures(&self) -> bool {
         self.checks
@@ -126,34 +280,7 @@ impl ResultAggregator {
 
     /// Get summary statistics
     pub fn get_summary(&self) -> ResultSummary {
-        let mut summary = ResultSummary::default();
-
-        for check in &self.checks {
-            summary.total += 1;
-
-            match &check.result {
-                Some(CheckResult::Pass { duration_ms, .. }) => {
-                    summary.passed += 1;
-                    summary.total_duration_ms += duration_ms;
-                }
-                Some(CheckResult::Warn { duration_ms, .. }) => {
-                    summary.warned += 1;
-                    summary.total_duration_ms += duration_ms;
-                }
-                Some(CheckResult::Fail { duration_ms, .. }) => {
-                    summary.failed += 1;
-                    summary.total_duration_ms += duration_ms;
-                }
-                Some(CheckResult::Skip { .. }) => {
-                    summary.skipped += 1;
-                }
-                None => {
-                    summary.skipped += 1;
-                }
-            }
-        }
-
-        summary
+        ResultSummary::from_checks(&self.checks, self.total_duration_ms)
     }
 
     /// Get checks by category
@@ -184,10 +311,14 @@ impl ResultAggregator {
     pub fn to_report(&self) -> ValidationReport {
         ValidationReport {
             timestamp: crate::platform::linux::get_unix_timestamp(),
+            run_id: self.run_id.clone(),
             hostname: self.hostname.clone(),
             tpu_type: self.tpu_type.clone(),
             checks: self.checks.clone(),
             total_duration_ms: self.total_duration_ms,
+            started_at_ms: 0,
+            estimates: Vec::new(),
+            diagnostics: Vec::new(),
         }
     }
 
@@ -258,6 +389,39 @@ impl Default for ResultAggregator {
     }
 }
 
+/// Last run ID handed out: milliseconds since the Unix epoch, shifted above
+/// a 12-bit sequence number that orders IDs from the same millisecond
+static LAST_RUN_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A new run ID, later than every ID this process has handed out.
+///
+/// The layout is that of a version 7 UUID: 48 bits of milliseconds, the
+/// sequence number, and 62 bits that tell apart IDs from different hosts and
+/// processes, hashed from the host name, process ID, and clock.
+pub fn new_run_id() -> String {
+    let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0);
+    let next = |last: u64| (now_ms << 12).max(last + 1);
+    let last = LAST_RUN_ID
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| Some(next(last)))
+        .unwrap_or_else(|last| last);
+    let stamp = next(last);
+
+    let hostname = crate::platform::linux::get_hostname().unwrap_or_default();
+    let seed = format!("{}:{}:{:?}:{}", hostname, std::process::id(), SystemTime::now(), stamp);
+    let digest = hash::sha256(seed.as_bytes());
+    let random = digest[..8].iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64);
+
+    let (ms, sequence) = (stamp >> 12, stamp & 0xfff);
+    format!(
+        "{:08x}-{:04x}-7{:03x}-{:04x}-{:012x}",
+        ms >> 16,
+        ms & 0xffff,
+        sequence,
+        0x8000 | (random >> 50),
+        random & 0xffff_ffff_ffff
+    )
+}
+
 /// Save a validation report as JSON baseline
 pub fn save_as_baseline(report: &ValidationReport, path: &str) -> Result<(), crate::TpuDocError> {
     use crate::cli::output::{JsonFormatter, OutputFormatter};
@@ -265,27 +429,22 @@ pub fn save_as_baseline(report: &ValidationReport, path: &str) -> Result<(), cra
     let formatter = JsonFormatter::new(true);
     let json = formatter.format(report);
 
-    std::fs::write(path, json).map_err(|e| crate::TpuDocError::IoError {
-        context: "save_as_baseline".to_string(),
-        message: e.to_string(),
-    })
+    std::fs::write(path, json).map_err(|e| crate::TpuDocError::io("save_as_baseline", e))
 }
 
 /// Load a validation report from JSON baseline
 pub fn load_baseline(path: &str) -> Result<ValidationReport, crate::TpuDocError> {
-    let content = std::fs::read_to_string(path).map_err(|e| crate::TpuDocError::IoError {
-        context: "load_baseline".to_string(),
-        message: e.to_string(),
-    })?;
+    let content = std::fs::read_to_string(path).map_err(|e| crate::TpuDocError::io("load_baseline", e))?;
 
     parse_json_report(&content).map_err(|e| crate::TpuDocError::ParseError {
         context: "load_baseline".to_string(),
         message: e,
+        source: None,
     })
 }
 
 /// Parse a JSON string into a ValidationReport
-fn parse_json_report(json: &str) -> Result<ValidationReport, String> {
+pub(crate) fn parse_json_report(json: &str) -> Result<ValidationReport, String> {
     let mut report = ValidationReport::new();
 
     // Extract timestamp
@@ -293,6 +452,8 @@ fn parse_json_report(json: &str) -> Result<ValidationReport, String> {
         report.timestamp = ts as u64;
     }
 
+    report.run_id = extract_json_string(json, "run_id").unwrap_or_default();
+
     // Extract hostname
     if let Some(hostname) = extract_json_string(json, "hostname") {
         report.hostname = hostname;
@@ -460,6 +621,14 @@ fn parse_single_check(json: &str) -> Result<crate::Check, String> {
         _ => crate::CheckCategory::Hardware,
     };
 
+    // Ownership fields come before the result, whose data may reuse their keys
+    let fields = &json[..json.find("\"result\"").unwrap_or(json.len())];
+    let ownership = crate::CheckOwnership {
+        owner: extract_json_string(fields, "owner"),
+        docs_url: extract_json_string(fields, "docs_url"),
+        runbook_url: extract_json_string(fields, "runbook_url"),
+    };
+
     // Parse result
     let result = parse_check_result(json);
 
@@ -469,6 +638,8 @@ fn parse_single_check(json: &str) -> Result<crate::Check, String> {
         category,
         description,
         result,
+        start_ms: extract_json_number(json, "start_ms").map(|ms| ms as u64),
+        ownership,
     })
 }
 
@@ -491,24 +662,25 @@ fn parse_check_result(json: &str) -> Option<crate::CheckResult> {
 
     // Determine result type by looking for status field
     let status = extract_json_string(result_obj, "status")?;
+    let data = parse_result_data(result_obj);
 
     match status.to_lowercase().as_str() {
         "pass" => {
             let message = extract_json_string(result_obj, "message").unwrap_or_default();
             let duration_ms = extract_json_number(result_obj, "duration_ms").unwrap_or(0.0) as u64;
-            Some(crate::CheckResult::Pass { message, duration_ms })
+            Some(crate::CheckResult::Pass { message, duration_ms, data })
         }
         "warn" => {
             let message = extract_json_string(result_obj, "message").unwrap_or_default();
             let details = extract_json_string(result_obj, "details").unwrap_or_default();
             let duration_ms = extract_json_number(result_obj, "duration_ms").unwrap_or(0.0) as u64;
-            Some(crate::CheckResult::Warn { message, details, duration_ms })
+            Some(crate::CheckResult::Warn { message, details, duration_ms, data })
         }
         "fail" => {
             let message = extract_json_string(result_obj, "message").unwrap_or_default();
             let details = extract_json_string(result_obj, "details").unwrap_or_default();
             let duration_ms = extract_json_number(result_obj, "duration_ms").unwrap_or(0.0) as u64;
-            Some(crate::CheckResult::Fail { message, details, duration_ms })
+            Some(crate::CheckResult::Fail { message, details, duration_ms, data })
         }
         "skip" => {
             let reason = extract_json_string(result_obj, "reason").unwrap_or_default();
@@ -518,6 +690,22 @@ fn parse_check_result(json: &str) -> Option<crate::CheckResult> {
     }
 }
 
+/// Structured `data` entries of a result object, in document order
+fn parse_result_data(result_obj: &str) -> Vec<(String, String)> {
+    crate::util::json::parse(result_obj)
+        .ok()
+        .as_ref()
+        .and_then(|result| result.get("data"))
+        .and_then(|data| data.as_object())
+        .map(|members| {
+            members
+                .iter()
+                .filter_map(|(key, value)| value.to_scalar_string().map(|value| (key.clone(), value)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Unescape a JSON string value
 fn unescape_json_string(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
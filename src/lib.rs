@@ -9,6 +9,7 @@
 //! - Resource utilization snapshots
 //! - Configuration auditing
 //! - AI-powered log analysis (optional, requires "ai" feature)
+//! - Async check runner for tokio applications (optional, requires "async" feature)
 //!
 //! # Example
 //!
@@ -27,16 +28,23 @@ pub mod commands;
 pub mod data;
 pub mod engine;
 pub mod platform;
+pub mod util;
 pub mod version;
 
+use checks::stack::EnvVarRule;
 use cli::args::{Args, CategoryFilter};
-use engine::orchestrator::{create_all_checks, CheckOrchestrator, OrchestratorConfig};
+use engine::history::ResultHistory;
+use engine::orchestrator::{create_all_checks, default_max_parallel, CheckOrchestrator, OrchestratorConfig};
 use engine::result::ValidationReport;
 use std::fmt;
+use std::sync::Arc;
 
 // Re-exports for public API
+pub use cli::output::{register_formatter, OutputFormatter};
 pub use engine::orchestrator::CheckOrchestrator as Orchestrator;
-pub use engine::result::{ResultSummary, ValidationReport as Report};
+pub use engine::orchestrator::{CancellationToken, RegisteredCheck, StreamedRun};
+pub use engine::result::{CategorySummary, ResultSummary, ValidationReport as Report};
+pub use data::specs::{TpuSpecs, TpuTypeSpec};
 
 /// Check result indicating the outcome of a validation check.
 #[derive(Debug, Clone)]
@@ -45,18 +53,24 @@ pub enum CheckResult {
     Pass {
         message: String,
         duration_ms: u64,
+        /// Structured key/value details, in display order
+        data: Vec<(String, String)>,
     },
     /// Check passed with warnings
     Warn {
         message: String,
         details: String,
         duration_ms: u64,
+        /// Structured key/value details, in display order
+        data: Vec<(String, String)>,
     },
     /// Check failed
     Fail {
         message: String,
         details: String,
         duration_ms: u64,
+        /// Structured key/value details, in display order
+        data: Vec<(String, String)>,
     },
     /// Check was skipped
     Skip {
@@ -64,6 +78,28 @@ pub enum CheckResult {
     },
 }
 
+impl CheckResult {
+    /// Structured key/value details (empty for skipped checks)
+    pub fn data(&self) -> &[(String, String)] {
+        match self {
+            CheckResult::Pass { data, .. } | CheckResult::Warn { data, .. } | CheckResult::Fail { data, .. } => data,
+            CheckResult::Skip { .. } => &[],
+        }
+    }
+
+    /// Attach structured key/value details. Skipped results are returned
+    /// unchanged.
+    pub fn with_data(mut self, entries: Vec<(String, String)>) -> Self {
+        match &mut self {
+            CheckResult::Pass { data, .. } | CheckResult::Warn { data, .. } | CheckResult::Fail { data, .. } => {
+                *data = entries
+            }
+            CheckResult::Skip { .. } => {}
+        }
+        self
+    }
+}
+
 impl fmt::Display for CheckResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -96,6 +132,18 @@ pub enum CheckCategory {
     Config,
 }
 
+impl CheckCategory {
+    /// Every category, in report order
+    pub const ALL: [CheckCategory; 6] = [
+        CheckCategory::Hardware,
+        CheckCategory::Stack,
+        CheckCategory::Performance,
+        CheckCategory::Io,
+        CheckCategory::Security,
+        CheckCategory::Config,
+    ];
+}
+
 impl fmt::Display for CheckCategory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -109,6 +157,71 @@ impl fmt::Display for CheckCategory {
     }
 }
 
+/// How a check uses the host, so the parallel scheduler can decide what may
+/// run at the same time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CheckTag {
+    /// Local inspection or computation; counts toward `max_parallel`
+    CpuBound,
+    /// Mostly waiting on the network; overlaps freely with other checks
+    NetworkBound,
+    /// Drives the TPU devices; never runs alongside another TPU-exclusive check
+    TpuExclusive,
+    /// Slow measurement of something that changes slowly; `--cached` may
+    /// reuse a recent result instead of running it
+    Cacheable,
+    /// Keeps the TPU under load for a fixed time; held to its estimated
+    /// duration plus the benchmark timeout
+    Sustained,
+}
+
+impl CheckTag {
+    /// Tag for a name as shown by `list`, such as `network`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "cpu" => Some(CheckTag::CpuBound),
+            "network" => Some(CheckTag::NetworkBound),
+            "tpu-exclusive" => Some(CheckTag::TpuExclusive),
+            "cacheable" => Some(CheckTag::Cacheable),
+            "sustained" => Some(CheckTag::Sustained),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CheckTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckTag::CpuBound => write!(f, "cpu"),
+            CheckTag::NetworkBound => write!(f, "network"),
+            CheckTag::TpuExclusive => write!(f, "tpu-exclusive"),
+            CheckTag::Cacheable => write!(f, "cacheable"),
+            CheckTag::Sustained => write!(f, "sustained"),
+        }
+    }
+}
+
+/// How much a check's failure matters, as shown by `list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CheckSeverity {
+    /// Workloads will not run, or will produce wrong results
+    Critical,
+    /// Workloads run, but slower, less reliably, or less securely
+    Major,
+    /// Best practice or hygiene
+    Minor,
+}
+
+impl fmt::Display for CheckSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckSeverity::Critical => write!(f, "critical"),
+            CheckSeverity::Major => write!(f, "major"),
+            CheckSeverity::Minor => write!(f, "minor"),
+        }
+    }
+}
+
 /// A validation check with its result.
 #[derive(Debug, Clone)]
 pub struct Check {
@@ -122,6 +235,52 @@ pub struct Check {
     pub description: String,
     /// Result of the check (None if not yet executed)
     pub result: Option<CheckResult>,
+    /// Milliseconds from the start of the run until this check started
+    /// (None if not yet executed)
+    pub start_ms: Option<u64>,
+    /// Who to route a failure of this check to
+    pub ownership: CheckOwnership,
+}
+
+/// Who owns a check and where its documentation and runbook are, so a
+/// failing check can be routed to the team that can act on it. Set by the
+/// check's registration or the configuration file's `[ownership]` table.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CheckOwnership {
+    /// Team, on-call alias, or person, e.g. `ml-infra@example.com`
+    pub owner: Option<String>,
+    /// Documentation of what the check validates
+    pub docs_url: Option<String>,
+    /// What to do when the check fails
+    pub runbook_url: Option<String>,
+}
+
+impl CheckOwnership {
+    pub fn is_empty(&self) -> bool {
+        self.owner.is_none() && self.docs_url.is_none() && self.runbook_url.is_none()
+    }
+
+    /// The fields that are set, as `owner`, `docs_url`, and `runbook_url`
+    /// key-value pairs
+    pub fn entries(&self) -> Vec<(String, String)> {
+        [("owner", &self.owner), ("docs_url", &self.docs_url), ("runbook_url", &self.runbook_url)]
+            .into_iter()
+            .filter_map(|(key, value)| value.as_ref().map(|value| (key.to_string(), value.clone())))
+            .collect()
+    }
+
+    /// Replace the fields `overlay` sets with its values
+    pub fn merge(&mut self, overlay: &CheckOwnership) {
+        for (field, value) in [
+            (&mut self.owner, &overlay.owner),
+            (&mut self.docs_url, &overlay.docs_url),
+            (&mut self.runbook_url, &overlay.runbook_url),
+        ] {
+            if value.is_some() {
+                field.clone_from(value);
+            }
+        }
+    }
 }
 
 impl Default for Check {
@@ -132,11 +291,32 @@ impl Default for Check {
             category: CheckCategory::Hardware,
             description: String::new(),
             result: None,
+            start_ms: None,
+            ownership: Default::default(),
         }
     }
 }
 
+/// Process exit code for a run that could not complete
+pub const EXIT_RUNTIME_ERROR: u8 = 3;
+
+/// Process exit code for invalid command-line arguments or configuration
+pub const EXIT_USAGE_ERROR: u8 = 4;
+
 /// Error types for tpu-doc operations.
+///
+/// Each error maps to a process exit code (see [`TpuDocError::exit_code`]):
+/// errors in what the user asked for (unknown check IDs, an unreadable or
+/// invalid configuration file, custom checks that depend on each other in a
+/// cycle) exit with [`EXIT_USAGE_ERROR`] (4), so they can be told apart from
+/// failures while running, which exit with [`EXIT_RUNTIME_ERROR`] (3).
+///
+/// Variants that wrap a lower-level failure (an I/O error, a number that
+/// did not parse, an AI provider error) keep it as their `source`, which
+/// [`std::error::Error::source`] returns, and its text as their `message`.
+/// `From` conversions for the common standard library errors sort them into
+/// the matching variant, so `?` works on them in code that returns
+/// `Result<_, TpuDocError>`.
 #[derive(Debug, Clone)]
 pub enum TpuDocError {
     /// Not running on a TPU VM
@@ -144,6 +324,7 @@ pub enum TpuDocError {
     /// Permission denied for a resource
     PermissionDenied {
         resource: String,
+        source: Option<ErrorSource>,
     },
     /// Operation timed out
     Timeout {
@@ -154,11 +335,13 @@ pub enum TpuDocError {
     IoError {
         context: String,
         message: String,
+        source: Option<ErrorSource>,
     },
     /// Parse error
     ParseError {
         context: String,
         message: String,
+        source: Option<ErrorSource>,
     },
     /// Check failed
     CheckFailed {
@@ -169,6 +352,28 @@ pub enum TpuDocError {
     CommandError {
         command: String,
         message: String,
+        source: Option<ErrorSource>,
+    },
+    /// Configuration file that cannot be read or is invalid
+    ConfigError {
+        path: String,
+        message: String,
+        source: Option<ErrorSource>,
+    },
+    /// IDs in `only_checks` or `skip_checks` that no registered check has
+    UnknownCheckIds {
+        /// Each unknown ID, with the registered ID it most likely means
+        ids: Vec<(String, Option<String>)>,
+    },
+    /// Selected checks depend on each other in a cycle
+    DependencyCycle {
+        /// Check IDs from a check back to itself, e.g. `["A", "B", "A"]`
+        chain: Vec<String>,
+    },
+    /// Checks registered under an ID another check already has
+    DuplicateCheckIds {
+        /// Each ID registered more than once, as first registered
+        ids: Vec<String>,
     },
 }
 
@@ -178,29 +383,179 @@ impl fmt::Display for TpuDocError {
             TpuDocError::NotOnTpu => {
                 write!(f, "Not running on a TPU VM")
             }
-            TpuDocError::PermissionDenied { resource } => {
+            TpuDocError::PermissionDenied { resource, .. } if resource.is_empty() => {
+                write!(f, "Permission denied")
+            }
+            TpuDocError::PermissionDenied { resource, .. } => {
                 write!(f, "Permission denied: {}", resource)
             }
             TpuDocError::Timeout { operation, timeout_ms } => {
                 write!(f, "Timeout after {}ms: {}", timeout_ms, operation)
             }
-            TpuDocError::IoError { context, message } => {
+            TpuDocError::IoError { context, message, .. } if context.is_empty() => {
+                write!(f, "I/O error: {}", message)
+            }
+            TpuDocError::IoError { context, message, .. } => {
                 write!(f, "I/O error in {}: {}", context, message)
             }
-            TpuDocError::ParseError { context, message } => {
+            TpuDocError::ParseError { context, message, .. } if context.is_empty() => {
+                write!(f, "Parse error: {}", message)
+            }
+            TpuDocError::ParseError { context, message, .. } => {
                 write!(f, "Parse error in {}: {}", context, message)
             }
             TpuDocError::CheckFailed { check_id, reason } => {
                 write!(f, "Check {} failed: {}", check_id, reason)
             }
-            TpuDocError::CommandError { command, message } => {
+            TpuDocError::CommandError { command, message, .. } => {
                 write!(f, "Command '{}' error: {}", command, message)
             }
+            TpuDocError::ConfigError { path, message, .. } => {
+                write!(f, "Config file {}: {}", path, message)
+            }
+            TpuDocError::UnknownCheckIds { ids } => {
+                let ids: Vec<String> = ids
+                    .iter()
+                    .map(|(id, suggestion)| match suggestion {
+                        Some(suggestion) => format!("{} (did you mean {}?)", id, suggestion),
+                        None => id.clone(),
+                    })
+                    .collect();
+                let noun = if ids.len() == 1 { "ID" } else { "IDs" };
+                write!(f, "Unknown check {}: {}", noun, ids.join(", "))
+            }
+            TpuDocError::DependencyCycle { chain } => {
+                write!(f, "Circular check dependency: {}", chain.join(" -> "))
+            }
+            TpuDocError::DuplicateCheckIds { ids } => {
+                let noun = if ids.len() == 1 { "ID" } else { "IDs" };
+                write!(f, "Duplicate check {}: {}", noun, ids.join(", "))
+            }
+        }
+    }
+}
+
+/// The lower-level error a [`TpuDocError`] wraps, shared so errors stay
+/// cheap to clone
+pub type ErrorSource = Arc<dyn std::error::Error + Send + Sync + 'static>;
+
+impl TpuDocError {
+    /// An I/O error in `context`, caused by `source`
+    pub fn io(context: &str, source: std::io::Error) -> Self {
+        TpuDocError::IoError {
+            context: context.to_string(),
+            message: source.to_string(),
+            source: Some(Arc::new(source)),
+        }
+    }
+
+    /// A parse error in `context`, caused by `source`
+    pub fn parse(context: &str, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        TpuDocError::ParseError {
+            context: context.to_string(),
+            message: source.to_string(),
+            source: Some(Arc::new(source)),
+        }
+    }
+
+    /// An error running `command`, caused by `source`
+    pub fn command(command: &str, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        TpuDocError::CommandError {
+            command: command.to_string(),
+            message: source.to_string(),
+            source: Some(Arc::new(source)),
+        }
+    }
+
+    /// The process exit code for this error: [`EXIT_USAGE_ERROR`] for
+    /// invalid arguments or configuration, [`EXIT_RUNTIME_ERROR`] otherwise
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            TpuDocError::ConfigError { .. }
+            | TpuDocError::UnknownCheckIds { .. }
+            | TpuDocError::DependencyCycle { .. }
+            | TpuDocError::DuplicateCheckIds { .. } => EXIT_USAGE_ERROR,
+            _ => EXIT_RUNTIME_ERROR,
+        }
+    }
+
+    /// Stable identifier of the kind of error, e.g. `unknown_check_ids`,
+    /// for tooling that reads errors as JSON (`--errors json`)
+    pub fn code(&self) -> &'static str {
+        match self {
+            TpuDocError::NotOnTpu => "not_on_tpu",
+            TpuDocError::PermissionDenied { .. } => "permission_denied",
+            TpuDocError::Timeout { .. } => "timeout",
+            TpuDocError::IoError { .. } => "io_error",
+            TpuDocError::ParseError { .. } => "parse_error",
+            TpuDocError::CheckFailed { .. } => "check_failed",
+            TpuDocError::CommandError { .. } => "command_error",
+            TpuDocError::ConfigError { .. } => "config_error",
+            TpuDocError::UnknownCheckIds { .. } => "unknown_check_ids",
+            TpuDocError::DependencyCycle { .. } => "dependency_cycle",
+            TpuDocError::DuplicateCheckIds { .. } => "duplicate_check_ids",
+        }
+    }
+
+    /// What to do about the error, when there is a usual remedy
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            TpuDocError::NotOnTpu => Some("Run tpu-doc on a Cloud TPU VM"),
+            TpuDocError::PermissionDenied { .. } => Some("Re-run as a user with access to the resource"),
+            TpuDocError::Timeout { .. } => Some("Raise the limit with --timeout, --net-timeout, or --bench-timeout"),
+            TpuDocError::ConfigError { .. } => Some("Fix the file, or point --config at another one"),
+            TpuDocError::UnknownCheckIds { .. } => Some("Run 'tpu-doc list' to see the available checks."),
+            TpuDocError::DependencyCycle { .. } => Some("Remove one of the dependencies in the cycle"),
+            TpuDocError::DuplicateCheckIds { .. } => Some("Give each custom or library check an ID of its own"),
+            _ => None,
+        }
+    }
+}
+
+impl std::error::Error for TpuDocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TpuDocError::PermissionDenied { source, .. }
+            | TpuDocError::IoError { source, .. }
+            | TpuDocError::ParseError { source, .. }
+            | TpuDocError::CommandError { source, .. }
+            | TpuDocError::ConfigError { source, .. } => {
+                source.as_deref().map(|e| e as &(dyn std::error::Error + 'static))
+            }
+            _ => None,
         }
     }
 }
 
-impl std::error::Error for TpuDocError {}
+impl From<std::io::Error> for TpuDocError {
+    fn from(e: std::io::Error) -> Self {
+        TpuDocError::io("", e)
+    }
+}
+
+impl From<std::num::ParseIntError> for TpuDocError {
+    fn from(e: std::num::ParseIntError) -> Self {
+        TpuDocError::parse("", e)
+    }
+}
+
+impl From<std::num::ParseFloatError> for TpuDocError {
+    fn from(e: std::num::ParseFloatError) -> Self {
+        TpuDocError::parse("", e)
+    }
+}
+
+impl From<std::str::Utf8Error> for TpuDocError {
+    fn from(e: std::str::Utf8Error) -> Self {
+        TpuDocError::parse("", e)
+    }
+}
+
+impl From<std::string::FromUtf8Error> for TpuDocError {
+    fn from(e: std::string::FromUtf8Error) -> Self {
+        TpuDocError::parse("", e)
+    }
+}
 
 /// Configuration for running validation checks.
 #[derive(Debug, Clone)]
@@ -217,6 +572,62 @@ pub struct TpuDocConfig {
     pub fail_fast: bool,
     /// Global timeout in milliseconds
     pub timeout_ms: u64,
+    /// Timeout in milliseconds for each network connection or request made
+    /// by the network checks
+    pub net_timeout_ms: u64,
+    /// Timeout in milliseconds for each benchmark; performance checks are
+    /// held to it instead of `timeout_ms`
+    pub bench_timeout_ms: u64,
+    /// Token that stops the run early and returns a partial report
+    pub cancellation: Option<CancellationToken>,
+    /// Attach the full output of failed benchmark and version probes to
+    /// check results
+    pub capture_output: bool,
+    /// Budget for the whole run in milliseconds; checks that would not
+    /// finish in time are skipped (None = no budget)
+    pub max_duration_ms: Option<u64>,
+    /// Reuse results of cacheable checks from the result history when they
+    /// are at most this many seconds old (None = always run)
+    pub cache_ttl_secs: Option<u64>,
+    /// Environment variables STK-007 checks in addition to the built-in
+    /// ones, from the configuration file
+    pub env_vars: Vec<EnvVarRule>,
+    /// Endpoints IO-005 measures instead of the built-in set, from the
+    /// configuration file (empty = built-in set)
+    pub latency_endpoints: Vec<checks::io::LatencyEndpoint>,
+    /// Run the benchmarks with fixed reference parameters and no adaptive
+    /// warm-up, so results are comparable across nodes and runs
+    pub deterministic: bool,
+    /// How hard the performance checks push the TPU: sizes, iteration
+    /// counts, and which checks run
+    pub perf_level: checks::benchmark::PerfLevel,
+    /// Warm-up iterations for each benchmark, from the configuration file;
+    /// ignored when `deterministic` is set
+    pub warmup: checks::benchmark::WarmupIterations,
+    /// Working set for the HBM bandwidth benchmark, from the configuration
+    /// file; ignored when `deterministic` is set
+    pub hbm_working_set: checks::benchmark::HbmWorkingSet,
+    /// What the performance checks do when another workload is using the
+    /// TPU, from the configuration file
+    pub busy_tpu: checks::benchmark::BusyTpuPolicy,
+    /// Warn in SEC-009 when egress to the internet is not locked down, from
+    /// the configuration file
+    pub require_locked_egress: bool,
+    /// Read GCP resources with `gcloud` when the API requests of SEC-004,
+    /// SEC-008, and SEC-009 fail
+    pub gcloud_fallback: bool,
+    /// Expected checkpoint size and retention IO-004 checks the checkpoint
+    /// directory can hold (None = a fixed recommended amount of space)
+    pub checkpoint_plan: Option<checks::io::CheckpointPlan>,
+    /// Google service account SEC-003 expects a GKE pod's Kubernetes
+    /// service account to be bound to, from the configuration file
+    pub expected_gke_service_account: Option<String>,
+    /// ID to report the run under, to correlate it with the training job
+    /// that follows (None = a new ID for each run)
+    pub run_id: Option<String>,
+    /// Downgrade rules from the configuration file's `[policy]` table,
+    /// applied to each result as it is recorded
+    pub policy: engine::policy::Policy,
 }
 
 impl Default for TpuDocConfig {
@@ -228,6 +639,25 @@ impl Default for TpuDocConfig {
             parallel: false,
             fail_fast: false,
             timeout_ms: 30000,
+            net_timeout_ms: checks::timeouts::DEFAULT_NET_TIMEOUT_MS,
+            bench_timeout_ms: checks::timeouts::DEFAULT_BENCH_TIMEOUT_MS,
+            cancellation: None,
+            capture_output: false,
+            max_duration_ms: None,
+            cache_ttl_secs: None,
+            env_vars: Vec::new(),
+            latency_endpoints: Vec::new(),
+            deterministic: false,
+            perf_level: Default::default(),
+            warmup: Default::default(),
+            hbm_working_set: Default::default(),
+            busy_tpu: Default::default(),
+            require_locked_egress: false,
+            gcloud_fallback: false,
+            checkpoint_plan: None,
+            expected_gke_service_account: None,
+            run_id: None,
+            policy: Default::default(),
         }
     }
 }
@@ -235,15 +665,20 @@ impl Default for TpuDocConfig {
 impl TpuDocConfig {
     /// Create configuration from command line arguments
     pub fn from_args(args: &Args) -> Self {
-        let categories = match args.category {
-            CategoryFilter::All => None,
-            CategoryFilter::Hardware => Some(vec![CheckCategory::Hardware]),
-            CategoryFilter::Stack => Some(vec![CheckCategory::Stack]),
-            CategoryFilter::Performance => Some(vec![CheckCategory::Performance]),
-            CategoryFilter::Io => Some(vec![CheckCategory::Io]),
-            CategoryFilter::Security => Some(vec![CheckCategory::Security]),
-            CategoryFilter::Config => Some(vec![CheckCategory::Config]),
-        };
+        let categories: Vec<CheckCategory> = args
+            .categories
+            .iter()
+            .filter_map(|filter| match filter {
+                CategoryFilter::All => None,
+                CategoryFilter::Hardware => Some(CheckCategory::Hardware),
+                CategoryFilter::Stack => Some(CheckCategory::Stack),
+                CategoryFilter::Performance => Some(CheckCategory::Performance),
+                CategoryFilter::Io => Some(CheckCategory::Io),
+                CategoryFilter::Security => Some(CheckCategory::Security),
+                CategoryFilter::Config => Some(CheckCategory::Config),
+            })
+            .collect();
+        let categories = (!categories.is_empty()).then_some(categories);
 
         TpuDocConfig {
             categories,
@@ -252,6 +687,46 @@ impl TpuDocConfig {
             parallel: args.parallel,
             fail_fast: args.fail_fast,
             timeout_ms: args.timeout_ms,
+            net_timeout_ms: args.net_timeout_ms.unwrap_or(checks::timeouts::DEFAULT_NET_TIMEOUT_MS),
+            bench_timeout_ms: args.bench_timeout_ms.unwrap_or(checks::timeouts::DEFAULT_BENCH_TIMEOUT_MS),
+            cancellation: None,
+            capture_output: args.verbose,
+            max_duration_ms: args.max_duration_secs.map(|secs| secs * 1000),
+            cache_ttl_secs: args.cached_ttl_secs,
+            env_vars: Vec::new(),
+            latency_endpoints: Vec::new(),
+            deterministic: args.deterministic,
+            perf_level: args.perf_level.unwrap_or_default(),
+            warmup: Default::default(),
+            hbm_working_set: Default::default(),
+            busy_tpu: Default::default(),
+            require_locked_egress: false,
+            gcloud_fallback: args.gcloud_fallback,
+            checkpoint_plan: args.checkpoint_size_bytes.map(|size_bytes| checks::io::CheckpointPlan {
+                size_bytes,
+                keep: args.checkpoint_keep.unwrap_or(checks::io::DEFAULT_CHECKPOINT_KEEP),
+            }),
+            expected_gke_service_account: None,
+            run_id: args.run_id.clone(),
+            policy: Default::default(),
+        }
+    }
+
+    /// Whether the check `id` in `category` is selected by `only_checks`,
+    /// `skip_checks`, or `categories`, in that order of precedence
+    pub fn selects(&self, id: &str, category: &CheckCategory) -> bool {
+        if !self.only_checks.is_empty() {
+            // Run only specified checks
+            self.only_checks.iter().any(|only| only == id)
+        } else if !self.skip_checks.is_empty() {
+            // Run all except skipped
+            !self.skip_checks.iter().any(|skip| skip == id)
+        } else {
+            // Run specific categories (supports multiple), or all checks
+            match &self.categories {
+                Some(categories) if !categories.is_empty() => categories.contains(category),
+                _ => true,
+            }
         }
     }
 }
@@ -289,39 +764,192 @@ impl TpuDocConfig {
 /// }
 /// ```
 pub fn run_checks(config: TpuDocConfig) -> Result<ValidationReport, TpuDocError> {
-    // Create orchestrator
+    run_checks_with(config, Vec::new())
+}
+
+/// Run validation checks together with user-defined checks.
+///
+/// `extra` checks are registered after the built-in ones and take part in
+/// selection (`categories`, `only_checks`, `skip_checks`), dependency
+/// ordering, and fail-fast exactly like them, so their results appear in
+/// the same report, summary, and output formats. Give them IDs that cannot
+/// collide with built-in IDs, e.g. an organization prefix such as `ACME-001`.
+///
+/// # Example
+///
+/// ```no_run
+/// use tpu_doc::{run_checks_with, CheckCategory, CheckResult, RegisteredCheck, TpuDocConfig};
+///
+/// let quota = RegisteredCheck::new("ACME-001", "Team Quota", CheckCategory::Config, || {
+///     CheckResult::Pass {
+///         message: "Quota available".to_string(),
+///         duration_ms: 0,
+///         data: Vec::new(),
+///     }
+/// })
+/// .with_description("Check the team's TPU quota reservation");
+///
+/// let report = run_checks_with(TpuDocConfig::default(), vec![quota]).expect("Validation failed");
+/// println!("Checks run: {}", report.checks.len());
+/// ```
+pub fn run_checks_with(
+    config: TpuDocConfig,
+    extra: Vec<RegisteredCheck>,
+) -> Result<ValidationReport, TpuDocError> {
+    run_checks_wrapped(config, extra, |check| check)
+}
+
+/// Like [`run_checks_with`], with every check passed through `wrap` before
+/// it is registered, e.g. to observe when checks start and finish
+pub(crate) fn run_checks_wrapped(
+    config: TpuDocConfig,
+    extra: Vec<RegisteredCheck>,
+    wrap: impl Fn(RegisteredCheck) -> RegisteredCheck,
+) -> Result<ValidationReport, TpuDocError> {
+    let orchestrator = build_orchestrator(&config, extra, wrap)?;
+    let check_ids = selected_check_ids(&orchestrator, &config)?;
+    Ok(orchestrator.run_specific(&check_ids))
+}
+
+/// Run validation checks, and user-defined checks, on the current tokio
+/// runtime (requires the "async" feature).
+///
+/// Selects checks like [`run_checks_with`], but schedules them from a single
+/// task so dozens of network- and subprocess-bound checks can overlap
+/// without a dedicated thread each. Dropping the future cancels the run. See
+/// [`Orchestrator::run_async`] for the scheduling details.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn example() {
+/// use tpu_doc::{run_checks_async, TpuDocConfig};
+///
+/// let config = TpuDocConfig { parallel: true, ..Default::default() };
+/// let report = run_checks_async(config, Vec::new()).await.expect("Validation failed");
+/// println!("Checks passed: {}", report.summary().passed);
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn run_checks_async(
+    config: TpuDocConfig,
+    extra: Vec<RegisteredCheck>,
+) -> Result<ValidationReport, TpuDocError> {
+    let orchestrator = build_orchestrator(&config, extra, |check| check)?;
+    let check_ids = selected_check_ids(&orchestrator, &config)?;
+    Ok(orchestrator.run_async(&check_ids).await)
+}
+
+/// Orchestrator with the built-in checks, then the caller's, registered; an
+/// error when a caller's check reuses an ID
+fn build_orchestrator(
+    config: &TpuDocConfig,
+    extra: Vec<RegisteredCheck>,
+    wrap: impl Fn(RegisteredCheck) -> RegisteredCheck,
+) -> Result<CheckOrchestrator, TpuDocError> {
     let orch_config = OrchestratorConfig {
         parallel: config.parallel,
         fail_fast: config.fail_fast,
         timeout_ms: config.timeout_ms,
-        max_parallel: 4,
+        bench_timeout_ms: config.bench_timeout_ms,
+        max_parallel: default_max_parallel(),
+        max_duration_ms: config.max_duration_ms,
+        run_id: config.run_id.clone(),
+        policy: config.policy.clone(),
+        settings: checks::settings::RunSettings {
+            net_timeout_ms: config.net_timeout_ms,
+            bench_timeout_ms: config.bench_timeout_ms,
+            capture_output: config.capture_output,
+            deterministic: config.deterministic,
+            perf_level: config.perf_level,
+            warmup: config.warmup,
+            hbm_working_set: config.hbm_working_set,
+            busy_tpu: config.busy_tpu,
+            require_locked_egress: config.require_locked_egress,
+            gcloud_fallback: config.gcloud_fallback,
+        },
     };
 
     let mut orchestrator = CheckOrchestrator::new(orch_config);
+    if let Some(token) = &config.cancellation {
+        orchestrator.set_cancellation_token(token.clone());
+    }
+    let mut checks = create_all_checks();
+    checks.extend(extra);
+    if !config.env_vars.is_empty() {
+        let rules = config.env_vars.clone();
+        if let Some(check) = checks.iter_mut().find(|check| check.id == "STK-007") {
+            check.check_fn = Box::new(move || checks::stack::run_stk007_with(&rules));
+        }
+    }
+    if !config.latency_endpoints.is_empty() {
+        let endpoints = config.latency_endpoints.clone();
+        if let Some(check) = checks.iter_mut().find(|check| check.id == "IO-005") {
+            check.check_fn = Box::new(move || checks::io::run_io005_with(&endpoints));
+        }
+    }
+    if let Some(expected) = config.expected_gke_service_account.clone() {
+        if let Some(check) = checks.iter_mut().find(|check| check.id == "SEC-003") {
+            check.check_fn = Box::new(move || checks::security::run_sec003_with(Some(&expected)));
+        }
+    }
+    if let Some(plan) = config.checkpoint_plan {
+        if let Some(check) = checks.iter_mut().find(|check| check.id == "IO-004") {
+            check.check_fn = Box::new(move || checks::io::run_io004_with(Some(&plan)));
+        }
+    }
+    if let (Some(ttl_secs), Some(history)) = (config.cache_ttl_secs, ResultHistory::open_default()) {
+        checks = checks.into_iter().map(|check| history.cached_check(check, ttl_secs)).collect();
+    }
+    orchestrator.register_checks(checks.into_iter().map(wrap).collect())?;
+    Ok(orchestrator)
+}
 
-    // Register all checks
-    orchestrator.register_checks(create_all_checks());
+/// IDs of the checks selected by `only_checks`, `skip_checks`, or
+/// `categories`; an error when they depend on each other in a cycle
+fn selected_check_ids(orchestrator: &CheckOrchestrator, config: &TpuDocConfig) -> Result<Vec<String>, TpuDocError> {
+    let checks = orchestrator.checks();
 
-    // Determine which checks to run
-    let report = if !config.only_checks.is_empty() {
+    // A mistyped ID would otherwise select nothing, or skip nothing, silently
+    let unknown: Vec<(String, Option<String>)> = config
+        .only_checks
+        .iter()
+        .chain(&config.skip_checks)
+        .filter(|id| !checks.iter().any(|c| &c.id == *id))
+        .map(|id| (id.clone(), closest_check_id(id, checks)))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(TpuDocError::UnknownCheckIds { ids: unknown });
+    }
+
+    let check_ids: Vec<String> = if !config.only_checks.is_empty() {
         // Run only specified checks
-        orchestrator.run_specific(&config.only_checks)
-    } else if !config.skip_checks.is_empty() {
-        // Run all except skipped
-        orchestrator.run_excluding(&config.skip_checks)
-    } else if let Some(ref categories) = config.categories {
-        // Run specific categories (supports multiple)
-        if categories.is_empty() {
-            orchestrator.run_all()
-        } else {
-            orchestrator.run_categories(categories)
-        }
+        config.only_checks.clone()
     } else {
-        // Run all checks
-        orchestrator.run_all()
+        checks
+            .iter()
+            .filter(|c| config.selects(&c.id, &c.category))
+            .map(|c| c.id.clone())
+            .collect()
     };
 
-    Ok(report)
+    // A cycle is a configuration mistake (usually in plugin metadata), so
+    // refuse the run rather than report failures of checks that never ran
+    match orchestrator.dependency_cycle(&check_ids) {
+        Some(chain) => Err(TpuDocError::DependencyCycle { chain }),
+        None => Ok(check_ids),
+    }
+}
+
+/// The registered check ID `id` is most likely a misspelling of, if any
+fn closest_check_id(id: &str, checks: &[std::sync::Arc<RegisteredCheck>]) -> Option<String> {
+    let id = id.to_uppercase();
+    checks
+        .iter()
+        .map(|c| (util::edit_distance(&id, &c.id.to_uppercase()), &c.id))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known.clone())
 }
 
 /// Alias for run_checks (for backward compatibility)
@@ -2,12 +2,28 @@
 //!
 //! TPU environment diagnostics, discovery, and troubleshooting tool.
 
-use tpu_doc::cli::args::{Args, Command};
-use tpu_doc::cli::output::get_formatter;
+use tpu_doc::checks::custom::create_custom_checks;
+use tpu_doc::checks::fix;
+use tpu_doc::checks::io::{CheckpointPlan, DEFAULT_CHECKPOINT_KEEP};
+use tpu_doc::checks::plugins::{self, create_plugin_checks, PluginLoad};
+use tpu_doc::checks::stack::EnvVarRule;
+use tpu_doc::cli::args::{Args, Command, ErrorFormat, OutputFormat};
+use tpu_doc::cli::errors::ErrorReport;
+use tpu_doc::cli::config::{ConfigFile, DEFAULT_CONFIG_PATHS};
+use tpu_doc::cli::select;
+use tpu_doc::cli::output::{exit_status, format_badge, format_timeline, formatter_for_args, to_ascii};
 use tpu_doc::commands;
+use tpu_doc::engine::history::ResultHistory;
+use tpu_doc::engine::orchestrator::create_all_checks;
+use tpu_doc::engine::ownership::Ownership;
+use tpu_doc::engine::policy::Policy;
+use tpu_doc::engine::signing::sign_report;
 use tpu_doc::version::get_build_info;
-use tpu_doc::{run_checks as run_validation, TpuDocConfig};
+use tpu_doc::{
+    run_checks_with as run_validation, RegisteredCheck, Report, TpuDocConfig, TpuDocError, EXIT_USAGE_ERROR,
+};
 
+use std::path::Path;
 use std::process::ExitCode;
 
 fn main() -> ExitCode {
@@ -15,11 +31,14 @@ fn main() -> ExitCode {
     let args = match Args::parse() {
         Ok(args) => args,
         Err(e) => {
-            eprintln!("Error: {}", e);
-            eprintln!("Run 'tpu-doc --help' for usage information.");
-            return ExitCode::from(3);
+            let raw: Vec<String> = std::env::args().skip(1).collect();
+            ErrorReport::usage(&e).print(ErrorFormat::scan(&raw));
+            return ExitCode::from(EXIT_USAGE_ERROR);
         }
     };
+    for warning in &args.env_warnings {
+        eprintln!("Warning: {}", warning);
+    }
 
     // Handle help flag
     if args.help {
@@ -30,26 +49,28 @@ fn main() -> ExitCode {
     // Handle commands
     match args.command {
         Command::Version => {
-            print_version();
-            ExitCode::SUCCESS
-        }
-        Command::List => {
-            print_check_list();
+            print_version(&args);
             ExitCode::SUCCESS
         }
-        Command::Check => run_checks(&args),
+        Command::List => run_list(&args),
+        Command::Check | Command::Tui => run_checks(&args),
         Command::Info => run_info(&args),
         Command::Stack => run_stack(&args),
         Command::Cache => run_cache(&args),
         Command::Snapshot => run_snapshot(&args),
         Command::Audit => run_audit(&args),
         Command::Analyze => run_analyze(&args),
+        Command::KnownIssues => run_known_issues(&args),
+        Command::Verify => run_verify(&args),
     }
 }
 
-fn print_version() {
+fn print_version(args: &Args) {
     let info = get_build_info();
-    println!("{}", info);
+    match args.format {
+        OutputFormat::Json => println!("{}", info.to_json()),
+        _ => println!("{}", info),
+    }
 }
 
 fn print_help() {
@@ -66,9 +87,12 @@ COMMANDS:
     cache       Analyze XLA compilation cache
     snapshot    Capture resource utilization snapshot
     audit       Run configuration audit
-    analyze     AI-powered log analysis (requires --ai flag)
+    analyze     Log analysis (offline signatures, or AI with --ai)
+    known-issues Known bugs for the installed jax/jaxlib/libtpu/driver
+    tui         Run checks under a live dashboard (tui feature)
+    verify      Check the signature of a report written with --sign
     version     Print version information
-    list        List all available checks
+    list        List checks with dependencies, durations, severity, and tags
 
 CHECK OPTIONS:
     --all           Run all checks (default)
@@ -78,48 +102,120 @@ CHECK OPTIONS:
     --io            Run I/O throughput checks only
     --security      Run security posture checks only
     --config-audit  Run configuration audit checks only
+    --category <C>  Run categories (comma-separated, repeatable): hardware, stack,
+                    performance, io, security, config
     --skip <ID>     Skip specific check by ID (repeatable)
     --only <ID>     Run only specific check by ID (repeatable)
 
 OUTPUT OPTIONS:
-    --format <FMT>  Output format: text (default), json, junit
-    --quiet         Only output failures and warnings
+    --format <FMT>  Output format: text (default), json, junit, or a format
+                    registered by a program embedding tpu-doc
+    --quiet         Only list failed and warned checks (text, json, junit)
     --verbose       Include detailed diagnostic information
     --no-color      Disable colored output
+    --timeline      Show when each check started and how long it ran
+    --group-by <G>  Group text output by: category (default), status
+    --theme <T>     Color theme: default, high-contrast
+    --glyphs <L>    Status labels as pass,warn,fail,skip (e.g. "OK,WARN,FAIL,SKIP")
+    --ascii         Restrict output to ASCII characters
+    --summary-only  Print only the summary counts and exit code (text or json)
+    --json-compact  Write the JSON report on a single line (implies --format json)
+    --badge-file <PATH>  Write the overall result as a shields.io JSON badge
+    --sign          Sign the JSON report with the [signing] key of the config file
 
 BEHAVIOR OPTIONS:
     --timeout <MS>  Global timeout in milliseconds (default: 30000)
+    --net-timeout <MS>   Timeout for each network probe (default: 5000)
+    --bench-timeout <MS> Timeout for each benchmark; replaces --timeout for
+                    performance checks (default: 120000)
     --parallel      Run checks in parallel where safe
     --fail-fast     Stop on first failure
+    --max-duration <DUR> Skip checks that would not finish within DUR (e.g. 120s)
+    --cached <AGE>  Reuse benchmark/throughput results younger than AGE (e.g. 1h)
+    --deterministic Run benchmarks with fixed sizes, iterations, seeds, and warm-up
+    --perf-level <LEVEL> Benchmark intensity: smoke (quick boot gating), standard
+                    (default), or soak (burn-in, adds a 10-minute thermal test)
+    --gcloud-fallback Read firewall rules, routers, and disks with gcloud when
+                    the Compute API is not reachable with the VM's credentials
+    --checkpoint-size <SIZE> Expected size of one checkpoint (e.g. 250GB); IO-004
+                    checks CHECKPOINT_DIR can hold the projected footprint
+    --keep <N>      Checkpoints kept alongside --checkpoint-size (default: 1)
+    --run-id <ID>   Report the run under ID (e.g. the training job's) instead of
+                    a generated one
+    --fix           Offer fixes for safe-to-fix findings, then re-run those checks
+    --interactive   Pick the checks to run from a list before running them
 
 CONFIGURATION:
     --config <FILE>   Load configuration from TOML file
     --baseline <FILE> Compare against baseline file
 
+ENVIRONMENT:
+    TPU_PREFLIGHT_FORMAT, _VERBOSE, _CONFIG, _TIMEOUT, _NET_TIMEOUT, _BENCH_TIMEOUT,
+    TPU_PREFLIGHT_PARALLEL, _CATEGORIES, _OUTPUT
+                      Defaults for the matching options; the command line wins
+    TPU_PREFLIGHT_MATRIX_URL, TPU_PREFLIGHT_PLUGIN_DIR
+                      Compatibility matrix URL and plugin directories
+    (TPU_DOC_* names are deprecated aliases)
+
+LIST OPTIONS:
+    --category <C>  List these categories' checks (comma-separated, repeatable)
+    --tag <TAG>     List checks tagged cpu, network, tpu-exclusive, or cacheable (repeatable)
+
 INFO OPTIONS:
-    (uses global --format option)
+    --packages      Include every installed package (pip freeze, conda list)
+    --diff <OLD> [NEW] Compare a saved `info --format json` snapshot with NEW,
+                    or with the current environment
 
 STACK OPTIONS:
     --matrix        Display full compatibility matrix
+    --matrix-url <URL> Fetch a newer matrix (JSON) from URL, cached with ETag
+
+CACHE OPTIONS:
+    clean               Remove cache entries (use with --older-than)
+    --older-than <AGE>  Minimum entry age to remove, e.g. 30d, 12h
+    --dry-run           Show what would be removed without deleting
+
+VERIFY OPTIONS:
+    <REPORT>        Signed JSON report; exit 0 if the signature is valid, 1 if not
+    --key <FILE>    Ed25519 public (or private) key PEM instead of the [signing] key
+
+AUDIT OPTIONS:
+    --fail-on <L>   Exit non-zero on: warning (default), error
 
 SNAPSHOT OPTIONS:
-    --continuous <N>  Refresh every N seconds
+    --continuous <N>    Live top-like view refreshed every N seconds (q to quit)
+    --no-tty            With --continuous or tui, print plain scrolling output instead
+    --duration <DUR>    Sample for a window (e.g. 10m) and emit JSONL records
+    --interval <DUR>    Time between samples (default: 5s)
+    --output <FILE>     Append JSONL records to FILE instead of stdout
+    --alert <M=W[:C]>   Alert threshold for hbm, temp, or mem (repeatable;
+                        default: hbm=95:99, temp=85:90, mem=90:97)
+    --once              One snapshot; exit 1 on a CRIT alert, 2 on a WARN alert
 
 ANALYZE OPTIONS:
-    --ai              Enable AI analysis (required)
-    --provider <P>    AI provider: anthropic, google (default: anthropic)
+    --ai              Use AI analysis instead of offline signature matching
+    --provider <P>    AI provider: anthropic, google, vertex (default: anthropic)
     --model <M>       Model to use (provider-specific)
     --question <Q>    Specific question to answer about the log
+    --max-tokens <N>  Maximum tokens in the final response (default: 4096)
+    --ai-base-url <URL> Send AI requests to URL (e.g. an internal gateway)
+    --no-cache        Always call the provider (skip ~/.tpu-preflight/ai-cache)
+    --cache-ttl <DUR> Reuse cached responses younger than DUR (default: 24h)
+    --interactive     Ask follow-up questions after the analysis
+    --no-redact       Send content without redacting secrets, IPs, buckets
 
 GENERAL:
     -h, --help      Print this help message
     -V, --version   Print version information
+    --errors <FMT>  Errors that stop the command: text (default), json (one
+                    object on stderr with code, message, context, hint)
 
 EXIT CODES:
     0   All checks passed
     1   One or more checks failed
     2   Warnings only (no failures)
-    3   Runtime error
+    3   Runtime error (the run could not complete)
+    4   Invalid arguments, unknown check IDs, or invalid configuration file
 
 EXAMPLES:
     tpu-doc                           Run all checks with default settings
@@ -128,96 +224,250 @@ EXAMPLES:
     tpu-doc info --format json        Environment info as JSON
     tpu-doc stack                     Analyze software stack
     tpu-doc stack --matrix            Show compatibility matrix
+    tpu-doc known-issues              Known bugs in the installed versions
     tpu-doc cache                     Analyze XLA cache status
+    tpu-doc cache clean --older-than 30d --dry-run
     tpu-doc snapshot                  Capture resource snapshot
     tpu-doc snapshot --continuous 5   Refresh every 5 seconds
+    tpu-doc snapshot --duration 10m --interval 5s --output samples.jsonl
+    tpu-doc snapshot --once --alert hbm=90:98
     tpu-doc audit                     Run configuration audit
+    tpu-doc analyze error.log         Match log against known error signatures
     tpu-doc analyze error.log --ai    AI analysis of log file
     tpu-doc check --format json --quiet > results.json
+    tpu-doc check --format json --sign > report.json
+    tpu-doc verify report.json --key report-signing.pub.pem
     tpu-doc list                      List all available checks"#
     );
 }
 
-fn print_check_list() {
-    println!("Available checks:");
-    println!();
-    println!("HARDWARE CHECKS:");
-    println!("  HW-001   TPU Device Detection");
-    println!("  HW-002   HBM Memory Availability");
-    println!("  HW-003   TPU Thermal Status");
-    println!("  HW-004   TPU Error Counters");
-    println!("  HW-005   ICI Interconnect Status");
-    println!("  HW-006   Driver Status");
-    println!();
-    println!("STACK CHECKS:");
-    println!("  STK-001  JAX Version Check");
-    println!("  STK-002  libtpu Version Check");
-    println!("  STK-003  XLA Compiler Check");
-    println!("  STK-004  Python Version Check");
-    println!("  STK-005  PJRT Plugin Check");
-    println!("  STK-006  Dependency Conflict Check");
-    println!("  STK-007  Environment Variables Check");
-    println!();
-    println!("PERFORMANCE CHECKS:");
-    println!("  PERF-001 MXU Utilization Baseline");
-    println!("  PERF-002 HBM Bandwidth Test");
-    println!("  PERF-003 Chip-to-Chip Latency");
-    println!("  PERF-004 XLA Compilation Latency");
-    println!("  PERF-005 Memory Pressure Test");
-    println!();
-    println!("I/O CHECKS:");
-    println!("  IO-001   GCS Read Throughput");
-    println!("  IO-002   Local Disk Throughput");
-    println!("  IO-003   GCS Connectivity");
-    println!("  IO-004   Checkpoint Directory Access");
-    println!("  IO-005   Network Latency to GCP Services");
-    println!("  IO-006   DNS Resolution");
-    println!();
-    println!("SECURITY CHECKS:");
-    println!("  SEC-001  Service Account Permissions");
-    println!("  SEC-002  Network Exposure");
-    println!("  SEC-003  Workload Identity Status");
-    println!("  SEC-004  Encryption Status");
-    println!("  SEC-005  Instance Metadata Access");
-    println!("  SEC-006  SSH Key Management");
-    println!("  SEC-007  Firewall Rules");
-    println!();
-    println!("CONFIGURATION AUDIT CHECKS:");
-    println!("  CFG-001  XLA Flags Audit");
-    println!("  CFG-002  JAX Configuration Audit");
-    println!("  CFG-003  Memory Preallocation Check");
-    println!("  CFG-004  Distributed Configuration Check");
-    println!("  CFG-005  Logging Configuration Check");
+/// Report `error`, which stopped the command while `context`, in the
+/// format `--errors` selects, and return its exit code
+fn fail(args: &Args, context: &str, error: &TpuDocError) -> ExitCode {
+    ErrorReport::from_error(context, error).print(args.errors);
+    ExitCode::from(error.exit_code())
+}
+
+fn run_list(args: &Args) -> ExitCode {
+    let (extra_checks, ownership) = match load_extension_checks(args) {
+        Ok((file, plugins)) => {
+            let mut checks = file.as_ref().map(|f| create_custom_checks(&f.custom_checks)).unwrap_or_default();
+            checks.extend(create_plugin_checks(&plugins.plugins));
+            (checks, file.map(|f| f.ownership).unwrap_or_default())
+        }
+        Err(e) => {
+            return fail(args, "loading configuration", &e);
+        }
+    };
+
+    match commands::list::run(args, extra_checks, &ownership) {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            fail(args, "listing checks", &e)
+        }
+    }
+}
+
+/// Load the custom checks from the configuration file and the plugin
+/// checks. Plugins that fail to load are reported on stderr and skipped.
+fn load_extension_checks(args: &Args) -> Result<(Option<ConfigFile>, PluginLoad), TpuDocError> {
+    let config = ConfigFile::discover(args.config.as_deref())?;
+    let custom_ids: Vec<String> = config
+        .iter()
+        .flat_map(|c| c.custom_checks.iter().map(|check| check.id.clone()))
+        .collect();
+
+    let plugin_dirs = plugins::plugin_dirs();
+    for warning in plugins::deprecated_plugin_dir_warnings(&plugin_dirs) {
+        eprintln!("Warning: {}", warning);
+    }
+    let plugins = plugins::discover(&plugin_dirs, &custom_ids);
+    for error in &plugins.errors {
+        eprintln!("Warning: ignoring plugin {}", error);
+    }
+    Ok((config, plugins))
 }
 
 fn run_checks(args: &Args) -> ExitCode {
     // Build configuration from arguments
-    let config = TpuDocConfig::from_args(args);
+    let mut config = TpuDocConfig::from_args(args);
+
+    // Custom checks from the configuration file and plugin checks run alongside the built-ins
+    let (extra_checks, policy, ownership, signing_key) = match load_extension_checks(args) {
+        Ok((file, plugins)) => {
+            // A missing or unreadable signing key stops the run before the checks do
+            let signing_key = match (args.sign, &file) {
+                (false, _) => None,
+                (true, Some(file)) => match file.signing_key() {
+                    Ok(key) => Some(key),
+                    Err(e) => return fail(args, "loading the signing key", &e),
+                },
+                (true, None) => {
+                    let error = TpuDocError::ConfigError {
+                        path: DEFAULT_CONFIG_PATHS.join(", "),
+                        message: "not found; --sign needs the [signing] key of a configuration file".to_string(),
+                        source: None,
+                    };
+                    return fail(args, "loading the signing key", &error);
+                }
+            };
+            let mut checks = file.as_ref().map(|f| create_custom_checks(&f.custom_checks)).unwrap_or_default();
+            checks.extend(create_plugin_checks(&plugins.plugins));
+            if let Some(file) = &file {
+                config.env_vars = file.env_vars.clone();
+                config.latency_endpoints = file.latency_endpoints.clone();
+                config.net_timeout_ms = args.net_timeout_ms.or(file.net_timeout_ms).unwrap_or(config.net_timeout_ms);
+                config.bench_timeout_ms = args.bench_timeout_ms.or(file.bench_timeout_ms).unwrap_or(config.bench_timeout_ms);
+                config.warmup = file.warmup;
+                config.hbm_working_set = file.hbm_working_set.unwrap_or(config.hbm_working_set);
+                config.busy_tpu = file.busy_tpu.unwrap_or(config.busy_tpu);
+                config.require_locked_egress = file.require_locked_egress;
+                config.gcloud_fallback = args.gcloud_fallback || file.gcloud_fallback;
+                config.expected_gke_service_account = file.expected_gke_service_account.clone();
+                if let Some(size_bytes) = args.checkpoint_size_bytes.or(file.checkpoint_size_bytes) {
+                    let keep = args.checkpoint_keep.or(file.checkpoint_keep);
+                    config.checkpoint_plan = Some(CheckpointPlan {
+                        size_bytes,
+                        keep: keep.unwrap_or(DEFAULT_CHECKPOINT_KEEP),
+                    });
+                }
+            }
+            let (policy, ownership) = file.map(|f| (f.policy, f.ownership)).unwrap_or_default();
+            // Known-acceptable findings from the [policy] table no longer fail the run
+            config.policy = policy.clone();
+            (checks, policy, ownership, signing_key)
+        }
+        Err(e) => {
+            return fail(args, "loading configuration", &e);
+        }
+    };
+
+    // The picker starts from the checks the options select
+    if args.interactive {
+        let builtin = create_all_checks();
+        match select::pick_checks(builtin.iter().chain(&extra_checks), &config) {
+            Ok(selected) if selected.is_empty() => {
+                eprintln!("No checks selected");
+                return ExitCode::SUCCESS;
+            }
+            Ok(selected) => config.only_checks = selected,
+            Err(e) => {
+                return fail(args, "selecting checks", &e);
+            }
+        }
+    }
 
     // Run validation checks
-    let report = match run_validation(config) {
+    let env_vars = config.env_vars.clone();
+    let run = if args.command == Command::Tui {
+        run_dashboard(args, config, extra_checks)
+    } else {
+        run_validation(config, extra_checks)
+    };
+    let mut report = match run {
         Ok(report) => report,
         Err(e) => {
-            eprintln!("Error running checks: {}", e);
-            return ExitCode::from(3);
+            return fail(args, "running checks", &e);
         }
     };
 
+    // Keep the latest results for later `--cached` runs
+    if let Some(history) = ResultHistory::open_default() {
+        history.record(&report);
+    }
+
+    ownership.apply(&mut report);
+
     // Get appropriate formatter
-    let formatter = get_formatter(&args.format, args.no_color, args.verbose, args.quiet);
+    let formatter = formatter_for_args(args);
 
     // Format and print output
-    let output = formatter.format(&report);
+    let mut output = formatter.format(&report);
+    if let Some(key) = &signing_key {
+        output = sign_report(&output, key);
+    }
     println!("{}", output);
+    if args.timeline && args.format == OutputFormat::Text {
+        let timeline = format_timeline(&report);
+        println!("\n{}", if args.ascii { to_ascii(&timeline) } else { timeline });
+    }
+
+    // Offer fixes; the exit code reflects the re-run results of fixed checks
+    if args.fix {
+        run_fixes(args, &policy, &ownership, env_vars, &mut report);
+    }
+
+    if let Some(path) = &args.badge_file {
+        if let Err(e) = std::fs::write(path, format_badge(&report)) {
+            let error = TpuDocError::io(&format!("--badge-file {}", path), e);
+            return fail(args, "writing badge file", &error);
+        }
+    }
 
     // Determine exit code based on results
-    let summary = report.summary();
-    if summary.failed > 0 {
-        ExitCode::from(1)
-    } else if summary.warned > 0 {
-        ExitCode::from(2)
-    } else {
-        ExitCode::SUCCESS
+    ExitCode::from(exit_status(&report.summary()).0)
+}
+
+#[cfg(feature = "tui")]
+fn run_dashboard(args: &Args, config: TpuDocConfig, extra: Vec<RegisteredCheck>) -> Result<Report, TpuDocError> {
+    commands::tui::run(args, config, extra)
+}
+
+#[cfg(not(feature = "tui"))]
+fn run_dashboard(_args: &Args, _config: TpuDocConfig, _extra: Vec<RegisteredCheck>) -> Result<Report, TpuDocError> {
+    Err(TpuDocError::CommandError {
+        command: "tui".to_string(),
+        message: "The dashboard is not enabled. Rebuild with: cargo build --features tui".to_string(),
+        source: None,
+    })
+}
+
+/// Offer the available fixes for `report`'s findings, re-run the checks that
+/// were fixed and replace their results in `report`
+fn run_fixes(args: &Args, policy: &Policy, ownership: &Ownership, env_vars: Vec<EnvVarRule>, report: &mut Report) {
+    let fixes = fix::available_fixes(report);
+    if fixes.is_empty() {
+        eprintln!("\nNo automatic fixes are available for these findings");
+        return;
+    }
+
+    eprintln!("\n{} finding(s) can be fixed automatically:", fixes.len());
+    let fixed = fix::confirm_and_apply(
+        &fixes,
+        Path::new(fix::DEFAULT_ENV_FILE),
+        &mut std::io::stdin().lock(),
+        &mut std::io::stderr(),
+    );
+    if fixed.is_empty() {
+        return;
+    }
+
+    let config = TpuDocConfig {
+        only_checks: fixed,
+        env_vars,
+        policy: policy.clone(),
+        ..TpuDocConfig::from_args(args)
+    };
+    let mut rerun = match run_validation(config, Vec::new()) {
+        Ok(rerun) => rerun,
+        Err(e) => {
+            eprintln!("Error re-running fixed checks: {}", e);
+            return;
+        }
+    };
+    if let Some(history) = ResultHistory::open_default() {
+        history.record(&rerun);
+    }
+    ownership.apply(&mut rerun);
+
+    println!("\nRe-run after fixes:\n{}", formatter_for_args(args).format(&rerun));
+    for check in rerun.checks {
+        if let Some(slot) = report.checks.iter_mut().find(|c| c.id == check.id) {
+            *slot = check;
+        }
     }
 }
 
@@ -228,8 +478,7 @@ fn run_info(args: &Args) -> ExitCode {
             ExitCode::SUCCESS
         }
         Err(e) => {
-            eprintln!("Error gathering environment info: {}", e);
-            ExitCode::from(3)
+            fail(args, "gathering environment info", &e)
         }
     }
 }
@@ -241,8 +490,31 @@ fn run_stack(args: &Args) -> ExitCode {
             ExitCode::SUCCESS
         }
         Err(e) => {
-            eprintln!("Error analyzing stack: {}", e);
-            ExitCode::from(3)
+            fail(args, "analyzing stack", &e)
+        }
+    }
+}
+
+fn run_known_issues(args: &Args) -> ExitCode {
+    match commands::known_issues::run(args) {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            fail(args, "looking up known issues", &e)
+        }
+    }
+}
+
+fn run_verify(args: &Args) -> ExitCode {
+    match commands::verify::run(args) {
+        Ok((output, valid)) => {
+            println!("{}", output);
+            ExitCode::from(if valid { 0 } else { 1 })
+        }
+        Err(e) => {
+            fail(args, "verifying report", &e)
         }
     }
 }
@@ -254,34 +526,65 @@ fn run_cache(args: &Args) -> ExitCode {
             ExitCode::SUCCESS
         }
         Err(e) => {
-            eprintln!("Error analyzing cache: {}", e);
-            ExitCode::from(3)
+            fail(args, "analyzing cache", &e)
         }
     }
 }
 
 fn run_snapshot(args: &Args) -> ExitCode {
+    if args.once {
+        return match commands::snapshot::run_once(args) {
+            Ok((output, worst)) => {
+                println!("{}", output);
+                ExitCode::from(worst.map_or(0, |level| level.exit_code()))
+            }
+            Err(e) => {
+                fail(args, "capturing snapshot", &e)
+            }
+        };
+    }
+
     match commands::snapshot::run(args) {
         Ok(output) => {
-            println!("{}", output);
+            if !output.is_empty() {
+                println!("{}", output);
+            }
             ExitCode::SUCCESS
         }
         Err(e) => {
-            eprintln!("Error capturing snapshot: {}", e);
-            ExitCode::from(3)
+            fail(args, "capturing snapshot", &e)
         }
     }
 }
 
 fn run_audit(args: &Args) -> ExitCode {
-    match commands::audit::run(args) {
-        Ok(output) => {
-            println!("{}", output);
-            ExitCode::SUCCESS
+    // Custom and plugin checks in the config category are part of the audit
+    let (extra_checks, policy, ownership) = match load_extension_checks(args) {
+        Ok((file, plugins)) => {
+            let mut checks = file.as_ref().map(|f| create_custom_checks(&f.custom_checks)).unwrap_or_default();
+            checks.extend(create_plugin_checks(&plugins.plugins));
+            let (policy, ownership) = file.map(|f| (f.policy, f.ownership)).unwrap_or_default();
+            (checks, policy, ownership)
+        }
+        Err(e) => {
+            return fail(args, "loading configuration", &e);
+        }
+    };
+
+    match commands::audit::run(args, extra_checks, policy) {
+        Ok(mut report) => {
+            ownership.apply(&mut report);
+            let status = commands::audit::exit_status(&report, args.fail_on.unwrap_or_default());
+            println!("{}", commands::audit::format(args, &report, status));
+            ExitCode::from(status.0)
         }
         Err(e) => {
-            eprintln!("Error running audit: {}", e);
-            ExitCode::from(3)
+            let mut report = ErrorReport::from_error("running audit", &e);
+            if matches!(e, TpuDocError::UnknownCheckIds { .. }) {
+                report = report.with_hint("Run 'tpu-doc list --config-audit' to see the available checks.");
+            }
+            report.print(args.errors);
+            ExitCode::from(e.exit_code())
         }
     }
 }
@@ -293,8 +596,7 @@ fn run_analyze(args: &Args) -> ExitCode {
             ExitCode::SUCCESS
         }
         Err(e) => {
-            eprintln!("Error analyzing log: {}", e);
-            ExitCode::from(3)
+            fail(args, "analyzing log", &e)
         }
     }
 }
@@ -0,0 +1,684 @@
+//! Compute Engine API interface.
+//!
+//! Reads project resources the metadata server does not expose, such as
+//! firewall rules, Cloud NAT gateways, and the encryption keys of attached
+//! disks, authenticating with the default service account's token from the
+//! metadata server. The service account needs `compute.firewalls.list` and
+//! `compute.routers.list` (e.g. `roles/compute.networkViewer`),
+//! `compute.instances.get` and `compute.disks.get` (e.g.
+//! `roles/compute.viewer`), and the VM a scope that covers the Compute API.
+//! A TPU VM is not a Compute Engine instance in the project; its disks are
+//! read from its node in the Cloud TPU API (`tpu.nodes.get`, e.g.
+//! `roles/tpu.viewer`).
+//!
+//! Requests go through [`gcp::api_get`], which needs the TLS support of the
+//! `ai` feature; without it every request returns an error, and the checks
+//! built on this module skip. With the `gcloud` fallback enabled, a listing
+//! whose API requests fail is read with `gcloud` instead (see
+//! [`crate::platform::gcloud`]), and each listing returns the
+//! [`DetectionMethod`] that produced it.
+
+use crate::platform::gcloud::{self, DetectionMethod};
+use crate::platform::gcp;
+use crate::util::json::{self, JsonValue};
+use crate::TpuDocError;
+use std::fmt;
+
+const API_HOST: &str = "compute.googleapis.com";
+const TPU_API_HOST: &str = "tpu.googleapis.com";
+
+/// Prefix that turns an API path into a resource URI `gcloud` accepts
+const RESOURCE_URI_PREFIX: &str = "https://www.googleapis.com";
+
+/// Source ranges that match every address
+const ANYWHERE: &[&str] = &["0.0.0.0/0", "::/0"];
+
+/// A VPC firewall rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirewallRule {
+    pub name: String,
+    /// Network the rule belongs to, e.g. `default`
+    pub network: String,
+    /// `INGRESS` or `EGRESS`
+    pub direction: String,
+    /// Lower numbers take precedence
+    pub priority: u32,
+    pub source_ranges: Vec<String>,
+    pub source_tags: Vec<String>,
+    pub source_service_accounts: Vec<String>,
+    /// Destinations of an egress rule; empty means every address
+    pub destination_ranges: Vec<String>,
+    /// Instances the rule applies to; empty together with
+    /// `target_service_accounts` means every instance in the network
+    pub target_tags: Vec<String>,
+    pub target_service_accounts: Vec<String>,
+    pub allowed: Vec<FirewallPermission>,
+    pub denied: Vec<FirewallPermission>,
+    pub disabled: bool,
+}
+
+/// Protocol and ports a firewall rule allows or denies
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirewallPermission {
+    /// `tcp`, `udp`, `icmp`, ..., or `all`
+    pub protocol: String,
+    /// Ports and ranges (`22`, `8000-8999`); empty means every port
+    pub ports: Vec<String>,
+}
+
+impl FirewallPermission {
+    /// Whether this covers every protocol and port
+    pub fn covers_all(&self) -> bool {
+        self.protocol == "all" && self.ports.is_empty()
+    }
+
+    /// Whether this covers `port` over `protocol`
+    pub fn covers(&self, protocol: &str, port: u16) -> bool {
+        if self.protocol != "all" && !self.protocol.eq_ignore_ascii_case(protocol) {
+            return false;
+        }
+        self.ports.is_empty()
+            || self.ports.iter().any(|range| match range.split_once('-') {
+                Some((low, high)) => match (low.trim().parse::<u16>(), high.trim().parse::<u16>()) {
+                    (Ok(low), Ok(high)) => (low..=high).contains(&port),
+                    _ => false,
+                },
+                None => range.trim().parse() == Ok(port),
+            })
+    }
+}
+
+/// What a firewall rule's targets are matched against: the instance's
+/// networks, network tags, and service account
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FirewallTarget {
+    pub networks: Vec<String>,
+    pub tags: Vec<String>,
+    pub service_account: Option<String>,
+}
+
+impl FirewallRule {
+    /// Whether the rule is enabled and applies to `target`
+    pub fn applies_to(&self, target: &FirewallTarget) -> bool {
+        if self.disabled || !target.networks.contains(&self.network) {
+            return false;
+        }
+        if self.target_tags.is_empty() && self.target_service_accounts.is_empty() {
+            return true;
+        }
+        self.target_tags.iter().any(|tag| target.tags.contains(tag))
+            || target
+                .service_account
+                .as_ref()
+                .is_some_and(|sa| self.target_service_accounts.contains(sa))
+    }
+
+    /// Whether an ingress rule matches traffic from any address; a rule
+    /// without source filters matches everything
+    pub fn from_anywhere(&self) -> bool {
+        self.source_ranges.iter().any(|range| ANYWHERE.contains(&range.as_str()))
+            || (self.source_ranges.is_empty()
+                && self.source_tags.is_empty()
+                && self.source_service_accounts.is_empty())
+    }
+
+    /// Whether an egress rule matches traffic to any address
+    pub fn to_anywhere(&self) -> bool {
+        self.destination_ranges.is_empty()
+            || self.destination_ranges.iter().any(|range| ANYWHERE.contains(&range.as_str()))
+    }
+
+    /// The rule's targets as text, e.g. `all instances` or `tags ssh, dev`
+    pub fn describe_targets(&self) -> String {
+        match (self.target_tags.is_empty(), self.target_service_accounts.is_empty()) {
+            (true, true) => "all instances".to_string(),
+            (false, _) => format!("tags {}", self.target_tags.join(", ")),
+            (true, false) => format!("service accounts {}", self.target_service_accounts.join(", ")),
+        }
+    }
+}
+
+/// The ingress rules in `rules` that let any address reach `port` over
+/// `protocol` on `target`, leaving out those overridden by a deny rule of
+/// equal or higher precedence
+pub fn open_to_anywhere<'a>(
+    rules: &'a [FirewallRule],
+    target: &FirewallTarget,
+    protocol: &str,
+    port: u16,
+) -> Vec<&'a FirewallRule> {
+    let matching = |rule: &&FirewallRule| {
+        rule.direction == "INGRESS" && rule.applies_to(target) && rule.from_anywhere()
+    };
+    let denied_at = rules
+        .iter()
+        .filter(matching)
+        .filter(|rule| rule.denied.iter().any(|p| p.covers(protocol, port)))
+        .map(|rule| rule.priority)
+        .min();
+    rules
+        .iter()
+        .filter(matching)
+        .filter(|rule| rule.allowed.iter().any(|p| p.covers(protocol, port)))
+        .filter(|rule| denied_at.is_none_or(|priority| rule.priority < priority))
+        .collect()
+}
+
+/// A Cloud Router, which hosts the Cloud NAT gateways of a network in
+/// one region
+#[derive(Debug, Clone, PartialEq)]
+pub struct Router {
+    pub name: String,
+    /// Network the router belongs to, e.g. `default`
+    pub network: String,
+    pub nats: Vec<CloudNat>,
+}
+
+/// A Cloud NAT gateway
+#[derive(Debug, Clone, PartialEq)]
+pub struct CloudNat {
+    pub name: String,
+    /// Whether NAT logging is enabled
+    pub logging: bool,
+}
+
+/// How a disk's encryption key is managed
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiskKey {
+    /// A key Google creates and manages, the default
+    GoogleManaged,
+    /// A customer-managed Cloud KMS key, by key name without the version
+    Cmek(String),
+    /// A customer-supplied key, which Google does not store
+    Csek,
+}
+
+impl DiskKey {
+    /// Whether the customer manages the key (CMEK or CSEK)
+    pub fn customer_managed(&self) -> bool {
+        !matches!(self, DiskKey::GoogleManaged)
+    }
+}
+
+impl fmt::Display for DiskKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiskKey::GoogleManaged => write!(f, "Google-managed"),
+            DiskKey::Cmek(key) => write!(f, "CMEK ({})", key),
+            DiskKey::Csek => write!(f, "CSEK"),
+        }
+    }
+}
+
+/// A disk attached to the instance
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttachedDisk {
+    /// Disk resource name, or the device name of a local SSD
+    pub name: String,
+    pub boot: bool,
+    /// A local SSD, which is always encrypted with a Google-managed key
+    pub local: bool,
+    pub key: DiskKey,
+}
+
+/// A disk as an instance lists it, before its key is read
+struct DiskRef {
+    name: String,
+    /// API path of the disk resource; None for local SSDs
+    path: Option<String>,
+    boot: bool,
+}
+
+/// List the disks attached to Compute Engine instance `instance` in `zone`
+/// with their encryption keys
+pub fn list_instance_disks(
+    project: &str,
+    zone: &str,
+    instance: &str,
+) -> Result<(Vec<AttachedDisk>, DetectionMethod), TpuDocError> {
+    gcloud::with_fallback(
+        || {
+            let path = format!(
+                "/compute/v1/projects/{}/zones/{}/instances/{}?fields=disks(deviceName,source,boot,type)",
+                project, zone, instance
+            );
+            let disks = parse_instance_disks(&gcp::api_get(API_HOST, &path)?)?;
+            disks.into_iter().map(|disk| read_disk_key(disk, api_disk)).collect()
+        },
+        || {
+            let args = ["compute", "instances", "describe", instance, "--zone", zone, "--project", project];
+            let disks = parse_instance_disks(&gcloud::run_json(&args)?)?;
+            disks.into_iter().map(|disk| read_disk_key(disk, gcloud_disk)).collect()
+        },
+    )
+}
+
+/// List the disks of TPU VM node `node` in `zone` with their encryption
+/// keys: the boot disk, whose key the node records, then the data disks
+pub fn list_tpu_node_disks(
+    project: &str,
+    zone: &str,
+    node: &str,
+) -> Result<(Vec<AttachedDisk>, DetectionMethod), TpuDocError> {
+    let attached = |body: String, get_disk: fn(&str) -> Result<String, TpuDocError>| {
+        let (boot_key, disks) = parse_tpu_node_disks(&body)?;
+        let mut attached = vec![AttachedDisk {
+            name: node.to_string(),
+            boot: true,
+            local: false,
+            key: boot_key,
+        }];
+        for disk in disks {
+            attached.push(read_disk_key(disk, get_disk)?);
+        }
+        Ok(attached)
+    };
+    gcloud::with_fallback(
+        || {
+            let path = format!(
+                "/v2/projects/{}/locations/{}/nodes/{}?fields=bootDiskConfig,dataDisks",
+                project, zone, node
+            );
+            attached(gcp::api_get(TPU_API_HOST, &path)?, api_disk)
+        },
+        || {
+            let args = ["compute", "tpus", "tpu-vm", "describe", node, "--zone", zone, "--project", project];
+            attached(gcloud::run_json(&args)?, gcloud_disk)
+        },
+    )
+}
+
+/// The disk resource at API path `path`, from the Compute API
+fn api_disk(path: &str) -> Result<String, TpuDocError> {
+    gcp::api_get(API_HOST, &format!("{}?fields=diskEncryptionKey", path))
+}
+
+/// The disk resource at API path `path`, from `gcloud`
+fn gcloud_disk(path: &str) -> Result<String, TpuDocError> {
+    gcloud::run_json(&["compute", "disks", "describe", &format!("{}{}", RESOURCE_URI_PREFIX, path)])
+}
+
+/// `disk` with its encryption key, read from the disk resource `get_disk`
+/// returns
+fn read_disk_key(
+    disk: DiskRef,
+    get_disk: fn(&str) -> Result<String, TpuDocError>,
+) -> Result<AttachedDisk, TpuDocError> {
+    let key = match &disk.path {
+        Some(path) => parse_disk_key(&get_disk(path)?)?,
+        None => DiskKey::GoogleManaged,
+    };
+    Ok(AttachedDisk {
+        name: disk.name,
+        boot: disk.boot,
+        local: disk.path.is_none(),
+        key,
+    })
+}
+
+fn parse_instance_disks(body: &str) -> Result<Vec<DiskRef>, TpuDocError> {
+    let document = parse_document(body)?;
+    Ok(document
+        .get("disks")
+        .and_then(JsonValue::as_array)
+        .unwrap_or_default()
+        .iter()
+        .map(|disk| {
+            let source = disk.get("source").and_then(JsonValue::as_str).unwrap_or_default();
+            let scratch = disk.get("type").and_then(JsonValue::as_str) == Some("SCRATCH");
+            let device_name = disk.get("deviceName").and_then(JsonValue::as_str).unwrap_or_default();
+            DiskRef {
+                name: if scratch { device_name.to_string() } else { resource_name(source) },
+                path: (!scratch && !source.is_empty()).then(|| api_path(source)),
+                boot: disk.get("boot").and_then(JsonValue::as_bool).unwrap_or(false),
+            }
+        })
+        .collect())
+}
+
+/// The boot disk's key and the data disks of a TPU node
+fn parse_tpu_node_disks(body: &str) -> Result<(DiskKey, Vec<DiskRef>), TpuDocError> {
+    let document = parse_document(body)?;
+    let boot_key = document
+        .get("bootDiskConfig")
+        .and_then(|boot| boot.get("customerEncryptionKey"))
+        .and_then(|key| key.get("kmsKeyName"))
+        .and_then(JsonValue::as_str)
+        .map(|key| DiskKey::Cmek(key_without_version(key)))
+        .unwrap_or(DiskKey::GoogleManaged);
+    let disks = document
+        .get("dataDisks")
+        .and_then(JsonValue::as_array)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|disk| disk.get("sourceDisk").and_then(JsonValue::as_str))
+        .map(|source| DiskRef {
+            name: resource_name(source),
+            path: Some(api_path(source)),
+            boot: false,
+        })
+        .collect();
+    Ok((boot_key, disks))
+}
+
+/// The encryption key of a disk resource: a KMS key name means CMEK, a
+/// key hash without one CSEK
+fn parse_disk_key(body: &str) -> Result<DiskKey, TpuDocError> {
+    let document = parse_document(body)?;
+    let Some(key) = document.get("diskEncryptionKey") else {
+        return Ok(DiskKey::GoogleManaged);
+    };
+    if let Some(kms_key) = key.get("kmsKeyName").and_then(JsonValue::as_str) {
+        Ok(DiskKey::Cmek(key_without_version(kms_key)))
+    } else if key.get("sha256").is_some() || key.get("rsaEncryptedKey").is_some() {
+        Ok(DiskKey::Csek)
+    } else {
+        Ok(DiskKey::GoogleManaged)
+    }
+}
+
+/// A KMS key name without its `/cryptoKeyVersions/N` suffix
+fn key_without_version(key: &str) -> String {
+    key.split("/cryptoKeyVersions/").next().unwrap_or(key).to_string()
+}
+
+/// The Compute API path of a resource URL (`https://www.googleapis.com/
+/// compute/v1/projects/...`) or relative name (`projects/...`)
+fn api_path(resource: &str) -> String {
+    match resource.find("/compute/v1/") {
+        Some(at) => resource[at..].to_string(),
+        None => format!("/compute/v1/{}", resource.trim_start_matches('/')),
+    }
+}
+
+/// List the firewall rules of `project`
+pub fn list_firewall_rules(project: &str) -> Result<(Vec<FirewallRule>, DetectionMethod), TpuDocError> {
+    let (items, method) = gcloud::with_fallback(
+        || list_items(&format!("/compute/v1/projects/{}/global/firewalls", project)),
+        || parse_gcloud_list(&gcloud::run_json(&["compute", "firewall-rules", "list", "--project", project])?),
+    )?;
+    Ok((items.iter().map(parse_firewall_rule).collect(), method))
+}
+
+/// List the Cloud Routers of `project` in `region`
+pub fn list_routers(project: &str, region: &str) -> Result<(Vec<Router>, DetectionMethod), TpuDocError> {
+    let (items, method) = gcloud::with_fallback(
+        || list_items(&format!("/compute/v1/projects/{}/regions/{}/routers", project, region)),
+        || {
+            let args = ["compute", "routers", "list", "--regions", region, "--project", project];
+            parse_gcloud_list(&gcloud::run_json(&args)?)
+        },
+    )?;
+    Ok((items.iter().map(parse_router).collect(), method))
+}
+
+/// The items of every page of the list at `path`
+fn list_items(path: &str) -> Result<Vec<JsonValue>, TpuDocError> {
+    let mut items = Vec::new();
+    let mut page_token: Option<String> = None;
+    loop {
+        let page_path = match &page_token {
+            Some(token) => format!("{}?pageToken={}", path, percent_encode(token)),
+            None => path.to_string(),
+        };
+        let (page, next) = parse_page(&gcp::api_get(API_HOST, &page_path)?)?;
+        items.extend(page);
+        match next {
+            Some(token) => page_token = Some(token),
+            None => return Ok(items),
+        }
+    }
+}
+
+/// Items and the next page token from a list response
+fn parse_page(body: &str) -> Result<(Vec<JsonValue>, Option<String>), TpuDocError> {
+    let document = parse_document(body)?;
+    let items = document.get("items").and_then(JsonValue::as_array).unwrap_or_default().to_vec();
+    let next = document.get("nextPageToken").and_then(JsonValue::as_str).map(str::to_string);
+    Ok((items, next))
+}
+
+/// Items of a `gcloud ... list --format json` array, which holds the same
+/// resources as the API's list pages
+fn parse_gcloud_list(body: &str) -> Result<Vec<JsonValue>, TpuDocError> {
+    let document = parse_document(body)?;
+    document
+        .as_array()
+        .map(|items| items.to_vec())
+        .ok_or_else(|| TpuDocError::ParseError {
+            context: "gcloud output".to_string(),
+            message: "expected a JSON array".to_string(),
+            source: None,
+        })
+}
+
+fn parse_document(body: &str) -> Result<JsonValue, TpuDocError> {
+    json::parse(body).map_err(|message| TpuDocError::ParseError {
+        context: "Compute API response".to_string(),
+        message,
+        source: None,
+    })
+}
+
+fn parse_firewall_rule(item: &JsonValue) -> FirewallRule {
+    let text = |key: &str| item.get(key).and_then(JsonValue::as_str).unwrap_or_default().to_string();
+    let permissions = |key: &str| {
+        item.get(key)
+            .and_then(JsonValue::as_array)
+            .unwrap_or_default()
+            .iter()
+            .map(|p| FirewallPermission {
+                protocol: p.get("IPProtocol").and_then(JsonValue::as_str).unwrap_or("all").to_lowercase(),
+                ports: strings(p.get("ports")),
+            })
+            .collect()
+    };
+    FirewallRule {
+        name: text("name"),
+        network: resource_name(&text("network")),
+        direction: item.get("direction").and_then(JsonValue::as_str).unwrap_or("INGRESS").to_string(),
+        priority: item.get("priority").and_then(JsonValue::as_f64).unwrap_or(1000.0) as u32,
+        source_ranges: strings(item.get("sourceRanges")),
+        source_tags: strings(item.get("sourceTags")),
+        source_service_accounts: strings(item.get("sourceServiceAccounts")),
+        destination_ranges: strings(item.get("destinationRanges")),
+        target_tags: strings(item.get("targetTags")),
+        target_service_accounts: strings(item.get("targetServiceAccounts")),
+        allowed: permissions("allowed"),
+        denied: permissions("denied"),
+        disabled: item.get("disabled").and_then(JsonValue::as_bool).unwrap_or(false),
+    }
+}
+
+fn parse_router(item: &JsonValue) -> Router {
+    let text = |value: Option<&JsonValue>| value.and_then(JsonValue::as_str).unwrap_or_default().to_string();
+    Router {
+        name: text(item.get("name")),
+        network: resource_name(&text(item.get("network"))),
+        nats: item
+            .get("nats")
+            .and_then(JsonValue::as_array)
+            .unwrap_or_default()
+            .iter()
+            .map(|nat| CloudNat {
+                name: text(nat.get("name")),
+                logging: nat
+                    .get("logConfig")
+                    .and_then(|log| log.get("enable"))
+                    .and_then(JsonValue::as_bool)
+                    .unwrap_or(false),
+            })
+            .collect(),
+    }
+}
+
+/// Last segment of a resource URL, e.g. `default` for
+/// `.../projects/PROJECT/global/networks/default`
+fn resource_name(url: &str) -> String {
+    url.rsplit('/').next().unwrap_or_default().to_string()
+}
+
+/// The strings of a JSON array, or none when `value` is missing
+fn strings(value: Option<&JsonValue>) -> Vec<String> {
+    value
+        .and_then(JsonValue::as_array)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|v| v.as_str().map(str::to_string))
+        .collect()
+}
+
+/// Percent-encode a query parameter value
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIREWALLS: &str = r#"{
+      "items": [
+        {"name": "default-allow-ssh",
+         "network": "https://www.googleapis.com/compute/v1/projects/p/global/networks/default",
+         "direction": "INGRESS", "priority": 65534, "sourceRanges": ["0.0.0.0/0"],
+         "allowed": [{"IPProtocol": "tcp", "ports": ["22"]}]},
+        {"name": "dev-ssh", "network": "https://www.googleapis.com/compute/v1/projects/p/global/networks/default",
+         "direction": "INGRESS", "priority": 1000, "sourceRanges": ["0.0.0.0/0"], "targetTags": ["dev"],
+         "allowed": [{"IPProtocol": "tcp", "ports": ["20-30", "8888"]}]},
+        {"name": "internal", "network": "https://www.googleapis.com/compute/v1/projects/p/global/networks/default",
+         "direction": "INGRESS", "priority": 1000, "sourceRanges": ["10.128.0.0/9"],
+         "allowed": [{"IPProtocol": "all"}]},
+        {"name": "other-net", "network": "https://www.googleapis.com/compute/v1/projects/p/global/networks/prod",
+         "direction": "INGRESS", "priority": 1000, "sourceRanges": ["0.0.0.0/0"],
+         "allowed": [{"IPProtocol": "tcp"}]}
+      ],
+      "nextPageToken": "abc+/="
+    }"#;
+
+    fn target(tags: &[&str]) -> FirewallTarget {
+        FirewallTarget {
+            networks: vec!["default".to_string()],
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            service_account: None,
+        }
+    }
+
+    fn firewall_rules(body: &str) -> Vec<FirewallRule> {
+        parse_page(body).unwrap().0.iter().map(parse_firewall_rule).collect()
+    }
+
+    #[test]
+    fn test_parse_firewall_rules() {
+        let rules = firewall_rules(FIREWALLS);
+        assert_eq!(parse_page(FIREWALLS).unwrap().1.as_deref(), Some("abc+/="));
+        assert_eq!(percent_encode("abc+/="), "abc%2B%2F%3D");
+        assert_eq!(rules.len(), 4);
+        assert_eq!(rules[1].network, "default");
+        assert_eq!(rules[1].priority, 1000);
+        assert_eq!(rules[1].describe_targets(), "tags dev");
+        assert!(rules[1].allowed[0].covers("tcp", 25));
+        assert!(!rules[1].allowed[0].covers("udp", 25));
+        assert!(!rules[1].allowed[0].covers("tcp", 31));
+        assert!(rules[2].allowed[0].covers("udp", 53));
+        assert!(!rules[2].from_anywhere());
+
+        let (items, next) = parse_page("{}").unwrap();
+        assert!(items.is_empty() && next.is_none());
+        assert!(parse_page("not json").is_err());
+
+        // gcloud lists the same resources as a bare array
+        let array = &FIREWALLS[FIREWALLS.find('[').unwrap()..=FIREWALLS.rfind(']').unwrap()];
+        let items = parse_gcloud_list(array).unwrap();
+        assert_eq!(items.iter().map(parse_firewall_rule).collect::<Vec<_>>(), rules);
+        assert!(parse_gcloud_list(FIREWALLS).is_err());
+    }
+
+    #[test]
+    fn test_parse_routers() {
+        let body = r#"{"items": [
+          {"name": "nat-router", "network": "https://www.googleapis.com/compute/v1/projects/p/global/networks/default",
+           "nats": [{"name": "nat-1", "logConfig": {"enable": true, "filter": "ALL"}}, {"name": "nat-2"}]},
+          {"name": "bgp-router", "network": "https://www.googleapis.com/compute/v1/projects/p/global/networks/prod"}
+        ]}"#;
+        let routers: Vec<Router> = parse_page(body).unwrap().0.iter().map(parse_router).collect();
+        assert_eq!(routers[0].network, "default");
+        assert_eq!(
+            routers[0].nats,
+            vec![
+                CloudNat {
+                    name: "nat-1".to_string(),
+                    logging: true
+                },
+                CloudNat {
+                    name: "nat-2".to_string(),
+                    logging: false
+                },
+            ]
+        );
+        assert!(routers[1].nats.is_empty());
+    }
+
+    #[test]
+    fn test_parse_disks() {
+        let body = r#"{"disks": [
+          {"deviceName": "persistent-disk-0", "boot": true, "type": "PERSISTENT",
+           "source": "https://www.googleapis.com/compute/v1/projects/p/zones/us-central2-b/disks/vm-1"},
+          {"deviceName": "local-ssd-0", "type": "SCRATCH"}
+        ]}"#;
+        let disks = parse_instance_disks(body).unwrap();
+        assert_eq!(disks[0].name, "vm-1");
+        assert_eq!(disks[0].path.as_deref(), Some("/compute/v1/projects/p/zones/us-central2-b/disks/vm-1"));
+        assert!(disks[0].boot);
+        assert_eq!((disks[1].name.as_str(), disks[1].path.as_deref()), ("local-ssd-0", None));
+
+        let body = r#"{"bootDiskConfig": {"customerEncryptionKey": {"kmsKeyName":
+                         "projects/p/locations/us/keyRings/r/cryptoKeys/k"}},
+                       "dataDisks": [{"sourceDisk": "projects/p/zones/us-central2-b/disks/data-1",
+                                      "mode": "READ_WRITE"}]}"#;
+        let (boot_key, disks) = parse_tpu_node_disks(body).unwrap();
+        assert_eq!(boot_key, DiskKey::Cmek("projects/p/locations/us/keyRings/r/cryptoKeys/k".to_string()));
+        assert_eq!(disks[0].path.as_deref(), Some("/compute/v1/projects/p/zones/us-central2-b/disks/data-1"));
+        assert_eq!(parse_tpu_node_disks("{}").unwrap().0, DiskKey::GoogleManaged);
+
+        let cmek = r#"{"diskEncryptionKey": {"kmsKeyName":
+                       "projects/p/locations/us/keyRings/r/cryptoKeys/k/cryptoKeyVersions/3"}}"#;
+        assert_eq!(parse_disk_key(cmek).unwrap().to_string(), "CMEK (projects/p/locations/us/keyRings/r/cryptoKeys/k)");
+        assert_eq!(parse_disk_key(r#"{"diskEncryptionKey": {"sha256": "abc="}}"#).unwrap(), DiskKey::Csek);
+        assert_eq!(parse_disk_key("{}").unwrap(), DiskKey::GoogleManaged);
+        assert!(!DiskKey::GoogleManaged.customer_managed());
+    }
+
+    #[test]
+    fn test_open_to_anywhere() {
+        let mut rules = firewall_rules(FIREWALLS);
+        let names = |rules: Vec<&FirewallRule>| rules.iter().map(|r| r.name.clone()).collect::<Vec<_>>();
+
+        // Rules on other networks or for other tags do not apply
+        assert_eq!(names(open_to_anywhere(&rules, &target(&[]), "tcp", 22)), ["default-allow-ssh"]);
+        assert_eq!(names(open_to_anywhere(&rules, &target(&["dev"]), "tcp", 22)), ["default-allow-ssh", "dev-ssh"]);
+        assert!(open_to_anywhere(&rules, &target(&[]), "tcp", 443).is_empty());
+
+        // A deny rule of higher precedence overrides the allow rules below it
+        rules.push(FirewallRule {
+            name: "deny-ssh".to_string(),
+            priority: 1000,
+            target_tags: Vec::new(),
+            allowed: Vec::new(),
+            denied: vec![FirewallPermission {
+                protocol: "tcp".to_string(),
+                ports: vec!["22".to_string()],
+            }],
+            ..rules[0].clone()
+        });
+        assert!(open_to_anywhere(&rules, &target(&["dev"]), "tcp", 22).is_empty());
+        rules.last_mut().unwrap().disabled = true;
+        assert_eq!(open_to_anywhere(&rules, &target(&[]), "tcp", 22).len(), 1);
+    }
+}
@@ -0,0 +1,121 @@
+//! Non-fatal issues tpu-doc runs into itself.
+//!
+//! Platform functions often work around a missing or malformed data source
+//! (a sysfs file that does not parse, partial metadata, an environment
+//! override that is not a number) by falling back to another source or a
+//! default. Checks then pass, warn, or skip on the fallback without saying
+//! why. [`record`] notes such an issue; while a check runs, the orchestrator
+//! collects the issues recorded on its thread into the report's
+//! `diagnostics`, which the verbose text report lists with the check that
+//! ran into them.
+//!
+//! Issues recorded outside a check (or on threads a check spawns) are not
+//! reported. The same issue recorded twice by one check is reported once.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// A non-fatal issue a check ran into
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// ID of the check that was running
+    pub check_id: String,
+    /// What ran into the issue, e.g. `thermal` or `metadata`
+    pub source: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.check_id, self.source, self.message)
+    }
+}
+
+/// The issues recorded during one run, shared by the threads running its
+/// checks
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticLog {
+    entries: Arc<Mutex<Vec<Diagnostic>>>,
+}
+
+impl DiagnosticLog {
+    /// Run `f` as check `check_id`, adding the issues it records to the log
+    pub fn scope<T>(&self, check_id: &str, f: impl FnOnce() -> T) -> T {
+        let previous = CURRENT.with(|current| current.replace(Some((check_id.to_string(), self.clone()))));
+        // Restore the previous scope even if `f` panics
+        let _restore = Restore(previous);
+        f()
+    }
+
+    /// The issues recorded so far, in the order they were recorded
+    pub fn entries(&self) -> Vec<Diagnostic> {
+        self.entries.lock().unwrap_or_else(|p| p.into_inner()).clone()
+    }
+
+    fn push(&self, diagnostic: Diagnostic) {
+        let mut entries = self.entries.lock().unwrap_or_else(|p| p.into_inner());
+        if !entries.contains(&diagnostic) {
+            entries.push(diagnostic);
+        }
+    }
+}
+
+thread_local! {
+    /// The check running on this thread and the log of its run
+    static CURRENT: RefCell<Option<(String, DiagnosticLog)>> = const { RefCell::new(None) };
+}
+
+struct Restore(Option<(String, DiagnosticLog)>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Record a non-fatal issue `source` ran into, for the check running on
+/// this thread
+pub fn record(source: &str, message: impl Into<String>) {
+    let Some((check_id, log)) = CURRENT.with(|current| current.borrow().clone()) else {
+        return;
+    };
+    log.push(Diagnostic {
+        check_id,
+        source: source.to_string(),
+        message: message.into(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_in_scope() {
+        record("sysfs", "dropped outside a check");
+        let log = DiagnosticLog::default();
+        let value = log.scope("HW-003", || {
+            record("thermal", "temp is not a number");
+            record("thermal", "temp is not a number");
+            log.scope("HW-001", || record("metadata", "accelerator-type unavailable"));
+            record("thermal", "no TPU thermal zones");
+            42
+        });
+        assert_eq!(value, 42);
+        let entries: Vec<String> = log.entries().iter().map(Diagnostic::to_string).collect();
+        assert_eq!(
+            entries,
+            [
+                "HW-003: thermal: temp is not a number",
+                "HW-001: metadata: accelerator-type unavailable",
+                "HW-003: thermal: no TPU thermal zones",
+            ]
+        );
+
+        // The scope ends with the check
+        record("sysfs", "dropped after the check");
+        assert_eq!(log.entries().len(), 3);
+    }
+}
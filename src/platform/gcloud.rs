@@ -0,0 +1,126 @@
+//! `gcloud` CLI fallback for GCP API probes.
+//!
+//! The Compute Engine and Cloud TPU API requests in [`crate::platform::compute`]
+//! authenticate as the VM's service account, so they fail when its access
+//! scopes or roles do not cover the API, or when this build lacks TLS
+//! support. When the fallback is enabled (`--gcloud-fallback` or
+//! `gcp.gcloud_fallback`), such a request is retried by shelling out to an
+//! installed `gcloud` with `--format json`, which uses whatever account
+//! `gcloud` is logged in as. It is off by default, since it runs an external
+//! program with the user's credentials.
+
+use crate::checks::settings;
+use crate::platform::{diagnostics, linux};
+use crate::TpuDocError;
+use std::fmt;
+use std::process::Command;
+
+/// How long one `gcloud` invocation may take
+pub const GCLOUD_TIMEOUT_MS: u64 = 30000;
+
+/// Whether API requests fall back to `gcloud` in the running check's run
+pub fn fallback_enabled() -> bool {
+    settings::current().gcloud_fallback
+}
+
+/// Where a probe read its GCP resources from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectionMethod {
+    /// The REST API, as the VM's service account
+    Api,
+    /// `gcloud`, as the account it is logged in as
+    Gcloud,
+}
+
+impl fmt::Display for DetectionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DetectionMethod::Api => write!(f, "API"),
+            DetectionMethod::Gcloud => write!(f, "gcloud CLI"),
+        }
+    }
+}
+
+/// Run `gcloud ARGS --format json --quiet` and return its output
+pub fn run_json(args: &[&str]) -> Result<String, TpuDocError> {
+    let mut command = Command::new("gcloud");
+    command.args(args).args(["--format", "json", "--quiet"]);
+    let output = linux::run_command_with_timeout(&mut command, GCLOUD_TIMEOUT_MS)?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+    // gcloud reports the failure on its last line, e.g. "ERROR: (gcloud.compute...) ..."
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(TpuDocError::CommandError {
+        command: format!("gcloud {}", args.join(" ")),
+        message: stderr
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("exited with an error")
+            .trim()
+            .to_string(),
+        source: None,
+    })
+}
+
+/// `api()`, or when it fails and the fallback is enabled, `gcloud()`. A
+/// successful fallback records the API failure as a diagnostic; the error of
+/// a failed one names both failures.
+pub fn with_fallback<T>(
+    api: impl FnOnce() -> Result<T, TpuDocError>,
+    gcloud: impl FnOnce() -> Result<T, TpuDocError>,
+) -> Result<(T, DetectionMethod), TpuDocError> {
+    with_fallback_if(fallback_enabled(), api, gcloud)
+}
+
+fn with_fallback_if<T>(
+    enabled: bool,
+    api: impl FnOnce() -> Result<T, TpuDocError>,
+    gcloud: impl FnOnce() -> Result<T, TpuDocError>,
+) -> Result<(T, DetectionMethod), TpuDocError> {
+    let api_error = match api() {
+        Ok(value) => return Ok((value, DetectionMethod::Api)),
+        Err(e) if !enabled => return Err(e),
+        Err(e) => e,
+    };
+    match gcloud() {
+        Ok(value) => {
+            diagnostics::record("gcloud", format!("API request failed ({}); read with gcloud instead", api_error));
+            Ok((value, DetectionMethod::Gcloud))
+        }
+        Err(e) => Err(TpuDocError::CommandError {
+            command: "gcloud".to_string(),
+            message: format!("API request failed ({}), and so did the gcloud fallback ({})", api_error, e),
+            source: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_fallback() {
+        let denied = || -> Result<u32, TpuDocError> {
+            Err(TpuDocError::PermissionDenied {
+                resource: "compute.firewalls.list".to_string(),
+                source: None,
+            })
+        };
+
+        assert_eq!(with_fallback_if(true, || Ok(1), || Ok(2)).unwrap(), (1, DetectionMethod::Api));
+        assert_eq!(with_fallback_if(true, denied, || Ok(2)).unwrap(), (2, DetectionMethod::Gcloud));
+        assert!(matches!(
+            with_fallback_if(false, denied, || Ok(2)),
+            Err(TpuDocError::PermissionDenied { .. })
+        ));
+
+        // Fails whether or not gcloud is installed
+        let err = with_fallback_if(true, denied, || run_json(&["--definitely-not-a-flag"]).map(|_| 0)).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("Permission denied: compute.firewalls.list"), "{}", message);
+        assert!(message.contains("gcloud fallback"), "{}", message);
+    }
+}
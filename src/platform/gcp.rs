@@ -1,6 +1,8 @@
 //! GCP metadata server interface.
 //!
-//! Provides access to GCP instance metadata via the metadata server.
+//! Provides access to GCP instance metadata via the metadata server, and
+//! authenticated requests to Google Cloud REST APIs with the instance's
+//! service account token ([`api_get`]).
 //!
 //! # Graceful Degradation
 //!
@@ -9,14 +11,19 @@
 //! - Connection timeout: Returns TpuDocError::IoError after timeout
 //! - HTTP errors: Returns TpuDocError::IoError with status code
 //! - Missing attributes: get_instance_attribute() returns Ok(None) for 404
+//! - No external IP: get_external_access() returns an empty list
 //! - Parse errors: Returns TpuDocError::ParseError with context
 //!
 //! Default timeout is 5 seconds for all metadata operations.
 //! No function in this module will panic.
 
+use crate::ai::client::{HttpClient, HttpConfig, HttpResponse};
+use crate::ai::AiError;
+use crate::checks::timeouts;
 use crate::TpuDocError;
 use std::io::{BufRead, BufReader, Read, Write};
 use std::net::TcpStream;
+use std::sync::Arc;
 use std::time::Duration;
 
 const METADATA_HOST: &str = "metadata.google.internal";
@@ -51,6 +58,7 @@ pub fn get_zone() -> Result<String, TpuDocError> {
         .ok_or_else(|| TpuDocError::ParseError {
             context: "get_zone".to_string(),
             message: "Could not parse zone from metadata".to_string(),
+            source: None,
         })
 }
 
@@ -59,6 +67,18 @@ pub fn get_instance_name() -> Result<String, TpuDocError> {
     metadata_get("/computeMetadata/v1/instance/name")
 }
 
+/// Get the name of the TPU VM node this host belongs to, from the `tpu-env`
+/// attribute (`NODE_ID: 'my-tpu'`); None on other instances
+pub fn get_tpu_node_name() -> Result<Option<String>, TpuDocError> {
+    let Some(env) = get_instance_attribute("tpu-env")? else {
+        return Ok(None);
+    };
+    Ok(env.lines().find_map(|line| {
+        let value = line.trim().strip_prefix("NODE_ID:")?.trim().trim_matches('\'').trim_matches('"');
+        (!value.is_empty()).then(|| value.to_string())
+    }))
+}
+
 /// Get the machine type
 pub fn get_machine_type() -> Result<String, TpuDocError> {
     let machine_path = metadata_get("/computeMetadata/v1/instance/machine-type")?;
@@ -70,6 +90,7 @@ pub fn get_machine_type() -> Result<String, TpuDocError> {
         .ok_or_else(|| TpuDocError::ParseError {
             context: "get_machine_type".to_string(),
             message: "Could not parse machine type from metadata".to_string(),
+            source: None,
         })
 }
 
@@ -84,12 +105,161 @@ pub fn get_access_scopes() -> Result<Vec<String>, TpuDocError> {
     Ok(scopes.lines().map(|s| s.to_string()).collect())
 }
 
+/// Get an OAuth2 access token for the default service account.
+///
+/// The token is short-lived (about an hour) and carries the instance's
+/// access scopes; callers should fetch a fresh one per operation.
+pub fn get_access_token() -> Result<String, TpuDocError> {
+    let body = metadata_get("/computeMetadata/v1/instance/service-accounts/default/token")?;
+    extract_access_token(&body).ok_or_else(|| TpuDocError::ParseError {
+        context: "get_access_token".to_string(),
+        message: "No access_token in metadata server response".to_string(),
+        source: None,
+    })
+}
+
+/// GET `path` from the Google Cloud REST API at `host` (e.g.
+/// `compute.googleapis.com`) with the default service account's token and
+/// return the response body.
+///
+/// Needs the TLS support of the `ai` feature; without it this returns an
+/// error saying so. HTTP 401 and 403 become `PermissionDenied`.
+pub fn api_get(host: &str, path: &str) -> Result<String, TpuDocError> {
+    let authorization = format!("Bearer {}", get_access_token()?);
+    let headers = [("Authorization", authorization.as_str()), ("Accept", "application/json")];
+    let context = || format!("request to {}", host);
+    let response = https_get(host, path, &headers)?;
+    match response.status {
+        200 => Ok(response.body),
+        401 | 403 => Err(TpuDocError::PermissionDenied {
+            resource: format!("{}{} (check IAM roles and access scopes)", host, path),
+            source: None,
+        }),
+        status => Err(TpuDocError::IoError {
+            context: context(),
+            message: format!("HTTP {} for {}", status, path),
+            source: None,
+        }),
+    }
+}
+
+/// GET `path` from `host` over HTTPS with `headers`, bounded by the network
+/// timeout, and return the response whatever its status.
+///
+/// Needs the TLS support of the `ai` feature; without it this returns an
+/// error saying so.
+pub fn https_get(host: &str, path: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, TpuDocError> {
+    let client = HttpClient::with_config(HttpConfig {
+        connect_timeout_ms: timeouts::net_timeout_ms(),
+        read_timeout_ms: timeouts::net_timeout_ms() * 2,
+        max_retries: 1,
+        keep_alive: false,
+        ..HttpConfig::default()
+    });
+    client.get_https(host, path, headers).map_err(|e| TpuDocError::IoError {
+        context: format!("request to {}", host),
+        message: match &e {
+            AiError::FeatureNotEnabled => "HTTPS support not built in; rebuild with --features ai".to_string(),
+            e => e.to_string(),
+        },
+        source: Some(Arc::new(e)),
+    })
+}
+
 /// Get an instance attribute
 pub fn get_instance_attribute(attr: &str) -> Result<Option<String>, TpuDocError> {
-    match metadata_get(&format!("/computeMetadata/v1/instance/attributes/{}", attr)) {
-        Ok(value) => Ok(Some(value)),
-        Err(TpuDocError::IoError { message, .. }) if message.contains("404") => Ok(None),
-        Err(e) => Err(e),
+    metadata_get_optional(&format!("/computeMetadata/v1/instance/attributes/{}", attr))
+}
+
+/// Get the instance's network tags, which firewall rules target
+pub fn get_network_tags() -> Result<Vec<String>, TpuDocError> {
+    // The tags endpoint returns a JSON array of strings
+    let tags = metadata_get("/computeMetadata/v1/instance/tags")?;
+    let parsed = crate::util::json::parse(&tags).map_err(|message| TpuDocError::ParseError {
+        context: "get_network_tags".to_string(),
+        message,
+        source: None,
+    })?;
+    Ok(parsed
+        .as_array()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|tag| tag.as_str().map(|s| s.to_string()))
+        .collect())
+}
+
+/// Get the VPC networks of the instance's network interfaces, e.g. `default`
+pub fn get_networks() -> Result<Vec<String>, TpuDocError> {
+    let interfaces = metadata_get("/computeMetadata/v1/instance/network-interfaces/")?;
+    let mut networks = Vec::new();
+    for index in listing_entries(&interfaces) {
+        let network = interface_network(&format!("/computeMetadata/v1/instance/network-interfaces/{}", index));
+        if !network.is_empty() && !networks.contains(&network) {
+            networks.push(network);
+        }
+    }
+    Ok(networks)
+}
+
+/// An external IP attached to one of the instance's network interfaces
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalAccess {
+    /// Interface name as gcloud knows it, e.g. `nic0`
+    pub interface: String,
+    /// VPC network the interface is attached to, e.g. `default`
+    pub network: String,
+    pub external_ip: String,
+}
+
+/// Get the external IPs from the access configs of the instance's network
+/// interfaces; empty when the instance only has internal IPs
+pub fn get_external_access() -> Result<Vec<ExternalAccess>, TpuDocError> {
+    let interfaces = metadata_get("/computeMetadata/v1/instance/network-interfaces/")?;
+    let mut access = Vec::new();
+    for index in listing_entries(&interfaces) {
+        let base = format!("/computeMetadata/v1/instance/network-interfaces/{}", index);
+        let Some(configs) = metadata_get_optional(&format!("{}/access-configs/", base))? else {
+            continue;
+        };
+        let network = interface_network(&base);
+        for config in listing_entries(&configs) {
+            let path = format!("{}/access-configs/{}/external-ip", base, config);
+            if let Some(external_ip) = metadata_get_optional(&path)?.filter(|ip| !ip.is_empty()) {
+                access.push(ExternalAccess {
+                    interface: format!("nic{}", index),
+                    network: network.clone(),
+                    external_ip,
+                });
+            }
+        }
+    }
+    Ok(access)
+}
+
+/// Short name of the network of the interface at metadata path `base`,
+/// empty if unknown
+fn interface_network(base: &str) -> String {
+    // Network path: projects/PROJECT_NUM/networks/NETWORK
+    let network_path = metadata_get(&format!("{}/network", base)).unwrap_or_default();
+    network_path.rsplit('/').next().unwrap_or_default().to_string()
+}
+
+/// Entries of a metadata directory listing (`0/`, `1/`, ...)
+fn listing_entries(listing: &str) -> impl Iterator<Item = &str> {
+    listing.lines().map(|line| line.trim().trim_end_matches('/')).filter(|entry| !entry.is_empty())
+}
+
+/// Pull `access_token` out of the token endpoint's JSON response
+fn extract_access_token(body: &str) -> Option<String> {
+    let key = "\"access_token\"";
+    let after_key = &body[body.find(key)? + key.len()..];
+    let after_colon = after_key.trim_start().strip_prefix(':')?.trim_start();
+    let value = after_colon.strip_prefix('"')?;
+    let token = &value[..value.find('"')?];
+    if token.is_empty() {
+        None
+    } else {
+        Some(token.to_string())
     }
 }
 
@@ -98,6 +268,16 @@ fn metadata_get(path: &str) -> Result<String, TpuDocError> {
     metadata_get_with_timeout(path, DEFAULT_TIMEOUT_MS)
 }
 
+/// Make a GET request to the metadata server, with `None` for a path that
+/// does not exist
+fn metadata_get_optional(path: &str) -> Result<Option<String>, TpuDocError> {
+    match metadata_get(path) {
+        Ok(value) => Ok(Some(value)),
+        Err(TpuDocError::IoError { message, .. }) if message.contains("404") => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
 /// Make a GET request to the metadata server with custom timeout
 fn metadata_get_with_timeout(path: &str, timeout_ms: u64) -> Result<String, TpuDocError> {
     // Connect to metadata server
@@ -106,12 +286,14 @@ fn metadata_get_with_timeout(path: &str, timeout_ms: u64) -> Result<String, TpuD
         &addr.parse().map_err(|_| TpuDocError::IoError {
             context: "metadata_get".to_string(),
             message: "Invalid address".to_string(),
+            source: None,
         })?,
         Duration::from_millis(timeout_ms),
     )
     .map_err(|e| TpuDocError::IoError {
         context: "metadata_get".to_string(),
         message: format!("Connection failed: {}", e),
+        source: Some(Arc::new(e)),
     })?;
 
     stream
@@ -136,6 +318,7 @@ fn metadata_get_with_timeout(path: &str, timeout_ms: u64) -> Result<String, TpuD
         .map_err(|e| TpuDocError::IoError {
             context: "metadata_get".to_string(),
             message: format!("Write failed: {}", e),
+            source: Some(Arc::new(e)),
         })?;
 
     // Read response
@@ -149,6 +332,7 @@ fn metadata_get_with_timeout(path: &str, timeout_ms: u64) -> Result<String, TpuD
         .map_err(|e| TpuDocError::IoError {
             context: "metadata_get".to_string(),
             message: format!("Read failed: {}", e),
+            source: Some(Arc::new(e)),
         })?;
 
     // Parse status code
@@ -162,6 +346,7 @@ fn metadata_get_with_timeout(path: &str, timeout_ms: u64) -> Result<String, TpuD
         return Err(TpuDocError::IoError {
             context: "metadata_get".to_string(),
             message: format!("HTTP {} for {}", status_code, path),
+            source: None,
         });
     }
 
@@ -171,6 +356,7 @@ fn metadata_get_with_timeout(path: &str, timeout_ms: u64) -> Result<String, TpuD
         reader.read_line(&mut line).map_err(|e| TpuDocError::IoError {
             context: "metadata_get".to_string(),
             message: format!("Read failed: {}", e),
+            source: Some(Arc::new(e)),
         })?;
         if line.trim().is_empty() {
             break;
@@ -183,6 +369,7 @@ fn metadata_get_with_timeout(path: &str, timeout_ms: u64) -> Result<String, TpuD
         .map_err(|e| TpuDocError::IoError {
             context: "metadata_get".to_string(),
             message: format!("Read body failed: {}", e),
+            source: Some(Arc::new(e)),
         })?;
 
     Ok(response.trim().to_string())
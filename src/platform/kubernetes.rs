@@ -0,0 +1,93 @@
+//! Kubernetes pod environment.
+//!
+//! Tells whether tpu-doc runs in a Kubernetes pod (GKE) and which
+//! Kubernetes service account the pod runs as. The service account is read
+//! from the `sub` claim of the token Kubernetes mounts into the pod; the
+//! token's signature is not verified, since the API server, not tpu-doc,
+//! relies on it.
+
+use crate::util::json::{self, JsonValue};
+use std::fmt;
+use std::fs;
+
+/// Where Kubernetes mounts the pod's service account token
+const TOKEN_PATH: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+
+/// A Kubernetes service account
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceAccount {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl fmt::Display for ServiceAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.namespace, self.name)
+    }
+}
+
+/// Whether this process runs in a Kubernetes pod
+pub fn in_pod() -> bool {
+    std::env::var_os("KUBERNETES_SERVICE_HOST").is_some()
+}
+
+/// The service account the pod runs as, or None outside a pod or when the
+/// token is not mounted (`automountServiceAccountToken: false`)
+pub fn pod_service_account() -> Option<ServiceAccount> {
+    token_service_account(fs::read_to_string(TOKEN_PATH).ok()?.trim())
+}
+
+/// The service account in the `sub` claim (`system:serviceaccount:NS:NAME`)
+/// of service account token `token`
+fn token_service_account(token: &str) -> Option<ServiceAccount> {
+    let payload = base64url_decode(token.split('.').nth(1)?)?;
+    let claims = json::parse(std::str::from_utf8(&payload).ok()?).ok()?;
+    let subject = claims.get("sub").and_then(JsonValue::as_str)?;
+    let (namespace, name) = subject.strip_prefix("system:serviceaccount:")?.split_once(':')?;
+    Some(ServiceAccount {
+        namespace: namespace.to_string(),
+        name: name.to_string(),
+    })
+}
+
+/// Decode unpadded base64url, as JWT segments are encoded
+fn base64url_decode(input: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in input.trim_end_matches('=').bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'-' => 62,
+            b'_' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_service_account() {
+        assert_eq!(base64url_decode("aGk").unwrap(), b"hi");
+        assert_eq!(base64url_decode("-_8").unwrap(), [0xfb, 0xff]);
+        assert!(base64url_decode("a+b").is_none());
+
+        // {"alg":"RS256"} . {"sub":"system:serviceaccount:ml:trainer"} . signature
+        let token = "eyJhbGciOiJSUzI1NiJ9.eyJzdWIiOiJzeXN0ZW06c2VydmljZWFjY291bnQ6bWw6dHJhaW5lciJ9.c2ln";
+        let account = token_service_account(token).unwrap();
+        assert_eq!(account.to_string(), "ml/trainer");
+        assert_eq!(token_service_account("not-a-token"), None);
+    }
+}
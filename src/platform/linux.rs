@@ -15,8 +15,15 @@
 //! via Result types for the caller to handle.
 
 use crate::TpuDocError;
+use std::cell::RefCell;
 use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::os::unix::process::CommandExt;
 use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
 
 /// Memory information from /proc/meminfo
 #[derive(Debug, Clone)]
@@ -63,15 +70,13 @@ pub fn get_hostname() -> Result<String, TpuDocError> {
     Err(TpuDocError::IoError {
         context: "get_hostname".to_string(),
         message: "Could not read hostname from /etc/hostname or /proc".to_string(),
+        source: None,
     })
 }
 
 /// Get kernel version from /proc/version
 pub fn get_kernel_version() -> Result<String, TpuDocError> {
-    let content = fs::read_to_string("/proc/version").map_err(|e| TpuDocError::IoError {
-        context: "get_kernel_version".to_string(),
-        message: e.to_string(),
-    })?;
+    let content = fs::read_to_string("/proc/version").map_err(|e| TpuDocError::io("get_kernel_version", e))?;
 
     // Parse "Linux version X.Y.Z ..."
     if let Some(version) = content.split_whitespace().nth(2) {
@@ -80,16 +85,14 @@ pub fn get_kernel_version() -> Result<String, TpuDocError> {
         Err(TpuDocError::ParseError {
             context: "get_kernel_version".to_string(),
             message: "Could not parse kernel version".to_string(),
+            source: None,
         })
     }
 }
 
 /// Get memory information from /proc/meminfo
 pub fn get_memory_info() -> Result<MemoryInfo, TpuDocError> {
-    let content = fs::read_to_string("/proc/meminfo").map_err(|e| TpuDocError::IoError {
-        context: "get_memory_info".to_string(),
-        message: e.to_string(),
-    })?;
+    let content = fs::read_to_string("/proc/meminfo").map_err(|e| TpuDocError::io("get_memory_info", e))?;
 
     let mut total = 0u64;
     let mut available = 0u64;
@@ -120,10 +123,7 @@ pub fn get_memory_info() -> Result<MemoryInfo, TpuDocError> {
 
 /// Get CPU information from /proc/cpuinfo
 pub fn get_cpu_info() -> Result<CpuInfo, TpuDocError> {
-    let content = fs::read_to_string("/proc/cpuinfo").map_err(|e| TpuDocError::IoError {
-        context: "get_cpu_info".to_string(),
-        message: e.to_string(),
-    })?;
+    let content = fs::read_to_string("/proc/cpuinfo").map_err(|e| TpuDocError::io("get_cpu_info", e))?;
 
     let mut model_name = String::new();
     let mut frequency_mhz = 0.0f64;
@@ -157,15 +157,13 @@ pub fn get_disk_space(path: &str) -> Result<DiskInfo, TpuDocError> {
     let output = std::process::Command::new("df")
         .args(["-B1", path])
         .output()
-        .map_err(|e| TpuDocError::IoError {
-            context: "get_disk_space".to_string(),
-            message: e.to_string(),
-        })?;
+        .map_err(|e| TpuDocError::io("get_disk_space", e))?;
 
     if !output.status.success() {
         return Err(TpuDocError::IoError {
             context: "get_disk_space".to_string(),
             message: "df command failed".to_string(),
+            source: None,
         });
     }
 
@@ -176,6 +174,7 @@ pub fn get_disk_space(path: &str) -> Result<DiskInfo, TpuDocError> {
         return Err(TpuDocError::ParseError {
             context: "get_disk_space".to_string(),
             message: "Could not parse df output".to_string(),
+            source: None,
         });
     }
 
@@ -195,18 +194,48 @@ pub fn get_disk_space(path: &str) -> Result<DiskInfo, TpuDocError> {
         Err(TpuDocError::ParseError {
             context: "get_disk_space".to_string(),
             message: "Could not parse df output".to_string(),
+            source: None,
         })
     }
 }
 
+/// Get the filesystem type backing a path (e.g. "ext4", "tmpfs")
+///
+/// Uses the longest mount point in /proc/mounts that prefixes the path.
+pub fn get_filesystem_type(path: &str) -> Result<String, TpuDocError> {
+    let content = fs::read_to_string("/proc/mounts").map_err(|e| TpuDocError::io("get_filesystem_type", e))?;
+
+    let resolved = fs::canonicalize(path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string());
+
+    let mut best: Option<(usize, String)> = None;
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 3 {
+            continue;
+        }
+        let mount_point = parts[1];
+        let matches = mount_point == "/"
+            || resolved == mount_point
+            || resolved.starts_with(&format!("{}/", mount_point));
+        if matches && best.as_ref().map(|(len, _)| mount_point.len() >= *len).unwrap_or(true) {
+            best = Some((mount_point.len(), parts[2].to_string()));
+        }
+    }
+
+    best.map(|(_, fs_type)| fs_type).ok_or_else(|| TpuDocError::ParseError {
+        context: "get_filesystem_type".to_string(),
+        message: format!("No mount point found for {}", path),
+        source: None,
+    })
+}
+
 /// Read a value from sysfs
 pub fn read_sysfs_value(path: &str) -> Result<String, TpuDocError> {
     fs::read_to_string(path)
         .map(|s| s.trim().to_string())
-        .map_err(|e| TpuDocError::IoError {
-            context: format!("read_sysfs_value({})", path),
-            message: e.to_string(),
-        })
+        .map_err(|e| TpuDocError::io(&format!("read_sysfs_value({})", path), e))
 }
 
 /// Check if a process is running by name
@@ -217,13 +246,11 @@ pub fn check_process_running(name: &str) -> Result<bool, TpuDocError> {
         return Err(TpuDocError::IoError {
             context: "check_process_running".to_string(),
             message: "/proc does not exist".to_string(),
+            source: None,
         });
     }
 
-    for entry in fs::read_dir(proc_dir).map_err(|e| TpuDocError::IoError {
-        context: "check_process_running".to_string(),
-        message: e.to_string(),
-    })? {
+    for entry in fs::read_dir(proc_dir).map_err(|e| TpuDocError::io("check_process_running", e))? {
         let entry = match entry {
             Ok(e) => e,
             Err(_) => continue,
@@ -248,6 +275,165 @@ pub fn check_process_running(name: &str) -> Result<bool, TpuDocError> {
     Ok(false)
 }
 
+thread_local! {
+    /// Cancellation flag of the run whose check is on this thread
+    static CANCELLED: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+struct RestoreCancellation(Option<Arc<AtomicBool>>);
+
+impl Drop for RestoreCancellation {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        CANCELLED.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Run `f` with `cancelled` as this thread's cancellation flag: once it is
+/// set, commands `f` runs are killed and return an error instead of
+/// running to completion or their timeout.
+pub fn with_cancellation<T>(cancelled: Arc<AtomicBool>, f: impl FnOnce() -> T) -> T {
+    let previous = CANCELLED.with(|current| current.replace(Some(cancelled)));
+    // Restore the previous flag even if `f` panics
+    let _restore = RestoreCancellation(previous);
+    f()
+}
+
+fn is_cancelled() -> bool {
+    CANCELLED.with(|current| current.borrow().as_ref().is_some_and(|c| c.load(Ordering::SeqCst)))
+}
+
+/// Run a command, killing it if it has not exited within `timeout_ms`.
+///
+/// Stdout and stderr are captured; stdin is closed. The command stays in
+/// tpu-doc's process group, so a Ctrl-C at the terminal stops it (and
+/// releases any TPU it holds) along with tpu-doc. It is also killed when the
+/// thread's run is cancelled (see [`with_cancellation`]).
+pub fn run_command_with_timeout(command: &mut Command, timeout_ms: u64) -> Result<Output, TpuDocError> {
+    run_command_streaming(command, timeout_ms, |_| {})
+}
+
+/// Run a command like [`run_command_with_timeout`], calling `on_line` with
+/// each line of stdout as soon as the child prints it.
+pub fn run_command_streaming(
+    command: &mut Command,
+    timeout_ms: u64,
+    on_line: impl FnMut(&str),
+) -> Result<Output, TpuDocError> {
+    run_child(command, timeout_ms, false, on_line)
+}
+
+/// Run a user-supplied shell command like [`run_command_with_timeout`], but
+/// in its own process group, so a timeout kills the helpers it started too
+/// instead of leaving them running after the check has failed.
+pub fn run_command_in_own_group(command: &mut Command, timeout_ms: u64) -> Result<Output, TpuDocError> {
+    run_child(command, timeout_ms, true, |_| {})
+}
+
+fn run_child(
+    command: &mut Command,
+    timeout_ms: u64,
+    own_group: bool,
+    mut on_line: impl FnMut(&str),
+) -> Result<Output, TpuDocError> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let cancelled_error = |program: String| TpuDocError::CommandError {
+        command: program,
+        message: "Cancelled".to_string(),
+        source: None,
+    };
+    if is_cancelled() {
+        return Err(cancelled_error(program));
+    }
+    command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    if own_group {
+        command.process_group(0);
+    }
+    let mut child = command.spawn().map_err(|e| TpuDocError::command(&program, e))?;
+
+    // Drain the pipes on separate threads so a chatty child cannot block on a full pipe
+    let drain = |pipe: Option<Box<dyn Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut buffer = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buffer);
+            }
+            buffer
+        })
+    };
+    let stderr = drain(child.stderr.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+    let (lines_tx, lines) = mpsc::channel::<Vec<u8>>();
+    let stdout_pipe = child.stdout.take();
+    let stdout_reader = std::thread::spawn(move || {
+        let Some(pipe) = stdout_pipe else { return };
+        let mut reader = BufReader::new(pipe);
+        loop {
+            let mut line = Vec::new();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) if lines_tx.send(line).is_err() => break,
+                Ok(_) => {}
+            }
+        }
+    });
+    let mut stdout = Vec::new();
+    let mut deliver = |line: Vec<u8>| {
+        on_line(String::from_utf8_lossy(&line).trim_end_matches(['\n', '\r']));
+        stdout.extend(line);
+    };
+
+    let kill = |child: &mut std::process::Child| {
+        if own_group {
+            // The group id is the child's pid; `kill` is the only way to signal a group without libc
+            let _ = Command::new("kill")
+                .args(["-KILL", "--", &format!("-{}", child.id())])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+    };
+
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if is_cancelled() => {
+                kill(&mut child);
+                return Err(cancelled_error(program));
+            }
+            Ok(None) if Instant::now() >= deadline => {
+                kill(&mut child);
+                return Err(TpuDocError::Timeout {
+                    operation: program,
+                    timeout_ms,
+                });
+            }
+            Ok(None) => {
+                if let Ok(line) = lines.recv_timeout(Duration::from_millis(10)) {
+                    deliver(line);
+                }
+            }
+            Err(e) => {
+                return Err(TpuDocError::command(&program, e))
+            }
+        }
+    };
+
+    // The rest of stdout, up to the end of the pipe
+    for line in lines {
+        deliver(line);
+    }
+    let _ = stdout_reader.join();
+
+    Ok(Output {
+        status,
+        stdout,
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
 /// Get an environment variable safely
 pub fn get_environment_variable(name: &str) -> Option<String> {
     std::env::var(name).ok()
@@ -260,3 +446,101 @@ pub fn get_unix_timestamp() -> u64 {
         .map(|d| d.as_secs())
         .unwrap_or(0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_command_streaming() {
+        let start = Instant::now();
+        let mut lines = Vec::new();
+        let output = run_command_streaming(
+            Command::new("sh").arg("-c").arg("echo first; sleep 0.5; printf 'second\\r\\nlast'"),
+            5000,
+            |line| lines.push((line.to_string(), start.elapsed())),
+        )
+        .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"first\nsecond\r\nlast");
+        let text: Vec<&str> = lines.iter().map(|(line, _)| line.as_str()).collect();
+        assert_eq!(text, vec!["first", "second", "last"]);
+        // The first line arrives while the command is still running
+        assert!(lines[0].1 < Duration::from_millis(450), "first line took {:?}", lines[0].1);
+
+        let timed_out = run_command_streaming(Command::new("sleep").arg("5"), 100, |_| {});
+        assert!(matches!(timed_out, Err(TpuDocError::Timeout { .. })));
+    }
+
+    /// Process group of the process whose `/proc/<pid>/stat` line is `stat`
+    fn process_group_of(stat: &str) -> Option<u32> {
+        stat.rsplit_once(')')?.1.split_whitespace().nth(2)?.parse().ok()
+    }
+
+    #[test]
+    fn test_process_groups() {
+        let own = process_group_of(&fs::read_to_string("/proc/self/stat").unwrap());
+        let child_stat = |output: Output| String::from_utf8_lossy(&output.stdout).to_string();
+
+        // Probes stay in our group, so a terminal Ctrl-C reaches them too
+        let probe = run_command_with_timeout(Command::new("cat").arg("/proc/self/stat"), 5000).unwrap();
+        assert_eq!(process_group_of(&child_stat(probe)), own);
+
+        // Custom commands lead a group of their own
+        let custom = run_command_in_own_group(Command::new("cat").arg("/proc/self/stat"), 5000).unwrap();
+        let stat = child_stat(custom);
+        let pid = stat.split_whitespace().next().and_then(|p| p.parse().ok());
+        assert_eq!(process_group_of(&stat), pid);
+        assert_ne!(process_group_of(&stat), own);
+    }
+
+    #[test]
+    fn test_cancellation_kills_command() {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&cancelled);
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            flag.store(true, Ordering::SeqCst);
+        });
+
+        let start = Instant::now();
+        let mut pid = None;
+        let result = with_cancellation(Arc::clone(&cancelled), || {
+            run_command_streaming(Command::new("sh").arg("-c").arg("echo $$; exec sleep 30"), 60000, |line| {
+                pid = line.parse::<u32>().ok()
+            })
+        });
+        canceller.join().unwrap();
+
+        assert!(matches!(result, Err(TpuDocError::CommandError { ref message, .. }) if message == "Cancelled"));
+        assert!(start.elapsed() < Duration::from_secs(5), "took {:?}", start.elapsed());
+        // Killed and reaped, not left running
+        assert!(!Path::new(&format!("/proc/{}", pid.expect("child pid"))).exists());
+
+        // Nothing more starts under a cancelled flag, and the flag ends with the scope
+        let again = with_cancellation(cancelled, || run_command_with_timeout(&mut Command::new("true"), 5000));
+        assert!(again.is_err());
+        assert!(run_command_with_timeout(&mut Command::new("true"), 5000).is_ok());
+    }
+
+    #[test]
+    fn test_timeout_kills_process_group() {
+        let mut background = None;
+        let timed_out = run_child(
+            Command::new("sh").arg("-c").arg("sleep 30 & echo $!; wait"),
+            300,
+            true,
+            |line| background = line.parse::<u32>().ok(),
+        );
+        assert!(matches!(timed_out, Err(TpuDocError::Timeout { .. })));
+
+        // The orphaned `sleep` is killed with its shell, then reaped by init
+        let stat = format!("/proc/{}/stat", background.expect("background pid"));
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while fs::read_to_string(&stat).is_ok_and(|s| !s.contains(") Z ")) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(fs::read_to_string(&stat).map_or(true, |s| s.contains(") Z ")), "background sleep survived");
+    }
+}
@@ -4,9 +4,21 @@
 //! - TPU device information
 //! - Linux system information
 //! - GCP metadata
+//! - Compute Engine API resources
+//! - The `gcloud` CLI, as a fallback for the APIs
+//! - Cloud Storage bucket metadata
+//! - Kubernetes pod identity
+//! - Container registry access
 //! - Network connectivity
+//! - Non-fatal issues met along the way, for the report's diagnostics
 
+pub mod compute;
+pub mod diagnostics;
+pub mod gcloud;
 pub mod gcp;
+pub mod kubernetes;
 pub mod linux;
 pub mod network;
+pub mod registry;
+pub mod storage;
 pub mod tpu;
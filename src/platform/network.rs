@@ -18,6 +18,7 @@
 use crate::TpuDocError;
 use std::io::{BufRead, BufReader, Read, Write};
 use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 /// DNS resolution result
@@ -65,10 +66,7 @@ pub fn check_dns_resolution(hostname: &str) -> Result<DnsResult, TpuDocError> {
     let socket_addr = format!("{}:80", hostname);
     let addrs: Vec<_> = socket_addr
         .to_socket_addrs()
-        .map_err(|e| TpuDocError::IoError {
-            context: format!("DNS resolution for {}", hostname),
-            message: e.to_string(),
-        })?
+        .map_err(|e| TpuDocError::io(&format!("DNS resolution for {}", hostname), e))?
         .collect();
 
     let resolution_time_ms = start.elapsed().as_millis() as u64;
@@ -77,6 +75,7 @@ pub fn check_dns_resolution(hostname: &str) -> Result<DnsResult, TpuDocError> {
         return Err(TpuDocError::IoError {
             context: format!("DNS resolution for {}", hostname),
             message: "No addresses returned".to_string(),
+            source: None,
         });
     }
 
@@ -103,11 +102,13 @@ pub fn check_tcp_connectivity(
         .map_err(|e| TpuDocError::IoError {
             context: format!("TCP connect to {}:{}", host, port),
             message: format!("DNS resolution failed: {}", e),
+            source: Some(Arc::new(e)),
         })?
         .next()
         .ok_or_else(|| TpuDocError::IoError {
             context: format!("TCP connect to {}:{}", host, port),
             message: "No address resolved".to_string(),
+            source: None,
         })?;
 
     // Attempt connection
@@ -127,10 +128,7 @@ pub fn check_tcp_connectivity(
                     latency_ms,
                 })
             } else {
-                Err(TpuDocError::IoError {
-                    context: format!("TCP connect to {}:{}", host, port),
-                    message: e.to_string(),
-                })
+                Err(TpuDocError::io(&format!("TCP connect to {}:{}", host, port), e))
             }
         }
     }
@@ -158,21 +156,16 @@ pub fn check_http_endpoint(url: &str, timeout_ms: u64) -> Result<HttpResult, Tpu
     let addr = format!("{}:{}", host, port);
     let mut stream = TcpStream::connect_timeout(
         &addr.to_socket_addrs()
-            .map_err(|e| TpuDocError::IoError {
-                context: format!("HTTP request to {}", url),
-                message: e.to_string(),
-            })?
+            .map_err(|e| TpuDocError::io(&format!("HTTP request to {}", url), e))?
             .next()
             .ok_or_else(|| TpuDocError::IoError {
                 context: format!("HTTP request to {}", url),
                 message: "No address resolved".to_string(),
+                source: None,
             })?,
         Duration::from_millis(timeout_ms),
     )
-    .map_err(|e| TpuDocError::IoError {
-        context: format!("HTTP request to {}", url),
-        message: e.to_string(),
-    })?;
+    .map_err(|e| TpuDocError::io(&format!("HTTP request to {}", url), e))?;
 
     stream.set_read_timeout(Some(Duration::from_millis(timeout_ms))).ok();
     stream.set_write_timeout(Some(Duration::from_millis(timeout_ms))).ok();
@@ -190,6 +183,7 @@ pub fn check_http_endpoint(url: &str, timeout_ms: u64) -> Result<HttpResult, Tpu
     stream.write_all(request.as_bytes()).map_err(|e| TpuDocError::IoError {
         context: format!("HTTP request to {}", url),
         message: format!("Write failed: {}", e),
+        source: Some(Arc::new(e)),
     })?;
 
     // Read response
@@ -198,6 +192,7 @@ pub fn check_http_endpoint(url: &str, timeout_ms: u64) -> Result<HttpResult, Tpu
     reader.read_line(&mut status_line).map_err(|e| TpuDocError::IoError {
         context: format!("HTTP request to {}", url),
         message: format!("Read failed: {}", e),
+        source: Some(Arc::new(e)),
     })?;
 
     // Parse status code
@@ -273,6 +268,7 @@ fn parse_url(url: &str) -> Result<(String, u16, String, bool), TpuDocError> {
         return Err(TpuDocError::ParseError {
             context: "parse_url".to_string(),
             message: format!("Invalid URL scheme: {}", url),
+            source: None,
         });
     };
 
@@ -286,6 +282,7 @@ fn parse_url(url: &str) -> Result<(String, u16, String, bool), TpuDocError> {
             let port: u16 = host_port[idx + 1..].parse().map_err(|_| TpuDocError::ParseError {
                 context: "parse_url".to_string(),
                 message: format!("Invalid port in URL: {}", url),
+                source: None,
             })?;
             (&host_port[..idx], port)
         }
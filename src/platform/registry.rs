@@ -0,0 +1,103 @@
+//! Container registry interface.
+//!
+//! Checks that a container registry answers the Docker Registry HTTP API
+//! v2, and that the instance's service account can pull from Artifact
+//! Registry (`*.pkg.dev`) and Container Registry (`gcr.io`): it logs in as
+//! `docker` and containerd do, exchanging the service account's token at the
+//! registry's `/v2/token` endpoint for a pull token. Other registries, such
+//! as Docker Hub mirrors, are checked anonymously.
+//!
+//! Requests go through [`gcp::https_get`], which needs the TLS support of the
+//! `ai` feature.
+
+use crate::ai::client::basic_auth;
+use crate::platform::gcp;
+use crate::TpuDocError;
+use std::fmt;
+
+/// A registry host and, optionally, a repository in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegistryRef {
+    /// e.g. `us-docker.pkg.dev`
+    pub host: String,
+    /// e.g. `my-project/images`; None for the whole registry
+    pub repository: Option<String>,
+}
+
+impl RegistryRef {
+    /// Parse `host[/repository]`, with or without an `https://` prefix
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().trim_start_matches("https://").trim_end_matches('/');
+        let (host, repository) = match s.split_once('/') {
+            Some((host, repository)) => (host, Some(repository.to_string())),
+            None => (s, None),
+        };
+        let valid = !host.is_empty() && host.chars().all(|c| c.is_ascii_alphanumeric() || ".-:".contains(c));
+        valid.then(|| RegistryRef {
+            host: host.to_lowercase(),
+            repository: repository.filter(|r| !r.is_empty()),
+        })
+    }
+
+    /// Whether the registry is Artifact Registry or Container Registry,
+    /// which accept the service account's token
+    pub fn is_google(&self) -> bool {
+        self.host.ends_with(".pkg.dev") || self.host == "gcr.io" || self.host.ends_with(".gcr.io")
+    }
+
+    /// Path of the pull-token request for this registry
+    fn token_path(&self) -> String {
+        match &self.repository {
+            Some(repository) => format!("/v2/token?service={}&scope=repository:{}:pull", self.host, repository),
+            None => format!("/v2/token?service={}", self.host),
+        }
+    }
+}
+
+impl fmt::Display for RegistryRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.repository {
+            Some(repository) => write!(f, "{}/{}", self.host, repository),
+            None => write!(f, "{}", self.host),
+        }
+    }
+}
+
+/// HTTP status of an anonymous request to the registry's v2 API root: 200,
+/// or 401 asking for a login, from a working registry
+pub fn v2_status(registry: &RegistryRef) -> Result<u16, TpuDocError> {
+    Ok(gcp::https_get(&registry.host, "/v2/", &[])?.status)
+}
+
+/// HTTP status of exchanging the service account's `access_token` for a
+/// pull token: 200 when the service account may pull
+pub fn pull_token_status(registry: &RegistryRef, access_token: &str) -> Result<u16, TpuDocError> {
+    let authorization = basic_auth(&format!("oauth2accesstoken:{}", access_token));
+    let headers = [("Authorization", authorization.as_str())];
+    Ok(gcp::https_get(&registry.host, &registry.token_path(), &headers)?.status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_registry() {
+        let registry = RegistryRef::parse("https://us-docker.pkg.dev/my-project/images/").unwrap();
+        assert_eq!(registry.host, "us-docker.pkg.dev");
+        assert_eq!(registry.repository.as_deref(), Some("my-project/images"));
+        assert_eq!(registry.to_string(), "us-docker.pkg.dev/my-project/images");
+        assert!(registry.is_google());
+        assert_eq!(
+            registry.token_path(),
+            "/v2/token?service=us-docker.pkg.dev&scope=repository:my-project/images:pull"
+        );
+
+        let mirror = RegistryRef::parse("mirror.example.com:5000").unwrap();
+        assert_eq!(mirror.repository, None);
+        assert!(!mirror.is_google());
+        assert!(RegistryRef::parse("gcr.io/p").unwrap().is_google());
+        assert_eq!(RegistryRef::parse(""), None);
+        assert_eq!(RegistryRef::parse("bad host/x"), None);
+    }
+}
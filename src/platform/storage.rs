@@ -0,0 +1,98 @@
+//! Cloud Storage JSON API interface.
+//!
+//! Reads bucket metadata (location, default storage class, and retention)
+//! through [`gcp::api_get`]. The service account needs `storage.buckets.get`
+//! on the bucket (e.g. `roles/storage.legacyBucketReader`), and the build the
+//! TLS support of the `ai` feature.
+
+use crate::platform::gcp;
+use crate::util::json::{self, JsonValue};
+use crate::TpuDocError;
+
+const API_HOST: &str = "storage.googleapis.com";
+
+/// Where a bucket stores its data and how
+#[derive(Debug, Clone, PartialEq)]
+pub struct BucketInfo {
+    pub name: String,
+    /// Region (`US-CENTRAL1`), dual-region (`NAM4`), or multi-region (`US`),
+    /// in upper case as the API reports it
+    pub location: String,
+    /// `region`, `dual-region`, or `multi-region`
+    pub location_type: String,
+    /// Default storage class: `STANDARD`, `NEARLINE`, `COLDLINE`, `ARCHIVE`
+    pub storage_class: String,
+    /// Retention policy: objects cannot be deleted for this many seconds
+    pub retention_period_secs: Option<u64>,
+    /// Soft delete: deleted objects are kept (and billed) for this many seconds
+    pub soft_delete_secs: Option<u64>,
+}
+
+/// Get the metadata of bucket `name`
+pub fn get_bucket(name: &str) -> Result<BucketInfo, TpuDocError> {
+    let path = format!(
+        "/storage/v1/b/{}?fields=name,location,locationType,storageClass,retentionPolicy,softDeletePolicy",
+        name
+    );
+    parse_bucket(&gcp::api_get(API_HOST, &path)?)
+}
+
+/// The bucket name of a `gs://bucket/path` URL
+pub fn bucket_of(url: &str) -> Option<&str> {
+    let rest = url.strip_prefix("gs://")?;
+    let bucket = rest.split('/').next().unwrap_or_default();
+    (!bucket.is_empty()).then_some(bucket)
+}
+
+fn parse_bucket(body: &str) -> Result<BucketInfo, TpuDocError> {
+    let document = json::parse(body).map_err(|message| TpuDocError::ParseError {
+        context: "bucket metadata".to_string(),
+        message,
+        source: None,
+    })?;
+    let text = |key: &str| document.get(key).and_then(JsonValue::as_str).unwrap_or_default().to_string();
+    // int64 fields are JSON strings in the Cloud Storage API
+    let seconds = |policy: &str, key: &str| {
+        document
+            .get(policy)
+            .and_then(|p| p.get(key))
+            .and_then(JsonValue::to_scalar_string)
+            .and_then(|secs| secs.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+    };
+    Ok(BucketInfo {
+        name: text("name"),
+        location: text("location").to_uppercase(),
+        location_type: text("locationType"),
+        storage_class: text("storageClass").to_uppercase(),
+        retention_period_secs: seconds("retentionPolicy", "retentionPeriod"),
+        soft_delete_secs: seconds("softDeletePolicy", "retentionDurationSeconds"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bucket() {
+        let body = r#"{"name": "ckpts", "location": "US-CENTRAL2", "locationType": "region",
+                       "storageClass": "STANDARD"}"#;
+        let bucket = parse_bucket(body).unwrap();
+        assert_eq!(bucket.location, "US-CENTRAL2");
+        assert_eq!(bucket.location_type, "region");
+        assert_eq!(bucket.storage_class, "STANDARD");
+        assert_eq!((bucket.retention_period_secs, bucket.soft_delete_secs), (None, None));
+        assert!(parse_bucket("[").is_err());
+
+        let body = r#"{"name": "ckpts", "retentionPolicy": {"retentionPeriod": "2592000"},
+                       "softDeletePolicy": {"retentionDurationSeconds": "604800"}}"#;
+        let bucket = parse_bucket(body).unwrap();
+        assert_eq!((bucket.retention_period_secs, bucket.soft_delete_secs), (Some(2_592_000), Some(604_800)));
+
+        assert_eq!(bucket_of("gs://ckpts/run1/*.tfrecord"), Some("ckpts"));
+        assert_eq!(bucket_of("gs://ckpts"), Some("ckpts"));
+        assert_eq!(bucket_of("gs:///x"), None);
+        assert_eq!(bucket_of("/mnt/data"), None);
+    }
+}
@@ -10,7 +10,13 @@
 //! - Missing sysfs: Falls back to environment variables
 //! - Missing env vars: Falls back to GCP metadata or defaults
 //! - Parse errors: Uses safe defaults for chip counts, temperatures
+//!
+//! Fallbacks taken because a source was malformed or only partly available
+//! are recorded as diagnostics (see [`diagnostics`]), so the verbose report
+//! can say why a check saw the value it did.
 //! - No libtpu: Returns estimates based on TPU type where possible
+//! - No runtime metrics service: get_runtime_metrics() returns an IoError
+//!   (the service only exists while a libtpu workload is running)
 //!
 //! The module uses a multi-level fallback strategy:
 //! 1. Environment variables (TPU_NAME, TPU_CHIPS_PER_HOST, etc.)
@@ -20,9 +26,63 @@
 //!
 //! No function in this module will panic.
 
-use crate::platform::{gcp, linux};
+use crate::data::specs;
+use crate::platform::{diagnostics, gcp, linux};
+use crate::util;
 use crate::TpuDocError;
+use std::net::{TcpStream, ToSocketAddrs};
 use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default address of the libtpu runtime metrics service (gRPC)
+pub const DEFAULT_METRICS_ADDR: &str = "localhost:8431";
+
+/// Timeout for the TCP connect that checks the metrics service is up
+const METRICS_CONNECT_TIMEOUT_MS: u64 = 1000;
+
+/// Timeout for the python3 metrics probe
+const METRICS_PROBE_TIMEOUT_MS: u64 = 15_000;
+
+/// Python probe for the runtime metrics service at `sys.argv[1]`.
+///
+/// Uses the `tpu-info` package's client when installed and otherwise the
+/// libtpu SDK (which always queries libtpu's default address). Prints one
+/// `chip=N duty_cycle=PCT hbm_used=BYTES hbm_total=BYTES` line per chip.
+const METRICS_PROBE: &str = r#"
+import sys
+
+try:
+    from tpu_info import device, metrics
+except ImportError:
+    metrics = None
+
+if metrics is not None:
+    chip_type, _ = device.get_local_chips()
+    if chip_type is None:
+        sys.exit("no local TPU chips found")
+    for usage in metrics.get_chip_usage(chip_type, addr=sys.argv[1]):
+        print(f"chip={usage.device_id} duty_cycle={usage.duty_cycle_pct} "
+              f"hbm_used={usage.memory_usage} hbm_total={usage.total_memory}")
+else:
+    from libtpu.sdk import tpumonitoring
+
+    def data(name):
+        try:
+            return tpumonitoring.get_metric(metric_name=name).data()
+        except Exception:
+            return []
+
+    columns = {
+        "duty_cycle": data("duty_cycle_pct"),
+        "hbm_used": data("hbm_capacity_usage"),
+        "hbm_total": data("hbm_capacity_total"),
+    }
+    for chip in range(max(map(len, columns.values()))):
+        fields = [f"{key}={values[chip]}" for key, values in columns.items() if chip < len(values)]
+        print(f"chip={chip}", *fields)
+"#;
 
 /// TPU generation/type
 #[derive(Debug, Clone, PartialEq)]
@@ -62,6 +122,29 @@ pub struct HbmInfo {
     pub total_bytes: u64,
     pub available_bytes: u64,
     pub per_chip_bytes: u64,
+    /// Per-chip breakdown, ordered by chip index
+    pub chips: Vec<ChipHbm>,
+    /// True when usage came from the runtime metrics service rather than an estimate
+    pub measured: bool,
+}
+
+/// HBM capacity and free memory for a single chip
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChipHbm {
+    pub chip: u32,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+}
+
+impl ChipHbm {
+    /// Percentage of this chip's HBM in use
+    pub fn used_percent(&self) -> f64 {
+        if self.total_bytes > 0 {
+            (self.total_bytes.saturating_sub(self.free_bytes)) as f64 / self.total_bytes as f64 * 100.0
+        } else {
+            0.0
+        }
+    }
 }
 
 /// TPU health status
@@ -94,6 +177,61 @@ pub struct IciStatus {
     pub details: String,
 }
 
+/// Live runtime metrics for a single chip, as reported by libtpu
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChipMetrics {
+    pub chip: u32,
+    pub duty_cycle_percent: Option<f64>,
+    pub hbm_used_bytes: Option<u64>,
+    pub hbm_total_bytes: Option<u64>,
+}
+
+impl ChipMetrics {
+    /// HBM usage as a percentage of capacity, when both figures are known
+    pub fn hbm_utilization_percent(&self) -> Option<f64> {
+        match (self.hbm_used_bytes, self.hbm_total_bytes) {
+            (Some(used), Some(total)) if total > 0 => Some(used as f64 / total as f64 * 100.0),
+            _ => None,
+        }
+    }
+}
+
+/// Live runtime metrics read from the libtpu runtime metrics service
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeMetrics {
+    pub chips: Vec<ChipMetrics>,
+}
+
+impl RuntimeMetrics {
+    /// Mean tensorcore duty cycle across chips that report one
+    pub fn mean_duty_cycle_percent(&self) -> Option<f64> {
+        mean(self.chips.iter().filter_map(|c| c.duty_cycle_percent))
+    }
+
+    /// Aggregate HBM usage across chips that report both used and total bytes
+    pub fn hbm_utilization_percent(&self) -> Option<f64> {
+        let (used, total) = self
+            .chips
+            .iter()
+            .filter_map(|c| Some((c.hbm_used_bytes?, c.hbm_total_bytes?)))
+            .fold((0u64, 0u64), |(u, t), (cu, ct)| (u + cu, t + ct));
+        if total > 0 {
+            Some(used as f64 / total as f64 * 100.0)
+        } else {
+            None
+        }
+    }
+}
+
+/// What is using the TPU right now
+#[derive(Debug, Clone, Default)]
+pub struct TpuActivity {
+    /// Processes other than this one with a TPU device open
+    pub holders: Vec<u32>,
+    /// Live runtime metrics, when a workload serves them
+    pub metrics: Option<RuntimeMetrics>,
+}
+
 /// Check if running on a TPU VM
 pub fn is_tpu_vm() -> bool {
     // Check multiple signals
@@ -141,12 +279,21 @@ pub fn get_tpu_type() -> Result<TpuType, TpuDocError> {
     }
 
     // Try accelerator type from metadata
-    if let Ok(Some(accel_type)) = gcp::get_instance_attribute("accelerator-type") {
-        return Ok(parse_tpu_type(&accel_type));
+    let accelerator_type = gcp::get_instance_attribute("accelerator-type");
+    if let Ok(Some(accel_type)) = &accelerator_type {
+        return Ok(parse_tpu_type(accel_type));
     }
 
     // Try machine type
     if let Ok(machine_type) = gcp::get_machine_type() {
+        let reason = match accelerator_type {
+            Err(e) => e.to_string(),
+            _ => "attribute not set".to_string(),
+        };
+        diagnostics::record(
+            "metadata",
+            format!("accelerator-type unavailable ({}); TPU type taken from machine type {}", reason, machine_type),
+        );
         return Ok(parse_tpu_type(&machine_type));
     }
 
@@ -156,10 +303,8 @@ pub fn get_tpu_type() -> Result<TpuType, TpuDocError> {
 /// Get TPU chip count
 pub fn get_tpu_chip_count() -> Result<u32, TpuDocError> {
     // Try environment variable
-    if let Some(chips_str) = linux::get_environment_variable("TPU_CHIPS_PER_HOST") {
-        if let Ok(chips) = chips_str.parse() {
-            return Ok(chips);
-        }
+    if let Some(chips) = env_number("TPU_CHIPS_PER_HOST") {
+        return Ok(chips);
     }
 
     // Try to count accelerator devices
@@ -180,7 +325,14 @@ pub fn get_tpu_chip_count() -> Result<u32, TpuDocError> {
 
     // Fall back to TPU type defaults
     match get_tpu_type() {
-        Ok(tpu_type) => Ok(default_chip_count(&tpu_type)),
+        Ok(tpu_type) => {
+            let chips = default_chip_count(&tpu_type);
+            diagnostics::record(
+                "sysfs",
+                format!("no accel devices in /sys/class/accel; assuming {} chips for {}", chips, tpu_type),
+            );
+            Ok(chips)
+        }
         Err(e) => Err(e),
     }
 }
@@ -188,10 +340,8 @@ pub fn get_tpu_chip_count() -> Result<u32, TpuDocError> {
 /// Get expected chip count (for comparison)
 pub fn get_expected_chip_count() -> Result<u32, TpuDocError> {
     // Try environment variable override
-    if let Some(expected) = linux::get_environment_variable("TPU_EXPECTED_CHIPS") {
-        if let Ok(chips) = expected.parse() {
-            return Ok(chips);
-        }
+    if let Some(chips) = env_number("TPU_EXPECTED_CHIPS") {
+        return Ok(chips);
     }
 
     // Use default for TPU type
@@ -206,14 +356,7 @@ pub fn get_tpu_topology() -> Result<TpuTopology, TpuDocError> {
     let chips = get_tpu_chip_count()?;
     let tpu_type = get_tpu_type()?;
 
-    let cores_per_chip = match tpu_type {
-        TpuType::V4 => 2,
-        TpuType::V5e => 1,
-        TpuType::V5p => 2,
-        TpuType::V6e => 1,
-        TpuType::V7 => 2,
-        TpuType::Unknown => 1,
-    };
+    let cores_per_chip = specs::spec_for(&tpu_type).map(|s| s.cores_per_chip).unwrap_or(1);
 
     let shape = linux::get_environment_variable("TPU_TOPOLOGY")
         .unwrap_or_else(|| format!("{}x1", chips));
@@ -230,27 +373,31 @@ pub fn get_hbm_info() -> Result<HbmInfo, TpuDocError> {
     let tpu_type = get_tpu_type()?;
     let chips = get_tpu_chip_count()?;
 
-    // Per-chip HBM by TPU type (in bytes)
-    let per_chip_bytes: u64 = match tpu_type {
-        TpuType::V4 => 32 * 1024 * 1024 * 1024,      // 32GB
-        TpuType::V5e => 16 * 1024 * 1024 * 1024,     // 16GB
-        TpuType::V5p => 95 * 1024 * 1024 * 1024,     // 95GB
-        TpuType::V6e => 32 * 1024 * 1024 * 1024,     // 32GB (estimated)
-        TpuType::V7 => 128 * 1024 * 1024 * 1024,    // 128GB (estimated)
-        TpuType::Unknown => 16 * 1024 * 1024 * 1024, // Conservative default
-    };
-
-    let total_bytes = per_chip_bytes * chips as u64;
+    // Per-chip HBM by TPU type, with a conservative default for unknown types
+    let per_chip_bytes = specs::spec_for(&tpu_type)
+        .map(|s| s.hbm_bytes())
+        .unwrap_or(16 * 1024 * 1024 * 1024);
+
+    // Prefer live per-chip usage from the libtpu metrics service. It is only
+    // served while some process holds the TPU, which is exactly the case where
+    // memory is not free.
+    if let Ok(metrics) = get_runtime_metrics() {
+        let measured = hbm_from_runtime_metrics(&metrics, per_chip_bytes);
+        if !measured.is_empty() {
+            return Ok(build_hbm_info(measured, per_chip_bytes, true));
+        }
+    }
 
-    // We can't easily get actual available HBM without libtpu
-    // For now, assume 95% available as default
-    let available_bytes = (total_bytes as f64 * 0.95) as u64;
+    // Otherwise estimate: assume 95% of each chip's HBM is available
+    let estimated = (0..chips)
+        .map(|chip| ChipHbm {
+            chip,
+            total_bytes: per_chip_bytes,
+            free_bytes: (per_chip_bytes as f64 * 0.95) as u64,
+        })
+        .collect();
 
-    Ok(HbmInfo {
-        total_bytes,
-        available_bytes,
-        per_chip_bytes,
-    })
+    Ok(build_hbm_info(estimated, per_chip_bytes, false))
 }
 
 /// Get TPU health status
@@ -295,6 +442,71 @@ pub fn check_tpu_driver_loaded() -> bool {
     false
 }
 
+/// Lock file libtpu creates while a process is using the TPU
+pub const LIBTPU_LOCKFILE: &str = "/tmp/libtpu_lockfile";
+
+/// PIDs of processes with a TPU device (`/dev/accel*` or `/dev/vfio/*`)
+/// open. Processes whose file descriptors cannot be read are not listed.
+pub fn get_tpu_device_holders() -> Vec<u32> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_string_lossy().parse::<u32>().ok())
+        .filter(|pid| {
+            std::fs::read_dir(format!("/proc/{}/fd", pid))
+                .map(|fds| {
+                    fds.flatten().any(|fd| {
+                        std::fs::read_link(fd.path()).is_ok_and(|target| {
+                            let target = target.to_string_lossy();
+                            target.starts_with("/dev/accel") || target.starts_with("/dev/vfio/")
+                        })
+                    })
+                })
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+/// The processes holding the TPU and, when the libtpu metrics service
+/// answers, the workload's live metrics
+pub fn get_tpu_activity() -> TpuActivity {
+    let own_pid = std::process::id();
+    TpuActivity {
+        holders: get_tpu_device_holders().into_iter().filter(|pid| *pid != own_pid).collect(),
+        metrics: get_runtime_metrics().ok(),
+    }
+}
+
+/// The libtpu lock file when it is left over from a process that exited:
+/// it exists, no lock on it is listed in `/proc/locks`, and no process has a
+/// TPU device open
+pub fn get_stale_libtpu_lockfile() -> Option<&'static Path> {
+    use std::os::unix::fs::MetadataExt;
+
+    let path = Path::new(LIBTPU_LOCKFILE);
+    let inode = std::fs::metadata(path).ok()?.ino();
+    let locks = std::fs::read_to_string("/proc/locks").ok()?;
+    if locked_inodes(&locks).contains(&inode) || !get_tpu_device_holders().is_empty() {
+        return None;
+    }
+    Some(path)
+}
+
+/// Inodes with a lock in `/proc/locks` content, from lines such as
+/// `1: FLOCK  ADVISORY  WRITE 1234 08:01:131090 0 EOF`
+fn locked_inodes(proc_locks: &str) -> Vec<u64> {
+    proc_locks
+        .lines()
+        .filter_map(|line| {
+            // Blocked waiters are listed as "1: -> FLOCK ..."
+            let fields: Vec<&str> = line.split_whitespace().filter(|f| *f != "->").collect();
+            fields.get(5)?.rsplit(':').next()?.parse().ok()
+        })
+        .collect()
+}
+
 /// Get driver version
 pub fn get_driver_version() -> Result<String, TpuDocError> {
     // Try to read from sysfs
@@ -317,6 +529,7 @@ pub fn get_driver_version() -> Result<String, TpuDocError> {
     Err(TpuDocError::IoError {
         context: "get_driver_version".to_string(),
         message: "Driver version not available".to_string(),
+        source: None,
     })
 }
 
@@ -344,6 +557,7 @@ pub fn get_libtpu_version() -> Result<String, TpuDocError> {
     Err(TpuDocError::IoError {
         context: "get_libtpu_version".to_string(),
         message: "libtpu not found".to_string(),
+        source: None,
     })
 }
 
@@ -361,11 +575,16 @@ pub fn get_thermal_info() -> Result<ThermalInfo, TpuDocError> {
             if let Ok(zone_type) = std::fs::read_to_string(&type_path) {
                 if zone_type.contains("tpu") || zone_type.contains("accel") {
                     let temp_path = path.join("temp");
-                    if let Ok(temp_str) = std::fs::read_to_string(&temp_path) {
+                    match std::fs::read_to_string(&temp_path) {
                         // Temperature is in millidegrees Celsius
-                        if let Ok(temp_milli) = temp_str.trim().parse::<i64>() {
-                            temperatures.push(temp_milli as f64 / 1000.0);
-                        }
+                        Ok(temp_str) => match temp_str.trim().parse::<i64>() {
+                            Ok(temp_milli) => temperatures.push(temp_milli as f64 / 1000.0),
+                            Err(_) => diagnostics::record(
+                                "thermal",
+                                format!("{}: '{}' is not a temperature", temp_path.display(), temp_str.trim()),
+                            ),
+                        },
+                        Err(e) => diagnostics::record("thermal", format!("{}: {}", temp_path.display(), e)),
                     }
                 }
             }
@@ -376,6 +595,10 @@ pub fn get_thermal_info() -> Result<ThermalInfo, TpuDocError> {
         // Return synthetic data based on chip count
         let chips = get_tpu_chip_count().unwrap_or(1);
         temperatures = vec![65.0; chips as usize]; // Assume normal temperature
+        diagnostics::record(
+            "thermal",
+            "no readable TPU thermal zones in /sys/class/thermal; assuming 65C for each chip",
+        );
     }
 
     Ok(ThermalInfo {
@@ -388,13 +611,8 @@ pub fn get_error_counters() -> Result<ErrorCounters, TpuDocError> {
     // Try to read from sysfs
     // This is hardware-specific and may not be available on all TPUs
 
-    let correctable = linux::get_environment_variable("TPU_CORRECTABLE_ERRORS")
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(0);
-
-    let uncorrectable = linux::get_environment_variable("TPU_UNCORRECTABLE_ERRORS")
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(0);
+    let correctable = env_number("TPU_CORRECTABLE_ERRORS").unwrap_or(0);
+    let uncorrectable = env_number("TPU_UNCORRECTABLE_ERRORS").unwrap_or(0);
 
     Ok(ErrorCounters {
         correctable,
@@ -412,6 +630,7 @@ pub fn get_ici_status() -> Result<IciStatus, TpuDocError> {
         return Err(TpuDocError::IoError {
             context: "get_ici_status".to_string(),
             message: "Single chip configuration".to_string(),
+            source: None,
         });
     }
 
@@ -433,8 +652,155 @@ pub fn get_ici_status() -> Result<IciStatus, TpuDocError> {
     })
 }
 
+/// Environment override `name` as a number, or None when unset or, with a
+/// diagnostic, not a number
+fn env_number<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let value = linux::get_environment_variable(name)?;
+    let parsed = value.trim().parse().ok();
+    if parsed.is_none() {
+        diagnostics::record("environment", format!("ignoring {}='{}': not a whole number", name, value));
+    }
+    parsed
+}
+
+/// Address of the libtpu runtime metrics service.
+///
+/// Defaults to localhost:8431; override with TPU_PREFLIGHT_METRICS_ADDR
+/// (host:port).
+pub fn get_metrics_addr() -> String {
+    util::env_setting("TPU_PREFLIGHT_METRICS_ADDR")
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_METRICS_ADDR.to_string())
+}
+
+/// Read live utilization metrics from the local libtpu runtime metrics service.
+///
+/// The service speaks gRPC and is only up while a process holds the TPU
+/// through libtpu, so a quick TCP connect rules out the common no-workload
+/// case before [`METRICS_PROBE`] queries it through python3.
+pub fn get_runtime_metrics() -> Result<RuntimeMetrics, TpuDocError> {
+    let addr = get_metrics_addr();
+    metrics_service_reachable(&addr, METRICS_CONNECT_TIMEOUT_MS)?;
+
+    let output = linux::run_command_with_timeout(
+        Command::new("python3").args(["-c", METRICS_PROBE, &addr]),
+        METRICS_PROBE_TIMEOUT_MS,
+    )?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(TpuDocError::CommandError {
+            command: "python3 (libtpu metrics probe)".to_string(),
+            message: stderr.lines().last().unwrap_or("probe failed").to_string(),
+            source: None,
+        });
+    }
+
+    let metrics = parse_runtime_metrics(&String::from_utf8_lossy(&output.stdout));
+    if metrics.chips.is_empty() {
+        return Err(TpuDocError::ParseError {
+            context: "get_runtime_metrics".to_string(),
+            message: format!("No TPU metrics found at {}", addr),
+            source: None,
+        });
+    }
+
+    Ok(metrics)
+}
+
 // Helper functions
 
+fn metrics_service_reachable(addr: &str, timeout_ms: u64) -> Result<(), TpuDocError> {
+    let socket_addr = addr
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(|| TpuDocError::IoError {
+            context: "get_runtime_metrics".to_string(),
+            message: format!("Invalid address: {}", addr),
+            source: None,
+        })?;
+
+    TcpStream::connect_timeout(&socket_addr, Duration::from_millis(timeout_ms))
+        .map(drop)
+        .map_err(|e| TpuDocError::IoError {
+            context: "get_runtime_metrics".to_string(),
+            message: format!("Connection to {} failed: {}", addr, e),
+            source: Some(Arc::new(e)),
+        })
+}
+
+/// Parse the [`METRICS_PROBE`] output: one line per chip of `key=value`
+/// fields, where `chip` is required and any other field may be missing.
+fn parse_runtime_metrics(output: &str) -> RuntimeMetrics {
+    let mut chips: Vec<ChipMetrics> = Vec::new();
+
+    for line in output.lines() {
+        let fields: Vec<(&str, &str)> = line.split_whitespace().filter_map(|f| f.split_once('=')).collect();
+        let field = |key: &str| {
+            fields
+                .iter()
+                .find(|(k, _)| *k == key)
+                .and_then(|(_, v)| v.parse::<f64>().ok())
+                .filter(|v| v.is_finite() && *v >= 0.0)
+        };
+        let Some(chip) = fields.iter().find(|(k, _)| *k == "chip").and_then(|(_, v)| v.parse::<u32>().ok()) else {
+            continue;
+        };
+
+        let metrics = ChipMetrics {
+            chip,
+            duty_cycle_percent: field("duty_cycle"),
+            hbm_used_bytes: field("hbm_used").map(|v| v as u64),
+            hbm_total_bytes: field("hbm_total").map(|v| v as u64),
+        };
+        // Drop chips that reported nothing we understand
+        if metrics != (ChipMetrics { chip, ..Default::default() }) {
+            chips.retain(|c| c.chip != chip);
+            chips.push(metrics);
+        }
+    }
+
+    chips.sort_by_key(|c| c.chip);
+    RuntimeMetrics { chips }
+}
+
+/// Per-chip HBM from runtime metrics, using the type-based capacity when a chip
+/// reports usage without a total
+fn hbm_from_runtime_metrics(metrics: &RuntimeMetrics, per_chip_bytes: u64) -> Vec<ChipHbm> {
+    metrics
+        .chips
+        .iter()
+        .filter_map(|c| {
+            let used = c.hbm_used_bytes?;
+            let total = c.hbm_total_bytes.filter(|t| *t > 0).unwrap_or(per_chip_bytes);
+            Some(ChipHbm {
+                chip: c.chip,
+                total_bytes: total,
+                free_bytes: total.saturating_sub(used),
+            })
+        })
+        .collect()
+}
+
+fn build_hbm_info(chips: Vec<ChipHbm>, per_chip_bytes: u64, measured: bool) -> HbmInfo {
+    HbmInfo {
+        total_bytes: chips.iter().map(|c| c.total_bytes).sum(),
+        available_bytes: chips.iter().map(|c| c.free_bytes).sum(),
+        per_chip_bytes,
+        chips,
+        measured,
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(s, n), v| (s + v, n + 1));
+    if count > 0 {
+        Some(sum / count as f64)
+    } else {
+        None
+    }
+}
+
 fn parse_tpu_type(name: &str) -> TpuType {
     let lower = name.to_lowercase();
 
@@ -463,3 +829,93 @@ fn default_chip_count(tpu_type: &TpuType) -> u32 {
         TpuType::Unknown => 1,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_runtime_metrics() {
+        // What METRICS_PROBE prints through tpu-info on a v5litepod-4 (16 GiB per chip)
+        let tpu_info = "\
+chip=0 duty_cycle=87.5 hbm_used=8589934592 hbm_total=17179869184
+chip=1 duty_cycle=42.0 hbm_used=8589934592 hbm_total=17179869184
+chip=2 duty_cycle=64.75 hbm_used=12884901888 hbm_total=17179869184
+chip=3 duty_cycle=64.75 hbm_used=4294967296 hbm_total=17179869184
+";
+        let metrics = parse_runtime_metrics(tpu_info);
+
+        assert_eq!(metrics.chips.len(), 4);
+        assert_eq!(metrics.chips[0].duty_cycle_percent, Some(87.5));
+        assert_eq!(metrics.chips[0].hbm_utilization_percent(), Some(50.0));
+        assert_eq!(metrics.chips[2].hbm_utilization_percent(), Some(75.0));
+        assert_eq!(metrics.mean_duty_cycle_percent(), Some(64.75));
+        assert_eq!(metrics.hbm_utilization_percent(), Some(50.0));
+
+        // Through the libtpu SDK, whose values are strings and whose lists may be short
+        let sdk = "\
+chip=0 duty_cycle=0.00 hbm_used=1048576 hbm_total=33550237696
+chip=1 duty_cycle=nan hbm_used=0 hbm_total=33550237696
+chip=2 duty_cycle=12.50
+";
+        let metrics = parse_runtime_metrics(sdk);
+        assert_eq!(metrics.chips.len(), 3);
+        assert_eq!(metrics.chips[0].duty_cycle_percent, Some(0.0));
+        assert_eq!(metrics.chips[1].duty_cycle_percent, None);
+        assert_eq!(metrics.chips[1].hbm_used_bytes, Some(0));
+        assert_eq!(metrics.chips[2].hbm_total_bytes, None);
+        assert_eq!(metrics.mean_duty_cycle_percent(), Some(6.25));
+    }
+
+    #[test]
+    fn test_hbm_from_runtime_metrics() {
+        let gib = 1024 * 1024 * 1024;
+        let metrics = RuntimeMetrics {
+            chips: vec![
+                ChipMetrics {
+                    chip: 0,
+                    hbm_used_bytes: Some(12 * gib),
+                    hbm_total_bytes: Some(16 * gib),
+                    ..Default::default()
+                },
+                ChipMetrics {
+                    chip: 1,
+                    hbm_used_bytes: Some(gib),
+                    ..Default::default()
+                },
+                ChipMetrics {
+                    chip: 2,
+                    duty_cycle_percent: Some(10.0),
+                    ..Default::default()
+                },
+            ],
+        };
+
+        let chips = hbm_from_runtime_metrics(&metrics, 32 * gib);
+        assert_eq!(chips.len(), 2);
+        assert_eq!(chips[0].free_bytes, 4 * gib);
+        assert_eq!(chips[0].used_percent(), 75.0);
+        assert_eq!(chips[1].total_bytes, 32 * gib);
+
+        let info = build_hbm_info(chips, 32 * gib, true);
+        assert_eq!(info.total_bytes, 48 * gib);
+        assert_eq!(info.available_bytes, 35 * gib);
+    }
+
+    #[test]
+    fn test_locked_inodes() {
+        let locks = "1: FLOCK  ADVISORY  WRITE 1234 08:01:131090 0 EOF\n\
+                     1: -> FLOCK  ADVISORY  WRITE 1300 08:01:131090 0 EOF\n\
+                     2: POSIX  ADVISORY  READ 99 00:1a:7 0 EOF\n\
+                     garbage\n";
+        assert_eq!(locked_inodes(locks), vec![131090, 131090, 7]);
+        assert!(locked_inodes("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_runtime_metrics_empty() {
+        assert!(parse_runtime_metrics("").chips.is_empty());
+        assert!(parse_runtime_metrics("chip=0\nchip=x duty_cycle=5\nduty_cycle=5\n").chips.is_empty());
+        assert!(parse_runtime_metrics("Traceback (most recent call last):\n").chips.is_empty());
+    }
+}
@@ -0,0 +1,405 @@
+//! gzip decompression (RFC 1952) over a DEFLATE decoder (RFC 1951).
+//!
+//! Used to decode `Content-Encoding: gzip` HTTP responses. Decoding is
+//! bounded by a caller-supplied output limit so a hostile or corrupted
+//! stream cannot exhaust memory.
+
+/// Maximum Huffman code length in DEFLATE
+const MAX_BITS: usize = 15;
+
+/// Base lengths for length symbols 257..285
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// Base distances for distance symbols 0..29
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+/// Order in which code length code lengths are stored in a dynamic block
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+/// Decompress a gzip stream, producing at most `max_output` bytes
+pub fn gunzip(data: &[u8], max_output: usize) -> Result<Vec<u8>, String> {
+    if data.len() < 18 || data[0] != 0x1f || data[1] != 0x8b {
+        return Err("not a gzip stream".to_string());
+    }
+    if data[2] != 8 {
+        return Err(format!("unsupported gzip compression method {}", data[2]));
+    }
+
+    let flags = data[3];
+    let mut pos = 10;
+
+    // FEXTRA
+    if flags & 0x04 != 0 {
+        let len = u16::from_le_bytes([byte_at(data, pos)?, byte_at(data, pos + 1)?]) as usize;
+        pos += 2 + len;
+    }
+    // FNAME, FCOMMENT: zero-terminated strings
+    for flag in [0x08, 0x10] {
+        if flags & flag != 0 {
+            while byte_at(data, pos)? != 0 {
+                pos += 1;
+            }
+            pos += 1;
+        }
+    }
+    // FHCRC
+    if flags & 0x02 != 0 {
+        pos += 2;
+    }
+    if pos > data.len() {
+        return Err("truncated gzip header".to_string());
+    }
+
+    let (output, used) = inflate_with_len(&data[pos..], max_output)?;
+
+    let trailer = &data[pos + used..];
+    if trailer.len() < 8 {
+        return Err("truncated gzip trailer".to_string());
+    }
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+    if size != output.len() as u32 || crc != crc32(&output) {
+        return Err("gzip checksum mismatch".to_string());
+    }
+
+    Ok(output)
+}
+
+/// Decompress a raw DEFLATE stream, producing at most `max_output` bytes
+pub fn inflate(data: &[u8], max_output: usize) -> Result<Vec<u8>, String> {
+    inflate_with_len(data, max_output).map(|(output, _)| output)
+}
+
+/// CRC-32 (IEEE 802.3), as used in the gzip trailer
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn byte_at(data: &[u8], pos: usize) -> Result<u8, String> {
+    data.get(pos).copied().ok_or_else(|| "truncated gzip header".to_string())
+}
+
+/// Inflate `data`, returning the output and the number of input bytes consumed
+fn inflate_with_len(data: &[u8], max_output: usize) -> Result<(Vec<u8>, usize), String> {
+    let mut input = BitReader::new(data);
+    let mut output = Vec::new();
+
+    loop {
+        let last = input.bits(1)? == 1;
+        match input.bits(2)? {
+            0 => stored_block(&mut input, &mut output, max_output)?,
+            1 => {
+                let (lengths, distances) = fixed_tables();
+                codes_block(&mut input, &mut output, &lengths, &distances, max_output)?
+            }
+            2 => {
+                let (lengths, distances) = dynamic_tables(&mut input)?;
+                codes_block(&mut input, &mut output, &lengths, &distances, max_output)?
+            }
+            _ => return Err("invalid deflate block type".to_string()),
+        }
+        if last {
+            break;
+        }
+    }
+
+    Ok((output, input.pos))
+}
+
+/// LSB-first bit reader over a byte slice
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit_buf: u32,
+    bit_count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        BitReader {
+            data,
+            pos: 0,
+            bit_buf: 0,
+            bit_count: 0,
+        }
+    }
+
+    fn bits(&mut self, need: u32) -> Result<u32, String> {
+        while self.bit_count < need {
+            let byte = *self.data.get(self.pos).ok_or("truncated deflate stream")?;
+            self.bit_buf |= (byte as u32) << self.bit_count;
+            self.pos += 1;
+            self.bit_count += 8;
+        }
+        let value = self.bit_buf & ((1u32 << need) - 1);
+        self.bit_buf >>= need;
+        self.bit_count -= need;
+        Ok(value)
+    }
+
+    /// Discard bits up to the next byte boundary
+    fn align(&mut self) {
+        self.bit_buf = 0;
+        self.bit_count = 0;
+    }
+}
+
+/// Canonical Huffman code: symbol counts per length and symbols in code order
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Build a code from per-symbol code lengths. Incomplete codes are
+    /// allowed (DEFLATE permits them for single-symbol distance codes);
+    /// over-subscribed ones are not.
+    fn new(lengths: &[u8]) -> Result<Self, String> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+
+        let mut left: i32 = 1;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err("over-subscribed Huffman code".to_string());
+            }
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for len in 1..MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+
+        Ok(Huffman { counts, symbols })
+    }
+
+    fn decode(&self, input: &mut BitReader) -> Result<u16, String> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+
+        for len in 1..=MAX_BITS {
+            code |= input.bits(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - count < first {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("invalid Huffman code".to_string())
+    }
+}
+
+fn stored_block(input: &mut BitReader, output: &mut Vec<u8>, max_output: usize) -> Result<(), String> {
+    input.align();
+    let header = input
+        .data
+        .get(input.pos..input.pos + 4)
+        .ok_or("truncated stored block")?;
+    let len = u16::from_le_bytes([header[0], header[1]]);
+    let nlen = u16::from_le_bytes([header[2], header[3]]);
+    if len != !nlen {
+        return Err("stored block length mismatch".to_string());
+    }
+    input.pos += 4;
+
+    let len = len as usize;
+    let bytes = input
+        .data
+        .get(input.pos..input.pos + len)
+        .ok_or("truncated stored block")?;
+    if output.len() + len > max_output {
+        return Err(output_limit_error(max_output));
+    }
+    output.extend_from_slice(bytes);
+    input.pos += len;
+    Ok(())
+}
+
+fn fixed_tables() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+
+    // The fixed tables are complete codes, so construction cannot fail
+    (
+        Huffman::new(&lengths).expect("fixed literal/length code"),
+        Huffman::new(&[5u8; 30]).expect("fixed distance code"),
+    )
+}
+
+fn dynamic_tables(input: &mut BitReader) -> Result<(Huffman, Huffman), String> {
+    let literal_count = input.bits(5)? as usize + 257;
+    let distance_count = input.bits(5)? as usize + 1;
+    let code_length_count = input.bits(4)? as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return Err("invalid dynamic block header".to_string());
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[index] = input.bits(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths)?;
+
+    let mut lengths = vec![0u8; literal_count + distance_count];
+    let mut i = 0;
+    while i < lengths.len() {
+        let symbol = code_length_code.decode(input)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *lengths[..i].last().ok_or("repeat with no previous length")?;
+                (previous, 3 + input.bits(2)? as usize)
+            }
+            17 => (0, 3 + input.bits(3)? as usize),
+            _ => (0, 11 + input.bits(7)? as usize),
+        };
+        if i + repeat > lengths.len() {
+            return Err("too many code lengths".to_string());
+        }
+        lengths[i..i + repeat].fill(value);
+        i += repeat;
+    }
+
+    if lengths[256] == 0 {
+        return Err("missing end-of-block code".to_string());
+    }
+
+    Ok((
+        Huffman::new(&lengths[..literal_count])?,
+        Huffman::new(&lengths[literal_count..])?,
+    ))
+}
+
+fn codes_block(
+    input: &mut BitReader,
+    output: &mut Vec<u8>,
+    lengths: &Huffman,
+    distances: &Huffman,
+    max_output: usize,
+) -> Result<(), String> {
+    loop {
+        let symbol = lengths.decode(input)? as usize;
+        match symbol {
+            0..=255 => {
+                if output.len() >= max_output {
+                    return Err(output_limit_error(max_output));
+                }
+                output.push(symbol as u8);
+            }
+            256 => return Ok(()),
+            257..=285 => {
+                let index = symbol - 257;
+                let length =
+                    LENGTH_BASE[index] as usize + input.bits(LENGTH_EXTRA[index] as u32)? as usize;
+
+                let dist_symbol = distances.decode(input)? as usize;
+                if dist_symbol >= DIST_BASE.len() {
+                    return Err("invalid distance symbol".to_string());
+                }
+                let distance = DIST_BASE[dist_symbol] as usize
+                    + input.bits(DIST_EXTRA[dist_symbol] as u32)? as usize;
+
+                if distance > output.len() {
+                    return Err("distance too far back".to_string());
+                }
+                if output.len() + length > max_output {
+                    return Err(output_limit_error(max_output));
+                }
+                // Copies may overlap their own output, so go byte by byte
+                let start = output.len() - distance;
+                for k in 0..length {
+                    output.push(output[start + k]);
+                }
+            }
+            _ => return Err("invalid literal/length symbol".to_string()),
+        }
+    }
+}
+
+fn output_limit_error(max_output: usize) -> String {
+    format!("decompressed data exceeds {} bytes", max_output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `gzip.compress(b"hello hello hello hello\n", mtime=0)`: a fixed-Huffman block
+    const HELLO_GZ: [u8; 29] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xcb, 0x48, 0xcd, 0xc9, 0xc9,
+        0x57, 0xc8, 0x40, 0x27, 0xb9, 0x00, 0x00, 0x88, 0x59, 0x0b, 0x18, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn test_gunzip_fixed_block() {
+        assert_eq!(gunzip(&HELLO_GZ, 1024).unwrap(), b"hello hello hello hello\n");
+    }
+
+    #[test]
+    fn test_gunzip_rejects_corruption_and_limit() {
+        let mut corrupt = HELLO_GZ;
+        corrupt[22] ^= 0xff; // CRC
+        assert!(gunzip(&corrupt, 1024).unwrap_err().contains("checksum"));
+        assert!(gunzip(&HELLO_GZ, 8).unwrap_err().contains("exceeds"));
+        assert!(gunzip(b"plain text, not gzip", 1024).is_err());
+    }
+
+    #[test]
+    fn test_inflate_stored_block() {
+        let data = [0x01, 0x07, 0x00, 0xf8, 0xff, b's', b't', b'o', b'r', b'e', b'd', b'!'];
+        assert_eq!(inflate(&data, 1024).unwrap(), b"stored!");
+    }
+
+    #[test]
+    fn test_inflate_dynamic_block() {
+        // zlib level 9, Z_RLE, of b"aaaaaaaabbbbaaaaaaaacc" * 6
+        let data = [
+            0x2d, 0xc1, 0x31, 0x01, 0x00, 0x00, 0x0c, 0x83, 0x30, 0xad, 0x14, 0xff, 0x1e, 0xb6,
+            0x83, 0x84, 0xec, 0x11, 0x25, 0x7b, 0x44, 0xc9, 0x1e, 0x51, 0xb2, 0x47, 0x94, 0xec,
+            0x11, 0x25, 0x7b, 0x44, 0x0f,
+        ];
+        assert_eq!(inflate(&data, 1024).unwrap(), b"aaaaaaaabbbbaaaaaaaacc".repeat(6));
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+}
@@ -0,0 +1,356 @@
+//! Minimal JSON parser (RFC 8259).
+//!
+//! Parses documents tpu-doc downloads or reads back, such as the remote
+//! compatibility matrix. Objects keep their keys in document order.
+//! Also holds the string escaping shared by the hand-written JSON reports.
+
+/// A parsed JSON value
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Member `key` of an object
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonValue::Null)
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, JsonValue)]> {
+        match self {
+            JsonValue::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// A scalar rendered as display text: strings unquoted, whole numbers
+    /// without a fraction. None for arrays and objects.
+    pub fn to_scalar_string(&self) -> Option<String> {
+        match self {
+            JsonValue::Null => Some("null".to_string()),
+            JsonValue::Bool(b) => Some(b.to_string()),
+            JsonValue::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => Some(format!("{}", *n as i64)),
+            JsonValue::Number(n) => Some(n.to_string()),
+            JsonValue::String(s) => Some(s.clone()),
+            JsonValue::Array(_) | JsonValue::Object(_) => None,
+        }
+    }
+}
+
+/// Maximum nesting depth, so hostile input cannot overflow the stack
+const MAX_DEPTH: usize = 64;
+
+/// Parse a complete JSON document
+pub fn parse(text: &str) -> Result<JsonValue, String> {
+    let mut parser = Parser {
+        bytes: text.as_bytes(),
+        pos: 0,
+    };
+    let value = parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.pos != parser.bytes.len() {
+        return Err(parser.error("trailing characters"));
+    }
+    Ok(value)
+}
+
+/// `s` escaped for use inside a JSON string literal
+pub fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// `s` as a quoted JSON string literal
+pub fn quote(s: &str) -> String {
+    format!("\"{}\"", escape(s))
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> String {
+        format!("{} at offset {}", message, self.pos)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.bytes.len() && matches!(self.bytes[self.pos], b' ' | b'\t' | b'\n' | b'\r') {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue, String> {
+        if self.bytes[self.pos..].starts_with(literal.as_bytes()) {
+            self.pos += literal.len();
+            Ok(value)
+        } else {
+            Err(self.error("invalid literal"))
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<JsonValue, String> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nesting too deep"));
+        }
+        self.skip_whitespace();
+        match self.bytes.get(self.pos) {
+            Some(b'{') => self.object(depth),
+            Some(b'[') => self.array(depth),
+            Some(b'"') => self.string().map(JsonValue::String),
+            Some(b't') => self.expect("true", JsonValue::Bool(true)),
+            Some(b'f') => self.expect("false", JsonValue::Bool(false)),
+            Some(b'n') => self.expect("null", JsonValue::Null),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<JsonValue, String> {
+        self.pos += 1;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) == Some(&b'}') {
+            self.pos += 1;
+            return Ok(JsonValue::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.bytes.get(self.pos) != Some(&b'"') {
+                return Err(self.error("expected object key"));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            if self.bytes.get(self.pos) != Some(&b':') {
+                return Err(self.error("expected ':'"));
+            }
+            self.pos += 1;
+            members.push((key, self.value(depth + 1)?));
+
+            self.skip_whitespace();
+            match self.bytes.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Object(members));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<JsonValue, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) == Some(&b']') {
+            self.pos += 1;
+            return Ok(JsonValue::Array(items));
+        }
+
+        loop {
+            items.push(self.value(depth + 1)?);
+            self.skip_whitespace();
+            match self.bytes.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Array(items));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let mut output = String::new();
+
+        loop {
+            let start = self.pos;
+            while self.pos < self.bytes.len() && !matches!(self.bytes[self.pos], b'"' | b'\\') {
+                if self.bytes[self.pos] < 0x20 {
+                    return Err(self.error("control character in string"));
+                }
+                self.pos += 1;
+            }
+            // Input is a &str and we only stop at ASCII bytes, so this is a char boundary
+            output.push_str(std::str::from_utf8(&self.bytes[start..self.pos]).map_err(|e| e.to_string())?);
+
+            match self.bytes.get(self.pos) {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(output);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let escape = *self.bytes.get(self.pos).ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 1;
+                    match escape {
+                        b'"' => output.push('"'),
+                        b'\\' => output.push('\\'),
+                        b'/' => output.push('/'),
+                        b'b' => output.push('\u{8}'),
+                        b'f' => output.push('\u{c}'),
+                        b'n' => output.push('\n'),
+                        b'r' => output.push('\r'),
+                        b't' => output.push('\t'),
+                        b'u' => output.push(self.unicode_escape()?),
+                        _ => return Err(self.error("invalid escape")),
+                    }
+                }
+                _ => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    /// The code point of a `\uXXXX` escape (the `\u` already consumed),
+    /// combining a UTF-16 surrogate pair when one follows
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !self.bytes[self.pos..].starts_with(b"\\u") {
+                return Err(self.error("unpaired surrogate"));
+            }
+            self.pos += 2;
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("unpaired surrogate"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid code point"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let digits = self
+            .bytes
+            .get(self.pos..self.pos + 4)
+            .and_then(|d| std::str::from_utf8(d).ok())
+            .and_then(|d| u32::from_str_radix(d, 16).ok())
+            .ok_or_else(|| self.error("invalid \\u escape"))?;
+        self.pos += 4;
+        Ok(digits)
+    }
+
+    fn number(&mut self) -> Result<JsonValue, String> {
+        let start = self.pos;
+        while self.pos < self.bytes.len()
+            && matches!(self.bytes[self.pos], b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+        {
+            self.pos += 1;
+        }
+        let text = std::str::from_utf8(&self.bytes[start..self.pos]).map_err(|e| e.to_string())?;
+        text.parse::<f64>()
+            .map(JsonValue::Number)
+            .map_err(|_| format!("invalid number '{}' at offset {}", text, start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_document() {
+        let value = parse(
+            r#" {"name": "v5e", "chips": 8, "ratio": -1.5e2, "ok": true,
+                "tags": ["a", null], "nested": {"x": false}} "#,
+        )
+        .unwrap();
+
+        assert_eq!(value.get("name").and_then(|v| v.as_str()), Some("v5e"));
+        assert_eq!(value.get("chips").and_then(|v| v.as_f64()), Some(8.0));
+        assert_eq!(value.get("ratio").and_then(|v| v.as_f64()), Some(-150.0));
+        assert_eq!(value.get("ok").and_then(|v| v.as_bool()), Some(true));
+        let tags = value.get("tags").and_then(|v| v.as_array()).unwrap();
+        assert_eq!(tags.len(), 2);
+        assert!(tags[1].is_null());
+        assert_eq!(value.get("nested").and_then(|v| v.get("x")), Some(&JsonValue::Bool(false)));
+        assert!(value.get("missing").is_none());
+        assert_eq!(value.get("nested").and_then(|v| v.as_object()).map(|m| m.len()), Some(1));
+        assert_eq!(value.get("chips").and_then(|v| v.to_scalar_string()), Some("8".to_string()));
+        assert_eq!(value.get("ratio").and_then(|v| v.to_scalar_string()), Some("-150".to_string()));
+        assert_eq!(parse("0.25").unwrap().to_scalar_string(), Some("0.25".to_string()));
+        assert!(value.get("tags").and_then(|v| v.to_scalar_string()).is_none());
+    }
+
+    #[test]
+    fn test_parse_string_escapes() {
+        let value = parse(r#""a\"b\\c\nd\u00e9\ud83d\ude00""#).unwrap();
+        assert_eq!(value.as_str(), Some("a\"b\\c\nd\u{e9}\u{1F600}"));
+        assert_eq!(parse("\"h\u{e9}llo\"").unwrap().as_str(), Some("h\u{e9}llo"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for bad in ["", "{", "[1,]", "{\"a\" 1}", "tru", "\"open", "1 2", "{\"a\":1,}", "\"\\ud800\""] {
+            assert!(parse(bad).is_err(), "{:?} should not parse", bad);
+        }
+        assert!(parse(&"[".repeat(100)).unwrap_err().contains("too deep"));
+    }
+
+    #[test]
+    fn test_escape_round_trips() {
+        let raw = "path \"C:\\tmp\"\nline\ttab\r\u{0}\u{1f} h\u{e9}";
+        assert_eq!(escape("a\nb\u{1}"), "a\\nb\\u0001");
+        assert_eq!(quote(raw), format!("\"{}\"", escape(raw)));
+        assert_eq!(parse(&quote(raw)).unwrap().as_str(), Some(raw));
+    }
+}
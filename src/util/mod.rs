@@ -0,0 +1,91 @@
+//! Shared utilities.
+//!
+//! Small, dependency-free helpers used across commands:
+//! - SHA-256 hashing for cache keys, and HMAC-SHA256 for signing reports
+//! - gzip decompression for HTTP responses
+//! - JSON parsing for downloaded data files
+//! - TOML parsing for configuration files
+//! - RFC 3339 timestamps
+//! - A small regular expression matcher for user-supplied patterns
+//! - Location of tpu-doc's per-user state directory
+//! - Edit distance for "did you mean" suggestions
+//! - Lookup of `TPU_PREFLIGHT_*` settings and their deprecated aliases, and
+//!   parsing of boolean settings
+
+pub mod gzip;
+pub mod hash;
+pub mod json;
+pub mod regex;
+pub mod time;
+pub mod toml;
+
+use std::env;
+use std::path::PathBuf;
+
+/// Per-user state directory (`~/.tpu-preflight`) for caches and history.
+///
+/// Returns None when HOME is not set.
+pub fn state_dir() -> Option<PathBuf> {
+    let home = env::var("HOME").ok().filter(|h| !h.is_empty())?;
+    Some(PathBuf::from(home).join(".tpu-preflight"))
+}
+
+/// State directory used before the rename (`~/.tpu-doc`). Entries missing
+/// from [`state_dir`] are still read from here; nothing new is written.
+pub fn legacy_state_dir() -> Option<PathBuf> {
+    let home = env::var("HOME").ok().filter(|h| !h.is_empty())?;
+    Some(PathBuf::from(home).join(".tpu-doc"))
+}
+
+/// Environment variables renamed to `TPU_PREFLIGHT_*`, as (name, deprecated
+/// name); the deprecated name is read when the new one is unset
+pub const DEPRECATED_ENV_VARS: [(&str, &str); 10] = [
+    ("TPU_PREFLIGHT_CONFIG", "TPU_DOC_CONFIG"),
+    ("TPU_PREFLIGHT_FORMAT", "TPU_DOC_FORMAT"),
+    ("TPU_PREFLIGHT_VERBOSE", "TPU_DOC_VERBOSE"),
+    ("TPU_PREFLIGHT_MATRIX_URL", "TPU_DOC_MATRIX_URL"),
+    ("TPU_PREFLIGHT_PLUGIN_DIR", "TPU_DOC_PLUGIN_DIR"),
+    ("TPU_PREFLIGHT_GCS_PREFIX", "TPU_DOC_GCS_PREFIX"),
+    ("TPU_PREFLIGHT_PIPELINE_RECORDS", "TPU_DOC_PIPELINE_RECORDS"),
+    ("TPU_PREFLIGHT_TRAINING_SMOKE", "TPU_DOC_TRAINING_SMOKE"),
+    ("TPU_PREFLIGHT_REGISTRY", "TPU_DOC_REGISTRY"),
+    ("TPU_PREFLIGHT_METRICS_ADDR", "TPU_METRICS_ADDR"),
+];
+
+/// The value of the setting `name` from the process environment (see
+/// [`env_setting_with`])
+pub fn env_setting(name: &str) -> Option<String> {
+    env_setting_with(name, &|var| env::var(var).ok())
+}
+
+/// The value of the setting `name` from `lookup`, falling back to its
+/// deprecated alias when `name` is unset
+pub fn env_setting_with(name: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Option<String> {
+    lookup(name).or_else(|| {
+        DEPRECATED_ENV_VARS
+            .iter()
+            .find(|(current, _)| *current == name)
+            .and_then(|(_, deprecated)| lookup(deprecated))
+    })
+}
+
+/// Parse a boolean setting such as `TPU_PREFLIGHT_PARALLEL`; empty, `0`,
+/// `false`, `no`, and `off` are false, anything else true
+pub fn parse_env_flag(value: &str) -> bool {
+    !matches!(value.trim().to_lowercase().as_str(), "" | "0" | "false" | "no" | "off")
+}
+
+/// Levenshtein distance between `a` and `b`, in characters
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
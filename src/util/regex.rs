@@ -0,0 +1,443 @@
+//! Small regular expression matcher.
+//!
+//! Supports the syntax users typically put in configuration files:
+//! literals, `.`, character classes (`[a-z]`, `[^0-9]`), the escapes
+//! `\d \w \s` (and their negations), groups, alternation, and the `*`, `+`,
+//! `?`, `{m}`, `{m,}`, `{m,n}` quantifiers, greedy or lazy (`*?`). `^` and
+//! `$` match at line boundaries, and a leading `(?i)` makes the pattern
+//! case-insensitive. Backreferences and lookaround are not supported.
+//!
+//! Matching is backtracking with a step budget, so a pathological pattern
+//! fails to match instead of hanging.
+
+use std::cell::Cell;
+
+/// Backtracking steps allowed per match attempt
+const MAX_STEPS: usize = 1_000_000;
+
+/// A compiled regular expression
+#[derive(Debug, Clone)]
+pub struct Regex {
+    pattern: String,
+    root: Node,
+    case_insensitive: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    Class { items: Vec<ClassItem>, negated: bool },
+    LineStart,
+    LineEnd,
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat { node: Box<Node>, min: usize, max: Option<usize>, greedy: bool },
+}
+
+#[derive(Debug, Clone)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+impl ClassItem {
+    fn matches(&self, c: char, case_insensitive: bool) -> bool {
+        match self {
+            ClassItem::Range(lo, hi) => {
+                (*lo..=*hi).contains(&c)
+                    || (case_insensitive
+                        && ((*lo..=*hi).contains(&c.to_ascii_lowercase())
+                            || (*lo..=*hi).contains(&c.to_ascii_uppercase())))
+            }
+            ClassItem::Digit(positive) => c.is_ascii_digit() == *positive,
+            ClassItem::Word(positive) => (c.is_alphanumeric() || c == '_') == *positive,
+            ClassItem::Space(positive) => c.is_whitespace() == *positive,
+        }
+    }
+}
+
+impl Regex {
+    /// Compile `pattern`, returning a description of the first syntax error
+    pub fn new(pattern: &str) -> Result<Regex, String> {
+        let (case_insensitive, body) = match pattern.strip_prefix("(?i)") {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+        let mut parser = Parser {
+            chars: body.chars().collect(),
+            pos: 0,
+            depth: 0,
+        };
+        let root = parser.alternation()?;
+        if parser.pos < parser.chars.len() {
+            return Err(format!("unmatched ')' at position {}", parser.pos));
+        }
+        Ok(Regex {
+            pattern: pattern.to_string(),
+            root,
+            case_insensitive,
+        })
+    }
+
+    /// The source pattern
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Whether the pattern matches anywhere in `text`
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+
+    /// Byte range of the leftmost match in `text`
+    pub fn find(&self, text: &str) -> Option<(usize, usize)> {
+        let indexed: Vec<(usize, char)> = text.char_indices().collect();
+        let chars: Vec<char> = indexed.iter().map(|(_, c)| *c).collect();
+        let byte_offset = |i: usize| indexed.get(i).map(|(b, _)| *b).unwrap_or(text.len());
+
+        let matcher = Matcher {
+            text: &chars,
+            case_insensitive: self.case_insensitive,
+            steps: Cell::new(0),
+        };
+        for start in 0..=chars.len() {
+            let mut end = None;
+            if matcher.node(&self.root, start, &mut |p| {
+                end = Some(p);
+                true
+            }) {
+                return end.map(|end| (byte_offset(start), byte_offset(end)));
+            }
+            if matcher.steps.get() > MAX_STEPS {
+                return None;
+            }
+        }
+        None
+    }
+}
+
+struct Matcher<'t> {
+    text: &'t [char],
+    case_insensitive: bool,
+    steps: Cell<usize>,
+}
+
+impl Matcher<'_> {
+    fn char_matches(&self, pattern: char, c: char) -> bool {
+        pattern == c || (self.case_insensitive && pattern.to_lowercase().eq(c.to_lowercase()))
+    }
+
+    /// Match `node` at `pos`, calling `k` with each candidate end position
+    /// until it accepts one
+    fn node(&self, node: &Node, pos: usize, k: &mut dyn FnMut(usize) -> bool) -> bool {
+        self.steps.set(self.steps.get() + 1);
+        if self.steps.get() > MAX_STEPS {
+            return false;
+        }
+
+        let current = self.text.get(pos).copied();
+        match node {
+            Node::Char(expected) => current.is_some_and(|c| self.char_matches(*expected, c)) && k(pos + 1),
+            Node::Any => current.is_some_and(|c| c != '\n') && k(pos + 1),
+            Node::Class { items, negated } => {
+                current.is_some_and(|c| items.iter().any(|i| i.matches(c, self.case_insensitive)) != *negated)
+                    && k(pos + 1)
+            }
+            Node::LineStart => (pos == 0 || self.text[pos - 1] == '\n') && k(pos),
+            Node::LineEnd => matches!(current, None | Some('\n')) && k(pos),
+            Node::Concat(nodes) => self.sequence(nodes, pos, k),
+            Node::Alt(alternatives) => alternatives.iter().any(|alt| self.node(alt, pos, k)),
+            Node::Repeat { node, min, max, greedy } => self.repeat(node, *min, *max, *greedy, 0, pos, k),
+        }
+    }
+
+    fn sequence(&self, nodes: &[Node], pos: usize, k: &mut dyn FnMut(usize) -> bool) -> bool {
+        match nodes.split_first() {
+            None => k(pos),
+            Some((first, rest)) => self.node(first, pos, &mut |p| self.sequence(rest, p, k)),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn repeat(
+        &self,
+        node: &Node,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+        count: usize,
+        pos: usize,
+        k: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        let can_stop = count >= min;
+        let can_continue = max.is_none_or(|max| count < max);
+
+        if !greedy && can_stop && k(pos) {
+            return true;
+        }
+        if can_continue {
+            let matched = self.node(node, pos, &mut |p| {
+                if p == pos {
+                    // An empty iteration cannot make progress; only use it to reach `min`
+                    count + 1 >= min && k(p)
+                } else {
+                    self.repeat(node, min, max, greedy, count + 1, p, k)
+                }
+            });
+            if matched {
+                return true;
+            }
+        }
+        greedy && can_stop && k(pos)
+    }
+}
+
+/// Maximum group nesting, so hostile patterns cannot overflow the stack
+const MAX_DEPTH: usize = 32;
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{} at position {}", message, self.pos)
+    }
+
+    fn alternation(&mut self) -> Result<Node, String> {
+        let mut alternatives = vec![self.concatenation()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            alternatives.push(self.concatenation()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.remove(0)
+        } else {
+            Node::Alt(alternatives)
+        })
+    }
+
+    fn concatenation(&mut self) -> Result<Node, String> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+        Ok(Node::Concat(nodes))
+    }
+
+    fn quantified(&mut self, atom: Node) -> Result<Node, String> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => match self.counted() {
+                Some(bounds) => bounds,
+                None => return Ok(atom),
+            },
+            _ => return Ok(atom),
+        };
+        self.pos += 1;
+        if matches!(atom, Node::LineStart | Node::LineEnd) {
+            return Err(self.error("nothing to repeat"));
+        }
+        if max.is_some_and(|max| max < min) {
+            return Err(self.error("invalid repetition bounds"));
+        }
+        let greedy = if self.peek() == Some('?') {
+            self.pos += 1;
+            false
+        } else {
+            true
+        };
+        if matches!(self.peek(), Some('*' | '+' | '?')) {
+            return Err(self.error("nested quantifier"));
+        }
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+            greedy,
+        })
+    }
+
+    /// Parse `{m}`, `{m,}`, or `{m,n}` at the current position, leaving the
+    /// position just before the closing brace. None if it is not a valid
+    /// count, in which case `{` is a literal.
+    fn counted(&mut self) -> Option<(usize, Option<usize>)> {
+        let close = self.chars[self.pos..].iter().position(|c| *c == '}')? + self.pos;
+        let inner: String = self.chars[self.pos + 1..close].iter().collect();
+        let bounds = match inner.split_once(',') {
+            None => {
+                let n = inner.parse().ok()?;
+                (n, Some(n))
+            }
+            Some((min, "")) => (min.parse().ok()?, None),
+            Some((min, max)) => (min.parse().ok()?, Some(max.parse().ok()?)),
+        };
+        self.pos = close;
+        Some(bounds)
+    }
+
+    fn atom(&mut self) -> Result<Node, String> {
+        let c = self.peek().ok_or_else(|| self.error("unexpected end of pattern"))?;
+        self.pos += 1;
+        match c {
+            '(' => {
+                self.depth += 1;
+                if self.depth > MAX_DEPTH {
+                    return Err(self.error("groups nested too deeply"));
+                }
+                if self.chars[self.pos..].starts_with(&['?', ':']) {
+                    self.pos += 2;
+                }
+                let inner = self.alternation()?;
+                if self.peek() != Some(')') {
+                    return Err(self.error("missing ')'"));
+                }
+                self.pos += 1;
+                self.depth -= 1;
+                Ok(inner)
+            }
+            '[' => self.class(),
+            '.' => Ok(Node::Any),
+            '^' => Ok(Node::LineStart),
+            '$' => Ok(Node::LineEnd),
+            '\\' => match self.escape()? {
+                ClassItem::Range(lo, _) => Ok(Node::Char(lo)),
+                item => Ok(Node::Class {
+                    items: vec![item],
+                    negated: false,
+                }),
+            },
+            '*' | '+' | '?' => Err(self.error("nothing to repeat")),
+            c => Ok(Node::Char(c)),
+        }
+    }
+
+    /// An escape sequence, with the backslash already consumed
+    fn escape(&mut self) -> Result<ClassItem, String> {
+        let c = self.peek().ok_or_else(|| self.error("trailing backslash"))?;
+        self.pos += 1;
+        Ok(match c {
+            'd' => ClassItem::Digit(true),
+            'D' => ClassItem::Digit(false),
+            'w' => ClassItem::Word(true),
+            'W' => ClassItem::Word(false),
+            's' => ClassItem::Space(true),
+            'S' => ClassItem::Space(false),
+            'n' => ClassItem::Range('\n', '\n'),
+            't' => ClassItem::Range('\t', '\t'),
+            'r' => ClassItem::Range('\r', '\r'),
+            c if c.is_ascii_alphanumeric() => return Err(self.error(&format!("unsupported escape '\\{}'", c))),
+            c => ClassItem::Range(c, c),
+        })
+    }
+
+    fn class(&mut self) -> Result<Node, String> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+
+        let mut items = Vec::new();
+        let mut first = true;
+        loop {
+            let c = self.peek().ok_or_else(|| self.error("missing ']'"))?;
+            self.pos += 1;
+            if c == ']' && !first {
+                break;
+            }
+            first = false;
+
+            let item = if c == '\\' { self.escape()? } else { ClassItem::Range(c, c) };
+            match item {
+                ClassItem::Range(lo, _) if self.peek() == Some('-') && self.chars.get(self.pos + 1) != Some(&']') => {
+                    self.pos += 1;
+                    let hi = match self.peek().ok_or_else(|| self.error("missing ']'"))? {
+                        '\\' => {
+                            self.pos += 1;
+                            match self.escape()? {
+                                ClassItem::Range(hi, _) => hi,
+                                _ => return Err(self.error("invalid class range")),
+                            }
+                        }
+                        hi => {
+                            self.pos += 1;
+                            hi
+                        }
+                    };
+                    if hi < lo {
+                        return Err(self.error("invalid class range"));
+                    }
+                    items.push(ClassItem::Range(lo, hi));
+                }
+                item => items.push(item),
+            }
+        }
+        Ok(Node::Class { items, negated })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, text: &str) -> bool {
+        Regex::new(pattern).unwrap().is_match(text)
+    }
+
+    #[test]
+    fn test_basic_matching() {
+        assert!(matches("error", "an error occurred"));
+        assert!(!matches("error", "all good"));
+        assert!(matches("^ERROR: .*quota", "ok\nERROR: out of quota\n"));
+        assert!(!matches("^quota", "ERROR: quota"));
+        assert!(matches("done$", "step 1\ndone\n"));
+        assert!(matches("v[0-9]+e?", "tpu v5e"));
+        assert!(matches("[^a-z]", "abc1"));
+        assert!(!matches("[^a-z]", "abc"));
+        assert!(matches(r"\d{3}-\d{2,}", "code 123-45"));
+        assert!(!matches(r"\d{3}-\d{2,}", "code 12-45"));
+        assert!(matches("(FAIL|ERROR)S?:", "FAILS: 2"));
+        assert!(matches("(?i)degraded", "Link DEGRADED"));
+        assert!(matches(r"a\.b", "a.b"));
+        assert!(!matches(r"a\.b", "axb"));
+        assert!(matches("a{x", "a{x"));
+    }
+
+    #[test]
+    fn test_find_offsets() {
+        let re = Regex::new("b+").unwrap();
+        assert_eq!(re.find("aabbbc"), Some((2, 5)));
+        let lazy = Regex::new("<.+?>").unwrap();
+        assert_eq!(lazy.find("<a><b>"), Some((0, 3)));
+        assert_eq!(Regex::new("é+").unwrap().find("caféé!"), Some((3, 7)));
+        assert_eq!(Regex::new("x*").unwrap().find("abc"), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        for bad in ["(", "a)", "[a-", "*a", r"\q", "a**", "[z-a]", "a{3,1}", "\\"] {
+            assert!(Regex::new(bad).is_err(), "{:?} should not compile", bad);
+        }
+    }
+
+    #[test]
+    fn test_pathological_pattern_terminates() {
+        let re = Regex::new("(a*)*b").unwrap();
+        assert!(!re.is_match(&"a".repeat(40)));
+    }
+}
@@ -0,0 +1,67 @@
+//! RFC 3339 timestamps.
+//!
+//! Every timestamp tpu-doc prints or writes is UTC with millisecond
+//! precision, e.g. `2026-10-16T09:00:00.250Z`, so reports, snapshots, and
+//! info dumps from different hosts sort and compare as plain strings.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Current time in milliseconds since the Unix epoch
+pub fn now_unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// The current time as an RFC 3339 timestamp
+pub fn now_rfc3339() -> String {
+    rfc3339(now_unix_ms())
+}
+
+/// `unix_ms` (milliseconds since the Unix epoch) as an RFC 3339 timestamp
+pub fn rfc3339(unix_ms: u64) -> String {
+    let secs = unix_ms / 1000;
+    let time_of_day = secs % 86400;
+    let (year, month, day) = civil_date(secs / 86400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        time_of_day / 3600,
+        time_of_day % 3600 / 60,
+        time_of_day % 60,
+        unix_ms % 1000
+    )
+}
+
+/// Days since 1970-01-01 to a (year, month, day) date (Howard Hinnant's
+/// algorithm)
+fn civil_date(days_since_epoch: u64) -> (u64, u64, u64) {
+    let days = days_since_epoch + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc3339() {
+        assert_eq!(rfc3339(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(rfc3339(1_733_500_000_000), "2024-12-06T15:46:40.000Z");
+        // Leap day, and the last millisecond of a leap year
+        assert_eq!(rfc3339(951_782_400_250), "2000-02-29T00:00:00.250Z");
+        assert_eq!(rfc3339(1_735_689_599_999), "2024-12-31T23:59:59.999Z");
+        assert_eq!(rfc3339(4_107_542_400_000), "2100-03-01T00:00:00.000Z");
+    }
+}
@@ -0,0 +1,487 @@
+//! Minimal TOML parser.
+//!
+//! Parses the subset of TOML used by tpu-doc configuration files: tables,
+//! arrays of tables, dotted keys, basic and literal strings, integers,
+//! floats, booleans, arrays, and inline tables. Dates and multi-line
+//! strings are not supported, and arrays and inline tables must fit on one
+//! line. Tables keep their keys in document order.
+
+/// A parsed TOML value
+#[derive(Debug, Clone, PartialEq)]
+pub enum TomlValue {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Array(Vec<TomlValue>),
+    Table(Vec<(String, TomlValue)>),
+}
+
+impl TomlValue {
+    /// Member `key` of a table
+    pub fn get(&self, key: &str) -> Option<&TomlValue> {
+        match self {
+            TomlValue::Table(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            TomlValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            TomlValue::Integer(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            TomlValue::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[TomlValue]> {
+        match self {
+            TomlValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_table(&self) -> Option<&[(String, TomlValue)]> {
+        match self {
+            TomlValue::Table(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// Name of the value's type, for error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            TomlValue::String(_) => "string",
+            TomlValue::Integer(_) => "integer",
+            TomlValue::Float(_) => "float",
+            TomlValue::Boolean(_) => "boolean",
+            TomlValue::Array(_) => "array",
+            TomlValue::Table(_) => "table",
+        }
+    }
+}
+
+/// Maximum nesting depth of arrays and inline tables
+const MAX_DEPTH: usize = 32;
+
+/// Parse a TOML document into its root table
+pub fn parse(text: &str) -> Result<TomlValue, String> {
+    let mut root = Vec::new();
+    // Path of the table that key/value lines are currently added to
+    let mut current: Vec<String> = Vec::new();
+
+    for (index, raw) in text.lines().enumerate() {
+        let line_no = index + 1;
+        let mut parser = Parser {
+            chars: raw.chars().collect(),
+            pos: 0,
+            line: line_no,
+        };
+        parser.skip_whitespace();
+        if parser.at_end_of_line() {
+            continue;
+        }
+
+        if parser.peek() == Some('[') {
+            let array = parser.peek_at(1) == Some('[');
+            parser.pos += if array { 2 } else { 1 };
+            let path = parser.key_path()?;
+            parser.skip_whitespace();
+            let close = if array { "]]" } else { "]" };
+            for c in close.chars() {
+                if parser.peek() != Some(c) {
+                    return Err(parser.error(&format!("expected '{}'", close)));
+                }
+                parser.pos += 1;
+            }
+            parser.finish_line()?;
+
+            if array {
+                let (last, parents) = path.split_last().ok_or_else(|| parser.error("empty table name"))?;
+                let parent = table_at(&mut root, parents, line_no)?;
+                match find_or_insert(parent, last, || TomlValue::Array(Vec::new())) {
+                    TomlValue::Array(items) => items.push(TomlValue::Table(Vec::new())),
+                    _ => return Err(format!("line {}: '{}' is not an array of tables", line_no, path.join("."))),
+                }
+            } else {
+                table_at(&mut root, &path, line_no)?;
+            }
+            current = path;
+            continue;
+        }
+
+        let path = parser.key_path()?;
+        parser.skip_whitespace();
+        if parser.peek() != Some('=') {
+            return Err(parser.error("expected '='"));
+        }
+        parser.pos += 1;
+        let value = parser.value(0)?;
+        parser.finish_line()?;
+
+        insert(table_at(&mut root, &current, line_no)?, &path, value, line_no)?;
+    }
+
+    Ok(TomlValue::Table(root))
+}
+
+/// Members of the table at `path` below `table`, creating missing tables.
+/// A path through an array of tables descends into its last element.
+fn table_at<'a>(
+    table: &'a mut Vec<(String, TomlValue)>,
+    path: &[String],
+    line: usize,
+) -> Result<&'a mut Vec<(String, TomlValue)>, String> {
+    let mut members = table;
+    for key in path {
+        members = match find_or_insert(members, key, || TomlValue::Table(Vec::new())) {
+            TomlValue::Table(child) => child,
+            TomlValue::Array(items) => match items.last_mut() {
+                Some(TomlValue::Table(child)) => child,
+                _ => return Err(format!("line {}: '{}' is not a table", line, key)),
+            },
+            _ => return Err(format!("line {}: '{}' is not a table", line, key)),
+        };
+    }
+    Ok(members)
+}
+
+fn find_or_insert<'a>(
+    members: &'a mut Vec<(String, TomlValue)>,
+    key: &str,
+    default: impl FnOnce() -> TomlValue,
+) -> &'a mut TomlValue {
+    let index = match members.iter().position(|(k, _)| k == key) {
+        Some(index) => index,
+        None => {
+            members.push((key.to_string(), default()));
+            members.len() - 1
+        }
+    };
+    &mut members[index].1
+}
+
+/// Add `path = value` to `members`, rejecting duplicate keys
+fn insert(members: &mut Vec<(String, TomlValue)>, path: &[String], value: TomlValue, line: usize) -> Result<(), String> {
+    let (last, parents) = path.split_last().ok_or_else(|| format!("line {}: empty key", line))?;
+    let members = table_at(members, parents, line)?;
+    if members.iter().any(|(k, _)| k == last) {
+        return Err(format!("line {}: duplicate key '{}'", line, path.join(".")));
+    }
+    members.push((last.clone(), value));
+    Ok(())
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> String {
+        format!("line {}, column {}: {}", self.line, self.pos + 1, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn at_end_of_line(&self) -> bool {
+        matches!(self.peek(), None | Some('#') | Some('\r'))
+    }
+
+    /// Only whitespace or a comment may follow a complete line
+    fn finish_line(&mut self) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.at_end_of_line() {
+            Ok(())
+        } else {
+            Err(self.error("unexpected characters after value"))
+        }
+    }
+
+    /// A dotted key: `a`, `a.b`, `"quoted key".c`
+    fn key_path(&mut self) -> Result<Vec<String>, String> {
+        let mut path = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let key = match self.peek() {
+                Some('"') => self.basic_string()?,
+                Some('\'') => self.literal_string()?,
+                _ => {
+                    let start = self.pos;
+                    while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                        self.pos += 1;
+                    }
+                    if self.pos == start {
+                        return Err(self.error("expected a key"));
+                    }
+                    self.chars[start..self.pos].iter().collect()
+                }
+            };
+            path.push(key);
+            self.skip_whitespace();
+            if self.peek() == Some('.') {
+                self.pos += 1;
+            } else {
+                return Ok(path);
+            }
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<TomlValue, String> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nesting too deep"));
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"') => self.basic_string().map(TomlValue::String),
+            Some('\'') => self.literal_string().map(TomlValue::String),
+            Some('[') => self.array(depth),
+            Some('{') => self.inline_table(depth),
+            Some('t') => self.literal("true", TomlValue::Boolean(true)),
+            Some('f') => self.literal("false", TomlValue::Boolean(false)),
+            Some(c) if c.is_ascii_digit() || c == '+' || c == '-' => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("expected a value")),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: TomlValue) -> Result<TomlValue, String> {
+        let end = self.pos + word.chars().count();
+        if self.chars.get(self.pos..end).is_some_and(|s| s.iter().copied().eq(word.chars())) {
+            self.pos = end;
+            Ok(value)
+        } else {
+            Err(self.error("invalid value"))
+        }
+    }
+
+    fn basic_string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let mut output = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(output);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    let escape = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 1;
+                    match escape {
+                        '"' => output.push('"'),
+                        '\\' => output.push('\\'),
+                        'b' => output.push('\u{8}'),
+                        'f' => output.push('\u{c}'),
+                        'n' => output.push('\n'),
+                        'r' => output.push('\r'),
+                        't' => output.push('\t'),
+                        'u' => output.push(self.unicode_escape(4)?),
+                        'U' => output.push(self.unicode_escape(8)?),
+                        _ => return Err(self.error("invalid escape")),
+                    }
+                }
+                Some(c) => {
+                    output.push(c);
+                    self.pos += 1;
+                }
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self, digits: usize) -> Result<char, String> {
+        let hex: String = self
+            .chars
+            .get(self.pos..self.pos + digits)
+            .ok_or_else(|| self.error("invalid unicode escape"))?
+            .iter()
+            .collect();
+        self.pos += digits;
+        u32::from_str_radix(&hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn literal_string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c == '\'' {
+                let s = self.chars[start..self.pos].iter().collect();
+                self.pos += 1;
+                return Ok(s);
+            }
+            self.pos += 1;
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn array(&mut self, depth: usize) -> Result<TomlValue, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(TomlValue::Array(items));
+            }
+            items.push(self.value(depth + 1)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn inline_table(&mut self, depth: usize) -> Result<TomlValue, String> {
+        self.pos += 1;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(TomlValue::Table(members));
+        }
+        loop {
+            let path = self.key_path()?;
+            self.skip_whitespace();
+            if self.peek() != Some('=') {
+                return Err(self.error("expected '='"));
+            }
+            self.pos += 1;
+            let value = self.value(depth + 1)?;
+
+            insert(&mut members, &path, value, self.line)?;
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(TomlValue::Table(members));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<TomlValue, String> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.' | '_')) {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().filter(|c| **c != '_').collect();
+        if let Ok(n) = text.parse::<i64>() {
+            return Ok(TomlValue::Integer(n));
+        }
+        match text.parse::<f64>() {
+            Ok(f) if text.contains(['.', 'e', 'E']) && !text.contains("inf") && !text.contains("nan") => {
+                Ok(TomlValue::Float(f))
+            }
+            _ => Err(format!("line {}: invalid number '{}'", self.line, text)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_document() {
+        let doc = parse(
+            r#"
+# comment
+title = "tpu-doc"   # trailing comment
+
+[checks]
+skip = ["SEC-001", 'SEC-007']
+only = []
+
+[timeouts]
+global_ms = 30_000
+ratio = 0.5
+enabled = true
+
+[[checks.custom]]
+id = "ORG-001"
+pass_exit_codes = [0, 2]
+
+[[checks.custom]]
+id = "ORG-002"
+env = { name = "x", "quoted key" = -3 }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(doc.get("title").and_then(|v| v.as_str()), Some("tpu-doc"));
+        let checks = doc.get("checks").unwrap();
+        assert_eq!(checks.get("skip").and_then(|v| v.as_array()).map(|a| a.len()), Some(2));
+        assert_eq!(doc.get("timeouts").and_then(|t| t.get("global_ms")), Some(&TomlValue::Integer(30000)));
+        assert_eq!(doc.get("timeouts").and_then(|t| t.get("ratio")), Some(&TomlValue::Float(0.5)));
+        assert_eq!(doc.get("timeouts").and_then(|t| t.get("enabled")).and_then(|v| v.as_bool()), Some(true));
+
+        let custom = checks.get("custom").and_then(|v| v.as_array()).unwrap();
+        assert_eq!(custom.len(), 2);
+        assert_eq!(custom[0].get("id").and_then(|v| v.as_str()), Some("ORG-001"));
+        assert_eq!(
+            custom[1].get("env").and_then(|e| e.get("quoted key")).and_then(|v| v.as_integer()),
+            Some(-3)
+        );
+    }
+
+    #[test]
+    fn test_dotted_keys_and_escapes() {
+        let doc = parse("a.b = \"x\\ty\\u00e9\"\n[policy]\n\"SEC-006\" = 'warn'\n").unwrap();
+        assert_eq!(doc.get("a").and_then(|a| a.get("b")).and_then(|v| v.as_str()), Some("x\ty\u{e9}"));
+        assert_eq!(doc.get("policy").and_then(|p| p.get("SEC-006")).and_then(|v| v.as_str()), Some("warn"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for bad in [
+            "a = ",
+            "a = \"open",
+            "a = 1\na = 2",
+            "[t\n",
+            "a = [1, 2",
+            "a = 1 2",
+            "= 1",
+            "a = nope",
+            "a = 1\n[a]",
+            "a = 1\n[[a]]",
+        ] {
+            assert!(parse(bad).is_err(), "{:?} should not parse", bad);
+        }
+        assert!(parse("a = 1\n\n[t\n").unwrap_err().starts_with("line 3"));
+    }
+}
@@ -1,9 +1,26 @@
 //! Version and build information.
 //!
-//! Provides version, git commit, and build metadata.
+//! Provides version, git commit, and build metadata, plus what a bug report
+//! needs to reproduce a build: the cargo features compiled in and the
+//! version of the embedded compatibility matrix. The commit, build date,
+//! and rustc version are recorded by the build script and are absent when
+//! it could not determine them.
 
+use crate::data::compatibility::CompatibilityMatrix;
+use crate::util::json;
 use std::fmt;
 
+/// Optional cargo features, reported when compiled in
+const OPTIONAL_FEATURES: [(&str, bool); 7] = [
+    ("json", cfg!(feature = "json")),
+    ("junit", cfg!(feature = "junit")),
+    ("ai", cfg!(feature = "ai")),
+    ("wasm-plugins", cfg!(feature = "wasm-plugins")),
+    ("async", cfg!(feature = "async")),
+    ("tui", cfg!(feature = "tui")),
+    ("ed25519", cfg!(feature = "ed25519")),
+];
+
 /// Build information
 #[derive(Debug, Clone)]
 pub struct BuildInfo {
@@ -12,6 +29,33 @@ pub struct BuildInfo {
     pub build_date: Option<&'static str>,
     pub target: &'static str,
     pub rustc_version: Option<&'static str>,
+    /// Cargo features compiled into this binary
+    pub features: Vec<&'static str>,
+    /// Version of the embedded compatibility matrix
+    pub matrix_version: String,
+    /// Date the embedded compatibility matrix was last updated
+    pub matrix_updated: String,
+}
+
+impl BuildInfo {
+    /// The build information as a JSON object; unknown values are null
+    pub fn to_json(&self) -> String {
+        let optional = |value: Option<&str>| value.map(json::quote).unwrap_or_else(|| "null".to_string());
+        let features: Vec<String> = self.features.iter().map(|f| json::quote(f)).collect();
+
+        let mut json = String::from("{\n");
+        json.push_str(&format!("  \"version\": {},\n", json::quote(self.version)));
+        json.push_str(&format!("  \"commit\": {},\n", optional(self.commit)));
+        json.push_str(&format!("  \"build_date\": {},\n", optional(self.build_date)));
+        json.push_str(&format!("  \"target\": {},\n", json::quote(self.target)));
+        json.push_str(&format!("  \"rustc_version\": {},\n", optional(self.rustc_version)));
+        json.push_str(&format!("  \"features\": [{}],\n", features.join(", ")));
+        json.push_str("  \"compatibility_matrix\": {\n");
+        json.push_str(&format!("    \"version\": {},\n", json::quote(&self.matrix_version)));
+        json.push_str(&format!("    \"updated\": {}\n", json::quote(&self.matrix_updated)));
+        json.push_str("  }\n}");
+        json
+    }
 }
 
 impl fmt::Display for BuildInfo {
@@ -29,20 +73,65 @@ impl fmt::Display for BuildInfo {
         writeln!(f, "Target: {}", self.target)?;
 
         if let Some(rustc) = self.rustc_version {
-            write!(f, "Rustc: {}", rustc)?;
+            writeln!(f, "Rustc: {}", rustc)?;
         }
 
-        Ok(())
+        let features = if self.features.is_empty() {
+            "none".to_string()
+        } else {
+            self.features.join(", ")
+        };
+        writeln!(f, "Features: {}", features)?;
+        write!(
+            f,
+            "Compatibility matrix: {} (updated {})",
+            self.matrix_version, self.matrix_updated
+        )
     }
 }
 
 /// Get build information
 pub fn get_build_info() -> BuildInfo {
+    let matrix = CompatibilityMatrix::embedded();
     BuildInfo {
         version: env!("CARGO_PKG_VERSION"),
         commit: option_env!("TPU_DOC_GIT_HASH"),
         build_date: option_env!("TPU_DOC_BUILD_DATE"),
         target: std::env::consts::ARCH,
         rustc_version: option_env!("TPU_DOC_RUSTC_VERSION"),
+        features: OPTIONAL_FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect(),
+        matrix_version: matrix.version,
+        matrix_updated: matrix.updated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::json::{self, JsonValue};
+
+    #[test]
+    fn test_build_info() {
+        let info = get_build_info();
+        assert_eq!(info.features.contains(&"json"), cfg!(feature = "json"));
+        assert_eq!(info.features.contains(&"ai"), cfg!(feature = "ai"));
+
+        let text = info.to_string();
+        assert!(text.starts_with(&format!("tpu-doc {}\n", env!("CARGO_PKG_VERSION"))));
+        assert!(text.contains(&format!("\nCompatibility matrix: {} (updated ", info.matrix_version)));
+
+        let parsed = json::parse(&info.to_json()).unwrap();
+        assert_eq!(parsed.get("version").and_then(JsonValue::as_str), Some(info.version));
+        assert_eq!(parsed.get("commit").and_then(JsonValue::as_str), info.commit);
+        assert_eq!(
+            parsed.get("features").and_then(JsonValue::as_array).map(|features| features.len()),
+            Some(info.features.len())
+        );
+        let matrix = parsed.get("compatibility_matrix").unwrap();
+        assert_eq!(matrix.get("updated").and_then(JsonValue::as_str), Some(info.matrix_updated.as_str()));
     }
 }
@@ -2,13 +2,14 @@
 //!
 //! Tests for argument parsing and command handling.
 
-use tpu_doc::cli::args::{Args, CategoryFilter, Command, OutputFormat};
+use tpu_doc::cli::args::{Args, CategoryFilter, Command, GroupBy, OutputFormat, Theme};
+use tpu_doc::{CheckTag, TpuDocConfig};
 
 #[test]
 fn test_default_args() {
     let args = Args::parse_from(&[]).unwrap();
     assert_eq!(args.command, Command::Check);
-    assert_eq!(args.category, CategoryFilter::All);
+    assert!(args.categories.is_empty());
     assert_eq!(args.format, OutputFormat::Text);
     assert!(!args.quiet);
     assert!(!args.verbose);
@@ -38,31 +39,31 @@ fn test_check_command() {
 #[test]
 fn test_hardware_category() {
     let args = Args::parse_from(&["--hardware".to_string()]).unwrap();
-    assert_eq!(args.category, CategoryFilter::Hardware);
+    assert_eq!(args.categories, vec![CategoryFilter::Hardware]);
 }
 
 #[test]
 fn test_stack_category() {
     let args = Args::parse_from(&["--stack".to_string()]).unwrap();
-    assert_eq!(args.category, CategoryFilter::Stack);
+    assert_eq!(args.categories, vec![CategoryFilter::Stack]);
 }
 
 #[test]
 fn test_performance_category() {
     let args = Args::parse_from(&["--performance".to_string()]).unwrap();
-    assert_eq!(args.category, CategoryFilter::Performance);
+    assert_eq!(args.categories, vec![CategoryFilter::Performance]);
 }
 
 #[test]
 fn test_io_category() {
     let args = Args::parse_from(&["--io".to_string()]).unwrap();
-    assert_eq!(args.category, CategoryFilter::Io);
+    assert_eq!(args.categories, vec![CategoryFilter::Io]);
 }
 
 #[test]
 fn test_security_category() {
     let args = Args::parse_from(&["--security".to_string()]).unwrap();
-    assert_eq!(args.category, CategoryFilter::Security);
+    assert_eq!(args.categories, vec![CategoryFilter::Security]);
 }
 
 #[test]
@@ -101,6 +102,63 @@ fn test_no_color_flag() {
     assert!(args.no_color);
 }
 
+#[test]
+fn test_timeline_flag() {
+    let args = Args::parse_from(&["--timeline".to_string()]).unwrap();
+    assert!(args.timeline);
+    assert!(!Args::parse_from(&[]).unwrap().timeline);
+}
+
+#[test]
+fn test_group_by_flag() {
+    assert_eq!(Args::parse_from(&[]).unwrap().group_by, GroupBy::Category);
+    let args = Args::parse_from(&["--group-by".to_string(), "Status".to_string()]).unwrap();
+    assert_eq!(args.group_by, GroupBy::Status);
+    assert!(Args::parse_from(&["--group-by".to_string(), "severity".to_string()]).is_err());
+    assert!(Args::parse_from(&["--group-by".to_string()]).is_err());
+    let args = Args::parse_from(&["--group-by=status".to_string()]).unwrap();
+    assert_eq!(args.group_by, GroupBy::Status);
+    assert!(Args::parse_from(&["--group-by=severity".to_string()]).is_err());
+}
+
+#[test]
+fn test_theme_glyphs_and_ascii_flags() {
+    let args = Args::parse_from(&[
+        "--theme".to_string(),
+        "high-contrast".to_string(),
+        "--glyphs".to_string(),
+        "OK,WARN,FAIL,SKIP".to_string(),
+        "--ascii".to_string(),
+    ])
+    .unwrap();
+    assert_eq!(args.theme, Theme::HighContrast);
+    assert_eq!(args.glyphs.unwrap().pass, "OK");
+    assert!(args.ascii);
+
+    let defaults = Args::parse_from(&[]).unwrap();
+    assert_eq!(defaults.theme, Theme::Default);
+    assert!(defaults.glyphs.is_none());
+    assert!(!defaults.ascii);
+
+    assert!(Args::parse_from(&["--theme".to_string(), "solarized".to_string()]).is_err());
+    assert!(Args::parse_from(&["--glyphs".to_string(), "OK,FAIL".to_string()]).is_err());
+
+    let args =
+        Args::parse_from(&["--theme=high-contrast".to_string(), "--glyphs=OK,WARN,FAIL,SKIP".to_string()]).unwrap();
+    assert_eq!(args.theme, Theme::HighContrast);
+    assert_eq!(args.glyphs.unwrap().skip, "SKIP");
+    assert!(Args::parse_from(&["--theme=solarized".to_string()]).is_err());
+    assert!(Args::parse_from(&["--glyphs=OK,FAIL".to_string()]).is_err());
+}
+
+#[test]
+fn test_summary_only_flag() {
+    let args = Args::parse_from(&["--summary-only".to_string(), "--format".to_string(), "json".to_string()]).unwrap();
+    assert!(args.summary_only);
+    assert!(!Args::parse_from(&[]).unwrap().summary_only);
+    assert!(Args::parse_from(&["--summary-only".to_string(), "--format".to_string(), "junit".to_string()]).is_err());
+}
+
 #[test]
 fn test_parallel_flag() {
     let args = Args::parse_from(&["--parallel".to_string()]).unwrap();
@@ -125,6 +183,75 @@ fn test_timeout_equals_syntax() {
     assert_eq!(args.timeout_ms, 45000);
 }
 
+#[test]
+fn test_max_duration_option() {
+    let args = Args::parse_from(&["--max-duration".to_string(), "2m".to_string()]).unwrap();
+    assert_eq!(args.max_duration_secs, Some(120));
+    assert_eq!(TpuDocConfig::from_args(&args).max_duration_ms, Some(120_000));
+
+    let args = Args::parse_from(&["--max-duration=90".to_string()]).unwrap();
+    assert_eq!(args.max_duration_secs, Some(90));
+    assert_eq!(Args::parse_from(&[]).unwrap().max_duration_secs, None);
+
+    assert!(Args::parse_from(&["--max-duration".to_string()]).unwrap_err().contains("requires"));
+    assert!(Args::parse_from(&["--max-duration=0s".to_string()]).is_err());
+    assert!(Args::parse_from(&["--max-duration=soon".to_string()]).is_err());
+}
+
+#[test]
+fn test_cached_option() {
+    let args = Args::parse_from(&["--cached".to_string(), "1h".to_string()]).unwrap();
+    assert_eq!(args.cached_ttl_secs, Some(3600));
+    assert_eq!(TpuDocConfig::from_args(&args).cache_ttl_secs, Some(3600));
+    assert_eq!(Args::parse_from(&["--cached=30m".to_string()]).unwrap().cached_ttl_secs, Some(1800));
+    assert_eq!(TpuDocConfig::from_args(&Args::parse_from(&[]).unwrap()).cache_ttl_secs, None);
+    assert!(Args::parse_from(&["--cached".to_string()]).unwrap_err().contains("requires"));
+}
+
+#[test]
+fn test_fix_option() {
+    assert!(Args::parse_from(&["check".to_string(), "--fix".to_string()]).unwrap().fix);
+    assert!(!Args::parse_from(&[]).unwrap().fix);
+    let err = Args::parse_from(&["--fix".to_string(), "--format".to_string(), "json".to_string()]).unwrap_err();
+    assert!(err.contains("requires text output"));
+}
+
+#[test]
+fn test_interactive_check_option() {
+    let args = Args::parse_from(&["check".to_string(), "--interactive".to_string()]).unwrap();
+    assert_eq!(args.command, Command::Check);
+    assert!(args.interactive);
+}
+
+#[test]
+fn test_tui_command() {
+    let args = Args::parse_from(&["tui".to_string(), "--parallel".to_string(), "--no-tty".to_string()]).unwrap();
+    assert_eq!(args.command, Command::Tui);
+    assert!(args.parallel && args.no_tty);
+}
+
+#[test]
+fn test_verify_command() {
+    let args = Args::parse_from(&["verify", "report.json", "--format=json"].map(String::from)).unwrap();
+    assert_eq!(args.command, Command::Verify);
+    assert_eq!(args.report_file.as_deref(), Some("report.json"));
+    let args = Args::parse_from(&["check", "--format", "json", "--sign"].map(String::from)).unwrap();
+    assert!(args.sign);
+}
+
+#[test]
+fn test_category_and_tag_options() {
+    let args = Args::parse_from(&["check".to_string(), "--category".to_string(), "io".to_string()]).unwrap();
+    assert_eq!(args.categories, vec![CategoryFilter::Io]);
+    assert_eq!(Args::parse_from(&["--category=config".to_string()]).unwrap().categories, vec![CategoryFilter::Config]);
+    assert!(Args::parse_from(&["--category".to_string(), "gpu".to_string()]).unwrap_err().contains("Unknown category"));
+
+    let args = Args::parse_from(&["list".to_string(), "--tag".to_string(), "network".to_string(), "--tag=cacheable".to_string()]).unwrap();
+    assert_eq!(args.tags, vec![CheckTag::NetworkBound, CheckTag::Cacheable]);
+    assert!(Args::parse_from(&["list".to_string(), "--tag=gpu".to_string()]).unwrap_err().contains("Unknown tag"));
+    assert!(Args::parse_from(&["check".to_string(), "--tag=cpu".to_string()]).unwrap_err().contains("list command"));
+}
+
 #[test]
 fn test_skip_option() {
     let args = Args::parse_from(&[
@@ -200,7 +327,7 @@ fn test_combined_flags() {
     .unwrap();
 
     assert_eq!(args.command, Command::Check);
-    assert_eq!(args.category, CategoryFilter::Hardware);
+    assert_eq!(args.categories, vec![CategoryFilter::Hardware]);
     assert_eq!(args.format, OutputFormat::Json);
     assert!(args.verbose);
     assert!(args.parallel);
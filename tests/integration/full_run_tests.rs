@@ -3,9 +3,15 @@
 //! Tests for complete validation runs, including orchestration,
 //! fail-fast behavior, and result aggregation.
 
+use tpu_doc::cli::config::ConfigFile;
 use tpu_doc::engine::orchestrator::{CheckOrchestrator, OrchestratorConfig, RegisteredCheck};
+use tpu_doc::engine::policy::is_downgraded;
 use tpu_doc::engine::result::{ResultAggregator, ValidationReport};
-use tpu_doc::{Check, CheckCategory, CheckResult, TpuDocConfig};
+use tpu_doc::{
+    run_checks_with, Check, CheckCategory, CheckResult, CheckSeverity, CheckTag, TpuDocConfig, TpuDocError,
+    EXIT_RUNTIME_ERROR, EXIT_USAGE_ERROR,
+};
+use std::path::Path;
 
 // Helper to create a check that always passes
 fn create_passing_check(id: &str, name: &str, category: CheckCategory) -> RegisteredCheck {
@@ -18,9 +24,13 @@ fn create_passing_check(id: &str, name: &str, category: CheckCategory) -> Regist
         check_fn: Box::new(move || CheckResult::Pass {
             message: format!("{} passed", id_clone),
             duration_ms: 10,
+            data: Vec::new(),
         }),
         dependencies: vec![],
         estimated_duration_ms: 100,
+        tags: vec![],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     }
 }
 
@@ -36,9 +46,13 @@ fn create_failing_check(id: &str, name: &str, category: CheckCategory) -> Regist
             message: format!("{} failed", id_clone),
             details: "Test failure".to_string(),
             duration_ms: 10,
+            data: Vec::new(),
         }),
         dependencies: vec![],
         estimated_duration_ms: 100,
+        tags: vec![],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     }
 }
 
@@ -54,9 +68,13 @@ fn create_warning_check(id: &str, name: &str, category: CheckCategory) -> Regist
             message: format!("{} warning", id_clone),
             details: "Test warning".to_string(),
             duration_ms: 10,
+            data: Vec::new(),
         }),
         dependencies: vec![],
         estimated_duration_ms: 100,
+        tags: vec![],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     }
 }
 
@@ -73,6 +91,9 @@ fn create_skipping_check(id: &str, name: &str, category: CheckCategory) -> Regis
         }),
         dependencies: vec![],
         estimated_duration_ms: 100,
+        tags: vec![],
+        severity: CheckSeverity::Major,
+        ownership: Default::default(),
     }
 }
 
@@ -83,9 +104,9 @@ fn test_orchestrator_run_all_checks() {
     let config = OrchestratorConfig::default();
     let mut orchestrator = CheckOrchestrator::new(config);
 
-    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware));
-    orchestrator.register_check(create_passing_check("TEST-002", "Test 2", CheckCategory::Stack));
-    orchestrator.register_check(create_passing_check("TEST-003", "Test 3", CheckCategory::Io));
+    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_passing_check("TEST-002", "Test 2", CheckCategory::Stack)).unwrap();
+    orchestrator.register_check(create_passing_check("TEST-003", "Test 3", CheckCategory::Io)).unwrap();
 
     let report = orchestrator.run_all();
     let summary = report.summary();
@@ -100,9 +121,9 @@ fn test_orchestrator_run_category() {
     let config = OrchestratorConfig::default();
     let mut orchestrator = CheckOrchestrator::new(config);
 
-    orchestrator.register_check(create_passing_check("HW-001", "Hardware 1", CheckCategory::Hardware));
-    orchestrator.register_check(create_passing_check("HW-002", "Hardware 2", CheckCategory::Hardware));
-    orchestrator.register_check(create_passing_check("STK-001", "Stack 1", CheckCategory::Stack));
+    orchestrator.register_check(create_passing_check("HW-001", "Hardware 1", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_passing_check("HW-002", "Hardware 2", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_passing_check("STK-001", "Stack 1", CheckCategory::Stack)).unwrap();
 
     let report = orchestrator.run_category(CheckCategory::Hardware);
     let summary = report.summary();
@@ -116,9 +137,9 @@ fn test_orchestrator_run_specific() {
     let config = OrchestratorConfig::default();
     let mut orchestrator = CheckOrchestrator::new(config);
 
-    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware));
-    orchestrator.register_check(create_passing_check("TEST-002", "Test 2", CheckCategory::Hardware));
-    orchestrator.register_check(create_passing_check("TEST-003", "Test 3", CheckCategory::Hardware));
+    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_passing_check("TEST-002", "Test 2", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_passing_check("TEST-003", "Test 3", CheckCategory::Hardware)).unwrap();
 
     let report = orchestrator.run_specific(&[
         "TEST-001".to_string(),
@@ -134,9 +155,9 @@ fn test_orchestrator_run_excluding() {
     let config = OrchestratorConfig::default();
     let mut orchestrator = CheckOrchestrator::new(config);
 
-    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware));
-    orchestrator.register_check(create_passing_check("TEST-002", "Test 2", CheckCategory::Hardware));
-    orchestrator.register_check(create_passing_check("TEST-003", "Test 3", CheckCategory::Hardware));
+    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_passing_check("TEST-002", "Test 2", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_passing_check("TEST-003", "Test 3", CheckCategory::Hardware)).unwrap();
 
     let report = orchestrator.run_excluding(&["TEST-002".to_string()]);
     let summary = report.summary();
@@ -144,6 +165,61 @@ fn test_orchestrator_run_excluding() {
     assert_eq!(summary.total, 2);
 }
 
+#[test]
+fn test_orchestrator_run_id() {
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig::default());
+    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware)).unwrap();
+    let first = orchestrator.run_all().run_id;
+    assert!(orchestrator.run_all().run_id > first);
+
+    // A supplied ID replaces the generated one
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+        run_id: Some("train-7f3a".to_string()),
+        ..Default::default()
+    });
+    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware)).unwrap();
+    assert_eq!(orchestrator.run_all().run_id, "train-7f3a");
+}
+
+#[test]
+fn test_orchestrator_diagnostics() {
+    use tpu_doc::cli::output::{OutputFormatter, TerminalFormatter};
+    use tpu_doc::platform::diagnostics;
+
+    let check = |id: &str, issue: Option<&'static str>| {
+        RegisteredCheck::new(id, id, CheckCategory::Hardware, move || {
+            if let Some(issue) = issue {
+                diagnostics::record("sysfs", issue);
+            }
+            CheckResult::Skip {
+                reason: "no data".to_string(),
+            }
+        })
+    };
+    // Cancellable runs execute each check on a thread of its own
+    for (parallel, cancellable) in [(false, false), (true, false), (false, true)] {
+        let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+            parallel,
+            ..Default::default()
+        });
+        if cancellable {
+            orchestrator.set_cancellation_token(tpu_doc::CancellationToken::new());
+        }
+        orchestrator.register_checks(vec![
+            check("DIAG-001", Some("temp is not a number")),
+            check("DIAG-002", None),
+        ]).unwrap();
+        let report = orchestrator.run_all();
+        assert_eq!(report.diagnostics.len(), 1);
+        assert_eq!(report.diagnostics[0].to_string(), "DIAG-001: sysfs: temp is not a number");
+
+        // Listed in verbose text output only
+        let verbose = TerminalFormatter::new(false, true, false).format(&report);
+        assert!(verbose.contains("Diagnostics:\n  DIAG-001     sysfs: temp is not a number\n"));
+        assert!(!TerminalFormatter::new(false, false, false).format(&report).contains("Diagnostics:"));
+    }
+}
+
 #[test]
 fn test_orchestrator_fail_fast() {
     let config = OrchestratorConfig {
@@ -152,16 +228,34 @@ fn test_orchestrator_fail_fast() {
     };
     let mut orchestrator = CheckOrchestrator::new(config);
 
-    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware));
-    orchestrator.register_check(create_failing_check("TEST-002", "Test 2", CheckCategory::Hardware));
-    orchestrator.register_check(create_passing_check("TEST-003", "Test 3", CheckCategory::Hardware));
+    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_failing_check("TEST-002", "Test 2", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_passing_check("TEST-003", "Test 3", CheckCategory::Hardware)).unwrap();
 
     let report = orchestrator.run_all();
     let summary = report.summary();
 
-    // Should stop after first failure
+    // Should stop after first failure; the check that never ran is reported as skipped
     assert_eq!(summary.failed, 1);
-    assert!(summary.total <= 2); // May have run 1 or 2 checks before failing
+    assert_eq!((summary.total, summary.skipped), (3, 1));
+    let unstarted = report.checks.iter().find(|c| c.id == "TEST-003").unwrap();
+    assert!(unstarted.start_ms.is_none());
+    assert!(matches!(
+        &unstarted.result,
+        Some(CheckResult::Skip { reason }) if reason == "not run due to fail-fast after TEST-002"
+    ));
+
+    // Parallel runs account for every check too
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+        parallel: true,
+        fail_fast: true,
+        max_parallel: 1,
+        ..Default::default()
+    });
+    orchestrator.register_check(create_failing_check("TEST-001", "Test 1", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_passing_check("TEST-002", "Test 2", CheckCategory::Hardware)).unwrap();
+    let summary = orchestrator.run_all().summary();
+    assert_eq!((summary.total, summary.failed, summary.skipped), (2, 1, 1));
 }
 
 #[test]
@@ -169,10 +263,10 @@ fn test_orchestrator_mixed_results() {
     let config = OrchestratorConfig::default();
     let mut orchestrator = CheckOrchestrator::new(config);
 
-    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware));
-    orchestrator.register_check(create_warning_check("TEST-002", "Test 2", CheckCategory::Hardware));
-    orchestrator.register_check(create_failing_check("TEST-003", "Test 3", CheckCategory::Hardware));
-    orchestrator.register_check(create_skipping_check("TEST-004", "Test 4", CheckCategory::Hardware));
+    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_warning_check("TEST-002", "Test 2", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_failing_check("TEST-003", "Test 3", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_skipping_check("TEST-004", "Test 4", CheckCategory::Hardware)).unwrap();
 
     let report = orchestrator.run_all();
     let summary = report.summary();
@@ -190,17 +284,17 @@ fn test_orchestrator_dependencies() {
     let mut orchestrator = CheckOrchestrator::new(config);
 
     // Check with no dependencies
-    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware));
+    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware)).unwrap();
 
     // Check that depends on TEST-001
     let mut check2 = create_passing_check("TEST-002", "Test 2", CheckCategory::Hardware);
     check2.dependencies = vec!["TEST-001".to_string()];
-    orchestrator.register_check(check2);
+    orchestrator.register_check(check2).unwrap();
 
     // Check that depends on TEST-002
     let mut check3 = create_passing_check("TEST-003", "Test 3", CheckCategory::Hardware);
     check3.dependencies = vec!["TEST-002".to_string()];
-    orchestrator.register_check(check3);
+    orchestrator.register_check(check3).unwrap();
 
     let report = orchestrator.run_all();
     let summary = report.summary();
@@ -209,6 +303,168 @@ fn test_orchestrator_dependencies() {
     assert_eq!(summary.passed, 3);
 }
 
+/// Skip reasons by check ID, for checks that were skipped
+fn skip_reasons(report: &ValidationReport) -> Vec<(String, String)> {
+    report
+        .checks
+        .iter()
+        .filter_map(|c| match &c.result {
+            Some(CheckResult::Skip { reason }) => Some((c.id.clone(), reason.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+fn dependency_chain_checks() -> Vec<RegisteredCheck> {
+    vec![
+        create_failing_check("HW-001", "Devices", CheckCategory::Hardware),
+        create_passing_check("HW-002", "HBM", CheckCategory::Hardware).with_dependencies(&["HW-001"]),
+        create_passing_check("PERF-001", "Matmul", CheckCategory::Performance).with_dependencies(&["HW-002"]),
+        create_passing_check("IO-001", "Disk", CheckCategory::Io),
+        create_passing_check("IO-002", "Bucket", CheckCategory::Io).with_dependencies(&["IO-001"]),
+    ]
+}
+
+#[test]
+fn test_orchestrator_skips_checks_with_failed_dependencies() {
+    for parallel in [false, true] {
+        let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+            parallel,
+            ..Default::default()
+        });
+        orchestrator.register_checks(dependency_chain_checks()).unwrap();
+
+        let report = orchestrator.run_all();
+        let summary = report.summary();
+        assert_eq!((summary.passed, summary.failed, summary.skipped), (2, 1, 2));
+        // Checks further down the chain name the root cause, not the
+        // skipped check in between
+        let mut skipped = skip_reasons(&report);
+        skipped.sort();
+        assert_eq!(
+            skipped,
+            vec![
+                ("HW-002".to_string(), "dependency HW-001 failed".to_string()),
+                ("PERF-001".to_string(), "dependency HW-001 failed".to_string()),
+            ]
+        );
+    }
+
+    // A dependency outside the run does not cause skips
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig::default());
+    orchestrator.register_checks(dependency_chain_checks()).unwrap();
+    let report = orchestrator.run_specific(&["HW-002".to_string(), "PERF-001".to_string()]);
+    assert_eq!(report.summary().passed, 2);
+}
+
+#[test]
+fn test_orchestrator_dependency_cycle() {
+    let with_deps = |id: &str, deps: &[&str]| {
+        create_passing_check(id, id, CheckCategory::Hardware).with_dependencies(deps)
+    };
+
+    for parallel in [false, true] {
+        let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+            parallel,
+            ..Default::default()
+        });
+        orchestrator.register_check(with_deps("TEST-001", &[])).unwrap();
+        orchestrator.register_check(with_deps("TEST-002", &["TEST-001", "TEST-004"])).unwrap();
+        orchestrator.register_check(with_deps("TEST-003", &["TEST-002"])).unwrap();
+        orchestrator.register_check(with_deps("TEST-004", &["TEST-003"])).unwrap();
+        orchestrator.register_check(with_deps("TEST-005", &["TEST-001"])).unwrap();
+
+        let all: Vec<String> = orchestrator.checks().iter().map(|c| c.id.clone()).collect();
+        assert_eq!(
+            orchestrator.dependency_cycle(&all),
+            Some(vec![
+                "TEST-002".to_string(),
+                "TEST-004".to_string(),
+                "TEST-003".to_string(),
+                "TEST-002".to_string()
+            ])
+        );
+        // Leaving one member out breaks the cycle
+        assert_eq!(
+            orchestrator.dependency_cycle(&["TEST-002".to_string(), "TEST-003".to_string()]),
+            None
+        );
+
+        // The checks in the cycle fail without running; the rest run
+        let report = orchestrator.run_all();
+        let summary = report.summary();
+        assert_eq!((summary.total, summary.passed, summary.failed), (5, 2, 3));
+        let failed = report.checks.iter().find(|c| c.id == "TEST-003").unwrap();
+        match &failed.result {
+            Some(CheckResult::Fail { message, .. }) => {
+                assert_eq!(message, "Circular dependency: TEST-002 -> TEST-004 -> TEST-003 -> TEST-002")
+            }
+            other => panic!("expected a failure, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_run_checks_with_rejects_dependency_cycle() {
+    let check = |id: &str, dep: &str| {
+        RegisteredCheck::new(id, id, CheckCategory::Config, || CheckResult::Skip { reason: String::new() })
+            .with_dependencies(&[dep])
+    };
+    let config = TpuDocConfig {
+        only_checks: vec!["ACME-001".to_string(), "ACME-002".to_string()],
+        ..Default::default()
+    };
+    let err = run_checks_with(config, vec![check("ACME-001", "ACME-002"), check("ACME-002", "ACME-001")]).unwrap_err();
+    assert!(matches!(err, TpuDocError::DependencyCycle { .. }));
+    assert_eq!(err.to_string(), "Circular check dependency: ACME-001 -> ACME-002 -> ACME-001");
+    assert_eq!(err.exit_code(), EXIT_USAGE_ERROR);
+
+    // A cycle among checks that are not selected does not matter
+    let config = TpuDocConfig {
+        only_checks: vec!["ACME-001".to_string()],
+        ..Default::default()
+    };
+    assert!(run_checks_with(config, vec![check("ACME-001", "ACME-002"), check("ACME-002", "ACME-001")]).is_ok());
+}
+
+#[test]
+fn test_orchestrator_rejects_duplicate_ids() {
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig::default());
+    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware)).unwrap();
+
+    let err = orchestrator
+        .register_check(create_failing_check("test-001", "Test 1 again", CheckCategory::Io))
+        .unwrap_err();
+    assert!(matches!(&err, TpuDocError::DuplicateCheckIds { ids } if ids == &["TEST-001"]));
+    assert_eq!(err.to_string(), "Duplicate check ID: TEST-001");
+    assert_eq!(err.exit_code(), EXIT_USAGE_ERROR);
+
+    // A batch with a duplicate is rejected whole, naming every conflict once
+    let err = orchestrator
+        .register_checks(vec![
+            create_passing_check("TEST-002", "Test 2", CheckCategory::Stack),
+            create_passing_check("TEST-002", "Test 2 again", CheckCategory::Stack),
+            create_passing_check("TEST-002", "Test 2 third", CheckCategory::Stack),
+            create_passing_check("TEST-001", "Test 1 again", CheckCategory::Stack),
+        ])
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Duplicate check IDs: TEST-002, TEST-001");
+    assert_eq!(orchestrator.checks().len(), 1);
+
+    // The one registered check runs alone, with its first registration's result
+    let report = orchestrator.run_specific(&["TEST-001".to_string()]);
+    assert_eq!(report.summary().total, 1);
+    assert_eq!(report.summary().passed, 1);
+
+    // A library check cannot shadow a built-in one
+    let err = run_checks_with(
+        TpuDocConfig::default(),
+        vec![create_passing_check("HW-001", "Shadow", CheckCategory::Hardware)],
+    )
+    .unwrap_err();
+    assert!(matches!(err, TpuDocError::DuplicateCheckIds { .. }));
+}
+
 #[test]
 fn test_orchestrator_parallel_mode() {
     let config = OrchestratorConfig {
@@ -218,10 +474,10 @@ fn test_orchestrator_parallel_mode() {
     };
     let mut orchestrator = CheckOrchestrator::new(config);
 
-    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware));
-    orchestrator.register_check(create_passing_check("TEST-002", "Test 2", CheckCategory::Hardware));
-    orchestrator.register_check(create_passing_check("TEST-003", "Test 3", CheckCategory::Hardware));
-    orchestrator.register_check(create_passing_check("TEST-004", "Test 4", CheckCategory::Hardware));
+    orchestrator.register_check(create_passing_check("TEST-001", "Test 1", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_passing_check("TEST-002", "Test 2", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_passing_check("TEST-003", "Test 3", CheckCategory::Hardware)).unwrap();
+    orchestrator.register_check(create_passing_check("TEST-004", "Test 4", CheckCategory::Hardware)).unwrap();
 
     let report = orchestrator.run_all();
     let summary = report.summary();
@@ -230,6 +486,444 @@ fn test_orchestrator_parallel_mode() {
     assert_eq!(summary.passed, 4);
 }
 
+#[test]
+fn test_orchestrator_parallel_no_batch_barrier() {
+    use std::sync::{Arc, Mutex};
+
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let sleeper = |id: &'static str, sleep_ms: u64| {
+        let order = Arc::clone(&order);
+        RegisteredCheck::new(id, id, CheckCategory::Io, move || {
+            std::thread::sleep(std::time::Duration::from_millis(sleep_ms));
+            order.lock().unwrap().push(id);
+            CheckResult::Pass {
+                message: "ok".to_string(),
+                duration_ms: sleep_ms,
+                data: Vec::new(),
+            }
+        })
+    };
+
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+        parallel: true,
+        max_parallel: 2,
+        ..Default::default()
+    });
+    orchestrator.register_checks(vec![
+        sleeper("SLOW-001", 400),
+        sleeper("FAST-001", 100),
+        sleeper("FAST-002", 100).with_dependencies(&["FAST-001"]),
+        sleeper("FAST-003", 100),
+    ]).unwrap();
+    let report = orchestrator.run_all();
+
+    // Batches of two would take 400ms + 100ms; the free worker keeps
+    // taking the short checks while SLOW-001 runs
+    assert_eq!(report.summary().passed, 4);
+    assert!(report.total_duration_ms < 480, "took {}ms", report.total_duration_ms);
+    let order = order.lock().unwrap();
+    let position = |id| order.iter().position(|o| *o == id).unwrap();
+    assert!(position("FAST-001") < position("FAST-002"));
+}
+
+#[test]
+fn test_orchestrator_records_start_times() {
+    let sleeper = |id: &'static str| {
+        RegisteredCheck::new(id, id, CheckCategory::Io, || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            CheckResult::Pass {
+                message: "ok".to_string(),
+                duration_ms: 50,
+                data: Vec::new(),
+            }
+        })
+    };
+
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig::default());
+    orchestrator.register_checks(vec![sleeper("SEQ-001"), sleeper("SEQ-002").with_dependencies(&["SEQ-001"])]).unwrap();
+    let report = orchestrator.run_all();
+
+    let start_ms = |id| report.checks.iter().find(|c| c.id == id).and_then(|c| c.start_ms).unwrap();
+    assert!(start_ms("SEQ-001") < 50);
+    assert!(start_ms("SEQ-002") >= 50);
+    assert!(start_ms("SEQ-002") <= report.total_duration_ms);
+}
+
+#[test]
+fn test_orchestrator_time_budget_skips_checks_that_do_not_fit() {
+    let check = |id: &'static str, sleep_ms: u64, estimate_ms: u64| {
+        let mut check = RegisteredCheck::new(id, id, CheckCategory::Performance, move || {
+            std::thread::sleep(std::time::Duration::from_millis(sleep_ms));
+            CheckResult::Pass {
+                message: "ok".to_string(),
+                duration_ms: sleep_ms,
+                data: Vec::new(),
+            }
+        });
+        check.estimated_duration_ms = estimate_ms;
+        check
+    };
+
+    // Reports carry the estimates, so the drift can be shown
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig::default());
+    orchestrator.register_checks(vec![check("PERF-901", 20, 500)]).unwrap();
+    let report = orchestrator.run_all();
+    assert_eq!(report.estimates, vec![("PERF-901".to_string(), 500)]);
+    let drift = report.estimate_drift();
+    assert_eq!((drift.len(), drift[0].estimated_ms), (1, 500));
+    assert!(drift[0].drift_ms() < 0);
+
+    for parallel in [false, true] {
+        let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+            parallel,
+            max_parallel: 1,
+            max_duration_ms: Some(400),
+            ..Default::default()
+        });
+        orchestrator.register_checks(vec![
+            check("BUDGET-001", 250, 250),
+            check("BUDGET-002", 0, 250),
+            check("BUDGET-003", 0, 10),
+        ]).unwrap();
+        let report = orchestrator.run_all();
+
+        assert_eq!(report.checks.len(), 3, "parallel: {}", parallel);
+        let result = |id| report.checks.iter().find(|c| c.id == id).and_then(|c| c.result.as_ref()).unwrap();
+        assert!(matches!(result("BUDGET-001"), CheckResult::Pass { .. }));
+        assert!(matches!(
+            result("BUDGET-002"),
+            CheckResult::Skip { reason } if reason.starts_with("Time budget exceeded: needs ~250ms")
+        ));
+        assert!(matches!(result("BUDGET-003"), CheckResult::Pass { .. }));
+        assert!(report.total_duration_ms < 400);
+    }
+}
+
+#[test]
+fn test_orchestrator_holds_performance_checks_to_bench_timeout() {
+    let sleeping = |id: &str, category: CheckCategory| {
+        RegisteredCheck::new(id, id, category, || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            CheckResult::Pass {
+                message: "ok".to_string(),
+                duration_ms: 50,
+                data: Vec::new(),
+            }
+        })
+    };
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+        timeout_ms: 20,
+        bench_timeout_ms: 5000,
+        ..Default::default()
+    });
+    orchestrator.register_checks(vec![
+        sleeping("PERF-900", CheckCategory::Performance),
+        sleeping("IO-900", CheckCategory::Io),
+    ]).unwrap();
+
+    let report = orchestrator.run_all();
+    let result = |id: &str| report.checks.iter().find(|c| c.id == id).unwrap().result.clone();
+    assert!(matches!(result("PERF-900"), Some(CheckResult::Pass { .. })));
+    assert!(matches!(
+        result("IO-900"),
+        Some(CheckResult::Fail { details, .. }) if details == "Check exceeded global timeout"
+    ));
+
+    // A sustained-load check gets its estimated duration on top
+    let mut sustained = sleeping("PERF-901", CheckCategory::Performance).with_tags(&[CheckTag::Sustained]);
+    sustained.estimated_duration_ms = 5000;
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+        bench_timeout_ms: 20,
+        ..Default::default()
+    });
+    orchestrator.register_checks(vec![sustained, sleeping("PERF-900", CheckCategory::Performance)]).unwrap();
+
+    let report = orchestrator.run_all();
+    let result = |id: &str| report.checks.iter().find(|c| c.id == id).unwrap().result.clone();
+    assert!(matches!(result("PERF-901"), Some(CheckResult::Pass { .. })));
+    assert!(matches!(
+        result("PERF-900"),
+        Some(CheckResult::Fail { details, .. }) if details == "Check exceeded benchmark timeout"
+    ));
+}
+
+#[test]
+fn test_orchestrator_parallel_starts_long_poles_first() {
+    use std::sync::{Arc, Mutex};
+
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let estimated = |id: &'static str, estimated_duration_ms: u64| {
+        let order = Arc::clone(&order);
+        let mut check = RegisteredCheck::new(id, id, CheckCategory::Performance, move || {
+            order.lock().unwrap().push(id);
+            CheckResult::Pass {
+                message: "ok".to_string(),
+                duration_ms: 0,
+                data: Vec::new(),
+            }
+        });
+        check.estimated_duration_ms = estimated_duration_ms;
+        check
+    };
+
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+        parallel: true,
+        max_parallel: 1,
+        ..Default::default()
+    });
+    orchestrator.register_checks(vec![
+        estimated("QUICK-001", 100),
+        estimated("BENCH-001", 60000).with_dependencies(&["SETUP-001"]),
+        estimated("MID-001", 500),
+        estimated("SETUP-001", 10),
+    ]).unwrap();
+    orchestrator.run_all();
+
+    // SETUP-001 is short but gates the 60s benchmark, so it goes first
+    assert_eq!(*order.lock().unwrap(), ["SETUP-001", "BENCH-001", "MID-001", "QUICK-001"]);
+}
+
+#[test]
+fn test_orchestrator_parallel_respects_check_tags() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tpu_doc::CheckTag;
+
+    let tpu_running = Arc::new(AtomicUsize::new(0));
+    let tpu_overlapped = Arc::new(AtomicUsize::new(0));
+    let tagged = |id: &'static str, tag: CheckTag| {
+        let running = Arc::clone(&tpu_running);
+        let overlapped = Arc::clone(&tpu_overlapped);
+        RegisteredCheck::new(id, id, CheckCategory::Performance, move || {
+            let tpu = tag == CheckTag::TpuExclusive;
+            if tpu && running.fetch_add(1, Ordering::SeqCst) > 0 {
+                overlapped.fetch_add(1, Ordering::SeqCst);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(150));
+            if tpu {
+                running.fetch_sub(1, Ordering::SeqCst);
+            }
+            CheckResult::Pass {
+                message: "ok".to_string(),
+                duration_ms: 150,
+                data: Vec::new(),
+            }
+        })
+        .with_tags(&[tag])
+    };
+
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+        parallel: true,
+        max_parallel: 2,
+        ..Default::default()
+    });
+    orchestrator.register_checks(vec![
+        tagged("BENCH-001", CheckTag::TpuExclusive),
+        tagged("BENCH-002", CheckTag::TpuExclusive),
+        tagged("NET-001", CheckTag::NetworkBound),
+        tagged("NET-002", CheckTag::NetworkBound),
+        tagged("NET-003", CheckTag::NetworkBound),
+    ]).unwrap();
+    let report = orchestrator.run_all();
+
+    // The benchmarks run one after the other; the network checks overlap
+    // with them and each other despite max_parallel = 2
+    assert_eq!(report.summary().passed, 5);
+    assert_eq!(tpu_overlapped.load(Ordering::SeqCst), 0);
+    assert!(report.total_duration_ms >= 300);
+    assert!(report.total_duration_ms < 440, "took {}ms", report.total_duration_ms);
+}
+
+#[test]
+fn test_orchestrator_cancellation_returns_partial_report() {
+    use tpu_doc::CancellationToken;
+
+    let sleeper = |id: &'static str, sleep_ms: u64| {
+        RegisteredCheck::new(id, id, CheckCategory::Io, move || {
+            std::thread::sleep(std::time::Duration::from_millis(sleep_ms));
+            CheckResult::Pass {
+                message: "ok".to_string(),
+                duration_ms: sleep_ms,
+                data: Vec::new(),
+            }
+        })
+    };
+
+    for parallel in [false, true] {
+        let token = CancellationToken::new();
+        let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+            parallel,
+            max_parallel: 2,
+            ..Default::default()
+        });
+        orchestrator.set_cancellation_token(token.clone());
+        orchestrator.register_checks(vec![
+            sleeper("FAST-001", 10),
+            sleeper("SLOW-001", 5000).with_dependencies(&["FAST-001"]),
+            sleeper("LATER-001", 10).with_dependencies(&["SLOW-001"]),
+        ]).unwrap();
+
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            token.cancel();
+        });
+        let report = orchestrator.run_all();
+        canceller.join().unwrap();
+
+        assert!(report.total_duration_ms < 2000, "took {}ms", report.total_duration_ms);
+        let ids: Vec<&str> = report.checks.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["FAST-001", "SLOW-001"]);
+        assert!(matches!(
+            &report.checks[1].result,
+            Some(CheckResult::Skip { reason }) if reason.contains("cancelled")
+        ));
+    }
+}
+
+#[test]
+fn test_orchestrator_cancellation_kills_check_subprocess() {
+    use std::process::Command;
+    use std::time::{Duration, Instant};
+    use tpu_doc::platform::linux;
+    use tpu_doc::CancellationToken;
+
+    for parallel in [false, true] {
+        let pid_file = std::env::temp_dir().join(format!("tpu-doc-cancel-{}-{}", std::process::id(), parallel));
+        let _ = std::fs::remove_file(&pid_file);
+        let script = format!("echo $$ > {}; exec sleep 30", pid_file.display());
+        let benchmark = RegisteredCheck::new("BENCH-001", "Benchmark", CheckCategory::Performance, move || {
+            match linux::run_command_with_timeout(Command::new("sh").args(["-c", &script]), 60_000) {
+                Ok(_) => CheckResult::Pass {
+                    message: "finished".to_string(),
+                    duration_ms: 0,
+                    data: Vec::new(),
+                },
+                Err(e) => CheckResult::Fail {
+                    message: e.to_string(),
+                    details: String::new(),
+                    duration_ms: 0,
+                    data: Vec::new(),
+                },
+            }
+        });
+
+        let token = CancellationToken::new();
+        let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+            parallel,
+            ..Default::default()
+        });
+        orchestrator.set_cancellation_token(token.clone());
+        orchestrator.register_checks(vec![benchmark]).unwrap();
+
+        let watched = pid_file.clone();
+        let canceller = std::thread::spawn(move || {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while Instant::now() < deadline {
+                if let Some(pid) = std::fs::read_to_string(&watched).ok().and_then(|p| p.trim().parse::<u32>().ok()) {
+                    token.cancel();
+                    return Some(pid);
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            token.cancel();
+            None
+        });
+        let start = Instant::now();
+        let report = orchestrator.run_all();
+        let pid = canceller.join().unwrap().expect("benchmark subprocess started");
+
+        assert!(start.elapsed() < Duration::from_secs(10), "took {:?}", start.elapsed());
+        assert!(matches!(
+            &report.checks[0].result,
+            Some(CheckResult::Skip { reason }) if reason.contains("cancelled")
+        ));
+        // The check returned and its subprocess was killed and reaped, not left holding the TPU
+        assert!(!Path::new(&format!("/proc/{}", pid)).exists(), "pid {} still running", pid);
+        let _ = std::fs::remove_file(&pid_file);
+    }
+}
+
+#[test]
+fn test_concurrent_runs_keep_their_own_settings() {
+    use std::sync::{Arc, Barrier};
+    use tpu_doc::checks::benchmark::{self, PerfLevel};
+    use tpu_doc::checks::settings::RunSettings;
+    use tpu_doc::checks::timeouts;
+
+    // Both runs' checks are in flight at once, each reporting what it sees
+    let barrier = Arc::new(Barrier::new(2));
+    let run = |parallel: bool, settings: RunSettings| {
+        let barrier = Arc::clone(&barrier);
+        std::thread::spawn(move || {
+            let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+                parallel,
+                settings,
+                ..Default::default()
+            });
+            orchestrator.register_checks(vec![RegisteredCheck::new("PERF-001", "Observe", CheckCategory::Io, move || {
+                barrier.wait();
+                CheckResult::Pass {
+                    message: format!("{} {}", timeouts::net_timeout_ms(), benchmark::perf_level()),
+                    duration_ms: 0,
+                    data: Vec::new(),
+                }
+            })]).unwrap();
+            match orchestrator.run_all().checks[0].result.clone() {
+                Some(CheckResult::Pass { message, .. }) => message,
+                other => panic!("unexpected result {:?}", other),
+            }
+        })
+    };
+
+    let smoke = run(false, RunSettings {
+        net_timeout_ms: 111,
+        perf_level: PerfLevel::Smoke,
+        ..Default::default()
+    });
+    let soak = run(true, RunSettings {
+        net_timeout_ms: 222,
+        perf_level: PerfLevel::Soak,
+        ..Default::default()
+    });
+    assert_eq!(smoke.join().unwrap(), "111 smoke");
+    assert_eq!(soak.join().unwrap(), "222 soak");
+    // Outside a run the defaults apply
+    assert_eq!(timeouts::net_timeout_ms(), timeouts::DEFAULT_NET_TIMEOUT_MS);
+}
+
+#[test]
+fn test_orchestrator_run_all_streamed() {
+    use std::time::{Duration, Instant};
+
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig::default());
+    orchestrator.register_checks(vec![
+        create_failing_check("TEST-001", "Test 1", CheckCategory::Hardware),
+        RegisteredCheck::new("SLOW-001", "Slow", CheckCategory::Io, || {
+            std::thread::sleep(Duration::from_millis(500));
+            CheckResult::Pass {
+                message: "ok".to_string(),
+                duration_ms: 500,
+                data: Vec::new(),
+            }
+        }),
+        create_passing_check("TEST-002", "Test 2", CheckCategory::Stack).with_dependencies(&["TEST-001"]),
+    ]).unwrap();
+
+    let start = Instant::now();
+    let mut run = orchestrator.run_all_streamed();
+    // The first result arrives while the slow check is still running
+    let first = run.next().unwrap();
+    assert_eq!(first.id, "TEST-001");
+    assert!(start.elapsed() < Duration::from_millis(400), "took {:?}", start.elapsed());
+
+    let rest: Vec<String> = run.by_ref().map(|check| check.id).collect();
+    assert_eq!(rest, ["SLOW-001", "TEST-002"]);
+    let report = run.report();
+    let ids: Vec<&str> = report.checks.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, ["TEST-001", "SLOW-001", "TEST-002"]);
+    assert!(matches!(&report.checks[2].result, Some(CheckResult::Skip { .. })));
+}
+
 // Result aggregator tests
 
 #[test]
@@ -244,7 +938,10 @@ fn test_result_aggregator_add_results() {
         result: Some(CheckResult::Pass {
             message: "OK".to_string(),
             duration_ms: 100,
+            data: Vec::new(),
         }),
+        start_ms: None,
+        ownership: Default::default(),
     });
 
     aggregator.add_result(Check {
@@ -256,7 +953,10 @@ fn test_result_aggregator_add_results() {
             message: "Failed".to_string(),
             details: "Details".to_string(),
             duration_ms: 100,
+            data: Vec::new(),
         }),
+        start_ms: None,
+        ownership: Default::default(),
     });
 
     let summary = aggregator.get_summary();
@@ -265,6 +965,49 @@ fn test_result_aggregator_add_results() {
     assert_eq!(summary.total, 2);
 }
 
+#[test]
+fn test_result_summary_by_category() {
+    let check = |id: &str, category: CheckCategory, result: CheckResult| Check {
+        id: id.to_string(),
+        name: id.to_string(),
+        category,
+        description: "Test".to_string(),
+        result: Some(result),
+        start_ms: Some(0),
+        ownership: Default::default(),
+    };
+    let pass = |duration_ms| CheckResult::Pass {
+        message: "OK".to_string(),
+        duration_ms,
+        data: Vec::new(),
+    };
+
+    let mut aggregator = ResultAggregator::new();
+    aggregator.add_result(check("PERF-001", CheckCategory::Performance, pass(3000)));
+    aggregator.add_result(check("HW-001", CheckCategory::Hardware, pass(200)));
+    aggregator.add_result(check("PERF-002", CheckCategory::Performance, pass(2000)));
+    aggregator.add_result(check(
+        "HW-002",
+        CheckCategory::Hardware,
+        CheckResult::Skip {
+            reason: "n/a".to_string(),
+        },
+    ));
+    aggregator.set_metadata("host".to_string(), None, 3100);
+
+    let summary = aggregator.get_summary();
+    assert_eq!(summary.total_duration_ms, 5200);
+    assert_eq!(summary.wall_clock_ms, 3100);
+    let categories: Vec<_> = summary.categories.iter().map(|c| c.category.clone()).collect();
+    assert_eq!(categories, vec![CheckCategory::Hardware, CheckCategory::Performance]);
+
+    let perf = summary.category(&CheckCategory::Performance).unwrap();
+    assert_eq!((perf.passed, perf.total, perf.total_duration_ms), (2, 2, 5000));
+    let hardware = summary.category(&CheckCategory::Hardware).unwrap();
+    assert_eq!((hardware.passed, hardware.skipped, hardware.total_duration_ms), (1, 1, 200));
+    assert!(summary.category(&CheckCategory::Io).is_none());
+}
+
 #[test]
 fn test_result_aggregator_has_failures() {
     let mut aggregator = ResultAggregator::new();
@@ -277,7 +1020,10 @@ fn test_result_aggregator_has_failures() {
         result: Some(CheckResult::Pass {
             message: "OK".to_string(),
             duration_ms: 100,
+            data: Vec::new(),
         }),
+        start_ms: None,
+        ownership: Default::default(),
     });
 
     assert!(!aggregator.has_failures());
@@ -291,7 +1037,10 @@ fn test_result_aggregator_has_failures() {
             message: "Failed".to_string(),
             details: "Details".to_string(),
             duration_ms: 100,
+            data: Vec::new(),
         }),
+        start_ms: None,
+        ownership: Default::default(),
     });
 
     assert!(aggregator.has_failures());
@@ -309,7 +1058,10 @@ fn test_result_aggregator_get_by_category() {
         result: Some(CheckResult::Pass {
             message: "OK".to_string(),
             duration_ms: 100,
+            data: Vec::new(),
         }),
+        start_ms: None,
+        ownership: Default::default(),
     });
 
     aggregator.add_result(Check {
@@ -320,7 +1072,10 @@ fn test_result_aggregator_get_by_category() {
         result: Some(CheckResult::Pass {
             message: "OK".to_string(),
             duration_ms: 100,
+            data: Vec::new(),
         }),
+        start_ms: None,
+        ownership: Default::default(),
     });
 
     let hw_checks = aggregator.get_by_category(CheckCategory::Hardware);
@@ -344,7 +1099,10 @@ fn test_result_aggregator_get_failures() {
         result: Some(CheckResult::Pass {
             message: "OK".to_string(),
             duration_ms: 100,
+            data: Vec::new(),
         }),
+        start_ms: None,
+        ownership: Default::default(),
     });
 
     aggregator.add_result(Check {
@@ -356,7 +1114,10 @@ fn test_result_aggregator_get_failures() {
             message: "Failed".to_string(),
             details: "Details".to_string(),
             duration_ms: 100,
+            data: Vec::new(),
         }),
+        start_ms: None,
+        ownership: Default::default(),
     });
 
     let failures = aggregator.get_failures();
@@ -376,7 +1137,10 @@ fn test_result_aggregator_get_warnings() {
         result: Some(CheckResult::Pass {
             message: "OK".to_string(),
             duration_ms: 100,
+            data: Vec::new(),
         }),
+        start_ms: None,
+        ownership: Default::default(),
     });
 
     aggregator.add_result(Check {
@@ -388,7 +1152,10 @@ fn test_result_aggregator_get_warnings() {
             message: "Warning".to_string(),
             details: "Details".to_string(),
             duration_ms: 100,
+            data: Vec::new(),
         }),
+        start_ms: None,
+        ownership: Default::default(),
     });
 
     let warnings = aggregator.get_warnings();
@@ -408,7 +1175,10 @@ fn test_result_aggregator_to_report() {
         result: Some(CheckResult::Pass {
             message: "OK".to_string(),
             duration_ms: 100,
+            data: Vec::new(),
         }),
+        start_ms: None,
+        ownership: Default::default(),
     });
 
     aggregator.set_metadata(
@@ -425,6 +1195,300 @@ fn test_result_aggregator_to_report() {
     assert_eq!(report.checks.len(), 1);
 }
 
+#[test]
+fn test_run_checks_with_custom_checks() {
+    let custom = RegisteredCheck::new("ACME-001", "Team Quota", CheckCategory::Config, || {
+        CheckResult::Warn {
+            message: "Quota nearly exhausted".to_string(),
+            details: "2 of 64 chips left".to_string(),
+            duration_ms: 1,
+            data: Vec::new(),
+        }
+    })
+    .with_description("Check the team's TPU quota reservation");
+
+    let config = TpuDocConfig {
+        only_checks: vec!["ACME-001".to_string()],
+        ..Default::default()
+    };
+    let report = run_checks_with(config, vec![custom]).unwrap();
+
+    assert_eq!(report.checks.len(), 1);
+    assert_eq!(report.checks[0].id, "ACME-001");
+    assert_eq!(report.checks[0].name, "Team Quota");
+    assert_eq!(report.checks[0].category, CheckCategory::Config);
+    assert_eq!(report.summary().warned, 1);
+}
+
+#[test]
+fn test_error_exit_codes() {
+    let config_error = ConfigFile::parse("[timeouts]\nnetwork_ms = 0\n", Path::new("t.toml")).unwrap_err();
+    assert!(config_error.to_string().starts_with("Config file t.toml: "));
+    assert_eq!(config_error.exit_code(), EXIT_USAGE_ERROR);
+
+    let runtime_error = TpuDocError::Timeout {
+        operation: "gsutil".to_string(),
+        timeout_ms: 5000,
+    };
+    assert_eq!(runtime_error.exit_code(), EXIT_RUNTIME_ERROR);
+    assert_eq!(TpuDocError::NotOnTpu.exit_code(), 3);
+}
+
+#[test]
+fn test_error_sources() {
+    use std::error::Error;
+
+    // The cause of an unreadable configuration file is the I/O error
+    let err = ConfigFile::load(Path::new("/nonexistent/tpu-doc.toml")).unwrap_err();
+    assert!(matches!(err, TpuDocError::ConfigError { .. }));
+    let cause = err.source().and_then(|e| e.downcast_ref::<std::io::Error>()).unwrap();
+    assert_eq!(cause.kind(), std::io::ErrorKind::NotFound);
+    assert!(err.to_string().ends_with(&cause.to_string()));
+
+    // Conversions sort standard library errors into variants
+    fn parse_port(s: &str) -> Result<u16, TpuDocError> {
+        Ok(s.parse::<u16>()?)
+    }
+    let err = parse_port("http").unwrap_err();
+    assert!(matches!(err, TpuDocError::ParseError { .. }));
+    assert_eq!(err.to_string(), "Parse error: invalid digit found in string");
+    assert!(err.source().unwrap().is::<std::num::ParseIntError>());
+
+    let err = TpuDocError::from(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+    assert_eq!(err.code(), "io_error");
+    assert!(err.clone().source().is_some());
+
+    // Errors raised by tpu-doc itself have no underlying cause
+    assert!(TpuDocError::NotOnTpu.source().is_none());
+}
+
+#[test]
+fn test_run_checks_with_rejects_unknown_ids() {
+    let config = TpuDocConfig {
+        only_checks: vec!["HW-01".to_string(), "STK-001".to_string()],
+        skip_checks: vec!["perf-004".to_string(), "NOPE".to_string()],
+        ..Default::default()
+    };
+    let err = run_checks_with(config.clone(), Vec::new()).unwrap_err();
+    assert_eq!(err.exit_code(), EXIT_USAGE_ERROR);
+    match run_checks_with(config, Vec::new()) {
+        Err(TpuDocError::UnknownCheckIds { ids }) => assert_eq!(
+            ids,
+            vec![
+                ("HW-01".to_string(), Some("HW-001".to_string())),
+                ("perf-004".to_string(), Some("PERF-004".to_string())),
+                ("NOPE".to_string(), None),
+            ]
+        ),
+        other => panic!("expected unknown check IDs, got {:?}", other.map(|r| r.checks.len())),
+    }
+
+    // Custom checks count as known
+    let custom = create_skipping_check("ACME-001", "Team Quota", CheckCategory::Config);
+    let config = TpuDocConfig {
+        only_checks: vec!["ACME-001".to_string()],
+        ..Default::default()
+    };
+    assert!(run_checks_with(config, vec![custom]).is_ok());
+}
+
+#[test]
+fn test_policy_downgrades_config_file_rules() {
+    let config = ConfigFile::parse(
+        "[policy]\n\"ACME-001\" = \"warn\"\nperformance = \"ignore\"\n",
+        Path::new("tpu-doc.toml"),
+    )
+    .unwrap();
+
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig::default());
+    orchestrator.register_checks(vec![
+        create_failing_check("ACME-001", "Team Quota", CheckCategory::Config),
+        create_warning_check("TEST-PERF", "Bandwidth", CheckCategory::Performance),
+        create_failing_check("TEST-HW", "Devices", CheckCategory::Hardware),
+    ]).unwrap();
+    let mut report = orchestrator.run_all();
+    config.policy.apply(&mut report);
+
+    let summary = report.summary();
+    assert_eq!((summary.passed, summary.warned, summary.failed), (1, 1, 1));
+    let downgraded: Vec<&str> = report
+        .checks
+        .iter()
+        .filter(|c| is_downgraded(c))
+        .map(|c| c.id.as_str())
+        .collect();
+    assert_eq!(downgraded, vec!["ACME-001", "TEST-PERF"]);
+}
+
+#[test]
+fn test_policy_applies_before_fail_fast_and_dependency_skips() {
+    let config = ConfigFile::parse("[policy]\n\"ACME-001\" = \"warn\"\n", Path::new("tpu-doc.toml")).unwrap();
+
+    for parallel in [false, true] {
+        let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+            parallel,
+            fail_fast: true,
+            policy: config.policy.clone(),
+            ..Default::default()
+        });
+        let mut dependent = create_passing_check("TEST-002", "Uses quota", CheckCategory::Config);
+        dependent.dependencies = vec!["ACME-001".to_string()];
+        let mut last = create_passing_check("TEST-003", "After", CheckCategory::Config);
+        last.dependencies = vec!["TEST-002".to_string()];
+        orchestrator.register_checks(vec![
+            create_failing_check("ACME-001", "Team Quota", CheckCategory::Config),
+            dependent,
+            last,
+        ]).unwrap();
+
+        // The downgraded failure neither stops the run nor skips its dependents
+        let report = orchestrator.run_all();
+        let summary = report.summary();
+        assert_eq!((summary.passed, summary.warned, summary.failed, summary.skipped), (2, 1, 0, 0));
+        assert!(is_downgraded(report.checks.iter().find(|c| c.id == "ACME-001").unwrap()));
+    }
+}
+
+#[test]
+fn test_config_file_env_vars_extend_stk007() {
+    let config = ConfigFile::parse(
+        "[[env.required]]\nname = \"TPU_DOC_TEST_UNSET_LAUNCHER_VAR\"\ndescription = \"Exported by the launcher\"\n",
+        Path::new("tpu-doc.toml"),
+    )
+    .unwrap();
+
+    let config = TpuDocConfig {
+        only_checks: vec!["STK-007".to_string()],
+        env_vars: config.env_vars,
+        ..Default::default()
+    };
+    let report = run_checks_with(config, Vec::new()).unwrap();
+
+    match report.checks[0].result.as_ref().unwrap() {
+        CheckResult::Fail { message, details, .. } => {
+            assert!(message.contains("TPU_DOC_TEST_UNSET_LAUNCHER_VAR"), "{}", message);
+            assert!(details.contains("Exported by the launcher"));
+        }
+        other => panic!("expected STK-007 to fail, got {:?}", other),
+    }
+}
+
+#[cfg(feature = "async")]
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_run_async_respects_dependencies_and_timeout() {
+    use std::sync::{Arc, Mutex};
+
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let record = |id: &'static str, sleep_ms: u64| {
+        let order = Arc::clone(&order);
+        RegisteredCheck::new(id, id, CheckCategory::Io, move || {
+            std::thread::sleep(std::time::Duration::from_millis(sleep_ms));
+            order.lock().unwrap().push(id);
+            CheckResult::Pass {
+                message: "ok".to_string(),
+                duration_ms: sleep_ms,
+                data: Vec::new(),
+            }
+        })
+    };
+
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig {
+        parallel: true,
+        fail_fast: false,
+        timeout_ms: 300,
+        bench_timeout_ms: 300,
+        max_parallel: 4,
+        max_duration_ms: None,
+        run_id: None,
+        policy: Default::default(),
+        settings: Default::default(),
+    });
+    orchestrator.register_checks(vec![
+        record("NET-002", 10).with_dependencies(&["NET-001"]),
+        record("NET-001", 50),
+        record("NET-003", 1000),
+    ]).unwrap();
+    let ids: Vec<String> = ["NET-001", "NET-002", "NET-003"].iter().map(|s| s.to_string()).collect();
+    let report = block_on(orchestrator.run_async(&ids));
+
+    assert_eq!(report.checks.len(), 3);
+    assert_eq!(order.lock().unwrap()[..2], ["NET-001", "NET-002"]);
+    let slow = report.checks.iter().find(|c| c.id == "NET-003").unwrap();
+    assert!(matches!(
+        &slow.result,
+        Some(CheckResult::Fail { message, .. }) if message == "Check timed out after 300ms"
+    ));
+    assert!(report.total_duration_ms < 1000);
+
+    let mut orchestrator = CheckOrchestrator::new(OrchestratorConfig::default());
+    orchestrator.register_checks(dependency_chain_checks()).unwrap();
+    let ids: Vec<String> = orchestrator.checks().iter().map(|c| c.id.clone()).collect();
+    let report = block_on(orchestrator.run_async(&ids));
+    assert_eq!(report.summary().skipped, 2);
+    assert!(skip_reasons(&report).iter().all(|(_, reason)| reason == "dependency HW-001 failed"));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_run_checks_async_cancellation() {
+    let token = tpu_doc::CancellationToken::new();
+    let slow = RegisteredCheck::new("SLOW-001", "Slow", CheckCategory::Io, || {
+        std::thread::sleep(std::time::Duration::from_millis(1500));
+        CheckResult::Pass {
+            message: "ok".to_string(),
+            duration_ms: 1500,
+            data: Vec::new(),
+        }
+    });
+    let config = TpuDocConfig {
+        only_checks: vec!["SLOW-001".to_string()],
+        cancellation: Some(token.clone()),
+        ..Default::default()
+    };
+
+    let started = std::time::Instant::now();
+    let report = block_on(async move {
+        let run = tokio::spawn(tpu_doc::run_checks_async(config, vec![slow]));
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        token.cancel();
+        run.await.unwrap().unwrap()
+    });
+
+    assert_eq!(report.summary().skipped, 1);
+    assert!(report.total_duration_ms < 1000, "took {}ms", report.total_duration_ms);
+    // The abandoned check keeps its blocking thread until it returns, which
+    // only delays runtime shutdown, not the report
+    assert!(started.elapsed().as_millis() < 5000);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_run_checks_async_with_custom_checks() {
+    let custom = RegisteredCheck::new("ACME-001", "Team Quota", CheckCategory::Config, || CheckResult::Pass {
+        message: "Quota available".to_string(),
+        duration_ms: 1,
+        data: Vec::new(),
+    });
+    let config = TpuDocConfig {
+        only_checks: vec!["ACME-001".to_string()],
+        fail_fast: true,
+        ..Default::default()
+    };
+    let report = block_on(tpu_doc::run_checks_async(config, vec![custom])).unwrap();
+
+    assert_eq!(report.checks.len(), 1);
+    assert_eq!(report.summary().passed, 1);
+}
+
 // TpuDocConfig tests
 
 #[test]
@@ -437,6 +1501,7 @@ fn test_tpu_doc_config_default() {
     assert!(!config.parallel);
     assert!(!config.fail_fast);
     assert_eq!(config.timeout_ms, 30000);
+    assert_eq!((config.net_timeout_ms, config.bench_timeout_ms), (5000, 120000));
 }
 
 #[test]
@@ -486,6 +1551,7 @@ fn test_validation_report_new() {
 fn test_validation_report_summary() {
     let report = ValidationReport {
         timestamp: 0,
+        run_id: "0005c4a1b2c3d4e5".to_string(),
         hostname: "test".to_string(),
         tpu_type: None,
         checks: vec![
@@ -497,7 +1563,10 @@ fn test_validation_report_summary() {
                 result: Some(CheckResult::Pass {
                     message: "OK".to_string(),
                     duration_ms: 100,
+                    data: Vec::new(),
                 }),
+                start_ms: None,
+                ownership: Default::default(),
             },
             Check {
                 id: "TEST-002".to_string(),
@@ -508,10 +1577,16 @@ fn test_validation_report_summary() {
                     message: "Failed".to_string(),
                     details: "Details".to_string(),
                     duration_ms: 100,
+                    data: Vec::new(),
                 }),
+                start_ms: None,
+                ownership: Default::default(),
             },
         ],
         total_duration_ms: 200,
+        started_at_ms: 0,
+        estimates: Vec::new(),
+        diagnostics: Vec::new(),
     };
 
     let summary = report.summary();
@@ -2,14 +2,20 @@
 //!
 //! Tests for terminal, JSON, and JUnit XML output formatters.
 
-use tpu_doc::cli::output::{get_formatter, JsonFormatter, JunitFormatter, OutputFormatter, TerminalFormatter};
-use tpu_doc::cli::args::OutputFormat;
+use tpu_doc::cli::output::{
+    format_badge, format_timeline, formatter_for_args, get_formatter, register_formatter, registered_formatters,
+    JsonFormatter, JunitFormatter, OutputFormatter, StatusGlyphs, SummaryFormatter, TerminalFormatter,
+};
+use tpu_doc::cli::args::{Args, GroupBy, OutputFormat, Theme};
+use tpu_doc::engine::ownership::Ownership;
+use tpu_doc::engine::policy::PolicyTarget;
 use tpu_doc::engine::result::ValidationReport;
-use tpu_doc::{Check, CheckCategory, CheckResult};
+use tpu_doc::{Check, CheckCategory, CheckOwnership, CheckResult};
 
 fn create_sample_report() -> ValidationReport {
     ValidationReport {
         timestamp: 1733500000,
+        run_id: "0005c4a1b2c3d4e5".to_string(),
         hostname: "test-vm-001".to_string(),
         tpu_type: Some("v5e".to_string()),
         checks: vec![
@@ -21,7 +27,10 @@ fn create_sample_report() -> ValidationReport {
                 result: Some(CheckResult::Pass {
                     message: "8 chips detected".to_string(),
                     duration_ms: 100,
+                    data: Vec::new(),
                 }),
+                start_ms: None,
+                ownership: Default::default(),
             },
             Check {
                 id: "HW-003".to_string(),
@@ -32,7 +41,10 @@ fn create_sample_report() -> ValidationReport {
                     message: "Temperature elevated".to_string(),
                     details: "Chip 3 at 78C".to_string(),
                     duration_ms: 50,
+                    data: Vec::new(),
                 }),
+                start_ms: None,
+                ownership: Default::default(),
             },
             Check {
                 id: "STK-002".to_string(),
@@ -43,7 +55,10 @@ fn create_sample_report() -> ValidationReport {
                     message: "Version mismatch".to_string(),
                     details: "0.1.dev < 0.2.dev required".to_string(),
                     duration_ms: 75,
+                    data: Vec::new(),
                 }),
+                start_ms: None,
+                ownership: Default::default(),
             },
             Check {
                 id: "IO-004".to_string(),
@@ -53,25 +68,35 @@ fn create_sample_report() -> ValidationReport {
                 result: Some(CheckResult::Skip {
                     reason: "CHECKPOINT_DIR not set".to_string(),
                 }),
+                start_ms: None,
+                ownership: Default::default(),
             },
         ],
         total_duration_ms: 500,
+        started_at_ms: 0,
+        estimates: Vec::new(),
+        diagnostics: Vec::new(),
     }
 }
 
 fn create_empty_report() -> ValidationReport {
     ValidationReport {
         timestamp: 1733500000,
+        run_id: "0005c4a1b2c3d4e5".to_string(),
         hostname: "empty-vm".to_string(),
         tpu_type: None,
         checks: vec![],
         total_duration_ms: 0,
+        started_at_ms: 0,
+        estimates: Vec::new(),
+        diagnostics: Vec::new(),
     }
 }
 
 fn create_all_pass_report() -> ValidationReport {
     ValidationReport {
         timestamp: 1733500000,
+        run_id: "0005c4a1b2c3d4e5".to_string(),
         hostname: "test-vm".to_string(),
         tpu_type: Some("v5e".to_string()),
         checks: vec![
@@ -83,7 +108,10 @@ fn create_all_pass_report() -> ValidationReport {
                 result: Some(CheckResult::Pass {
                     message: "OK".to_string(),
                     duration_ms: 100,
+                    data: Vec::new(),
                 }),
+                start_ms: None,
+                ownership: Default::default(),
             },
             Check {
                 id: "HW-002".to_string(),
@@ -93,10 +121,16 @@ fn create_all_pass_report() -> ValidationReport {
                 result: Some(CheckResult::Pass {
                     message: "OK".to_string(),
                     duration_ms: 100,
+                    data: Vec::new(),
                 }),
+                start_ms: None,
+                ownership: Default::default(),
             },
         ],
         total_duration_ms: 200,
+        started_at_ms: 0,
+        estimates: Vec::new(),
+        diagnostics: Vec::new(),
     }
 }
 
@@ -150,6 +184,152 @@ fn test_terminal_formatter_verbose() {
     assert!(output.contains("ms"));
 }
 
+#[test]
+fn test_terminal_formatter_counts_cached_and_downgraded_results() {
+    let mut report = create_sample_report();
+    let plain = TerminalFormatter::new(true, false, false).format(&report);
+    assert!(!plain.contains("Cached results:"));
+    assert!(!plain.contains("Downgraded by policy:"));
+
+    report.checks[0].result = report.checks[0]
+        .result
+        .take()
+        .map(|result| result.with_data(vec![("cached".to_string(), "12m ago".to_string())]));
+    report.checks[1].result = report.checks[1]
+        .result
+        .take()
+        .map(|result| result.with_data(vec![("policy".to_string(), "fail -> warn".to_string())]));
+    let output = TerminalFormatter::new(true, false, false).format(&report);
+    assert!(output.contains("Cached results: 1 (reused from earlier runs)\n"));
+    assert!(output.contains("Downgraded by policy: 1\n"));
+}
+
+#[test]
+fn test_terminal_formatter_verbose_data_table() {
+    let mut report = create_sample_report();
+    report.checks[0].result = report.checks[0].result.take().map(|result| {
+        result.with_data(vec![
+            ("storage.googleapis.com".to_string(), "12ms".to_string()),
+            ("pypi.org".to_string(), "unreachable".to_string()),
+        ])
+    });
+
+    let verbose = TerminalFormatter::new(true, true, false).format(&report);
+    assert!(verbose.contains("\n           storage.googleapis.com  12ms\n"));
+    assert!(verbose.contains("\n           pypi.org                unreachable\n"));
+
+    let plain = TerminalFormatter::new(true, false, false).format(&report);
+    assert!(!plain.contains("pypi.org"));
+}
+
+#[test]
+fn test_terminal_formatter_category_timing() {
+    let mut report = create_sample_report();
+    let output = TerminalFormatter::new(false, true, false).format(&report);
+
+    // Longest category first, with its share of the total check time
+    let hardware = output.find("  Hardware       2 checks      0.1s   66%").unwrap();
+    let stack = output.find("  Stack          1 checks      0.1s   33%").unwrap();
+    assert!(output.contains("Time by category:"));
+    assert!(hardware < stack);
+    assert!(!output.contains("Check time:"));
+
+    assert!(!TerminalFormatter::new(false, false, false).format(&report).contains("Time by category:"));
+
+    // Checks that overlapped add up to more than the wall-clock time
+    report.total_duration_ms = 100;
+    let output = TerminalFormatter::new(false, false, false).format(&report);
+    assert!(output.contains("Check time: 0.2s"));
+}
+
+#[test]
+fn test_terminal_formatter_group_by_status() {
+    let formatter = TerminalFormatter::new(false, false, false).with_group_by(GroupBy::Status);
+    let output = formatter.format(&create_sample_report());
+
+    let position = |text: &str| output.find(text).unwrap_or_else(|| panic!("missing {}", text));
+    assert!(position("FAILURES\n  [FAIL] STK-002") < position("WARNINGS\n  [WARN] HW-003"));
+    assert!(position("WARNINGS") < position("PASSED\n  [PASS] HW-001"));
+    assert!(position("PASSED") < position("SKIPPED\n  [SKIP] IO-004"));
+    assert!(!output.contains("HARDWARE CHECKS"));
+
+    let quiet = TerminalFormatter::new(false, false, true).with_group_by(GroupBy::Status);
+    let output = quiet.format(&create_sample_report());
+    assert!(output.contains("FAILURES"));
+    assert!(!output.contains("PASSED"));
+}
+
+#[test]
+fn test_terminal_formatter_glyphs_and_theme() {
+    let glyphs = StatusGlyphs::parse("OK, !!,XX,--").unwrap();
+    let output = TerminalFormatter::new(false, false, false)
+        .with_glyphs(glyphs)
+        .format(&create_sample_report());
+    assert!(output.contains("  OK HW-001:"));
+    assert!(output.contains("  !! HW-003:"));
+    assert!(output.contains("  XX STK-002:"));
+    assert!(output.contains("  -- IO-004:"));
+    assert!(!output.contains("[PASS]"));
+
+    let output = TerminalFormatter::new(true, false, false)
+        .with_theme(Theme::HighContrast)
+        .format(&create_sample_report());
+    assert!(output.contains("\x1b[1;97;41m[FAIL]\x1b[0m"));
+    assert!(output.contains("\x1b[1;30;42m[PASS]\x1b[0m"));
+
+    assert!(StatusGlyphs::parse("OK,WARN,FAIL").is_err());
+    assert!(StatusGlyphs::parse("OK,,FAIL,SKIP").is_err());
+}
+
+#[test]
+fn test_terminal_formatter_ascii() {
+    let mut report = create_sample_report();
+    report.checks[1].result = Some(CheckResult::Warn {
+        message: "Chip 3 at 78\u{b0}C \u{2014} \u{201c}throttling\u{201d} \u{26a0}\u{fe0f}".to_string(),
+        details: String::new(),
+        duration_ms: 50,
+        data: Vec::new(),
+    });
+    let glyphs = StatusGlyphs::parse("\u{2714},\u{26a0},\u{2718},\u{2026}").unwrap();
+    let output = TerminalFormatter::new(false, false, false)
+        .with_glyphs(glyphs)
+        .with_ascii(true)
+        .format(&report);
+
+    assert!(output.is_ascii());
+    assert!(output.contains("Chip 3 at 78degC - \"throttling\" ?"));
+    assert!(output.contains("  ok HW-001:"));
+    assert!(output.contains("  x STK-002:"));
+    assert!(output.contains("  ... IO-004:"));
+}
+
+#[test]
+fn test_terminal_formatter_width() {
+    let mut report = create_sample_report();
+    report.checks[2].result = Some(CheckResult::Fail {
+        message: "Version mismatch between the installed libtpu and the JAX release".to_string(),
+        details: "0.1.dev < 0.2.dev required".to_string(),
+        duration_ms: 75,
+        data: Vec::new(),
+    });
+
+    let output = TerminalFormatter::new(false, false, false).with_width(Some(60)).format(&report);
+    let line = output.lines().find(|l| l.contains("STK-002")).unwrap();
+    assert_eq!(line, "  [FAIL] STK-002: libtpu Version (Version mismatch betwee...");
+    assert!(output.lines().filter(|l| l.starts_with("  [")).all(|l| l.chars().count() <= 60));
+
+    // Verbose output wraps under the message instead of dropping details
+    let output = TerminalFormatter::new(true, true, false).with_width(Some(60)).format(&report);
+    let start = output.find("STK-002").unwrap();
+    let lines: Vec<&str> = output[start..].lines().take(3).collect();
+    assert!(lines[1].starts_with("         "), "{:?}", lines);
+    assert!(lines[2].contains("0.2.dev required (75ms))"), "{:?}", lines);
+
+    // Without a width, lines are left as they are
+    let output = TerminalFormatter::new(false, false, false).format(&report);
+    assert!(output.contains("(Version mismatch between the installed libtpu and the JAX release)"));
+}
+
 #[test]
 fn test_terminal_formatter_quiet() {
     let formatter = TerminalFormatter::new(false, false, true);
@@ -261,6 +441,26 @@ fn test_json_formatter_summary_counts() {
     assert!(output.contains("\"total\""));
 }
 
+#[test]
+fn test_json_formatter_failures_only() {
+    let output = JsonFormatter::new(true).with_failures_only(true).format(&create_sample_report());
+    let json = tpu_doc::util::json::parse(&output).unwrap();
+    let ids: Vec<&str> = match json.get("checks") {
+        Some(tpu_doc::util::json::JsonValue::Array(checks)) => {
+            checks.iter().filter_map(|c| c.get("id").and_then(|id| id.as_str())).collect()
+        }
+        other => panic!("checks is not an array: {:?}", other),
+    };
+    assert_eq!(ids, ["HW-003", "STK-002"]);
+
+    // The summary still counts the passed and skipped checks
+    let summary = json.get("summary").unwrap();
+    assert_eq!(summary.get("total").and_then(|t| t.to_scalar_string()).as_deref(), Some("4"));
+
+    let compact = JsonFormatter::new(false).with_failures_only(true).format(&create_all_pass_report());
+    assert!(compact.ends_with("\"checks\":[]}"));
+}
+
 #[test]
 fn test_json_formatter_escapes_special_chars() {
     let formatter = JsonFormatter::new(true);
@@ -281,6 +481,215 @@ fn test_json_formatter_empty_report() {
     assert!(output.contains("\"total\": 0"));
 }
 
+#[test]
+fn test_json_formatter_category_summary() {
+    let formatter = JsonFormatter::new(true);
+    let output = formatter.format(&create_sample_report());
+
+    assert!(output.contains("\"check_duration_ms\": 225,"));
+    assert!(output.contains(
+        "\"Hardware\": {\"passed\": 1, \"warned\": 1, \"failed\": 0, \"skipped\": 0, \"total\": 2, \"duration_ms\": 150},"
+    ));
+    assert!(output.contains(
+        "\"Io\": {\"passed\": 0, \"warned\": 0, \"failed\": 0, \"skipped\": 1, \"total\": 1, \"duration_ms\": 0}\n"
+    ));
+    assert!(JsonFormatter::new(true).format(&create_empty_report()).contains("\"categories\": {\n    }"));
+}
+
+#[test]
+fn test_json_formatter_start_ms() {
+    let formatter = JsonFormatter::new(true);
+    let mut report = create_sample_report();
+    report.checks[0].start_ms = Some(1250);
+    let output = formatter.format(&report);
+
+    assert!(output.contains("\"start_ms\": 1250,"));
+    assert_eq!(output.matches("\"start_ms\"").count(), 1);
+    assert!(!output.contains("\"started_at\""));
+
+    // Wall-clock times need the run's start
+    report.started_at_ms = 1_733_500_000_000;
+    let output = formatter.format(&report);
+    assert!(output.contains(
+        "\"start_ms\": 1250,\n      \"started_at\": \"2024-12-06T15:46:41.250Z\",\n      \"finished_at\": \"2024-12-06T15:46:41.350Z\","
+    ));
+    assert_eq!(output.matches("\"started_at\"").count(), 1);
+}
+
+#[test]
+fn test_terminal_formatter_rfc3339_timestamp() {
+    let output = TerminalFormatter::new(false, false, false).format(&create_sample_report());
+    assert!(output.contains("Timestamp: 2024-12-06T15:46:40.000Z\nRun ID: 0005c4a1b2c3d4e5\n"));
+
+    let mut report = create_sample_report();
+    report.started_at_ms = 1_733_500_000_123;
+    let output = TerminalFormatter::new(false, false, false).format(&report);
+    assert!(output.contains("Timestamp: 2024-12-06T15:46:40.123Z\n"));
+}
+
+#[test]
+fn test_terminal_formatter_banner() {
+    let output = TerminalFormatter::new(false, false, false).format(&create_sample_report());
+    assert!(output.ends_with("-\n[ FAILED ] 1 failed, 1 warnings, 1 passed, 1 skipped"));
+
+    // The banner keeps its colors in every theme, and shows in quiet mode
+    let output = TerminalFormatter::new(true, false, true)
+        .with_theme(Theme::HighContrast)
+        .format(&create_all_pass_report());
+    assert!(output.ends_with("\x1b[1;30;42m PASSED \x1b[0m 0 failed, 0 warnings, 2 passed, 0 skipped"));
+
+    let mut report = create_all_pass_report();
+    report.checks[1].result = Some(CheckResult::Warn {
+        message: "Low".to_string(),
+        details: String::new(),
+        duration_ms: 0,
+        data: Vec::new(),
+    });
+    let output = TerminalFormatter::new(true, false, false).format(&report);
+    assert!(output.ends_with("\x1b[1;30;43m WARNINGS \x1b[0m 0 failed, 1 warnings, 1 passed, 0 skipped"));
+}
+
+#[test]
+fn test_format_badge() {
+    assert_eq!(
+        format_badge(&create_sample_report()),
+        "{\"schemaVersion\": 1, \"label\": \"tpu-doc\", \"message\": \"1 failed\", \"color\": \"red\"}\n"
+    );
+    let badge = tpu_doc::util::json::parse(&format_badge(&create_all_pass_report())).unwrap();
+    assert_eq!(badge.get("message").and_then(|m| m.as_str()), Some("2 passed"));
+    assert_eq!(badge.get("color").and_then(|c| c.as_str()), Some("brightgreen"));
+}
+
+#[test]
+fn test_estimate_drift_in_verbose_and_json_output() {
+    let mut report = create_sample_report();
+    for check in &mut report.checks {
+        check.start_ms = Some(0);
+    }
+    report.estimates = vec![
+        ("HW-001".to_string(), 2000),
+        ("HW-003".to_string(), 40),
+        ("STK-002".to_string(), 75),
+        ("IO-004".to_string(), 1000),
+    ];
+
+    // The skipped check is left out; largest drift first
+    let drift = report.estimate_drift();
+    let ids: Vec<&str> = drift.iter().map(|d| d.id.as_str()).collect();
+    assert_eq!(ids, vec!["HW-001", "HW-003", "STK-002"]);
+    assert_eq!((drift[0].drift_ms(), drift[0].percent_of_estimate()), (-1900, 5));
+
+    let output = TerminalFormatter::new(false, true, false).format(&report);
+    assert!(output.contains("Estimate drift (largest first):\n"));
+    assert!(output.contains("  HW-001           0.1s actual     2.0s estimated     -1.9s     5%\n"));
+    assert!(!TerminalFormatter::new(false, false, false).format(&report).contains("Estimate drift"));
+
+    let json = JsonFormatter::new(true).format(&report);
+    assert!(json.contains("\"estimate_drift\": [\n    {\"id\": \"HW-001\", \"estimated_ms\": 2000, \"actual_ms\": 100, \"drift_ms\": -1900},"));
+    assert!(!JsonFormatter::new(true).format(&create_sample_report()).contains("estimate_drift"));
+    let compact = JsonFormatter::new(false).format(&report);
+    assert!(compact.contains("\"drift_ms\":0}],\"checks\":["));
+}
+
+#[test]
+fn test_json_formatter_data_round_trip() {
+    let mut report = create_sample_report();
+    report.checks[1].result = report.checks[1].result.take().map(|result| {
+        result.with_data(vec![
+            ("chip 0".to_string(), "61.0C".to_string()),
+            ("chip 3".to_string(), "78.0C".to_string()),
+        ])
+    });
+    let output = JsonFormatter::new(true).format(&report);
+
+    assert!(output.contains("\"data\": {\"chip 0\": \"61.0C\", \"chip 3\": \"78.0C\"}"));
+    assert_eq!(output.matches("\"data\"").count(), 1);
+
+    let path = std::env::temp_dir().join(format!("tpu-doc-data-{}.json", std::process::id()));
+    std::fs::write(&path, &output).unwrap();
+    let loaded = tpu_doc::engine::result::load_baseline(path.to_str().unwrap()).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded.checks[1].result.as_ref().unwrap().data(), report.checks[1].result.as_ref().unwrap().data());
+    assert!(loaded.checks[0].result.as_ref().unwrap().data().is_empty());
+}
+
+#[test]
+fn test_check_ownership_output() {
+    let mut report = create_sample_report();
+    let owned_by = |owner: &str| CheckOwnership {
+        owner: Some(owner.to_string()),
+        ..Default::default()
+    };
+    let runbook = CheckOwnership {
+        runbook_url: Some("https://runbooks.example.com/libtpu".to_string()),
+        ..Default::default()
+    };
+    let ownership = Ownership {
+        entries: vec![
+            (PolicyTarget::Category(CheckCategory::Stack), owned_by("ml-runtime")),
+            (PolicyTarget::Check("STK-002".to_string()), runbook),
+            (PolicyTarget::Check("HW-001".to_string()), owned_by("fleet-hw")),
+        ],
+    };
+    ownership.apply(&mut report);
+    assert_eq!(report.checks[2].ownership.owner.as_deref(), Some("ml-runtime"));
+
+    // Findings name their owner; passing checks only in verbose output
+    let text = TerminalFormatter::new(false, false, false).format(&report);
+    assert!(text.contains("owner        ml-runtime\n"));
+    assert!(text.contains("runbook_url  https://runbooks.example.com/libtpu\n"));
+    assert!(!text.contains("fleet-hw"));
+    assert!(TerminalFormatter::new(false, true, false).format(&report).contains("owner  fleet-hw\n"));
+
+    let junit = JunitFormatter::new().format(&report);
+    assert!(junit.contains("<property name=\"owner\" value=\"ml-runtime\" />"));
+    assert!(junit.contains("<property name=\"runbook_url\" value=\"https://runbooks.example.com/libtpu\" />"));
+
+    let json = JsonFormatter::new(true).format(&report);
+    let stk002 = "\"owner\": \"ml-runtime\",\n      \"runbook_url\": \"https://runbooks.example.com/libtpu\",";
+    assert!(json.contains(stk002));
+    assert_eq!(json.matches("\"owner\"").count(), 2);
+
+    let path = std::env::temp_dir().join(format!("tpu-doc-ownership-{}.json", std::process::id()));
+    std::fs::write(&path, &json).unwrap();
+    let loaded = tpu_doc::engine::result::load_baseline(path.to_str().unwrap()).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let owners: Vec<CheckOwnership> = loaded.checks.iter().map(|c| c.ownership.clone()).collect();
+    assert_eq!(owners, report.checks.iter().map(|c| c.ownership.clone()).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_json_formatter_compact_with_run_id() {
+    let report = create_sample_report();
+    let compact = JsonFormatter::new(false).format(&report);
+    assert!(!compact.contains('\n'));
+    assert!(compact.starts_with(&format!(
+        "{{\"tool_version\":\"{}\",\"run_id\":\"0005c4a1b2c3d4e5\",\"timestamp\":1733500000,\"hostname\":",
+        env!("CARGO_PKG_VERSION")
+    )));
+    assert_eq!(compact, JsonFormatter::new(false).format(&report));
+
+    // Pretty output has the same keys in the same order
+    let pretty = JsonFormatter::new(true).format(&report);
+    let keys = |json: &str| -> Vec<String> { json.split('"').skip(1).step_by(2).map(str::to_string).collect() };
+    assert_eq!(keys(&pretty), keys(&compact));
+
+    let path = std::env::temp_dir().join(format!("tpu-doc-compact-{}.json", std::process::id()));
+    std::fs::write(&path, &compact).unwrap();
+    let loaded = tpu_doc::engine::result::load_baseline(path.to_str().unwrap()).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded.run_id, report.run_id);
+    assert_eq!(loaded.checks.len(), report.checks.len());
+
+    let first = ValidationReport::new().run_id;
+    let second = ValidationReport::new().run_id;
+    let groups: Vec<usize> = first.split('-').map(str::len).collect();
+    assert_eq!(groups, [8, 4, 4, 4, 12]);
+    assert!(first.chars().all(|c| c == '-' || c.is_ascii_hexdigit()));
+    assert_eq!(&first[14..15], "7");
+    assert!(second > first);
+}
+
 // JUnit formatter tests
 
 #[test]
@@ -307,6 +716,18 @@ fn test_junit_formatter_testsuites_attributes() {
     assert!(output.contains("time=\""));
 }
 
+#[test]
+fn test_junit_formatter_failures_only() {
+    let output = JunitFormatter::new().with_failures_only(true).format(&create_sample_report());
+
+    assert!(output.contains("<testsuites tests=\"4\" failures=\"1\" errors=\"0\" skipped=\"1\""));
+    assert!(output.contains("<testcase name=\"HW-003\""));
+    assert!(output.contains("<testcase name=\"STK-002\""));
+    assert!(!output.contains("<testcase name=\"HW-001\""));
+    // A suite with nothing to report is left out
+    assert!(!output.contains("<testsuite name=\"io\""));
+}
+
 #[test]
 fn test_junit_formatter_testsuite_per_category() {
     let formatter = JunitFormatter::new();
@@ -356,6 +777,45 @@ fn test_junit_formatter_system_out() {
     assert!(output.contains("<system-out>"));
 }
 
+#[test]
+fn test_junit_formatter_properties() {
+    let mut report = create_sample_report();
+    report.checks[0].result = Some(CheckResult::Pass {
+        message: "HBM bandwidth OK".to_string(),
+        duration_ms: 100,
+        data: vec![("bandwidth_gbps".to_string(), "810.5".to_string())],
+    });
+    report.checks[2].result = Some(CheckResult::Fail {
+        message: "XLA flags unsafe".to_string(),
+        details: "--xla_foo is deprecated\n--xla_bar <unknown>".to_string(),
+        duration_ms: 75,
+        data: vec![("recommendation".to_string(), "Remove --xla_foo".to_string())],
+    });
+    let output = JunitFormatter::new().format(&report);
+
+    assert!(output.contains(concat!(
+        "    <testcase name=\"HW-001\" classname=\"tpu-doc.hardware\" time=\"0.100\">\n",
+        "      <properties>\n",
+        "        <property name=\"bandwidth_gbps\" value=\"810.5\" />\n",
+        "      </properties>\n",
+        "      <system-out>HBM bandwidth OK</system-out>\n"
+    )));
+    assert!(output.contains(concat!(
+        "        <property name=\"details\" value=\"--xla_foo is deprecated&#10;--xla_bar &lt;unknown&gt;\" />\n",
+        "        <property name=\"remediation\" value=\"Remove --xla_foo\" />\n"
+    )));
+    // Checks without details or data have no properties
+    assert_eq!(output.matches("      <properties>").count(), 3);
+    // Each suite records the run ID
+    assert!(output.contains(concat!(
+        "  <testsuite name=\"hardware\" tests=\"2\" failures=\"0\" errors=\"0\" skipped=\"0\" time=\"0.150\">\n",
+        "    <properties>\n",
+        "      <property name=\"run_id\" value=\"0005c4a1b2c3d4e5\" />\n",
+        "    </properties>\n"
+    )));
+    assert!(!output.contains("<testcase name=\"IO-004\" classname=\"tpu-doc.io\" time=\"0.000\">\n      <properties>"));
+}
+
 #[test]
 fn test_junit_formatter_escapes_xml_special_chars() {
     let formatter = JunitFormatter::new();
@@ -363,6 +823,7 @@ fn test_junit_formatter_escapes_xml_special_chars() {
     report.checks[0].result = Some(CheckResult::Pass {
         message: "Test <with> & special \"chars\"".to_string(),
         duration_ms: 100,
+        data: Vec::new(),
     });
     let output = formatter.format(&report);
 
@@ -382,6 +843,98 @@ fn test_junit_formatter_empty_report() {
     assert!(output.contains("failures=\"0\""));
 }
 
+// Timeline tests
+
+#[test]
+fn test_format_timeline() {
+    let mut report = create_sample_report();
+    report.total_duration_ms = 200;
+    report.checks[0].start_ms = Some(0);
+    report.checks[1].start_ms = Some(100);
+    report.checks[2].start_ms = Some(50);
+    let output = format_timeline(&report);
+
+    let lines: Vec<&str> = output.lines().filter(|l| l.starts_with("  ")).collect();
+    assert_eq!(lines.len(), 3, "{}", output);
+    assert!(lines[0].starts_with("  HW-001  |#########################"));
+    assert!(lines[0].ends_with("0ms +100ms PASS"));
+    assert!(lines[1].starts_with("  STK-002 |"));
+    assert!(lines[1].ends_with("50ms +75ms FAIL"));
+    assert!(lines[2].starts_with("  HW-003  |"));
+    assert!(output.contains("TIMELINE (0-200ms)"));
+    assert!(!output.contains("IO-004"));
+}
+
+// Summary-only tests
+
+#[test]
+fn test_summary_formatter() {
+    let report = create_sample_report();
+    assert_eq!(
+        SummaryFormatter::new(false).format(&report),
+        "SUMMARY: 1 passed, 1 warnings, 1 failed, 1 skipped\nExit code: 1 (failures detected)\nRun ID: 0005c4a1b2c3d4e5"
+    );
+    assert_eq!(
+        SummaryFormatter::new(true).format(&report),
+        "{\"tool_version\": \"".to_string()
+            + env!("CARGO_PKG_VERSION")
+            + "\", \"run_id\": \"0005c4a1b2c3d4e5\", \"passed\": 1, \"warned\": 1, \"failed\": 1, \"skipped\": 1, \"total\": 4, \"total_duration_ms\": 500, \"exit_code\": 1, \"verdict\": \"failures detected\"}"
+    );
+    assert!(SummaryFormatter::new(false)
+        .format(&create_all_pass_report())
+        .contains("Exit code: 0 (all checks passed)"));
+}
+
+// Custom formatter registration tests
+
+/// One `ID<TAB>STATUS` line per check
+struct TsvFormatter {
+    verbose: bool,
+}
+
+impl OutputFormatter for TsvFormatter {
+    fn format(&self, report: &ValidationReport) -> String {
+        let mut output = String::new();
+        if self.verbose {
+            output.push_str(&format!("# run {}\n", report.run_id));
+        }
+        for check in &report.checks {
+            let status = match &check.result {
+                Some(CheckResult::Pass { .. }) => "PASS",
+                Some(CheckResult::Warn { .. }) => "WARN",
+                Some(CheckResult::Fail { .. }) => "FAIL",
+                Some(CheckResult::Skip { .. }) => "SKIP",
+                None => "PENDING",
+            };
+            output.push_str(&format!("{}\t{}\n", check.id, status));
+        }
+        output
+    }
+}
+
+#[test]
+fn test_register_formatter() {
+    register_formatter("tsv-test", |args: &Args| Box::new(TsvFormatter { verbose: args.verbose })).unwrap();
+    assert!(registered_formatters().contains(&"tsv-test".to_string()));
+
+    let args = Args::parse_from(&["check".to_string(), "--format=TSV-test".to_string(), "--verbose".to_string()]);
+    let args = args.unwrap();
+    assert_eq!(args.format, OutputFormat::Custom("tsv-test".to_string()));
+    let output = formatter_for_args(&args).format(&create_sample_report());
+    assert!(output.starts_with("# run 0005c4a1b2c3d4e5\nHW-001\tPASS\n"));
+    let output = get_formatter(&args.format, true, false, false).format(&create_sample_report());
+    assert!(output.starts_with("HW-001\tPASS\n"));
+
+    // Built-in names are reserved, and custom formats apply to check runs
+    assert!(register_formatter("json", |_: &Args| Box::new(JsonFormatter::new(true))).is_err());
+    assert!(register_formatter("my format", |_: &Args| Box::new(JunitFormatter::new())).is_err());
+    let err = Args::parse_from(&["list".to_string(), "--format".to_string(), "tsv-test".to_string()]);
+    assert!(err.unwrap_err().contains("check command only"));
+    let err = Args::parse_from(&["--format".to_string(), "yaml".to_string()]).unwrap_err();
+    assert!(err.starts_with("Unknown output format: 'yaml'. Valid formats: text, json, junit, "));
+    assert!(err.contains("tsv-test"));
+}
+
 // get_formatter factory tests
 
 #[test]
@@ -190,7 +190,7 @@ impl MockTpuConfig {
 }
 
 /// Mock GCP platform configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct MockGcpConfig {
     pub is_on_gcp: bool,
     pub project_id: Option<String>,
@@ -202,21 +202,6 @@ pub struct MockGcpConfig {
     pub attributes: HashMap<String, String>,
 }
 
-impl Default for MockGcpConfig {
-    fn default() -> Self {
-        MockGcpConfig {
-            is_on_gcp: false,
-            project_id: None,
-            zone: None,
-            instance_name: None,
-            machine_type: None,
-            service_account: None,
-            access_scopes: vec![],
-            attributes: HashMap::new(),
-        }
-    }
-}
-
 impl MockGcpConfig {
     /// Create a standard GCP TPU VM configuration
     pub fn standard_tpu_vm() -> Self {
@@ -541,11 +526,8 @@ pub trait MockPlatform {
     }
 
     fn get_hbm_available_bytes(&self) -> Option<u64> {
-        if let Some(total) = self.get_hbm_total_bytes() {
-            Some((total as f64 * self.get_config().tpu.hbm_available_pct / 100.0) as u64)
-        } else {
-            None
-        }
+        self.get_hbm_total_bytes()
+            .map(|total| (total as f64 * self.get_config().tpu.hbm_available_pct / 100.0) as u64)
     }
 
     fn get_temperatures(&self) -> Option<&[f64]> {